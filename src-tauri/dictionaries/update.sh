@@ -0,0 +1,22 @@
+#!/bin/sh
+# Replace the bundled Hunspell dictionaries with the SCOWL builds from
+# http://wordlist.aspell.net. Each language's README, which carries the SCOWL
+# copyright and license terms, is kept next to it as LICENSE-<lang>.txt and
+# must ship with the dictionary.
+set -eu
+
+VERSION=2020.12.07
+cd "$(dirname "$0")"
+tmp=$(mktemp -d)
+trap 'rm -rf "$tmp"' EXIT
+
+# fetch <language> <archive name>
+fetch() {
+    curl -fsSL -o "$tmp/$2.zip" "https://downloads.sourceforge.net/wordlist/$2.zip"
+    unzip -q -o "$tmp/$2.zip" -d "$tmp/$1"
+    cp "$tmp/$1/$1.aff" "$tmp/$1/$1.dic" .
+    cp "$tmp/$1/README_$1.txt" "LICENSE-$1.txt"
+    echo "$1: $(sed -n 1p "$1.dic") words from $2"
+}
+
+fetch en_US "hunspell-en_US-$VERSION"
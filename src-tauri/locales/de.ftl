@@ -0,0 +1,15 @@
+# Project generation progress
+pipeline-understanding = Projektanforderungen werden analysiert...
+pipeline-planning = Projektplan wird erstellt...
+pipeline-structure = Projektstruktur wird erzeugt...
+pipeline-file = { $path } wird erzeugt ({ $index }/{ $total })
+pipeline-tests = Testdateien werden erzeugt...
+pipeline-docs = Dokumentation wird erzeugt...
+pipeline-complete = Projekt erfolgreich erzeugt!
+
+# Documents
+document-not-open = Dokument ist nicht geöffnet: { $path }
+document-conflict = { $path } wurde seit dem Öffnen auf der Festplatte geändert; neu laden oder Speichern erzwingen
+
+# Generic errors
+error-window-not-found = Fenster nicht gefunden: { $label }
@@ -0,0 +1,15 @@
+# Project generation progress
+pipeline-understanding = Understanding project requirements...
+pipeline-planning = Creating project plan...
+pipeline-structure = Generating project structure...
+pipeline-file = Generating { $path } ({ $index }/{ $total })
+pipeline-tests = Generating test files...
+pipeline-docs = Generating documentation...
+pipeline-complete = Project generation complete!
+
+# Documents
+document-not-open = Document is not open: { $path }
+document-conflict = { $path } changed on disk since it was opened; reload or force the save
+
+# Generic errors
+error-window-not-found = Window not found: { $label }
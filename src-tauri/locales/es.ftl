@@ -0,0 +1,15 @@
+# Project generation progress
+pipeline-understanding = Analizando los requisitos del proyecto...
+pipeline-planning = Creando el plan del proyecto...
+pipeline-structure = Generando la estructura del proyecto...
+pipeline-file = Generando { $path } ({ $index }/{ $total })
+pipeline-tests = Generando archivos de prueba...
+pipeline-docs = Generando documentación...
+pipeline-complete = ¡Proyecto generado!
+
+# Documents
+document-not-open = El documento no está abierto: { $path }
+document-conflict = { $path } cambió en el disco desde que se abrió; recárgalo o fuerza el guardado
+
+# Generic errors
+error-window-not-found = Ventana no encontrada: { $label }
@@ -0,0 +1,15 @@
+# Project generation progress
+pipeline-understanding = Analyse des besoins du projet...
+pipeline-planning = Création du plan du projet...
+pipeline-structure = Génération de la structure du projet...
+pipeline-file = Génération de { $path } ({ $index }/{ $total })
+pipeline-tests = Génération des fichiers de test...
+pipeline-docs = Génération de la documentation...
+pipeline-complete = Projet généré !
+
+# Documents
+document-not-open = Le document n'est pas ouvert : { $path }
+document-conflict = { $path } a été modifié sur le disque depuis son ouverture ; rechargez-le ou forcez l'enregistrement
+
+# Generic errors
+error-window-not-found = Fenêtre introuvable : { $label }
@@ -0,0 +1,579 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::agent::injection;
+use crate::database::{self, ChatMessage, ChatSession};
+use crate::markdown::{self, html_escape, MarkdownRenderOptions};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+/// One branch of a session, as written by the JSON export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatTranscript {
+    pub session: ChatSession,
+    /// Root to leaf along a single branch
+    pub messages: Vec<ChatMessage>,
+    pub exported_at: String,
+}
+
+/// A path from the first message to a leaf; every edit starts a new one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatBranch {
+    pub leaf_id: String,
+    pub message_count: usize,
+    /// First message that exists only on this branch, i.e. the edit that split it
+    /// off from its siblings; None for a conversation that was never edited
+    pub forked_from: Option<String>,
+    pub preview: String,
+    pub updated_at: String,
+}
+
+pub fn create_session(title: &str, project_id: Option<String>, persona_id: Option<String>) -> Result<ChatSession> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let session = ChatSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: title.to_string(),
+        project_id,
+        persona_id,
+        selection: None,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    database::open()?.create_chat_session(&session)?;
+    Ok(session)
+}
+
+/// Project a session belongs to, for its agent memories
+pub fn session_project(session_id: &str) -> Result<Option<String>> {
+    let session = database::open()?.get_chat_session(session_id)?.context("Chat session not found")?;
+    Ok(session.project_id)
+}
+
+/// Newest message in the session, which new prompts continue from by default
+pub fn latest_message_id(session_id: &str) -> Result<Option<String>> {
+    Ok(database::open()?.get_chat_messages(session_id)?.last().map(|m| m.id.clone()))
+}
+
+/// Store a message under `parent_id`; None starts a new root
+pub fn record_message(
+    session_id: &str,
+    parent_id: Option<String>,
+    role: &str,
+    content: &str,
+    context: Option<String>,
+    model: Option<String>,
+) -> Result<ChatMessage> {
+    let message = ChatMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        session_id: session_id.to_string(),
+        parent_id,
+        role: role.to_string(),
+        content: content.to_string(),
+        context,
+        model,
+        pinned: false,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    database::open()?.add_chat_message(&message)?;
+    Ok(message)
+}
+
+/// Messages from the root down to `leaf_id`
+pub fn path_to(messages: &[ChatMessage], leaf_id: &str) -> Vec<ChatMessage> {
+    let by_id: HashMap<&str, &ChatMessage> = messages.iter().map(|m| (m.id.as_str(), m)).collect();
+    let mut path = Vec::new();
+    let mut current = by_id.get(leaf_id).copied();
+
+    while let Some(message) = current {
+        // Guard against a corrupted parent chain looping back on itself
+        if path.len() > messages.len() {
+            break;
+        }
+        path.push(message.clone());
+        current = message.parent_id.as_deref().and_then(|id| by_id.get(id).copied());
+    }
+
+    path.reverse();
+    path
+}
+
+fn children_count(messages: &[ChatMessage]) -> HashMap<Option<&str>, usize> {
+    let mut counts = HashMap::new();
+    for message in messages {
+        *counts.entry(message.parent_id.as_deref()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Branches newest first
+pub fn branches(messages: &[ChatMessage]) -> Vec<ChatBranch> {
+    let parents: HashSet<&str> = messages.iter().filter_map(|m| m.parent_id.as_deref()).collect();
+    let children = children_count(messages);
+
+    let mut branches: Vec<ChatBranch> = messages
+        .iter()
+        .filter(|m| !parents.contains(m.id.as_str()))
+        .map(|leaf| {
+            let path = path_to(messages, &leaf.id);
+            let forked_from = path
+                .iter()
+                .rev()
+                .find(|m| children.get(&m.parent_id.as_deref()).copied().unwrap_or(0) > 1)
+                .map(|m| m.id.clone());
+            let preview = path
+                .iter()
+                .rev()
+                .find(|m| m.role == "user")
+                .map(|m| m.content.chars().take(80).collect())
+                .unwrap_or_default();
+
+            ChatBranch {
+                leaf_id: leaf.id.clone(),
+                message_count: path.len(),
+                forked_from,
+                preview,
+                updated_at: leaf.created_at.clone(),
+            }
+        })
+        .collect();
+
+    branches.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    branches
+}
+
+/// Load one branch of a session, defaulting to the one with the newest message
+pub fn load_transcript(session_id: &str, leaf_id: Option<&str>) -> Result<ChatTranscript> {
+    let db = database::open()?;
+    let session = db.get_chat_session(session_id)?.context("Chat session not found")?;
+    let all = db.get_chat_messages(session_id)?;
+
+    let leaf = match leaf_id {
+        Some(id) => id.to_string(),
+        None => all.last().map(|m| m.id.clone()).unwrap_or_default(),
+    };
+    let messages = path_to(&all, &leaf);
+    if leaf_id.is_some() && messages.is_empty() {
+        anyhow::bail!("Message {} is not part of this session", leaf);
+    }
+
+    Ok(ChatTranscript {
+        session,
+        messages,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Messages as "Role: text" paragraphs, with their project context
+pub fn transcript_text(path: &[ChatMessage]) -> String {
+    let mut text = String::new();
+    for message in path {
+        if let Some(context) = message.context.as_deref().filter(|c| !c.trim().is_empty()) {
+            let context = injection::sanitize(context, "chat context");
+            text.push_str(&format!("Context:\n{}\n\n", context.text));
+        }
+        text.push_str(&format!("{}: {}\n\n", role_label(&message.role), message.content));
+    }
+    text
+}
+
+/// Flatten a branch into a single prompt, since the agent's models are called without chat turns
+pub fn conversation_prompt(path: &[ChatMessage]) -> String {
+    format!("{}Assistant:", transcript_text(path))
+}
+
+/// Replace a user message with new text on a fresh branch and regenerate the reply.
+/// The original message and everything after it stay available on their own branch.
+pub async fn edit_and_regenerate(message_id: &str, content: &str) -> Result<ChatTranscript> {
+    let db = database::open()?;
+    let original = db.get_chat_message(message_id)?.context("Message not found")?;
+    if original.role != "user" {
+        anyhow::bail!("Only your own messages can be edited");
+    }
+
+    let edited = record_message(
+        &original.session_id,
+        original.parent_id.clone(),
+        "user",
+        content,
+        original.context.clone(),
+        None,
+    )?;
+
+    let generated = async {
+        let messages = db.get_chat_messages(&original.session_id)?;
+        let prompt = super::compaction::conversation_prompt(&original.session_id, &path_to(&messages, &edited.id), 0).await?;
+        let persona = super::personas::session_persona(&original.session_id)?;
+        let project_id = session_project(&original.session_id)?;
+        super::generate_reply(prompt, persona.as_ref(), project_id.as_deref()).await
+    }
+    .await;
+    let response = match generated {
+        Ok(response) => response,
+        Err(e) => {
+            // A failed regeneration shouldn't leave the edit behind as an unanswered branch
+            if let Err(cleanup) = db.delete_chat_messages(&[edited.id.clone()]) {
+                tracing::warn!("Failed to remove edited message {}: {}", edited.id, cleanup);
+            }
+            return Err(e);
+        }
+    };
+
+    let reply = record_message(
+        &original.session_id,
+        Some(edited.id),
+        "assistant",
+        response.text.trim(),
+        None,
+        Some(response.model),
+    )?;
+
+    load_transcript(&original.session_id, Some(&reply.id))
+}
+
+/// Delete a message together with every reply and branch below it
+pub fn delete_message(message_id: &str) -> Result<usize> {
+    let db = database::open()?;
+    let message = db.get_chat_message(message_id)?.context("Message not found")?;
+    let messages = db.get_chat_messages(&message.session_id)?;
+
+    let mut doomed = vec![message.id.clone()];
+    let mut index = 0;
+    while index < doomed.len() {
+        let parent = doomed[index].clone();
+        doomed.extend(messages.iter().filter(|m| m.parent_id.as_deref() == Some(parent.as_str())).map(|m| m.id.clone()));
+        index += 1;
+    }
+
+    db.delete_chat_messages(&doomed)?;
+    Ok(doomed.len())
+}
+
+fn role_label(role: &str) -> &str {
+    match role {
+        "user" => "You",
+        "assistant" => "Assistant",
+        "system" => "System",
+        other => other,
+    }
+}
+
+fn message_heading(message: &ChatMessage) -> String {
+    let mut heading = format!("{} · {}", role_label(&message.role), message.created_at);
+    if let Some(model) = &message.model {
+        heading.push_str(&format!(" · {}", model));
+    }
+    if message.pinned {
+        heading.push_str(" · pinned");
+    }
+    heading
+}
+
+/// A fence longer than any backtick run in the text, so context containing code can't close it early
+fn fence_for(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+pub fn to_markdown(transcript: &ChatTranscript, include_context: bool) -> String {
+    let session = &transcript.session;
+    let mut out = format!("# {}\n\n", session.title);
+    out.push_str(&format!("- Started: {}\n- Exported: {}\n", session.created_at, transcript.exported_at));
+    if let Some(project) = &session.project_id {
+        out.push_str(&format!("- Project: {}\n", project));
+    }
+
+    for message in &transcript.messages {
+        out.push_str(&format!("\n---\n\n### {}\n\n", message_heading(message)));
+
+        if include_context {
+            if let Some(context) = message.context.as_deref().filter(|c| !c.trim().is_empty()) {
+                let fence = fence_for(context);
+                out.push_str(&format!(
+                    "<details>\n<summary>Injected context</summary>\n\n{}\n{}\n{}\n\n</details>\n\n",
+                    fence, context.trim_end(), fence
+                ));
+            }
+        }
+
+        // Messages are already Markdown, code blocks included
+        out.push_str(message.content.trim_end());
+        out.push('\n');
+    }
+
+    out
+}
+
+pub fn to_html(transcript: &ChatTranscript, include_context: bool) -> Result<String> {
+    let session = &transcript.session;
+    let options = MarkdownRenderOptions::default();
+    let mut body = String::new();
+
+    for message in &transcript.messages {
+        body.push_str(&format!(
+            "<section class=\"message {}\">\n<header>{}</header>\n",
+            html_escape(&message.role),
+            html_escape(&message_heading(message))
+        ));
+
+        if include_context {
+            if let Some(context) = message.context.as_deref().filter(|c| !c.trim().is_empty()) {
+                body.push_str(&format!(
+                    "<details><summary>Injected context</summary><pre>{}</pre></details>\n",
+                    html_escape(context)
+                ));
+            }
+        }
+
+        body.push_str(&markdown::render(&message.content, &options)?);
+        body.push_str("\n</section>\n");
+    }
+
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; max-width: 860px; margin: 2rem auto; padding: 0 1rem; color: #1f2328; }}
+.meta {{ color: #656d76; font-size: 0.9rem; }}
+.message {{ border: 1px solid #d0d7de; border-radius: 8px; padding: 0.75rem 1rem; margin: 1rem 0; }}
+.message.user {{ background: #f6f8fa; }}
+.message header {{ font-weight: 600; font-size: 0.85rem; color: #656d76; margin-bottom: 0.5rem; }}
+pre {{ padding: 0.75rem; border-radius: 6px; overflow-x: auto; }}
+details pre {{ background: #f6f8fa; white-space: pre-wrap; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="meta">Started {created} · Exported {exported}</p>
+{body}</body>
+</html>
+"#,
+        title = html_escape(&session.title),
+        created = html_escape(&session.created_at),
+        exported = html_escape(&transcript.exported_at),
+        body = body,
+    ))
+}
+
+pub fn to_json(transcript: &ChatTranscript, include_context: bool) -> Result<String> {
+    let mut transcript = transcript.clone();
+    if !include_context {
+        for message in &mut transcript.messages {
+            message.context = None;
+        }
+    }
+    Ok(serde_json::to_string_pretty(&transcript)?)
+}
+
+pub fn export(
+    session_id: &str,
+    leaf_id: Option<&str>,
+    format: ExportFormat,
+    path: &Path,
+    include_context: bool,
+) -> Result<()> {
+    let transcript = load_transcript(session_id, leaf_id)?;
+
+    let output = match format {
+        ExportFormat::Markdown => to_markdown(&transcript, include_context),
+        ExportFormat::Html => to_html(&transcript, include_context)?,
+        ExportFormat::Json => to_json(&transcript, include_context)?,
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, output).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn create_chat_session(
+    title: Option<String>,
+    project_id: Option<String>,
+    persona_id: Option<String>,
+) -> Result<ChatSession, String> {
+    create_session(title.as_deref().unwrap_or("New chat"), project_id, persona_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_chat_sessions(project_id: Option<String>) -> Result<Vec<ChatSession>, String> {
+    database::open()
+        .and_then(|db| db.list_chat_sessions(project_id.as_deref()))
+        .map_err(|e| e.to_string())
+}
+
+/// Messages on one branch; `leaf_id` comes from `list_chat_branches`
+#[tauri::command]
+pub async fn get_chat_session(session_id: String, leaf_id: Option<String>) -> Result<ChatTranscript, String> {
+    load_transcript(&session_id, leaf_id.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_chat_branches(session_id: String) -> Result<Vec<ChatBranch>, String> {
+    let messages = database::open()
+        .and_then(|db| db.get_chat_messages(&session_id))
+        .map_err(|e| e.to_string())?;
+    Ok(branches(&messages))
+}
+
+#[tauri::command]
+pub async fn edit_chat_message(message_id: String, content: String) -> Result<ChatTranscript, String> {
+    edit_and_regenerate(&message_id, &content).await.map_err(|e| e.to_string())
+}
+
+/// Returns how many messages were removed, including replies below it
+#[tauri::command]
+pub async fn delete_chat_message(message_id: String) -> Result<usize, String> {
+    delete_message(&message_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn pin_chat_message(message_id: String, pinned: bool) -> Result<(), String> {
+    database::open()
+        .and_then(|db| db.set_chat_message_pinned(&message_id, pinned))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_pinned_chat_messages(session_id: String) -> Result<Vec<ChatMessage>, String> {
+    let messages = database::open()
+        .and_then(|db| db.get_chat_messages(&session_id))
+        .map_err(|e| e.to_string())?;
+    Ok(messages.into_iter().filter(|m| m.pinned).collect())
+}
+
+#[tauri::command]
+pub async fn delete_chat_session(session_id: String) -> Result<(), String> {
+    database::open()
+        .and_then(|db| db.delete_chat_session(&session_id))
+        .map_err(|e| e.to_string())
+}
+
+/// Write a session to `path` as Markdown, standalone HTML or JSON. Injected project
+/// context is left out unless `include_context` is set, so exports are safe to share.
+#[tauri::command]
+pub async fn export_chat_session(
+    session_id: String,
+    format: ExportFormat,
+    path: String,
+    include_context: Option<bool>,
+    leaf_id: Option<String>,
+) -> Result<String, String> {
+    let include_context = include_context.unwrap_or(false);
+
+    tokio::task::spawn_blocking(move || {
+        export(&session_id, leaf_id.as_deref(), format, Path::new(&path), include_context).map(|_| path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript() -> ChatTranscript {
+        let message = |role: &str, content: &str, context: Option<&str>| ChatMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: "s1".into(),
+            parent_id: None,
+            role: role.into(),
+            content: content.into(),
+            context: context.map(String::from),
+            model: (role == "assistant").then(|| "llama3.2:3b".to_string()),
+            pinned: false,
+            created_at: "2024-01-01T00:00:00Z".into(),
+        };
+
+        ChatTranscript {
+            session: ChatSession {
+                id: "s1".into(),
+                title: "Fix <parser>".into(),
+                project_id: None,
+                persona_id: None,
+                selection: None,
+                created_at: "2024-01-01T00:00:00Z".into(),
+                updated_at: "2024-01-01T00:00:00Z".into(),
+            },
+            messages: vec![
+                message("user", "Why does this fail?", Some("```rust\nfn main() {}\n```")),
+                message("assistant", "Try this:\n\n```rust\nfn main() { run(); }\n```", None),
+            ],
+            exported_at: "2024-01-02T00:00:00Z".into(),
+        }
+    }
+
+    #[test]
+    fn test_context_is_optional() {
+        let transcript = transcript();
+
+        let shared = to_markdown(&transcript, false);
+        assert!(!shared.contains("Injected context"));
+        assert!(shared.contains("Assistant · 2024-01-01T00:00:00Z · llama3.2:3b"));
+
+        let full = to_markdown(&transcript, true);
+        assert!(full.contains("````\n```rust"));
+
+        let json = to_json(&transcript, false).unwrap();
+        assert!(!json.contains("fn main() {}"));
+    }
+
+    #[test]
+    fn test_branches() {
+        let message = |id: &str, parent: Option<&str>, role: &str, at: &str| ChatMessage {
+            id: id.into(),
+            session_id: "s1".into(),
+            parent_id: parent.map(String::from),
+            role: role.into(),
+            content: format!("message {}", id),
+            context: None,
+            model: None,
+            pinned: false,
+            created_at: at.into(),
+        };
+        // u1 -> a1 -> u2 -> a2, with u2 edited into u3 -> a3
+        let messages = vec![
+            message("u1", None, "user", "1"),
+            message("a1", Some("u1"), "assistant", "2"),
+            message("u2", Some("a1"), "user", "3"),
+            message("a2", Some("u2"), "assistant", "4"),
+            message("u3", Some("a1"), "user", "5"),
+            message("a3", Some("u3"), "assistant", "6"),
+        ];
+
+        let branches = branches(&messages);
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].leaf_id, "a3");
+        assert_eq!(branches[0].forked_from.as_deref(), Some("u3"));
+        assert_eq!(branches[0].preview, "message u3");
+
+        let ids: Vec<String> = path_to(&messages, "a2").into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, ["u1", "a1", "u2", "a2"]);
+    }
+
+    #[test]
+    fn test_html_escapes_title() {
+        let html = to_html(&transcript(), false).unwrap();
+        assert!(html.contains("<title>Fix &lt;parser&gt;</title>"));
+        assert!(html.contains("class=\"message assistant\""));
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::Result;
+
+use crate::agent::chat::{self, transcript_text};
+use crate::agent::estimate::estimate_tokens;
+use crate::database::{ChatMessage, ChatSummary};
+use crate::llm::{GenerationRequest, LLMClient};
+
+/// Share of the context window the prompt may fill; the rest is left for the reply
+const PROMPT_SHARE: f64 = 0.75;
+const SUMMARY_MAX_TOKENS: u32 = 512;
+/// The latest exchange is always kept verbatim, however long
+const MIN_RECENT: usize = 2;
+
+/// What to do with a branch's history before the next prompt
+#[derive(Debug, PartialEq)]
+enum Compaction {
+    /// Everything fits verbatim
+    None,
+    /// The stored summary plus the messages from `keep_from` on fit
+    Reuse { keep_from: usize },
+    /// Fold `fold_from..keep_from` into a new summary, on top of the stored one when `extend`
+    Fold { fold_from: usize, keep_from: usize, extend: bool },
+}
+
+fn tokens(messages: &[ChatMessage]) -> u64 {
+    estimate_tokens(&transcript_text(messages))
+}
+
+/// Context window the agent's models run with, in prompt tokens
+async fn prompt_budget() -> Result<u64> {
+    let context_length = crate::llm::OllamaClient::configured().await?.context_length(super::AGENT_MODEL).await?;
+    Ok((context_length as f64 * PROMPT_SHARE) as u64)
+}
+
+/// Decide how much of `history` to summarize. When folding, the verbatim tail is cut
+/// to half the room left so the next few turns fit without summarizing again.
+fn plan(history: &[ChatMessage], stored: Option<&ChatSummary>, budget: u64, reserve: u64) -> Compaction {
+    if tokens(history) + reserve <= budget {
+        return Compaction::None;
+    }
+
+    // The stored summary only applies if it was made along this branch
+    let through = stored.and_then(|s| {
+        history.iter().position(|m| m.id == s.through_message_id).map(|i| (i + 1, estimate_tokens(&s.summary)))
+    });
+    if let Some((keep_from, summary_tokens)) = through {
+        if summary_tokens + tokens(&history[keep_from..]) + reserve <= budget {
+            return Compaction::Reuse { keep_from };
+        }
+    }
+
+    let room = budget.saturating_sub(reserve + SUMMARY_MAX_TOKENS as u64) / 2;
+    let mut keep_from = history.len();
+    let mut kept = 0;
+    while keep_from > 0 {
+        let next = tokens(&history[keep_from - 1..keep_from]);
+        if history.len() - keep_from >= MIN_RECENT && kept + next > room {
+            break;
+        }
+        kept += next;
+        keep_from -= 1;
+    }
+
+    if keep_from == 0 {
+        // Only the latest exchange is left and it's too long on its own
+        return Compaction::None;
+    }
+    match through {
+        // Nothing new to fold in; the summary is as small as it gets
+        Some((summarized, _)) if summarized >= keep_from => Compaction::Reuse { keep_from: summarized },
+        Some((summarized, _)) => Compaction::Fold { fold_from: summarized, keep_from, extend: true },
+        None => Compaction::Fold { fold_from: 0, keep_from, extend: false },
+    }
+}
+
+async fn summarize(previous: Option<&str>, messages: &[ChatMessage]) -> Result<String> {
+    let mut prompt = String::from(
+        "Condense this conversation between a developer and a coding assistant into a summary \
+         the assistant can continue from. Keep decisions made, file names, function and type \
+         names, code the developer is working on, and open questions. Leave out pleasantries. \
+         Reply with the summary only.\n\n",
+    );
+    if let Some(previous) = previous {
+        prompt.push_str(&format!("Summary of the conversation before this part:\n{}\n\n", previous));
+    }
+    prompt.push_str(&transcript_text(messages));
+
+    let request = GenerationRequest {
+        model: super::AGENT_MODEL.to_string(),
+        prompt,
+        system_prompt: Some("You summarize conversations accurately and concisely.".to_string()),
+        temperature: 0.2,
+        max_tokens: SUMMARY_MAX_TOKENS,
+    };
+    Ok(LLMClient::new().generate(request).await?.text.trim().to_string())
+}
+
+/// `history` as prompt text that leaves `reserve` tokens for what follows it. Older
+/// messages are condensed into the session's rolling summary, which is updated as
+/// the conversation grows; recent messages stay verbatim.
+pub async fn history_prompt(session_id: &str, history: &[ChatMessage], reserve: u64) -> Result<String> {
+    if history.is_empty() {
+        return Ok(String::new());
+    }
+    let db = crate::database::open()?;
+    let stored = db.get_chat_summary(session_id)?;
+    let budget = prompt_budget().await?;
+
+    let (summary, keep_from) = match plan(history, stored.as_ref(), budget, reserve) {
+        Compaction::None => return Ok(transcript_text(history)),
+        Compaction::Reuse { keep_from } => (stored.map(|s| s.summary).unwrap_or_default(), keep_from),
+        Compaction::Fold { fold_from, keep_from, extend } => {
+            let previous = stored.as_ref().filter(|_| extend).map(|s| s.summary.as_str());
+            let summary = summarize(previous, &history[fold_from..keep_from]).await?;
+            db.save_chat_summary(&ChatSummary {
+                session_id: session_id.to_string(),
+                summary: summary.clone(),
+                through_message_id: history[keep_from - 1].id.clone(),
+                updated_at: chrono::Utc::now().to_rfc3339(),
+            })?;
+            (summary, keep_from)
+        }
+    };
+
+    Ok(format!(
+        "Summary of the earlier conversation:\n{}\n\n{}",
+        summary,
+        transcript_text(&history[keep_from..])
+    ))
+}
+
+/// `chat::conversation_prompt` for a branch that may have outgrown the context window;
+/// `reserve` is tokens of other text sent along with it
+pub async fn conversation_prompt(session_id: &str, path: &[ChatMessage], reserve: u64) -> Result<String> {
+    let Some((last, history)) = path.split_last() else {
+        return Ok(chat::conversation_prompt(path));
+    };
+    let current = chat::conversation_prompt(std::slice::from_ref(last));
+    let history = history_prompt(session_id, history, reserve + estimate_tokens(&current)).await?;
+    Ok(format!("{}{}", history, current))
+}
+
+// Tauri commands
+
+/// The session's rolling summary, or None while it still fits the context window
+#[tauri::command]
+pub async fn get_session_summary(session_id: String) -> Result<Option<ChatSummary>, String> {
+    crate::database::open()
+        .and_then(|db| db.get_chat_summary(&session_id))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: usize, length: usize) -> ChatMessage {
+        ChatMessage {
+            id: id.to_string(),
+            session_id: "s".to_string(),
+            parent_id: id.checked_sub(1).map(|p| p.to_string()),
+            role: if id % 2 == 0 { "user" } else { "assistant" }.to_string(),
+            content: "x".repeat(length),
+            context: None,
+            model: None,
+            pinned: false,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_compaction_plan() {
+        // Ten messages of roughly 100 tokens each
+        let history: Vec<ChatMessage> = (0..10).map(|i| message(i, 400)).collect();
+        assert_eq!(plan(&history, None, 2000, 100), Compaction::None);
+
+        // 1000 tokens of room: half of (1000 - 100 - 512) keeps one message, but the last two always stay
+        let Compaction::Fold { fold_from, keep_from, extend } = plan(&history, None, 1000, 100) else {
+            panic!("expected a fold");
+        };
+        assert_eq!((fold_from, keep_from, extend), (0, 8, false));
+
+        let stored = ChatSummary {
+            session_id: "s".to_string(),
+            summary: "y".repeat(200),
+            through_message_id: "5".to_string(),
+            updated_at: String::new(),
+        };
+        assert_eq!(plan(&history, Some(&stored), 1000, 100), Compaction::Reuse { keep_from: 6 });
+
+        // With less room the stored summary is extended rather than rebuilt
+        assert_eq!(plan(&history, Some(&stored), 500, 100), Compaction::Fold { fold_from: 6, keep_from: 8, extend: true });
+
+        // A summary from another branch is ignored
+        let other = ChatSummary { through_message_id: "elsewhere".to_string(), ..stored };
+        assert_eq!(plan(&history, Some(&other), 500, 100), Compaction::Fold { fold_from: 0, keep_from: 8, extend: false });
+    }
+}
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::agent::estimate::estimate_tokens;
+use crate::dead_code;
+use crate::documents::read_document;
+use crate::filesystem::collect_source_files;
+
+/// Only text worth showing a model; lockfiles, images and data dumps are left out
+const CONTEXT_EXTENSIONS: &[&str] = &[
+    "ts", "tsx", "js", "jsx", "mjs", "cjs", "vue", "svelte", "py", "rs", "go", "java", "kt", "cs", "c", "h",
+    "cpp", "hpp", "swift", "dart", "rb", "php", "css", "scss", "html", "sql", "md", "toml", "yaml", "yml",
+];
+/// Larger files are generated or data more often than code worth reading
+const MAX_FILE_BYTES: u64 = 200 * 1024;
+/// Import hops from the edited file that still count as close
+const MAX_IMPORT_DEPTH: usize = 3;
+
+/// Tokens of context `send_prompt_with_context` adds when the caller doesn't say
+const DEFAULT_BUDGET: u64 = 8_000;
+
+const RECENCY_WEIGHT: f64 = 0.3;
+const PROXIMITY_WEIGHT: f64 = 0.4;
+const RELEVANCE_WEIGHT: f64 = 0.3;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContextRequest {
+    /// The file being edited; import-graph neighbours rank higher
+    #[serde(default)]
+    pub active_file: Option<String>,
+    /// The prompt or the code around the cursor; files mentioning its words rank higher
+    #[serde(default)]
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextCandidate {
+    /// Relative to the project root
+    pub path: String,
+    pub score: f64,
+    /// Each 0 to 1, before weighting
+    pub recency: f64,
+    pub proximity: f64,
+    pub relevance: f64,
+    pub tokens: u64,
+    /// Set for the candidates that fit the budget
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextSelection {
+    pub budget: u64,
+    pub used_tokens: u64,
+    /// Best first; those without content didn't fit
+    pub candidates: Vec<ContextCandidate>,
+}
+
+impl ContextSelection {
+    /// The included files as a prompt section
+    pub fn render(&self) -> String {
+        self.candidates
+            .iter()
+            .filter_map(|c| c.content.as_ref().map(|content| format!("File: {}\n```\n{}\n```", c.path, content.trim_end())))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Words of three or more letters, lowercased and deduplicated
+fn query_terms(query: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|word| seen.insert(word.clone()))
+        .collect()
+}
+
+/// Share of terms in the file, with a path hit counting double; saturates so one
+/// huge file that repeats a word doesn't win on length alone.
+///
+/// Scored here rather than with `search_fts`: that index is keyed by project id, only
+/// holds files the frontend chose to index and goes stale as soon as one is edited, while
+/// selection reads every candidate from disk (or the open buffer) anyway for its size and
+/// needs a score for each, not just the top FTS matches, to combine with the other signals.
+fn relevance(terms: &[String], path: &str, content: &str) -> f64 {
+    if terms.is_empty() {
+        return 0.0;
+    }
+    let path = path.to_lowercase();
+    let content = content.to_lowercase();
+    let score: f64 = terms
+        .iter()
+        .map(|term| {
+            let hits = content.matches(term.as_str()).count() as f64;
+            let in_path = if path.contains(term.as_str()) { 2.0 } else { 0.0 };
+            in_path + (1.0 + hits).ln().min(2.0)
+        })
+        .sum();
+    (score / (terms.len() as f64 * 4.0)).min(1.0)
+}
+
+/// 1 for a file changed just now, half after a day
+fn recency(modified: Option<SystemTime>) -> f64 {
+    let Some(age) = modified.and_then(|m| m.elapsed().ok()) else { return 0.0 };
+    1.0 / (1.0 + age.as_secs_f64() / 86_400.0)
+}
+
+/// Hops from `active` over imports in either direction, for TS/JS projects
+fn import_distances(project_path: &Path, active: &str) -> HashMap<String, usize> {
+    let mut neighbours: HashMap<String, Vec<String>> = HashMap::new();
+    for module in dead_code::module_graph(project_path) {
+        for import in &module.imports {
+            neighbours.entry(module.file.clone()).or_default().push(import.clone());
+            neighbours.entry(import.clone()).or_default().push(module.file.clone());
+        }
+    }
+
+    let mut distances = HashMap::from([(active.to_string(), 0)]);
+    let mut queue = VecDeque::from([active.to_string()]);
+    while let Some(file) = queue.pop_front() {
+        let depth = distances[&file];
+        if depth == MAX_IMPORT_DEPTH {
+            continue;
+        }
+        for next in neighbours.get(&file).into_iter().flatten() {
+            if !distances.contains_key(next) {
+                distances.insert(next.clone(), depth + 1);
+                queue.push_back(next.clone());
+            }
+        }
+    }
+    distances
+}
+
+fn proximity(distances: &HashMap<String, usize>, active: Option<&str>, path: &str) -> f64 {
+    if let Some(depth) = distances.get(path) {
+        return 1.0 / *depth as f64;
+    }
+    // Outside the import graph (or not TS/JS), a sibling of the edited file is still close
+    let parent = |p: &str| p.rsplit_once('/').map(|(dir, _)| dir.to_string()).unwrap_or_default();
+    match active {
+        Some(active) if parent(active) == parent(path) => 0.25,
+        _ => 0.0,
+    }
+}
+
+/// Rank the project's files for the agent's context and fill `budget` tokens with the
+/// best. Files the ignore rules exclude (node_modules, build output, `.gitignore`d
+/// paths) are never considered. The edited file itself is left out; the caller has it.
+pub fn select(project_path: &Path, budget: u64, request: &ContextRequest) -> ContextSelection {
+    let relative = |p: &Path| p.strip_prefix(project_path).unwrap_or(p).to_string_lossy().replace('\\', "/");
+    let active = request.active_file.as_deref().map(|file| relative(Path::new(file)));
+    let distances = active.as_deref().map(|a| import_distances(project_path, a)).unwrap_or_default();
+    let terms = query_terms(request.query.as_deref().unwrap_or(""));
+
+    let mut candidates: Vec<ContextCandidate> = collect_source_files(project_path, CONTEXT_EXTENSIONS)
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = path.metadata().ok()?;
+            if metadata.len() > MAX_FILE_BYTES {
+                return None;
+            }
+            let path_text = relative(&path);
+            if active.as_deref() == Some(path_text.as_str()) {
+                return None;
+            }
+            let content = read_document(&path).ok()?;
+            let recency = recency(metadata.modified().ok());
+            let proximity = proximity(&distances, active.as_deref(), &path_text);
+            let relevance = relevance(&terms, &path_text, &content);
+            Some(ContextCandidate {
+                score: RECENCY_WEIGHT * recency + PROXIMITY_WEIGHT * proximity + RELEVANCE_WEIGHT * relevance,
+                tokens: estimate_tokens(&content),
+                path: path_text,
+                recency,
+                proximity,
+                relevance,
+                content: Some(content),
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+
+    let mut used_tokens = 0;
+    for candidate in &mut candidates {
+        if used_tokens + candidate.tokens <= budget && candidate.score > 0.0 {
+            used_tokens += candidate.tokens;
+        } else {
+            candidate.content = None;
+        }
+    }
+    ContextSelection { budget, used_tokens, candidates }
+}
+
+// Tauri commands
+
+/// Ranked context files for the agent or inline completion, filled up to `budget` tokens
+#[tauri::command]
+pub async fn collect_context_candidates(
+    project_path: String,
+    budget: u64,
+    request: Option<ContextRequest>,
+) -> Result<ContextSelection, String> {
+    tokio::task::spawn_blocking(move || select(Path::new(&project_path), budget, &request.unwrap_or_default()))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `send_prompt` with the project files most related to the prompt and the edited
+/// file as context
+#[tauri::command]
+pub async fn send_prompt_with_context(
+    prompt: String,
+    session_id: Option<String>,
+    parent_id: Option<String>,
+    project_path: String,
+    active_file: Option<String>,
+    budget: Option<u64>,
+) -> Result<String, String> {
+    let request = ContextRequest { active_file, query: Some(prompt.clone()) };
+    let budget = budget.unwrap_or(DEFAULT_BUDGET);
+    let selection = tokio::task::spawn_blocking(move || select(Path::new(&project_path), budget, &request))
+        .await
+        .map_err(|e| e.to_string())?;
+    super::send_prompt(prompt, session_id, parent_id, Some(selection.render())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranking_and_budget() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        for dir in ["src/cart", "src/auth", "node_modules/left-pad", "dist"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("src/cart/Cart.tsx"), "import { total } from './total';\nexport const Cart = () => total([]);\n").unwrap();
+        std::fs::write(root.join("src/cart/total.ts"), "import { round } from '../money';\nexport const total = (xs: number[]) => round(0);\n").unwrap();
+        std::fs::write(root.join("src/money.ts"), "export const round = (n: number) => n;\n").unwrap();
+        std::fs::write(root.join("src/auth/login.ts"), "export function login(password: string) { return password; }\n").unwrap();
+        std::fs::write(root.join("node_modules/left-pad/index.js"), "module.exports = function login() {};\n").unwrap();
+        std::fs::write(root.join("dist/bundle.js"), "function login(){}\n").unwrap();
+
+        let request = ContextRequest {
+            active_file: Some(root.join("src/cart/Cart.tsx").to_string_lossy().to_string()),
+            query: Some("why does the cart total ignore discounts".to_string()),
+        };
+        let selection = select(root, 10_000, &request);
+        let paths: Vec<&str> = selection.candidates.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths[0], "src/cart/total.ts");
+        assert!(paths.iter().position(|p| *p == "src/money.ts") < paths.iter().position(|p| *p == "src/auth/login.ts"));
+        assert!(!paths.iter().any(|p| p.starts_with("node_modules") || p.starts_with("dist") || *p == "src/cart/Cart.tsx"));
+        assert!(selection.render().contains("File: src/cart/total.ts"));
+
+        let tight = select(root, 25, &request);
+        assert!(tight.used_tokens <= 25);
+        assert!(tight.candidates.iter().any(|c| c.content.is_none()));
+    }
+}
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::agent::injection;
+use crate::debugging::{self, DebugStatus, StackFrame, VariableValue};
+use crate::documents::read_document;
+use crate::llm::{LLMClient, GenerationRequest};
+
+/// Lines shown either side of the paused line
+const SOURCE_RADIUS: usize = 20;
+/// Frames beyond this are rarely relevant and crowd out the locals
+const MAX_FRAMES: usize = 15;
+/// Nested fields below this depth are summarised by their type only
+const MAX_VARIABLE_DEPTH: usize = 3;
+const MAX_VALUE_CHARS: usize = 200;
+
+/// What the debugger showed at the moment the question was asked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugStateSnapshot {
+    pub session_id: String,
+    pub language: String,
+    pub frame: StackFrame,
+    pub stack: Vec<StackFrame>,
+    pub variables: HashMap<String, VariableValue>,
+    /// Numbered source around the paused line, if the file could be read
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugAnswer {
+    pub answer: String,
+    pub snapshot: DebugStateSnapshot,
+}
+
+pub fn snapshot(session_id: &str) -> Result<DebugStateSnapshot> {
+    let session = debugging::session(session_id).context("Debug session not found")?;
+    if session.status != DebugStatus::Paused {
+        anyhow::bail!("Debug session is not paused");
+    }
+    let frame = session.current_frame.clone().context("No stack frame at the current stop")?;
+
+    let source = read_document(Path::new(&frame.file))
+        .ok()
+        .map(|content| {
+            let screened = injection::sanitize(&content, &frame.file);
+            if screened.quarantined {
+                screened.text
+            } else {
+                source_around(&screened.text, frame.line as usize)
+            }
+        });
+
+    Ok(DebugStateSnapshot {
+        session_id: session.id,
+        language: session.language,
+        stack: if session.stack.is_empty() { vec![frame.clone()] } else { session.stack },
+        frame,
+        variables: session.variables,
+        source,
+    })
+}
+
+/// Numbered lines around `line` (1-based), with the paused line marked
+fn source_around(content: &str, line: usize) -> String {
+    let from = line.saturating_sub(SOURCE_RADIUS).max(1);
+    content
+        .lines()
+        .enumerate()
+        .skip(from - 1)
+        .take(line + SOURCE_RADIUS + 1 - from)
+        .map(|(i, text)| {
+            let marker = if i + 1 == line { "->" } else { "  " };
+            format!("{} {:>5} | {}", marker, i + 1, text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn truncate(value: &str) -> String {
+    if value.chars().count() <= MAX_VALUE_CHARS {
+        return value.to_string();
+    }
+    let cut: String = value.chars().take(MAX_VALUE_CHARS).collect();
+    format!("{}…", cut)
+}
+
+fn format_variable(variable: &VariableValue, depth: usize, out: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    if depth >= MAX_VARIABLE_DEPTH && !variable.children.is_empty() {
+        out.push(format!("{}{}: {} = {{…{} fields}}", indent, variable.name, variable.type_name, variable.children.len()));
+        return;
+    }
+    out.push(format!("{}{}: {} = {}", indent, variable.name, variable.type_name, truncate(&variable.value)));
+    for child in &variable.children {
+        format_variable(child, depth + 1, out);
+    }
+}
+
+fn format_variables(variables: &HashMap<String, VariableValue>) -> String {
+    let mut sorted: Vec<&VariableValue> = variables.values().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut lines = Vec::new();
+    for variable in sorted {
+        format_variable(variable, 0, &mut lines);
+    }
+    if lines.is_empty() {
+        "(no locals reported)".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+fn format_stack(stack: &[StackFrame]) -> String {
+    let mut lines: Vec<String> = stack
+        .iter()
+        .take(MAX_FRAMES)
+        .enumerate()
+        .map(|(i, frame)| format!("#{} {} at {}:{}:{}", i, frame.name, frame.file, frame.line, frame.column))
+        .collect();
+    if stack.len() > MAX_FRAMES {
+        lines.push(format!("… {} more frames", stack.len() - MAX_FRAMES));
+    }
+    lines.join("\n")
+}
+
+/// Answer `question` about the paused program using its stack, locals and the code around the stop
+pub async fn ask(session_id: &str, question: &str) -> Result<DebugAnswer> {
+    let snapshot = snapshot(session_id)?;
+
+    let prompt = format!(
+        r#"A {language} program is paused in the debugger at {file}:{line} in `{function}`.
+
+Call stack (innermost first):
+{stack}
+
+Local variables:
+{variables}
+
+Source around the paused line (marked ->):
+```{language}
+{source}
+```
+
+Question: {question}
+
+Answer using the actual values above. If a value looks wrong, trace back through the code to where it was most likely set and say what to inspect or change next."#,
+        language = snapshot.language,
+        file = snapshot.frame.file,
+        line = snapshot.frame.line,
+        function = snapshot.frame.name,
+        stack = format_stack(&snapshot.stack),
+        variables = format_variables(&snapshot.variables),
+        source = snapshot.source.as_deref().unwrap_or("(source not available)"),
+        question = question
+    );
+
+    let request = GenerationRequest {
+        model: "deepseek-coder-v2:16b".to_string(),
+        prompt,
+        system_prompt: Some("You are an expert debugger helping a developer understand a paused program.".to_string()),
+        temperature: 0.3,
+        max_tokens: 2048,
+    };
+
+    let response = LLMClient::new().generate(request).await?;
+    Ok(DebugAnswer { answer: response.text.trim().to_string(), snapshot })
+}
+
+// Tauri commands
+
+/// Ask about the current stop of a paused debug session
+#[tauri::command]
+pub async fn ask_ai_about_debug_state(session_id: String, question: String) -> Result<DebugAnswer, String> {
+    crate::permissions::require_tool("debug_assistant").map_err(|e| e.to_string())?;
+    ask(&session_id, &question).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_formatting() {
+        let source = (1..=50).map(|i| format!("line {}", i)).collect::<Vec<_>>().join("\n");
+        let around = source_around(&source, 30);
+        assert!(around.starts_with("      10 | line 10"));
+        assert!(around.contains("->    30 | line 30"));
+        assert!(around.ends_with("line 50"));
+
+        let leaf = |name: &str| VariableValue {
+            name: name.to_string(),
+            value: "1".to_string(),
+            type_name: "int".to_string(),
+            children: vec![],
+        };
+        let mut nested = leaf("e");
+        for name in ["d", "c", "b", "a"] {
+            nested = VariableValue { children: vec![nested], ..leaf(name) };
+        }
+        let mut variables = HashMap::new();
+        variables.insert("a".to_string(), nested);
+        let formatted = format_variables(&variables);
+        assert!(formatted.contains("    c: int = 1"));
+        assert!(formatted.contains("      d: int = {…1 fields}"));
+        assert!(!formatted.contains("e: int"));
+    }
+}
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 use regex::Regex;
+use schemars::JsonSchema;
 
 use crate::llm::{LLMClient, GenerationRequest};
+use crate::llm::structured::generate_typed;
 use super::pipeline::ProjectPlan;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,7 +30,7 @@ pub enum DeploymentPlatform {
     Kubernetes,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DeploymentStep {
     pub order: usize,
     pub title: String,
@@ -37,6 +39,16 @@ pub struct DeploymentStep {
     pub notes: Vec<String>,
 }
 
+/// What the model fills in; the platform is known up front
+#[derive(Debug, Deserialize, JsonSchema)]
+struct GeneratedGuide {
+    steps: Vec<DeploymentStep>,
+    prerequisites: Vec<String>,
+    environment_setup: String,
+    deployment_script: Option<String>,
+    ci_cd_config: Option<String>,
+}
+
 pub struct DeploymentGenerator {
     llm_client: LLMClient,
 }
@@ -114,16 +126,16 @@ Generate ONLY valid JSON:",
             max_tokens: 3072,
         };
         
-        let response = self.llm_client.generate(request).await?;
-        let json_str = self.extract_json(&response.text)?;
-        
-        let mut guide: serde_json::Value = serde_json::from_str(&json_str)?;
+        let guide: GeneratedGuide = generate_typed(&self.llm_client, request).await?;
         
-        guide["platform"] = serde_json::json!(self.platform_to_string(&platform));
-        
-        let deployment_guide: DeploymentGuide = serde_json::from_value(guide)?;
-        
-        Ok(deployment_guide)
+        Ok(DeploymentGuide {
+            platform,
+            steps: guide.steps,
+            prerequisites: guide.prerequisites,
+            environment_setup: guide.environment_setup,
+            deployment_script: guide.deployment_script,
+            ci_cd_config: guide.ci_cd_config,
+        })
     }
     
     pub async fn generate_multiple_guides(
@@ -264,33 +276,6 @@ Include comments explaining each step."#,
         }
     }
 
-    fn platform_to_string(&self, platform: &DeploymentPlatform) -> String {
-        match platform {
-            DeploymentPlatform::Vercel => "vercel".to_string(),
-            DeploymentPlatform::Netlify => "netlify".to_string(),
-            DeploymentPlatform::Railway => "railway".to_string(),
-            DeploymentPlatform::Heroku => "heroku".to_string(),
-            DeploymentPlatform::AWS => "aws".to_string(),
-            DeploymentPlatform::DigitalOcean => "digitalocean".to_string(),
-            DeploymentPlatform::Docker => "docker".to_string(),
-            DeploymentPlatform::Kubernetes => "kubernetes".to_string(),
-        }
-    }
-    
-    fn extract_json(&self, text: &str) -> Result<String> {
-        let code_block_re = Regex::new(r"```(?:json)?\s*\n(.*?)\n```")?;
-        if let Some(captures) = code_block_re.captures(text) {
-            return Ok(captures.get(1).unwrap().as_str().to_string());
-        }
-        
-        let json_re = Regex::new(r"\{[\s\S]*\}")?;
-        if let Some(captures) = json_re.find(text) {
-            return Ok(captures.as_str().to_string());
-        }
-        
-        Ok(text.to_string())
-    }
-    
     fn clean_code(&self, text: &str) -> String {
         match Regex::new(r"```[\w]*\s*\n([\s\S]*?)\n```") {
             Ok(code_block_re) => {
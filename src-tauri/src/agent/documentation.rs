@@ -0,0 +1,394 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+use crate::agent::refactorer::CodeRefactorer;
+use crate::docs_site::{extract_api, ApiItem};
+use crate::filesystem::collect_source_files;
+use crate::syntax::{detect_language, SyntaxLanguage, SUPPORTED_EXTENSIONS};
+
+pub const DEFAULT_BATCH_SIZE: usize = 8;
+/// Lines of each function's source sent with its signature
+const MAX_FUNCTION_LINES: usize = 40;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalStatus {
+    Pending,
+    Accepted,
+    Rejected,
+    Applied,
+    /// The function moved or changed since the doc was generated
+    Stale,
+}
+
+/// A generated doc comment for one function, waiting for review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocProposal {
+    pub id: String,
+    pub file: String,
+    pub name: String,
+    /// 1-based line of the declaration when the proposal was made
+    pub line: usize,
+    pub signature: String,
+    /// Documentation text without comment markers
+    pub doc: String,
+    /// `doc` rendered as JSDoc, rustdoc or a docstring, exactly as it will be inserted
+    pub comment: String,
+    pub status: ProposalStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocApplyReport {
+    pub applied: usize,
+    pub stale: usize,
+    pub files_changed: Vec<String>,
+}
+
+/// One function the model is asked to document
+#[derive(Debug, Clone)]
+pub struct UndocumentedFunction {
+    pub file: String,
+    pub language: SyntaxLanguage,
+    pub item: ApiItem,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GeneratedDoc {
+    /// Index of the function in the request
+    pub id: usize,
+    pub doc: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GeneratedDocBatch {
+    pub docs: Vec<GeneratedDoc>,
+}
+
+fn proposals_path(project_path: &Path) -> PathBuf {
+    project_path.join(".sai-metadata").join("doc_proposals.json")
+}
+
+pub fn load_proposals(project_path: &Path) -> Result<Vec<DocProposal>> {
+    let path = proposals_path(project_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn save_proposals(project_path: &Path, proposals: &[DocProposal]) -> Result<()> {
+    let path = proposals_path(project_path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(proposals)?)?;
+    Ok(())
+}
+
+fn is_function(item: &ApiItem) -> bool {
+    matches!(item.kind.as_str(), "function" | "fn") || (item.kind == "const" && item.signature.contains("=>"))
+}
+
+/// Exported functions without a doc comment, in file order
+pub fn find_undocumented(project_path: &Path) -> Vec<UndocumentedFunction> {
+    let mut found = Vec::new();
+    for path in collect_source_files(project_path, SUPPORTED_EXTENSIONS) {
+        let relative = path.strip_prefix(project_path).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        if relative.contains(".test.") || relative.contains(".spec.") || relative.ends_with(".d.ts") {
+            continue;
+        }
+        let (Some(language), Ok(content)) = (detect_language(&path), std::fs::read_to_string(&path)) else { continue };
+        let Ok(items) = extract_api(&path, &content) else { continue };
+
+        let lines: Vec<&str> = content.lines().collect();
+        for item in items.into_iter().filter(|i| i.doc.is_none() && is_function(i)) {
+            let start = item.line.saturating_sub(1);
+            let source = lines[start..(start + MAX_FUNCTION_LINES).min(lines.len())].join("\n");
+            found.push(UndocumentedFunction { file: relative.clone(), language, item, source });
+        }
+    }
+    found
+}
+
+pub fn convention(language: SyntaxLanguage) -> &'static str {
+    match language {
+        SyntaxLanguage::TypeScript | SyntaxLanguage::Tsx => "JSDoc: a summary sentence, then @param and @returns tags",
+        SyntaxLanguage::Python => "a Google-style docstring: a summary sentence, then Args: and Returns: sections",
+        SyntaxLanguage::Rust => "rustdoc: a summary sentence, then a short paragraph on errors or panics if relevant",
+    }
+}
+
+fn indentation(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Comment lines for `doc`, indented to match the declaration
+pub fn render_comment(language: SyntaxLanguage, doc: &str, indent: &str) -> String {
+    let lines: Vec<&str> = doc.trim().lines().map(str::trim_end).collect();
+    match language {
+        SyntaxLanguage::TypeScript | SyntaxLanguage::Tsx => {
+            let mut out = format!("{}/**\n", indent);
+            for line in lines {
+                out.push_str(&format!("{} *{}{}\n", indent, if line.is_empty() { "" } else { " " }, line.replace("*/", "* /")));
+            }
+            out.push_str(&format!("{} */", indent));
+            out
+        }
+        SyntaxLanguage::Rust => lines
+            .iter()
+            .map(|line| format!("{}///{}{}", indent, if line.is_empty() { "" } else { " " }, line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        SyntaxLanguage::Python => {
+            let body = lines
+                .iter()
+                .map(|line| if line.is_empty() { String::new() } else { format!("{}{}", indent, line.replace("\"\"\"", "'''")) })
+                .collect::<Vec<_>>()
+                .join("\n");
+            if lines.len() == 1 {
+                format!("{}\"\"\"{}\"\"\"", indent, lines[0].replace("\"\"\"", "'''"))
+            } else {
+                format!("{}\"\"\"\n{}\n{}\"\"\"", indent, body, indent)
+            }
+        }
+    }
+}
+
+/// Where the comment goes, as a 0-based line index to insert before, and its indentation.
+/// Python docstrings go after the `def` header; everything else above the declaration.
+fn insertion_point(language: SyntaxLanguage, lines: &[&str], decl_index: usize) -> Option<(usize, String)> {
+    let decl = lines.get(decl_index)?;
+    if language != SyntaxLanguage::Python {
+        return Some((decl_index, indentation(decl).to_string()));
+    }
+
+    let header_end = (decl_index..lines.len()).find(|&i| lines[i].split('#').next().unwrap_or("").trim_end().ends_with(':'))?;
+    let body_indent = lines[header_end + 1..]
+        .iter()
+        .find(|l| !l.trim().is_empty())
+        .map(|l| indentation(l).to_string())
+        .filter(|i| i.len() > indentation(decl).len())
+        .unwrap_or_else(|| format!("{}    ", indentation(decl)));
+    Some((header_end + 1, body_indent))
+}
+
+/// Generate proposals for every undocumented exported function, replacing earlier pending ones
+pub async fn propose_documentation(
+    project_path: &Path,
+    batch_size: usize,
+    on_progress: impl Fn(usize, usize),
+) -> Result<Vec<DocProposal>> {
+    let root = project_path.to_path_buf();
+    let functions = tokio::task::spawn_blocking(move || find_undocumented(&root)).await?;
+    let refactorer = CodeRefactorer::new();
+
+    let mut proposals: Vec<DocProposal> = load_proposals(project_path)?
+        .into_iter()
+        .filter(|p| p.status != ProposalStatus::Pending)
+        .collect();
+
+    let batches: Vec<&[UndocumentedFunction]> = functions.chunks(batch_size.max(1)).collect();
+    for (index, batch) in batches.iter().enumerate() {
+        on_progress(index * batch_size, functions.len());
+
+        let docs = match refactorer.document_functions(batch).await {
+            Ok(docs) => docs,
+            Err(e) => {
+                tracing::warn!("Skipping documentation batch {}: {}", index + 1, e);
+                continue;
+            }
+        };
+        for generated in docs {
+            let function = &batch[generated.id];
+            let content = std::fs::read_to_string(project_path.join(&function.file)).unwrap_or_default();
+            let lines: Vec<&str> = content.lines().collect();
+            let indent = insertion_point(function.language, &lines, function.item.line - 1)
+                .map(|(_, indent)| indent)
+                .unwrap_or_default();
+
+            proposals.push(DocProposal {
+                id: uuid::Uuid::new_v4().to_string(),
+                file: function.file.clone(),
+                name: function.item.name.clone(),
+                line: function.item.line,
+                signature: function.item.signature.clone(),
+                comment: render_comment(function.language, &generated.doc, &indent),
+                doc: generated.doc.trim().to_string(),
+                status: ProposalStatus::Pending,
+            });
+        }
+    }
+    on_progress(functions.len(), functions.len());
+
+    save_proposals(project_path, &proposals)?;
+    Ok(proposals)
+}
+
+/// Accept or reject one proposal, optionally with edited text
+pub fn review_proposal(project_path: &Path, proposal_id: &str, accepted: bool, doc: Option<String>) -> Result<DocProposal> {
+    let mut proposals = load_proposals(project_path)?;
+    let proposal = proposals.iter_mut().find(|p| p.id == proposal_id).context("Proposal not found")?;
+    if proposal.status == ProposalStatus::Applied {
+        anyhow::bail!("Proposal was already applied");
+    }
+
+    if let Some(doc) = doc {
+        let path = project_path.join(&proposal.file);
+        let language = detect_language(&path).context("Unsupported file type")?;
+        let indent = proposal.comment.lines().next().map(indentation).unwrap_or("").to_string();
+        proposal.comment = render_comment(language, &doc, &indent);
+        proposal.doc = doc.trim().to_string();
+    }
+    proposal.status = if accepted { ProposalStatus::Accepted } else { ProposalStatus::Rejected };
+
+    let updated = proposal.clone();
+    save_proposals(project_path, &proposals)?;
+    Ok(updated)
+}
+
+/// Insert accepted comments as line edits, bottom-up so earlier insertions don't shift later ones
+pub fn apply_accepted(project_path: &Path) -> Result<DocApplyReport> {
+    let mut proposals = load_proposals(project_path)?;
+    let mut report = DocApplyReport::default();
+
+    let mut by_file: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (index, proposal) in proposals.iter().enumerate().filter(|(_, p)| p.status == ProposalStatus::Accepted) {
+        by_file.entry(proposal.file.clone()).or_default().push(index);
+    }
+    if by_file.len() > 1 {
+        crate::checkpoints::before_agent_run(project_path, "Apply documentation");
+    }
+
+    for (file, mut indexes) in by_file {
+        let path = project_path.join(&file);
+        let Some(language) = detect_language(&path) else { continue };
+        crate::readonly::check_writable(&path)?;
+        let _lock = crate::file_locks::acquire(&path, "agent", crate::file_locks::WRITE_TTL_SECS)?;
+        let content = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", file))?;
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+        indexes.sort_by_key(|&i| std::cmp::Reverse(proposals[i].line));
+        let mut changed = false;
+        for index in indexes {
+            let proposal = &mut proposals[index];
+            let decl_index = proposal.line - 1;
+            let borrowed: Vec<&str> = lines.iter().map(String::as_str).collect();
+
+            // The declaration must still be where it was when the doc was written
+            let still_there = borrowed.get(decl_index).map_or(false, |l| l.contains(&proposal.name));
+            let Some((at, _)) = insertion_point(language, &borrowed, decl_index).filter(|_| still_there) else {
+                proposal.status = ProposalStatus::Stale;
+                report.stale += 1;
+                continue;
+            };
+
+            let comment: Vec<String> = proposal.comment.lines().map(String::from).collect();
+            lines.splice(at..at, comment);
+            proposal.status = ProposalStatus::Applied;
+            report.applied += 1;
+            changed = true;
+        }
+
+        if changed {
+            let mut updated = lines.join("\n");
+            if content.ends_with('\n') {
+                updated.push('\n');
+            }
+            std::fs::write(&path, &updated)?;
+            crate::edit_journal::record(&path, "agent", Some(&content), &updated);
+            report.files_changed.push(file);
+        }
+    }
+
+    save_proposals(project_path, &proposals)?;
+    Ok(report)
+}
+
+// Tauri commands
+
+/// Generate doc comments for every exported function that lacks one. Nothing is
+/// written until proposals are accepted and applied. Emits `documentation-progress`.
+#[tauri::command]
+pub async fn document_project(
+    app: tauri::AppHandle,
+    project_path: String,
+    batch_size: Option<usize>,
+) -> Result<Vec<DocProposal>, String> {
+    crate::permissions::require_tool("documentation").map_err(|e| e.to_string())?;
+    propose_documentation(Path::new(&project_path), batch_size.unwrap_or(DEFAULT_BATCH_SIZE), |done, total| {
+        app.emit("documentation-progress", serde_json::json!({ "done": done, "total": total })).ok();
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_doc_proposals(project_path: String) -> Result<Vec<DocProposal>, String> {
+    load_proposals(Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn review_doc_proposal(
+    project_path: String,
+    proposal_id: String,
+    accepted: bool,
+    doc: Option<String>,
+) -> Result<DocProposal, String> {
+    review_proposal(Path::new(&project_path), &proposal_id, accepted, doc).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn apply_doc_proposals(project_path: String) -> Result<DocApplyReport, String> {
+    crate::permissions::require_tool("documentation").map_err(|e| e.to_string())?;
+    apply_accepted(Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proposal(file: &str, name: &str, line: usize, comment: &str) -> DocProposal {
+        DocProposal {
+            id: name.to_string(),
+            file: file.to_string(),
+            name: name.to_string(),
+            line,
+            signature: String::new(),
+            doc: String::new(),
+            comment: comment.to_string(),
+            status: ProposalStatus::Accepted,
+        }
+    }
+
+    #[test]
+    fn test_apply_inserts_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("math.ts"), "export function add(a: number, b: number) {\n  return a + b;\n}\n\nexport function sub(a: number, b: number) {\n  return a - b;\n}\n").unwrap();
+        std::fs::write(dir.path().join("util.py"), "def load(path):\n    return open(path)\n").unwrap();
+
+        let found = find_undocumented(dir.path());
+        assert_eq!(found.iter().map(|f| f.item.name.as_str()).collect::<Vec<_>>(), ["add", "sub", "load"]);
+
+        let ts = |doc: &str| render_comment(SyntaxLanguage::TypeScript, doc, "");
+        save_proposals(dir.path(), &[
+            proposal("math.ts", "add", 1, &ts("Adds two numbers.")),
+            proposal("math.ts", "sub", 5, &ts("Subtracts b from a.")),
+            proposal("util.py", "load", 1, &render_comment(SyntaxLanguage::Python, "Open a file.", "    ")),
+            proposal("util.py", "missing", 1, "# gone"),
+        ]).unwrap();
+
+        let report = apply_accepted(dir.path()).unwrap();
+        assert_eq!((report.applied, report.stale), (3, 1));
+
+        let math = std::fs::read_to_string(dir.path().join("math.ts")).unwrap();
+        assert!(math.starts_with("/**\n * Adds two numbers.\n */\nexport function add"));
+        assert!(math.contains("}\n\n/**\n * Subtracts b from a.\n */\nexport function sub"));
+        let util = std::fs::read_to_string(dir.path().join("util.py")).unwrap();
+        assert_eq!(util, "def load(path):\n    \"\"\"Open a file.\"\"\"\n    return open(path)\n");
+    }
+}
@@ -0,0 +1,443 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use schemars::JsonSchema;
+use similar::TextDiff;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::agent::injection;
+use crate::docs_cache::{self, DocCitation, DocsCache};
+use crate::documents::read_document;
+use crate::llm::{LLMClient, GenerationRequest};
+use crate::llm::structured::generate_typed;
+use crate::rename::{self, FileEdit, TextEdit};
+
+/// Lines shown either side of each referenced line
+const SNIPPET_RADIUS: usize = 15;
+/// Error output often repeats the same frames; the first few carry the signal
+const MAX_LOCATIONS: usize = 6;
+/// Context files longer than this are cut off in the prompt
+const MAX_CONTEXT_LINES: usize = 400;
+/// Keep the tail of very long output, where the actual error usually is
+const MAX_OUTPUT_CHARS: usize = 8000;
+/// Cached documentation pages quoted alongside the code
+const MAX_REFERENCES: usize = 2;
+
+lazy_static::lazy_static! {
+    /// Python tracebacks: `File "app/main.py", line 12`
+    static ref PYTHON_LOCATION: Regex = Regex::new(r#"File "([^"]+)", line (\d+)"#).unwrap();
+    /// tsc: `src/app.ts(12,5): error TS2322`
+    static ref TSC_LOCATION: Regex = Regex::new(r"([\w./\\@-]+\.\w+)\((\d+),\d+\)").unwrap();
+    /// rustc `--> src/main.rs:12:5`, node/jest stack frames, go, eslint and most others
+    static ref PATH_LOCATION: Regex = Regex::new(r"(?:file://)?([\w./\\@-]+\.[A-Za-z]{1,5}):(\d+)(?::\d+)?").unwrap();
+}
+
+/// A file and line referenced by error output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ErrorLocation {
+    /// Path as written in the output
+    pub file: String,
+    /// Resolved path on disk
+    pub path: String,
+    /// 1-based
+    pub line: usize,
+}
+
+/// Replace lines `start_line..=end_line` (1-based) of `file` with `replacement`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LineEdit {
+    /// File path exactly as labelled in the prompt
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorAnalysis {
+    /// What the error means, in plain terms
+    pub diagnosis: String,
+    /// Where in the code the problem comes from and why
+    pub likely_cause: String,
+    /// Summary of the proposed fix; omit when no code change would help
+    pub fix_description: Option<String>,
+    /// Line replacements that fix the error; empty when the fix is outside the shown code
+    pub edits: Vec<LineEdit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedFix {
+    pub description: String,
+    /// Ready for `rename::apply_file_edits`
+    pub files: Vec<FileEdit>,
+    /// Unified diff of the change, for review
+    pub patch: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorExplanation {
+    pub diagnosis: String,
+    pub likely_cause: String,
+    pub locations: Vec<ErrorLocation>,
+    pub fix: Option<ProposedFix>,
+    /// Locally cached documentation the model was shown
+    #[serde(default)]
+    pub references: Vec<DocCitation>,
+}
+
+/// Code shown to the model, keyed by the label it is told to use in edits
+struct ShownFile {
+    path: PathBuf,
+    content: String,
+}
+
+/// Dependency and toolchain frames are never where the fix goes
+fn is_external(path: &str) -> bool {
+    ["node_modules", "site-packages", "dist-packages", ".cargo/registry", "/rustc/"]
+        .iter()
+        .any(|marker| path.replace('\\', "/").contains(marker))
+}
+
+fn resolve(file: &str, project_path: Option<&Path>) -> Option<PathBuf> {
+    let candidate = Path::new(file);
+    let path = match project_path {
+        Some(root) if candidate.is_relative() => root.join(candidate),
+        _ => candidate.to_path_buf(),
+    };
+    path.is_file().then_some(path)
+}
+
+/// File/line references in compiler, test runner or runtime output, in order of appearance.
+/// Only references that exist on disk and are not in dependencies are kept.
+pub fn find_locations(output: &str, project_path: Option<&Path>) -> Vec<ErrorLocation> {
+    let mut found: Vec<(std::ops::Range<usize>, String, usize)> = Vec::new();
+    for pattern in [&*PYTHON_LOCATION, &*TSC_LOCATION, &*PATH_LOCATION] {
+        for captures in pattern.captures_iter(output) {
+            let span = captures.get(0).map(|m| m.range()).unwrap_or_default();
+            // Later, more generic patterns can match inside a reference already found
+            if found.iter().any(|(seen, _, _)| seen.start < span.end && span.start < seen.end) {
+                continue;
+            }
+            if let Ok(line) = captures[2].parse::<usize>() {
+                found.push((span, captures[1].to_string(), line));
+            }
+        }
+    }
+    found.sort_by_key(|(span, _, _)| span.start);
+
+    let mut locations: Vec<ErrorLocation> = Vec::new();
+    for (_, file, line) in found {
+        if line == 0 || is_external(&file) {
+            continue;
+        }
+        let Some(path) = resolve(&file, project_path) else { continue };
+        let path = path.to_string_lossy().to_string();
+        if locations.iter().any(|l| l.path == path && l.line == line) {
+            continue;
+        }
+        locations.push(ErrorLocation { file, path, line });
+        if locations.len() == MAX_LOCATIONS {
+            break;
+        }
+    }
+    locations
+}
+
+fn numbered(content: &str, from: usize, to: usize) -> String {
+    content
+        .lines()
+        .enumerate()
+        .skip(from - 1)
+        .take(to + 1 - from)
+        .map(|(i, line)| format!("{:>5} | {}", i + 1, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn label_for(path: &Path, project_path: Option<&Path>) -> String {
+    project_path
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// The line that names the error, e.g. "TypeError: x is not a function", for documentation lookups
+fn error_summary(output: &str) -> &str {
+    let lines: Vec<&str> = output.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    lines
+        .iter()
+        .rev()
+        .find(|l| l.contains("Error") || l.starts_with("error") || l.contains("panicked"))
+        .or(lines.last())
+        .copied()
+        .unwrap_or_default()
+}
+
+fn tail(text: &str, max_chars: usize) -> &str {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text;
+    }
+    let start = text.char_indices().nth(count - max_chars).map(|(i, _)| i).unwrap_or(0);
+    &text[start..]
+}
+
+/// Turn the model's line edits into editor edits, dropping any that point outside
+/// the shown files or past their end
+fn to_file_edits(edits: &[LineEdit], shown: &BTreeMap<String, ShownFile>) -> Vec<FileEdit> {
+    let mut by_file: BTreeMap<String, Vec<TextEdit>> = BTreeMap::new();
+    for edit in edits {
+        let Some(file) = shown.get(edit.file.trim_start_matches("./")) else {
+            tracing::warn!("Ignoring fix for {} which was not in the prompt", edit.file);
+            continue;
+        };
+        let line_count = file.content.lines().count();
+        if edit.start_line == 0 || edit.start_line > edit.end_line || edit.end_line > line_count {
+            tracing::warn!("Ignoring out-of-range fix for {} lines {}-{}", edit.file, edit.start_line, edit.end_line);
+            continue;
+        }
+
+        let mut new_text = edit.replacement.clone();
+        if !new_text.is_empty() && !new_text.ends_with('\n') {
+            new_text.push('\n');
+        }
+        // Whole-line replacement: from the start of the first line to the start of the line after the last
+        by_file.entry(file.path.to_string_lossy().to_string()).or_default().push(TextEdit {
+            start_line: edit.start_line - 1,
+            start_character: 0,
+            end_line: edit.end_line,
+            end_character: 0,
+            new_text,
+        });
+    }
+
+    by_file
+        .into_iter()
+        .map(|(file, edits)| FileEdit { file, edits })
+        .collect()
+}
+
+fn patch_for(files: &[FileEdit], shown: &BTreeMap<String, ShownFile>) -> String {
+    files
+        .iter()
+        .filter_map(|file_edit| {
+            let (label, shown_file) = shown.iter().find(|(_, s)| s.path.to_string_lossy() == file_edit.file)?;
+            let updated = rename::apply_edits(&shown_file.content, &file_edit.edits);
+            Some(
+                TextDiff::from_lines(&shown_file.content, &updated)
+                    .unified_diff()
+                    .context_radius(3)
+                    .header(&format!("a/{}", label), &format!("b/{}", label))
+                    .to_string(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Diagnose `output_text` from a build, test run or crash, with the referenced code
+/// and any `context_files` in view, and propose a fix as editor edits
+pub async fn explain(
+    output_text: &str,
+    context_files: &[String],
+    project_path: Option<&Path>,
+) -> Result<ErrorExplanation> {
+    let output_text = tail(output_text, MAX_OUTPUT_CHARS);
+    let locations = find_locations(output_text, project_path);
+
+    let mut shown: BTreeMap<String, ShownFile> = BTreeMap::new();
+    // What the model sees of each file, after injection screening; line numbers match `shown`
+    let mut screened: BTreeMap<String, String> = BTreeMap::new();
+    let mut code_sections = Vec::new();
+
+    for location in &locations {
+        let path = PathBuf::from(&location.path);
+        let label = label_for(&path, project_path);
+        if !shown.contains_key(&label) {
+            let content = read_document(&path).with_context(|| format!("Failed to read {}", location.path))?;
+            screened.insert(label.clone(), injection::guard(&content, &label).await.text);
+            shown.insert(label.clone(), ShownFile { path: path.clone(), content });
+        }
+        let content = &screened[&label];
+        let line_count = content.lines().count().max(1);
+        let to = (location.line + SNIPPET_RADIUS).min(line_count);
+        // Stale output can point past the end of a file that has since shrunk
+        let from = location.line.saturating_sub(SNIPPET_RADIUS).clamp(1, to);
+        code_sections.push(format!(
+            "File: {} (lines {}-{}, error at line {})\n```\n{}\n```",
+            label, from, to, location.line, numbered(content, from, to)
+        ));
+    }
+
+    for context_file in context_files {
+        let Some(path) = resolve(context_file, project_path) else {
+            tracing::warn!("Context file {} not found", context_file);
+            continue;
+        };
+        let label = label_for(&path, project_path);
+        let content = match screened.get(&label) {
+            Some(content) => content.clone(),
+            None => {
+                let content = read_document(&path).with_context(|| format!("Failed to read {}", context_file))?;
+                let safe = injection::guard(&content, &label).await.text;
+                screened.insert(label.clone(), safe.clone());
+                shown.insert(label.clone(), ShownFile { path, content });
+                safe
+            }
+        };
+        let to = content.lines().count().min(MAX_CONTEXT_LINES);
+        if to > 0 {
+            code_sections.push(format!("File: {} (lines 1-{})\n```\n{}\n```", label, to, numbered(&content, 1, to)));
+        }
+    }
+
+    let code = if code_sections.is_empty() {
+        "No source files could be located from the output.".to_string()
+    } else {
+        code_sections.join("\n\n")
+    };
+
+    // Offline docs, when any are cached, so the answer can point at a reference
+    let references = match DocsCache::new() {
+        Ok(cache) if cache.has_docsets() => cache.cite(error_summary(output_text), MAX_REFERENCES).unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    let docs = match docs_cache::format_citations(&references) {
+        // Cached docs come from the web, so they are screened like fetched pages
+        Some(cited) => format!(
+            "\n\nReference documentation from the local cache (cite as [n] if you rely on it):\n\n{}",
+            injection::guard(&cited, "documentation cache").await.text
+        ),
+        None => String::new(),
+    };
+
+    let prompt = format!(
+        r#"This error was captured from a compiler, test runner or running program:
+
+```
+{output}
+```
+
+Relevant source code (line numbers on the left):
+
+{code}{docs}
+
+Provide:
+1. diagnosis - What the error means
+2. likely_cause - Which code causes it and why
+3. fix_description - How to fix it
+4. edits - Line replacements that fix it. Use the file labels above, 1-based inclusive line numbers, and the full replacement text for those lines without line numbers. Only edit code shown above; leave edits empty if the fix is elsewhere."#,
+        output = output_text,
+        code = code,
+        docs = docs
+    );
+
+    let request = GenerationRequest {
+        model: "deepseek-coder-v2:16b".to_string(),
+        prompt,
+        system_prompt: Some("You are an expert debugger. Find the root cause of errors and propose minimal, correct fixes.".to_string()),
+        temperature: 0.2,
+        max_tokens: 2048,
+    };
+
+    let client = LLMClient::new();
+    let analysis: ErrorAnalysis = generate_typed(&client, request).await?;
+
+    let files = to_file_edits(&analysis.edits, &shown);
+    let fix = (!files.is_empty()).then(|| ProposedFix {
+        description: analysis.fix_description.clone().unwrap_or_default(),
+        patch: patch_for(&files, &shown),
+        files,
+    });
+
+    Ok(ErrorExplanation {
+        diagnosis: analysis.diagnosis,
+        likely_cause: analysis.likely_cause,
+        locations,
+        fix,
+        references,
+    })
+}
+
+// Tauri commands
+
+/// Explain an error pasted from the terminal or test runner. Relative paths in the
+/// output and in `context_files` are resolved against `project_path`.
+#[tauri::command]
+pub async fn explain_error(
+    output_text: String,
+    context_files: Option<Vec<String>>,
+    project_path: Option<String>,
+) -> Result<ErrorExplanation, String> {
+    crate::permissions::require_tool("error_explainer").map_err(|e| e.to_string())?;
+    explain(
+        &output_text,
+        &context_files.unwrap_or_default(),
+        project_path.as_deref().map(Path::new),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Apply a proposed fix; `undo_rename` reverts it
+#[tauri::command]
+pub async fn apply_error_fix(fix: ProposedFix) -> Result<usize, String> {
+    crate::permissions::require_tool("error_fix").map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        crate::checkpoints::before_file_edits(&fix.files, &format!("Fix: {}", fix.description));
+        rename::apply_file_edits(&fix.files, "agent")
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_locations_across_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        for file in ["src/main.rs", "src/app.ts", "app.py"] {
+            std::fs::write(dir.path().join(file), "x\n".repeat(30)).unwrap();
+        }
+
+        let output = "\
+error[E0308]: mismatched types
+  --> src/main.rs:12:5
+src/app.ts(7,3): error TS2322: Type 'string' is not assignable to type 'number'.
+    at load (node_modules/lib/index.js:4:1)
+  File \"app.py\", line 21, in <module>
+  --> src/main.rs:12:5
+  --> src/missing.rs:3:1";
+
+        let locations = find_locations(output, Some(dir.path()));
+        let found: Vec<_> = locations.iter().map(|l| (l.file.as_str(), l.line)).collect();
+        assert_eq!(found, vec![("src/main.rs", 12), ("src/app.ts", 7), ("app.py", 21)]);
+    }
+
+    #[test]
+    fn test_line_edits_are_checked_against_shown_files() {
+        let mut shown = BTreeMap::new();
+        shown.insert("src/a.ts".to_string(), ShownFile {
+            path: PathBuf::from("/p/src/a.ts"),
+            content: "one\ntwo\nthree\n".to_string(),
+        });
+        let edit = |file: &str, start_line, end_line| LineEdit {
+            file: file.to_string(),
+            start_line,
+            end_line,
+            replacement: "TWO".to_string(),
+        };
+
+        let files = to_file_edits(&[edit("src/a.ts", 2, 2), edit("src/b.ts", 1, 1), edit("src/a.ts", 3, 9)], &shown);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].edits.len(), 1);
+        assert_eq!(rename::apply_edits("one\ntwo\nthree\n", &files[0].edits), "one\nTWO\nthree\n");
+        assert!(patch_for(&files, &shown).contains("+TWO"));
+    }
+}
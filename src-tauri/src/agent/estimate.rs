@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+
+use crate::agent::pipeline::{AgentPipeline, NodeType, ProjectPlan, ProjectRequest, PIPELINE_MODEL};
+use crate::llm::throughput;
+
+/// Rough tokens-per-character ratio for English text and code
+const CHARS_PER_TOKEN: usize = 4;
+/// Fixed instructions and system prompt around each file prompt
+const FILE_PROMPT_OVERHEAD: u64 = 300;
+/// Typical generated file; most land well under the 4096 token cap
+const AVG_FILE_TOKENS: u64 = 900;
+const README_TOKENS: u64 = 1200;
+/// Each earlier file contributes a 15-line preview, up to 5 files
+const CONTEXT_TOKENS_PER_FILE: u64 = 150;
+const MAX_CONTEXT_FILES: u64 = 5;
+/// Prompt processing runs this many times faster than generation
+const PROMPT_EVAL_SPEEDUP: f64 = 10.0;
+
+/// List prices in USD per million (input, output) tokens
+const CLOUD_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("claude-3-5-sonnet", 3.00, 15.00),
+    ("gemini-1.5-pro", 1.25, 5.00),
+    ("gemini-1.5-flash", 0.075, 0.30),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudCost {
+    pub model: String,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationEstimate {
+    pub request: ProjectRequest,
+    pub plan: ProjectPlan,
+    pub model: String,
+    pub file_count: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+    pub tokens_per_sec: f64,
+    /// False when no generation has been timed for this model yet and the
+    /// speed is a guess from the hardware profile
+    pub throughput_measured: bool,
+    pub estimated_seconds: u64,
+    /// What the same token counts would cost on hosted models
+    pub cloud_costs: Vec<CloudCost>,
+}
+
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() / CHARS_PER_TOKEN) as u64
+}
+
+/// Input and output tokens for regenerating the plan, every file and the README
+pub fn token_counts(plan: &ProjectPlan) -> (u64, u64) {
+    let plan_json = serde_json::to_string(plan).unwrap_or_default();
+    let mut input = FILE_PROMPT_OVERHEAD + estimate_tokens(&plan.description);
+    let mut output = estimate_tokens(&plan_json);
+
+    let files = plan.file_structure.iter().filter(|f| matches!(f.node_type, NodeType::File));
+    for (index, file) in files.enumerate() {
+        let context = (index as u64).min(MAX_CONTEXT_FILES) * CONTEXT_TOKENS_PER_FILE;
+        input += FILE_PROMPT_OVERHEAD + estimate_tokens(&file.path) + estimate_tokens(&file.description) + context;
+        output += AVG_FILE_TOKENS;
+    }
+
+    input += FILE_PROMPT_OVERHEAD + estimate_tokens(&plan_json) / 2;
+    output += README_TOKENS;
+    (input, output)
+}
+
+/// Measured speed for `model`, or a conservative guess for this hardware
+fn tokens_per_sec(model: &str) -> (f64, bool) {
+    if let Some(measured) = throughput::measured(model) {
+        return (measured.tokens_per_sec, true);
+    }
+    let profile = crate::hardware::profile();
+    let guess = if profile.has_usable_gpu() { 40.0 } else { 8.0 };
+    (guess, false)
+}
+
+pub fn cloud_costs(input_tokens: u64, output_tokens: u64) -> Vec<CloudCost> {
+    CLOUD_PRICES
+        .iter()
+        .map(|(model, input_price, output_price)| CloudCost {
+            model: model.to_string(),
+            cost_usd: (input_tokens as f64 * input_price + output_tokens as f64 * output_price) / 1_000_000.0,
+        })
+        .collect()
+}
+
+/// Run understanding and planning, then size up the rest of the generation
+pub async fn estimate(description: &str) -> Result<GenerationEstimate> {
+    let pipeline = AgentPipeline::new();
+    let request = pipeline.understand_request(description).await?;
+    let plan = pipeline.create_plan(&request).await?;
+
+    let (input_tokens, output_tokens) = token_counts(&plan);
+    let (rate, measured) = tokio::task::spawn_blocking(|| tokens_per_sec(PIPELINE_MODEL)).await?;
+    let seconds = output_tokens as f64 / rate + input_tokens as f64 / (rate * PROMPT_EVAL_SPEEDUP);
+
+    Ok(GenerationEstimate {
+        file_count: plan.file_structure.iter().filter(|f| matches!(f.node_type, NodeType::File)).count() + 1,
+        request,
+        plan,
+        model: PIPELINE_MODEL.to_string(),
+        input_tokens,
+        output_tokens,
+        total_tokens: input_tokens + output_tokens,
+        tokens_per_sec: rate,
+        throughput_measured: measured,
+        estimated_seconds: seconds.ceil() as u64,
+        cloud_costs: cloud_costs(input_tokens, output_tokens),
+    })
+}
+
+// Tauri commands
+
+/// Estimate tokens, time and cost for a project before committing to full generation
+#[tauri::command]
+pub async fn estimate_generation(description: String) -> Result<GenerationEstimate, String> {
+    estimate(&description).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::pipeline::FileNode;
+
+    #[test]
+    fn test_token_counts_scale_with_files() {
+        let node = |path: &str, node_type| FileNode {
+            path: path.to_string(),
+            node_type,
+            description: "Entry point".to_string(),
+            priority: 1,
+        };
+        let mut plan = ProjectPlan {
+            name: "app".to_string(),
+            description: "A small app".to_string(),
+            file_structure: vec![node("src", NodeType::Directory), node("src/main.ts", NodeType::File)],
+            dependencies: vec![],
+            setup_commands: vec![],
+            environment_variables: vec![],
+        };
+
+        let (_, one_file) = token_counts(&plan);
+        plan.file_structure.push(node("src/util.ts", NodeType::File));
+        let (_, two_files) = token_counts(&plan);
+        assert!(two_files - one_file >= AVG_FILE_TOKENS);
+
+        let costs = cloud_costs(1_000_000, 0);
+        assert_eq!(costs.iter().find(|c| c.model == "gpt-4o").unwrap().cost_usd, 2.5);
+    }
+}
@@ -0,0 +1,415 @@
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::Emitter;
+
+use crate::agent::context::{self, ContextRequest};
+use crate::agent::guardrails::{self, GuardrailConfig};
+use crate::agent::pipeline::{
+    project_relative, write_generated_file, AgentPipeline, GeneratedFile, GenerationProgress, PipelineStage,
+    RegeneratedFile, PIPELINE_MODEL,
+};
+use crate::filesystem::collect_source_files;
+use crate::llm::structured::generate_typed;
+use crate::llm::{GenerationRequest, LLMClient};
+use crate::project_profile;
+use crate::rename::{self, FileEdit, TextEdit};
+
+/// Files listed to the planner so it can see the layout and naming
+const TREE_EXTENSIONS: &[&str] = &[
+    "ts", "tsx", "js", "jsx", "mjs", "vue", "svelte", "py", "rs", "go", "java", "kt", "cs", "c", "h", "cpp",
+    "hpp", "swift", "dart", "rb", "php", "css", "scss", "html", "sql", "md", "toml", "json", "yaml", "yml",
+];
+const MAX_TREE_FILES: usize = 300;
+/// Tokens of existing code shown as examples of the project's conventions
+const CONVENTION_BUDGET: u64 = 6_000;
+/// A feature touching more files than this is better split into several requests
+const MAX_CHANGES: usize = 15;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub enum ChangeAction {
+    Create,
+    Modify,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PlannedChange {
+    /// Relative to the project root
+    pub path: String,
+    pub action: ChangeAction,
+    /// What the file gains for the feature
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExtensionPlan {
+    pub summary: String,
+    /// In the order they should be written, dependencies first
+    pub changes: Vec<PlannedChange>,
+}
+
+/// Replace lines `start_line..=end_line` (1-based) with `replacement`. An `end_line`
+/// of `start_line - 1` inserts before `start_line` without removing anything.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LineReplacement {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub replacement: String,
+}
+
+/// Edits to an existing file; line numbers refer to the version shown in the prompt
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileModification {
+    pub edits: Vec<LineReplacement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectExtension {
+    pub plan: ExtensionPlan,
+    pub files: Vec<RegeneratedFile>,
+    /// Whether `files` were written; a preview leaves the project untouched
+    pub applied: bool,
+    /// Checkpoint taken before writing, to roll the whole feature back
+    pub checkpoint_id: Option<String>,
+}
+
+/// Drop duplicate and unsafe paths and fix each action against the disk: the model
+/// sometimes "creates" a file that exists or "modifies" one that doesn't
+pub fn validate_plan(project_path: &Path, plan: &mut ExtensionPlan) -> Result<()> {
+    let mut seen = HashSet::new();
+    plan.changes.retain(|change| {
+        let safe = project_relative(&change.path).is_ok() && !change.path.trim().is_empty();
+        if !safe {
+            tracing::warn!("Dropping planned change outside the project: {}", change.path);
+        }
+        safe && seen.insert(change.path.clone())
+    });
+    for change in &mut plan.changes {
+        change.action = if project_path.join(&change.path).is_file() { ChangeAction::Modify } else { ChangeAction::Create };
+    }
+    if plan.changes.is_empty() {
+        anyhow::bail!("The plan doesn't change any files");
+    }
+    if plan.changes.len() > MAX_CHANGES {
+        anyhow::bail!(
+            "The plan touches {} files; describe a smaller feature (at most {})",
+            plan.changes.len(),
+            MAX_CHANGES
+        );
+    }
+    Ok(())
+}
+
+/// Turn the model's line replacements into editor edits against `original`. Out-of-range
+/// or overlapping edits reject the whole change: applying the rest could leave the
+/// file half-updated.
+pub fn to_text_edits(path: &str, original: &str, modification: &FileModification) -> Result<Vec<TextEdit>> {
+    let line_count = original.lines().count();
+    let mut edits: Vec<&LineReplacement> = modification.edits.iter().collect();
+    edits.sort_by_key(|e| e.start_line);
+
+    let mut text_edits = Vec::new();
+    let mut next_free = 1;
+    for edit in edits {
+        if edit.start_line == 0 || edit.end_line + 1 < edit.start_line || edit.end_line > line_count {
+            anyhow::bail!("Edit to {} has out-of-range lines {}-{}", path, edit.start_line, edit.end_line);
+        }
+        if edit.start_line < next_free {
+            anyhow::bail!("Edits to {} overlap at line {}", path, edit.start_line);
+        }
+        next_free = edit.end_line + 1;
+
+        let mut new_text = edit.replacement.clone();
+        if !new_text.is_empty() && !new_text.ends_with('\n') {
+            new_text.push('\n');
+        }
+        // Whole lines: from the start of the first line to the start of the line after the last
+        text_edits.push(TextEdit {
+            start_line: edit.start_line - 1,
+            start_character: 0,
+            end_line: edit.end_line,
+            end_character: 0,
+            new_text,
+        });
+    }
+    if text_edits.is_empty() {
+        anyhow::bail!("The model returned no edits for {}", path);
+    }
+    Ok(text_edits)
+}
+
+fn numbered(content: &str) -> String {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| format!("{:>5} | {}", i + 1, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Relative paths of the project's source files, ignore rules applied
+fn project_tree(project_path: &Path) -> String {
+    let mut paths: Vec<String> = collect_source_files(project_path, TREE_EXTENSIONS)
+        .iter()
+        .map(|p| p.strip_prefix(project_path).unwrap_or(p).to_string_lossy().replace('\\', "/"))
+        .collect();
+    paths.sort();
+    let more = paths.len().saturating_sub(MAX_TREE_FILES);
+    paths.truncate(MAX_TREE_FILES);
+    if more > 0 {
+        paths.push(format!("... and {} more", more));
+    }
+    paths.join("\n")
+}
+
+/// Stack, layout and convention samples for the prompts
+struct ProjectAnalysis {
+    profile: String,
+    tree: String,
+    conventions: String,
+}
+
+fn analyze(project_path: &Path, feature: &str) -> Result<ProjectAnalysis> {
+    let profile = project_profile::ensure(project_path)?;
+    let request = ContextRequest { active_file: None, query: Some(feature.to_string()) };
+    Ok(ProjectAnalysis {
+        profile: project_profile::prompt_context(&profile),
+        tree: project_tree(project_path),
+        conventions: context::select(project_path, CONVENTION_BUDGET, &request).render(),
+    })
+}
+
+async fn plan_extension(analysis: &ProjectAnalysis, feature: &str) -> Result<ExtensionPlan> {
+    let prompt = format!(
+        r#"Plan how to add this feature to an existing project:
+{}
+
+Project:
+{}
+
+Files:
+{}
+
+Related existing code:
+{}
+
+List the files to create or modify. Follow the project's existing conventions:
+put new files in the folders similar code already lives in, name them the way
+neighbouring files are named, and import through the same paths and aliases.
+Prefer modifying existing files over creating parallel ones. Don't touch
+lockfiles or generated output."#,
+        feature, analysis.profile, analysis.tree, analysis.conventions
+    );
+    let request = GenerationRequest {
+        model: PIPELINE_MODEL.to_string(),
+        prompt,
+        system_prompt: Some("You are a senior engineer who extends codebases without breaking their conventions.".to_string()),
+        temperature: 0.3,
+        max_tokens: 2048,
+    };
+    generate_typed(&LLMClient::new(), request).await
+}
+
+fn change_prompt(
+    analysis: &ProjectAnalysis,
+    feature: &str,
+    plan: &ExtensionPlan,
+    change: &PlannedChange,
+    original: Option<&str>,
+    done: &str,
+) -> String {
+    let mut prompt = format!(
+        r#"Feature being added: {}
+Plan: {}
+
+File: {}
+Change: {}
+
+Project:
+{}
+
+Related existing code (match its style, naming and imports):
+{}
+
+Files already written for this feature:
+{}"#,
+        feature, plan.summary, change.path, change.description, analysis.profile, analysis.conventions, done
+    );
+    match original {
+        Some(current) => prompt.push_str(&format!(
+            "\n\nCurrent version of {} (line numbers are not part of the file):\n{}\n\n\
+             Return only the edits: each replaces lines start_line..=end_line with replacement. \
+             To insert without removing, set end_line to start_line - 1. Edits must not overlap; \
+             leave everything unrelated to the feature unchanged.",
+            change.path,
+            numbered(current)
+        )),
+        None => prompt.push_str("\n\nReturn the COMPLETE content of the new file."),
+    }
+    prompt
+}
+
+fn edit_request(prompt: String) -> GenerationRequest {
+    GenerationRequest {
+        model: PIPELINE_MODEL.to_string(),
+        prompt,
+        system_prompt: Some("You are a senior engineer who extends codebases without breaking their conventions.".to_string()),
+        temperature: 0.3,
+        max_tokens: 4096,
+    }
+}
+
+/// Plan the feature against the project's stack and layout, generate each file and,
+/// unless `preview_only`, checkpoint the project and write them
+pub async fn extend_project(
+    project_path: &Path,
+    feature: &str,
+    preview_only: bool,
+    progress: impl Fn(GenerationProgress),
+) -> Result<ProjectExtension> {
+    progress(GenerationProgress {
+        stage: PipelineStage::Understanding,
+        progress: 0.05,
+        message: "Analyzing the project".to_string(),
+    });
+    let analysis = {
+        let (project_path, feature) = (project_path.to_path_buf(), feature.to_string());
+        tokio::task::spawn_blocking(move || analyze(&project_path, &feature)).await??
+    };
+
+    progress(GenerationProgress { stage: PipelineStage::Planning, progress: 0.15, message: "Planning changes".to_string() });
+    let mut plan = plan_extension(&analysis, feature).await?;
+    validate_plan(project_path, &mut plan)?;
+
+    let pipeline = AgentPipeline::new();
+    let mut written: Vec<GeneratedFile> = Vec::new();
+    let mut files = Vec::new();
+    let mut edits: Vec<FileEdit> = Vec::new();
+    for (index, change) in plan.changes.iter().enumerate() {
+        progress(GenerationProgress {
+            stage: PipelineStage::GeneratingCode,
+            progress: 0.2 + 0.7 * index as f32 / plan.changes.len() as f32,
+            message: format!("{} {}", if change.action == ChangeAction::Create { "Creating" } else { "Updating" }, change.path),
+        });
+        let original = std::fs::read_to_string(project_path.join(&change.path)).ok();
+        let prompt = change_prompt(&analysis, feature, &plan, change, original.as_deref(), &pipeline.build_file_context(&written));
+        // Existing files are changed through edits rather than regenerated whole, so a
+        // file longer than one response can't come back truncated
+        let content = match &original {
+            Some(current) => {
+                let modification: FileModification = generate_typed(&LLMClient::new(), edit_request(prompt)).await?;
+                let text_edits = to_text_edits(&change.path, current, &modification)?;
+                let content = rename::apply_edits(current, &text_edits);
+                if let Some(violation) = guardrails::process(&content, &change.path, &GuardrailConfig::default()).violations.first() {
+                    anyhow::bail!("Rejected edits to {}: {}", change.path, violation);
+                }
+                edits.push(FileEdit { file: project_path.join(&change.path).to_string_lossy().to_string(), edits: text_edits });
+                content
+            }
+            None => pipeline.generate_guarded(&change.path, prompt).await?,
+        };
+        let diff = TextDiff::from_lines(original.as_deref().unwrap_or(""), &content)
+            .unified_diff()
+            .context_radius(3)
+            .header(&format!("a/{}", change.path), &format!("b/{}", change.path))
+            .to_string();
+        let language = pipeline.detect_language(&change.path);
+        written.push(GeneratedFile { path: change.path.clone(), content: content.clone(), language: language.clone() });
+        files.push(RegeneratedFile { path: change.path.clone(), language, original, content, diff });
+    }
+
+    let mut checkpoint_id = None;
+    if !preview_only {
+        checkpoint_id = crate::checkpoints::before_agent_run(project_path, &format!("Extend: {}", feature));
+        let with_checkpoint = |e: anyhow::Error| match &checkpoint_id {
+            Some(id) => anyhow::anyhow!("{} (restore checkpoint {} to undo)", e, id),
+            None => e,
+        };
+        rename::apply_file_edits(&edits, "agent").map_err(with_checkpoint)?;
+        for file in files.iter().filter(|f| f.original.is_none()) {
+            write_generated_file(project_path, &file.path, &file.content)
+                .map_err(|e| with_checkpoint(anyhow::anyhow!("Failed to write {}: {}", file.path, e)))?;
+        }
+    }
+
+    progress(GenerationProgress { stage: PipelineStage::Complete, progress: 1.0, message: plan.summary.clone() });
+    Ok(ProjectExtension { plan, files, applied: !preview_only, checkpoint_id })
+}
+
+// Tauri commands
+
+/// Add a feature to an existing project in its own conventions. Progress is emitted
+/// as `project-extension-progress`. Unless `preview_only` is explicitly false only the
+/// diffs are returned; they can be applied one by one with `apply_regenerated_file`.
+#[tauri::command]
+pub async fn extend_existing_project(
+    window: tauri::Window,
+    project_path: String,
+    feature_description: String,
+    preview_only: Option<bool>,
+) -> Result<ProjectExtension, String> {
+    extend_project(Path::new(&project_path), &feature_description, preview_only.unwrap_or(true), |progress| {
+        window.emit("project-extension-progress", &progress).ok();
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_plan() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(project.path().join("src/routes")).unwrap();
+        std::fs::write(project.path().join("src/routes/index.ts"), "export {};\n").unwrap();
+
+        let change = |path: &str, action| PlannedChange { path: path.to_string(), action, description: String::new() };
+        let mut plan = ExtensionPlan {
+            summary: "Add a users route".to_string(),
+            changes: vec![
+                change("src/routes/users.ts", ChangeAction::Modify),
+                change("src/routes/index.ts", ChangeAction::Create),
+                change("src/routes/users.ts", ChangeAction::Create),
+                change("../outside.ts", ChangeAction::Create),
+                change("/etc/passwd", ChangeAction::Modify),
+            ],
+        };
+        validate_plan(project.path(), &mut plan).unwrap();
+        let actions: Vec<(&str, &ChangeAction)> = plan.changes.iter().map(|c| (c.path.as_str(), &c.action)).collect();
+        assert_eq!(actions, vec![("src/routes/users.ts", &ChangeAction::Create), ("src/routes/index.ts", &ChangeAction::Modify)]);
+
+        plan.changes.clear();
+        assert!(validate_plan(project.path(), &mut plan).is_err());
+    }
+
+    #[test]
+    fn test_to_text_edits() {
+        let original = "import a from 'a';\n\nexport const routes = [a];\n";
+        let replace = |start_line, end_line, replacement: &str| LineReplacement {
+            start_line,
+            end_line,
+            replacement: replacement.to_string(),
+        };
+        let modification = FileModification {
+            edits: vec![
+                replace(3, 3, "export const routes = [a, users];"),
+                replace(2, 1, "import users from './users';"),
+            ],
+        };
+        let edits = to_text_edits("index.ts", original, &modification).unwrap();
+        assert_eq!(
+            rename::apply_edits(original, &edits),
+            "import a from 'a';\nimport users from './users';\n\nexport const routes = [a, users];\n"
+        );
+
+        let past_end = FileModification { edits: vec![replace(4, 4, "x")] };
+        assert!(to_text_edits("index.ts", original, &past_end).is_err());
+        let overlapping = FileModification { edits: vec![replace(1, 2, "x"), replace(2, 3, "y")] };
+        assert!(to_text_edits("index.ts", original, &overlapping).is_err());
+    }
+}
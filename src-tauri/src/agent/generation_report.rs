@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::agent::estimate::estimate_tokens;
+use crate::agent::pipeline::{GeneratedFile, PipelineStage};
+use crate::database;
+use crate::health::{test_summary, TestSummary};
+use crate::testing::TestRunner;
+
+const HISTORY_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageReport {
+    pub stage: PipelineStage,
+    pub duration_ms: u64,
+    pub llm_calls: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// A guardrail violation in a generated file; fixed when a re-prompt passed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GenerationIssue {
+    pub file: String,
+    pub message: String,
+    pub fixed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProducedFile {
+    pub path: String,
+    pub language: String,
+    pub lines: usize,
+}
+
+/// What one `generate_full_project` run cost and produced. Token counts are
+/// estimated from prompt and reply length since Ollama's counts aren't surfaced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationReport {
+    pub job_id: String,
+    pub project_path: Option<String>,
+    pub project_name: Option<String>,
+    pub model: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u64,
+    pub files: Vec<ProducedFile>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub stages: Vec<StageReport>,
+    pub issues_found: usize,
+    pub issues_fixed: usize,
+    pub issues: Vec<GenerationIssue>,
+    /// Filled in by `run_generation_tests` once the files are on disk
+    pub tests: Option<TestSummary>,
+    /// Set when generation failed part way
+    pub error: Option<String>,
+}
+
+/// Collects stage timings, token estimates and guardrail issues while the pipeline runs
+#[derive(Debug, Default)]
+pub struct GenerationMetrics {
+    stages: Vec<StageReport>,
+    stage_started: Option<Instant>,
+    issues: Vec<GenerationIssue>,
+}
+
+impl GenerationMetrics {
+    /// Close the running stage and start timing `stage`
+    pub fn begin(&mut self, stage: PipelineStage) {
+        self.finish();
+        self.stages.push(StageReport { stage, duration_ms: 0, llm_calls: 0, input_tokens: 0, output_tokens: 0 });
+        self.stage_started = Some(Instant::now());
+    }
+
+    pub fn finish(&mut self) {
+        if let (Some(started), Some(stage)) = (self.stage_started.take(), self.stages.last_mut()) {
+            stage.duration_ms = started.elapsed().as_millis() as u64;
+        }
+    }
+
+    pub fn record_call(&mut self, prompt: &str, reply: &str) {
+        if let Some(stage) = self.stages.last_mut() {
+            stage.llm_calls += 1;
+            stage.input_tokens += estimate_tokens(prompt);
+            stage.output_tokens += estimate_tokens(reply);
+        }
+    }
+
+    pub fn record_issue(&mut self, file: &str, message: String) {
+        self.issues.push(GenerationIssue { file: file.to_string(), message, fixed: false });
+    }
+
+    /// The file passed the guardrails after its issues were re-prompted
+    pub fn mark_fixed(&mut self, file: &str) {
+        for issue in self.issues.iter_mut().filter(|i| i.file == file) {
+            issue.fixed = true;
+        }
+    }
+
+    pub fn into_report(mut self, job_id: &str, started_at: chrono::DateTime<chrono::Utc>, files: &[GeneratedFile]) -> GenerationReport {
+        self.finish();
+        let finished_at = chrono::Utc::now();
+        GenerationReport {
+            job_id: job_id.to_string(),
+            project_path: None,
+            project_name: None,
+            model: crate::agent::pipeline::PIPELINE_MODEL.to_string(),
+            started_at: started_at.to_rfc3339(),
+            finished_at: finished_at.to_rfc3339(),
+            duration_ms: (finished_at - started_at).num_milliseconds().max(0) as u64,
+            files: files
+                .iter()
+                .map(|f| ProducedFile { path: f.path.clone(), language: f.language.clone(), lines: f.content.lines().count() })
+                .collect(),
+            input_tokens: self.stages.iter().map(|s| s.input_tokens).sum(),
+            output_tokens: self.stages.iter().map(|s| s.output_tokens).sum(),
+            stages: self.stages,
+            issues_found: self.issues.len(),
+            issues_fixed: self.issues.iter().filter(|i| i.fixed).count(),
+            issues: self.issues,
+            tests: None,
+            error: None,
+        }
+    }
+}
+
+pub fn save(report: &GenerationReport) -> Result<()> {
+    database::open()?.save_generation_report(
+        &report.job_id,
+        report.project_path.as_deref(),
+        &report.started_at,
+        &serde_json::to_string(report)?,
+    )
+}
+
+pub fn load(job_id: &str) -> Result<GenerationReport> {
+    let data = database::open()?.get_generation_report(job_id)?.context("No report for this generation")?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// Newest first; all projects when `project_path` is None
+pub fn history(project_path: Option<&str>) -> Result<Vec<GenerationReport>> {
+    let reports = database::open()?
+        .list_generation_reports(project_path, HISTORY_LIMIT)?
+        .into_iter()
+        .filter_map(|data| serde_json::from_str(&data).ok())
+        .collect();
+    Ok(reports)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_generation_report(job_id: String) -> Result<GenerationReport, String> {
+    load(&job_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_generation_reports(project_path: Option<String>) -> Result<Vec<GenerationReport>, String> {
+    history(project_path.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Run the generated project's tests once its files are written and add the
+/// results to the report
+#[tauri::command]
+pub async fn run_generation_tests(job_id: String) -> Result<GenerationReport, String> {
+    let mut report = load(&job_id).map_err(|e| e.to_string())?;
+    let project_path = PathBuf::from(report.project_path.as_deref().ok_or("The generation has no project path")?);
+
+    let framework = TestRunner::detect_framework(&project_path).map_err(|e| e.to_string())?;
+    let result = TestRunner::new(framework, project_path).run_tests().await.map_err(|e| e.to_string())?;
+    report.tests = Some(test_summary(&result));
+
+    save(&report).map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_metrics() {
+        let mut metrics = GenerationMetrics::default();
+        // Calls before any stage has begun aren't attributed anywhere
+        metrics.record_call("ignored", "ignored");
+
+        metrics.begin(PipelineStage::Planning);
+        metrics.record_call(&"p".repeat(400), &"r".repeat(800));
+        metrics.begin(PipelineStage::GeneratingCode);
+        metrics.record_issue("src/a.ts", "placeholder".to_string());
+        metrics.record_issue("src/b.ts", "truncated".to_string());
+        metrics.record_call(&"p".repeat(40), &"r".repeat(40));
+        metrics.record_call(&"p".repeat(40), &"r".repeat(40));
+        metrics.mark_fixed("src/a.ts");
+
+        let files = vec![GeneratedFile { path: "src/a.ts".to_string(), content: "a\nb\n".to_string(), language: "typescript".to_string() }];
+        let report = metrics.into_report("job-1", chrono::Utc::now(), &files);
+
+        assert_eq!(report.stages.len(), 2);
+        assert_eq!(report.stages[1].llm_calls, 2);
+        assert_eq!((report.input_tokens, report.output_tokens), (120, 220));
+        assert_eq!((report.issues_found, report.issues_fixed), (2, 1));
+        assert_eq!(report.files[0].lines, 2);
+    }
+}
@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::path::Path;
+
+use crate::agent::guardrails::strip_fences;
+use crate::figma;
+use crate::llm::{vision, GenerationRequest, LLMClient};
+
+const MOCKUP_SYSTEM_PROMPT: &str = "You are a senior frontend engineer who turns design mockups into \
+production-quality UI code. Match the layout, spacing, colors, typography and copy in the design as \
+closely as you can. Use semantic markup and accessible labels. Do not invent features that are not \
+visible in the design.";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UiFramework {
+    React,
+    Vue,
+    Svelte,
+    Angular,
+    Html,
+}
+
+impl UiFramework {
+    fn file_name(&self, component: &str) -> String {
+        match self {
+            UiFramework::React => format!("{}.tsx", component),
+            UiFramework::Vue => format!("{}.vue", component),
+            UiFramework::Svelte => format!("{}.svelte", component),
+            UiFramework::Angular => format!("{}.component.ts", kebab_case(component)),
+            UiFramework::Html => format!("{}.html", kebab_case(component)),
+        }
+    }
+
+    fn instructions(&self, component: &str) -> String {
+        match self {
+            UiFramework::React => format!(
+                "a React function component named {} in TypeScript, styled with Tailwind CSS classes, \
+                 exported as default",
+                component
+            ),
+            UiFramework::Vue => format!(
+                "a Vue 3 single-file component for {} using <script setup lang=\"ts\"> and scoped styles",
+                component
+            ),
+            UiFramework::Svelte => format!(
+                "a Svelte component for {} with <script lang=\"ts\"> and a <style> block",
+                component
+            ),
+            UiFramework::Angular => format!(
+                "a standalone Angular component class {}Component with an inline template and inline styles",
+                component
+            ),
+            UiFramework::Html => format!(
+                "a single self-contained HTML page for {} with its CSS in a <style> tag and no external assets",
+                component
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockupComponent {
+    pub framework: UiFramework,
+    pub component_name: String,
+    pub file_name: String,
+    pub code: String,
+    /// The model's notes on the design, e.g. assumptions about interactions
+    pub notes: String,
+    pub model: String,
+}
+
+fn kebab_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push('-');
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+/// `login-screen@2x` or `Login / Screen 2x` becomes `LoginScreen`
+fn pascal_case(name: &str) -> String {
+    let name: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty() && !word.chars().next().unwrap().is_ascii_digit())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect();
+    if name.is_empty() {
+        "Mockup".to_string()
+    } else {
+        name
+    }
+}
+
+fn component_name_from(path: &Path) -> String {
+    pascal_case(&path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default())
+}
+
+/// Text the model wrote outside its code block
+fn notes_from(reply: &str) -> String {
+    let mut notes = Vec::new();
+    let mut in_fence = false;
+    for line in reply.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence && !line.trim().is_empty() {
+            notes.push(line.trim());
+        }
+    }
+    notes.join("\n")
+}
+
+fn reply_format(framework: UiFramework, component_name: &str) -> String {
+    format!(
+        "Reply with one or two sentences on anything you had to assume, then the complete \
+         contents of {} in a single fenced code block.",
+        framework.file_name(component_name)
+    )
+}
+
+fn component_from_reply(framework: UiFramework, component_name: String, reply: &str, model: String) -> Result<MockupComponent> {
+    let file_name = framework.file_name(&component_name);
+    let code = strip_fences(reply, &file_name);
+    if code.trim().is_empty() {
+        anyhow::bail!("The model did not return any code");
+    }
+    Ok(MockupComponent {
+        framework,
+        component_name,
+        file_name,
+        code,
+        notes: notes_from(reply),
+        model,
+    })
+}
+
+/// Turn a design screenshot into component code
+pub async fn from_mockup(
+    image_path: &Path,
+    framework: UiFramework,
+    component_name: Option<String>,
+    provider: Option<&str>,
+    model: Option<String>,
+) -> Result<MockupComponent> {
+    let component_name = component_name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| component_name_from(image_path));
+
+    let prompt = format!(
+        "Implement the UI in this image as {}. {}",
+        framework.instructions(&component_name),
+        reply_format(framework, &component_name)
+    );
+    let analysis = vision::analyze(image_path, &prompt, Some(MOCKUP_SYSTEM_PROMPT), provider, model).await?;
+    component_from_reply(framework, component_name, &analysis.text, analysis.model)
+}
+
+/// Turn a Figma frame into component code. The layout tree carries exact sizes,
+/// spacing and colors, so no vision model is needed.
+pub async fn from_figma(url: &str, framework: UiFramework, component_name: Option<String>) -> Result<MockupComponent> {
+    let layout = figma::import(url).await?;
+    let component_name = component_name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| pascal_case(&layout.root.name));
+
+    let prompt = format!(
+        "Here is a design from Figma as a layout tree, one node per line, indented under its parent. \
+         Sizes are in pixels; \"row\"/\"column\" frames use auto layout (flexbox), others place \
+         children at the given offsets.\n\n{}\nImplement it as {}. Use the exact colors, font sizes, \
+         gaps and padding given. {}",
+        figma::describe(&layout),
+        framework.instructions(&component_name),
+        reply_format(framework, &component_name)
+    );
+    let request = GenerationRequest {
+        model: super::AGENT_MODEL.to_string(),
+        prompt,
+        system_prompt: Some(MOCKUP_SYSTEM_PROMPT.to_string()),
+        temperature: 0.3,
+        max_tokens: 4096,
+    };
+    let response = LLMClient::new().generate(request).await?;
+    component_from_reply(framework, component_name, &response.text, response.model)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn generate_ui_from_mockup(
+    image_path: String,
+    framework: UiFramework,
+    component_name: Option<String>,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Result<MockupComponent, String> {
+    from_mockup(Path::new(&image_path), framework, component_name, provider.as_deref(), model)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn generate_ui_from_figma(
+    url: String,
+    framework: UiFramework,
+    component_name: Option<String>,
+) -> Result<MockupComponent, String> {
+    from_figma(&url, framework, component_name).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_naming() {
+        assert_eq!(component_name_from(Path::new("/tmp/login-screen@2x.png")), "LoginScreen");
+        assert_eq!(component_name_from(Path::new("/tmp/2024.png")), "Mockup");
+        assert_eq!(pascal_case("Sign up / Mobile"), "SignUpMobile");
+        assert_eq!(UiFramework::Angular.file_name("LoginScreen"), "login-screen.component.ts");
+        assert_eq!(UiFramework::React.file_name("LoginScreen"), "LoginScreen.tsx");
+
+        let reply = "Assumed the button submits the form.\n```tsx\nexport default function A() {}\n```\n";
+        assert_eq!(notes_from(reply), "Assumed the button submits the form.");
+        assert_eq!(strip_fences(reply, "A.tsx"), "export default function A() {}\n");
+    }
+}
@@ -3,11 +3,35 @@ pub mod test_generator;
 pub mod validator;
 pub mod deployment;
 pub mod refactorer;
+pub mod openapi;
+pub mod sql_assistant;
+pub mod project_converter;
+pub mod chat;
+pub mod compaction;
+pub mod memory;
+pub mod personas;
+pub mod guardrails;
+pub mod estimate;
+pub mod generation_report;
+pub mod documentation;
+pub mod error_explainer;
+pub mod test_fixer;
+pub mod debug_assistant;
+pub mod injection;
+pub mod web_fetch;
+pub mod mockup;
+pub mod project_assets;
+pub mod context;
+pub mod extension;
+pub mod repro;
 
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 
-use crate::llm::{LLMClient, GenerationRequest};
+use crate::llm::{LLMClient, GenerationRequest, GenerationResponse};
+
+const AGENT_MODEL: &str = "deepseek-r1:8b";
+const AGENT_SYSTEM_PROMPT: &str = "You are a helpful AI coding assistant.";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentMessage {
@@ -49,10 +73,45 @@ fn add_message(message: AgentMessage) {
     }
 }
 
+/// Generate with the persona's system prompt, model and temperature, or the agent defaults.
+/// With a `project_id` the project's memories are injected and the reply's memory tool
+/// calls are stripped and queued for the user to confirm.
+async fn generate_reply(
+    prompt: String,
+    persona: Option<&personas::Persona>,
+    project_id: Option<&str>,
+) -> Result<GenerationResponse> {
+    let base = persona
+        .map(|p| p.system_prompt.clone())
+        .unwrap_or_else(|| AGENT_SYSTEM_PROMPT.to_string());
+    let base = match project_id.and_then(crate::project_profile::prompt_for_project) {
+        Some(profile) => format!("{}\n\n{}", base, profile),
+        None => base,
+    };
+    let request = GenerationRequest {
+        model: persona
+            .and_then(|p| p.model.clone())
+            .unwrap_or_else(|| AGENT_MODEL.to_string()),
+        prompt,
+        system_prompt: Some(memory::system_prompt(&base, project_id)),
+        temperature: persona.and_then(|p| p.temperature).unwrap_or(0.7),
+        max_tokens: 2048,
+    };
+    let mut response = LLMClient::new().generate(request).await?;
+    response.text = memory::apply_reply(project_id, &response.text);
+    Ok(response)
+}
+
+/// Send a prompt to the agent. With a `session_id` the exchange is also saved to
+/// that chat session, continuing from `parent_id` or the newest message;
+/// `context` is project context prepended to the prompt.
 #[tauri::command]
-pub async fn send_prompt(prompt: String) -> Result<String, String> {
-    let client = LLMClient::new();
-    
+pub async fn send_prompt(
+    prompt: String,
+    session_id: Option<String>,
+    parent_id: Option<String>,
+    context: Option<String>,
+) -> Result<String, String> {
     // Add user message to history
     add_message(AgentMessage {
         id: uuid::Uuid::new_v4().to_string(),
@@ -61,28 +120,94 @@ pub async fn send_prompt(prompt: String) -> Result<String, String> {
         timestamp: chrono::Utc::now().to_rfc3339(),
     });
     
-    let request = GenerationRequest {
-        model: "deepseek-r1:8b".to_string(),
-        prompt: prompt.clone(),
-        system_prompt: Some("You are a helpful AI coding assistant.".to_string()),
-        temperature: 0.7,
-        max_tokens: 2048,
+    let user_message = match &session_id {
+        Some(session_id) => {
+            let parent_id = match parent_id {
+                Some(id) => Some(id),
+                None => chat::latest_message_id(session_id).map_err(|e| e.to_string())?,
+            };
+            Some(
+                chat::record_message(session_id, parent_id, "user", &prompt, context.clone(), None)
+                    .map_err(|e| e.to_string())?,
+            )
+        }
+        None => None,
     };
     
-    match client.generate(request).await {
-        Ok(response) => {
-            // Add assistant message to history
-            add_message(AgentMessage {
-                id: uuid::Uuid::new_v4().to_string(),
-                role: "assistant".to_string(),
-                content: response.text.clone(),
-                timestamp: chrono::Utc::now().to_rfc3339(),
-            });
+    // Everything after the user message is recorded; a failure removes it again so the
+    // session isn't left with a prompt that never got a reply
+    let result: Result<String, String> = async {
+        let persona = match &session_id {
+            Some(session_id) => personas::session_persona(session_id).map_err(|e| e.to_string())?,
+            None => None,
+        };
+        let project_id = match &session_id {
+            Some(session_id) => chat::session_project(session_id).map_err(|e| e.to_string())?,
+            None => None,
+        };
+        let templated = persona.as_ref().map(|p| p.apply(&prompt)).unwrap_or_else(|| prompt.clone());
+    
+        // Project files and other retrieved text are screened like any other untrusted input
+        let full_prompt = match &context {
+            Some(context) if !context.trim().is_empty() => {
+                let context = injection::guard(context, "chat context").await;
+                format!("Context:\n{}\n\n{}", context.text, templated)
+            }
+            _ => templated,
+        };
+    
+        // Earlier messages on the branch, condensed once they outgrow the context window
+        let full_prompt = match &user_message {
+            Some(user_message) => {
+                let messages = crate::database::open()
+                    .and_then(|db| db.get_chat_messages(&user_message.session_id))
+                    .map_err(|e| e.to_string())?;
+                let path = chat::path_to(&messages, &user_message.id);
+                let history = &path[..path.len().saturating_sub(1)];
+                let reserve = estimate::estimate_tokens(&full_prompt);
+                let history = compaction::history_prompt(&user_message.session_id, history, reserve)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                format!("{}{}", history, full_prompt)
+            }
+            None => full_prompt,
+        };
+    
+        match generate_reply(full_prompt, persona.as_ref(), project_id.as_deref()).await {
+            Ok(response) => {
+                // Add assistant message to history
+                add_message(AgentMessage {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    role: "assistant".to_string(),
+                    content: response.text.clone(),
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                });
             
-            Ok(response.text)
+                if let Some(user_message) = &user_message {
+                    chat::record_message(
+                        &user_message.session_id,
+                        Some(user_message.id.clone()),
+                        "assistant",
+                        &response.text,
+                        None,
+                        Some(response.model.clone()),
+                    )
+                    .map_err(|e| e.to_string())?;
+                }
+            
+                Ok(response.text)
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+    .await;
+
+    if let (Err(_), Some(user_message)) = (&result, &user_message) {
+        if let Err(e) = chat::delete_message(&user_message.id) {
+            tracing::warn!("Failed to remove unanswered message {}: {}", user_message.id, e);
         }
-        Err(e) => Err(e.to_string()),
     }
+    result
 }
 
 #[tauri::command]
@@ -0,0 +1,442 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use openapiv3::{OpenAPI, Operation, Parameter, ReferenceOr};
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::filesystem::collect_source_files;
+use crate::llm::{LLMClient, GenerationRequest};
+use crate::llm::structured::generate_structured;
+
+/// Where the generated spec is stored, relative to the project root
+pub const SPEC_FILE: &str = "openapi.json";
+
+// Keep the prompt within the model's context
+const MAX_SOURCE_CHARS: usize = 24_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedRoute {
+    pub method: String,
+    pub path: String,
+    pub file: String,
+    pub line: usize,
+    pub framework: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApiGenerationResult {
+    pub spec: serde_json::Value,
+    pub spec_path: String,
+    pub routes: Vec<ExtractedRoute>,
+    /// Extracted routes the model left out of the spec
+    pub missing_routes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiChange {
+    pub path: String,
+    pub method: Option<String>,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApiDiff {
+    pub breaking: Vec<ApiChange>,
+    pub non_breaking: Vec<ApiChange>,
+}
+
+/// Find Express (`app.get('/x', ...)`) and FastAPI (`@app.get("/x")`) routes
+pub fn extract_routes(project_path: &Path) -> Vec<ExtractedRoute> {
+    let express_re = Regex::new(
+        r#"\b(?:app|router|server)\.(get|post|put|patch|delete|options|head)\(\s*['"`]([^'"`]+)['"`]"#
+    ).unwrap();
+    let fastapi_re = Regex::new(
+        r#"@\w+\.(get|post|put|patch|delete|options|head)\(\s*['"]([^'"]+)['"]"#
+    ).unwrap();
+
+    let mut routes = Vec::new();
+
+    for file in collect_source_files(project_path, &["js", "ts", "mjs", "cjs", "py"]) {
+        let content = match std::fs::read_to_string(&file) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+
+        let (re, framework) = if file.extension().and_then(|e| e.to_str()) == Some("py") {
+            (&fastapi_re, "fastapi")
+        } else {
+            (&express_re, "express")
+        };
+
+        for (idx, line) in content.lines().enumerate() {
+            for cap in re.captures_iter(line) {
+                routes.push(ExtractedRoute {
+                    method: cap[1].to_uppercase(),
+                    path: normalize_route_path(&cap[2]),
+                    file: file.strip_prefix(project_path)
+                        .unwrap_or(&file)
+                        .to_string_lossy()
+                        .to_string(),
+                    line: idx + 1,
+                    framework: framework.to_string(),
+                });
+            }
+        }
+    }
+
+    routes
+}
+
+/// Express `:id` becomes OpenAPI `{id}`; FastAPI already uses braces
+fn normalize_route_path(path: &str) -> String {
+    let param_re = Regex::new(r":(\w+)").unwrap();
+    param_re.replace_all(path, "{$1}").to_string()
+}
+
+/// Just the top-level shape of an OpenAPI 3 document; `validate_spec` checks the rest
+fn openapi_document_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "required": ["openapi", "info", "paths"],
+        "properties": {
+            "openapi": { "type": "string", "pattern": "^3\\." },
+            "info": {
+                "type": "object",
+                "required": ["title", "version"],
+                "properties": {
+                    "title": { "type": "string" },
+                    "version": { "type": "string" }
+                }
+            },
+            "paths": { "type": "object" },
+            "components": { "type": "object" }
+        }
+    })
+}
+
+/// Parse and sanity check an OpenAPI 3 document
+pub fn validate_spec(spec: &str) -> Result<OpenAPI> {
+    let parsed: OpenAPI = serde_json::from_str(spec).context("Not a valid OpenAPI document")?;
+
+    if !parsed.openapi.starts_with("3.") {
+        anyhow::bail!("Expected OpenAPI 3.x, got {}", parsed.openapi);
+    }
+
+    for (path, item) in parsed.paths.paths.iter() {
+        if !path.starts_with('/') {
+            anyhow::bail!("Path '{}' must start with '/'", path);
+        }
+        if let ReferenceOr::Item(item) = item {
+            for (method, operation) in item.iter() {
+                if operation.responses.responses.is_empty() && operation.responses.default.is_none() {
+                    anyhow::bail!("{} {} has no responses", method.to_uppercase(), path);
+                }
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn resolve_parameter<'a>(spec: &'a OpenAPI, param: &'a ReferenceOr<Parameter>) -> Option<&'a Parameter> {
+    match param {
+        ReferenceOr::Item(param) => Some(param),
+        ReferenceOr::Reference { reference } => {
+            let name = reference.strip_prefix("#/components/parameters/")?;
+            spec.components.as_ref()?.parameters.get(name)?.as_item()
+        }
+    }
+}
+
+fn same_parameter(a: &Parameter, b: &Parameter) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b) && a.parameter_data_ref().name == b.parameter_data_ref().name
+}
+
+/// Every operation with the parameters it accepts, including those declared on its path
+fn operations(spec: &OpenAPI) -> Vec<(String, String, &Operation, Vec<&Parameter>)> {
+    spec.paths.paths
+        .iter()
+        .filter_map(|(path, item)| item.as_item().map(|item| (path, item)))
+        .flat_map(|(path, item)| {
+            item.iter().map(move |(method, op)| {
+                // An operation's own parameter overrides a path one with the same name and location
+                let mut params: Vec<&Parameter> = op.parameters.iter().filter_map(|p| resolve_parameter(spec, p)).collect();
+                for shared in item.parameters.iter().filter_map(|p| resolve_parameter(spec, p)) {
+                    if !params.iter().any(|p| same_parameter(p, shared)) {
+                        params.push(shared);
+                    }
+                }
+                (path.clone(), method.to_uppercase(), op, params)
+            })
+        })
+        .collect()
+}
+
+fn required_params(params: &[&Parameter]) -> BTreeSet<String> {
+    params
+        .iter()
+        .map(|p| p.parameter_data_ref())
+        .filter(|p| p.required)
+        .map(|p| p.name.clone())
+        .collect()
+}
+
+fn all_params(params: &[&Parameter]) -> BTreeSet<String> {
+    params.iter().map(|p| p.parameter_data_ref().name.clone()).collect()
+}
+
+fn response_codes(op: &Operation) -> BTreeSet<String> {
+    op.responses.responses.keys().map(|code| code.to_string()).collect()
+}
+
+fn body_required(op: &Operation) -> bool {
+    op.request_body
+        .as_ref()
+        .and_then(|b| b.as_item())
+        .map(|b| b.required)
+        .unwrap_or(false)
+}
+
+/// Compare two specs and classify changes by whether they break existing clients
+pub fn diff_specs(old: &OpenAPI, new: &OpenAPI) -> OpenApiDiff {
+    let mut diff = OpenApiDiff {
+        breaking: Vec::new(),
+        non_breaking: Vec::new(),
+    };
+
+    let new_ops = operations(new);
+    let old_ops = operations(old);
+
+    for (path, method, old_op, old_declared) in &old_ops {
+        let change = |description: String| ApiChange {
+            path: path.clone(),
+            method: Some(method.clone()),
+            description,
+        };
+
+        let (new_op, new_declared) = match new_ops.iter().find(|(p, m, _, _)| p == path && m == method) {
+            Some((_, _, op, params)) => (op, params),
+            None => {
+                diff.breaking.push(change("Operation removed".to_string()));
+                continue;
+            }
+        };
+
+        let old_params = all_params(old_declared);
+        for param in required_params(new_declared).difference(&required_params(old_declared)) {
+            diff.breaking.push(change(format!("Parameter '{}' is now required", param)));
+        }
+        for param in old_params.difference(&all_params(new_declared)) {
+            diff.breaking.push(change(format!("Parameter '{}' removed", param)));
+        }
+        for param in all_params(new_declared).difference(&old_params) {
+            if !required_params(new_declared).contains(param) {
+                diff.non_breaking.push(change(format!("Optional parameter '{}' added", param)));
+            }
+        }
+
+        if body_required(new_op) && !body_required(old_op) {
+            diff.breaking.push(change("Request body is now required".to_string()));
+        }
+
+        let new_codes = response_codes(new_op);
+        for code in response_codes(old_op).difference(&new_codes) {
+            if code.starts_with('2') {
+                diff.breaking.push(change(format!("Success response {} removed", code)));
+            } else {
+                diff.non_breaking.push(change(format!("Response {} removed", code)));
+            }
+        }
+        for code in new_codes.difference(&response_codes(old_op)) {
+            diff.non_breaking.push(change(format!("Response {} added", code)));
+        }
+
+        if new_op.deprecated && !old_op.deprecated {
+            diff.non_breaking.push(change("Operation deprecated".to_string()));
+        }
+    }
+
+    for (path, method, _, _) in &new_ops {
+        if !old_ops.iter().any(|(p, m, _, _)| p == path && m == method) {
+            diff.non_breaking.push(ApiChange {
+                path: path.clone(),
+                method: Some(method.clone()),
+                description: "Operation added".to_string(),
+            });
+        }
+    }
+
+    diff
+}
+
+pub struct OpenApiGenerator {
+    llm_client: LLMClient,
+}
+
+impl OpenApiGenerator {
+    pub fn new() -> Self {
+        Self {
+            llm_client: LLMClient::new(),
+        }
+    }
+
+    fn collect_route_sources(&self, project_path: &Path, routes: &[ExtractedRoute]) -> String {
+        let files: BTreeSet<&str> = routes.iter().map(|r| r.file.as_str()).collect();
+        let mut sources = String::new();
+
+        for file in files {
+            let content = std::fs::read_to_string(project_path.join(file)).unwrap_or_default();
+            if sources.len() + content.len() > MAX_SOURCE_CHARS {
+                break;
+            }
+            sources.push_str(&format!("--- {} ---\n{}\n\n", file, content));
+        }
+
+        sources
+    }
+
+    pub async fn generate(&self, project_path: &Path) -> Result<OpenApiGenerationResult> {
+        let routes = extract_routes(project_path);
+        if routes.is_empty() {
+            anyhow::bail!("No Express or FastAPI routes found in project");
+        }
+
+        let route_list = routes
+            .iter()
+            .map(|r| format!("{} {} ({}:{})", r.method, r.path, r.file, r.line))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let project_name = project_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "API".to_string());
+
+        let prompt = format!(
+            r#"Write an OpenAPI 3.0.3 document for the "{name}" API.
+
+ROUTES:
+{routes}
+
+SOURCE:
+{sources}
+
+Requirements:
+1. Include every route listed above, using the exact paths
+2. Describe path/query parameters, request bodies and responses from the handler code
+3. Put shared schemas under components/schemas
+4. Every operation needs an operationId and at least one response
+
+Respond with ONLY the JSON document."#,
+            name = project_name,
+            routes = route_list,
+            sources = self.collect_route_sources(project_path, &routes),
+        );
+
+        let request = GenerationRequest {
+            model: "deepseek-coder-v2:16b".to_string(),
+            prompt,
+            system_prompt: Some("You are an API designer. Produce valid OpenAPI 3 JSON that matches the code exactly.".to_string()),
+            temperature: 0.2,
+            max_tokens: 8192,
+        };
+
+        let spec: serde_json::Value =
+            generate_structured(&self.llm_client, request, &openapi_document_schema()).await?;
+        let parsed = validate_spec(&spec.to_string())?;
+
+        let documented: BTreeSet<(String, String)> = operations(&parsed)
+            .into_iter()
+            .map(|(path, method, _, _)| (path, method))
+            .collect();
+
+        let missing_routes = routes
+            .iter()
+            .filter(|r| !documented.contains(&(r.path.clone(), r.method.clone())))
+            .map(|r| format!("{} {}", r.method, r.path))
+            .collect();
+
+        let spec_path = project_path.join(SPEC_FILE);
+        std::fs::write(&spec_path, serde_json::to_string_pretty(&spec)?)?;
+
+        Ok(OpenApiGenerationResult {
+            spec,
+            spec_path: spec_path.to_string_lossy().to_string(),
+            routes,
+            missing_routes,
+        })
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn generate_openapi_spec(project_path: String) -> Result<OpenApiGenerationResult, String> {
+    let generator = OpenApiGenerator::new();
+    generator.generate(Path::new(&project_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn validate_openapi_spec(spec: String) -> Result<(), String> {
+    validate_spec(&spec).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Both arguments are OpenAPI JSON documents
+#[tauri::command]
+pub async fn diff_openapi(old: String, new: String) -> Result<OpenApiDiff, String> {
+    let old = validate_spec(&old).map_err(|e| format!("Old spec: {}", e))?;
+    let new = validate_spec(&new).map_err(|e| format!("New spec: {}", e))?;
+    Ok(diff_specs(&old, &new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(paths: &str) -> OpenAPI {
+        let json = format!(r#"{{"openapi": "3.0.3", "info": {{"title": "t", "version": "1"}}, "paths": {}}}"#, paths);
+        validate_spec(&json).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_route_path() {
+        assert_eq!(normalize_route_path("/users/:id/posts/:postId"), "/users/{id}/posts/{postId}");
+        assert_eq!(normalize_route_path("/items/{item_id}"), "/items/{item_id}");
+    }
+
+    #[test]
+    fn test_diff_detects_breaking_changes() {
+        let old = spec(r#"{
+            "/users": {"get": {"responses": {"200": {"description": "ok"}}}},
+            "/users/{id}": {"delete": {"responses": {"204": {"description": "gone"}}}}
+        }"#);
+        let new = spec(r#"{
+            "/users": {"get": {
+                "parameters": [{"name": "team", "in": "query", "required": true, "schema": {"type": "string"}}],
+                "responses": {"200": {"description": "ok"}}
+            }},
+            "/teams": {"get": {"responses": {"200": {"description": "ok"}}}}
+        }"#);
+
+        let diff = diff_specs(&old, &new);
+        assert_eq!(diff.breaking.len(), 2);
+        assert!(diff.breaking.iter().any(|c| c.description == "Operation removed"));
+        assert!(diff.breaking.iter().any(|c| c.description.contains("'team' is now required")));
+        assert!(diff.non_breaking.iter().any(|c| c.path == "/teams"));
+    }
+
+    #[test]
+    fn test_diff_includes_path_level_parameters() {
+        let old = spec(r#"{"/items": {"get": {"responses": {"200": {"description": "ok"}}}}}"#);
+        let new = spec(r#"{"/items": {
+            "parameters": [{"name": "tenant", "in": "header", "required": true, "schema": {"type": "string"}}],
+            "get": {"responses": {"200": {"description": "ok"}}}
+        }}"#);
+
+        let diff = diff_specs(&old, &new);
+        assert!(diff.breaking.iter().any(|c| c.description.contains("'tenant' is now required")));
+    }
+}
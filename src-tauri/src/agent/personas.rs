@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::database;
+
+/// Placeholder in `prompt_template` replaced with the user's message
+pub const PROMPT_PLACEHOLDER: &str = "{prompt}";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Persona {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub system_prompt: String,
+    /// Wraps each user message; must contain `{prompt}`
+    pub prompt_template: Option<String>,
+    /// Overrides the agent's default model
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl Persona {
+    pub fn apply(&self, prompt: &str) -> String {
+        match &self.prompt_template {
+            Some(template) => template.replace(PROMPT_PLACEHOLDER, prompt),
+            None => prompt.to_string(),
+        }
+    }
+}
+
+fn builtin(id: &str, name: &str, description: &str, system_prompt: &str, template: Option<&str>, temperature: f32) -> Persona {
+    Persona {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        system_prompt: system_prompt.to_string(),
+        prompt_template: template.map(String::from),
+        model: None,
+        temperature: Some(temperature),
+        builtin: true,
+    }
+}
+
+pub fn builtin_personas() -> Vec<Persona> {
+    vec![
+        builtin(
+            "architect",
+            "Architect",
+            "Thinks in components, boundaries and trade-offs before writing code",
+            "You are a senior software architect. Before proposing code, describe the components involved, \
+             their responsibilities and the trade-offs of the approach. Prefer simple designs, call out risks, \
+             and only include code where it clarifies an interface.",
+            None,
+            0.6,
+        ),
+        builtin(
+            "reviewer",
+            "Reviewer",
+            "Reviews code for bugs, security issues and maintainability",
+            "You are a meticulous code reviewer. Point out bugs, security issues, performance problems and \
+             unclear code, ordered by severity. Quote the relevant lines, explain why each is a problem and \
+             suggest a concrete fix. Do not rewrite code that is already fine.",
+            Some("Review the following:\n\n{prompt}"),
+            0.2,
+        ),
+        builtin(
+            "teacher",
+            "Teacher",
+            "Explains concepts step by step for learning",
+            "You are a patient programming teacher. Explain concepts step by step, define terms the first \
+             time you use them, use small examples, and end with a short summary of the key points.",
+            None,
+            0.7,
+        ),
+        builtin(
+            "terse",
+            "Terse coder",
+            "Answers with code only, minimal prose",
+            "You are an expert programmer. Reply with code only. Add at most one short sentence of explanation \
+             when the code alone would be ambiguous. Never repeat the question.",
+            None,
+            0.3,
+        ),
+    ]
+}
+
+pub struct PersonaManager {
+    personas_file: PathBuf,
+}
+
+impl PersonaManager {
+    pub fn new() -> Result<Self> {
+        let app_dir = dirs::data_dir()
+            .context("Failed to get data directory")?
+            .join(".sai-ide");
+
+        std::fs::create_dir_all(&app_dir)?;
+
+        Ok(Self {
+            personas_file: app_dir.join("personas.json"),
+        })
+    }
+
+    fn load_custom(&self) -> Result<Vec<Persona>> {
+        if !self.personas_file.exists() {
+            return Ok(Vec::new());
+        }
+        let json = std::fs::read_to_string(&self.personas_file)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn save_custom(&self, personas: &[Persona]) -> Result<()> {
+        let json = serde_json::to_string_pretty(personas)?;
+        std::fs::write(&self.personas_file, json)?;
+        Ok(())
+    }
+
+    /// Built-in personas first, then the user's own
+    pub fn list(&self) -> Result<Vec<Persona>> {
+        let mut personas = builtin_personas();
+        personas.extend(self.load_custom()?);
+        Ok(personas)
+    }
+
+    pub fn get(&self, persona_id: &str) -> Result<Option<Persona>> {
+        Ok(self.list()?.into_iter().find(|p| p.id == persona_id))
+    }
+
+    /// Copy any persona into an editable custom one
+    pub fn clone_persona(&self, persona_id: &str, name: Option<String>) -> Result<Persona> {
+        let source = self.get(persona_id)?.context("Persona not found")?;
+        let persona = Persona {
+            id: Uuid::new_v4().to_string(),
+            name: name.unwrap_or_else(|| format!("{} (copy)", source.name)),
+            builtin: false,
+            ..source
+        };
+
+        let mut custom = self.load_custom()?;
+        custom.push(persona.clone());
+        self.save_custom(&custom)?;
+        Ok(persona)
+    }
+
+    /// Create or update a custom persona. Built-ins are read-only; clone them instead.
+    pub fn save(&self, mut persona: Persona) -> Result<Persona> {
+        validate(&persona)?;
+        if let Some(temperature) = persona.temperature {
+            persona.temperature = Some(temperature.clamp(0.0, 2.0));
+        }
+        if persona.id.is_empty() {
+            persona.id = Uuid::new_v4().to_string();
+        }
+        persona.builtin = false;
+
+        let mut custom = self.load_custom()?;
+        match custom.iter_mut().find(|p| p.id == persona.id) {
+            Some(existing) => *existing = persona.clone(),
+            None => custom.push(persona.clone()),
+        }
+        self.save_custom(&custom)?;
+        Ok(persona)
+    }
+
+    pub fn delete(&self, persona_id: &str) -> Result<()> {
+        if builtin_personas().iter().any(|p| p.id == persona_id) {
+            anyhow::bail!("Built-in personas can't be deleted");
+        }
+        let mut custom = self.load_custom()?;
+        custom.retain(|p| p.id != persona_id);
+        self.save_custom(&custom)
+    }
+}
+
+/// The checks `PersonaManager::save` applies, for callers that must vet several personas up front
+pub fn validate(persona: &Persona) -> Result<()> {
+    if builtin_personas().iter().any(|p| p.id == persona.id) {
+        anyhow::bail!("Built-in personas can't be edited; clone it first");
+    }
+    if let Some(template) = &persona.prompt_template {
+        if !template.contains(PROMPT_PLACEHOLDER) {
+            anyhow::bail!("Prompt template must contain {}", PROMPT_PLACEHOLDER);
+        }
+    }
+    Ok(())
+}
+
+/// Persona assigned to a chat session, if any
+pub fn session_persona(session_id: &str) -> Result<Option<Persona>> {
+    let db = database::open()?;
+    let session = db.get_chat_session(session_id)?.context("Chat session not found")?;
+
+    match session.persona_id {
+        Some(persona_id) => PersonaManager::new()?.get(&persona_id),
+        None => Ok(None),
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_personas() -> Result<Vec<Persona>, String> {
+    let manager = PersonaManager::new().map_err(|e| e.to_string())?;
+    manager.list().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clone_persona(persona_id: String, name: Option<String>) -> Result<Persona, String> {
+    let manager = PersonaManager::new().map_err(|e| e.to_string())?;
+    manager.clone_persona(&persona_id, name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_persona(persona: Persona) -> Result<Persona, String> {
+    let manager = PersonaManager::new().map_err(|e| e.to_string())?;
+    manager.save(persona).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_persona(persona_id: String) -> Result<(), String> {
+    let manager = PersonaManager::new().map_err(|e| e.to_string())?;
+    manager.delete(&persona_id).map_err(|e| e.to_string())
+}
+
+/// Use a persona for the rest of a chat session; None goes back to the default agent
+#[tauri::command]
+pub async fn set_session_persona(session_id: String, persona_id: Option<String>) -> Result<(), String> {
+    if let Some(persona_id) = &persona_id {
+        let manager = PersonaManager::new().map_err(|e| e.to_string())?;
+        manager
+            .get(persona_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Persona {} not found", persona_id))?;
+    }
+
+    let db = database::open().map_err(|e| e.to_string())?;
+    db.set_chat_session_persona(&session_id, persona_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_personas() {
+        let personas = builtin_personas();
+        assert_eq!(personas.len(), 4);
+        assert!(personas.iter().all(|p| p.builtin));
+
+        let reviewer = personas.iter().find(|p| p.id == "reviewer").unwrap();
+        assert_eq!(reviewer.apply("fn main() {}"), "Review the following:\n\nfn main() {}");
+    }
+}
@@ -1,17 +1,32 @@
 use serde::{Deserialize, Serialize};
-use anyhow::{Result, Context};
-use regex::Regex;
+use anyhow::Result;
+use schemars::JsonSchema;
+use similar::TextDiff;
+use std::path::{Component, Path};
+use std::sync::Mutex;
 use tauri::Emitter;
 
-
+use crate::agent::generation_report::{self, GenerationMetrics};
+use crate::agent::guardrails::{self, GuardrailConfig};
+use crate::agent::project_assets::{self, ProjectAsset};
 use crate::llm::{LLMClient, GenerationRequest};
+use crate::llm::structured::generate_typed;
+use crate::package_manager;
+use crate::i18n::{t, t_args};
+
+/// Model used by every pipeline stage
+pub const PIPELINE_MODEL: &str = "deepseek-coder-v2:16b";
 
 /// Agent pipeline for multi-stage code generation
 pub struct AgentPipeline {
     llm_client: LLMClient,
+    guardrails: GuardrailConfig,
+    metrics: Mutex<GenerationMetrics>,
+    /// User files copied into the project, which generated code can reference
+    assets: Vec<ProjectAsset>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectRequest {
     pub description: String,
     pub project_type: ProjectType,
@@ -20,7 +35,7 @@ pub struct ProjectRequest {
     pub constraints: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum ProjectType {
     WebApp,
     MobileApp,
@@ -31,7 +46,7 @@ pub enum ProjectType {
     Microservice,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectPlan {
     pub name: String,
     pub description: String,
@@ -41,7 +56,7 @@ pub struct ProjectPlan {
     pub environment_variables: Vec<EnvVariable>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FileNode {
     pub path: String,
     pub node_type: NodeType,
@@ -49,13 +64,13 @@ pub struct FileNode {
     pub priority: i32, // For generation order
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum NodeType {
     File,
     Directory,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Dependency {
     pub name: String,
     pub version: String,
@@ -63,7 +78,7 @@ pub struct Dependency {
     pub reason: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EnvVariable {
     pub name: String,
     pub description: String,
@@ -78,6 +93,60 @@ pub struct GeneratedFile {
     pub language: String,
 }
 
+/// `file_path` as a path that can't escape the project directory
+pub(crate) fn project_relative(file_path: &str) -> Result<&Path> {
+    let relative = Path::new(file_path);
+    if relative.is_absolute() || relative.components().any(|c| matches!(c, Component::ParentDir)) {
+        anyhow::bail!("File path must be relative to the project: {}", file_path);
+    }
+    Ok(relative)
+}
+
+/// Request and plan behind a generated project, kept in `.sai-metadata/generation.json`
+/// so single files can be regenerated with the original context later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationRecord {
+    pub request: ProjectRequest,
+    pub plan: ProjectPlan,
+    #[serde(default)]
+    pub assets: Vec<ProjectAsset>,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl GenerationRecord {
+    fn path(project_path: &Path) -> std::path::PathBuf {
+        project_path.join(".sai-metadata").join("generation.json")
+    }
+
+    pub fn save(&self, project_path: &Path) -> Result<()> {
+        let path = Self::path(project_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(project_path: &Path) -> Result<Self> {
+        let path = Self::path(project_path);
+        let json = std::fs::read_to_string(&path)
+            .map_err(|_| anyhow::anyhow!("No generation plan saved for {}", project_path.display()))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// A regenerated file awaiting the user's approval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegeneratedFile {
+    pub path: String,
+    pub language: String,
+    /// Content on disk, None if the file doesn't exist yet
+    pub original: Option<String>,
+    pub content: String,
+    /// Unified diff from `original` to `content`
+    pub diff: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationProgress {
     pub stage: PipelineStage,
@@ -101,9 +170,26 @@ impl AgentPipeline {
     pub fn new() -> Self {
         Self {
             llm_client: LLMClient::new(),
+            guardrails: GuardrailConfig::default(),
+            metrics: Mutex::new(GenerationMetrics::default()),
+            assets: Vec::new(),
         }
     }
     
+    pub fn with_assets(mut self, assets: Vec<ProjectAsset>) -> Self {
+        self.assets = assets;
+        self
+    }
+    
+    fn with_metrics(&self, f: impl FnOnce(&mut GenerationMetrics)) {
+        f(&mut self.metrics.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+    
+    /// Timings, token estimates and guardrail issues since the last call
+    pub fn take_metrics(&self) -> GenerationMetrics {
+        std::mem::take(&mut *self.metrics.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+    
     /// Stage 1: Understand the request and classify intent
     pub async fn understand_request(&self, description: &str) -> Result<ProjectRequest> {
         let prompt = format!(
@@ -130,34 +216,23 @@ Rules:
         );
         
         let request = GenerationRequest {
-            model: "deepseek-coder-v2:16b".to_string(),
+            model: PIPELINE_MODEL.to_string(),
             prompt,
             system_prompt: Some("You are a requirements analyst. Extract structured data from natural language descriptions. Always respond with valid JSON only.".to_string()),
             temperature: 0.3, // Lower for more deterministic parsing
             max_tokens: 1024,
         };
         
-        let response = self.llm_client.generate(request).await?;
-        let json_str = self.extract_json(&response.text)?;
-        
-        let mut project_request: ProjectRequest = serde_json::from_str(&json_str)
-            .context("Failed to parse project request JSON")?;
-        
-        // Parse project_type string to enum
-        project_request.project_type = match project_request.project_type {
-            ProjectType::WebApp => ProjectType::WebApp,
-            _ => ProjectType::WebApp, // Default fallback
-        };
-        
-        Ok(project_request)
+        generate_typed(&self.llm_client, request).await
     }
     
     /// Stage 2: Create detailed project plan
     pub async fn create_plan(&self, request: &ProjectRequest) -> Result<ProjectPlan> {
         let tech_stack = request.tech_stack.join(", ");
+        let manager = package_manager::preferred().manager;
         let features = request.features.join("\n- ");
         
-        let prompt = format!(
+        let mut prompt = format!(
             r#"Create a detailed project plan for:
 
 Description: {}
@@ -179,8 +254,8 @@ Respond ONLY with valid JSON:
     {{"name": "typescript", "version": "^5.0.0", "dev": true, "reason": "Type safety"}}
   ],
   "setup_commands": [
-    "npm install",
-    "npm run build"
+    "{}",
+    "{}"
   ],
   "environment_variables": [
     {{"name": "API_KEY", "description": "API authentication key", "required": true, "default_value": null}},
@@ -199,23 +274,31 @@ Guidelines:
             request.description,
             request.project_type,
             tech_stack,
-            features
+            features,
+            manager.install(),
+            manager.run("build", &[])
         );
+        if let Some(assets) = project_assets::prompt_context(&self.assets) {
+            prompt.push_str(&format!(
+                "\n\nThe user provided these files, which will be copied to the paths shown. \
+                 Do not list them in file_structure, but reference them where the project needs them:\n{}",
+                assets
+            ));
+        }
         
         let gen_request = GenerationRequest {
-            model: "deepseek-coder-v2:16b".to_string(),
+            model: PIPELINE_MODEL.to_string(),
             prompt,
             system_prompt: Some("You are a software architect. Create comprehensive project plans with complete file structures and dependencies.".to_string()),
             temperature: 0.4,
             max_tokens: 2048,
         };
         
-        let response = self.llm_client.generate(gen_request).await?;
-        let json_str = self.extract_json(&response.text)?;
-        
-        let plan: ProjectPlan = serde_json::from_str(&json_str)
-            .context("Failed to parse project plan JSON")?;
-        
+        let prompt = gen_request.prompt.clone();
+        let mut plan: ProjectPlan = generate_typed(&self.llm_client, gen_request).await?;
+        self.with_metrics(|m| m.record_call(&prompt, &serde_json::to_string(&plan).unwrap_or_default()));
+        // Models write npm commands out of habit whatever the example says
+        plan.setup_commands = plan.setup_commands.iter().map(|c| manager.translate(c)).collect();
         Ok(plan)
     }
     
@@ -228,8 +311,88 @@ Guidelines:
     ) -> Result<GeneratedFile> {
         // Build context from existing files
         let context = self.build_file_context(existing_files);
+        let prompt = self.file_prompt(file_node, plan, &context, &self.assets);
         
-        let prompt = format!(
+        let cleaned_code = self.generate_guarded(&file_node.path, prompt).await?;
+        
+        // Detect language from file extension
+        let language = self.detect_language(&file_node.path);
+        
+        Ok(GeneratedFile {
+            path: file_node.path.clone(),
+            content: cleaned_code,
+            language,
+        })
+    }
+    
+    /// Regenerate one file of an existing project from its saved plan, with the
+    /// files around it on disk as context. Nothing is written.
+    pub async fn regenerate_file(
+        &self,
+        project_path: &Path,
+        file_path: &str,
+        instruction: Option<&str>,
+    ) -> Result<RegeneratedFile> {
+        let relative = project_relative(file_path)?;
+        let record = GenerationRecord::load(project_path)?;
+        let file_node = record.plan.file_structure
+            .iter()
+            .find(|f| f.path == file_path)
+            .cloned()
+            .unwrap_or_else(|| FileNode {
+                path: file_path.to_string(),
+                node_type: NodeType::File,
+                description: "File added to the project after generation".to_string(),
+                priority: 0,
+            });
+        
+        let original = std::fs::read_to_string(project_path.join(relative)).ok();
+        
+        // Siblings in the same directory first, then the rest of the plan in generation order
+        let directory = relative.parent();
+        let mut planned: Vec<&FileNode> = record.plan.file_structure
+            .iter()
+            .filter(|f| matches!(f.node_type, NodeType::File) && f.path != file_path)
+            .collect();
+        planned.sort_by_key(|f| (Path::new(&f.path).parent() != directory, f.priority));
+        let siblings: Vec<GeneratedFile> = planned
+            .into_iter()
+            .filter_map(|f| {
+                let content = std::fs::read_to_string(project_path.join(&f.path)).ok()?;
+                Some(GeneratedFile {
+                    path: f.path.clone(),
+                    content,
+                    language: self.detect_language(&f.path),
+                })
+            })
+            .collect();
+        
+        let mut prompt = self.file_prompt(&file_node, &record.plan, &self.build_file_context(&siblings), &record.assets);
+        if let Some(current) = &original {
+            prompt.push_str(&format!("\n\nCurrent version of {}:\n{}", file_path, current));
+        }
+        if let Some(instruction) = instruction.filter(|i| !i.trim().is_empty()) {
+            prompt.push_str(&format!("\n\nApply this change: {}", instruction.trim()));
+        }
+        
+        let content = self.generate_guarded(file_path, prompt).await?;
+        let diff = TextDiff::from_lines(original.as_deref().unwrap_or(""), &content)
+            .unified_diff()
+            .context_radius(3)
+            .header(&format!("a/{}", file_path), &format!("b/{}", file_path))
+            .to_string();
+        
+        Ok(RegeneratedFile {
+            path: file_path.to_string(),
+            language: self.detect_language(file_path),
+            original,
+            content,
+            diff,
+        })
+    }
+    
+    fn file_prompt(&self, file_node: &FileNode, plan: &ProjectPlan, context: &str, assets: &[ProjectAsset]) -> String {
+        let mut prompt = format!(
             r#"Generate the complete code for this file:
 
 File: {}
@@ -260,26 +423,49 @@ Generate the COMPLETE file content now. Start with any necessary imports, then t
             plan.dependencies.iter().map(|d| &d.name).take(5).cloned().collect::<Vec<_>>().join(", "),
             context
         );
-        
-        let gen_request = GenerationRequest {
-            model: "deepseek-coder-v2:16b".to_string(),
-            prompt,
-            system_prompt: Some("You are an expert software engineer. Generate clean, production-ready code with proper error handling and comments.".to_string()),
-            temperature: 0.7,
-            max_tokens: 4096,
-        };
-        
-        let response = self.llm_client.generate(gen_request).await?;
-        let cleaned_code = self.extract_code(&response.text);
-        
-        // Detect language from file extension
-        let language = self.detect_language(&file_node.path);
-        
-        Ok(GeneratedFile {
-            path: file_node.path.clone(),
-            content: cleaned_code,
-            language,
-        })
+        if let Some(assets) = project_assets::prompt_context(assets) {
+            prompt.push_str(&format!(
+                "\n\nUser-provided assets in the project (use these exact paths instead of placeholders):\n{}",
+                assets
+            ));
+        }
+        prompt
+    }
+    
+    /// Generate a file's content, re-prompting when the reply breaks a guardrail
+    pub(crate) async fn generate_guarded(&self, path: &str, prompt: String) -> Result<String> {
+        let mut prompt = prompt;
+        let mut attempt = 0;
+        
+        loop {
+            let gen_request = GenerationRequest {
+                model: PIPELINE_MODEL.to_string(),
+                prompt: prompt.clone(),
+                system_prompt: Some("You are an expert software engineer. Generate clean, production-ready code with proper error handling and comments.".to_string()),
+                temperature: 0.7,
+                max_tokens: 4096,
+            };
+            
+            let response = self.llm_client.generate(gen_request).await?;
+            self.with_metrics(|m| m.record_call(&prompt, &response.text));
+            let report = guardrails::process(&response.text, path, &self.guardrails);
+            
+            let Some(violation) = report.violations.first() else {
+                if attempt > 0 {
+                    self.with_metrics(|m| m.mark_fixed(path));
+                }
+                return Ok(report.content);
+            };
+            self.with_metrics(|m| m.record_issue(path, violation.to_string()));
+            
+            if attempt >= self.guardrails.max_retries {
+                anyhow::bail!("Rejected output for {}: {}", path, violation);
+            }
+            attempt += 1;
+            tracing::warn!("Re-prompting for {} (attempt {}): {}", path, attempt, violation);
+            prompt.push_str("\n\n");
+            prompt.push_str(&violation.reprompt(path));
+        }
     }
     
     /// Generate entire project
@@ -287,48 +473,54 @@ Generate the COMPLETE file content now. Start with any necessary imports, then t
         &self,
         request: &ProjectRequest,
         progress_callback: impl Fn(GenerationProgress),
-    ) -> Result<Vec<GeneratedFile>> {
+    ) -> Result<(ProjectPlan, Vec<GeneratedFile>)> {
         let mut generated_files = Vec::new();
         
         // Stage 1: Understanding
+        self.with_metrics(|m| m.begin(PipelineStage::Understanding));
         progress_callback(GenerationProgress {
             stage: PipelineStage::Understanding,
             progress: 0.1,
-            message: "Understanding project requirements...".to_string(),
+            message: t("pipeline-understanding"),
         });
         
         // Stage 2: Planning
+        self.with_metrics(|m| m.begin(PipelineStage::Planning));
         progress_callback(GenerationProgress {
             stage: PipelineStage::Planning,
             progress: 0.2,
-            message: "Creating project plan...".to_string(),
+            message: t("pipeline-planning"),
         });
         
         let plan = self.create_plan(request).await?;
         
         // Stage 3: Generate structure
+        self.with_metrics(|m| m.begin(PipelineStage::GeneratingStructure));
         progress_callback(GenerationProgress {
             stage: PipelineStage::GeneratingStructure,
             progress: 0.3,
-            message: "Generating project structure...".to_string(),
+            message: t("pipeline-structure"),
         });
         
         // Sort files by priority
         let mut files_to_generate: Vec<_> = plan.file_structure
             .iter()
             .filter(|f| matches!(f.node_type, NodeType::File))
+            // Copied in, not generated
+            .filter(|f| !project_assets::is_asset(&self.assets, &f.path))
             .collect();
         files_to_generate.sort_by_key(|f| f.priority);
         
         let total_files = files_to_generate.len();
         
         // Stage 4: Generate code
+        self.with_metrics(|m| m.begin(PipelineStage::GeneratingCode));
         for (index, file_node) in files_to_generate.iter().enumerate() {
             let progress = 0.3 + (0.5 * (index as f32 / total_files as f32));
             progress_callback(GenerationProgress {
                 stage: PipelineStage::GeneratingCode,
                 progress,
-                message: format!("Generating {} ({}/{})", file_node.path, index + 1, total_files),
+                message: t_args("pipeline-file", &[("path", &file_node.path), ("index", &(index + 1)), ("total", &total_files)]),
             });
             
             let generated = self.generate_file(file_node, &plan, &generated_files).await?;
@@ -336,17 +528,19 @@ Generate the COMPLETE file content now. Start with any necessary imports, then t
         }
         
         // Stage 5: Generate tests
+        self.with_metrics(|m| m.begin(PipelineStage::GeneratingTests));
         progress_callback(GenerationProgress {
             stage: PipelineStage::GeneratingTests,
             progress: 0.85,
-            message: "Generating test files...".to_string(),
+            message: t("pipeline-tests"),
         });
         
         // Stage 6: Generate documentation
+        self.with_metrics(|m| m.begin(PipelineStage::GeneratingDocs));
         progress_callback(GenerationProgress {
             stage: PipelineStage::GeneratingDocs,
             progress: 0.95,
-            message: "Generating documentation...".to_string(),
+            message: t("pipeline-docs"),
         });
         
         // Generate README
@@ -354,13 +548,14 @@ Generate the COMPLETE file content now. Start with any necessary imports, then t
         generated_files.push(readme);
         
         // Stage 7: Complete
+        self.with_metrics(|m| m.finish());
         progress_callback(GenerationProgress {
             stage: PipelineStage::Complete,
             progress: 1.0,
-            message: "Project generation complete!".to_string(),
+            message: t("pipeline-complete"),
         });
         
-        Ok(generated_files)
+        Ok((plan, generated_files))
     }
     
     /// Generate README documentation
@@ -386,7 +581,7 @@ Generate the COMPLETE file content now. Start with any necessary imports, then t
         
         let setup_cmds = plan.setup_commands.join("\n");
         
-        let prompt = format!(
+        let mut prompt = format!(
             r#"Generate a comprehensive README.md for this project:
 
 Project: {}
@@ -419,55 +614,41 @@ Make it professional and comprehensive."#,
             env_vars,
             setup_cmds
         );
+        if let Some(assets) = project_assets::prompt_context(&self.assets) {
+            prompt.push_str(&format!(
+                "\n\nThe project ships with these user-provided assets. Add an \"Assets\" section \
+                 listing them and where they live:\n{}",
+                assets
+            ));
+        }
         
         let request = GenerationRequest {
-            model: "deepseek-coder-v2:16b".to_string(),
+            model: PIPELINE_MODEL.to_string(),
             prompt,
             system_prompt: Some("You are a technical writer. Create clear, comprehensive README documentation.".to_string()),
             temperature: 0.6,
             max_tokens: 2048,
         };
         
+        let prompt = request.prompt.clone();
         let response = self.llm_client.generate(request).await?;
+        self.with_metrics(|m| m.record_call(&prompt, &response.text));
+        let report = guardrails::process(&response.text, "README.md", &self.guardrails);
+        if let Some(violation) = report.violations.first() {
+            self.with_metrics(|m| m.record_issue("README.md", violation.to_string()));
+            anyhow::bail!("Rejected README: {}", violation);
+        }
         
         Ok(GeneratedFile {
             path: "README.md".to_string(),
-            content: response.text,
+            content: report.content,
             language: "markdown".to_string(),
         })
     }
     
     // Helper methods
     
-    fn extract_json(&self, text: &str) -> Result<String> {
-        // Try to find JSON in markdown code blocks
-        let code_block_re = Regex::new(r"```(?:json)?\s*\n(.*?)\n```").unwrap();
-        if let Some(captures) = code_block_re.captures(text) {
-            return Ok(captures.get(1).unwrap().as_str().to_string());
-        }
-        
-        // Try to find raw JSON (looking for { ... })
-        let json_re = Regex::new(r"\{[\s\S]*\}").unwrap();
-        if let Some(captures) = json_re.find(text) {
-            return Ok(captures.as_str().to_string());
-        }
-        
-        // If nothing found, return the whole text and let parser fail with better error
-        Ok(text.to_string())
-    }
-    
-    fn extract_code(&self, text: &str) -> String {
-        // Remove markdown code blocks if present
-        let code_block_re = Regex::new(r"```[\w]*\s*\n([\s\S]*?)\n```").unwrap();
-        if let Some(captures) = code_block_re.captures(text) {
-            return captures.get(1).unwrap().as_str().to_string();
-        }
-        
-        // Return as-is if no code block found
-        text.to_string()
-    }
-    
-    fn detect_language(&self, path: &str) -> String {
+    pub(crate) fn detect_language(&self, path: &str) -> String {
         let ext = std::path::Path::new(path)
             .extension()
             .and_then(|e| e.to_str())
@@ -498,7 +679,7 @@ Make it professional and comprehensive."#,
         }.to_string()
     }
     
-    fn build_file_context(&self, files: &[GeneratedFile]) -> String {
+    pub(crate) fn build_file_context(&self, files: &[GeneratedFile]) -> String {
         if files.is_empty() {
             return "No files generated yet.".to_string();
         }
@@ -517,14 +698,26 @@ Make it professional and comprehensive."#,
 }
 
 // Tauri command for generating entire project
+/// Saves a generation report under `job_id` (a new id when not given) and emits
+/// it as `project-generation-report`, whether or not generation succeeded.
+/// `assets` (logos, fonts, datasets) are copied into `project_path`.
 #[tauri::command]
 pub async fn generate_full_project(
     window: tauri::Window,
     description: String,
     project_type: String,
     tech_stack: Vec<String>,
+    project_path: Option<String>,
+    job_id: Option<String>,
+    assets: Option<Vec<String>>,
 ) -> Result<Vec<GeneratedFile>, String> {
-    let pipeline = AgentPipeline::new();
+    let assets = project_assets::plan_assets(&assets.unwrap_or_default(), &tech_stack).map_err(|e| e.to_string())?;
+    if !assets.is_empty() && project_path.is_none() {
+        return Err("A project path is needed to copy assets into".to_string());
+    }
+    let pipeline = AgentPipeline::new().with_assets(assets.clone());
+    let job_id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let started_at = chrono::Utc::now();
     
     let request = ProjectRequest {
         description: description.clone(),
@@ -539,5 +732,69 @@ pub async fn generate_full_project(
         window.emit("project-generation-progress", &progress).ok();
     }).await;
     
-    result.map_err(|e| e.to_string())
+    let mut report = pipeline.take_metrics().into_report(
+        &job_id,
+        started_at,
+        result.as_ref().map(|(_, files)| files.as_slice()).unwrap_or_default(),
+    );
+    report.project_path = project_path.clone();
+    report.project_name = result.as_ref().ok().map(|(plan, _)| plan.name.clone());
+    report.error = result.as_ref().err().map(|e| e.to_string());
+    if let Err(e) = generation_report::save(&report) {
+        tracing::warn!("Failed to save generation report: {}", e);
+    }
+    window.emit("project-generation-report", &report).ok();
+    
+    let (plan, files) = result.map_err(|e| e.to_string())?;
+    
+    // Keep the plan so files can be regenerated individually later
+    if let Some(project_path) = project_path {
+        project_assets::copy_into(Path::new(&project_path), &assets).map_err(|e| e.to_string())?;
+        let record = GenerationRecord { request, plan, assets, generated_at: chrono::Utc::now() };
+        if let Err(e) = record.save(Path::new(&project_path)) {
+            tracing::warn!("Failed to save generation plan: {}", e);
+        }
+    }
+    
+    Ok(files)
+}
+
+/// Regenerate a single file of a generated project. Returns the new content and a
+/// diff against the file on disk; call `apply_regenerated_file` to overwrite it.
+#[tauri::command]
+pub async fn regenerate_file(
+    project_path: String,
+    file_path: String,
+    instruction: Option<String>,
+) -> Result<RegeneratedFile, String> {
+    let pipeline = AgentPipeline::new();
+    pipeline
+        .regenerate_file(Path::new(&project_path), &file_path, instruction.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Write agent output to a project file, honoring read-only paths and locks and
+/// journaling the edit so it can be undone
+pub(crate) fn write_generated_file(project_path: &Path, file_path: &str, content: &str) -> Result<()> {
+    let full_path = project_path.join(project_relative(file_path)?);
+    crate::edit_journal::write_tracked(&full_path, "agent", content)
+}
+
+#[tauri::command]
+pub async fn apply_regenerated_file(project_path: String, file_path: String, content: String) -> Result<(), String> {
+    write_generated_file(Path::new(&project_path), &file_path, &content).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_relative_rejects_escapes() {
+        assert!(project_relative("src/main.ts").is_ok());
+        assert!(project_relative("../outside.ts").is_err());
+        assert!(project_relative("src/../../outside.ts").is_err());
+        assert!(project_relative("/etc/passwd").is_err());
+    }
 }
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// User files bigger than this are almost certainly a mistake (a video, a database dump)
+const MAX_ASSET_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetKind {
+    Image,
+    Font,
+    Data,
+    Other,
+}
+
+impl AssetKind {
+    fn of(path: &Path) -> Self {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        match ext.as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "avif" | "ico" | "bmp" => AssetKind::Image,
+            "ttf" | "otf" | "woff" | "woff2" => AssetKind::Font,
+            "csv" | "tsv" | "json" | "jsonl" | "xml" | "parquet" | "xlsx" | "sqlite" | "db" => AssetKind::Data,
+            _ => AssetKind::Other,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            AssetKind::Image => "image",
+            AssetKind::Font => "font",
+            AssetKind::Data => "data",
+            AssetKind::Other => "file",
+        }
+    }
+}
+
+/// A user-provided file and where it goes in the generated project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectAsset {
+    pub source: String,
+    /// Relative to the project root
+    pub destination: String,
+    pub kind: AssetKind,
+    pub size: u64,
+}
+
+/// Where static files conventionally live for a stack
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+    /// Vite, CRA, Next.js and friends serve `public/` as-is
+    Web,
+    Flutter,
+    Django,
+    /// Everything else: `assets/`, with datasets in `data/`
+    Generic,
+}
+
+fn layout(tech_stack: &[String]) -> Layout {
+    let stack: Vec<String> = tech_stack.iter().map(|t| t.to_lowercase()).collect();
+    let uses = |names: &[&str]| stack.iter().any(|t| names.iter().any(|n| t.contains(n)));
+    if uses(&["flutter", "dart"]) {
+        Layout::Flutter
+    } else if uses(&["django"]) {
+        Layout::Django
+    } else if uses(&["react", "vue", "svelte", "next", "nuxt", "angular", "vite", "astro", "solid"]) {
+        Layout::Web
+    } else {
+        Layout::Generic
+    }
+}
+
+fn directory(layout: Layout, kind: AssetKind) -> &'static str {
+    match (layout, kind) {
+        (Layout::Web, AssetKind::Image) => "public/images",
+        (Layout::Web, AssetKind::Font) => "public/fonts",
+        (Layout::Web, AssetKind::Data) => "public/data",
+        (Layout::Web, AssetKind::Other) => "public",
+        (Layout::Flutter, AssetKind::Image) => "assets/images",
+        (Layout::Flutter, AssetKind::Font) => "assets/fonts",
+        (Layout::Flutter, _) => "assets",
+        (Layout::Django, AssetKind::Image) => "static/images",
+        (Layout::Django, AssetKind::Font) => "static/fonts",
+        (Layout::Django, AssetKind::Data) => "data",
+        (Layout::Django, AssetKind::Other) => "static",
+        (Layout::Generic, AssetKind::Image) => "assets/images",
+        (Layout::Generic, AssetKind::Font) => "assets/fonts",
+        (Layout::Generic, AssetKind::Data) => "data",
+        (Layout::Generic, AssetKind::Other) => "assets",
+    }
+}
+
+/// Decide where each file goes. Two files with the same name get `-2`, `-3`... suffixes.
+pub fn plan_assets(paths: &[String], tech_stack: &[String]) -> Result<Vec<ProjectAsset>> {
+    let layout = layout(tech_stack);
+    let mut taken = HashSet::new();
+    let mut assets = Vec::new();
+
+    for source in paths {
+        let path = Path::new(source);
+        let metadata = std::fs::metadata(path).with_context(|| format!("Asset not found: {}", source))?;
+        if !metadata.is_file() {
+            anyhow::bail!("Asset is not a file: {}", source);
+        }
+        if metadata.len() > MAX_ASSET_BYTES {
+            anyhow::bail!("Asset is larger than {} MB: {}", MAX_ASSET_BYTES / 1024 / 1024, source);
+        }
+
+        let kind = AssetKind::of(path);
+        let dir = directory(layout, kind);
+        let stem = path.file_stem().and_then(|s| s.to_str()).context("Asset has no file name")?;
+        let ext = path.extension().and_then(|e| e.to_str()).map(|e| format!(".{}", e)).unwrap_or_default();
+        let mut destination = format!("{}/{}{}", dir, stem, ext);
+        let mut n = 2;
+        while !taken.insert(destination.clone()) {
+            destination = format!("{}/{}-{}{}", dir, stem, n, ext);
+            n += 1;
+        }
+        assets.push(ProjectAsset { source: source.clone(), destination, kind, size: metadata.len() });
+    }
+    Ok(assets)
+}
+
+/// Assets as a list for prompts, or None when there are none
+pub fn prompt_context(assets: &[ProjectAsset]) -> Option<String> {
+    if assets.is_empty() {
+        return None;
+    }
+    Some(
+        assets
+            .iter()
+            .map(|a| {
+                let name = Path::new(&a.source).file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+                format!("- {} ({}, {} KB, provided as {})", a.destination, a.kind.as_str(), a.size.div_ceil(1024), name)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+pub fn is_asset(assets: &[ProjectAsset], path: &str) -> bool {
+    assets.iter().any(|a| a.destination == path)
+}
+
+pub fn copy_into(project_path: &Path, assets: &[ProjectAsset]) -> Result<()> {
+    for asset in assets {
+        let target = project_path.join(&asset.destination);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(&asset.source, &target)
+            .with_context(|| format!("Failed to copy {} to {}", asset.source, asset.destination))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_assets() {
+        let dir = std::env::temp_dir().join(format!("assets-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("other")).unwrap();
+        let files = ["logo.svg", "other/logo.svg", "Inter.woff2", "sales.csv"].map(|name| {
+            let path = dir.join(name);
+            std::fs::write(&path, "x").unwrap();
+            path.to_string_lossy().to_string()
+        });
+
+        let web = plan_assets(&files, &["React".to_string(), "TypeScript".to_string()]).unwrap();
+        let destinations: Vec<&str> = web.iter().map(|a| a.destination.as_str()).collect();
+        assert_eq!(destinations, ["public/images/logo.svg", "public/images/logo-2.svg", "public/fonts/Inter.woff2", "public/data/sales.csv"]);
+
+        let python = plan_assets(&files[3..], &["Python".to_string()]).unwrap();
+        assert_eq!(python[0].destination, "data/sales.csv");
+        assert!(plan_assets(&[dir.join("missing.png").to_string_lossy().to_string()], &[]).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
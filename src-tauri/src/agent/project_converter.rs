@@ -0,0 +1,391 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+use crate::filesystem::collect_source_files;
+use crate::llm::{LLMClient, GenerationRequest};
+
+// Files above this size are left for manual conversion
+const MAX_FILE_BYTES: u64 = 48 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionReport {
+    pub src_dir: String,
+    pub output_dir: String,
+    pub converted: Vec<String>,
+    pub skipped: Vec<SkippedFile>,
+    pub needs_attention: Vec<SkippedFile>,
+    /// Source name -> target name applied across all files
+    pub symbol_map: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionProgress {
+    pub current: usize,
+    pub total: usize,
+    pub file: String,
+}
+
+fn language_extensions(language: &str) -> &'static [&'static str] {
+    match language.to_lowercase().as_str() {
+        "javascript" | "js" => &["js", "jsx", "mjs", "cjs"],
+        "typescript" | "ts" => &["ts", "tsx"],
+        "python" | "py" => &["py"],
+        "rust" | "rs" => &["rs"],
+        "go" => &["go"],
+        "java" => &["java"],
+        "kotlin" => &["kt"],
+        "csharp" | "c#" => &["cs"],
+        "ruby" => &["rb"],
+        "php" => &["php"],
+        "swift" => &["swift"],
+        _ => &[],
+    }
+}
+
+/// Languages whose functions and variables use snake_case
+fn uses_snake_case(language: &str) -> bool {
+    matches!(language.to_lowercase().as_str(), "python" | "py" | "rust" | "rs" | "ruby")
+}
+
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).map(|n| n.is_lowercase()).unwrap_or(false);
+            // A run of capitals is one word: "HTTPServer" -> "http_server"
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_is_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+fn to_camel_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper_next = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper_next = !out.is_empty();
+        } else if upper_next {
+            out.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Collect top-level function and class names declared in a source file
+fn declared_symbols(content: &str) -> Vec<(String, bool)> {
+    let function_re = Regex::new(
+        r"(?m)^\s*(?:export\s+)?(?:pub\s+)?(?:async\s+)?(?:function|def|fn|func)\s+([A-Za-z_]\w*)"
+    ).unwrap();
+    let class_re = Regex::new(
+        r"(?m)^\s*(?:export\s+)?(?:pub\s+)?(?:abstract\s+)?(?:class|struct|interface|trait|enum)\s+([A-Za-z_]\w*)"
+    ).unwrap();
+
+    let mut symbols: Vec<(String, bool)> = function_re
+        .captures_iter(content)
+        .map(|c| (c[1].to_string(), false))
+        .collect();
+    symbols.extend(class_re.captures_iter(content).map(|c| (c[1].to_string(), true)));
+    symbols
+}
+
+/// Build one naming table for the whole tree so every file refers to
+/// the same converted names. Explicit mapping rules win.
+pub fn build_symbol_map(
+    sources: &[(PathBuf, String)],
+    to_language: &str,
+    mapping_rules: &HashMap<String, String>,
+) -> BTreeMap<String, String> {
+    let snake = uses_snake_case(to_language);
+    let mut map = BTreeMap::new();
+
+    for (_, content) in sources {
+        for (name, is_type) in declared_symbols(content) {
+            // Type names stay PascalCase in every target language
+            let converted = if is_type {
+                name.clone()
+            } else if snake {
+                to_snake_case(&name)
+            } else {
+                to_camel_case(&name)
+            };
+            map.insert(name, converted);
+        }
+    }
+
+    for (from, to) in mapping_rules {
+        map.insert(from.clone(), to.clone());
+    }
+
+    map
+}
+
+fn target_path(file: &Path, src_dir: &Path, output_dir: &Path, to_language: &str) -> PathBuf {
+    let relative = file.strip_prefix(src_dir).unwrap_or(file);
+    let mut target = output_dir.join(relative);
+
+    if let Some(ext) = language_extensions(to_language).first() {
+        target.set_extension(ext);
+    }
+
+    // Python modules can't contain dashes
+    if uses_snake_case(to_language) {
+        if let Some(stem) = target.file_stem().map(|s| s.to_string_lossy().replace('-', "_")) {
+            let ext = target.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+            target.set_file_name(format!("{}.{}", to_snake_case(&stem), ext));
+        }
+    }
+
+    target
+}
+
+pub struct ProjectConverter {
+    llm_client: LLMClient,
+}
+
+impl ProjectConverter {
+    pub fn new() -> Self {
+        Self {
+            llm_client: LLMClient::new(),
+        }
+    }
+
+    async fn convert_file(
+        &self,
+        relative_path: &str,
+        code: &str,
+        from_language: &str,
+        to_language: &str,
+        symbol_table: &str,
+        rules: &str,
+    ) -> Result<String> {
+        let prompt = format!(
+            r#"Convert this file from {from_lang} to {to_lang}. It is `{path}`, part of a larger project being converted file by file.
+
+ORIGINAL ({from_lang}):
+```{from_lang}
+{code}
+```
+
+SHARED SYMBOL NAMES (use exactly these names for declarations and references, including imports from other files):
+{symbols}
+
+MAPPING RULES:
+{rules}
+
+Requirements:
+1. Preserve exact functionality
+2. Use idiomatic {to_lang} patterns and imports
+3. Keep relative imports pointing at the converted files
+4. If something cannot be converted faithfully, leave a comment starting with `TODO(convert):` explaining why
+
+Generate ONLY the converted code:"#,
+            from_lang = from_language,
+            to_lang = to_language,
+            path = relative_path,
+            code = code,
+            symbols = symbol_table,
+            rules = rules,
+        );
+
+        let request = GenerationRequest {
+            model: "deepseek-coder-v2:16b".to_string(),
+            prompt,
+            system_prompt: Some(format!(
+                "You are an expert in both {} and {}. Convert code accurately and keep names consistent across files.",
+                from_language, to_language
+            )),
+            temperature: 0.3,
+            max_tokens: 4096,
+        };
+
+        let response = self.llm_client.generate(request).await?;
+        Ok(extract_code(&response.text))
+    }
+
+    pub async fn convert_project(
+        &self,
+        app: &tauri::AppHandle,
+        src_dir: &Path,
+        output_dir: &Path,
+        from_language: &str,
+        to_language: &str,
+        mapping_rules: &HashMap<String, String>,
+    ) -> Result<ConversionReport> {
+        let extensions = language_extensions(from_language);
+        if extensions.is_empty() {
+            anyhow::bail!("Unsupported source language: {}", from_language);
+        }
+        // Without a target extension every output file would keep the source one
+        if language_extensions(to_language).is_empty() {
+            anyhow::bail!("Unsupported target language: {}", to_language);
+        }
+
+        let mut report = ConversionReport {
+            src_dir: src_dir.to_string_lossy().to_string(),
+            output_dir: output_dir.to_string_lossy().to_string(),
+            converted: Vec::new(),
+            skipped: Vec::new(),
+            needs_attention: Vec::new(),
+            symbol_map: BTreeMap::new(),
+        };
+
+        let mut sources = Vec::new();
+        for file in collect_source_files(src_dir, extensions) {
+            let relative = file.strip_prefix(src_dir).unwrap_or(&file).to_string_lossy().to_string();
+            let size = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+
+            if size > MAX_FILE_BYTES {
+                report.skipped.push(SkippedFile { path: relative, reason: "File too large to convert automatically".to_string() });
+                continue;
+            }
+
+            match std::fs::read_to_string(&file) {
+                Ok(content) => sources.push((file, content)),
+                Err(_) => report.skipped.push(SkippedFile { path: relative, reason: "Not a UTF-8 text file".to_string() }),
+            }
+        }
+
+        report.symbol_map = build_symbol_map(&sources, to_language, mapping_rules);
+
+        let symbol_table = report.symbol_map
+            .iter()
+            .map(|(from, to)| format!("{} -> {}", from, to))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let rules = if mapping_rules.is_empty() {
+            "(none)".to_string()
+        } else {
+            mapping_rules.iter().map(|(from, to)| format!("{} => {}", from, to)).collect::<Vec<_>>().join("\n")
+        };
+
+        let total = sources.len();
+
+        for (index, (file, content)) in sources.iter().enumerate() {
+            let relative = file.strip_prefix(src_dir).unwrap_or(file).to_string_lossy().to_string();
+
+            app.emit("project-conversion-progress", ConversionProgress {
+                current: index + 1,
+                total,
+                file: relative.clone(),
+            }).ok();
+
+            let converted = match self.convert_file(&relative, content, from_language, to_language, &symbol_table, &rules).await {
+                Ok(code) if !code.trim().is_empty() => code,
+                Ok(_) => {
+                    report.needs_attention.push(SkippedFile { path: relative, reason: "Model returned no code".to_string() });
+                    continue;
+                }
+                Err(e) => {
+                    report.needs_attention.push(SkippedFile { path: relative, reason: format!("Conversion failed: {}", e) });
+                    continue;
+                }
+            };
+
+            let target = target_path(file, src_dir, output_dir, to_language);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&target, &converted)?;
+
+            let todo_count = converted.matches("TODO(convert)").count();
+            if todo_count > 0 {
+                report.needs_attention.push(SkippedFile {
+                    path: relative.clone(),
+                    reason: format!("{} construct(s) need manual conversion", todo_count),
+                });
+            }
+
+            report.converted.push(relative);
+        }
+
+        let report_path = output_dir.join("CONVERSION_REPORT.json");
+        std::fs::create_dir_all(output_dir)?;
+        std::fs::write(&report_path, serde_json::to_string_pretty(&report)?)?;
+
+        tracing::info!(
+            "Converted {} of {} files from {} to {}",
+            report.converted.len(),
+            total,
+            from_language,
+            to_language
+        );
+
+        Ok(report)
+    }
+}
+
+fn extract_code(text: &str) -> String {
+    let code_block_re = Regex::new(r"```[\w#+]*\s*\n([\s\S]*?)\n```").unwrap();
+    if let Some(captures) = code_block_re.captures(text) {
+        return captures[1].to_string();
+    }
+    text.to_string()
+}
+
+// Tauri commands
+
+/// Convert every matching file under `src_dir`. Output defaults to a sibling `<src_dir>-<to>` folder.
+#[tauri::command]
+pub async fn convert_project_language(
+    app: tauri::AppHandle,
+    src_dir: String,
+    from: String,
+    to: String,
+    mapping_rules: Option<HashMap<String, String>>,
+    output_dir: Option<String>,
+) -> Result<ConversionReport, String> {
+    let src = PathBuf::from(&src_dir);
+    let output = output_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(format!("{}-{}", src_dir.trim_end_matches(['/', '\\']), to.to_lowercase())));
+
+    let converter = ProjectConverter::new();
+    converter.convert_project(&app, &src, &output, &from, &to, &mapping_rules.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_map_naming() {
+        let sources = vec![(
+            PathBuf::from("a.js"),
+            "export function getUserName() {}\nclass UserStore {}\n".to_string(),
+        )];
+        let mut rules = HashMap::new();
+        rules.insert("express".to_string(), "fastapi".to_string());
+
+        let map = build_symbol_map(&sources, "python", &rules);
+        assert_eq!(map["getUserName"], "get_user_name");
+        assert_eq!(map["UserStore"], "UserStore");
+        assert_eq!(map["express"], "fastapi");
+
+        assert_eq!(to_camel_case("get_user_name"), "getUserName");
+        assert_eq!(to_snake_case("HTTPServer"), "http_server");
+        assert_eq!(to_snake_case("parseURL"), "parse_url");
+        assert_eq!(to_snake_case("toV2Format"), "to_v2_format");
+    }
+}
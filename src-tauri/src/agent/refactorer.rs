@@ -1,7 +1,13 @@
 use serde::{Deserialize, Serialize};
-use anyhow::Result;
+use anyhow::{Result, Context};
+use schemars::JsonSchema;
+use std::path::Path;
 
+use crate::agent::chat;
+use crate::agent::documentation::{convention, GeneratedDoc, GeneratedDocBatch, UndocumentedFunction};
+use crate::database::ChatMessage;
 use crate::llm::{LLMClient, GenerationRequest};
+use crate::llm::structured::generate_typed;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RefactoringResult {
@@ -40,6 +46,185 @@ pub struct RefactoringImpact {
     pub testability: i32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExplanationStep {
+    /// Line range this step covers, e.g. "12-18"
+    pub lines: Option<String>,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CodeExplanation {
+    pub overview: String,
+    pub steps: Vec<ExplanationStep>,
+    pub pitfalls: Vec<String>,
+}
+
+impl CodeExplanation {
+    /// Readable form stored as the assistant's chat message
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("## Overview\n\n{}\n\n## Step by step\n\n", self.overview.trim());
+        for (index, step) in self.steps.iter().enumerate() {
+            match &step.lines {
+                Some(lines) => out.push_str(&format!("{}. **Lines {}**: {}\n", index + 1, lines, step.explanation.trim())),
+                None => out.push_str(&format!("{}. {}\n", index + 1, step.explanation.trim())),
+            }
+        }
+        if !self.pitfalls.is_empty() {
+            out.push_str("\n## Pitfalls\n\n");
+            for pitfall in &self.pitfalls {
+                out.push_str(&format!("- {}\n", pitfall.trim()));
+            }
+        }
+        out
+    }
+}
+
+/// What an explanation session is about. File selections are re-read for every
+/// follow-up so answers describe the code as it is now.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeSelection {
+    pub file_path: Option<String>,
+    /// 1-based, inclusive
+    pub start_line: usize,
+    pub end_line: usize,
+    pub language: String,
+    /// Only kept for pasted code without a file
+    pub code: Option<String>,
+}
+
+impl CodeSelection {
+    /// Current text of the selection
+    pub fn read(&self) -> Result<String> {
+        let Some(path) = &self.file_path else {
+            return Ok(self.code.clone().unwrap_or_default());
+        };
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        let lines: Vec<&str> = content.lines().collect();
+        if self.start_line == 0 || self.start_line > self.end_line || self.start_line > lines.len() {
+            anyhow::bail!("Lines {}-{} are outside {} ({} lines)", self.start_line, self.end_line, path, lines.len());
+        }
+        let end = self.end_line.min(lines.len());
+        Ok(lines[self.start_line - 1..end].join("\n"))
+    }
+    
+    fn label(&self) -> String {
+        match &self.file_path {
+            Some(path) => {
+                let name = Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.clone());
+                format!("{}:{}-{}", name, self.start_line, self.end_line)
+            }
+            None => format!("{} snippet", self.language),
+        }
+    }
+    
+    fn context(&self, code: &str) -> String {
+        let location = match &self.file_path {
+            Some(path) => format!("{} lines {}-{}\n", path, self.start_line, self.end_line),
+            None => String::new(),
+        };
+        format!("{}```{}\n{}\n```", location, self.language, code)
+    }
+}
+
+/// An explanation plus the chat session that holds follow-up questions about it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionExplanation {
+    pub session_id: String,
+    pub selection: CodeSelection,
+    pub code: String,
+    pub explanation: CodeExplanation,
+}
+
+fn language_for_path(path: &str) -> String {
+    let ext = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    match ext.as_str() {
+        "ts" | "tsx" => "typescript".to_string(),
+        "js" | "jsx" | "mjs" | "cjs" => "javascript".to_string(),
+        "py" => "python".to_string(),
+        "rs" => "rust".to_string(),
+        "go" => "go".to_string(),
+        "rb" => "ruby".to_string(),
+        "" => "text".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Explain a selection and open a chat session bound to it
+pub async fn explain_selection(selection: CodeSelection, project_id: Option<String>) -> Result<SelectionExplanation> {
+    let code = selection.read()?;
+    if code.trim().is_empty() {
+        anyhow::bail!("Nothing to explain");
+    }
+    
+    let explanation = CodeRefactorer::new()
+        .explain_code(&code, &selection.language, selection.start_line)
+        .await?;
+    
+    let session = chat::create_session(&format!("Explain {}", selection.label()), project_id, None)?;
+    crate::database::open()?.set_chat_session_selection(&session.id, Some(&serde_json::to_string(&selection)?))?;
+    let question = chat::record_message(&session.id, None, "user", "Explain this code", Some(selection.context(&code)), None)?;
+    chat::record_message(&session.id, Some(question.id), "assistant", &explanation.to_markdown(), None, None)?;
+    
+    Ok(SelectionExplanation {
+        session_id: session.id,
+        selection,
+        code,
+        explanation,
+    })
+}
+
+/// Answer a follow-up in an explanation session, continuing from `parent_id` or the
+/// newest message, with the selection's current code in the prompt
+pub async fn answer_follow_up(session_id: &str, question: &str, parent_id: Option<String>) -> Result<ChatMessage> {
+    let db = crate::database::open()?;
+    let session = db.get_chat_session(session_id)?.context("Chat session not found")?;
+    let selection: CodeSelection = serde_json::from_str(
+        session.selection.as_deref().context("This chat session is not about a code selection")?,
+    )?;
+    let code = selection.read()?;
+    
+    let parent_id = match parent_id {
+        Some(id) => Some(id),
+        None => chat::latest_message_id(session_id)?,
+    };
+    let question = chat::record_message(session_id, parent_id, "user", question, None, None)?;
+    
+    let generated = async {
+        let path = chat::path_to(&db.get_chat_messages(session_id)?, &question.id);
+        let code_context = selection.context(&code);
+        let conversation = super::compaction::conversation_prompt(
+            session_id,
+            &path,
+            crate::agent::estimate::estimate_tokens(&code_context),
+        )
+        .await?;
+        let prompt = format!("Code under discussion:\n{}\n\n{}", code_context, conversation);
+        let persona = super::personas::session_persona(session_id)?;
+        super::generate_reply(prompt, persona.as_ref(), session.project_id.as_deref()).await
+    }
+    .await;
+    let response = match generated {
+        Ok(response) => response,
+        Err(e) => {
+            // Leave no unanswered question behind; the user can simply ask again
+            if let Err(cleanup) = db.delete_chat_messages(&[question.id.clone()]) {
+                tracing::warn!("Failed to remove follow-up question {}: {}", question.id, cleanup);
+            }
+            return Err(e);
+        }
+    };
+    
+    chat::record_message(
+        session_id,
+        Some(question.id),
+        "assistant",
+        response.text.trim(),
+        None,
+        Some(response.model),
+    )
+}
+
 pub struct CodeRefactorer {
     llm_client: LLMClient,
 }
@@ -121,7 +306,16 @@ Testability: [+XX or -XX]"#,
         &self,
         code: &str,
         language: &str,
-    ) -> Result<String> {
+        first_line: usize,
+    ) -> Result<CodeExplanation> {
+        // Numbered so steps can point at the lines they describe
+        let numbered = code
+            .lines()
+            .enumerate()
+            .map(|(i, line)| format!("{:>5} | {}", first_line + i, line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        
         let prompt = format!(
             r#"Explain this {language} code in detail:
 
@@ -130,15 +324,13 @@ Testability: [+XX or -XX]"#,
 ```
 
 Provide:
-1. **Overview** - What does this code do?
-2. **Step-by-step breakdown** - Explain each important part
-3. **Key concepts** - What programming concepts are used?
-4. **Potential issues** - Any problems or edge cases?
-5. **Suggestions** - How could it be improved?
+1. overview - What does this code do?
+2. steps - Explain each important part in order, with the line range it covers (e.g. "12-18")
+3. pitfalls - Bugs, edge cases or surprising behaviour to watch out for
 
 Make the explanation clear and educational."#,
             language = language,
-            code = code
+            code = numbered
         );
         
         let request = GenerationRequest {
@@ -149,8 +341,7 @@ Make the explanation clear and educational."#,
             max_tokens: 2048,
         };
         
-        let response = self.llm_client.generate(request).await?;
-        Ok(response.text)
+        generate_typed(&self.llm_client, request).await
     }
     
     /// Convert code between languages
@@ -236,6 +427,49 @@ Generate the fully documented code:"#,
         Ok(self.clean_code(&response.text))
     }
     
+    /// Documentation text for several functions in one request, keyed by position in `functions`
+    pub async fn document_functions(&self, functions: &[UndocumentedFunction]) -> Result<Vec<GeneratedDoc>> {
+        let listing = functions
+            .iter()
+            .enumerate()
+            .map(|(id, f)| {
+                format!(
+                    "### id {}: `{}` in {} ({})\n```\n{}\n```",
+                    id,
+                    f.item.name,
+                    f.file,
+                    convention(f.language),
+                    f.source
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        
+        let prompt = format!(
+            r#"Write documentation for each of these functions, following the convention named for it.
+
+{listing}
+
+Requirements:
+1. Return only the documentation text, without comment markers (no /**, ///, # or quotes)
+2. Describe what the function does, its parameters and its return value
+3. Don't restate the code line by line
+4. Use the id shown for each function"#,
+            listing = listing
+        );
+        
+        let request = GenerationRequest {
+            model: "deepseek-coder-v2:16b".to_string(),
+            prompt,
+            system_prompt: Some("You are a documentation expert. Add clear, helpful documentation to code.".to_string()),
+            temperature: 0.4,
+            max_tokens: 4096,
+        };
+        
+        let batch: GeneratedDocBatch = generate_typed(&self.llm_client, request).await?;
+        Ok(batch.docs.into_iter().filter(|d| d.id < functions.len() && !d.doc.trim().is_empty()).collect())
+    }
+    
     /// Optimize code for performance
     pub async fn optimize_performance(
         &self,
@@ -454,15 +688,51 @@ pub async fn refactor_code(
         .map_err(|e| e.to_string())
 }
 
+/// Explain pasted `code`, or lines `start_line..=end_line` of `file_path` read from disk.
+/// The result opens a chat session for follow-up questions via `ask_about_selection`.
 #[tauri::command]
 pub async fn explain_code(
-    code: String,
-    language: String,
-) -> Result<String, String> {
-    let refactorer = CodeRefactorer::new();
-    refactorer.explain_code(&code, &language)
-        .await
-        .map_err(|e| e.to_string())
+    code: Option<String>,
+    language: Option<String>,
+    file_path: Option<String>,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+    project_id: Option<String>,
+) -> Result<SelectionExplanation, String> {
+    let selection = match (file_path, code) {
+        (Some(file_path), _) => {
+            let line_count = std::fs::read_to_string(&file_path)
+                .map_err(|e| format!("Failed to read {}: {}", file_path, e))?
+                .lines()
+                .count();
+            CodeSelection {
+                language: language.unwrap_or_else(|| language_for_path(&file_path)),
+                start_line: start_line.unwrap_or(1),
+                end_line: end_line.unwrap_or(line_count),
+                file_path: Some(file_path),
+                code: None,
+            }
+        }
+        (None, Some(code)) => CodeSelection {
+            file_path: None,
+            start_line: 1,
+            end_line: code.lines().count(),
+            language: language.unwrap_or_else(|| "text".to_string()),
+            code: Some(code),
+        },
+        (None, None) => return Err("Provide code or a file path".to_string()),
+    };
+    
+    explain_selection(selection, project_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn ask_about_selection(
+    session_id: String,
+    question: String,
+    parent_id: Option<String>,
+) -> Result<ChatMessage, String> {
+    answer_follow_up(&session_id, &question, parent_id).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -476,3 +746,29 @@ pub async fn convert_code_language(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_reads_current_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("calc.py");
+        std::fs::write(&path, "import math\n\ndef area(r):\n    return math.pi * r * r\n").unwrap();
+
+        let mut selection = CodeSelection {
+            file_path: Some(path.to_string_lossy().to_string()),
+            start_line: 3,
+            end_line: 4,
+            language: language_for_path("calc.py"),
+            code: None,
+        };
+        assert_eq!(selection.read().unwrap(), "def area(r):\n    return math.pi * r * r");
+        assert_eq!(selection.label(), "calc.py:3-4");
+
+        selection.start_line = 9;
+        selection.end_line = 10;
+        assert!(selection.read().is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::agent::context::{self, ContextRequest};
+use crate::agent::pipeline::{write_generated_file, PIPELINE_MODEL};
+use crate::board::{self, CardInput, CardLink};
+use crate::database::BoardCard;
+use crate::llm::structured::generate_typed;
+use crate::llm::{GenerationRequest, LLMClient};
+use crate::project_profile;
+use crate::sandbox::{Sandbox, SandboxLanguage, SandboxRequest};
+use crate::testing::{TestFramework, TestRunner};
+
+const REPRO_DIR: &str = "repro";
+/// Drafts tried before giving up on a failing reproduction
+const MAX_ATTEMPTS: u32 = 3;
+/// Tokens of related project code shown to the model
+const CONTEXT_BUDGET: u64 = 6_000;
+/// Runner output kept for the card and the next prompt
+const MAX_OUTPUT_CHARS: usize = 4_000;
+/// Failures that happen before the draft reaches the project's code
+const SETUP_ERRORS: &[&str] = &[
+    "SyntaxError",
+    "IndentationError",
+    "ModuleNotFoundError",
+    "ImportError",
+    "Cannot find module",
+    "Test suite failed to run",
+    "ERROR collecting",
+];
+/// Signs that an assertion about the behaviour failed
+const ASSERTION_FAILURES: &[&str] = &["AssertionError", "assert ", "expect(", "Expected:"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReproKind {
+    /// Run by the project's test runner, importing the project's code
+    Test,
+    /// Standalone script run in the sandbox, for projects without a supported runner
+    Script,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+struct ReproDraft {
+    /// Complete file content
+    code: String,
+    /// How the bug shows when it runs, e.g. the failing assertion
+    expected_failure: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reproduction {
+    /// Relative to the project root, under `repro/`
+    pub path: String,
+    pub kind: ReproKind,
+    /// Whether the last draft failed as a reproduction should
+    pub confirmed: bool,
+    pub expected_failure: String,
+    /// Failure messages or script output of the last run
+    pub output: String,
+    pub attempts: u32,
+    pub card: BoardCard,
+}
+
+/// How the reproduction is written and run in this project
+#[derive(Debug, Clone)]
+enum Harness {
+    Runner(TestFramework),
+    Sandbox(SandboxLanguage),
+}
+
+fn harness(project_path: &Path) -> Result<Harness> {
+    if let Ok(framework) = TestRunner::detect_framework(&project_path.to_path_buf()) {
+        if matches!(framework, TestFramework::Jest | TestFramework::Vitest | TestFramework::Mocha | TestFramework::PyTest) {
+            return Ok(Harness::Runner(framework));
+        }
+    }
+    let profile = project_profile::ensure(project_path)?;
+    match profile.languages.first().map(|l| l.language.as_str()) {
+        Some("Python") => Ok(Harness::Sandbox(SandboxLanguage::Python)),
+        Some("JavaScript") => Ok(Harness::Sandbox(SandboxLanguage::JavaScript)),
+        _ => anyhow::bail!("Reproductions need a Jest, Vitest, Mocha or pytest project, or a Python or JavaScript one"),
+    }
+}
+
+/// Up to six words of the description, for the file name
+fn slug(description: &str, separator: &str) -> String {
+    let words: Vec<String> = description
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(6)
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        "bug".to_string()
+    } else {
+        words.join(separator)
+    }
+}
+
+/// A path under `repro/` the runner will pick up and no earlier reproduction uses
+fn repro_path(project_path: &Path, harness: &Harness, description: &str) -> String {
+    let typescript = project_path.join("tsconfig.json").exists();
+    let (stem, suffix) = match harness {
+        Harness::Runner(TestFramework::PyTest) => (format!("test_{}", slug(description, "_")), ".py"),
+        Harness::Runner(TestFramework::Mocha) => (slug(description, "-"), ".spec.js"),
+        Harness::Runner(_) if typescript => (slug(description, "-"), ".test.ts"),
+        Harness::Runner(_) => (slug(description, "-"), ".test.js"),
+        Harness::Sandbox(SandboxLanguage::Python) => (slug(description, "_"), ".py"),
+        Harness::Sandbox(SandboxLanguage::JavaScript) => (slug(description, "-"), ".js"),
+    };
+    (1..)
+        .map(|n| match n {
+            1 => format!("{}/{}{}", REPRO_DIR, stem, suffix),
+            n => format!("{}/{}-{}{}", REPRO_DIR, stem, n, suffix),
+        })
+        .find(|path| !project_path.join(path).exists())
+        .unwrap_or_default()
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((end, _)) => format!("{}\n... (truncated)", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn draft_prompt(harness: &Harness, path: &str, bug: &str, profile: &str, related: &str) -> String {
+    let how = match harness {
+        Harness::Runner(framework) => format!(
+            "Write a {:?} test file at {} with a single test that FAILS while the bug exists and passes once \
+             it is fixed. Import the project's code with paths relative to {}/.",
+            framework, path, REPRO_DIR
+        ),
+        Harness::Sandbox(language) => format!(
+            "Write a standalone {:?} script for {} that exits with a non-zero status (a failed assertion or \
+             uncaught error) while the bug exists. It runs in an empty directory without network access, so \
+             copy the minimal code it needs instead of importing the project.",
+            language, path
+        ),
+    };
+    format!(
+        r#"Reproduce this bug with the smallest possible program:
+{}
+
+{}
+Keep it minimal: no setup or cases unrelated to the bug.
+
+Project:
+{}
+
+Related project code:
+{}"#,
+        bug, how, profile, related
+    )
+}
+
+/// Why a failing draft doesn't count as a reproduction: it broke before reaching the
+/// code, or failed with something other than the expected failure or an assertion
+fn failure_mismatch(output: &str, expected_failure: &str) -> Option<String> {
+    if let Some(error) = SETUP_ERRORS.iter().find(|e| output.contains(*e)) {
+        return Some(format!("it failed with {} before reaching the buggy code", error));
+    }
+    // Error names the model expects, e.g. `TypeError` or `ValueError`
+    let expected_errors: Vec<&str> = expected_failure
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| word.len() > 5 && (word.ends_with("Error") || word.ends_with("Exception")))
+        .collect();
+    let reached = expected_errors.iter().any(|e| output.contains(e)) || ASSERTION_FAILURES.iter().any(|a| output.contains(a));
+    if reached {
+        None
+    } else {
+        Some(format!("it failed, but not with the expected failure ({}) or an assertion", expected_failure.trim()))
+    }
+}
+
+/// Run the draft. Returns why it doesn't reproduce the bug (None when it does) and the output.
+async fn run_draft(project_path: &Path, harness: &Harness, path: &str, draft: &ReproDraft) -> Result<(Option<String>, String)> {
+    let (failed, output) = match harness {
+        Harness::Runner(framework) => {
+            let runner = TestRunner::new(framework.clone(), project_path.to_path_buf());
+            let result = runner.run_files(&[path.to_string()]).await?;
+            let output = result
+                .failures
+                .iter()
+                .map(|f| format!("FAIL {}\n{}", f.test_name, f.stack_trace.as_deref().unwrap_or(&f.error_message)))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            let output = if result.total_tests == 0 { "No tests ran".to_string() } else { output };
+            (result.failed > 0, output)
+        }
+        Harness::Sandbox(language) => {
+            let request = SandboxRequest { language: language.clone(), code: draft.code.clone(), stdin: None, limits: None };
+            let result = tokio::task::spawn_blocking(move || Sandbox::new()?.run(&request)).await??;
+            let output = if result.timed_out {
+                "Timed out".to_string()
+            } else {
+                format!("exit code {:?}\n{}\n{}", result.exit_code, result.stdout, result.stderr)
+            };
+            let failed = !result.timed_out && result.exit_code.is_some_and(|code| code != 0);
+            (failed, output.trim().to_string())
+        }
+    };
+
+    let mismatch = if failed {
+        failure_mismatch(&output, &draft.expected_failure)
+    } else {
+        Some("it did not fail".to_string())
+    };
+    Ok((mismatch, truncate(&output)))
+}
+
+/// Write a minimal test or script under `repro/` that fails because of `bug`, re-drafting
+/// while it passes, and put it on the board. An unconfirmed reproduction is still kept
+/// and its card says so.
+pub async fn generate(project_id: &str, project_path: &Path, bug: &str) -> Result<Reproduction> {
+    let bug = bug.trim();
+    if bug.is_empty() {
+        anyhow::bail!("Describe the bug to reproduce");
+    }
+    let (harness, path, profile, related) = {
+        let (project_path, bug) = (project_path.to_path_buf(), bug.to_string());
+        tokio::task::spawn_blocking(move || -> Result<_> {
+            let harness = harness(&project_path)?;
+            let path = repro_path(&project_path, &harness, &bug);
+            let profile = project_profile::prompt_context(&project_profile::ensure(&project_path)?);
+            let request = ContextRequest { active_file: None, query: Some(bug) };
+            let related = context::select(&project_path, CONTEXT_BUDGET, &request).render();
+            Ok((harness, path, profile, related))
+        })
+        .await??
+    };
+
+    let client = LLMClient::new();
+    let mut prompt = draft_prompt(&harness, &path, bug, &profile, &related);
+    let mut attempts = 0;
+    let (draft, mismatch, output) = loop {
+        attempts += 1;
+        let request = GenerationRequest {
+            model: PIPELINE_MODEL.to_string(),
+            prompt: prompt.clone(),
+            system_prompt: Some("You are an expert at isolating bugs into minimal failing tests.".to_string()),
+            temperature: 0.3,
+            max_tokens: 2048,
+        };
+        let draft: ReproDraft = generate_typed(&client, request).await?;
+        write_generated_file(project_path, &path, &draft.code)?;
+        let (mismatch, output) = run_draft(project_path, &harness, &path, &draft).await?;
+        let Some(reason) = mismatch else { break (draft, None, output) };
+        if attempts >= MAX_ATTEMPTS {
+            break (draft, Some(reason), output);
+        }
+        prompt.push_str(&format!(
+            "\n\nThis draft doesn't reproduce the bug because {}:\n{}\n\nOutput:\n{}\n\nWrite a new draft.",
+            reason, draft.code, output
+        ));
+    };
+
+    let full_path = project_path.join(&path);
+    let status = match &mismatch {
+        None => "Confirmed failing".to_string(),
+        Some(reason) => format!("Not confirmed after {} drafts: {}", attempts, reason),
+    };
+    let card = CardInput {
+        title: format!("Bug: {}", bug.lines().next().unwrap_or(bug)),
+        description: format!("{}\n\nReproduction: `{}`\n{}. Expected: {}\n\n```\n{}\n```", bug, path, status, draft.expected_failure, output),
+        labels: vec!["bug".to_string(), "repro".to_string()],
+        due_date: None,
+        links: vec![CardLink::File { path: full_path.to_string_lossy().to_string(), line: None }],
+    };
+    let (project_id, source) = (project_id.to_string(), format!("repro:{}", path));
+    let card = tokio::task::spawn_blocking(move || -> Result<BoardCard> {
+        let column = board::load_board(&project_id)?.columns.into_iter().next().context("The board has no columns")?;
+        board::create_card(&project_id, column.column.id, &card, Some(source))
+    })
+    .await??;
+
+    Ok(Reproduction {
+        path,
+        kind: match harness {
+            Harness::Runner(_) => ReproKind::Test,
+            Harness::Sandbox(_) => ReproKind::Script,
+        },
+        confirmed: mismatch.is_none(),
+        expected_failure: draft.expected_failure,
+        output,
+        attempts,
+        card,
+    })
+}
+
+// Tauri commands
+
+/// Generate a failing reproduction of `bug_description` under `repro/` and a board card for it
+#[tauri::command]
+pub async fn generate_repro(project_id: String, project_path: String, bug_description: String) -> Result<Reproduction, String> {
+    crate::permissions::require_tool("repro").map_err(|e| e.to_string())?;
+    generate(&project_id, Path::new(&project_path), &bug_description).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repro_paths() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        let jest = Harness::Runner(TestFramework::Jest);
+        assert_eq!(repro_path(root, &jest, "Cart total ignores discount codes!"), "repro/cart-total-ignores-discount-codes.test.js");
+        assert_eq!(
+            repro_path(root, &Harness::Runner(TestFramework::PyTest), "Login fails with unicode passwords"),
+            "repro/test_login_fails_with_unicode_passwords.py"
+        );
+        assert_eq!(repro_path(root, &Harness::Sandbox(SandboxLanguage::Python), "???"), "repro/bug.py");
+
+        std::fs::write(root.join("tsconfig.json"), "{}").unwrap();
+        std::fs::create_dir_all(root.join(REPRO_DIR)).unwrap();
+        std::fs::write(root.join("repro/crash-on-empty-list.test.ts"), "").unwrap();
+        assert_eq!(repro_path(root, &jest, "Crash on empty list"), "repro/crash-on-empty-list-2.test.ts");
+    }
+
+    #[test]
+    fn test_failure_must_reach_the_bug() {
+        let expected = "TypeError: cannot read properties of undefined";
+        assert!(failure_mismatch("FAIL cart\nTypeError: Cannot read properties of undefined (reading 'id')", expected).is_none());
+        assert!(failure_mismatch("FAIL cart\nexpect(received).toBe(expected)", expected).is_none());
+        assert!(failure_mismatch("Cannot find module '../src/cart' from 'repro/cart.test.js'", expected).is_some());
+        assert!(failure_mismatch("exit code Some(1)\nNameError: name 'cart' is not defined", expected).is_some());
+    }
+}
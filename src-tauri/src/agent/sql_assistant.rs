@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use regex::Regex;
+
+use crate::db_browser::{self, DbConnectionManager, DbKind, DbQueryResult, DbSchema};
+use crate::llm::{LLMClient, GenerationRequest};
+
+// Large schemas are truncated to keep the prompt focused
+const MAX_SCHEMA_CHARS: usize = 12_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqlSuggestion {
+    pub sql: String,
+    pub explanation: String,
+    pub dialect: String,
+    /// Mutating statements must be confirmed before they are executed
+    pub requires_confirmation: bool,
+}
+
+/// Compact `table(column type, ...)` listing for the prompt
+pub fn describe_schema(schema: &DbSchema) -> String {
+    let mut text = String::new();
+
+    for table in &schema.tables {
+        let name = match &table.schema {
+            Some(s) if s != "public" => format!("{}.{}", s, table.name),
+            _ => table.name.clone(),
+        };
+
+        let columns = table.columns
+            .iter()
+            .map(|c| {
+                let mut col = format!("{} {}", c.name, c.data_type);
+                if c.primary_key {
+                    col.push_str(" PK");
+                }
+                if !c.nullable {
+                    col.push_str(" NOT NULL");
+                }
+                col
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let line = format!("{}({})\n", name, columns);
+        if text.len() + line.len() > MAX_SCHEMA_CHARS {
+            text.push_str("... (more tables omitted)\n");
+            break;
+        }
+        text.push_str(&line);
+    }
+
+    text
+}
+
+fn dialect_name(kind: &DbKind) -> &'static str {
+    match kind {
+        DbKind::Sqlite => "SQLite",
+        DbKind::Postgres => "PostgreSQL",
+        DbKind::MySql => "MySQL",
+    }
+}
+
+pub struct SqlAssistant {
+    llm_client: LLMClient,
+}
+
+impl SqlAssistant {
+    pub fn new() -> Self {
+        Self {
+            llm_client: LLMClient::new(),
+        }
+    }
+
+    async fn load_context(&self, connection_id: &str) -> Result<(String, String)> {
+        let manager = DbConnectionManager::new()?;
+        let profile = manager.get_profile(connection_id)?;
+        let schema = db_browser::get_schema(connection_id).await?;
+
+        Ok((dialect_name(&profile.kind).to_string(), describe_schema(&schema)))
+    }
+
+    /// Turn a natural language request into SQL for the connected database
+    pub async fn generate_sql(&self, connection_id: &str, natural_language: &str) -> Result<SqlSuggestion> {
+        let (dialect, schema) = self.load_context(connection_id).await?;
+
+        let prompt = format!(
+            r#"Write a {dialect} query for this request:
+
+REQUEST:
+{request}
+
+SCHEMA:
+{schema}
+
+Requirements:
+1. Use only the tables and columns in the schema
+2. Use {dialect} syntax
+3. Prefer explicit column lists over SELECT *
+
+Provide response in this format:
+
+SQL:
+```sql
+[query here]
+```
+
+EXPLANATION:
+[What the query does and any assumptions made]"#,
+            dialect = dialect,
+            request = natural_language,
+            schema = schema,
+        );
+
+        let request = GenerationRequest {
+            model: "deepseek-coder-v2:16b".to_string(),
+            prompt,
+            system_prompt: Some(format!("You are an expert {} database engineer. Write correct, efficient SQL.", dialect)),
+            temperature: 0.2,
+            max_tokens: 2048,
+        };
+
+        let response = self.llm_client.generate(request).await?;
+        let sql = extract_sql(&response.text);
+
+        Ok(SqlSuggestion {
+            requires_confirmation: !db_browser::is_read_only_statement(&sql),
+            explanation: extract_explanation(&response.text),
+            sql,
+            dialect,
+        })
+    }
+
+    /// Reverse mode: explain what an existing query does against this schema
+    pub async fn explain_sql(&self, connection_id: &str, sql: &str) -> Result<String> {
+        let (dialect, schema) = self.load_context(connection_id).await?;
+
+        let prompt = format!(
+            r#"Explain this {dialect} query:
+
+```sql
+{sql}
+```
+
+SCHEMA:
+{schema}
+
+Provide:
+1. **Summary** - What data does it read or change?
+2. **Step-by-step** - Joins, filters, grouping and ordering
+3. **Performance** - Missing indexes or expensive operations
+4. **Risks** - Anything that could modify or lock many rows"#,
+            dialect = dialect,
+            sql = sql,
+            schema = schema,
+        );
+
+        let request = GenerationRequest {
+            model: "deepseek-coder-v2:16b".to_string(),
+            prompt,
+            system_prompt: Some("You are a patient database teacher. Explain queries clearly.".to_string()),
+            temperature: 0.5,
+            max_tokens: 2048,
+        };
+
+        let response = self.llm_client.generate(request).await?;
+        Ok(response.text)
+    }
+}
+
+fn extract_sql(text: &str) -> String {
+    let code_block_re = Regex::new(r"```(?:sql)?\s*\n([\s\S]*?)\n```").unwrap();
+    if let Some(captures) = code_block_re.captures(text) {
+        return captures[1].trim().to_string();
+    }
+
+    let section_re = Regex::new(r"SQL:\s*\n([\s\S]*?)(?:\n\s*EXPLANATION:|$)").unwrap();
+    section_re.captures(text)
+        .map(|c| c[1].trim().to_string())
+        .unwrap_or_else(|| text.trim().to_string())
+}
+
+fn extract_explanation(text: &str) -> String {
+    text.split("EXPLANATION:")
+        .nth(1)
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn generate_sql(connection_id: String, natural_language: String) -> Result<SqlSuggestion, String> {
+    let assistant = SqlAssistant::new();
+    assistant.generate_sql(&connection_id, &natural_language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn explain_sql(connection_id: String, sql: String) -> Result<String, String> {
+    let assistant = SqlAssistant::new();
+    assistant.explain_sql(&connection_id, &sql)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run generated SQL. Mutating statements are refused unless `confirmed` is set.
+#[tauri::command]
+pub async fn execute_generated_sql(
+    connection_id: String,
+    sql: String,
+    confirmed: bool,
+) -> Result<DbQueryResult, String> {
+    if db_browser::split_statements(&sql).len() > 1 {
+        return Err("Run one statement at a time".to_string());
+    }
+    let read_only = db_browser::statement_is_read_only(&connection_id, &sql).await.map_err(|e| e.to_string())?;
+    if !read_only && !confirmed {
+        return Err("This statement modifies data and must be confirmed before running".to_string());
+    }
+
+    db_browser::run_query(&connection_id, &sql, 0, 100, uuid::Uuid::new_v4().to_string())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_sql_and_explanation() {
+        let response = "SQL:\n```sql\nSELECT id, email FROM users WHERE active = 1;\n```\n\nEXPLANATION:\nLists active users.";
+        assert_eq!(extract_sql(response), "SELECT id, email FROM users WHERE active = 1;");
+        assert_eq!(extract_explanation(response), "Lists active users.");
+    }
+}
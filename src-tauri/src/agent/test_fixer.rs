@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::path::Path;
+use tauri::Emitter;
+
+use crate::agent::error_explainer::{self, ProposedFix};
+use crate::rename;
+use crate::testing::{TestFailure, TestRunner};
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+/// Failures described to the model per attempt; the rest wait for a later one
+const MAX_FAILURES_PER_ATTEMPT: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptOutcome {
+    /// Every re-run test passed, and so did the rest of the suite
+    Fixed,
+    /// The re-run tests passed but the patch broke others, which the next attempt takes on
+    Regressed,
+    /// Fewer failures than before the patch
+    Improved,
+    NoProgress,
+    /// The model found nothing to change in the shown code
+    NoFixProposed,
+    ApplyFailed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixAttempt {
+    /// 1-based
+    pub attempt: u32,
+    pub failing_tests: Vec<String>,
+    pub diagnosis: String,
+    pub fix: Option<ProposedFix>,
+    pub outcome: AttemptOutcome,
+    pub remaining_failures: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixTestsReport {
+    pub framework: String,
+    pub initial_failures: usize,
+    pub attempts: Vec<FixAttempt>,
+    pub remaining: Vec<TestFailure>,
+    pub all_passing: bool,
+}
+
+/// Failures written out the way a test runner prints them, so file/line
+/// references in the stacks lead the explainer to the relevant source
+fn failure_report(failures: &[TestFailure]) -> String {
+    failures
+        .iter()
+        .map(|failure| {
+            let mut text = format!("FAIL {}", failure.test_name);
+            if let Some(file) = &failure.file {
+                text.push_str(&format!(" ({})", file));
+            }
+            // Jest and mocha stacks already open with the message
+            match &failure.stack_trace {
+                Some(stack) if stack.contains(&failure.error_message) => text.push_str(&format!("\n{}", stack)),
+                Some(stack) => text.push_str(&format!("\n{}\n{}", failure.error_message, stack)),
+                None => text.push_str(&format!("\n{}", failure.error_message)),
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Run the tests, then repeatedly ask for a fix to the failures, apply it and
+/// re-run the failing tests until they pass or `max_attempts` is spent. Once they
+/// pass, the whole suite runs again so a patch that breaks other tests isn't reported as a fix.
+/// Patches that don't help are left in place; the last one can be reverted with `undo_rename`.
+pub async fn fix_tests(
+    project_path: &Path,
+    max_attempts: u32,
+    on_attempt: impl Fn(&FixAttempt),
+) -> Result<FixTestsReport> {
+    let framework = TestRunner::detect_framework(&project_path.to_path_buf())?;
+    let runner = TestRunner::new(framework.clone(), project_path.to_path_buf());
+
+    let initial = runner.run_tests().await?;
+    if initial.failed > 0 && initial.failures.is_empty() {
+        anyhow::bail!("{} tests failed but the {:?} runner reported no failure details", initial.failed, framework);
+    }
+    let initial_failures = initial.failures.len();
+    let mut failures = initial.failures;
+    let mut attempts = Vec::new();
+    if !failures.is_empty() {
+        crate::checkpoints::before_agent_run(project_path, "Fix failing tests");
+    }
+
+    for attempt in 1..=max_attempts {
+        if failures.is_empty() {
+            break;
+        }
+        let batch = &failures[..failures.len().min(MAX_FAILURES_PER_ATTEMPT)];
+        let context_files: Vec<String> = batch.iter().filter_map(|f| f.file.clone()).collect();
+
+        let explanation = error_explainer::explain(&failure_report(batch), &context_files, Some(project_path)).await?;
+        let mut record = FixAttempt {
+            attempt,
+            failing_tests: batch.iter().map(|f| f.test_name.clone()).collect(),
+            diagnosis: explanation.diagnosis,
+            fix: explanation.fix,
+            outcome: AttemptOutcome::NoFixProposed,
+            remaining_failures: failures.len(),
+        };
+
+        let Some(fix) = record.fix.clone() else {
+            on_attempt(&record);
+            attempts.push(record);
+            break;
+        };
+
+        let applied = tokio::task::spawn_blocking(move || rename::apply_file_edits(&fix.files, "agent")).await?;
+        if let Err(e) = applied {
+            tracing::warn!("Failed to apply test fix: {}", e);
+            record.outcome = AttemptOutcome::ApplyFailed;
+            on_attempt(&record);
+            attempts.push(record);
+            break;
+        }
+
+        let rerun = runner.run_failures(&failures).await?;
+        if rerun.failed > 0 && rerun.failures.is_empty() {
+            anyhow::bail!("Re-run reported {} failures but no failure details", rerun.failed);
+        }
+        record.outcome = if rerun.failures.is_empty() {
+            AttemptOutcome::Fixed
+        } else if rerun.failures.len() < failures.len() {
+            AttemptOutcome::Improved
+        } else {
+            AttemptOutcome::NoProgress
+        };
+        failures = rerun.failures;
+
+        if failures.is_empty() {
+            let full = runner.run_tests().await?;
+            if full.failed > 0 && full.failures.is_empty() {
+                anyhow::bail!("{} tests failed but the {:?} runner reported no failure details", full.failed, framework);
+            }
+            if !full.failures.is_empty() {
+                record.outcome = AttemptOutcome::Regressed;
+            }
+            failures = full.failures;
+        }
+        record.remaining_failures = failures.len();
+
+        on_attempt(&record);
+        attempts.push(record);
+    }
+
+    Ok(FixTestsReport {
+        framework: format!("{:?}", framework),
+        initial_failures,
+        attempts,
+        all_passing: failures.is_empty(),
+        remaining: failures,
+    })
+}
+
+// Tauri commands
+
+/// Try to make the project's failing tests pass. Emits `fix-tests-progress` after each attempt.
+#[tauri::command]
+pub async fn fix_failing_tests(
+    app: tauri::AppHandle,
+    project_path: String,
+    max_attempts: Option<u32>,
+) -> Result<FixTestsReport, String> {
+    crate::permissions::require_tool("test_fixer").map_err(|e| e.to_string())?;
+    fix_tests(Path::new(&project_path), max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS), |attempt| {
+        app.emit("fix-tests-progress", attempt).ok();
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use reqwest::Url;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::agent::injection;
+use crate::preferences::{PreferencesManager, WebFetchPreferences};
+
+lazy_static::lazy_static! {
+    static ref TITLE: Regex = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap();
+    static ref ARTICLE: Regex = Regex::new(r"(?is)<article\b[^>]*>(.*)</article>").unwrap();
+    static ref MAIN: Regex = Regex::new(r"(?is)<main\b[^>]*>(.*)</main>").unwrap();
+    static ref BLOCK_TAG: Regex = Regex::new(
+        r"(?i)</?(p|div|section|h[1-6]|li|ul|ol|pre|blockquote|table|tr|dt|dd)\b[^>]*>|<br\s*/?>"
+    ).unwrap();
+    static ref ANY_TAG: Regex = Regex::new(r"<[^>]+>").unwrap();
+    static ref SPACES: Regex = Regex::new(r"[ \t]+").unwrap();
+    static ref BLANK_LINES: Regex = Regex::new(r"\n\s*\n\s*\n+").unwrap();
+}
+
+/// Pages fetched recently, keyed by the requested URL
+static CACHE: Mutex<Option<HashMap<String, (Instant, FetchedPage)>>> = Mutex::new(None);
+
+/// Elements whose content is dropped entirely, not just their tags
+const BOILERPLATE_TAGS: &[&str] = &[
+    "script", "style", "noscript", "nav", "header", "footer", "aside", "form", "svg", "iframe", "template", "button",
+];
+/// Kept through sanitizing so their boundaries can become line breaks
+const STRUCTURE_TAGS: &[&str] = &[
+    "p", "div", "section", "h1", "h2", "h3", "h4", "h5", "h6", "li", "ul", "ol", "pre", "code", "blockquote",
+    "table", "tr", "td", "th", "dt", "dd", "br",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchedPage {
+    pub url: String,
+    /// Where redirects ended up
+    pub final_url: String,
+    pub title: Option<String>,
+    pub content_type: String,
+    /// Readable text, screened for prompt injection
+    pub text: String,
+    pub truncated: bool,
+    /// Withheld by the prompt-injection screen
+    pub quarantined: bool,
+    pub from_cache: bool,
+}
+
+pub fn domain_allowed(url: &Url, allowed_domains: &[String]) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_end_matches('.').to_lowercase();
+    allowed_domains.iter().any(|domain| {
+        let domain = domain.trim().trim_start_matches("*.").to_lowercase();
+        !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+    })
+}
+
+/// Loopback, private, link-local and similar ranges a fetched page must never reach:
+/// the local machine, the LAN or a cloud metadata endpoint
+pub fn is_internal_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_internal_address(IpAddr::V4(v4)),
+            None => {
+                let first = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    // Unique local fc00::/7 and link-local fe80::/10
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
+
+/// Why `url` may not be requested, checked for the first request and every redirect
+fn blocked_reason(url: &Url, allowed_domains: &[String]) -> Option<String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Some("Only http and https URLs can be fetched".to_string());
+    }
+    if !domain_allowed(url, allowed_domains) {
+        return Some(format!("{} is not in the allowed domains for web fetch", url.host_str().unwrap_or(url.as_str())));
+    }
+    let internal = match url.host() {
+        Some(url::Host::Ipv4(ip)) => is_internal_address(IpAddr::V4(ip)),
+        Some(url::Host::Ipv6(ip)) => is_internal_address(IpAddr::V6(ip)),
+        Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost") || domain.to_lowercase().ends_with(".localhost"),
+        None => true,
+    };
+    internal.then(|| format!("{} is a local or private address", url.host_str().unwrap_or(url.as_str())))
+}
+
+/// Resolves only to public addresses, so an allowed name pointing into the LAN
+/// (or rebinding to it) can't be used to reach internal services
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| !is_internal_address(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} only resolves to local or private addresses", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&#160;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Main text of an HTML page: the `<article>` or `<main>` element when there is
+/// one, with scripts, navigation and other boilerplate removed
+pub fn readable_text(html: &str) -> (Option<String>, String) {
+    let title = TITLE
+        .captures(html)
+        .map(|c| decode_entities(ANY_TAG.replace_all(&c[1], "").trim()))
+        .filter(|t| !t.is_empty());
+
+    let body = ARTICLE
+        .captures(html)
+        .or_else(|| MAIN.captures(html))
+        .map(|c| c.get(1).map(|m| m.as_str()).unwrap_or_default())
+        .unwrap_or(html);
+
+    let cleaned = ammonia::Builder::empty()
+        .tags(STRUCTURE_TAGS.iter().copied().collect::<HashSet<_>>())
+        .clean_content_tags(BOILERPLATE_TAGS.iter().copied().collect::<HashSet<_>>())
+        .clean(body)
+        .to_string();
+
+    let text = BLOCK_TAG.replace_all(&cleaned, "\n");
+    let text = decode_entities(&ANY_TAG.replace_all(&text, ""));
+    let text = SPACES.replace_all(&text, " ");
+    let text = text.lines().map(str::trim).collect::<Vec<_>>().join("\n");
+    let text = BLANK_LINES.replace_all(&text, "\n\n").trim().to_string();
+
+    (title, text)
+}
+
+fn truncate(text: String, max_chars: usize) -> (String, bool) {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => (format!("{}\n\n[truncated]", &text[..cut]), true),
+        None => (text, false),
+    }
+}
+
+fn cached(url: &str, ttl: Duration) -> Option<FetchedPage> {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let (fetched_at, page) = cache.as_ref()?.get(url)?;
+    (fetched_at.elapsed() < ttl).then(|| FetchedPage { from_cache: true, ..page.clone() })
+}
+
+/// Download `url` within the preferences' domain allowlist and size limits and
+/// return its readable text, screened before it can reach a prompt
+pub async fn fetch(url: &str, settings: &WebFetchPreferences) -> Result<FetchedPage> {
+    let parsed = Url::parse(url).with_context(|| format!("Invalid URL: {}", url))?;
+    if let Some(reason) = blocked_reason(&parsed, &settings.allowed_domains) {
+        anyhow::bail!(reason);
+    }
+
+    let ttl = Duration::from_secs(settings.cache_minutes * 60);
+    if let Some(page) = cached(url, ttl) {
+        return Ok(page);
+    }
+
+    // Every redirect hop is checked before it is followed, not just where it ends up
+    let allowed_domains = settings.allowed_domains.clone();
+    let redirects = reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= 10 {
+            return attempt.error("Too many redirects");
+        }
+        match blocked_reason(attempt.url(), &allowed_domains) {
+            Some(reason) => attempt.error(format!("Redirect refused: {}", reason)),
+            None => attempt.follow(),
+        }
+    });
+    let client = crate::network::client_builder()
+        .timeout(Duration::from_secs(settings.timeout_secs))
+        .redirect(redirects)
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()?;
+    let mut response = client
+        .get(parsed)
+        .header("User-Agent", "SAI-IDE")
+        .send()
+        .await?
+        .error_for_status()?;
+    if response.content_length().is_some_and(|len| len as usize > settings.max_bytes) {
+        anyhow::bail!("Response is larger than the {} byte limit", settings.max_bytes);
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("text/plain")
+        .to_lowercase();
+    if !(content_type.starts_with("text/") || content_type.contains("json") || content_type.contains("xml")) {
+        anyhow::bail!("Unsupported content type {}", content_type);
+    }
+
+    let mut body = Vec::new();
+    let mut cut_off = false;
+    while let Some(chunk) = response.chunk().await? {
+        let room = settings.max_bytes - body.len();
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            cut_off = true;
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let raw = String::from_utf8_lossy(&body).to_string();
+
+    let (title, text) = if content_type.contains("html") {
+        readable_text(&raw)
+    } else {
+        (None, raw)
+    };
+    let (text, truncated) = truncate(text, settings.max_chars);
+    let screened = injection::guard(&text, final_url.as_str()).await;
+
+    let page = FetchedPage {
+        url: url.to_string(),
+        final_url: final_url.to_string(),
+        title,
+        content_type,
+        text: screened.text,
+        truncated: truncated || cut_off,
+        quarantined: screened.quarantined,
+        from_cache: false,
+    };
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.get_or_insert_with(HashMap::new).insert(url.to_string(), (Instant::now(), page.clone()));
+
+    Ok(page)
+}
+
+// Tauri commands
+
+/// Fetch a documentation page for the agent
+#[tauri::command]
+pub async fn fetch_url(url: String) -> Result<FetchedPage, String> {
+    crate::permissions::require_tool("web_fetch").map_err(|e| e.to_string())?;
+    let settings = PreferencesManager::new()
+        .and_then(|manager| manager.load())
+        .map_err(|e| e.to_string())?
+        .web_fetch;
+    fetch(&url, &settings).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_fetch_cache() -> Result<(), String> {
+    *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readable_text_and_allowlist() {
+        let html = r#"<html><head><title>Vec &amp; slices</title><script>track()</script></head>
+<body><nav><a href="/">Home</a> | <a href="/std">std</a></nav>
+<main><h1>Vec</h1><p>A contiguous <b>growable</b> array type.</p>
+<pre><code>let v = vec![1, 2];</code></pre><footer>Copyright</footer></main></body></html>"#;
+        let (title, text) = readable_text(html);
+        assert_eq!(title.as_deref(), Some("Vec & slices"));
+        assert_eq!(text, "Vec\n\nA contiguous growable array type.\n\nlet v = vec![1, 2];");
+
+        let allowed = vec!["docs.rs".to_string(), "*.python.org".to_string()];
+        assert!(domain_allowed(&Url::parse("https://docs.rs/serde").unwrap(), &allowed));
+        assert!(domain_allowed(&Url::parse("https://docs.python.org/3/").unwrap(), &allowed));
+        assert!(!domain_allowed(&Url::parse("https://evildocs.rs/").unwrap(), &allowed));
+        assert!(!domain_allowed(&Url::parse("https://docs.rs.evil.com/").unwrap(), &allowed));
+
+        let internal = vec!["127.0.0.1".to_string(), "169.254.169.254".to_string(), "[::1]".to_string()];
+        assert!(blocked_reason(&Url::parse("http://169.254.169.254/latest/meta-data").unwrap(), &internal).is_some());
+        assert!(blocked_reason(&Url::parse("http://127.0.0.1:8080/").unwrap(), &internal).is_some());
+        assert!(blocked_reason(&Url::parse("http://[::ffff:10.0.0.1]/").unwrap(), &["[::ffff:a00:1]".to_string()]).is_some());
+        assert!(blocked_reason(&Url::parse("https://docs.rs/serde").unwrap(), &allowed).is_none());
+        assert!(blocked_reason(&Url::parse("file:///etc/passwd").unwrap(), &allowed).is_some());
+    }
+}
@@ -0,0 +1,669 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::database::{self, Bookmark, DatabaseManager, Note};
+use crate::secrets;
+
+/// How long to wait for another writer on a shared database file
+const BUSY_TIMEOUT_MS: u64 = 5_000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Bookmark,
+    Note,
+}
+
+/// An annotation as the team sees it. File paths in `data` are relative to the
+/// repository root so they resolve on every machine.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SharedEntity {
+    pub uid: String,
+    pub kind: EntityKind,
+    pub data: serde_json::Value,
+    /// Bumped by the backend on every accepted change
+    pub revision: i64,
+    pub updated_at: String,
+    pub updated_by: String,
+    /// Deletions are kept so other machines remove their copy too
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+/// A change, accepted only if the backend still has `base_revision`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushChange {
+    pub entity: SharedEntity,
+    pub base_revision: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum PushOutcome {
+    Accepted { uid: String, revision: i64 },
+    /// Someone else changed it first; `current` is their version
+    Conflict { current: SharedEntity },
+}
+
+/// Where annotations are shared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SyncBackend {
+    /// `GET {url}/spaces/{space}/annotations` returns every entity;
+    /// `POST` the same path with `{"changes": [...]}` returns one outcome per change.
+    /// The bearer token lives in the OS credential store.
+    Rest { url: String },
+    /// A SQLite file on a network share or kept in step by litestream-style replication
+    SharedSqlite { path: String },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// The most recently edited version wins
+    LastWriteWins,
+    PreferLocal,
+    PreferRemote,
+    /// Leave both until `resolve_annotation_conflict`
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConfig {
+    pub backend: SyncBackend,
+    /// Name the team shares for this repository, e.g. its origin URL
+    pub space: String,
+    /// Shown to others as who changed an annotation
+    pub user: String,
+    pub policy: ConflictPolicy,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    Synced,
+    /// Changed locally, not accepted by the backend yet
+    Pending,
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedEntity {
+    pub uid: String,
+    pub kind: EntityKind,
+    /// Row id in the local database
+    pub local_id: i64,
+    /// The backend revision the local copy is based on; 0 before the first push
+    pub revision: i64,
+    /// Hash of the data as last synced, to notice local edits
+    pub hash: String,
+    pub status: SyncStatus,
+    /// The other side of a conflict
+    #[serde(default)]
+    pub remote: Option<SharedEntity>,
+}
+
+/// Per project, in the app data folder: sync settings and what each annotation maps to
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SyncState {
+    pub config: Option<SyncConfig>,
+    pub entities: Vec<TrackedEntity>,
+    pub last_sync: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SyncReport {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub conflicts: usize,
+    pub errors: Vec<String>,
+}
+
+/// What to do with one annotation
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncAction {
+    Nothing,
+    Push,
+    Pull,
+    Conflict,
+}
+
+/// Decide from whether each side changed since the last sync
+pub fn decide(local_changed: bool, remote_changed: bool, policy: ConflictPolicy, local_newer: bool) -> SyncAction {
+    match (local_changed, remote_changed) {
+        (false, false) => SyncAction::Nothing,
+        (true, false) => SyncAction::Push,
+        (false, true) => SyncAction::Pull,
+        (true, true) => match policy {
+            ConflictPolicy::PreferLocal => SyncAction::Push,
+            ConflictPolicy::PreferRemote => SyncAction::Pull,
+            ConflictPolicy::LastWriteWins if local_newer => SyncAction::Push,
+            ConflictPolicy::LastWriteWins => SyncAction::Pull,
+            ConflictPolicy::Manual => SyncAction::Conflict,
+        },
+    }
+}
+
+fn hash(data: &serde_json::Value) -> String {
+    blake3::hash(data.to_string().as_bytes()).to_hex().to_string()
+}
+
+fn secret_key(project_id: &str) -> String {
+    format!("annotation-sync:{}", project_id)
+}
+
+fn state_path(project_id: &str) -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .context("Failed to get data directory")?
+        .join(".sai-ide")
+        .join("annotation_sync");
+    std::fs::create_dir_all(&dir)?;
+    // Project ids can be paths
+    Ok(dir.join(format!("{}.json", &blake3::hash(project_id.as_bytes()).to_hex()[..16])))
+}
+
+pub fn load_state(project_id: &str) -> Result<SyncState> {
+    let path = state_path(project_id)?;
+    if !path.exists() {
+        return Ok(SyncState::default());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(&path)?)?)
+}
+
+fn save_state(project_id: &str, state: &SyncState) -> Result<()> {
+    std::fs::write(state_path(project_id)?, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+fn relative(path: &str, project_path: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(project_path)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn absolute(path: &str, project_path: &Path) -> String {
+    project_path.join(path).to_string_lossy().to_string()
+}
+
+/// A local annotation in shared form, with when it was last edited
+struct LocalEntity {
+    data: serde_json::Value,
+    modified_at: String,
+}
+
+fn local_entities(db: &DatabaseManager, project_id: &str, project_path: &Path) -> Result<HashMap<(EntityKind, i64), LocalEntity>> {
+    let mut entities = HashMap::new();
+    for bookmark in db.get_bookmarks(Some(project_id))? {
+        let data = serde_json::json!({
+            "file": relative(&bookmark.file_path, project_path),
+            "line": bookmark.line_number,
+            "description": bookmark.description,
+            "tags": serde_json::from_str::<serde_json::Value>(&bookmark.tags).unwrap_or_default(),
+            "anchor": bookmark.anchor,
+        });
+        entities.insert((EntityKind::Bookmark, bookmark.id), LocalEntity { data, modified_at: bookmark.created_at });
+    }
+    for note in db.list_notes(project_id, None)? {
+        let data = serde_json::json!({
+            "file": note.file_path.as_deref().map(|f| relative(f, project_path)),
+            "start_line": note.start_line,
+            "end_line": note.end_line,
+            "title": note.title,
+            "content": note.content,
+            "tags": serde_json::from_str::<serde_json::Value>(&note.tags).unwrap_or_default(),
+        });
+        entities.insert((EntityKind::Note, note.id), LocalEntity { data, modified_at: note.updated_at });
+    }
+    Ok(entities)
+}
+
+fn text(data: &serde_json::Value, key: &str) -> String {
+    data[key].as_str().unwrap_or_default().to_string()
+}
+
+fn line(data: &serde_json::Value, key: &str) -> Option<i32> {
+    data[key].as_i64().map(|n| n as i32)
+}
+
+/// Write a shared annotation to the local database; returns its row id (None once deleted)
+fn apply_local(
+    db: &DatabaseManager,
+    project_id: &str,
+    project_path: &Path,
+    local_id: Option<i64>,
+    entity: &SharedEntity,
+) -> Result<Option<i64>> {
+    let data = &entity.data;
+    match (entity.kind, entity.deleted) {
+        (EntityKind::Bookmark, true) => {
+            if let Some(id) = local_id {
+                db.delete_bookmark(id)?;
+            }
+            Ok(None)
+        }
+        (EntityKind::Note, true) => {
+            if let Some(id) = local_id {
+                db.delete_note(id)?;
+            }
+            Ok(None)
+        }
+        (EntityKind::Bookmark, false) => {
+            let bookmark = Bookmark {
+                id: local_id.unwrap_or(0),
+                project_id: project_id.to_string(),
+                file_path: absolute(&text(data, "file"), project_path),
+                line_number: line(data, "line"),
+                description: text(data, "description"),
+                tags: data["tags"].to_string(),
+                created_at: entity.updated_at.clone(),
+                anchor: data["anchor"].as_str().map(String::from),
+                orphaned: false,
+            };
+            match local_id {
+                Some(_) => db.update_bookmark(&bookmark).map(|_| Some(bookmark.id)),
+                None => db.add_bookmark(&bookmark).map(Some),
+            }
+        }
+        (EntityKind::Note, false) => {
+            let note = Note {
+                id: local_id.unwrap_or(0),
+                project_id: project_id.to_string(),
+                file_path: data["file"].as_str().map(|f| absolute(f, project_path)),
+                start_line: line(data, "start_line"),
+                end_line: line(data, "end_line"),
+                title: text(data, "title"),
+                content: text(data, "content"),
+                tags: data["tags"].to_string(),
+                created_at: entity.updated_at.clone(),
+                updated_at: entity.updated_at.clone(),
+            };
+            match local_id {
+                Some(_) => db.update_note(&note).map(|_| Some(note.id)),
+                None => db.add_note(&note).map(Some),
+            }
+        }
+    }
+}
+
+impl SyncBackend {
+    pub async fn pull(&self, space: &str, token: Option<&str>) -> Result<Vec<SharedEntity>> {
+        match self {
+            SyncBackend::Rest { url } => {
+                let mut request = crate::network::client().get(rest_url(url, space)?);
+                if let Some(token) = token {
+                    request = request.bearer_auth(token);
+                }
+                let response = request.send().await?.error_for_status()?;
+                Ok(response.json().await?)
+            }
+            SyncBackend::SharedSqlite { path } => {
+                let (path, space) = (PathBuf::from(path), space.to_string());
+                tokio::task::spawn_blocking(move || sqlite_pull(&path, &space)).await?
+            }
+        }
+    }
+
+    pub async fn push(&self, space: &str, token: Option<&str>, changes: Vec<PushChange>) -> Result<Vec<PushOutcome>> {
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
+        match self {
+            SyncBackend::Rest { url } => {
+                let mut request = crate::network::client()
+                    .post(rest_url(url, space)?)
+                    .json(&serde_json::json!({ "changes": changes }));
+                if let Some(token) = token {
+                    request = request.bearer_auth(token);
+                }
+                let response = request.send().await?.error_for_status()?;
+                Ok(response.json().await?)
+            }
+            SyncBackend::SharedSqlite { path } => {
+                let (path, space) = (PathBuf::from(path), space.to_string());
+                tokio::task::spawn_blocking(move || sqlite_push(&path, &space, &changes)).await?
+            }
+        }
+    }
+}
+
+fn rest_url(base: &str, space: &str) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(base).context("Invalid sync server URL")?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!("Invalid sync server URL"))?
+        .pop_if_empty()
+        .extend(["spaces", space, "annotations"]);
+    Ok(url)
+}
+
+fn open_shared(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path).with_context(|| format!("Failed to open shared database {:?}", path))?;
+    conn.busy_timeout(std::time::Duration::from_millis(BUSY_TIMEOUT_MS))?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS annotations (
+            space TEXT NOT NULL,
+            uid TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            data TEXT NOT NULL,
+            revision INTEGER NOT NULL,
+            updated_at TEXT NOT NULL,
+            updated_by TEXT NOT NULL,
+            deleted INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (space, uid)
+        )",
+        [],
+    )?;
+    Ok(conn)
+}
+
+fn entity_from_row(row: &rusqlite::Row) -> rusqlite::Result<(String, String, String, i64, String, String, bool)> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?))
+}
+
+fn to_entity(row: (String, String, String, i64, String, String, bool)) -> Result<SharedEntity> {
+    let (uid, kind, data, revision, updated_at, updated_by, deleted) = row;
+    Ok(SharedEntity {
+        uid,
+        kind: serde_json::from_value(serde_json::Value::String(kind))?,
+        data: serde_json::from_str(&data)?,
+        revision,
+        updated_at,
+        updated_by,
+        deleted,
+    })
+}
+
+const SELECT_ENTITY: &str = "SELECT uid, kind, data, revision, updated_at, updated_by, deleted FROM annotations";
+
+fn sqlite_pull(path: &Path, space: &str) -> Result<Vec<SharedEntity>> {
+    let conn = open_shared(path)?;
+    let mut stmt = conn.prepare(&format!("{} WHERE space = ?1", SELECT_ENTITY))?;
+    let rows = stmt.query_map(params![space], entity_from_row)?.collect::<rusqlite::Result<Vec<_>>>()?;
+    rows.into_iter().map(to_entity).collect()
+}
+
+fn sqlite_push(path: &Path, space: &str, changes: &[PushChange]) -> Result<Vec<PushOutcome>> {
+    let mut conn = open_shared(path)?;
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+    let mut outcomes = Vec::new();
+    for change in changes {
+        let current = tx
+            .query_row(&format!("{} WHERE space = ?1 AND uid = ?2", SELECT_ENTITY), params![space, change.entity.uid], entity_from_row)
+            .optional()?
+            .map(to_entity)
+            .transpose()?;
+        if let Some(current) = current.filter(|c| c.revision != change.base_revision) {
+            outcomes.push(PushOutcome::Conflict { current });
+            continue;
+        }
+        let revision = change.base_revision + 1;
+        let entity = &change.entity;
+        tx.execute(
+            "INSERT OR REPLACE INTO annotations (space, uid, kind, data, revision, updated_at, updated_by, deleted)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                space,
+                entity.uid,
+                serde_json::to_value(entity.kind)?.as_str().unwrap_or_default(),
+                entity.data.to_string(),
+                revision,
+                entity.updated_at,
+                entity.updated_by,
+                entity.deleted,
+            ],
+        )?;
+        outcomes.push(PushOutcome::Accepted { uid: entity.uid.clone(), revision });
+    }
+    tx.commit()?;
+    Ok(outcomes)
+}
+
+/// Exchange annotations with the backend, applying `policy` where both sides changed
+pub async fn sync(project_id: &str, project_path: &Path) -> Result<SyncReport> {
+    let mut state = load_state(project_id)?;
+    let config = state.config.clone().context("Annotation sync isn't set up for this project")?;
+    let token = match config.backend {
+        SyncBackend::Rest { .. } => secrets::get_secret(&secret_key(project_id))?,
+        SyncBackend::SharedSqlite { .. } => None,
+    };
+    let db = database::open()?;
+    let mut local = local_entities(&db, project_id, project_path)?;
+    let remote: HashMap<String, SharedEntity> = config
+        .backend
+        .pull(&config.space, token.as_deref())
+        .await?
+        .into_iter()
+        .map(|e| (e.uid.clone(), e))
+        .collect();
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut report = SyncReport::default();
+    let mut changes = Vec::new();
+
+    let shared = |uid: &str, kind: EntityKind, data: serde_json::Value, deleted: bool| SharedEntity {
+        uid: uid.to_string(),
+        kind,
+        data,
+        revision: 0,
+        updated_at: now.clone(),
+        updated_by: config.user.clone(),
+        deleted,
+    };
+
+    let mut tracked = Vec::new();
+    for mut entity in std::mem::take(&mut state.entities) {
+        let current = local.remove(&(entity.kind, entity.local_id));
+        let local_changed = current.as_ref().map_or(true, |c| hash(&c.data) != entity.hash);
+        let theirs = remote.get(&entity.uid);
+        let remote_changed = theirs.is_some_and(|t| t.revision > entity.revision);
+        let local_newer = match (&current, theirs) {
+            (Some(current), Some(theirs)) => current.modified_at > theirs.updated_at,
+            _ => true,
+        };
+
+        match decide(local_changed, remote_changed, config.policy, local_newer) {
+            SyncAction::Nothing => entity.status = SyncStatus::Synced,
+            SyncAction::Push => {
+                let base_revision = theirs.map_or(entity.revision, |t| t.revision);
+                let (data, deleted) = match current {
+                    Some(current) => (current.data, false),
+                    None => (serde_json::Value::Null, true),
+                };
+                changes.push(PushChange { entity: shared(&entity.uid, entity.kind, data, deleted), base_revision });
+                entity.status = SyncStatus::Pending;
+            }
+            SyncAction::Pull => {
+                let theirs = theirs.context("Remote annotation missing")?;
+                let local_id = current.is_some().then_some(entity.local_id);
+                report.pulled += 1;
+                match apply_local(&db, project_id, project_path, local_id, theirs)? {
+                    Some(id) => {
+                        entity.local_id = id;
+                        entity.revision = theirs.revision;
+                        entity.hash = hash(&theirs.data);
+                        entity.status = SyncStatus::Synced;
+                    }
+                    // Deleted on the other side; nothing left to track
+                    None => continue,
+                }
+            }
+            SyncAction::Conflict => {
+                entity.status = SyncStatus::Conflict;
+                entity.remote = theirs.cloned();
+                report.conflicts += 1;
+            }
+        }
+        tracked.push(entity);
+    }
+
+    // New on this machine
+    for ((kind, local_id), entity) in local {
+        let uid = uuid::Uuid::new_v4().to_string();
+        tracked.push(TrackedEntity {
+            uid: uid.clone(),
+            kind,
+            local_id,
+            revision: 0,
+            hash: String::new(),
+            status: SyncStatus::Pending,
+            remote: None,
+        });
+        changes.push(PushChange { entity: shared(&uid, kind, entity.data, false), base_revision: 0 });
+    }
+
+    // New from the team
+    let known: HashSet<String> = tracked.iter().map(|e| e.uid.clone()).collect();
+    for theirs in remote.values().filter(|t| !t.deleted && !known.contains(&t.uid)) {
+        if let Some(local_id) = apply_local(&db, project_id, project_path, None, theirs)? {
+            tracked.push(TrackedEntity {
+                uid: theirs.uid.clone(),
+                kind: theirs.kind,
+                local_id,
+                revision: theirs.revision,
+                hash: hash(&theirs.data),
+                status: SyncStatus::Synced,
+                remote: None,
+            });
+            report.pulled += 1;
+        }
+    }
+
+    let pushed_data: HashMap<String, serde_json::Value> =
+        changes.iter().map(|c| (c.entity.uid.clone(), c.entity.data.clone())).collect();
+    match config.backend.push(&config.space, token.as_deref(), changes).await {
+        Ok(outcomes) => {
+            for outcome in outcomes {
+                match outcome {
+                    PushOutcome::Accepted { uid, revision } => {
+                        if let Some(entity) = tracked.iter_mut().find(|e| e.uid == uid) {
+                            entity.revision = revision;
+                            entity.hash = pushed_data.get(&uid).map(hash).unwrap_or_default();
+                            entity.status = SyncStatus::Synced;
+                        }
+                        // Accepted deletions are tracked no longer
+                        if pushed_data.get(&uid).is_some_and(|d| d.is_null()) {
+                            tracked.retain(|e| e.uid != uid);
+                        }
+                        report.pushed += 1;
+                    }
+                    PushOutcome::Conflict { current } => {
+                        if let Some(entity) = tracked.iter_mut().find(|e| e.uid == current.uid) {
+                            entity.status = SyncStatus::Conflict;
+                            entity.remote = Some(current);
+                            report.conflicts += 1;
+                        }
+                    }
+                }
+            }
+        }
+        // Pending entities are pushed again next time
+        Err(e) => report.errors.push(format!("Push failed: {}", e)),
+    }
+
+    state.entities = tracked;
+    state.last_sync = Some(now.clone());
+    save_state(project_id, &state)?;
+    Ok(report)
+}
+
+/// Settle a conflict: keep the local version (pushed on the next sync) or take the remote one
+pub fn resolve(project_id: &str, project_path: &Path, uid: &str, keep_local: bool) -> Result<()> {
+    let mut state = load_state(project_id)?;
+    let entity = state
+        .entities
+        .iter_mut()
+        .find(|e| e.uid == uid && e.status == SyncStatus::Conflict)
+        .context("No conflict for this annotation")?;
+    let theirs = entity.remote.take().context("No conflict for this annotation")?;
+    if keep_local {
+        // Based on their revision, and marked changed so the next sync pushes it
+        entity.revision = theirs.revision;
+        entity.hash = String::new();
+        entity.status = SyncStatus::Pending;
+    } else {
+        let db = database::open()?;
+        match apply_local(&db, project_id, project_path, Some(entity.local_id), &theirs)? {
+            Some(id) => {
+                entity.local_id = id;
+                entity.revision = theirs.revision;
+                entity.hash = hash(&theirs.data);
+                entity.status = SyncStatus::Synced;
+            }
+            None => state.entities.retain(|e| e.uid != uid),
+        }
+    }
+    save_state(project_id, &state)
+}
+
+// Tauri commands
+
+/// Turn sharing on with `config`, or off with None. A REST `token` is kept in the
+/// OS credential store.
+#[tauri::command]
+pub async fn configure_annotation_sync(project_id: String, config: Option<SyncConfig>, token: Option<String>) -> Result<(), String> {
+    let mut state = load_state(&project_id).map_err(|e| e.to_string())?;
+    match &token {
+        Some(token) => secrets::store_secret(&secret_key(&project_id), token),
+        None if config.is_none() => secrets::delete_secret(&secret_key(&project_id)),
+        None => Ok(()),
+    }
+    .map_err(|e| e.to_string())?;
+    state.config = config;
+    save_state(&project_id, &state).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn sync_annotations(project_id: String, project_path: String) -> Result<SyncReport, String> {
+    sync(&project_id, Path::new(&project_path)).await.map_err(|e| e.to_string())
+}
+
+/// Sync settings and each shared annotation's status
+#[tauri::command]
+pub async fn get_annotation_sync_status(project_id: String) -> Result<SyncState, String> {
+    load_state(&project_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn resolve_annotation_conflict(project_id: String, project_path: String, uid: String, keep_local: bool) -> Result<(), String> {
+    resolve(&project_id, Path::new(&project_path), &uid, keep_local).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decisions_and_shared_sqlite() {
+        assert_eq!(decide(false, false, ConflictPolicy::Manual, true), SyncAction::Nothing);
+        assert_eq!(decide(true, false, ConflictPolicy::PreferRemote, false), SyncAction::Push);
+        assert_eq!(decide(false, true, ConflictPolicy::PreferLocal, true), SyncAction::Pull);
+        assert_eq!(decide(true, true, ConflictPolicy::Manual, true), SyncAction::Conflict);
+        assert_eq!(decide(true, true, ConflictPolicy::LastWriteWins, false), SyncAction::Pull);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shared.db");
+        let entity = SharedEntity {
+            uid: "b1".to_string(),
+            kind: EntityKind::Bookmark,
+            data: serde_json::json!({ "file": "src/main.rs", "line": 10, "description": "entry point" }),
+            revision: 0,
+            updated_at: "2026-10-15T09:00:00Z".to_string(),
+            updated_by: "ada".to_string(),
+            deleted: false,
+        };
+        let outcomes = sqlite_push(&path, "repo", &[PushChange { entity: entity.clone(), base_revision: 0 }]).unwrap();
+        assert!(matches!(&outcomes[0], PushOutcome::Accepted { revision: 1, .. }));
+
+        // A second writer still on revision 0 loses
+        let stale = PushChange { entity: SharedEntity { updated_by: "grace".to_string(), ..entity.clone() }, base_revision: 0 };
+        let outcomes = sqlite_push(&path, "repo", &[stale]).unwrap();
+        assert!(matches!(&outcomes[0], PushOutcome::Conflict { current } if current.updated_by == "ada"));
+
+        let pulled = sqlite_pull(&path, "repo").unwrap();
+        assert_eq!(pulled.len(), 1);
+        assert_eq!(pulled[0].revision, 1);
+        assert!(sqlite_pull(&path, "other").unwrap().is_empty());
+    }
+}
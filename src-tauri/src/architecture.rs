@@ -0,0 +1,406 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use crate::agent::pipeline::{GenerationRecord, NodeType, ProjectPlan};
+use crate::dead_code::{module_graph, GraphModule};
+use crate::readonly::WriteError;
+
+/// Above this many files the flowchart shows directories instead of files
+const MAX_FILE_NODES: usize = 60;
+const MAX_CLASS_MEMBERS: usize = 12;
+
+lazy_static::lazy_static! {
+    static ref HEADER: Regex = Regex::new(r"^(flowchart|graph)\s+(TD|TB|BT|LR|RL)$|^classDiagram$").unwrap();
+    static ref FLOW_NODE: Regex = Regex::new(r#"^(\w+)\["[^"]*"\]$"#).unwrap();
+    static ref FLOW_EDGE: Regex = Regex::new(r"^(\w+)\s*(?:-->|-\.->|==>)(?:\|[^|]*\|)?\s*(\w+)$").unwrap();
+    static ref SUBGRAPH: Regex = Regex::new(r#"^subgraph\s+(\w+)\["[^"]*"\]$"#).unwrap();
+    static ref CLASS_OPEN: Regex = Regex::new(r#"^class\s+(\w+)\["[^"]*"\]\s*\{$"#).unwrap();
+    static ref CLASS_EDGE: Regex = Regex::new(r"^(\w+)\s*(?:\.\.>|-->|\*--|o--|<\|--)\s*(\w+)(?:\s*:\s*[\w ]+)?$").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagramSource {
+    /// Built from the import graph of existing code
+    Imports,
+    /// Built from a project plan before any code exists
+    Plan,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchitectureDiagram {
+    pub source: DiagramSource,
+    pub flowchart: String,
+    pub class_diagram: String,
+    /// Markdown file the diagrams were written to, relative to the project
+    pub saved_to: Option<String>,
+}
+
+/// Mermaid label text; quotes would end the label early
+fn label(text: &str) -> String {
+    format!("[\"{}\"]", text.replace('"', "#quot;"))
+}
+
+/// Class member text without characters Mermaid treats as syntax
+fn member(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | ' ' | '$'))
+        .collect()
+}
+
+fn directory_of(file: &str) -> &str {
+    file.rsplit_once('/').map(|(dir, _)| dir).unwrap_or(".")
+}
+
+fn file_name(file: &str) -> &str {
+    file.rsplit('/').next().unwrap_or(file)
+}
+
+/// Flowchart of the import graph, grouped by directory
+pub fn imports_flowchart(modules: &[GraphModule]) -> String {
+    let mut out = String::from("flowchart LR\n");
+
+    if modules.len() > MAX_FILE_NODES {
+        let directories: BTreeSet<&str> = modules.iter().map(|m| directory_of(&m.file)).collect();
+        let ids: HashMap<&str, String> = directories.iter().enumerate().map(|(i, d)| (*d, format!("d{}", i))).collect();
+        for directory in &directories {
+            out.push_str(&format!("    {}{}\n", ids[directory], label(directory)));
+        }
+
+        // Edge weight is the number of file-level imports between two directories
+        let mut edges: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for module in modules {
+            for target in &module.imports {
+                let (from, to) = (directory_of(&module.file), directory_of(target));
+                if from != to {
+                    *edges.entry((from, to)).or_insert(0) += 1;
+                }
+            }
+        }
+        for ((from, to), count) in edges {
+            out.push_str(&format!("    {} -->|{}| {}\n", ids[from], count, ids[to]));
+        }
+        return out;
+    }
+
+    let ids: HashMap<&str, String> = modules.iter().enumerate().map(|(i, m)| (m.file.as_str(), format!("m{}", i))).collect();
+    let mut by_directory: BTreeMap<&str, Vec<&GraphModule>> = BTreeMap::new();
+    for module in modules {
+        by_directory.entry(directory_of(&module.file)).or_default().push(module);
+    }
+
+    for (index, (directory, members)) in by_directory.iter().enumerate() {
+        out.push_str(&format!("    subgraph g{}{}\n", index, label(directory)));
+        for module in members {
+            out.push_str(&format!("        {}{}\n", ids[module.file.as_str()], label(file_name(&module.file))));
+        }
+        out.push_str("    end\n");
+    }
+    for module in modules {
+        for target in module.imports.iter().filter_map(|t| ids.get(t.as_str())) {
+            out.push_str(&format!("    {} --> {}\n", ids[module.file.as_str()], target));
+        }
+    }
+    out
+}
+
+/// Class diagram with each module's exports as members and imports as dependencies
+pub fn imports_class_diagram(modules: &[GraphModule]) -> String {
+    let mut out = String::from("classDiagram\n");
+    let ids: HashMap<&str, String> = modules.iter().enumerate().map(|(i, m)| (m.file.as_str(), format!("m{}", i))).collect();
+
+    // Leaf modules with nothing exported add noise without structure
+    let shown: HashSet<&str> = modules
+        .iter()
+        .filter(|m| !m.exports.is_empty() || !m.imports.is_empty())
+        .map(|m| m.file.as_str())
+        .take(MAX_FILE_NODES)
+        .collect();
+
+    for module in modules.iter().filter(|m| shown.contains(m.file.as_str())) {
+        out.push_str(&format!("    class {}{} {{\n", ids[module.file.as_str()], label(&module.file)));
+        for export in module.exports.iter().take(MAX_CLASS_MEMBERS) {
+            out.push_str(&format!("        +{}\n", member(export)));
+        }
+        if module.exports.len() > MAX_CLASS_MEMBERS {
+            out.push_str(&format!("        +{} more\n", module.exports.len() - MAX_CLASS_MEMBERS));
+        }
+        out.push_str("    }\n");
+    }
+    for module in modules.iter().filter(|m| shown.contains(m.file.as_str())) {
+        for target in module.imports.iter().filter(|t| shown.contains(t.as_str())) {
+            out.push_str(&format!("    {} ..> {} : imports\n", ids[module.file.as_str()], ids[target.as_str()]));
+        }
+    }
+    out
+}
+
+/// Every directory in the plan, including parents that aren't listed explicitly
+fn plan_directories(plan: &ProjectPlan) -> BTreeSet<String> {
+    let mut directories = BTreeSet::new();
+    for node in &plan.file_structure {
+        let path = node.path.trim_end_matches('/');
+        let mut current = match node.node_type {
+            NodeType::Directory => Some(path),
+            NodeType::File => path.rsplit_once('/').map(|(dir, _)| dir),
+        };
+        while let Some(dir) = current {
+            directories.insert(dir.to_string());
+            current = dir.rsplit_once('/').map(|(parent, _)| parent);
+        }
+    }
+    directories
+}
+
+fn plan_files(plan: &ProjectPlan) -> impl Iterator<Item = &str> {
+    plan.file_structure
+        .iter()
+        .filter(|n| matches!(n.node_type, NodeType::File))
+        .map(|n| n.path.trim_end_matches('/'))
+}
+
+/// Directory tree of a planned project plus its external dependencies
+pub fn plan_flowchart(plan: &ProjectPlan) -> String {
+    let mut out = String::from("flowchart TD\n");
+    out.push_str(&format!("    root{}\n", label(&plan.name)));
+
+    let directories = plan_directories(plan);
+    let ids: HashMap<&str, String> = directories.iter().enumerate().map(|(i, d)| (d.as_str(), format!("d{}", i))).collect();
+    let parent_id = |path: &str| match path.rsplit_once('/') {
+        Some((parent, _)) => ids[parent].clone(),
+        None => "root".to_string(),
+    };
+
+    for directory in &directories {
+        out.push_str(&format!("    {}{}\n", ids[directory.as_str()], label(&format!("{}/", file_name(directory)))));
+        out.push_str(&format!("    {} --> {}\n", parent_id(directory), ids[directory.as_str()]));
+    }
+    for (index, file) in plan_files(plan).enumerate() {
+        out.push_str(&format!("    f{}{}\n", index, label(file_name(file))));
+        out.push_str(&format!("    {} --> f{}\n", parent_id(file), index));
+    }
+
+    if !plan.dependencies.is_empty() {
+        out.push_str("    subgraph deps[\"Dependencies\"]\n");
+        for (index, dependency) in plan.dependencies.iter().filter(|d| !d.dev).enumerate() {
+            out.push_str(&format!("        p{}{}\n", index, label(&dependency.name)));
+        }
+        out.push_str("    end\n");
+        out.push_str("    root -.-> deps\n");
+    }
+    out
+}
+
+/// One class per planned directory listing its files, nested by composition
+pub fn plan_class_diagram(plan: &ProjectPlan) -> String {
+    let mut out = String::from("classDiagram\n");
+
+    let mut files_by_directory: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for directory in plan_directories(plan) {
+        files_by_directory.insert(directory, Vec::new());
+    }
+    for file in plan_files(plan) {
+        let directory = file.rsplit_once('/').map(|(dir, _)| dir).unwrap_or(".");
+        files_by_directory.entry(directory.to_string()).or_default().push(file_name(file));
+    }
+
+    let ids: HashMap<&str, String> = files_by_directory.keys().enumerate().map(|(i, d)| (d.as_str(), format!("c{}", i))).collect();
+    for (directory, files) in &files_by_directory {
+        out.push_str(&format!("    class {}{} {{\n", ids[directory.as_str()], label(directory)));
+        for file in files.iter().take(MAX_CLASS_MEMBERS) {
+            out.push_str(&format!("        +{}\n", member(file)));
+        }
+        if files.len() > MAX_CLASS_MEMBERS {
+            out.push_str(&format!("        +{} more\n", files.len() - MAX_CLASS_MEMBERS));
+        }
+        out.push_str("    }\n");
+    }
+    for directory in files_by_directory.keys() {
+        if let Some((parent, _)) = directory.rsplit_once('/') {
+            if let Some(parent_id) = ids.get(parent) {
+                out.push_str(&format!("    {} *-- {}\n", parent_id, ids[directory.as_str()]));
+            }
+        }
+    }
+    out
+}
+
+/// Check a diagram in the subset of Mermaid this module writes: header, node and
+/// edge lines, balanced subgraph/class blocks, and edges to declared ids only
+pub fn validate_mermaid(diagram: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut lines = diagram.lines().map(str::trim).enumerate().filter(|(_, l)| !l.is_empty() && !l.starts_with("%%"));
+
+    let Some((_, header)) = lines.next() else {
+        return vec!["Diagram is empty".to_string()];
+    };
+    if !HEADER.is_match(header) {
+        errors.push(format!("Line 1: unknown diagram type '{}'", header));
+        return errors;
+    }
+    let is_class = header == "classDiagram";
+
+    let mut declared = HashSet::new();
+    let mut edges = Vec::new();
+    let mut depth = 0usize;
+    let mut in_class = false;
+
+    for (index, line) in lines {
+        let number = index + 1;
+        if line.matches('"').count() % 2 != 0 {
+            errors.push(format!("Line {}: unbalanced quotes", number));
+            continue;
+        }
+
+        if is_class {
+            if in_class {
+                if line == "}" {
+                    in_class = false;
+                } else if line.contains('{') || line.contains('}') {
+                    errors.push(format!("Line {}: braces inside a class body", number));
+                }
+            } else if let Some(c) = CLASS_OPEN.captures(line) {
+                declared.insert(c[1].to_string());
+                in_class = true;
+            } else if let Some(c) = CLASS_EDGE.captures(line) {
+                edges.push((number, c[1].to_string(), c[2].to_string()));
+            } else {
+                errors.push(format!("Line {}: unrecognized statement '{}'", number, line));
+            }
+        } else if let Some(c) = SUBGRAPH.captures(line) {
+            declared.insert(c[1].to_string());
+            depth += 1;
+        } else if line == "end" {
+            if depth == 0 {
+                errors.push(format!("Line {}: 'end' without a subgraph", number));
+            }
+            depth = depth.saturating_sub(1);
+        } else if let Some(c) = FLOW_NODE.captures(line) {
+            declared.insert(c[1].to_string());
+        } else if let Some(c) = FLOW_EDGE.captures(line) {
+            edges.push((number, c[1].to_string(), c[2].to_string()));
+        } else {
+            errors.push(format!("Line {}: unrecognized statement '{}'", number, line));
+        }
+    }
+
+    if in_class {
+        errors.push("Unclosed class block".to_string());
+    }
+    if depth > 0 {
+        errors.push(format!("{} unclosed subgraph(s)", depth));
+    }
+    for (number, from, to) in edges {
+        for id in [from, to] {
+            if !declared.contains(&id) {
+                errors.push(format!("Line {}: edge references undeclared node '{}'", number, id));
+            }
+        }
+    }
+    errors
+}
+
+fn to_markdown(diagram: &ArchitectureDiagram, title: &str) -> String {
+    let source = match diagram.source {
+        DiagramSource::Imports => "the import graph",
+        DiagramSource::Plan => "the project plan",
+    };
+    format!(
+        "# {} architecture\n\n_Generated from {} on {}._\n\n## Modules\n\n```mermaid\n{}```\n\n## Structure\n\n```mermaid\n{}```\n",
+        title,
+        source,
+        chrono::Local::now().format("%Y-%m-%d"),
+        diagram.flowchart,
+        diagram.class_diagram
+    )
+}
+
+/// Diagrams for a plan, or for the code in `project_path` (falling back to the plan
+/// it was generated from). Written to `docs/architecture.md` when a project is given.
+pub fn generate(project_path: Option<&Path>, plan: Option<&ProjectPlan>) -> Result<ArchitectureDiagram> {
+    let (source, flowchart, class_diagram, title) = match (plan, project_path) {
+        (Some(plan), _) => (DiagramSource::Plan, plan_flowchart(plan), plan_class_diagram(plan), plan.name.clone()),
+        (None, Some(project)) => {
+            let modules = module_graph(project);
+            let title = project.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if modules.is_empty() {
+                let saved = GenerationRecord::load(project)
+                    .context("No JS/TS sources to analyze and no saved generation plan")?;
+                (DiagramSource::Plan, plan_flowchart(&saved.plan), plan_class_diagram(&saved.plan), title)
+            } else {
+                (DiagramSource::Imports, imports_flowchart(&modules), imports_class_diagram(&modules), title)
+            }
+        }
+        (None, None) => anyhow::bail!("Provide a project path or a plan"),
+    };
+
+    for diagram in [&flowchart, &class_diagram] {
+        let errors = validate_mermaid(diagram);
+        if !errors.is_empty() {
+            anyhow::bail!("Generated invalid Mermaid: {}", errors.join("; "));
+        }
+    }
+
+    let mut diagram = ArchitectureDiagram { source, flowchart, class_diagram, saved_to: None };
+    if let Some(project) = project_path {
+        let target = project.join("docs").join("architecture.md");
+        crate::edit_journal::write_tracked(&target, "architecture", &to_markdown(&diagram, &title))?;
+        diagram.saved_to = Some("docs/architecture.md".to_string());
+    }
+    Ok(diagram)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn generate_architecture_diagram(
+    project_path: Option<String>,
+    plan: Option<ProjectPlan>,
+) -> Result<ArchitectureDiagram, WriteError> {
+    tokio::task::spawn_blocking(move || generate(project_path.as_deref().map(Path::new), plan.as_ref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(WriteError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::pipeline::{Dependency, FileNode};
+
+    #[test]
+    fn test_import_diagrams_validate() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::edit_journal::use_dir_in_tests(dir.path().join("journal"));
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(src.join("lib")).unwrap();
+        std::fs::write(src.join("main.ts"), "import { format } from './lib/format';\nexport const run = () => format(\"a\");\n").unwrap();
+        std::fs::write(src.join("lib/format.ts"), "export function format(s: string) { return s; }\n").unwrap();
+
+        let diagram = generate(Some(dir.path()), None).unwrap();
+        assert_eq!(diagram.source, DiagramSource::Imports);
+        assert!(diagram.flowchart.contains("m1 --> m0"), "{}", diagram.flowchart);
+        assert!(diagram.class_diagram.contains("+format"));
+        assert!(dir.path().join("docs/architecture.md").exists());
+    }
+
+    #[test]
+    fn test_plan_diagrams_validate() {
+        let node = |path: &str, node_type| FileNode { path: path.to_string(), node_type, description: String::new(), priority: 1 };
+        let plan = ProjectPlan {
+            name: "Shop \"demo\"".to_string(),
+            description: String::new(),
+            file_structure: vec![node("src/api", NodeType::Directory), node("src/api/orders.ts", NodeType::File), node("package.json", NodeType::File)],
+            dependencies: vec![Dependency { name: "express".to_string(), version: "^4".to_string(), dev: false, reason: String::new() }],
+            setup_commands: vec![],
+            environment_variables: vec![],
+        };
+
+        assert!(validate_mermaid(&plan_flowchart(&plan)).is_empty(), "{}", plan_flowchart(&plan));
+        assert!(validate_mermaid(&plan_class_diagram(&plan)).is_empty(), "{}", plan_class_diagram(&plan));
+        assert!(!validate_mermaid("flowchart TD\n    a --> b\n").is_empty());
+    }
+}
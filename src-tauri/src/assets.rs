@@ -0,0 +1,344 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use image::codecs::avif::AvifEncoder;
+use image::codecs::ico::{IcoEncoder, IcoFrame};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType as PngFilter, PngEncoder};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageEncoder, RgbaImage};
+use serde_json::{json, Value};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Icon files per target: (relative path, size in px)
+const TAURI_ICONS: &[(&str, u32)] = &[
+    ("32x32.png", 32),
+    ("128x128.png", 128),
+    ("128x128@2x.png", 256),
+    ("icon.png", 512),
+    ("Square30x30Logo.png", 30),
+    ("Square44x44Logo.png", 44),
+    ("Square71x71Logo.png", 71),
+    ("Square89x89Logo.png", 89),
+    ("Square107x107Logo.png", 107),
+    ("Square142x142Logo.png", 142),
+    ("Square150x150Logo.png", 150),
+    ("Square284x284Logo.png", 284),
+    ("Square310x310Logo.png", 310),
+    ("StoreLogo.png", 50),
+];
+
+const WEB_ICONS: &[(&str, u32)] = &[
+    ("favicon-16x16.png", 16),
+    ("favicon-32x32.png", 32),
+    ("apple-touch-icon.png", 180),
+    ("android-chrome-192x192.png", 192),
+    ("android-chrome-512x512.png", 512),
+];
+
+const ANDROID_ICONS: &[(&str, u32)] = &[
+    ("mipmap-mdpi/ic_launcher.png", 48),
+    ("mipmap-hdpi/ic_launcher.png", 72),
+    ("mipmap-xhdpi/ic_launcher.png", 96),
+    ("mipmap-xxhdpi/ic_launcher.png", 144),
+    ("mipmap-xxxhdpi/ic_launcher.png", 192),
+];
+
+const IOS_ICONS: &[(&str, u32)] = &[
+    ("AppIcon-20@2x.png", 40),
+    ("AppIcon-20@3x.png", 60),
+    ("AppIcon-29@2x.png", 58),
+    ("AppIcon-29@3x.png", 87),
+    ("AppIcon-40@2x.png", 80),
+    ("AppIcon-40@3x.png", 120),
+    ("AppIcon-60@2x.png", 120),
+    ("AppIcon-60@3x.png", 180),
+    ("AppIcon-76.png", 76),
+    ("AppIcon-76@2x.png", 152),
+    ("AppIcon-83.5@2x.png", 167),
+    ("AppIcon-512@2x.png", 1024),
+];
+
+const FAVICON_SIZES: &[u32] = &[16, 32, 48];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedAsset {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IconReport {
+    pub output_dir: String,
+    pub files: Vec<GeneratedAsset>,
+    /// `<link>` tags to paste into the page head, for the web target
+    pub head_tags: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Png,
+    Jpeg,
+    Webp,
+    Avif,
+}
+
+impl OutputFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Webp => "webp",
+            OutputFormat::Avif => "avif",
+        }
+    }
+}
+
+/// Centre the image on a transparent square canvas so icons aren't stretched
+fn make_square(image: DynamicImage) -> DynamicImage {
+    let (width, height) = image.dimensions();
+    if width == height {
+        return image;
+    }
+
+    let side = width.max(height);
+    let mut canvas = RgbaImage::new(side, side);
+    image::imageops::overlay(
+        &mut canvas,
+        &image.to_rgba8(),
+        ((side - width) / 2) as i64,
+        ((side - height) / 2) as i64,
+    );
+    DynamicImage::ImageRgba8(canvas)
+}
+
+fn record(path: &Path, width: u32, height: u32) -> Result<GeneratedAsset> {
+    Ok(GeneratedAsset {
+        path: path.to_string_lossy().to_string(),
+        width,
+        height,
+        bytes: std::fs::metadata(path)?.len(),
+    })
+}
+
+fn write_png(image: &DynamicImage, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let rgba = image.to_rgba8();
+    let file = std::fs::File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    PngEncoder::new_with_quality(std::io::BufWriter::new(file), CompressionType::Best, PngFilter::Adaptive)
+        .write_image(&rgba, rgba.width(), rgba.height(), image::ColorType::Rgba8)?;
+    Ok(())
+}
+
+fn write_ico(image: &DynamicImage, sizes: &[u32], path: &Path) -> Result<()> {
+    let mut frames = Vec::new();
+    for &size in sizes {
+        let resized = image.resize_exact(size, size, FilterType::Lanczos3).to_rgba8();
+        frames.push(IcoFrame::as_png(&resized, size, size, image::ColorType::Rgba8)?);
+    }
+
+    let file = std::fs::File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    IcoEncoder::new(std::io::BufWriter::new(file)).encode_images(&frames)?;
+    Ok(())
+}
+
+fn write_sized(image: &DynamicImage, icons: &[(&str, u32)], dir: &Path, files: &mut Vec<GeneratedAsset>) -> Result<()> {
+    for (name, size) in icons {
+        let path = dir.join(name);
+        write_png(&image.resize_exact(*size, *size, FilterType::Lanczos3), &path)?;
+        files.push(record(&path, *size, *size)?);
+    }
+    Ok(())
+}
+
+/// Add the generated icons to an existing web manifest, or create a new one
+fn write_web_manifest(dir: &Path, app_name: &str) -> Result<PathBuf> {
+    let path = dir.join("site.webmanifest");
+    let mut manifest: Value = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| json!({
+            "name": app_name,
+            "short_name": app_name,
+            "theme_color": "#ffffff",
+            "background_color": "#ffffff",
+            "display": "standalone",
+        }));
+
+    manifest["icons"] = json!([
+        { "src": "/android-chrome-192x192.png", "sizes": "192x192", "type": "image/png" },
+        { "src": "/android-chrome-512x512.png", "sizes": "512x512", "type": "image/png", "purpose": "any maskable" },
+    ]);
+
+    std::fs::write(&path, serde_json::to_string_pretty(&manifest)?)?;
+    Ok(path)
+}
+
+const WEB_HEAD_TAGS: &str = r#"<link rel="icon" href="/favicon.ico" sizes="any">
+<link rel="icon" type="image/png" sizes="32x32" href="/favicon-32x32.png">
+<link rel="icon" type="image/png" sizes="16x16" href="/favicon-16x16.png">
+<link rel="apple-touch-icon" sizes="180x180" href="/apple-touch-icon.png">
+<link rel="manifest" href="/site.webmanifest">"#;
+
+/// Generate icons for each target ("tauri", "web", "android", "ios") under `output_dir/<target>`
+pub fn generate(source: &Path, targets: &[String], output_dir: &Path) -> Result<IconReport> {
+    let original = image::open(source).with_context(|| format!("Failed to open {:?}", source))?;
+    let mut warnings = Vec::new();
+
+    let (width, height) = original.dimensions();
+    if width != height {
+        warnings.push(format!("Source is {}x{}; it was padded to a square", width, height));
+    }
+    if width.max(height) < 1024 {
+        warnings.push("Source is smaller than 1024px; large icons will be upscaled and may look blurry".to_string());
+    }
+
+    let image = make_square(original);
+    let app_name = source.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let mut files = Vec::new();
+    let mut head_tags = None;
+
+    for target in targets {
+        let dir = output_dir.join(target);
+        std::fs::create_dir_all(&dir)?;
+
+        match target.as_str() {
+            "tauri" => {
+                write_sized(&image, TAURI_ICONS, &dir, &mut files)?;
+                let ico = dir.join("icon.ico");
+                write_ico(&image, &[16, 24, 32, 48, 64, 256], &ico)?;
+                files.push(record(&ico, 256, 256)?);
+            }
+            "web" => {
+                write_sized(&image, WEB_ICONS, &dir, &mut files)?;
+                let ico = dir.join("favicon.ico");
+                write_ico(&image, FAVICON_SIZES, &ico)?;
+                files.push(record(&ico, 48, 48)?);
+                let manifest = write_web_manifest(&dir, &app_name)?;
+                files.push(record(&manifest, 0, 0)?);
+                head_tags = Some(WEB_HEAD_TAGS.to_string());
+            }
+            "android" => write_sized(&image, ANDROID_ICONS, &dir, &mut files)?,
+            "ios" => write_sized(&image, IOS_ICONS, &dir, &mut files)?,
+            other => warnings.push(format!("Unknown icon target '{}' skipped", other)),
+        }
+    }
+
+    Ok(IconReport {
+        output_dir: output_dir.to_string_lossy().to_string(),
+        files,
+        head_tags,
+        warnings,
+    })
+}
+
+/// Re-encode an image, optionally resizing so it fits within `max_width`.
+/// `quality` (1-100) applies to JPEG, WebP and AVIF; PNG is lossless.
+pub fn convert(source: &Path, format: &OutputFormat, quality: u8, max_width: Option<u32>, output: &Path) -> Result<GeneratedAsset> {
+    let mut image = image::open(source).with_context(|| format!("Failed to open {:?}", source))?;
+    if let Some(max_width) = max_width {
+        if image.width() > max_width {
+            image = image.resize(max_width, u32::MAX, FilterType::Lanczos3);
+        }
+    }
+    let quality = quality.clamp(1, 100);
+
+    match format {
+        OutputFormat::Png => write_png(&image, output)?,
+        OutputFormat::Webp => {
+            // image 0.24 can only write lossless WebP, so libwebp does the lossy encode
+            let rgba = DynamicImage::ImageRgba8(image.to_rgba8());
+            let encoder = webp::Encoder::from_image(&rgba).map_err(|e| anyhow::anyhow!("Failed to encode WebP: {}", e))?;
+            std::fs::write(output, &*encoder.encode(quality as f32))?;
+        }
+        OutputFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb = image.to_rgb8();
+            let mut buffer = Cursor::new(Vec::new());
+            JpegEncoder::new_with_quality(&mut buffer, quality)
+                .write_image(&rgb, rgb.width(), rgb.height(), image::ColorType::Rgb8)?;
+            std::fs::write(output, buffer.into_inner())?;
+        }
+        OutputFormat::Avif => {
+            let rgba = image.to_rgba8();
+            let mut buffer = Cursor::new(Vec::new());
+            AvifEncoder::new_with_speed_quality(&mut buffer, 6, quality)
+                .write_image(&rgba, rgba.width(), rgba.height(), image::ColorType::Rgba8)?;
+            std::fs::write(output, buffer.into_inner())?;
+        }
+    }
+
+    record(output, image.width(), image.height())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn generate_icons(
+    source: String,
+    targets: Vec<String>,
+    output_dir: Option<String>,
+) -> Result<IconReport, String> {
+    let source = PathBuf::from(source);
+    let output_dir = output_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| source.parent().unwrap_or(Path::new(".")).join("icons"));
+
+    tokio::task::spawn_blocking(move || generate(&source, &targets, &output_dir))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Convert or compress an image; writes next to the source unless `output` is given
+#[tauri::command]
+pub async fn convert_image(
+    source: String,
+    format: OutputFormat,
+    quality: Option<u8>,
+    max_width: Option<u32>,
+    output: Option<String>,
+) -> Result<GeneratedAsset, String> {
+    let source = PathBuf::from(source);
+    let output = output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| source.with_extension(format.extension()));
+
+    tokio::task::spawn_blocking(move || convert(&source, &format, quality.unwrap_or(80), max_width, &output))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_web_icons() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("logo.png");
+        DynamicImage::ImageRgba8(RgbaImage::new(64, 32)).save(&source).unwrap();
+
+        let report = generate(&source, &["web".to_string()], &dir.path().join("icons")).unwrap();
+        let web = dir.path().join("icons/web");
+
+        assert!(web.join("favicon.ico").exists());
+        assert_eq!(image::open(web.join("apple-touch-icon.png")).unwrap().dimensions(), (180, 180));
+        assert!(report.head_tags.unwrap().contains("site.webmanifest"));
+        assert_eq!(report.warnings.len(), 2);
+
+        let manifest: Value = serde_json::from_str(&std::fs::read_to_string(web.join("site.webmanifest")).unwrap()).unwrap();
+        assert_eq!(manifest["icons"].as_array().unwrap().len(), 2);
+
+        let webp = dir.path().join("logo.webp");
+        convert(&source, &OutputFormat::Webp, 75, Some(32), &webp).unwrap();
+        assert_eq!(image::open(&webp).unwrap().dimensions(), (32, 16));
+    }
+}
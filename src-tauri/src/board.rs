@@ -0,0 +1,362 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::database::{self, BoardCard, BoardColumn, DatabaseManager};
+use crate::filesystem::collect_source_files;
+
+/// Columns a project's board starts with
+const DEFAULT_COLUMNS: &[&str] = &["To do", "In progress", "Done"];
+
+const TODO_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "mjs", "py", "go", "java", "kt", "c", "h", "cpp", "hpp", "cs", "swift",
+    "rb", "php", "dart", "vue", "svelte", "css", "scss", "html", "sql", "sh", "lua", "toml", "yaml", "yml",
+];
+
+lazy_static::lazy_static! {
+    // Only in comments, so identifiers like `todo_list` and strings mentioning TODO don't count
+    static ref TODO_COMMENT: Regex = Regex::new(
+        r"(?://+|#|/\*+|^\s*\*|--|<!--)\s*(TODO|FIXME|HACK|XXX)\b(?:\([^)]*\))?:?\s*(.*?)\s*(?:\*/|-->)?\s*$"
+    ).unwrap();
+}
+
+/// Something a card refers to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum CardLink {
+    File { path: String, line: Option<u32> },
+    Commit { sha: String },
+}
+
+/// Editable fields of a card
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CardInput {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// YYYY-MM-DD
+    #[serde(default)]
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub links: Vec<CardLink>,
+}
+
+impl CardInput {
+    fn validate(&self) -> Result<()> {
+        if self.title.trim().is_empty() {
+            anyhow::bail!("A card needs a title");
+        }
+        if let Some(due) = &self.due_date {
+            chrono::NaiveDate::parse_from_str(due, "%Y-%m-%d")
+                .with_context(|| format!("Due date '{}' is not YYYY-MM-DD", due))?;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, card: &mut BoardCard) -> Result<()> {
+        self.validate()?;
+        let mut labels: Vec<String> = self.labels.iter().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect();
+        let mut seen = HashSet::new();
+        labels.retain(|l| seen.insert(l.clone()));
+        card.title = self.title.trim().to_string();
+        card.description = self.description.clone();
+        card.labels = serde_json::to_string(&labels)?;
+        card.due_date = self.due_date.clone();
+        card.links = serde_json::to_string(&self.links)?;
+        card.updated_at = chrono::Utc::now().to_rfc3339();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardColumnView {
+    pub column: BoardColumn,
+    pub cards: Vec<BoardCard>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Board {
+    pub project_id: String,
+    pub columns: Vec<BoardColumnView>,
+}
+
+/// The project's columns, creating the defaults on first use
+fn columns(db: &DatabaseManager, project_id: &str) -> Result<Vec<BoardColumn>> {
+    let columns = db.list_board_columns(project_id)?;
+    if !columns.is_empty() {
+        return Ok(columns);
+    }
+    for (position, name) in DEFAULT_COLUMNS.iter().enumerate() {
+        db.add_board_column(&BoardColumn {
+            id: 0,
+            project_id: project_id.to_string(),
+            name: name.to_string(),
+            position: position as i64,
+        })?;
+    }
+    db.list_board_columns(project_id)
+}
+
+pub fn load_board(project_id: &str) -> Result<Board> {
+    let db = database::open()?;
+    let columns = columns(&db, project_id)?;
+    let cards = db.list_board_cards(project_id)?;
+    Ok(Board {
+        project_id: project_id.to_string(),
+        columns: columns
+            .into_iter()
+            .map(|column| BoardColumnView {
+                cards: cards.iter().filter(|c| c.column_id == column.id).cloned().collect(),
+                column,
+            })
+            .collect(),
+    })
+}
+
+fn column_cards(db: &DatabaseManager, project_id: &str, column_id: i64) -> Result<Vec<BoardCard>> {
+    Ok(db.list_board_cards(project_id)?.into_iter().filter(|c| c.column_id == column_id).collect())
+}
+
+fn find_column(db: &DatabaseManager, project_id: &str, column_id: i64) -> Result<BoardColumn> {
+    columns(db, project_id)?
+        .into_iter()
+        .find(|c| c.id == column_id)
+        .context("Board column not found")
+}
+
+pub fn create_card(project_id: &str, column_id: i64, input: &CardInput, source: Option<String>) -> Result<BoardCard> {
+    let db = database::open()?;
+    find_column(&db, project_id, column_id)?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut card = BoardCard {
+        id: 0,
+        project_id: project_id.to_string(),
+        column_id,
+        title: String::new(),
+        description: String::new(),
+        labels: String::new(),
+        due_date: None,
+        links: String::new(),
+        position: column_cards(&db, project_id, column_id)?.len() as i64,
+        source,
+        created_at: now,
+        updated_at: String::new(),
+    };
+    input.apply(&mut card)?;
+    card.id = db.add_board_card(&card)?;
+    Ok(card)
+}
+
+pub fn update_card(card_id: i64, input: &CardInput) -> Result<BoardCard> {
+    let db = database::open()?;
+    let mut card = db.get_board_card(card_id)?.context("Board card not found")?;
+    input.apply(&mut card)?;
+    db.update_board_card(&card)?;
+    Ok(card)
+}
+
+/// `ids` with `id` moved to `position`, clamped to the end
+fn reorder(mut ids: Vec<i64>, id: i64, position: usize) -> Vec<i64> {
+    ids.retain(|other| *other != id);
+    ids.insert(position.min(ids.len()), id);
+    ids
+}
+
+/// Move a card to `position` in `column_id`, renumbering the cards around it
+pub fn move_card(card_id: i64, column_id: i64, position: usize) -> Result<()> {
+    let db = database::open()?;
+    let card = db.get_board_card(card_id)?.context("Board card not found")?;
+    find_column(&db, &card.project_id, column_id)?;
+
+    let target: Vec<i64> = column_cards(&db, &card.project_id, column_id)?.iter().map(|c| c.id).collect();
+    for (index, id) in reorder(target, card_id, position).into_iter().enumerate() {
+        db.set_board_card_position(id, column_id, index as i64)?;
+    }
+    // Close the gap the card left behind
+    if card.column_id != column_id {
+        for (index, other) in column_cards(&db, &card.project_id, card.column_id)?.iter().enumerate() {
+            db.set_board_card_position(other.id, card.column_id, index as i64)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TodoComment {
+    pub file: String,
+    /// 1-based
+    pub line: u32,
+    /// TODO, FIXME, HACK or XXX
+    pub tag: String,
+    pub text: String,
+}
+
+/// Tag and text of a TODO-style comment on `line`
+pub fn parse_todo(line: &str) -> Option<(String, String)> {
+    let captures = TODO_COMMENT.captures(line)?;
+    Some((captures[1].to_string(), captures[2].to_string()))
+}
+
+/// TODO-style comments in the project's source files
+pub fn scan_todos(project_path: &Path) -> Vec<TodoComment> {
+    let mut todos = Vec::new();
+    for file in collect_source_files(project_path, TODO_EXTENSIONS) {
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        for (index, line) in content.lines().enumerate() {
+            if let Some((tag, text)) = parse_todo(line) {
+                todos.push(TodoComment {
+                    file: file.to_string_lossy().to_string(),
+                    line: index as u32 + 1,
+                    tag,
+                    text,
+                });
+            }
+        }
+    }
+    todos
+}
+
+/// Import scanned TODOs as cards in `column_id` (the first column by default).
+/// Comments imported before are skipped even if their line moved.
+pub fn import_todos(project_id: &str, project_path: &Path, column_id: Option<i64>) -> Result<Vec<BoardCard>> {
+    let db = database::open()?;
+    let column_id = match column_id {
+        Some(id) => id,
+        None => columns(&db, project_id)?.first().context("The board has no columns")?.id,
+    };
+    let imported: HashSet<String> = db.list_board_cards(project_id)?.into_iter().filter_map(|c| c.source).collect();
+
+    let mut cards = Vec::new();
+    for todo in scan_todos(project_path) {
+        let relative = Path::new(&todo.file)
+            .strip_prefix(project_path)
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| todo.file.clone());
+        let source = format!("todo:{}:{}", relative, todo.text);
+        if imported.contains(&source) {
+            continue;
+        }
+
+        let title = if todo.text.is_empty() { format!("{} in {}", todo.tag, relative) } else { todo.text.clone() };
+        let input = CardInput {
+            title,
+            description: format!("`{}:{}`", relative, todo.line),
+            labels: vec![todo.tag.to_lowercase()],
+            due_date: None,
+            links: vec![CardLink::File { path: todo.file.clone(), line: Some(todo.line) }],
+        };
+        cards.push(create_card(project_id, column_id, &input, Some(source))?);
+    }
+    Ok(cards)
+}
+
+// Tauri commands
+
+/// The project's board; the default columns are created on first use
+#[tauri::command]
+pub async fn get_task_board(project_id: String) -> Result<Board, String> {
+    load_board(&project_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_board_column(project_id: String, name: String) -> Result<BoardColumn, String> {
+    let db = database::open().map_err(|e| e.to_string())?;
+    let existing = columns(&db, &project_id).map_err(|e| e.to_string())?;
+    let mut column = BoardColumn {
+        id: 0,
+        project_id,
+        name: name.trim().to_string(),
+        position: existing.len() as i64,
+    };
+    column.id = db.add_board_column(&column).map_err(|e| e.to_string())?;
+    Ok(column)
+}
+
+/// Rename a column and/or move it to `position`
+#[tauri::command]
+pub async fn update_board_column(column_id: i64, project_id: String, name: String, position: usize) -> Result<(), String> {
+    let db = database::open().map_err(|e| e.to_string())?;
+    let existing = columns(&db, &project_id).map_err(|e| e.to_string())?;
+    if !existing.iter().any(|c| c.id == column_id) {
+        return Err("Board column not found".to_string());
+    }
+    let order = reorder(existing.iter().map(|c| c.id).collect(), column_id, position);
+    for (index, column) in order.iter().filter_map(|id| existing.iter().find(|c| c.id == *id)).enumerate() {
+        let name = if column.id == column_id { name.trim() } else { column.name.as_str() };
+        db.update_board_column(column.id, name, index as i64).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Delete a column along with its cards
+#[tauri::command]
+pub async fn delete_board_column(column_id: i64) -> Result<(), String> {
+    database::open().and_then(|db| db.delete_board_column(column_id)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_board_card(project_id: String, column_id: i64, card: CardInput) -> Result<BoardCard, String> {
+    create_card(&project_id, column_id, &card, None).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_board_card(card_id: i64, card: CardInput) -> Result<BoardCard, String> {
+    update_card(card_id, &card).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn move_board_card(card_id: i64, column_id: i64, position: usize) -> Result<(), String> {
+    move_card(card_id, column_id, position).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_board_card(card_id: i64) -> Result<(), String> {
+    database::open().and_then(|db| db.delete_board_card(card_id)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn scan_project_todos(project_path: String) -> Result<Vec<TodoComment>, String> {
+    tokio::task::spawn_blocking(move || scan_todos(Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the cards created; TODOs already on the board are skipped
+#[tauri::command]
+pub async fn import_todos_to_board(
+    project_id: String,
+    project_path: String,
+    column_id: Option<i64>,
+) -> Result<Vec<BoardCard>, String> {
+    tokio::task::spawn_blocking(move || import_todos(&project_id, Path::new(&project_path), column_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_todos_and_reorder() {
+        assert_eq!(parse_todo("    // TODO: handle retries"), Some(("TODO".to_string(), "handle retries".to_string())));
+        assert_eq!(parse_todo("# FIXME(sam) flaky on CI"), Some(("FIXME".to_string(), "flaky on CI".to_string())));
+        assert_eq!(parse_todo("/* HACK: until v2 */"), Some(("HACK".to_string(), "until v2".to_string())));
+        assert_eq!(parse_todo("let todo_list = vec![];"), None);
+        assert_eq!(parse_todo("println!(\"TODO later\");"), None);
+
+        assert_eq!(reorder(vec![1, 2, 3], 3, 0), vec![3, 1, 2]);
+        assert_eq!(reorder(vec![1, 2, 3], 1, 9), vec![2, 3, 1]);
+        assert_eq!(reorder(vec![1, 2], 7, 1), vec![1, 7, 2]);
+
+        let input = CardInput { title: "Ship".to_string(), due_date: Some("2024-13-01".to_string()), ..Default::default() };
+        assert!(input.validate().is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::Emitter;
+
+use crate::database::{Bookmark, DatabaseManager};
+
+/// Lines hashed on each side of the bookmarked line
+const CONTEXT_LINES: usize = 2;
+
+/// Where a bookmark points, by content rather than line number. Lines are
+/// hashed trimmed, so re-indenting doesn't lose the bookmark.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LineAnchor {
+    pub line: String,
+    /// Nearest first
+    pub before: Vec<String>,
+    /// Nearest first
+    pub after: Vec<String>,
+}
+
+fn hash_line(line: &str) -> String {
+    blake3::hash(line.trim().as_bytes()).to_hex()[..16].to_string()
+}
+
+/// Anchor for a 1-based line, or None when the file is shorter
+pub fn anchor_at(content: &str, line_number: usize) -> Option<LineAnchor> {
+    let hashes: Vec<String> = content.lines().map(hash_line).collect();
+    let index = line_number.checked_sub(1).filter(|i| *i < hashes.len())?;
+    Some(LineAnchor {
+        line: hashes[index].clone(),
+        before: hashes[..index].iter().rev().take(CONTEXT_LINES).cloned().collect(),
+        after: hashes[index + 1..].iter().take(CONTEXT_LINES).cloned().collect(),
+    })
+}
+
+/// New 1-based line for `anchor`, preferring matches with more intact context and
+/// then the one nearest `hint`. A line edited in place is still found when all of
+/// its context survived; a line that occurs more than once needs some context.
+pub fn relocate(content: &str, anchor: &LineAnchor, hint: usize) -> Option<usize> {
+    let hashes: Vec<String> = content.lines().map(hash_line).collect();
+    let occurrences = hashes.iter().filter(|h| **h == anchor.line).count();
+    let context_size = anchor.before.len() + anchor.after.len();
+
+    let mut best: Option<(usize, usize)> = None;
+    for index in 0..hashes.len() {
+        let before = anchor
+            .before
+            .iter()
+            .enumerate()
+            .filter(|(distance, hash)| index > *distance && hashes[index - distance - 1] == **hash)
+            .count();
+        let after = anchor
+            .after
+            .iter()
+            .enumerate()
+            .filter(|(distance, hash)| hashes.get(index + distance + 1) == Some(*hash))
+            .count();
+        let context = before + after;
+
+        let score = if hashes[index] == anchor.line {
+            if context == 0 && occurrences > 1 {
+                continue;
+            }
+            // Any match on the line itself beats a context-only match
+            context_size + 1 + context
+        } else if context_size > 0 && context == context_size {
+            context
+        } else {
+            continue;
+        };
+
+        let line = index + 1;
+        let better = best.map_or(true, |(best_score, best_line)| {
+            score > best_score || (score == best_score && line.abs_diff(hint) < best_line.abs_diff(hint))
+        });
+        if better {
+            best = Some((score, line));
+        }
+    }
+
+    best.map(|(_, line)| line)
+}
+
+/// Anchor JSON for a new bookmark, read from the file on disk
+pub fn anchor_for_bookmark(bookmark: &Bookmark) -> Option<String> {
+    let line = usize::try_from(bookmark.line_number?).ok()?;
+    let content = std::fs::read_to_string(&bookmark.file_path).ok()?;
+    serde_json::to_string(&anchor_at(&content, line)?).ok()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkUpdate {
+    pub id: i64,
+    pub file_path: String,
+    pub old_line: Option<i32>,
+    pub new_line: Option<i32>,
+    pub orphaned: bool,
+}
+
+/// Re-locate bookmarks whose files changed. Orphaned bookmarks keep their last
+/// line number and are retried on every pass, so undoing an edit restores them.
+pub fn reconcile(manager: &DatabaseManager, bookmarks: Vec<Bookmark>) -> Result<Vec<BookmarkUpdate>> {
+    let mut by_file: BTreeMap<String, Vec<Bookmark>> = BTreeMap::new();
+    for bookmark in bookmarks {
+        // Whole-file bookmarks have nothing to track
+        if bookmark.line_number.is_some() && crate::remote::parse_remote_path(&bookmark.file_path).is_none() {
+            by_file.entry(bookmark.file_path.clone()).or_default().push(bookmark);
+        }
+    }
+
+    let mut updates = Vec::new();
+    for (file_path, bookmarks) in by_file {
+        let content = std::fs::read_to_string(&file_path).ok();
+
+        for bookmark in bookmarks {
+            let old_line = bookmark.line_number.unwrap_or(1);
+            let hint = usize::try_from(old_line).unwrap_or(1);
+            let stored: Option<LineAnchor> = bookmark.anchor.as_deref().and_then(|a| serde_json::from_str(a).ok());
+
+            let new_line = match (&content, &stored) {
+                (None, _) => None,
+                (Some(content), Some(anchor)) => relocate(content, anchor, hint),
+                // Bookmarks from before anchors existed get one at their current line
+                (Some(content), None) => Some(hint).filter(|line| *line <= content.lines().count()),
+            };
+
+            let (line_number, anchor, orphaned) = match new_line {
+                Some(line) => {
+                    let anchor = content.as_deref().and_then(|c| anchor_at(c, line));
+                    (Some(line as i32), anchor, false)
+                }
+                None => (bookmark.line_number, stored.clone(), true),
+            };
+            if line_number == bookmark.line_number && anchor == stored && orphaned == bookmark.orphaned {
+                continue;
+            }
+
+            let anchor_json = anchor.as_ref().map(serde_json::to_string).transpose()?;
+            manager.update_bookmark_position(bookmark.id, line_number, anchor_json.as_deref(), orphaned)?;
+            updates.push(BookmarkUpdate {
+                id: bookmark.id,
+                file_path: file_path.clone(),
+                old_line: bookmark.line_number,
+                new_line: line_number,
+                orphaned,
+            });
+        }
+    }
+
+    Ok(updates)
+}
+
+/// Reconcile the bookmarks in one file after the IDE writes or deletes it
+pub fn reconcile_file(app: &tauri::AppHandle, path: &Path) {
+    let app = app.clone();
+    let path = path.to_string_lossy().to_string();
+    tokio::task::spawn_blocking(move || {
+        let result = DatabaseManager::new()
+            .and_then(|manager| reconcile(&manager, manager.get_bookmarks_for_file(&path)?));
+        match result {
+            Ok(updates) if !updates.is_empty() => {
+                app.emit("bookmarks-updated", updates).ok();
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to reconcile bookmarks in {}: {}", path, e),
+        }
+    });
+}
+
+// Tauri commands
+
+/// Re-locate every bookmark in a project, e.g. after a git checkout or pull
+#[tauri::command]
+pub async fn reconcile_bookmarks(app: tauri::AppHandle, project_id: String) -> Result<Vec<BookmarkUpdate>, String> {
+    let updates = tokio::task::spawn_blocking(move || {
+        let manager = DatabaseManager::new()?;
+        let bookmarks = manager.get_bookmarks(Some(&project_id))?;
+        reconcile(&manager, bookmarks)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    if !updates.is_empty() {
+        app.emit("bookmarks-updated", updates.clone()).ok();
+    }
+    Ok(updates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relocate_bookmark() {
+        let original = "fn a() {\n    one();\n}\n\nfn b() {\n    two();\n}\n";
+        let anchor = anchor_at(original, 6).unwrap();
+
+        // Lines inserted above, and the whole block re-indented
+        let shifted = "// header\n\nmod m {\n    fn a() {\n        one();\n    }\n\n    fn b() {\n        two();\n    }\n}\n";
+        assert_eq!(relocate(shifted, &anchor, 6), Some(9));
+
+        // The bookmarked line itself edited, its surroundings intact
+        let edited = "fn a() {\n    one();\n}\n\nfn b() {\n    two(2);\n}\n";
+        assert_eq!(relocate(edited, &anchor, 6), Some(6));
+
+        // The block deleted
+        let deleted = "fn a() {\n    one();\n}\n";
+        assert_eq!(relocate(deleted, &anchor, 6), None);
+
+        // A repeated line with none of its context left is ambiguous
+        let closing = anchor_at(original, 7).unwrap();
+        assert_eq!(relocate("}\n}\n", &closing, 7), None);
+    }
+}
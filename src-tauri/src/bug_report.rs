@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::secrets::redact_secrets;
+
+const MAX_LOG_LINES: usize = 2000;
+
+/// Tail of the application log, kept in memory for bug reports
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// `tracing` writer that appends formatted log lines to the in-memory tail
+#[derive(Clone, Copy)]
+pub struct RecentLogs;
+
+impl Write for RecentLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        let mut logs = RECENT_LOGS.lock().unwrap();
+        for line in text.lines() {
+            if logs.len() == MAX_LOG_LINES {
+                logs.pop_front();
+            }
+            logs.push_back(line.to_string());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for RecentLogs {
+    type Writer = RecentLogs;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RecentLogs
+    }
+}
+
+pub fn recent_logs(lines: usize) -> Vec<String> {
+    let logs = RECENT_LOGS.lock().unwrap();
+    logs.iter().skip(logs.len().saturating_sub(lines)).cloned().collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BugReportBundle {
+    pub path: String,
+    pub included_screenshot: bool,
+    pub log_lines: usize,
+    pub redactions: usize,
+}
+
+fn report_markdown(app: &AppHandle, title: &str, description: &str) -> String {
+    format!(
+        "# {}\n\n{}\n\n## Environment\n\n- App version: {}\n- OS: {} ({})\n- Created: {}\n",
+        title,
+        description,
+        app.package_info().version,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        chrono::Local::now().to_rfc3339(),
+    )
+}
+
+fn write_bundle(path: &Path, report: &str, logs: &str, screenshot: Option<&Path>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+    let options = zip::write::FileOptions::default();
+
+    zip.start_file("report.md", options)?;
+    zip.write_all(report.as_bytes())?;
+    zip.start_file("logs.txt", options)?;
+    zip.write_all(logs.as_bytes())?;
+
+    if let Some(screenshot) = screenshot {
+        // PNG is already compressed
+        zip.start_file("screenshot.png", options.compression_method(zip::CompressionMethod::Stored))?;
+        zip.write_all(&std::fs::read(screenshot)?)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+// Tauri commands
+
+/// Bundle a description, the last `log_lines` of the log and optionally the
+/// latest screenshot into a zip. Secrets in the logs are redacted.
+#[tauri::command]
+pub async fn create_bug_report(
+    app: AppHandle,
+    title: String,
+    description: String,
+    include_screenshot: Option<bool>,
+    log_lines: Option<usize>,
+    output_path: Option<String>,
+) -> Result<BugReportBundle, String> {
+    let logs = recent_logs(log_lines.unwrap_or(500));
+    let (redacted_logs, redactions) = redact_secrets(&logs.join("\n"));
+
+    let screenshot = if include_screenshot.unwrap_or(true) {
+        crate::screenshot::last_screenshot()
+    } else {
+        None
+    };
+
+    let path = match output_path {
+        Some(path) => PathBuf::from(path),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("bug-reports")
+            .join(format!("bug-report-{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S"))),
+    };
+
+    let report = report_markdown(&app, &title, &description);
+    write_bundle(&path, &report, &redacted_logs, screenshot.as_deref()).map_err(|e| e.to_string())?;
+
+    tracing::info!("Bug report written to {:?}", path);
+
+    Ok(BugReportBundle {
+        path: path.to_string_lossy().to_string(),
+        included_screenshot: screenshot.is_some(),
+        log_lines: logs.len(),
+        redactions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_logs_are_bounded() {
+        for i in 0..MAX_LOG_LINES + 10 {
+            RecentLogs.write_all(format!("line {}\n", i).as_bytes()).unwrap();
+        }
+        let logs = recent_logs(usize::MAX);
+        assert_eq!(logs.len(), MAX_LOG_LINES);
+        assert_eq!(logs.last().unwrap(), &format!("line {}", MAX_LOG_LINES + 9));
+        assert_eq!(recent_logs(2).len(), 2);
+    }
+}
@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use chrono::{DateTime, Utc};
+use similar::TextDiff;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::edit_journal::{BackendEdit, EditJournal};
+use crate::project::PROJECT_CONFIG_DIR;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeExportFormat {
+    /// One unified diff, applicable with `git apply`
+    Patch,
+    /// A zip with a `.diff` per file and a `changes.md` overview
+    Bundle,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeStatus {
+    Added,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedFile {
+    /// Relative to the repository or project the files are in
+    pub path: String,
+    pub status: ChangeStatus,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Journal edits folded into this diff
+    pub edits: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeExport {
+    pub session_id: String,
+    pub format: ChangeExportFormat,
+    pub files: Vec<ChangedFile>,
+    /// The patch text, for `patch`
+    pub patch: Option<String>,
+    /// Where the bundle or patch was written
+    pub output_path: Option<String>,
+}
+
+/// One stretch of agent edits to a file that nobody else edited in between: what it
+/// was before the first, and after the last
+struct Run {
+    before: Option<String>,
+    after: String,
+}
+
+/// The agent's runs per file. An edit from another source (the user, a refactoring)
+/// ends a run, so the export never attributes it to the agent.
+struct NetChange {
+    runs: Vec<Run>,
+    edits: usize,
+}
+
+/// Start of the range: an RFC 3339 time (e.g. a checkpoint's) or a journal edit id,
+/// whose own edit is left out
+fn edits_since<'a>(edits: &'a [BackendEdit], since: Option<&str>) -> Result<&'a [BackendEdit]> {
+    let Some(since) = since else { return Ok(edits) };
+    if let Ok(time) = DateTime::parse_from_rfc3339(since) {
+        let time = time.with_timezone(&Utc);
+        let start = edits.iter().position(|e| e.applied_at >= time).unwrap_or(edits.len());
+        return Ok(&edits[start..]);
+    }
+    let index = edits.iter().position(|e| e.id == since).context("No edit or time to start from")?;
+    Ok(&edits[index + 1..])
+}
+
+fn net_changes(edits: &[BackendEdit]) -> BTreeMap<PathBuf, NetChange> {
+    let mut changes: BTreeMap<PathBuf, NetChange> = BTreeMap::new();
+    // Files someone else edited since the agent's last edit to them
+    let mut interrupted: HashSet<PathBuf> = HashSet::new();
+    for edit in edits.iter().filter(|e| e.undone_at.is_none()) {
+        let path = PathBuf::from(&edit.file);
+        if edit.source != "agent" {
+            interrupted.insert(path);
+            continue;
+        }
+        let resumed = interrupted.remove(&path);
+        let change = changes.entry(path).or_insert_with(|| NetChange { runs: Vec::new(), edits: 0 });
+        change.edits += 1;
+        match change.runs.last_mut() {
+            Some(run) if !resumed => run.after = edit.after.clone(),
+            _ => change.runs.push(Run { before: edit.before.clone(), after: edit.after.clone() }),
+        }
+    }
+    // Edits that cancelled each other out
+    for change in changes.values_mut() {
+        change.runs.retain(|run| run.before.as_deref() != Some(run.after.as_str()));
+    }
+    changes.retain(|_, change| !change.runs.is_empty());
+    changes
+}
+
+/// Deepest folder containing every path
+fn common_root(paths: &[&PathBuf]) -> PathBuf {
+    let mut root = paths.first().and_then(|p| p.parent()).map(Path::to_path_buf).unwrap_or_default();
+    for path in paths {
+        while !path.starts_with(&root) {
+            if !root.pop() {
+                break;
+            }
+        }
+    }
+    root
+}
+
+/// The repository or project the changed files belong to, so diff paths apply from its
+/// root; the deepest shared folder when they aren't in one
+fn export_root(paths: &[&PathBuf]) -> PathBuf {
+    let shared = common_root(paths);
+    shared
+        .ancestors()
+        .find(|dir| dir.join(".git").exists() || dir.join(PROJECT_CONFIG_DIR).is_dir())
+        .map(Path::to_path_buf)
+        .unwrap_or(shared)
+}
+
+/// A run after someone else's edit is a separate patch to the same file; `git apply`
+/// applies them in order
+fn file_diff(path: &str, change: &NetChange) -> String {
+    change
+        .runs
+        .iter()
+        .map(|run| {
+            let old_header = if run.before.is_some() { format!("a/{}", path) } else { "/dev/null".to_string() };
+            let diff = TextDiff::from_lines(run.before.as_deref().unwrap_or(""), &run.after)
+                .unified_diff()
+                .context_radius(3)
+                .header(&old_header, &format!("b/{}", path))
+                .to_string();
+            let mode = if run.before.is_none() { "new file mode 100644\n" } else { "" };
+            format!("diff --git a/{} b/{}\n{}{}", path, path, mode, diff)
+        })
+        .collect()
+}
+
+fn count_lines(change: &NetChange) -> (usize, usize) {
+    change.runs.iter().fold((0, 0), |counts, run| {
+        let diff = TextDiff::from_lines(run.before.as_deref().unwrap_or(""), &run.after);
+        diff.iter_all_changes().fold(counts, |(added, removed), c| match c.tag() {
+            similar::ChangeTag::Insert => (added + 1, removed),
+            similar::ChangeTag::Delete => (added, removed + 1),
+            similar::ChangeTag::Equal => (added, removed),
+        })
+    })
+}
+
+fn overview(session_id: &str, files: &[ChangedFile]) -> String {
+    let mut markdown = format!("# AI changes\n\nSession `{}`, {} files changed.\n\n", session_id, files.len());
+    markdown.push_str("| File | Status | + | - |\n|---|---|---|---|\n");
+    for file in files {
+        markdown.push_str(&format!(
+            "| {} | {:?} | {} | {} |\n",
+            file.path, file.status, file.lines_added, file.lines_removed
+        ));
+    }
+    markdown
+}
+
+fn write_bundle(path: &Path, session_id: &str, files: &[ChangedFile], diffs: &[String]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+    let options = zip::write::FileOptions::default();
+
+    zip.start_file("changes.md", options)?;
+    zip.write_all(overview(session_id, files).as_bytes())?;
+    for (file, diff) in files.iter().zip(diffs) {
+        zip.start_file(format!("diffs/{}.diff", file.path), options)?;
+        zip.write_all(diff.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+pub fn export(
+    journal: &EditJournal,
+    session_id: &str,
+    format: ChangeExportFormat,
+    since: Option<&str>,
+    output_path: Option<&Path>,
+) -> Result<ChangeExport> {
+    let edits = journal.load(session_id)?;
+    let changes = net_changes(edits_since(&edits, since)?);
+    let root = export_root(&changes.keys().collect::<Vec<_>>());
+
+    let mut files = Vec::new();
+    let mut diffs = Vec::new();
+    for (path, change) in &changes {
+        let relative = path.strip_prefix(&root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        let (lines_added, lines_removed) = count_lines(change);
+        diffs.push(file_diff(&relative, change));
+        files.push(ChangedFile {
+            path: relative,
+            status: if change.runs[0].before.is_some() { ChangeStatus::Modified } else { ChangeStatus::Added },
+            lines_added,
+            lines_removed,
+            edits: change.edits,
+        });
+    }
+
+    let patch = match format {
+        ChangeExportFormat::Patch => {
+            let patch = diffs.concat();
+            if let Some(output) = output_path {
+                std::fs::write(output, &patch).with_context(|| format!("Failed to write {:?}", output))?;
+            }
+            Some(patch)
+        }
+        ChangeExportFormat::Bundle => {
+            let output = output_path.context("A bundle needs a path to be written to")?;
+            write_bundle(output, session_id, &files, &diffs)?;
+            None
+        }
+    };
+
+    Ok(ChangeExport {
+        session_id: session_id.to_string(),
+        format,
+        files,
+        patch,
+        output_path: output_path.map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+// Tauri commands
+
+/// Everything the agent changed in `session_id` (this session when empty), from `since`
+/// (an edit id or RFC 3339 time) on, as a patch or a zip of per-file diffs
+#[tauri::command]
+pub async fn export_ai_changes(
+    session_id: Option<String>,
+    format: ChangeExportFormat,
+    since: Option<String>,
+    output_path: Option<String>,
+) -> Result<ChangeExport, String> {
+    tokio::task::spawn_blocking(move || {
+        let session_id = session_id.unwrap_or_else(|| crate::edit_journal::current_session().to_string());
+        export(&EditJournal::new()?, &session_id, format, since.as_deref(), output_path.as_deref().map(Path::new))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Sessions with journaled edits, newest first
+#[tauri::command]
+pub async fn list_edit_sessions() -> Result<Vec<String>, String> {
+    EditJournal::new().map(|journal| journal.sessions()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_patch_and_bundle() {
+        let data = tempfile::tempdir().unwrap();
+        let journal = EditJournal::at(data.path().join("journal"));
+        let project = data.path().join("project");
+        let app = project.join("src/app.ts");
+        let util = project.join("src/lib/util.ts");
+        std::fs::create_dir_all(project.join(".git")).unwrap();
+
+        let first = journal.record("s", &app, "agent", Some("a\n"), "a\nb\n").unwrap().unwrap();
+        journal.record("s", &app, "agent", Some("a\nb\n"), "a\nb\nc\n").unwrap();
+        journal.record("s", &util, "agent", None, "export {}\n").unwrap();
+        journal.record("s", &app, "refactor", Some("a\nb\nc\n"), "z\nb\nc\n").unwrap();
+        journal.record("s", &app, "agent", Some("z\nb\nc\n"), "z\nb\nc\nd\n").unwrap();
+
+        let export_all = export(&journal, "s", ChangeExportFormat::Patch, None, None).unwrap();
+        let patch = export_all.patch.unwrap();
+        assert!(patch.contains("--- a/src/app.ts\n+++ b/src/app.ts\n"));
+        assert!(patch.contains("+c\n") && patch.contains("+d\n"));
+        // The refactoring in between is not the agent's
+        assert!(!patch.contains("+z\n"));
+        assert_eq!(patch.matches("diff --git a/src/app.ts b/src/app.ts\n").count(), 2);
+        assert!(patch.contains("new file mode 100644\n--- /dev/null\n+++ b/src/lib/util.ts\n"));
+        assert_eq!(export_all.files[0].edits, 3);
+        assert_eq!((export_all.files[0].lines_added, export_all.files[0].lines_removed), (3, 0));
+        assert_eq!(export_all.files[1].status, ChangeStatus::Added);
+
+        let later = export(&journal, "s", ChangeExportFormat::Patch, Some(&first), None).unwrap();
+        assert!(later.patch.unwrap().contains("--- a/src/app.ts\n+++ b/src/app.ts\n@@ -1,2 +1,3 @@"));
+
+        let bundle = data.path().join("out/changes.zip");
+        assert!(export(&journal, "s", ChangeExportFormat::Bundle, None, None).is_err());
+        export(&journal, "s", ChangeExportFormat::Bundle, None, Some(&bundle)).unwrap();
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&bundle).unwrap()).unwrap();
+        assert!(archive.by_name("diffs/src/lib/util.ts.diff").is_ok());
+    }
+}
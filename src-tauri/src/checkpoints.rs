@@ -0,0 +1,330 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::documents;
+use crate::filesystem::collect_source_files;
+use crate::project::ProjectManager;
+use crate::readonly;
+
+/// Oldest checkpoints past this are dropped, with the objects only they used
+const MAX_CHECKPOINTS: usize = 30;
+/// Bigger files (datasets, binaries) are left out and left alone on restore
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckpointTrigger {
+    /// Taken automatically before a multi-file agent operation
+    AgentRun,
+    Manual,
+    /// Taken before restoring another checkpoint, so a restore can be undone
+    BeforeRestore,
+}
+
+/// A snapshot of the project's files: relative path to the blake3 hash of its content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: String,
+    pub label: String,
+    pub trigger: CheckpointTrigger,
+    pub created_at: DateTime<Utc>,
+    pub files: BTreeMap<String, String>,
+    /// Over `MAX_FILE_BYTES`, not captured
+    #[serde(default)]
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointSummary {
+    pub id: String,
+    pub label: String,
+    pub trigger: CheckpointTrigger,
+    pub created_at: DateTime<Utc>,
+    pub file_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub checkpoint_id: String,
+    /// Taken just before restoring; restore it to undo this restore
+    pub safety_checkpoint_id: String,
+    pub restored: Vec<String>,
+    /// Created after the checkpoint, so removed
+    pub removed: Vec<String>,
+    /// Read-only paths, left as they are
+    pub skipped_readonly: Vec<String>,
+}
+
+impl Checkpoint {
+    pub fn summary(&self) -> CheckpointSummary {
+        CheckpointSummary {
+            id: self.id.clone(),
+            label: self.label.clone(),
+            trigger: self.trigger,
+            created_at: self.created_at,
+            file_count: self.files.len(),
+        }
+    }
+}
+
+/// Content-addressed snapshots in `.sai-metadata/checkpoints`: identical content is
+/// stored once, however many checkpoints include it
+pub struct CheckpointStore {
+    project_path: PathBuf,
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn for_project(project_path: &Path) -> Self {
+        Self {
+            project_path: project_path.to_path_buf(),
+            dir: project_path.join(".sai-metadata").join("checkpoints"),
+        }
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.dir.join("objects").join(&hash[..2]).join(hash)
+    }
+
+    fn manifest_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.project_path).unwrap_or(path).to_string_lossy().replace('\\', "/")
+    }
+
+    /// Project files as the checkpoint sees them: what the ignore rules keep, minus hidden folders
+    fn current_files(&self) -> Vec<PathBuf> {
+        collect_source_files(&self.project_path, &[])
+    }
+
+    pub fn create(&self, label: &str, trigger: CheckpointTrigger) -> Result<CheckpointSummary> {
+        let checkpoint = self.snapshot(label, trigger)?;
+        self.prune(None)?;
+        Ok(checkpoint.summary())
+    }
+
+    fn snapshot(&self, label: &str, trigger: CheckpointTrigger) -> Result<Checkpoint> {
+        let mut checkpoint = Checkpoint {
+            id: format!("{}-{}", Utc::now().format("%Y%m%d%H%M%S%3f"), &uuid::Uuid::new_v4().to_string()[..8]),
+            label: label.to_string(),
+            trigger,
+            created_at: Utc::now(),
+            files: BTreeMap::new(),
+            skipped: Vec::new(),
+        };
+
+        for path in self.current_files() {
+            let relative = self.relative(&path);
+            if path.metadata().map(|m| m.len() > MAX_FILE_BYTES).unwrap_or(true) {
+                checkpoint.skipped.push(relative);
+                continue;
+            }
+            let content = std::fs::read(&path).with_context(|| format!("Failed to read {}", relative))?;
+            let hash = blake3::hash(&content).to_hex().to_string();
+            let object = self.object_path(&hash);
+            if !object.exists() {
+                std::fs::create_dir_all(object.parent().unwrap())?;
+                std::fs::write(&object, &content)?;
+            }
+            checkpoint.files.insert(relative, hash);
+        }
+
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.manifest_path(&checkpoint.id), serde_json::to_string(&checkpoint)?)?;
+        tracing::info!("Checkpoint {} of {} files: {}", checkpoint.id, checkpoint.files.len(), label);
+        Ok(checkpoint)
+    }
+
+    pub fn load(&self, id: &str) -> Result<Checkpoint> {
+        let json = std::fs::read_to_string(self.manifest_path(id)).with_context(|| format!("Checkpoint not found: {}", id))?;
+        serde_json::from_str(&json).with_context(|| format!("Invalid checkpoint: {}", id))
+    }
+
+    fn all(&self) -> Vec<Checkpoint> {
+        let mut checkpoints: Vec<Checkpoint> = std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+                    .filter_map(|e| serde_json::from_str(&std::fs::read_to_string(e.path()).ok()?).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        checkpoints.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.id.cmp(&a.id)));
+        checkpoints
+    }
+
+    /// Newest first
+    pub fn list(&self) -> Vec<CheckpointSummary> {
+        self.all().iter().map(Checkpoint::summary).collect()
+    }
+
+    /// Drop checkpoints past `MAX_CHECKPOINTS`, except `keep`, and objects nothing uses any more
+    fn prune(&self, keep: Option<&str>) -> Result<()> {
+        let checkpoints = self.all();
+        if checkpoints.len() <= MAX_CHECKPOINTS {
+            return Ok(());
+        }
+        let (live, old): (Vec<(usize, &Checkpoint)>, Vec<(usize, &Checkpoint)>) = checkpoints
+            .iter()
+            .enumerate()
+            .partition(|(index, c)| *index < MAX_CHECKPOINTS || Some(c.id.as_str()) == keep);
+        for (_, checkpoint) in old {
+            std::fs::remove_file(self.manifest_path(&checkpoint.id))?;
+        }
+        let live: HashSet<&String> = live.iter().flat_map(|(_, c)| c.files.values()).collect();
+        for shard in std::fs::read_dir(self.dir.join("objects"))?.flatten() {
+            for object in std::fs::read_dir(shard.path())?.flatten() {
+                if !live.contains(&object.file_name().to_string_lossy().to_string()) {
+                    std::fs::remove_file(object.path())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Put the project back as it was at checkpoint `id`. A checkpoint of the current
+    /// state is taken first, so the restore itself can be rolled back.
+    pub fn restore(&self, id: &str) -> Result<RestoreReport> {
+        let checkpoint = self.load(id)?;
+        // Pruned only once the restore is done, in case `checkpoint` is the oldest
+        let safety = self.snapshot(&format!("Before restoring \"{}\"", checkpoint.label), CheckpointTrigger::BeforeRestore)?;
+        let mut report = RestoreReport {
+            checkpoint_id: checkpoint.id.clone(),
+            safety_checkpoint_id: safety.id,
+            restored: Vec::new(),
+            removed: Vec::new(),
+            skipped_readonly: Vec::new(),
+        };
+
+        let current: BTreeSet<String> = self.current_files().iter().map(|p| self.relative(p)).collect();
+        let skipped: BTreeSet<&String> = checkpoint.skipped.iter().collect();
+
+        for (relative, hash) in &checkpoint.files {
+            let path = self.project_path.join(relative);
+            let unchanged = std::fs::read(&path).map(|c| blake3::hash(&c).to_hex().as_str() == hash.as_str()).unwrap_or(false);
+            if unchanged {
+                continue;
+            }
+            if readonly::check_writable(&path).is_err() {
+                report.skipped_readonly.push(relative.clone());
+                continue;
+            }
+            let content = std::fs::read(self.object_path(hash)).with_context(|| format!("Missing content for {}", relative))?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, content)?;
+            documents::reload(&path.to_string_lossy()).ok();
+            report.restored.push(relative.clone());
+        }
+
+        for relative in current.iter().filter(|r| !checkpoint.files.contains_key(*r) && !skipped.contains(r)) {
+            let path = self.project_path.join(relative);
+            if readonly::check_writable(&path).is_err() {
+                report.skipped_readonly.push(relative.clone());
+                continue;
+            }
+            std::fs::remove_file(&path)?;
+            report.removed.push(relative.clone());
+        }
+
+        tracing::info!(
+            "Restored checkpoint {}: {} files restored, {} removed",
+            id,
+            report.restored.len(),
+            report.removed.len()
+        );
+        self.prune(Some(id))?;
+        Ok(report)
+    }
+}
+
+/// Checkpoint taken before a multi-file agent operation. A failed checkpoint is logged
+/// rather than stopping the operation.
+pub fn before_agent_run(project_path: &Path, label: &str) -> Option<String> {
+    match CheckpointStore::for_project(project_path).create(label, CheckpointTrigger::AgentRun) {
+        Ok(checkpoint) => Some(checkpoint.id),
+        Err(e) => {
+            tracing::warn!("Failed to checkpoint {} before \"{}\": {}", project_path.display(), label, e);
+            None
+        }
+    }
+}
+
+/// `before_agent_run` for an edit set that only names its files, e.g. an error fix or a
+/// rename: the checkpoint covers the project around the first file
+pub fn before_file_edits(files: &[crate::rename::FileEdit], label: &str) -> Option<String> {
+    let first = Path::new(&files.first()?.file);
+    let project_path = crate::ignore_files::find_root(first.parent()?)?;
+    before_agent_run(&project_path, label)
+}
+
+fn store_for(project_id: &str) -> Result<CheckpointStore> {
+    Ok(CheckpointStore::for_project(&ProjectManager::new()?.project_dir(project_id)))
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn create_checkpoint(project_id: String, label: Option<String>) -> Result<CheckpointSummary, String> {
+    tokio::task::spawn_blocking(move || {
+        store_for(&project_id)?.create(label.as_deref().unwrap_or("Manual checkpoint"), CheckpointTrigger::Manual)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_checkpoints(project_id: String) -> Result<Vec<CheckpointSummary>, String> {
+    store_for(&project_id).map(|store| store.list()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_checkpoint(project_id: String, checkpoint_id: String) -> Result<RestoreReport, String> {
+    tokio::task::spawn_blocking(move || store_for(&project_id)?.restore(&checkpoint_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_and_restore() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/app.ts"), "original").unwrap();
+        std::fs::write(root.join("src/same.ts"), "original").unwrap();
+        let store = CheckpointStore::for_project(root);
+
+        let before = store.create("Before agent run", CheckpointTrigger::AgentRun).unwrap();
+        assert_eq!(before.file_count, 2);
+        // Identical content is stored once
+        assert_eq!(std::fs::read_dir(store.dir.join("objects")).unwrap().count(), 1);
+
+        std::fs::write(root.join("src/app.ts"), "changed by agent").unwrap();
+        std::fs::write(root.join("src/new.ts"), "created by agent").unwrap();
+        std::fs::remove_file(root.join("src/same.ts")).unwrap();
+
+        let report = store.restore(&before.id).unwrap();
+        assert_eq!(report.restored, vec!["src/app.ts", "src/same.ts"]);
+        assert_eq!(report.removed, vec!["src/new.ts"]);
+        assert_eq!(std::fs::read_to_string(root.join("src/app.ts")).unwrap(), "original");
+        assert!(!root.join("src/new.ts").exists());
+
+        // The restore is itself undoable
+        store.restore(&report.safety_checkpoint_id).unwrap();
+        assert_eq!(std::fs::read_to_string(root.join("src/new.ts")).unwrap(), "created by agent");
+        assert_eq!(store.list().len(), 3);
+    }
+}
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const READ_BUFFER: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+/// One expected digest; `path` is relative to the manifest's `base_dir` when set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumEntry {
+    pub path: String,
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
+    pub expected: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChecksumManifest {
+    pub base_dir: Option<String>,
+    pub entries: Vec<ChecksumEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumResult {
+    pub path: String,
+    pub expected: String,
+    pub actual: Option<String>,
+    pub ok: bool,
+    /// Why the file couldn't be hashed, e.g. missing
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub all_ok: bool,
+    pub results: Vec<ChecksumResult>,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Lowercase hex digest of the file, read in chunks so large artifacts aren't loaded whole
+pub fn digest_file(path: &Path, algorithm: HashAlgorithm) -> Result<String> {
+    let mut file = std::fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut buffer = vec![0u8; READ_BUFFER];
+
+    match algorithm {
+        HashAlgorithm::Sha256 => {
+            let mut hasher = Sha256::new();
+            loop {
+                let read = file.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..read]);
+            }
+            Ok(to_hex(&hasher.finalize()))
+        }
+        HashAlgorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            loop {
+                let read = file.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..read]);
+            }
+            Ok(hasher.finalize().to_hex().to_string())
+        }
+    }
+}
+
+/// Compare against an expected digest, ignoring case and surrounding whitespace
+pub fn matches(path: &Path, algorithm: HashAlgorithm, expected: &str) -> Result<bool> {
+    Ok(digest_file(path, algorithm)? == expected.trim().to_lowercase())
+}
+
+/// Entries from a `sha256sum`-style listing: `<digest>  <path>` per line,
+/// with an optional `*` marking binary mode
+pub fn parse_sums(text: &str, algorithm: HashAlgorithm) -> Vec<ChecksumEntry> {
+    text.lines()
+        .filter_map(|line| {
+            let (digest, path) = line.trim().split_once(char::is_whitespace)?;
+            let path = path.trim_start().trim_start_matches('*');
+            if digest.is_empty() || path.is_empty() || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            Some(ChecksumEntry {
+                path: path.to_string(),
+                algorithm,
+                expected: digest.to_lowercase(),
+            })
+        })
+        .collect()
+}
+
+pub fn verify(manifest: &ChecksumManifest) -> VerificationReport {
+    let base = manifest.base_dir.as_deref().map(PathBuf::from);
+    let results: Vec<ChecksumResult> = manifest
+        .entries
+        .iter()
+        .map(|entry| {
+            let path = match &base {
+                Some(base) => base.join(&entry.path),
+                None => PathBuf::from(&entry.path),
+            };
+            let expected = entry.expected.trim().to_lowercase();
+            match digest_file(&path, entry.algorithm) {
+                Ok(actual) => ChecksumResult {
+                    path: entry.path.clone(),
+                    ok: actual == expected,
+                    expected,
+                    actual: Some(actual),
+                    error: None,
+                },
+                Err(e) => ChecksumResult {
+                    path: entry.path.clone(),
+                    expected,
+                    actual: None,
+                    ok: false,
+                    error: Some(e.to_string()),
+                },
+            }
+        })
+        .collect();
+
+    VerificationReport {
+        all_ok: results.iter().all(|r| r.ok),
+        results,
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn hash_file(path: String, algorithm: Option<HashAlgorithm>) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || digest_file(Path::new(&path), algorithm.unwrap_or_default()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Check every file in the manifest; failures are reported per file rather than as an error
+#[tauri::command]
+pub async fn verify_checksums(manifest: ChecksumManifest) -> Result<VerificationReport, String> {
+    tokio::task::spawn_blocking(move || verify(&manifest))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "abc").unwrap();
+
+        let sha = digest_file(&dir.path().join("a.txt"), HashAlgorithm::Sha256).unwrap();
+        assert_eq!(sha, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let blake = digest_file(&dir.path().join("a.txt"), HashAlgorithm::Blake3).unwrap();
+        assert_eq!(blake, "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+
+        let sums = format!("{}  a.txt\n{} *missing.bin\nnot a checksum line\n", sha.to_uppercase(), sha);
+        let manifest = ChecksumManifest {
+            base_dir: Some(dir.path().to_string_lossy().to_string()),
+            entries: parse_sums(&sums, HashAlgorithm::Sha256),
+        };
+        let report = verify(&manifest);
+        assert_eq!(report.results.len(), 2);
+        assert!(report.results[0].ok);
+        assert!(report.results[1].error.is_some());
+        assert!(!report.all_ok);
+    }
+}
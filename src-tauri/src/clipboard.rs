@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+use crate::secrets::redact_secrets;
+
+/// Unpinned entries beyond this are dropped, oldest first
+const MAX_ENTRIES: usize = 200;
+const MAX_ENTRY_CHARS: usize = 100_000;
+const POLL_INTERVAL: Duration = Duration::from_millis(750);
+
+static WATCHING: AtomicBool = AtomicBool::new(false);
+static HISTORY: Mutex<Option<ClipboardHistory>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardEntry {
+    pub id: String,
+    pub text: String,
+    pub language: Option<String>,
+    pub pinned: bool,
+    /// Whether secrets were blanked out before storing
+    pub redacted: bool,
+    pub copied_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ClipboardHistory {
+    /// Opt-in flag, restored on the next launch
+    enabled: bool,
+    /// Newest first
+    entries: Vec<ClipboardEntry>,
+    /// Last raw clipboard text seen, so unchanged content isn't recorded twice
+    #[serde(skip)]
+    last_seen: Option<String>,
+}
+
+fn history_file() -> Result<PathBuf> {
+    let dir = dirs::data_dir().context("Failed to get data directory")?.join(".sai-ide");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join("clipboard_history.json"))
+}
+
+fn with_history<T>(f: impl FnOnce(&mut ClipboardHistory) -> T) -> T {
+    let mut guard = HISTORY.lock().unwrap();
+    let history = guard.get_or_insert_with(|| {
+        history_file()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    });
+    f(history)
+}
+
+fn persist() -> Result<()> {
+    let json = with_history(|history| serde_json::to_string_pretty(history))?;
+    std::fs::write(history_file()?, json)?;
+    Ok(())
+}
+
+/// Rough guess at the language of a copied snippet, from telltale syntax
+pub fn detect_snippet_language(text: &str) -> Option<String> {
+    let trimmed = text.trim();
+    let has = |needles: &[&str]| needles.iter().any(|n| trimmed.contains(n));
+
+    let language = if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        "json"
+    } else if has(&["fn ", "let mut ", "impl ", "pub struct ", "use std::", "::new("]) {
+        "rust"
+    } else if has(&["def ", "elif ", "import numpy", "self.", "__init__"]) && !trimmed.contains(';') {
+        "python"
+    } else if has(&["interface ", ": string", ": number", "export type ", "as const"]) {
+        "typescript"
+    } else if has(&["const ", "function ", "=> {", "require(", "console.log"]) {
+        "javascript"
+    } else if has(&["SELECT ", "INSERT INTO", "CREATE TABLE", "UPDATE "]) {
+        "sql"
+    } else if trimmed.starts_with('<') && trimmed.ends_with('>') {
+        "html"
+    } else if trimmed.starts_with("#!/bin/")
+        || (has(&["npm install", "cargo ", "git ", "sudo "]) && trimmed.lines().count() <= 5)
+    {
+        "shell"
+    } else if has(&["{\n", "}\n"]) && has(&[": ", ";\n"]) && has(&["px", "color", "margin", "display"]) {
+        "css"
+    } else {
+        return None;
+    };
+
+    Some(language.to_string())
+}
+
+/// Record copied text; returns the entry unless it was empty or unchanged
+pub fn record(text: &str) -> Option<ClipboardEntry> {
+    if text.trim().is_empty() || text.chars().count() > MAX_ENTRY_CHARS {
+        return None;
+    }
+
+    let (stored, redactions) = redact_secrets(text);
+
+    let entry = with_history(|history| {
+        if history.last_seen.as_deref() == Some(text) {
+            return None;
+        }
+        history.last_seen = Some(text.to_string());
+
+        // Copying the same text again moves it to the top and keeps its pin
+        let pinned = match history.entries.iter().position(|e| e.text == stored) {
+            Some(index) => history.entries.remove(index).pinned,
+            None => false,
+        };
+
+        let entry = ClipboardEntry {
+            id: Uuid::new_v4().to_string(),
+            language: detect_snippet_language(&stored),
+            text: stored,
+            pinned,
+            redacted: redactions > 0,
+            copied_at: chrono::Utc::now().to_rfc3339(),
+        };
+        history.entries.insert(0, entry.clone());
+
+        // Drop the oldest unpinned entries past the limit
+        let mut unpinned = 0;
+        history.entries.retain(|e| {
+            if e.pinned {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= MAX_ENTRIES
+        });
+
+        Some(entry)
+    })?;
+
+    if let Err(e) = persist() {
+        tracing::warn!("Failed to save clipboard history: {}", e);
+    }
+    Some(entry)
+}
+
+fn app_focused(app: &AppHandle) -> bool {
+    app.webview_windows().values().any(|w| w.is_focused().unwrap_or(false))
+}
+
+/// Poll the system clipboard while the IDE has focus, so only copies made in the IDE are recorded
+fn start_watcher(app: AppHandle) {
+    if WATCHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let mut clipboard = match arboard::Clipboard::new() {
+            Ok(clipboard) => clipboard,
+            Err(e) => {
+                tracing::error!("Clipboard watcher unavailable: {}", e);
+                WATCHING.store(false, Ordering::SeqCst);
+                return;
+            }
+        };
+
+        while WATCHING.load(Ordering::SeqCst) {
+            std::thread::sleep(POLL_INTERVAL);
+
+            if !app_focused(&app) {
+                // Content copied elsewhere is marked seen without being recorded
+                if let Ok(text) = clipboard.get_text() {
+                    with_history(|history| history.last_seen = Some(text));
+                }
+                continue;
+            }
+
+            if let Ok(text) = clipboard.get_text() {
+                if let Some(entry) = record(&text) {
+                    app.emit("clipboard-entry-added", &entry).ok();
+                }
+            }
+        }
+    });
+}
+
+/// Restart the watcher at launch if the user opted in previously
+pub fn resume_watcher(app: AppHandle) {
+    if with_history(|history| history.enabled) {
+        start_watcher(app);
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn set_clipboard_watcher(app: AppHandle, enabled: bool) -> Result<(), String> {
+    with_history(|history| history.enabled = enabled);
+    persist().map_err(|e| e.to_string())?;
+
+    if enabled {
+        start_watcher(app);
+    } else {
+        WATCHING.store(false, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_clipboard_watcher_status() -> Result<bool, String> {
+    Ok(WATCHING.load(Ordering::SeqCst))
+}
+
+/// Pinned entries first, then newest; `query` matches text case-insensitively
+#[tauri::command]
+pub async fn list_clipboard_history(
+    query: Option<String>,
+    language: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ClipboardEntry>, String> {
+    let query = query.map(|q| q.to_lowercase());
+
+    let mut entries: Vec<ClipboardEntry> = with_history(|history| {
+        history.entries
+            .iter()
+            .filter(|e| query.as_ref().map_or(true, |q| e.text.to_lowercase().contains(q)))
+            .filter(|e| language.is_none() || e.language == language)
+            .cloned()
+            .collect()
+    });
+
+    entries.sort_by_key(|e| !e.pinned);
+    entries.truncate(limit.unwrap_or(50));
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn pin_clipboard_entry(id: String, pinned: bool) -> Result<(), String> {
+    with_history(|history| {
+        let entry = history.entries.iter_mut().find(|e| e.id == id).ok_or("Entry not found")?;
+        entry.pinned = pinned;
+        Ok::<_, String>(())
+    })?;
+    persist().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_clipboard_entry(id: String) -> Result<(), String> {
+    with_history(|history| history.entries.retain(|e| e.id != id));
+    persist().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_clipboard_history(keep_pinned: Option<bool>) -> Result<(), String> {
+    let keep_pinned = keep_pinned.unwrap_or(true);
+    with_history(|history| history.entries.retain(|e| keep_pinned && e.pinned));
+    persist().map_err(|e| e.to_string())
+}
+
+/// Put an entry back on the system clipboard
+#[tauri::command]
+pub async fn copy_clipboard_entry(id: String) -> Result<(), String> {
+    let text = with_history(|history| {
+        let text = history.entries.iter().find(|e| e.id == id).map(|e| e.text.clone());
+        // Don't record our own write as a new copy
+        history.last_seen = text.clone();
+        text
+    })
+    .ok_or("Entry not found")?;
+
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| format!("Failed to write clipboard: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_snippet_language() {
+        assert_eq!(detect_snippet_language("fn main() {\n    let mut x = 1;\n}").as_deref(), Some("rust"));
+        assert_eq!(detect_snippet_language("def greet(name):\n    return name").as_deref(), Some("python"));
+        assert_eq!(detect_snippet_language("{\"a\": 1}").as_deref(), Some("json"));
+        assert_eq!(detect_snippet_language("interface User { name: string }").as_deref(), Some("typescript"));
+        assert_eq!(detect_snippet_language("just some words"), None);
+    }
+}
@@ -85,7 +85,7 @@ pub struct CloudLLMClient {
 impl CloudLLMClient {
     pub fn new() -> Self {
         Self {
-            client: Client::builder()
+            client: crate::network::client_builder()
                 .timeout(Duration::from_secs(300)) // 5 min timeout for large models
                 .build()
                 .expect("Failed to create HTTP client"),
@@ -52,6 +52,12 @@ pub struct Bookmark {
     pub description: String,
     pub tags: String, // JSON array
     pub created_at: String,
+    /// Hashes of the bookmarked line and its neighbours, as JSON; see `bookmarks::LineAnchor`
+    #[serde(default)]
+    pub anchor: Option<String>,
+    /// The anchored line could not be found after the file changed
+    #[serde(default)]
+    pub orphaned: bool,
 }
 
 // Sessions
@@ -66,10 +72,199 @@ pub struct WorkspaceSession {
     pub updated_at: String,
 }
 
+// Terminal command history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalHistoryEntry {
+    pub id: i64,
+    pub project_id: Option<String>,
+    pub command: String,
+    pub working_dir: Option<String>,
+    pub exit_code: i32,
+    pub duration_ms: i64,
+    pub executed_at: String,
+}
+
+// Cached per-file line counts for project statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStatsEntry {
+    pub file_path: String,
+    pub modified: i64,
+    pub size: i64,
+    pub language: String,
+    pub code: i64,
+    pub comments: i64,
+    pub blanks: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandSuggestion {
+    pub command: String,
+    pub count: i64,
+    pub last_used: String,
+}
+
+// Agent chat sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatSession {
+    pub id: String,
+    pub title: String,
+    pub project_id: Option<String>,
+    /// Agent persona used for new replies, see agent::personas
+    pub persona_id: Option<String>,
+    /// Code selection the session discusses, as JSON; see agent::refactorer::CodeSelection
+    pub selection: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub id: String,
+    pub session_id: String,
+    /// Previous message on this branch; editing a message adds a sibling under the same parent
+    pub parent_id: Option<String>,
+    pub role: String, // "user" or "assistant"
+    pub content: String,
+    /// Project context injected into the prompt alongside the user's message
+    pub context: Option<String>,
+    pub model: Option<String>,
+    pub pinned: bool,
+    pub created_at: String,
+}
+
+/// Rolling summary of a session's older messages, along one branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatSummary {
+    pub session_id: String,
+    pub summary: String,
+    /// Last message folded into the summary; everything after it is kept verbatim
+    pub through_message_id: String,
+    pub updated_at: String,
+}
+
+// Project facts, decisions and preferences the agent remembers across sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentMemory {
+    pub id: i64,
+    pub project_id: String,
+    pub kind: String, // "fact", "decision" or "preference"
+    pub content: String,
+    pub source: String, // "agent" or "user"
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// Notes attached to a file, a line range or the whole project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Note {
+    #[serde(default)]
+    pub id: i64,
+    pub project_id: String,
+    /// None for project-wide notes
+    pub file_path: Option<String>,
+    /// 1-based and inclusive; None for whole-file notes
+    pub start_line: Option<i32>,
+    pub end_line: Option<i32>,
+    pub title: String,
+    pub content: String, // Markdown
+    pub tags: String, // JSON array
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteSearchResult {
+    pub note: Note,
+    /// Matching text with hits wrapped in [brackets]
+    pub snippet: String,
+}
+
+// Task board columns and cards, per project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardColumn {
+    #[serde(default)]
+    pub id: i64,
+    pub project_id: String,
+    pub name: String,
+    /// Left to right, from 0
+    pub position: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardCard {
+    #[serde(default)]
+    pub id: i64,
+    pub project_id: String,
+    pub column_id: i64,
+    pub title: String,
+    pub description: String, // Markdown
+    pub labels: String, // JSON array
+    /// YYYY-MM-DD
+    pub due_date: Option<String>,
+    pub links: String, // JSON array, see board::CardLink
+    /// Top to bottom within the column, from 0
+    pub position: i64,
+    /// Where an imported card came from, so re-importing skips it
+    pub source: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// Tracked work time; a session is open while ended_at is None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkSession {
+    pub id: i64,
+    pub project_id: String,
+    pub feature: Option<String>,
+    pub started_at: String,
+    pub last_activity_at: String,
+    pub ended_at: Option<String>,
+    /// Time between activity, excluding idle gaps
+    pub active_seconds: i64,
+}
+
+// Focus timer intervals, kept with the time tracking data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusSession {
+    pub id: i64,
+    pub project_id: Option<String>,
+    pub phase: String, // "work", "short_break" or "long_break"
+    pub planned_seconds: i64,
+    pub actual_seconds: i64,
+    /// False when skipped or stopped early
+    pub completed: bool,
+    pub started_at: String,
+    pub ended_at: String,
+}
+
+// Prompt A/B experiments: one prompt run through two variants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptExperiment {
+    pub id: String,
+    pub prompt: String,
+    pub results: String, // JSON array of the two variant results
+    pub preference: Option<String>, // "a", "b" or "tie"
+    pub created_at: String,
+}
+
 pub struct DatabaseManager {
     db_path: PathBuf,
 }
 
+/// Set once the schema has been created or migrated in this process
+static SCHEMA_READY: std::sync::Mutex<bool> = std::sync::Mutex::new(false);
+
+/// The app database, with the schema set up on first use only
+pub fn open() -> Result<DatabaseManager> {
+    let db = DatabaseManager::new()?;
+    let mut ready = SCHEMA_READY.lock().unwrap();
+    if !*ready {
+        db.initialize()?;
+        *ready = true;
+    }
+    Ok(db)
+}
+
 impl DatabaseManager {
     pub fn new() -> Result<Self> {
         let app_dir = dirs::data_dir()
@@ -151,6 +346,98 @@ impl DatabaseManager {
             [],
         )?;
         
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS terminal_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT,
+                command TEXT NOT NULL,
+                working_dir TEXT,
+                exit_code INTEGER NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                executed_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS file_stats_cache (
+                project_path TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                modified INTEGER NOT NULL,
+                size INTEGER NOT NULL,
+                language TEXT NOT NULL,
+                code INTEGER NOT NULL,
+                comments INTEGER NOT NULL,
+                blanks INTEGER NOT NULL,
+                PRIMARY KEY (project_path, file_path)
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS git_stats_cache (
+                project_path TEXT PRIMARY KEY,
+                last_commit TEXT NOT NULL,
+                data TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS chat_sessions (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                project_id TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS chat_messages (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL REFERENCES chat_sessions(id) ON DELETE CASCADE,
+                parent_id TEXT,
+                role TEXT NOT NULL,
+                content TEXT NOT NULL,
+                context TEXT,
+                model TEXT,
+                pinned INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS chat_summaries (
+                session_id TEXT PRIMARY KEY REFERENCES chat_sessions(id) ON DELETE CASCADE,
+                summary TEXT NOT NULL,
+                through_message_id TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS agent_memories (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                content TEXT NOT NULL,
+                source TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        add_column_if_missing(&conn, "chat_sessions", "persona_id", "TEXT")?;
+        add_column_if_missing(&conn, "chat_sessions", "selection", "TEXT")?;
+        add_column_if_missing(&conn, "bookmarks", "anchor", "TEXT")?;
+        add_column_if_missing(&conn, "bookmarks", "orphaned", "INTEGER NOT NULL DEFAULT 0")?;
+        
         // Create indexes
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_history_project ON project_history(project_id)",
@@ -162,6 +449,16 @@ impl DatabaseManager {
             [],
         )?;
         
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_terminal_history_project ON terminal_history(project_id)",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_chat_messages_session ON chat_messages(session_id)",
+            [],
+        )?;
+        
         conn.execute(
             "CREATE VIRTUAL TABLE IF NOT EXISTS search_fts USING fts5(
                 project_id, file_path, content
@@ -169,6 +466,133 @@ impl DatabaseManager {
             [],
         )?;
         
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                file_path TEXT,
+                start_line INTEGER,
+                end_line INTEGER,
+                title TEXT NOT NULL,
+                content TEXT NOT NULL,
+                tags TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_notes_project ON notes(project_id)",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS board_columns (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                position INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS board_cards (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                column_id INTEGER NOT NULL REFERENCES board_columns(id) ON DELETE CASCADE,
+                title TEXT NOT NULL,
+                description TEXT NOT NULL,
+                labels TEXT NOT NULL,
+                due_date TEXT,
+                links TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                source TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_board_cards_project ON board_cards(project_id)",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS work_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                feature TEXT,
+                started_at TEXT NOT NULL,
+                last_activity_at TEXT NOT NULL,
+                ended_at TEXT,
+                active_seconds INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_work_sessions_started ON work_sessions(started_at)",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS focus_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT,
+                phase TEXT NOT NULL,
+                planned_seconds INTEGER NOT NULL,
+                actual_seconds INTEGER NOT NULL,
+                completed INTEGER NOT NULL,
+                started_at TEXT NOT NULL,
+                ended_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_experiments (
+                id TEXT PRIMARY KEY,
+                prompt TEXT NOT NULL,
+                results TEXT NOT NULL,
+                preference TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS model_benchmarks (
+                id TEXT PRIMARY KEY,
+                model TEXT NOT NULL,
+                suite TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                data TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        // One row per generate_full_project run; the report is JSON
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS generation_reports (
+                job_id TEXT PRIMARY KEY,
+                project_path TEXT,
+                started_at TEXT NOT NULL,
+                data TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        // rowid is the note id
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+                title, content, tags
+            )",
+            [],
+        )?;
+        
         tracing::info!("Database initialized successfully");
         Ok(())
     }
@@ -329,71 +753,404 @@ impl DatabaseManager {
         Ok(stats)
     }
     
-    // Bookmark Methods
+    // Work Session Methods
     
-    pub fn add_bookmark(&self, bookmark: &Bookmark) -> Result<i64> {
+    pub fn add_work_session(&self, session: &WorkSession) -> Result<i64> {
         let conn = Connection::open(&self.db_path)?;
-        
         conn.execute(
-            "INSERT INTO bookmarks (project_id, file_path, line_number, description, tags, created_at)
+            "INSERT INTO work_sessions (project_id, feature, started_at, last_activity_at, ended_at, active_seconds)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![
-                bookmark.project_id,
-                bookmark.file_path,
-                bookmark.line_number,
-                bookmark.description,
-                bookmark.tags,
-                bookmark.created_at,
+                session.project_id,
+                session.feature,
+                session.started_at,
+                session.last_activity_at,
+                session.ended_at,
+                session.active_seconds,
             ],
         )?;
-        
         Ok(conn.last_insert_rowid())
     }
     
-    pub fn get_bookmarks(&self, project_id: Option<&str>) -> Result<Vec<Bookmark>> {
+    pub fn update_work_session(&self, session: &WorkSession) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "UPDATE work_sessions SET last_activity_at = ?2, ended_at = ?3, active_seconds = ?4 WHERE id = ?1",
+            params![session.id, session.last_activity_at, session.ended_at, session.active_seconds],
+        )?;
+        Ok(())
+    }
+    
+    pub fn get_open_work_session(&self) -> Result<Option<WorkSession>> {
         let conn = Connection::open(&self.db_path)?;
         
-        let (sql, params_vec): (String, Vec<&str>) = if let Some(pid) = project_id {
-            (
-                "SELECT id, project_id, file_path, line_number, description, tags, created_at
-                 FROM bookmarks WHERE project_id = ?1 ORDER BY created_at DESC".to_string(),
-                vec![pid],
-            )
-        } else {
-            (
-                "SELECT id, project_id, file_path, line_number, description, tags, created_at
-                 FROM bookmarks ORDER BY created_at DESC".to_string(),
-                vec![],
-            )
-        };
-        
-        let mut stmt = conn.prepare(&sql)?;
-        
-        let bookmarks = stmt.query_map(rusqlite::params_from_iter(params_vec), |row| {
-            Ok(Bookmark {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                file_path: row.get(2)?,
-                line_number: row.get(3)?,
-                description: row.get(4)?,
-                tags: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        })?
-        .collect::<SqlResult<Vec<_>>>()?;
+        let session = conn.query_row(
+            "SELECT id, project_id, feature, started_at, last_activity_at, ended_at, active_seconds
+             FROM work_sessions WHERE ended_at IS NULL ORDER BY started_at DESC LIMIT 1",
+            [],
+            work_session_from_row,
+        ).optional()?;
         
-        Ok(bookmarks)
+        Ok(session)
     }
     
-    pub fn delete_bookmark(&self, bookmark_id: i64) -> Result<()> {
+    /// Sessions started at or after `since` (RFC 3339), oldest first
+    pub fn list_work_sessions(&self, project_id: Option<&str>, since: &str) -> Result<Vec<WorkSession>> {
         let conn = Connection::open(&self.db_path)?;
-        conn.execute("DELETE FROM bookmarks WHERE id = ?1", params![bookmark_id])?;
-        Ok(())
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, feature, started_at, last_activity_at, ended_at, active_seconds
+             FROM work_sessions
+             WHERE (?1 IS NULL OR project_id = ?1) AND started_at >= ?2
+             ORDER BY started_at"
+        )?;
+        
+        let sessions = stmt.query_map(params![project_id, since], work_session_from_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(sessions)
     }
     
-    // Session Methods
-    
-    pub fn save_session(&self, session: &WorkspaceSession) -> Result<i64> {
+    pub fn add_focus_session(&self, session: &FocusSession) -> Result<i64> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "INSERT INTO focus_sessions (project_id, phase, planned_seconds, actual_seconds, completed, started_at, ended_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                session.project_id,
+                session.phase,
+                session.planned_seconds,
+                session.actual_seconds,
+                session.completed,
+                session.started_at,
+                session.ended_at,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+    
+    pub fn list_focus_sessions(&self, project_id: Option<&str>, limit: i64) -> Result<Vec<FocusSession>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, phase, planned_seconds, actual_seconds, completed, started_at, ended_at
+             FROM focus_sessions
+             WHERE ?1 IS NULL OR project_id = ?1
+             ORDER BY started_at DESC
+             LIMIT ?2"
+        )?;
+        
+        let sessions = stmt.query_map(params![project_id, limit], |row| {
+            Ok(FocusSession {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                phase: row.get(2)?,
+                planned_seconds: row.get(3)?,
+                actual_seconds: row.get(4)?,
+                completed: row.get(5)?,
+                started_at: row.get(6)?,
+                ended_at: row.get(7)?,
+            })
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(sessions)
+    }
+    
+    pub fn save_generation_report(&self, job_id: &str, project_path: Option<&str>, started_at: &str, data: &str) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO generation_reports (job_id, project_path, started_at, data)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![job_id, project_path, started_at, data],
+        )?;
+        Ok(())
+    }
+    
+    pub fn get_generation_report(&self, job_id: &str) -> Result<Option<String>> {
+        let conn = Connection::open(&self.db_path)?;
+        let data = conn.query_row(
+            "SELECT data FROM generation_reports WHERE job_id = ?1",
+            params![job_id],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(data)
+    }
+    
+    /// Report JSON, newest first
+    pub fn list_generation_reports(&self, project_path: Option<&str>, limit: i64) -> Result<Vec<String>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT data FROM generation_reports
+             WHERE ?1 IS NULL OR project_path = ?1
+             ORDER BY started_at DESC
+             LIMIT ?2"
+        )?;
+        
+        let reports = stmt.query_map(params![project_path, limit], |row| row.get(0))?
+            .collect::<SqlResult<Vec<String>>>()?;
+        
+        Ok(reports)
+    }
+    
+    pub fn add_model_benchmark(&self, id: &str, model: &str, suite: &str, started_at: &str, data: &str) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "INSERT INTO model_benchmarks (id, model, suite, started_at, data) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id, model, suite, started_at, data],
+        )?;
+        Ok(())
+    }
+    
+    /// Run JSON, newest first
+    pub fn list_model_benchmarks(&self, model: Option<&str>, suite: Option<&str>) -> Result<Vec<String>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT data FROM model_benchmarks
+             WHERE (?1 IS NULL OR model = ?1) AND (?2 IS NULL OR suite = ?2)
+             ORDER BY started_at DESC"
+        )?;
+        
+        let runs = stmt.query_map(params![model, suite], |row| row.get(0))?
+            .collect::<SqlResult<Vec<String>>>()?;
+        
+        Ok(runs)
+    }
+    
+    // Agent Memory Methods
+    
+    pub fn add_agent_memory(&self, memory: &AgentMemory) -> Result<i64> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "INSERT INTO agent_memories (project_id, kind, content, source, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                memory.project_id,
+                memory.kind,
+                memory.content,
+                memory.source,
+                memory.created_at,
+                memory.updated_at,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+    
+    pub fn update_agent_memory(&self, memory: &AgentMemory) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        let updated = conn.execute(
+            "UPDATE agent_memories SET kind = ?2, content = ?3, source = ?4, updated_at = ?5 WHERE id = ?1",
+            params![memory.id, memory.kind, memory.content, memory.source, memory.updated_at],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Memory entry not found");
+        }
+        Ok(())
+    }
+    
+    pub fn get_agent_memory(&self, id: i64) -> Result<Option<AgentMemory>> {
+        let conn = Connection::open(&self.db_path)?;
+        let memory = conn.query_row(
+            "SELECT id, project_id, kind, content, source, created_at, updated_at FROM agent_memories WHERE id = ?1",
+            params![id],
+            agent_memory_from_row,
+        ).optional()?;
+        Ok(memory)
+    }
+    
+    /// Most recently updated first
+    pub fn list_agent_memories(&self, project_id: &str) -> Result<Vec<AgentMemory>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, kind, content, source, created_at, updated_at
+             FROM agent_memories
+             WHERE project_id = ?1
+             ORDER BY updated_at DESC"
+        )?;
+        
+        let memories = stmt.query_map(params![project_id], agent_memory_from_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(memories)
+    }
+    
+    pub fn delete_agent_memory(&self, id: i64) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("DELETE FROM agent_memories WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+    
+    // Prompt Experiment Methods
+    
+    pub fn add_prompt_experiment(&self, experiment: &PromptExperiment) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "INSERT INTO prompt_experiments (id, prompt, results, preference, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                experiment.id,
+                experiment.prompt,
+                experiment.results,
+                experiment.preference,
+                experiment.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+    
+    pub fn set_experiment_preference(&self, id: &str, preference: Option<&str>) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        let updated = conn.execute(
+            "UPDATE prompt_experiments SET preference = ?2 WHERE id = ?1",
+            params![id, preference],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Experiment not found");
+        }
+        Ok(())
+    }
+    
+    pub fn get_prompt_experiment(&self, id: &str) -> Result<Option<PromptExperiment>> {
+        let conn = Connection::open(&self.db_path)?;
+        let experiment = conn.query_row(
+            "SELECT id, prompt, results, preference, created_at FROM prompt_experiments WHERE id = ?1",
+            params![id],
+            prompt_experiment_from_row,
+        ).optional()?;
+        Ok(experiment)
+    }
+    
+    /// Newest first
+    pub fn list_prompt_experiments(&self, limit: Option<i64>) -> Result<Vec<PromptExperiment>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, prompt, results, preference, created_at
+             FROM prompt_experiments
+             ORDER BY created_at DESC
+             LIMIT ?1"
+        )?;
+        
+        let experiments = stmt.query_map(params![limit.unwrap_or(-1)], prompt_experiment_from_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(experiments)
+    }
+    
+    pub fn delete_prompt_experiment(&self, id: &str) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("DELETE FROM prompt_experiments WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+    
+    // Bookmark Methods
+    
+    pub fn add_bookmark(&self, bookmark: &Bookmark) -> Result<i64> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        conn.execute(
+            "INSERT INTO bookmarks (project_id, file_path, line_number, description, tags, created_at, anchor, orphaned)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                bookmark.project_id,
+                bookmark.file_path,
+                bookmark.line_number,
+                bookmark.description,
+                bookmark.tags,
+                bookmark.created_at,
+                bookmark.anchor,
+                bookmark.orphaned,
+            ],
+        )?;
+        
+        Ok(conn.last_insert_rowid())
+    }
+    
+    pub fn get_bookmarks(&self, project_id: Option<&str>) -> Result<Vec<Bookmark>> {
+        match project_id {
+            Some(pid) => self.query_bookmarks("WHERE project_id = ?1", &[pid]),
+            None => self.query_bookmarks("", &[]),
+        }
+    }
+    
+    pub fn get_bookmarks_for_file(&self, file_path: &str) -> Result<Vec<Bookmark>> {
+        self.query_bookmarks("WHERE file_path = ?1", &[file_path])
+    }
+    
+    fn query_bookmarks(&self, filter: &str, params_vec: &[&str]) -> Result<Vec<Bookmark>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let sql = format!(
+            "SELECT id, project_id, file_path, line_number, description, tags, created_at, anchor, orphaned
+             FROM bookmarks {} ORDER BY created_at DESC",
+            filter
+        );
+        
+        let mut stmt = conn.prepare(&sql)?;
+        
+        let bookmarks = stmt.query_map(rusqlite::params_from_iter(params_vec), |row| {
+            Ok(Bookmark {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                file_path: row.get(2)?,
+                line_number: row.get(3)?,
+                description: row.get(4)?,
+                tags: row.get(5)?,
+                created_at: row.get(6)?,
+                anchor: row.get(7)?,
+                orphaned: row.get(8)?,
+            })
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(bookmarks)
+    }
+    
+    pub fn update_bookmark_position(
+        &self,
+        bookmark_id: i64,
+        line_number: Option<i32>,
+        anchor: Option<&str>,
+        orphaned: bool,
+    ) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "UPDATE bookmarks SET line_number = ?1, anchor = ?2, orphaned = ?3 WHERE id = ?4",
+            params![line_number, anchor, orphaned, bookmark_id],
+        )?;
+        Ok(())
+    }
+    
+    pub fn update_bookmark(&self, bookmark: &Bookmark) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "UPDATE bookmarks SET file_path = ?1, line_number = ?2, description = ?3, tags = ?4, anchor = ?5, orphaned = ?6
+             WHERE id = ?7",
+            params![
+                bookmark.file_path,
+                bookmark.line_number,
+                bookmark.description,
+                bookmark.tags,
+                bookmark.anchor,
+                bookmark.orphaned,
+                bookmark.id,
+            ],
+        )?;
+        Ok(())
+    }
+    
+    pub fn delete_bookmark(&self, bookmark_id: i64) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("DELETE FROM bookmarks WHERE id = ?1", params![bookmark_id])?;
+        Ok(())
+    }
+    
+    // Session Methods
+    
+    pub fn save_session(&self, session: &WorkspaceSession) -> Result<i64> {
         let conn = Connection::open(&self.db_path)?;
         
         conn.execute(
@@ -466,6 +1223,566 @@ impl DatabaseManager {
         Ok(())
     }
     
+    // Terminal History Methods
+    
+    pub fn add_terminal_history(&self, entry: &TerminalHistoryEntry) -> Result<i64> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        conn.execute(
+            "INSERT INTO terminal_history (project_id, command, working_dir, exit_code, duration_ms, executed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                entry.project_id,
+                entry.command,
+                entry.working_dir,
+                entry.exit_code,
+                entry.duration_ms,
+                entry.executed_at,
+            ],
+        )?;
+        
+        Ok(conn.last_insert_rowid())
+    }
+    
+    /// Search history across sessions, newest first
+    pub fn search_terminal_history(
+        &self,
+        query: &str,
+        project_id: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<TerminalHistoryEntry>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, command, working_dir, exit_code, duration_ms, executed_at
+             FROM terminal_history
+             WHERE command LIKE '%' || ?1 || '%' ESCAPE '\\'
+               AND (?2 IS NULL OR project_id = ?2)
+             ORDER BY executed_at DESC
+             LIMIT ?3"
+        )?;
+        
+        let escaped = query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        
+        let entries = stmt.query_map(params![escaped, project_id, limit], |row| {
+            Ok(TerminalHistoryEntry {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                command: row.get(2)?,
+                working_dir: row.get(3)?,
+                exit_code: row.get(4)?,
+                duration_ms: row.get(5)?,
+                executed_at: row.get(6)?,
+            })
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(entries)
+    }
+    
+    /// Frequency-ranked completions for a command prefix
+    pub fn get_command_suggestions(
+        &self,
+        prefix: &str,
+        project_id: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<CommandSuggestion>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT command, COUNT(*) AS uses, MAX(executed_at) AS last_used
+             FROM terminal_history
+             WHERE substr(command, 1, length(?1)) = ?1
+               AND (?2 IS NULL OR project_id = ?2)
+             GROUP BY command
+             ORDER BY uses DESC, last_used DESC
+             LIMIT ?3"
+        )?;
+        
+        let suggestions = stmt.query_map(params![prefix, project_id, limit], |row| {
+            Ok(CommandSuggestion {
+                command: row.get(0)?,
+                count: row.get(1)?,
+                last_used: row.get(2)?,
+            })
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(suggestions)
+    }
+    
+    // Chat Session Methods
+    
+    pub fn create_chat_session(&self, session: &ChatSession) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        conn.execute(
+            "INSERT INTO chat_sessions (id, title, project_id, persona_id, selection, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                session.id,
+                session.title,
+                session.project_id,
+                session.persona_id,
+                session.selection,
+                session.created_at,
+                session.updated_at,
+            ],
+        )?;
+        
+        Ok(())
+    }
+    
+    pub fn get_chat_session(&self, session_id: &str) -> Result<Option<ChatSession>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let session = conn.query_row(
+            "SELECT id, title, project_id, persona_id, selection, created_at, updated_at FROM chat_sessions WHERE id = ?1",
+            params![session_id],
+            |row| {
+                Ok(ChatSession {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    project_id: row.get(2)?,
+                    persona_id: row.get(3)?,
+                    selection: row.get(4)?,
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            },
+        ).optional()?;
+        
+        Ok(session)
+    }
+    
+    pub fn list_chat_sessions(&self, project_id: Option<&str>) -> Result<Vec<ChatSession>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, title, project_id, persona_id, selection, created_at, updated_at
+             FROM chat_sessions
+             WHERE ?1 IS NULL OR project_id = ?1
+             ORDER BY updated_at DESC"
+        )?;
+        
+        let sessions = stmt.query_map(params![project_id], |row| {
+            Ok(ChatSession {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                project_id: row.get(2)?,
+                persona_id: row.get(3)?,
+                selection: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(sessions)
+    }
+    
+    pub fn set_chat_session_selection(&self, session_id: &str, selection: Option<&str>) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        let updated = conn.execute(
+            "UPDATE chat_sessions SET selection = ?2 WHERE id = ?1",
+            params![session_id, selection],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Chat session not found");
+        }
+        Ok(())
+    }
+    
+    pub fn set_chat_session_persona(&self, session_id: &str, persona_id: Option<&str>) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        let updated = conn.execute(
+            "UPDATE chat_sessions SET persona_id = ?2 WHERE id = ?1",
+            params![session_id, persona_id],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Chat session not found");
+        }
+        Ok(())
+    }
+    
+    pub fn delete_chat_session(&self, session_id: &str) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("DELETE FROM chat_messages WHERE session_id = ?1", params![session_id])?;
+        conn.execute("DELETE FROM chat_summaries WHERE session_id = ?1", params![session_id])?;
+        conn.execute("DELETE FROM chat_sessions WHERE id = ?1", params![session_id])?;
+        Ok(())
+    }
+    
+    pub fn get_chat_summary(&self, session_id: &str) -> Result<Option<ChatSummary>> {
+        let conn = Connection::open(&self.db_path)?;
+        let summary = conn.query_row(
+            "SELECT session_id, summary, through_message_id, updated_at FROM chat_summaries WHERE session_id = ?1",
+            params![session_id],
+            |row| {
+                Ok(ChatSummary {
+                    session_id: row.get(0)?,
+                    summary: row.get(1)?,
+                    through_message_id: row.get(2)?,
+                    updated_at: row.get(3)?,
+                })
+            },
+        ).optional()?;
+        Ok(summary)
+    }
+    
+    pub fn save_chat_summary(&self, summary: &ChatSummary) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO chat_summaries (session_id, summary, through_message_id, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![summary.session_id, summary.summary, summary.through_message_id, summary.updated_at],
+        )?;
+        Ok(())
+    }
+    
+    pub fn add_chat_message(&self, message: &ChatMessage) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        conn.execute(
+            "INSERT INTO chat_messages (id, session_id, parent_id, role, content, context, model, pinned, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                message.id,
+                message.session_id,
+                message.parent_id,
+                message.role,
+                message.content,
+                message.context,
+                message.model,
+                message.pinned,
+                message.created_at,
+            ],
+        )?;
+        conn.execute(
+            "UPDATE chat_sessions SET updated_at = ?2 WHERE id = ?1",
+            params![message.session_id, message.created_at],
+        )?;
+        
+        Ok(())
+    }
+    
+    /// Messages of a session across all branches, in the order they were sent
+    pub fn get_chat_messages(&self, session_id: &str) -> Result<Vec<ChatMessage>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, parent_id, role, content, context, model, pinned, created_at
+             FROM chat_messages
+             WHERE session_id = ?1
+             ORDER BY created_at ASC, rowid ASC"
+        )?;
+        
+        let messages = stmt.query_map(params![session_id], chat_message_from_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(messages)
+    }
+    
+    pub fn get_chat_message(&self, message_id: &str) -> Result<Option<ChatMessage>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let message = conn.query_row(
+            "SELECT id, session_id, parent_id, role, content, context, model, pinned, created_at
+             FROM chat_messages WHERE id = ?1",
+            params![message_id],
+            chat_message_from_row,
+        ).optional()?;
+        
+        Ok(message)
+    }
+    
+    pub fn set_chat_message_pinned(&self, message_id: &str, pinned: bool) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("UPDATE chat_messages SET pinned = ?2 WHERE id = ?1", params![message_id, pinned])?;
+        Ok(())
+    }
+    
+    pub fn delete_chat_messages(&self, message_ids: &[String]) -> Result<()> {
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        for id in message_ids {
+            tx.execute("DELETE FROM chat_messages WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    // Note Methods
+    
+    pub fn add_note(&self, note: &Note) -> Result<i64> {
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        
+        tx.execute(
+            "INSERT INTO notes (project_id, file_path, start_line, end_line, title, content, tags, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                note.project_id,
+                note.file_path,
+                note.start_line,
+                note.end_line,
+                note.title,
+                note.content,
+                note.tags,
+                note.created_at,
+                note.updated_at,
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.execute(
+            "INSERT INTO notes_fts (rowid, title, content, tags) VALUES (?1, ?2, ?3, ?4)",
+            params![id, note.title, note.content, note.tags],
+        )?;
+        tx.commit()?;
+        
+        Ok(id)
+    }
+    
+    pub fn update_note(&self, note: &Note) -> Result<()> {
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        
+        let updated = tx.execute(
+            "UPDATE notes SET file_path = ?2, start_line = ?3, end_line = ?4, title = ?5, content = ?6,
+                              tags = ?7, updated_at = ?8
+             WHERE id = ?1",
+            params![
+                note.id,
+                note.file_path,
+                note.start_line,
+                note.end_line,
+                note.title,
+                note.content,
+                note.tags,
+                note.updated_at,
+            ],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Note not found");
+        }
+        tx.execute("DELETE FROM notes_fts WHERE rowid = ?1", params![note.id])?;
+        tx.execute(
+            "INSERT INTO notes_fts (rowid, title, content, tags) VALUES (?1, ?2, ?3, ?4)",
+            params![note.id, note.title, note.content, note.tags],
+        )?;
+        tx.commit()?;
+        
+        Ok(())
+    }
+    
+    pub fn get_note(&self, note_id: i64) -> Result<Option<Note>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let note = conn.query_row(
+            "SELECT id, project_id, file_path, start_line, end_line, title, content, tags, created_at, updated_at
+             FROM notes WHERE id = ?1",
+            params![note_id],
+            note_from_row,
+        ).optional()?;
+        
+        Ok(note)
+    }
+    
+    /// A project's notes, optionally only those on one file
+    pub fn list_notes(&self, project_id: &str, file_path: Option<&str>) -> Result<Vec<Note>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, file_path, start_line, end_line, title, content, tags, created_at, updated_at
+             FROM notes
+             WHERE project_id = ?1 AND (?2 IS NULL OR file_path = ?2)
+             ORDER BY file_path IS NOT NULL, file_path, start_line, updated_at DESC"
+        )?;
+        
+        let notes = stmt.query_map(params![project_id, file_path], note_from_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(notes)
+    }
+    
+    /// `fts_query` is an FTS5 expression; title hits outrank content hits
+    pub fn search_notes(&self, fts_query: &str, project_id: Option<&str>, limit: usize) -> Result<Vec<NoteSearchResult>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT n.id, n.project_id, n.file_path, n.start_line, n.end_line, n.title, n.content, n.tags,
+                    n.created_at, n.updated_at, snippet(notes_fts, -1, '[', ']', '…', 16)
+             FROM notes_fts JOIN notes n ON n.id = notes_fts.rowid
+             WHERE notes_fts MATCH ?1 AND (?2 IS NULL OR n.project_id = ?2)
+             ORDER BY bm25(notes_fts, 10.0, 1.0, 5.0)
+             LIMIT ?3"
+        )?;
+        
+        let results = stmt.query_map(params![fts_query, project_id, limit as i64], |row| {
+            Ok(NoteSearchResult {
+                note: note_from_row(row)?,
+                snippet: row.get(10)?,
+            })
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(results)
+    }
+    
+    pub fn delete_note(&self, note_id: i64) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("DELETE FROM notes_fts WHERE rowid = ?1", params![note_id])?;
+        conn.execute("DELETE FROM notes WHERE id = ?1", params![note_id])?;
+        Ok(())
+    }
+    
+    // Task Board Methods
+    
+    pub fn list_board_columns(&self, project_id: &str) -> Result<Vec<BoardColumn>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, name, position FROM board_columns
+             WHERE project_id = ?1 ORDER BY position, id"
+        )?;
+        
+        let columns = stmt.query_map(params![project_id], |row| {
+            Ok(BoardColumn {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                name: row.get(2)?,
+                position: row.get(3)?,
+            })
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(columns)
+    }
+    
+    pub fn add_board_column(&self, column: &BoardColumn) -> Result<i64> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "INSERT INTO board_columns (project_id, name, position) VALUES (?1, ?2, ?3)",
+            params![column.project_id, column.name, column.position],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+    
+    pub fn update_board_column(&self, column_id: i64, name: &str, position: i64) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        let updated = conn.execute(
+            "UPDATE board_columns SET name = ?2, position = ?3 WHERE id = ?1",
+            params![column_id, name, position],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Board column not found");
+        }
+        Ok(())
+    }
+    
+    /// Deletes the column's cards too
+    pub fn delete_board_column(&self, column_id: i64) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("DELETE FROM board_cards WHERE column_id = ?1", params![column_id])?;
+        conn.execute("DELETE FROM board_columns WHERE id = ?1", params![column_id])?;
+        Ok(())
+    }
+    
+    pub fn list_board_cards(&self, project_id: &str) -> Result<Vec<BoardCard>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, column_id, title, description, labels, due_date, links, position, source,
+                    created_at, updated_at
+             FROM board_cards WHERE project_id = ?1 ORDER BY column_id, position, id"
+        )?;
+        
+        let cards = stmt.query_map(params![project_id], board_card_from_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(cards)
+    }
+    
+    pub fn get_board_card(&self, card_id: i64) -> Result<Option<BoardCard>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let card = conn.query_row(
+            "SELECT id, project_id, column_id, title, description, labels, due_date, links, position, source,
+                    created_at, updated_at
+             FROM board_cards WHERE id = ?1",
+            params![card_id],
+            board_card_from_row,
+        ).optional()?;
+        
+        Ok(card)
+    }
+    
+    pub fn add_board_card(&self, card: &BoardCard) -> Result<i64> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        conn.execute(
+            "INSERT INTO board_cards (project_id, column_id, title, description, labels, due_date, links, position,
+                                      source, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                card.project_id,
+                card.column_id,
+                card.title,
+                card.description,
+                card.labels,
+                card.due_date,
+                card.links,
+                card.position,
+                card.source,
+                card.created_at,
+                card.updated_at,
+            ],
+        )?;
+        
+        Ok(conn.last_insert_rowid())
+    }
+    
+    pub fn update_board_card(&self, card: &BoardCard) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let updated = conn.execute(
+            "UPDATE board_cards SET column_id = ?2, title = ?3, description = ?4, labels = ?5, due_date = ?6,
+                                    links = ?7, position = ?8, updated_at = ?9
+             WHERE id = ?1",
+            params![
+                card.id,
+                card.column_id,
+                card.title,
+                card.description,
+                card.labels,
+                card.due_date,
+                card.links,
+                card.position,
+                card.updated_at,
+            ],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Board card not found");
+        }
+        
+        Ok(())
+    }
+    
+    pub fn set_board_card_position(&self, card_id: i64, column_id: i64, position: i64) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "UPDATE board_cards SET column_id = ?2, position = ?3 WHERE id = ?1",
+            params![card_id, column_id, position],
+        )?;
+        Ok(())
+    }
+    
+    pub fn delete_board_card(&self, card_id: i64) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("DELETE FROM board_cards WHERE id = ?1", params![card_id])?;
+        Ok(())
+    }
+    
     // Utility methods
     
     pub fn vacuum(&self) -> Result<()> {
@@ -478,14 +1795,205 @@ impl DatabaseManager {
         let metadata = std::fs::metadata(&self.db_path)?;
         Ok(metadata.len())
     }
+    
+    /// Run SQLite's integrity check; an empty list means the database is healthy
+    pub fn integrity_check(&self) -> Result<Vec<String>> {
+        let conn = Connection::open(&self.db_path)?;
+        let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        
+        let problems: Vec<String> = rows
+            .collect::<SqlResult<Vec<_>>>()?
+            .into_iter()
+            .filter(|line| line != "ok")
+            .collect();
+        Ok(problems)
+    }
+    
+    // Project Statistics Cache Methods
+    
+    pub fn get_file_stats(&self, project_path: &str) -> Result<Vec<FileStatsEntry>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT file_path, modified, size, language, code, comments, blanks
+             FROM file_stats_cache WHERE project_path = ?1"
+        )?;
+        
+        let entries = stmt.query_map(params![project_path], |row| {
+            Ok(FileStatsEntry {
+                file_path: row.get(0)?,
+                modified: row.get(1)?,
+                size: row.get(2)?,
+                language: row.get(3)?,
+                code: row.get(4)?,
+                comments: row.get(5)?,
+                blanks: row.get(6)?,
+            })
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+        
+        Ok(entries)
+    }
+    
+    /// Replace the cached rows for a project in one transaction
+    pub fn save_file_stats(&self, project_path: &str, entries: &[FileStatsEntry]) -> Result<()> {
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        
+        tx.execute("DELETE FROM file_stats_cache WHERE project_path = ?1", params![project_path])?;
+        
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO file_stats_cache
+                 (project_path, file_path, modified, size, language, code, comments, blanks)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+            )?;
+            
+            for entry in entries {
+                stmt.execute(params![
+                    project_path,
+                    entry.file_path,
+                    entry.modified,
+                    entry.size,
+                    entry.language,
+                    entry.code,
+                    entry.comments,
+                    entry.blanks,
+                ])?;
+            }
+        }
+        
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// Last processed commit and the accumulated git statistics JSON
+    pub fn get_git_stats(&self, project_path: &str) -> Result<Option<(String, String)>> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        let cached = conn.query_row(
+            "SELECT last_commit, data FROM git_stats_cache WHERE project_path = ?1",
+            params![project_path],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?;
+        
+        Ok(cached)
+    }
+    
+    pub fn save_git_stats(&self, project_path: &str, last_commit: &str, data: &str) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO git_stats_cache (project_path, last_commit, data, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![project_path, last_commit, data, Utc::now().to_rfc3339()],
+        )?;
+        
+        Ok(())
+    }
+}
+
+fn work_session_from_row(row: &rusqlite::Row) -> SqlResult<WorkSession> {
+    Ok(WorkSession {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        feature: row.get(2)?,
+        started_at: row.get(3)?,
+        last_activity_at: row.get(4)?,
+        ended_at: row.get(5)?,
+        active_seconds: row.get(6)?,
+    })
+}
+
+fn note_from_row(row: &rusqlite::Row) -> SqlResult<Note> {
+    Ok(Note {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        file_path: row.get(2)?,
+        start_line: row.get(3)?,
+        end_line: row.get(4)?,
+        title: row.get(5)?,
+        content: row.get(6)?,
+        tags: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+fn board_card_from_row(row: &rusqlite::Row) -> SqlResult<BoardCard> {
+    Ok(BoardCard {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        column_id: row.get(2)?,
+        title: row.get(3)?,
+        description: row.get(4)?,
+        labels: row.get(5)?,
+        due_date: row.get(6)?,
+        links: row.get(7)?,
+        position: row.get(8)?,
+        source: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+fn agent_memory_from_row(row: &rusqlite::Row) -> SqlResult<AgentMemory> {
+    Ok(AgentMemory {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        kind: row.get(2)?,
+        content: row.get(3)?,
+        source: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn prompt_experiment_from_row(row: &rusqlite::Row) -> SqlResult<PromptExperiment> {
+    Ok(PromptExperiment {
+        id: row.get(0)?,
+        prompt: row.get(1)?,
+        results: row.get(2)?,
+        preference: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+fn chat_message_from_row(row: &rusqlite::Row) -> SqlResult<ChatMessage> {
+    Ok(ChatMessage {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        parent_id: row.get(2)?,
+        role: row.get(3)?,
+        content: row.get(4)?,
+        context: row.get(5)?,
+        model: row.get(6)?,
+        pinned: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+/// Databases created by older versions lack columns added since; add them in place
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<SqlResult<Vec<_>>>()?
+        .iter()
+        .any(|name| name == column);
+    
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
 }
 
 // Tauri commands
 
 #[tauri::command]
 pub async fn init_database() -> Result<(), String> {
-    let manager = DatabaseManager::new().map_err(|e| e.to_string())?;
-    manager.initialize().map_err(|e| e.to_string())
+    open().map(|_| ()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -502,6 +2010,9 @@ pub async fn get_project_history(project_id: String, limit: i32) -> Result<Vec<P
 
 #[tauri::command]
 pub async fn index_file_content(project_id: String, file_path: String, content: String) -> Result<(), String> {
+    if crate::ignore_files::is_path_ignored(std::path::Path::new(&file_path)) {
+        return Ok(());
+    }
     let manager = DatabaseManager::new().map_err(|e| e.to_string())?;
     manager.index_file(&project_id, &file_path, &content).map_err(|e| e.to_string())
 }
@@ -525,7 +2036,10 @@ pub async fn get_all_usage_stats() -> Result<Vec<UsageStats>, String> {
 }
 
 #[tauri::command]
-pub async fn create_bookmark(bookmark: Bookmark) -> Result<i64, String> {
+pub async fn create_bookmark(mut bookmark: Bookmark) -> Result<i64, String> {
+    if bookmark.anchor.is_none() {
+        bookmark.anchor = crate::bookmarks::anchor_for_bookmark(&bookmark);
+    }
     let manager = DatabaseManager::new().map_err(|e| e.to_string())?;
     manager.add_bookmark(&bookmark).map_err(|e| e.to_string())
 }
@@ -566,6 +2080,28 @@ pub async fn delete_workspace_session(name: String) -> Result<(), String> {
     manager.delete_session(&name).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn search_terminal_history(
+    query: String,
+    project_id: Option<String>,
+    limit: Option<i32>,
+) -> Result<Vec<TerminalHistoryEntry>, String> {
+    let manager = DatabaseManager::new().map_err(|e| e.to_string())?;
+    manager.search_terminal_history(&query, project_id.as_deref(), limit.unwrap_or(100))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_terminal_suggestions(
+    prefix: String,
+    project_id: Option<String>,
+    limit: Option<i32>,
+) -> Result<Vec<CommandSuggestion>, String> {
+    let manager = DatabaseManager::new().map_err(|e| e.to_string())?;
+    manager.get_command_suggestions(&prefix, project_id.as_deref(), limit.unwrap_or(10))
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_database_size() -> Result<u64, String> {
     let manager = DatabaseManager::new().map_err(|e| e.to_string())?;
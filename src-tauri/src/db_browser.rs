@@ -0,0 +1,909 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use sqlx::mysql::{MySqlPool, MySqlPoolOptions};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::{Column, Row, TypeInfo};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::secrets;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DbKind {
+    Sqlite,
+    Postgres,
+    MySql,
+}
+
+/// Saved connection. The password lives in the OS credential store, not in this file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbConnectionProfile {
+    pub id: String,
+    pub name: String,
+    pub kind: DbKind,
+    pub file_path: Option<String>, // SQLite only
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub database: Option<String>,
+    pub username: Option<String>,
+    #[serde(default)]
+    pub has_password: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbColumn {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+    pub default_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbIndex {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbTable {
+    pub schema: Option<String>,
+    pub name: String,
+    pub columns: Vec<DbColumn>,
+    pub indexes: Vec<DbIndex>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbSchema {
+    pub connection_id: String,
+    pub tables: Vec<DbTable>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbQueryResult {
+    pub query_id: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub rows_affected: Option<u64>,
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(Clone)]
+enum DbPool {
+    Sqlite(SqlitePool),
+    Postgres(PgPool),
+    MySql(MySqlPool),
+}
+
+/// Column names, rows, and rows affected for writes
+type QueryOutput = (Vec<String>, Vec<Vec<serde_json::Value>>, Option<u64>);
+
+// Open pools keyed by connection id
+static DB_POOLS: Mutex<Option<HashMap<String, DbPool>>> = Mutex::new(None);
+
+/// How to stop a statement on the server; aborting the client task alone leaves it running there
+enum ServerCancel {
+    Postgres(PgPool, i32),
+    MySql(MySqlPool, u64),
+}
+
+impl ServerCancel {
+    async fn cancel(&self) -> Result<()> {
+        match self {
+            ServerCancel::Postgres(pool, pid) => {
+                sqlx::query("SELECT pg_cancel_backend($1)").bind(pid).execute(pool).await?;
+            }
+            ServerCancel::MySql(pool, connection_id) => {
+                sqlx::query(&format!("KILL QUERY {}", connection_id)).execute(pool).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Filled in once the query has a connection. SQLite runs in-process, so aborting is enough.
+type ServerSlot = std::sync::Arc<Mutex<Option<ServerCancel>>>;
+
+struct RunningQuery {
+    abort: tokio::task::AbortHandle,
+    server: ServerSlot,
+}
+
+// In-flight queries keyed by query id, so they can be cancelled
+static RUNNING_QUERIES: Mutex<Option<HashMap<String, RunningQuery>>> = Mutex::new(None);
+
+fn secret_key(connection_id: &str) -> String {
+    format!("db-connection:{}", connection_id)
+}
+
+/// Keywords that make a statement write, wherever they appear in it
+const WRITE_KEYWORDS: &[&str] = &[
+    "insert", "update", "delete", "merge", "replace", "into", "create", "drop", "alter", "truncate", "grant", "revoke",
+];
+
+/// `sql` with string literals, quoted identifiers and comments blanked out, split
+/// into statements on the semicolons that remain. Empty statements are dropped.
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                // Doubled quotes inside a literal close and reopen it, which is harmless here
+                for next in chars.by_ref() {
+                    if next == c {
+                        break;
+                    }
+                }
+                current.push(' ');
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+                current.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+                current.push(' ');
+            }
+            ';' => statements.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    statements.push(current);
+
+    statements.into_iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+/// Whether a single statement only reads data. Anything else, including several
+/// statements at once, is treated as mutating.
+pub fn is_read_only_statement(sql: &str) -> bool {
+    let statements = split_statements(sql);
+    let [statement] = statements.as_slice() else { return false };
+
+    let words: Vec<String> = statement
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let writes = || words.iter().any(|w| WRITE_KEYWORDS.contains(&w.as_str()));
+
+    match words.first().map(String::as_str) {
+        // SELECT ... INTO creates a table in Postgres; WITH may wrap a data-modifying CTE
+        Some("select" | "with" | "values" | "table") => !writes(),
+        Some("show" | "describe" | "desc") => true,
+        // EXPLAIN ANALYZE runs the statement it explains
+        Some("explain") if words.iter().any(|w| w == "analyze" || w == "analyse") => !writes(),
+        Some("explain") => true,
+        // `PRAGMA name = value` and `PRAGMA name(value)` both change settings, except for
+        // the introspection pragmas that take a table or index name
+        Some("pragma") => {
+            let introspection = words.get(1).is_some_and(|name| {
+                matches!(name.as_str(), "table_info" | "table_xinfo" | "index_list" | "index_info" | "index_xinfo" | "foreign_key_list")
+            });
+            !statement.contains('=') && (introspection || !statement.contains('('))
+        }
+        _ => false,
+    }
+}
+
+/// Like `is_read_only_statement`, but SQLite connections also ask SQLite itself,
+/// which knows about side effects no keyword check can see
+pub async fn statement_is_read_only(connection_id: &str, sql: &str) -> Result<bool> {
+    if !is_read_only_statement(sql) {
+        return Ok(false);
+    }
+
+    let profile = DbConnectionManager::new()?.get_profile(connection_id)?;
+    let (DbKind::Sqlite, Some(path)) = (&profile.kind, profile.file_path) else { return Ok(true) };
+    let sql = sql.to_string();
+    tokio::task::spawn_blocking(move || -> Result<bool> {
+        let conn = rusqlite::Connection::open_with_flags(&path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let read_only = conn.prepare(sql.trim().trim_end_matches(';'))?.readonly();
+        Ok(read_only)
+    })
+    .await?
+}
+
+fn encode_url_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+pub struct DbConnectionManager {
+    profiles_path: PathBuf,
+}
+
+impl DbConnectionManager {
+    pub fn new() -> Result<Self> {
+        let app_dir = dirs::data_dir()
+            .context("Failed to get data directory")?
+            .join(".sai-ide");
+
+        std::fs::create_dir_all(&app_dir)?;
+
+        Ok(Self {
+            profiles_path: app_dir.join("db_connections.json"),
+        })
+    }
+
+    pub fn list_profiles(&self) -> Result<Vec<DbConnectionProfile>> {
+        if !self.profiles_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = std::fs::read_to_string(&self.profiles_path)?;
+        Ok(serde_json::from_str(&json).unwrap_or_default())
+    }
+
+    pub fn get_profile(&self, connection_id: &str) -> Result<DbConnectionProfile> {
+        self.list_profiles()?
+            .into_iter()
+            .find(|p| p.id == connection_id)
+            .context("Database connection not found")
+    }
+
+    /// Save a profile. `password: None` keeps the stored password unchanged.
+    pub fn save_profile(&self, mut profile: DbConnectionProfile, password: Option<String>) -> Result<DbConnectionProfile> {
+        if profile.id.is_empty() {
+            profile.id = uuid::Uuid::new_v4().to_string();
+        }
+
+        let mut profiles = self.list_profiles()?;
+        let existing = profiles.iter().find(|p| p.id == profile.id).map(|p| p.has_password);
+
+        match password {
+            Some(password) if !password.is_empty() => {
+                secrets::store_secret(&secret_key(&profile.id), &password)?;
+                profile.has_password = true;
+            }
+            Some(_) => {
+                secrets::delete_secret(&secret_key(&profile.id))?;
+                profile.has_password = false;
+            }
+            None => profile.has_password = existing.unwrap_or(false),
+        }
+
+        profiles.retain(|p| p.id != profile.id);
+        profiles.push(profile.clone());
+        std::fs::write(&self.profiles_path, serde_json::to_string_pretty(&profiles)?)?;
+
+        // Settings may have changed; reconnect on next use
+        with_pools(|pools| pools.remove(&profile.id));
+
+        Ok(profile)
+    }
+
+    pub fn delete_profile(&self, connection_id: &str) -> Result<()> {
+        let mut profiles = self.list_profiles()?;
+        profiles.retain(|p| p.id != connection_id);
+        std::fs::write(&self.profiles_path, serde_json::to_string_pretty(&profiles)?)?;
+
+        secrets::delete_secret(&secret_key(connection_id))?;
+        with_pools(|pools| pools.remove(connection_id));
+        Ok(())
+    }
+
+    fn connection_url(&self, profile: &DbConnectionProfile) -> Result<String> {
+        let scheme = match profile.kind {
+            DbKind::Postgres => "postgres",
+            DbKind::MySql => "mysql",
+            DbKind::Sqlite => anyhow::bail!("SQLite connections use a file path"),
+        };
+
+        let password = if profile.has_password {
+            secrets::get_secret(&secret_key(&profile.id))?
+        } else {
+            None
+        };
+
+        let credentials = match (&profile.username, password) {
+            (Some(user), Some(pass)) => format!("{}:{}@", encode_url_component(user), encode_url_component(&pass)),
+            (Some(user), None) => format!("{}@", encode_url_component(user)),
+            _ => String::new(),
+        };
+
+        let default_port = if profile.kind == DbKind::Postgres { 5432 } else { 3306 };
+
+        Ok(format!(
+            "{}://{}{}:{}/{}",
+            scheme,
+            credentials,
+            profile.host.as_deref().unwrap_or("localhost"),
+            profile.port.unwrap_or(default_port),
+            profile.database.as_deref().unwrap_or("")
+        ))
+    }
+
+    async fn pool(&self, connection_id: &str) -> Result<DbPool> {
+        if let Some(pool) = with_pools(|pools| pools.get(connection_id).cloned()) {
+            return Ok(pool);
+        }
+
+        let profile = self.get_profile(connection_id)?;
+
+        let pool = match profile.kind {
+            DbKind::Sqlite => {
+                let path = profile.file_path.as_deref().context("SQLite connection has no file path")?;
+                let options = SqliteConnectOptions::new().filename(path).create_if_missing(false);
+                DbPool::Sqlite(SqlitePoolOptions::new().max_connections(2).connect_with(options).await?)
+            }
+            DbKind::Postgres => {
+                let url = self.connection_url(&profile)?;
+                // One connection to spare so a cancel can get through while a query runs
+                DbPool::Postgres(PgPoolOptions::new().max_connections(3).connect(&url).await?)
+            }
+            DbKind::MySql => {
+                let url = self.connection_url(&profile)?;
+                DbPool::MySql(MySqlPoolOptions::new().max_connections(3).connect(&url).await?)
+            }
+        };
+
+        with_pools(|pools| pools.insert(connection_id.to_string(), pool.clone()));
+        Ok(pool)
+    }
+}
+
+fn with_pools<T>(f: impl FnOnce(&mut HashMap<String, DbPool>) -> T) -> T {
+    let mut pools = DB_POOLS.lock().unwrap();
+    f(pools.get_or_insert_with(HashMap::new))
+}
+
+fn with_running<T>(f: impl FnOnce(&mut HashMap<String, RunningQuery>) -> T) -> T {
+    let mut running = RUNNING_QUERIES.lock().unwrap();
+    f(running.get_or_insert_with(HashMap::new))
+}
+
+/// Decode a column into JSON by trying the common Rust types in turn. `decimal` adds
+/// NUMERIC/DECIMAL, which SQLite has no decoder for; it's kept as a string to keep precision.
+macro_rules! decode_value {
+    ($row:expr, $idx:expr, decimal) => {{
+        let row = $row;
+        let idx = $idx;
+        match row.try_get::<Option<rust_decimal::Decimal>, _>(idx) {
+            Ok(v) => v.map(|d| serde_json::Value::from(d.to_string())).unwrap_or(serde_json::Value::Null),
+            Err(_) => decode_value!(row, idx),
+        }
+    }};
+    ($row:expr, $idx:expr) => {{
+        use serde_json::Value;
+        let row = $row;
+        let idx = $idx;
+
+        if let Ok(v) = row.try_get::<Option<i64>, _>(idx) {
+            v.map(Value::from).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<i32>, _>(idx) {
+            v.map(Value::from).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<f64>, _>(idx) {
+            v.map(Value::from).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<bool>, _>(idx) {
+            v.map(Value::from).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<String>, _>(idx) {
+            v.map(Value::from).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<chrono::NaiveDateTime>, _>(idx) {
+            v.map(|d| Value::from(d.to_string())).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<chrono::NaiveDate>, _>(idx) {
+            v.map(|d| Value::from(d.to_string())).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>(idx) {
+            v.map(|d| Value::from(d.to_rfc3339())).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<uuid::Uuid>, _>(idx) {
+            v.map(|u| Value::from(u.to_string())).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<sqlx::types::Json<Value>>, _>(idx) {
+            v.map(|json| json.0).unwrap_or(Value::Null)
+        } else if let Ok(v) = row.try_get::<Option<Vec<u8>>, _>(idx) {
+            v.map(|b| Value::from(format!("<{} bytes>", b.len()))).unwrap_or(Value::Null)
+        } else {
+            Value::from(format!("<{}>", row.columns()[idx].type_info().name()))
+        }
+    }};
+}
+
+macro_rules! rows_to_json {
+    ($rows:expr $(, $decimal:ident)?) => {{
+        let rows = $rows;
+        let columns: Vec<String> = rows
+            .first()
+            .map(|r| r.columns().iter().map(|c| c.name().to_string()).collect())
+            .unwrap_or_default();
+
+        let values: Vec<Vec<serde_json::Value>> = rows
+            .iter()
+            .map(|row| (0..row.columns().len()).map(|i| decode_value!(row, i $(, $decimal)?)).collect())
+            .collect();
+
+        (columns, values)
+    }};
+}
+
+impl DbPool {
+    async fn fetch(&self, sql: &str) -> Result<(Vec<String>, Vec<Vec<serde_json::Value>>)> {
+        Ok(match self {
+            DbPool::Sqlite(pool) => rows_to_json!(sqlx::query(sql).fetch_all(pool).await?),
+            DbPool::Postgres(pool) => rows_to_json!(sqlx::query(sql).fetch_all(pool).await?, decimal),
+            DbPool::MySql(pool) => rows_to_json!(sqlx::query(sql).fetch_all(pool).await?, decimal),
+        })
+    }
+
+    /// Run one statement on a connection of its own, recording in `server` how to
+    /// cancel it there. Reads return rows; writes return rows affected.
+    async fn run(&self, sql: &str, read: bool, server: &ServerSlot) -> Result<QueryOutput> {
+        macro_rules! run_on {
+            ($conn:expr $(, $decimal:ident)?) => {
+                if read {
+                    let (columns, rows) = rows_to_json!(sqlx::query(sql).fetch_all($conn).await? $(, $decimal)?);
+                    (columns, rows, None)
+                } else {
+                    (Vec::new(), Vec::new(), Some(sqlx::query(sql).execute($conn).await?.rows_affected()))
+                }
+            };
+        }
+
+        Ok(match self {
+            DbPool::Sqlite(pool) => run_on!(pool),
+            DbPool::Postgres(pool) => {
+                let mut conn = pool.acquire().await?;
+                let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()").fetch_one(&mut *conn).await?;
+                *server.lock().unwrap() = Some(ServerCancel::Postgres(pool.clone(), pid));
+                run_on!(&mut *conn, decimal)
+            }
+            DbPool::MySql(pool) => {
+                let mut conn = pool.acquire().await?;
+                let id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()").fetch_one(&mut *conn).await?;
+                *server.lock().unwrap() = Some(ServerCancel::MySql(pool.clone(), id));
+                run_on!(&mut *conn, decimal)
+            }
+        })
+    }
+
+    async fn introspect(&self) -> Result<Vec<DbTable>> {
+        match self {
+            DbPool::Sqlite(pool) => introspect_sqlite(pool).await,
+            DbPool::Postgres(pool) => introspect_postgres(pool).await,
+            DbPool::MySql(pool) => introspect_mysql(pool).await,
+        }
+    }
+}
+
+async fn introspect_sqlite(pool: &SqlitePool) -> Result<Vec<DbTable>> {
+    let names: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut tables = Vec::new();
+
+    for name in names {
+        let quoted = name.replace('"', "\"\"");
+
+        let columns = sqlx::query(&format!("PRAGMA table_info(\"{}\")", quoted))
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| DbColumn {
+                name: row.get("name"),
+                data_type: row.get("type"),
+                nullable: row.get::<i64, _>("notnull") == 0,
+                primary_key: row.get::<i64, _>("pk") > 0,
+                default_value: row.get("dflt_value"),
+            })
+            .collect();
+
+        let mut indexes = Vec::new();
+        for index in sqlx::query(&format!("PRAGMA index_list(\"{}\")", quoted)).fetch_all(pool).await? {
+            let index_name: String = index.get("name");
+            let index_columns = sqlx::query(&format!("PRAGMA index_info(\"{}\")", index_name.replace('"', "\"\"")))
+                .fetch_all(pool)
+                .await?
+                .iter()
+                .filter_map(|row| row.get::<Option<String>, _>("name")) // None for expression indexes
+                .collect();
+
+            indexes.push(DbIndex {
+                name: index_name,
+                columns: index_columns,
+                unique: index.get::<i64, _>("unique") != 0,
+            });
+        }
+
+        tables.push(DbTable {
+            schema: None,
+            name,
+            columns,
+            indexes,
+        });
+    }
+
+    Ok(tables)
+}
+
+async fn introspect_postgres(pool: &PgPool) -> Result<Vec<DbTable>> {
+    // information_schema uses domain types sqlx can't decode directly, hence the ::text casts
+    let column_rows = sqlx::query(
+        r#"SELECT c.table_schema::text AS table_schema, c.table_name::text AS table_name,
+                  c.column_name::text AS column_name, c.data_type::text AS data_type,
+                  c.is_nullable::text AS is_nullable, c.column_default::text AS column_default,
+                  EXISTS (
+                      SELECT 1 FROM information_schema.table_constraints tc
+                      JOIN information_schema.key_column_usage k
+                        ON tc.constraint_name = k.constraint_name AND tc.table_schema = k.table_schema
+                      WHERE tc.constraint_type = 'PRIMARY KEY'
+                        AND k.table_schema = c.table_schema AND k.table_name = c.table_name
+                        AND k.column_name = c.column_name
+                  ) AS is_pk
+           FROM information_schema.columns c
+           JOIN information_schema.tables t
+             ON t.table_schema = c.table_schema AND t.table_name = c.table_name
+           WHERE t.table_type = 'BASE TABLE'
+             AND c.table_schema NOT IN ('pg_catalog', 'information_schema')
+           ORDER BY c.table_schema, c.table_name, c.ordinal_position"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let index_rows = sqlx::query(
+        r#"SELECT n.nspname::text AS table_schema, t.relname::text AS table_name,
+                  i.relname::text AS index_name, ix.indisunique AS is_unique,
+                  array_agg(a.attname::text ORDER BY a.attnum) AS columns
+           FROM pg_index ix
+           JOIN pg_class t ON t.oid = ix.indrelid
+           JOIN pg_class i ON i.oid = ix.indexrelid
+           JOIN pg_namespace n ON n.oid = t.relnamespace
+           JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY(ix.indkey)
+           WHERE n.nspname NOT IN ('pg_catalog', 'information_schema', 'pg_toast')
+           GROUP BY n.nspname, t.relname, i.relname, ix.indisunique"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut tables: Vec<DbTable> = Vec::new();
+
+    for row in column_rows {
+        let schema: String = row.get("table_schema");
+        let name: String = row.get("table_name");
+
+        let column = DbColumn {
+            name: row.get("column_name"),
+            data_type: row.get("data_type"),
+            nullable: row.get::<String, _>("is_nullable") == "YES",
+            primary_key: row.get("is_pk"),
+            default_value: row.get("column_default"),
+        };
+
+        match tables.iter_mut().find(|t| t.schema.as_deref() == Some(schema.as_str()) && t.name == name) {
+            Some(table) => table.columns.push(column),
+            None => tables.push(DbTable {
+                schema: Some(schema),
+                name,
+                columns: vec![column],
+                indexes: Vec::new(),
+            }),
+        }
+    }
+
+    for row in index_rows {
+        let schema: String = row.get("table_schema");
+        let name: String = row.get("table_name");
+
+        if let Some(table) = tables.iter_mut().find(|t| t.schema.as_deref() == Some(schema.as_str()) && t.name == name) {
+            table.indexes.push(DbIndex {
+                name: row.get("index_name"),
+                columns: row.get("columns"),
+                unique: row.get("is_unique"),
+            });
+        }
+    }
+
+    Ok(tables)
+}
+
+async fn introspect_mysql(pool: &MySqlPool) -> Result<Vec<DbTable>> {
+    let column_rows = sqlx::query(
+        r#"SELECT CAST(c.TABLE_NAME AS CHAR) AS table_name, CAST(c.COLUMN_NAME AS CHAR) AS column_name,
+                  CAST(c.COLUMN_TYPE AS CHAR) AS data_type, CAST(c.IS_NULLABLE AS CHAR) AS is_nullable,
+                  CAST(c.COLUMN_DEFAULT AS CHAR) AS column_default, CAST(c.COLUMN_KEY AS CHAR) AS column_key
+           FROM information_schema.COLUMNS c
+           JOIN information_schema.TABLES t
+             ON t.TABLE_SCHEMA = c.TABLE_SCHEMA AND t.TABLE_NAME = c.TABLE_NAME
+           WHERE c.TABLE_SCHEMA = DATABASE() AND t.TABLE_TYPE = 'BASE TABLE'
+           ORDER BY c.TABLE_NAME, c.ORDINAL_POSITION"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let index_rows = sqlx::query(
+        r#"SELECT CAST(TABLE_NAME AS CHAR) AS table_name, CAST(INDEX_NAME AS CHAR) AS index_name,
+                  CAST(COLUMN_NAME AS CHAR) AS column_name, NON_UNIQUE AS non_unique
+           FROM information_schema.STATISTICS
+           WHERE TABLE_SCHEMA = DATABASE()
+           ORDER BY TABLE_NAME, INDEX_NAME, SEQ_IN_INDEX"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut tables: Vec<DbTable> = Vec::new();
+
+    for row in column_rows {
+        let name: String = row.get("table_name");
+        let column = DbColumn {
+            name: row.get("column_name"),
+            data_type: row.get("data_type"),
+            nullable: row.get::<String, _>("is_nullable") == "YES",
+            primary_key: row.get::<String, _>("column_key") == "PRI",
+            default_value: row.get("column_default"),
+        };
+
+        match tables.iter_mut().find(|t| t.name == name) {
+            Some(table) => table.columns.push(column),
+            None => tables.push(DbTable {
+                schema: None,
+                name,
+                columns: vec![column],
+                indexes: Vec::new(),
+            }),
+        }
+    }
+
+    for row in index_rows {
+        let table_name: String = row.get("table_name");
+        let index_name: String = row.get("index_name");
+        let column: String = row.get("column_name");
+
+        if let Some(table) = tables.iter_mut().find(|t| t.name == table_name) {
+            match table.indexes.iter_mut().find(|i| i.name == index_name) {
+                Some(index) => index.columns.push(column),
+                None => table.indexes.push(DbIndex {
+                    name: index_name,
+                    columns: vec![column],
+                    unique: row.get::<i64, _>("non_unique") == 0,
+                }),
+            }
+        }
+    }
+
+    Ok(tables)
+}
+
+pub async fn get_schema(connection_id: &str) -> Result<DbSchema> {
+    let manager = DbConnectionManager::new()?;
+    let pool = manager.pool(connection_id).await?;
+
+    Ok(DbSchema {
+        connection_id: connection_id.to_string(),
+        tables: pool.introspect().await?,
+    })
+}
+
+/// Run a statement as a cancellable task. Reads are paginated; writes report rows affected.
+pub async fn run_query(connection_id: &str, sql: &str, page: u32, page_size: u32, query_id: String) -> Result<DbQueryResult> {
+    let manager = DbConnectionManager::new()?;
+    let pool = manager.pool(connection_id).await?;
+
+    let sql = sql.trim().trim_end_matches(';').to_string();
+    let read_only = is_read_only_statement(&sql);
+    let paginate = read_only && matches!(sql.split_whitespace().next().map(|w| w.to_lowercase()).as_deref(), Some("select") | Some("with"));
+
+    let started = Instant::now();
+
+    let server = ServerSlot::default();
+    let slot = server.clone();
+    let task = tokio::spawn(async move {
+        let statement = if paginate {
+            // One extra row tells us whether there's a next page
+            format!(
+                "SELECT * FROM ({}) AS paged_query LIMIT {} OFFSET {}",
+                sql,
+                page_size + 1,
+                page as u64 * page_size as u64
+            )
+        } else {
+            sql
+        };
+        pool.run(&statement, read_only, &slot).await
+    });
+
+    with_running(|running| running.insert(query_id.clone(), RunningQuery { abort: task.abort_handle(), server }));
+    let outcome = task.await;
+    with_running(|running| running.remove(&query_id));
+
+    let (columns, mut rows, rows_affected) = match outcome {
+        Ok(result) => result?,
+        Err(e) if e.is_cancelled() => anyhow::bail!("Query cancelled"),
+        Err(e) => return Err(e.into()),
+    };
+
+    let has_more = paginate && rows.len() > page_size as usize;
+    rows.truncate(page_size as usize);
+
+    Ok(DbQueryResult {
+        query_id,
+        columns,
+        rows,
+        rows_affected,
+        page,
+        page_size,
+        has_more,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+fn csv_field(value: &serde_json::Value) -> String {
+    let text = match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    if text.contains(|c: char| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+pub fn to_csv(columns: &[String], rows: &[Vec<serde_json::Value>]) -> String {
+    let mut csv = columns.iter().map(|c| csv_field(&serde_json::Value::from(c.as_str()))).collect::<Vec<_>>().join(",");
+    csv.push('\n');
+
+    for row in rows {
+        csv.push_str(&row.iter().map(csv_field).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+pub fn to_json(columns: &[String], rows: &[Vec<serde_json::Value>]) -> Result<String> {
+    let objects: Vec<serde_json::Map<String, serde_json::Value>> = rows
+        .iter()
+        .map(|row| columns.iter().cloned().zip(row.iter().cloned()).collect())
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&objects)?)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_db_connections() -> Result<Vec<DbConnectionProfile>, String> {
+    let manager = DbConnectionManager::new().map_err(|e| e.to_string())?;
+    manager.list_profiles().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_db_connection(profile: DbConnectionProfile, password: Option<String>) -> Result<DbConnectionProfile, String> {
+    let manager = DbConnectionManager::new().map_err(|e| e.to_string())?;
+    manager.save_profile(profile, password).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_db_connection(connection_id: String) -> Result<(), String> {
+    let manager = DbConnectionManager::new().map_err(|e| e.to_string())?;
+    manager.delete_profile(&connection_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn test_db_connection(connection_id: String) -> Result<(), String> {
+    let manager = DbConnectionManager::new().map_err(|e| e.to_string())?;
+    let pool = manager.pool(&connection_id).await.map_err(|e| e.to_string())?;
+    pool.fetch("SELECT 1").await.map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_db_schema(connection_id: String) -> Result<DbSchema, String> {
+    get_schema(&connection_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn execute_db_query(
+    connection_id: String,
+    sql: String,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    query_id: Option<String>,
+) -> Result<DbQueryResult, String> {
+    let query_id = query_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+
+    run_query(&connection_id, &sql, page.unwrap_or(0), page_size, query_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn cancel_db_query(query_id: String) -> Result<bool, String> {
+    let Some(query) = with_running(|running| running.remove(&query_id)) else {
+        return Ok(false);
+    };
+    let server = query.server.lock().unwrap().take();
+    if let Some(server) = server {
+        if let Err(e) = server.cancel().await {
+            tracing::warn!("Failed to cancel query {} on the server: {}", query_id, e);
+        }
+    }
+    query.abort.abort();
+    Ok(true)
+}
+
+/// Export a full result set as `csv` or `json`; returns the number of rows written
+#[tauri::command]
+pub async fn export_db_query(
+    connection_id: String,
+    sql: String,
+    format: String,
+    output_path: String,
+) -> Result<usize, String> {
+    if !is_read_only_statement(&sql) {
+        return Err("Only read queries can be exported".to_string());
+    }
+
+    let manager = DbConnectionManager::new().map_err(|e| e.to_string())?;
+    let pool = manager.pool(&connection_id).await.map_err(|e| e.to_string())?;
+    let (columns, rows) = pool.fetch(sql.trim().trim_end_matches(';')).await.map_err(|e| e.to_string())?;
+
+    let content = match format.to_lowercase().as_str() {
+        "csv" => to_csv(&columns, &rows),
+        "json" => to_json(&columns, &rows).map_err(|e| e.to_string())?,
+        other => return Err(format!("Unsupported export format: {}", other)),
+    };
+
+    std::fs::write(&output_path, content).map_err(|e| e.to_string())?;
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_read_only_statement() {
+        assert!(is_read_only_statement("  SELECT * FROM users"));
+        assert!(is_read_only_statement("with t as (select 1) select * from t"));
+        assert!(!is_read_only_statement("DELETE FROM users"));
+        assert!(!is_read_only_statement("update users set name = 'x'"));
+
+        assert!(is_read_only_statement("SELECT ';' AS semi; -- trailing comment"));
+        assert!(!is_read_only_statement("SELECT 1; DROP TABLE t"));
+        assert!(!is_read_only_statement("EXPLAIN ANALYZE DELETE FROM users"));
+        assert!(is_read_only_statement("EXPLAIN SELECT * FROM users"));
+        assert!(!is_read_only_statement("PRAGMA journal_mode = WAL"));
+        assert!(!is_read_only_statement("pragma foreign_keys(off)"));
+        assert!(is_read_only_statement("PRAGMA table_info(\"users\")"));
+        assert!(!is_read_only_statement("WITH gone AS (DELETE FROM users RETURNING *) SELECT * FROM gone"));
+        assert!(!is_read_only_statement("SELECT * INTO backup FROM users"));
+        assert_eq!(split_statements("select 'a;b'; /* ; */ select 2;"), vec!["select", "select 2"]);
+    }
+
+    #[test]
+    fn test_to_csv_escapes_fields() {
+        let columns = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![vec![serde_json::json!(1), serde_json::json!("a, \"b\"")]];
+        assert_eq!(to_csv(&columns, &rows), "id,note\n1,\"a, \"\"b\"\"\"\n");
+    }
+}
@@ -0,0 +1,401 @@
+use serde::{Deserialize, Serialize};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use crate::documents::read_document;
+use crate::filesystem::collect_source_files;
+
+const EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Files that are loaded by tooling or frameworks rather than imported
+const CONVENTIONAL_ENTRYPOINTS: &[&str] = &[
+    "src/main", "src/index", "src/App", "index", "main", "server", "src/server",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnusedExport {
+    pub file: String,
+    pub name: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadCodeReport {
+    pub files_scanned: usize,
+    pub entrypoints: Vec<String>,
+    pub unreachable_files: Vec<String>,
+    pub unused_exports: Vec<UnusedExport>,
+    /// Unreachable files whose name is not mentioned anywhere else in the project
+    pub safe_delete_candidates: Vec<String>,
+}
+
+/// One file in the project's import graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphModule {
+    pub file: String,
+    pub exports: Vec<String>,
+    /// Project files this one imports; packages are left out
+    pub imports: Vec<String>,
+}
+
+/// What one import statement takes from a module
+#[derive(Debug, Clone)]
+enum ImportedNames {
+    /// Namespace, `export *`, require() or dynamic import: every export counts as used
+    All,
+    Named(Vec<String>),
+    SideEffect,
+}
+
+#[derive(Debug, Clone)]
+struct ModuleInfo {
+    imports: Vec<(String, ImportedNames)>,
+    /// Export name -> line
+    exports: Vec<(String, usize)>,
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+fn named_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|s| s.trim().trim_start_matches("type ").trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.split(" as ").next().unwrap_or(s).trim().to_string())
+        .collect()
+}
+
+fn parse_module(content: &str) -> ModuleInfo {
+    let import_re = Regex::new(r#"(?m)^\s*import\s+(?:type\s+)?([\s\S]*?)\s+from\s+['"]([^'"]+)['"]"#).unwrap();
+    let side_effect_re = Regex::new(r#"(?m)^\s*import\s+['"]([^'"]+)['"]"#).unwrap();
+    let reexport_re = Regex::new(r#"(?m)^\s*export\s+(?:type\s+)?(\*(?:\s+as\s+\w+)?|\{[^}]*\})\s+from\s+['"]([^'"]+)['"]"#).unwrap();
+    let dynamic_re = Regex::new(r#"(?:require|import)\(\s*['"]([^'"]+)['"]\s*\)"#).unwrap();
+    let decl_re = Regex::new(
+        r"(?m)^\s*export\s+(default\s+)?(?:declare\s+)?(?:async\s+)?(?:abstract\s+)?(?:function\*?|class|const|let|var|interface|type|enum)\s+([A-Za-z_$][\w$]*)"
+    ).unwrap();
+    let default_re = Regex::new(r"(?m)^\s*export\s+default\s").unwrap();
+    let list_re = Regex::new(r"(?m)^\s*export\s+(?:type\s+)?\{([^}]*)\}\s*;?\s*$").unwrap();
+
+    let mut info = ModuleInfo { imports: Vec::new(), exports: Vec::new() };
+
+    for c in import_re.captures_iter(content) {
+        let clause = c[1].trim();
+        let names = if clause.contains("* as") {
+            ImportedNames::All
+        } else {
+            let mut names = Vec::new();
+            let head = clause.split('{').next().unwrap_or("").trim().trim_end_matches(',').trim();
+            if !head.is_empty() {
+                names.push("default".to_string());
+            }
+            if let (Some(open), Some(close)) = (clause.find('{'), clause.rfind('}')) {
+                names.extend(named_list(&clause[open + 1..close]));
+            }
+            ImportedNames::Named(names)
+        };
+        info.imports.push((c[2].to_string(), names));
+    }
+
+    for c in side_effect_re.captures_iter(content) {
+        info.imports.push((c[1].to_string(), ImportedNames::SideEffect));
+    }
+
+    for c in reexport_re.captures_iter(content) {
+        let clause = &c[1];
+        if clause.starts_with('*') {
+            info.imports.push((c[2].to_string(), ImportedNames::All));
+            if let Some(alias) = clause.split(" as ").nth(1) {
+                info.exports.push((alias.trim().to_string(), line_of(content, c.get(0).unwrap().start())));
+            }
+        } else {
+            let inner = clause.trim_matches(|ch| ch == '{' || ch == '}');
+            info.imports.push((c[2].to_string(), ImportedNames::Named(named_list(inner))));
+
+            let line = line_of(content, c.get(0).unwrap().start());
+            for part in inner.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let exported = part.rsplit(" as ").next().unwrap_or(part).trim();
+                info.exports.push((exported.to_string(), line));
+            }
+        }
+    }
+
+    for c in dynamic_re.captures_iter(content) {
+        info.imports.push((c[1].to_string(), ImportedNames::All));
+    }
+
+    for c in decl_re.captures_iter(content) {
+        let name = if c.get(1).is_some() { "default".to_string() } else { c[2].to_string() };
+        info.exports.push((name, line_of(content, c.get(0).unwrap().start())));
+    }
+
+    if !info.exports.iter().any(|(n, _)| n == "default") {
+        if let Some(m) = default_re.find(content) {
+            info.exports.push(("default".to_string(), line_of(content, m.start())));
+        }
+    }
+
+    for c in list_re.captures_iter(content) {
+        let line = line_of(content, c.get(0).unwrap().start());
+        for part in c[1].split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let exported = part.rsplit(" as ").next().unwrap_or(part).trim();
+            info.exports.push((exported.to_string(), line));
+        }
+    }
+
+    info
+}
+
+/// Resolve a relative or `@/` specifier to a project file
+fn resolve(from: &Path, specifier: &str, project_path: &Path, files: &HashSet<PathBuf>) -> Option<PathBuf> {
+    let base = if specifier.starts_with('.') {
+        from.parent()?.join(specifier)
+    } else if let Some(rest) = specifier.strip_prefix("@/").or_else(|| specifier.strip_prefix("~/")) {
+        project_path.join("src").join(rest)
+    } else {
+        return None;
+    };
+
+    let base = normalize(&base);
+
+    // ESM TypeScript imports `./x.js` for `./x.ts`
+    let stripped = base.to_string_lossy()
+        .trim_end_matches(".js")
+        .trim_end_matches(".mjs")
+        .to_string();
+
+    let mut candidates = vec![base.clone()];
+    for stem in [base.to_string_lossy().to_string(), stripped] {
+        for ext in EXTENSIONS {
+            candidates.push(PathBuf::from(format!("{}.{}", stem, ext)));
+            candidates.push(PathBuf::from(&stem).join(format!("index.{}", ext)));
+        }
+    }
+
+    candidates.into_iter().find(|c| files.contains(c))
+}
+
+/// Collapse `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+fn is_tooling_file(relative: &str) -> bool {
+    let name = relative.rsplit('/').next().unwrap_or(relative);
+    name.contains(".config.")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || name.contains(".stories.")
+        || name.ends_with(".d.ts")
+        || relative.contains("__tests__/")
+        || relative.contains("__mocks__/")
+}
+
+/// Next.js, Remix and SvelteKit load route files by convention
+fn is_route_file(relative: &str) -> bool {
+    ["pages/", "app/", "src/pages/", "src/app/", "src/routes/", "app/routes/"]
+        .iter()
+        .any(|prefix| relative.starts_with(prefix))
+}
+
+fn package_entrypoints(project_path: &Path) -> Vec<String> {
+    let Some(json) = std::fs::read_to_string(project_path.join("package.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut entries = Vec::new();
+    let mut collect = |value: &serde_json::Value| {
+        let mut stack = vec![value.clone()];
+        while let Some(v) = stack.pop() {
+            match v {
+                serde_json::Value::String(s) => entries.push(s.trim_start_matches("./").to_string()),
+                serde_json::Value::Object(map) => stack.extend(map.into_iter().map(|(_, v)| v)),
+                serde_json::Value::Array(items) => stack.extend(items),
+                _ => {}
+            }
+        }
+    };
+
+    for field in ["main", "module", "types", "bin", "exports", "source"] {
+        collect(&json[field]);
+    }
+    entries
+}
+
+pub fn analyze(project_path: &Path, extra_entrypoints: &[String]) -> DeadCodeReport {
+    let paths = collect_source_files(project_path, EXTENSIONS);
+    let file_set: HashSet<PathBuf> = paths.iter().cloned().collect();
+    let relative = |p: &Path| p.strip_prefix(project_path).unwrap_or(p).to_string_lossy().replace('\\', "/");
+
+    let mut contents: HashMap<PathBuf, String> = HashMap::new();
+    let mut modules: HashMap<PathBuf, ModuleInfo> = HashMap::new();
+    for path in &paths {
+        let content = read_document(path).unwrap_or_default();
+        modules.insert(path.clone(), parse_module(&content));
+        contents.insert(path.clone(), content);
+    }
+
+    // Resolved edges: importer -> (target, names)
+    let mut edges: HashMap<PathBuf, Vec<(PathBuf, ImportedNames)>> = HashMap::new();
+    for (path, info) in &modules {
+        let resolved = info.imports
+            .iter()
+            .filter_map(|(spec, names)| resolve(path, spec, project_path, &file_set).map(|t| (t, names.clone())))
+            .collect();
+        edges.insert(path.clone(), resolved);
+    }
+
+    // Entrypoints: package.json fields, explicit ones, conventions, routes and tooling files
+    let declared: Vec<String> = package_entrypoints(project_path).into_iter().chain(extra_entrypoints.iter().cloned()).collect();
+    let entrypoints: BTreeSet<PathBuf> = paths
+        .iter()
+        .filter(|p| {
+            let rel = relative(p);
+            let stem = rel.rsplit_once('.').map(|(s, _)| s).unwrap_or(&rel);
+            declared.iter().any(|d| d == &rel || d.rsplit_once('.').map(|(s, _)| s) == Some(stem))
+                || CONVENTIONAL_ENTRYPOINTS.contains(&stem)
+                || is_route_file(&rel)
+                || is_tooling_file(&rel)
+        })
+        .cloned()
+        .collect();
+
+    let mut reachable: HashSet<PathBuf> = entrypoints.iter().cloned().collect();
+    let mut queue: VecDeque<PathBuf> = entrypoints.iter().cloned().collect();
+    while let Some(path) = queue.pop_front() {
+        for (target, _) in edges.get(&path).into_iter().flatten() {
+            if reachable.insert(target.clone()) {
+                queue.push_back(target.clone());
+            }
+        }
+    }
+
+    // Names each file's exports are imported under; None means all of them
+    let mut used: HashMap<PathBuf, Option<HashSet<String>>> = HashMap::new();
+    for targets in edges.values() {
+        for (target, names) in targets {
+            let entry = used.entry(target.clone()).or_insert_with(|| Some(HashSet::new()));
+            match names {
+                ImportedNames::All => *entry = None,
+                ImportedNames::Named(list) => {
+                    if let Some(set) = entry {
+                        set.extend(list.iter().cloned());
+                    }
+                }
+                ImportedNames::SideEffect => {}
+            }
+        }
+    }
+
+    let mut unused_exports = Vec::new();
+    for path in &paths {
+        // Entrypoint exports are public API; unreachable files are reported as a whole
+        if entrypoints.contains(path) || !reachable.contains(path) {
+            continue;
+        }
+        let Some(used_names) = used.get(path).cloned().unwrap_or_else(|| Some(HashSet::new())) else { continue };
+
+        for (name, line) in &modules[path].exports {
+            if !used_names.contains(name) {
+                unused_exports.push(UnusedExport { file: relative(path), name: name.clone(), line: *line });
+            }
+        }
+    }
+
+    let unreachable: Vec<&PathBuf> = paths.iter().filter(|p| !reachable.contains(*p)).collect();
+
+    let safe_delete_candidates = unreachable
+        .iter()
+        .filter(|p| {
+            let stem = p.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            // "index" is too common a word to search for
+            stem != "index" && !contents.iter().any(|(other, content)| other.as_path() != p.as_path() && content.contains(&stem))
+        })
+        .map(|p| relative(p))
+        .collect();
+
+    DeadCodeReport {
+        files_scanned: paths.len(),
+        entrypoints: entrypoints.iter().map(|p| relative(p)).collect(),
+        unreachable_files: unreachable.iter().map(|p| relative(p)).collect(),
+        unused_exports,
+        safe_delete_candidates,
+    }
+}
+
+/// Resolved import graph of the TS/JS files in a project, with project-relative paths
+pub fn module_graph(project_path: &Path) -> Vec<GraphModule> {
+    let paths = collect_source_files(project_path, EXTENSIONS);
+    let file_set: HashSet<PathBuf> = paths.iter().cloned().collect();
+    let relative = |p: &Path| p.strip_prefix(project_path).unwrap_or(p).to_string_lossy().replace('\\', "/");
+
+    paths
+        .iter()
+        .map(|path| {
+            let info = parse_module(&read_document(path).unwrap_or_default());
+            let imports: BTreeSet<String> = info.imports
+                .iter()
+                .filter_map(|(spec, _)| resolve(path, spec, project_path, &file_set))
+                .filter(|target| target != path)
+                .map(|target| relative(&target))
+                .collect();
+            let exports: BTreeSet<String> = info.exports.into_iter().map(|(name, _)| name).collect();
+
+            GraphModule {
+                file: relative(path),
+                exports: exports.into_iter().collect(),
+                imports: imports.into_iter().collect(),
+            }
+        })
+        .collect()
+}
+
+// Tauri commands
+
+/// Import-graph analysis of a TS/JS project. `entrypoints` adds project-relative
+/// files to the ones detected from package.json and framework conventions.
+#[tauri::command]
+pub async fn find_dead_code(project_path: String, entrypoints: Option<Vec<String>>) -> Result<DeadCodeReport, String> {
+    tokio::task::spawn_blocking(move || analyze(Path::new(&project_path), &entrypoints.unwrap_or_default()))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dead_code_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+
+        std::fs::write(src.join("main.ts"), "import { format } from './utils';\nconsole.log(format(1));\n").unwrap();
+        std::fs::write(src.join("utils.ts"), "export function format(n: number) { return `${n}`; }\nexport const unusedHelper = 1;\n").unwrap();
+        std::fs::write(src.join("orphan.ts"), "export const legacy = true;\n").unwrap();
+
+        let report = analyze(dir.path(), &[]);
+        assert_eq!(report.entrypoints, vec!["src/main.ts"]);
+        assert_eq!(report.unreachable_files, vec!["src/orphan.ts"]);
+        assert_eq!(report.safe_delete_candidates, vec!["src/orphan.ts"]);
+
+        assert_eq!(report.unused_exports.len(), 1);
+        assert_eq!(report.unused_exports[0].name, "unusedHelper");
+        assert_eq!(report.unused_exports[0].line, 2);
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Breakpoint {
@@ -21,6 +21,9 @@ pub struct DebugSession {
     pub status: DebugStatus,
     pub breakpoints: Vec<Breakpoint>,
     pub current_frame: Option<StackFrame>,
+    /// Innermost frame first; filled in when the adapter reports a stop
+    #[serde(default)]
+    pub stack: Vec<StackFrame>,
     pub variables: HashMap<String, VariableValue>,
 }
 
@@ -59,6 +62,9 @@ pub struct DebugConfiguration {
     pub args: Vec<String>,
     pub cwd: String,
     pub env: HashMap<String, String>,
+    /// Interpreter for Python configurations, as in VS Code's `python` launch option
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub python: Option<String>,
 }
 
 pub struct DebugManager {
@@ -117,6 +123,7 @@ impl DebugManager {
             status: DebugStatus::Idle,
             breakpoints: Vec::new(),
             current_frame: None,
+            stack: Vec::new(),
             variables: HashMap::new(),
         };
         self.sessions.insert(session_id.clone(), session);
@@ -151,6 +158,26 @@ impl DebugManager {
         Ok(())
     }
     
+    /// Record where the adapter stopped: the call stack and the locals of the top frame
+    pub fn set_paused_state(
+        &mut self,
+        session_id: &str,
+        stack: Vec<StackFrame>,
+        variables: HashMap<String, VariableValue>,
+    ) -> Result<()> {
+        let session = self.sessions.get_mut(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Debug session not found"))?;
+        session.status = DebugStatus::Paused;
+        session.current_frame = stack.first().cloned();
+        session.stack = stack;
+        session.variables = variables;
+        Ok(())
+    }
+    
+    pub fn get_session(&self, session_id: &str) -> Option<DebugSession> {
+        self.sessions.get(session_id).cloned()
+    }
+    
     // Step Controls
     
     pub fn step_over(&mut self, session_id: &str) -> Result<()> {
@@ -222,6 +249,7 @@ impl DebugManager {
                     args: vec![],
                     cwd: "${workspaceFolder}".to_string(),
                     env: HashMap::new(),
+                    python: None,
                 }
             ],
             "javascript" | "typescript" => vec![
@@ -233,6 +261,7 @@ impl DebugManager {
                     args: vec![],
                     cwd: "${workspaceFolder}".to_string(),
                     env: HashMap::new(),
+                    python: None,
                 }
             ],
             "python" => vec![
@@ -244,6 +273,7 @@ impl DebugManager {
                     args: vec![],
                     cwd: "${workspaceFolder}".to_string(),
                     env: HashMap::new(),
+                    python: None,
                 }
             ],
             _ => vec![],
@@ -263,6 +293,10 @@ fn get_debug_manager() -> &'static mut DebugManager {
     }
 }
 
+pub fn session(session_id: &str) -> Option<DebugSession> {
+    get_debug_manager().get_session(session_id)
+}
+
 // Tauri commands
 
 #[tauri::command]
@@ -291,6 +325,15 @@ pub async fn continue_debug(session_id: String) -> Result<(), String> {
     get_debug_manager().continue_debugging(&session_id).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn update_debug_state(
+    session_id: String,
+    stack: Vec<StackFrame>,
+    variables: HashMap<String, VariableValue>,
+) -> Result<(), String> {
+    get_debug_manager().set_paused_state(&session_id, stack, variables).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn debug_step_over(session_id: String) -> Result<(), String> {
     get_debug_manager().step_over(&session_id).map_err(|e| e.to_string())
@@ -347,7 +390,15 @@ pub async fn add_debug_configuration(config: DebugConfiguration) -> Result<(), S
     Ok(())
 }
 
+/// With a project, Python configurations use the project's selected interpreter
 #[tauri::command]
-pub async fn get_default_debug_configs(language: String) -> Result<Vec<DebugConfiguration>, String> {
-    Ok(get_debug_manager().create_default_configurations(&language))
+pub async fn get_default_debug_configs(language: String, project_path: Option<String>) -> Result<Vec<DebugConfiguration>, String> {
+    let mut configs = get_debug_manager().create_default_configurations(&language);
+    if let Some(project_path) = project_path {
+        let python = crate::python_envs::interpreter_for(Path::new(&project_path));
+        for config in configs.iter_mut().filter(|c| c.type_ == "python") {
+            config.python = python.as_ref().map(|p| p.to_string_lossy().to_string());
+        }
+    }
+    Ok(configs)
 }
@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use tree_sitter::Node;
+
+use crate::documents::read_document;
+use crate::filesystem::collect_source_files;
+use crate::llm::{LLMClient, GenerationRequest};
+use crate::syntax::{detect_language, parser_for, SUPPORTED_EXTENSIONS};
+
+const DEFAULT_MIN_TOKENS: usize = 50;
+
+// Winnowing window: one fingerprint is kept per this many k-grams
+const WINNOW_WINDOW: usize = 4;
+
+// Refactoring suggestions are only requested for the largest groups
+const MAX_SUGGESTIONS: usize = 5;
+
+const HASH_BASE: u64 = 1_000_003;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloneInstance {
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloneGroup {
+    pub token_count: usize,
+    pub line_count: usize,
+    pub instances: Vec<CloneInstance>,
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupReport {
+    pub files_scanned: usize,
+    pub min_tokens: usize,
+    pub groups: Vec<CloneGroup>,
+    /// Lines that appear in more than one place, counting every copy after the first
+    pub duplicated_lines: usize,
+}
+
+struct Token {
+    hash: u64,
+    line: usize,
+}
+
+/// Leaf tokens with identifiers and literals normalized, so renamed copies still match
+fn tokenize(path: &Path) -> Result<Vec<Token>> {
+    let language = detect_language(path).context("Unsupported file type")?;
+    let source = read_document(path)?;
+    let tree = parser_for(language)?.parse(&source, None).context("Failed to parse file")?;
+
+    let mut tokens = Vec::new();
+    let mut stack: Vec<Node> = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        let kind = node.kind();
+        if kind.contains("comment") {
+            continue;
+        }
+
+        if node.child_count() == 0 {
+            let normalized = if kind.ends_with("identifier") {
+                "$id"
+            } else if kind.contains("string") || kind.contains("number") || kind.contains("integer")
+                || kind.contains("float") || kind.contains("literal") {
+                "$lit"
+            } else {
+                node.utf8_text(source.as_bytes()).unwrap_or(kind)
+            };
+
+            let mut hasher = DefaultHasher::new();
+            normalized.hash(&mut hasher);
+            tokens.push(Token { hash: hasher.finish(), line: node.start_position().row + 1 });
+            continue;
+        }
+
+        // Push children in reverse so they pop in source order
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+
+    Ok(tokens)
+}
+
+/// Rolling hashes of every k-token window
+fn kgram_hashes(tokens: &[Token], k: usize) -> Vec<u64> {
+    if tokens.len() < k {
+        return Vec::new();
+    }
+
+    let high = (1..k).fold(1u64, |acc, _| acc.wrapping_mul(HASH_BASE));
+    let mut hash = tokens[..k].iter().fold(0u64, |acc, t| acc.wrapping_mul(HASH_BASE).wrapping_add(t.hash));
+    let mut hashes = vec![hash];
+
+    for i in k..tokens.len() {
+        hash = hash
+            .wrapping_sub(tokens[i - k].hash.wrapping_mul(high))
+            .wrapping_mul(HASH_BASE)
+            .wrapping_add(tokens[i].hash);
+        hashes.push(hash);
+    }
+
+    hashes
+}
+
+/// Winnowing: keep the rightmost minimum hash of each window as a fingerprint
+fn winnow(hashes: &[u64]) -> Vec<(u64, usize)> {
+    let mut fingerprints: Vec<(u64, usize)> = Vec::new();
+    let windows = if hashes.len() >= WINNOW_WINDOW {
+        hashes.len() - WINNOW_WINDOW + 1
+    } else {
+        usize::from(!hashes.is_empty())
+    };
+
+    for start in 0..windows {
+        let end = (start + WINNOW_WINDOW).min(hashes.len());
+        let (pos, hash) = hashes[start..end]
+            .iter()
+            .enumerate()
+            .rev()
+            .min_by_key(|(_, h)| **h)
+            .map(|(i, h)| (start + i, *h))
+            .unwrap();
+
+        if fingerprints.last().map(|(_, p)| *p) != Some(pos) {
+            fingerprints.push((hash, pos));
+        }
+    }
+
+    fingerprints
+}
+
+/// Group fingerprint matches into clone groups, merging overlapping runs
+fn find_clones(files: &[Vec<Token>], k: usize) -> Vec<Vec<(usize, usize, usize)>> {
+    let mut index: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+    for (file_idx, tokens) in files.iter().enumerate() {
+        for (hash, pos) in winnow(&kgram_hashes(tokens, k)) {
+            index.entry(hash).or_default().push((file_idx, pos));
+        }
+    }
+
+    let mut candidates: Vec<Vec<(usize, usize)>> = index
+        .into_values()
+        .map(|mut occurrences| {
+            occurrences.sort();
+            // Drop self-overlapping matches (e.g. long runs of repeated tokens)
+            occurrences.dedup_by(|b, a| a.0 == b.0 && b.1 < a.1 + k);
+            occurrences
+        })
+        .filter(|o| o.len() > 1)
+        .collect();
+    candidates.sort_by_key(|o| o[0]);
+
+    // Groups as (file, start token, end token); merged with the last group sharing the same files
+    let mut groups: Vec<Vec<(usize, usize, usize)>> = Vec::new();
+    let mut last_by_files: HashMap<Vec<usize>, usize> = HashMap::new();
+
+    for occurrences in candidates {
+        let files_key: Vec<usize> = occurrences.iter().map(|(f, _)| *f).collect();
+
+        if let Some(&group_idx) = last_by_files.get(&files_key) {
+            let group = &mut groups[group_idx];
+            let extends = group.iter().zip(&occurrences).all(|(g, o)| o.1 >= g.1 && o.1 <= g.2);
+            if extends {
+                for (g, o) in group.iter_mut().zip(&occurrences) {
+                    g.2 = g.2.max(o.1 + k);
+                }
+                continue;
+            }
+        }
+
+        last_by_files.insert(files_key, groups.len());
+        groups.push(occurrences.iter().map(|(f, p)| (*f, *p, p + k)).collect());
+    }
+
+    groups
+}
+
+pub fn analyze(project_path: &Path, min_tokens: usize) -> DedupReport {
+    let paths: Vec<PathBuf> = collect_source_files(project_path, SUPPORTED_EXTENSIONS);
+    let mut scanned = Vec::new();
+    let mut files = Vec::new();
+
+    for path in paths {
+        match tokenize(&path) {
+            Ok(tokens) => {
+                scanned.push(path);
+                files.push(tokens);
+            }
+            Err(e) => tracing::warn!("Skipping {:?}: {}", path, e),
+        }
+    }
+
+    let mut groups: Vec<CloneGroup> = find_clones(&files, min_tokens)
+        .into_iter()
+        .map(|ranges| {
+            let instances: Vec<CloneInstance> = ranges
+                .iter()
+                .map(|(file, start, end)| CloneInstance {
+                    file: scanned[*file].strip_prefix(project_path).unwrap_or(&scanned[*file]).to_string_lossy().to_string(),
+                    start_line: files[*file][*start].line,
+                    end_line: files[*file][end - 1].line,
+                })
+                .collect();
+
+            let (_, start, end) = ranges[0];
+            CloneGroup {
+                token_count: end - start,
+                line_count: instances[0].end_line - instances[0].start_line + 1,
+                instances,
+                suggestion: None,
+            }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| b.token_count.cmp(&a.token_count));
+
+    DedupReport {
+        files_scanned: scanned.len(),
+        min_tokens,
+        duplicated_lines: groups.iter().map(|g| g.line_count * (g.instances.len() - 1)).sum(),
+        groups,
+    }
+}
+
+fn read_lines(project_path: &Path, instance: &CloneInstance) -> String {
+    std::fs::read_to_string(project_path.join(&instance.file))
+        .unwrap_or_default()
+        .lines()
+        .skip(instance.start_line - 1)
+        .take(instance.end_line - instance.start_line + 1)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub async fn suggest_extraction(project_path: &Path, group: &CloneGroup) -> Result<String> {
+    let copies = group.instances
+        .iter()
+        .take(3)
+        .map(|i| format!("// {} (lines {}-{})\n{}", i.file, i.start_line, i.end_line, read_lines(project_path, i)))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let prompt = format!(
+        r#"These code blocks are duplicated across the project:
+
+{copies}
+
+Suggest how to remove the duplication:
+1. **Shared function** - Its signature and implementation, parameterizing what differs between copies
+2. **Location** - Which file or module should own it
+3. **Call sites** - How each copy changes to call it
+
+Keep the suggestion short and show code."#,
+        copies = copies,
+    );
+
+    let request = GenerationRequest {
+        model: "deepseek-coder-v2:16b".to_string(),
+        prompt,
+        system_prompt: Some("You are an expert at refactoring duplicated code into shared abstractions.".to_string()),
+        temperature: 0.3,
+        max_tokens: 2048,
+    };
+
+    let response = LLMClient::new().generate(request).await?;
+    Ok(response.text)
+}
+
+// Tauri commands
+
+/// Find duplicated blocks of at least `min_tokens` tokens. With `suggest_refactor`,
+/// the largest groups also get an "extract shared function" suggestion.
+#[tauri::command]
+pub async fn find_duplicate_code(
+    project_path: String,
+    min_tokens: Option<usize>,
+    suggest_refactor: Option<bool>,
+) -> Result<DedupReport, String> {
+    let min_tokens = min_tokens.unwrap_or(DEFAULT_MIN_TOKENS).max(10);
+    let path = PathBuf::from(&project_path);
+
+    let scan_path = path.clone();
+    let mut report = tokio::task::spawn_blocking(move || analyze(&scan_path, min_tokens))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if suggest_refactor.unwrap_or(false) {
+        for group in report.groups.iter_mut().take(MAX_SUGGESTIONS) {
+            match suggest_extraction(&path, group).await {
+                Ok(suggestion) => group.suggestion = Some(suggestion),
+                Err(e) => tracing::warn!("Failed to generate refactoring suggestion: {}", e),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_renamed_clone() {
+        let dir = tempfile::tempdir().unwrap();
+        let body = |name: &str, var: &str| format!(
+            "function {name}(items) {{\n  let {var} = 0;\n  for (const item of items) {{\n    if (item.active && item.price > 10) {{\n      {var} += item.price * item.quantity;\n    }}\n  }}\n  return {var} * 1.2;\n}}\n",
+            name = name, var = var
+        );
+
+        std::fs::write(dir.path().join("a.js"), body("orderTotal", "total")).unwrap();
+        std::fs::write(dir.path().join("b.js"), format!("const x = 1;\n\n{}", body("cartTotal", "sum"))).unwrap();
+        std::fs::write(dir.path().join("c.js"), "export const unrelated = () => 42;\n").unwrap();
+
+        let report = analyze(dir.path(), 20);
+        assert_eq!(report.files_scanned, 3);
+        assert_eq!(report.groups.len(), 1);
+
+        let group = &report.groups[0];
+        assert_eq!(group.instances.len(), 2);
+        assert_eq!(group.instances[0].file, "a.js");
+        assert_eq!(group.instances[1].start_line, 3);
+    }
+}
@@ -0,0 +1,848 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_edit::{DocumentMut, Item, TableLike, Value};
+
+use crate::llm::{LLMClient, GenerationRequest};
+use crate::package_manager;
+use crate::readonly::WriteError;
+
+// Changelogs are trimmed before they go into the prompt
+const MAX_CHANGELOG_CHARS: usize = 16_000;
+/// Registry lookups in flight at once
+const MAX_CONCURRENT_LOOKUPS: usize = 8;
+const CARGO_SECTIONS: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+lazy_static::lazy_static! {
+    /// A PEP 508 requirement with a version we can compare, e.g. `requests[socks]>=2.31`
+    static ref PEP508: Regex =
+        Regex::new(r#"^["']?([A-Za-z0-9_.-]+)(?:\[[^\]]*\])?\s*((?:==|>=|~=)\s*[0-9][^,;"'\s]*)"#).unwrap();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Ecosystem {
+    Npm,
+    Cargo,
+    PyPI,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum UpdateRisk {
+    /// Major bump, or a minor bump below 1.0
+    Major,
+    Minor,
+    Patch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclaredDependency {
+    pub name: String,
+    /// Requirement exactly as written in the manifest, e.g. `^1.2.0` or `>=2.0`
+    pub requirement: String,
+    pub ecosystem: Ecosystem,
+    pub manifest: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutdatedDependency {
+    pub name: String,
+    pub ecosystem: Ecosystem,
+    pub manifest: String,
+    pub requirement: String,
+    pub current: String,
+    pub latest: String,
+    pub risk: UpdateRisk,
+    pub repository: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OutdatedReport {
+    pub major: Vec<OutdatedDependency>,
+    pub minor: Vec<OutdatedDependency>,
+    pub patch: Vec<OutdatedDependency>,
+    /// Packages the registry lookup failed for
+    pub unresolved: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeResult {
+    pub updated: Vec<String>,
+    pub install_command: Vec<String>,
+    pub success: bool,
+    pub output: String,
+}
+
+struct RegistryInfo {
+    latest: String,
+    repository: Option<String>,
+}
+
+fn parse_package_json(path: &Path) -> Vec<DeclaredDependency> {
+    let Some(json) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut deps = Vec::new();
+    for section in ["dependencies", "devDependencies"] {
+        if let Some(map) = json[section].as_object() {
+            for (name, requirement) in map {
+                let Some(requirement) = requirement.as_str() else { continue };
+                // Skip workspace:, file:, git and URL dependencies
+                if !requirement.starts_with(|c: char| c.is_ascii_digit() || "^~>=".contains(c)) {
+                    continue;
+                }
+                deps.push(DeclaredDependency {
+                    name: name.clone(),
+                    requirement: requirement.to_string(),
+                    ecosystem: Ecosystem::Npm,
+                    manifest: path.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+    deps
+}
+
+/// Every dependency table in a Cargo manifest: top-level, per-target and the workspace's
+fn visit_cargo_tables(doc: &mut DocumentMut, visit: &mut dyn FnMut(&mut dyn TableLike)) {
+    for section in CARGO_SECTIONS {
+        if let Some(table) = doc.get_mut(section).and_then(Item::as_table_like_mut) {
+            visit(table);
+        }
+    }
+    if let Some(targets) = doc.get_mut("target").and_then(Item::as_table_like_mut) {
+        for (_, target) in targets.iter_mut() {
+            for section in CARGO_SECTIONS {
+                if let Some(table) = target.get_mut(section).and_then(Item::as_table_like_mut) {
+                    visit(table);
+                }
+            }
+        }
+    }
+    if let Some(table) = doc
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.get_mut("dependencies"))
+        .and_then(Item::as_table_like_mut)
+    {
+        visit(table);
+    }
+}
+
+/// The version requirement of a Cargo or Poetry entry: `"1.0"` or `{ version = "1.0", ... }`.
+/// `None` for path, git and `workspace = true` entries.
+fn version_value(entry: &mut Item) -> Option<&mut Value> {
+    if entry.is_str() {
+        return entry.as_value_mut();
+    }
+    entry
+        .as_table_like_mut()?
+        .get_mut("version")
+        .and_then(Item::as_value_mut)
+        .filter(|value| value.is_str())
+}
+
+/// Swap a string value, keeping its comments and spacing
+fn set_string(value: &mut Value, text: &str) {
+    let decor = value.decor().clone();
+    *value = Value::from(text);
+    *value.decor_mut() = decor;
+}
+
+fn parse_manifest(path: &Path) -> Option<DocumentMut> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match content.parse() {
+        Ok(doc) => Some(doc),
+        Err(e) => {
+            tracing::warn!("Failed to parse {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Entries of each `(name, requirement)` in a table of Cargo-style entries
+fn table_dependencies(table: &mut dyn TableLike, deps: &mut Vec<(String, String)>) {
+    for (name, entry) in table.iter_mut() {
+        if let Some(version) = version_value(entry).and_then(|v| v.as_str().map(str::to_string)) {
+            deps.push((name.get().to_string(), version));
+        }
+    }
+}
+
+fn parse_cargo_toml(path: &Path) -> Vec<DeclaredDependency> {
+    let Some(mut doc) = parse_manifest(path) else { return Vec::new() };
+    let mut found = Vec::new();
+    visit_cargo_tables(&mut doc, &mut |table| table_dependencies(table, &mut found));
+    found
+        .into_iter()
+        .map(|(name, requirement)| DeclaredDependency {
+            name,
+            requirement,
+            ecosystem: Ecosystem::Cargo,
+            manifest: path.to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+/// `[project]` dependency arrays: the main list and each optional group
+fn visit_pep508_arrays(doc: &mut DocumentMut, visit: &mut dyn FnMut(&mut toml_edit::Array)) {
+    let Some(project) = doc.get_mut("project").and_then(Item::as_table_like_mut) else { return };
+    if let Some(array) = project.get_mut("dependencies").and_then(Item::as_array_mut) {
+        visit(array);
+    }
+    if let Some(groups) = project.get_mut("optional-dependencies").and_then(Item::as_table_like_mut) {
+        for (_, group) in groups.iter_mut() {
+            if let Some(array) = group.as_array_mut() {
+                visit(array);
+            }
+        }
+    }
+}
+
+/// Poetry's dependency tables, which use Cargo-style entries
+fn visit_poetry_tables(doc: &mut DocumentMut, visit: &mut dyn FnMut(&mut dyn TableLike)) {
+    let Some(poetry) = doc
+        .get_mut("tool")
+        .and_then(|tool| tool.get_mut("poetry"))
+        .and_then(Item::as_table_like_mut)
+    else {
+        return;
+    };
+    for section in ["dependencies", "dev-dependencies"] {
+        if let Some(table) = poetry.get_mut(section).and_then(Item::as_table_like_mut) {
+            visit(table);
+        }
+    }
+    if let Some(groups) = poetry.get_mut("group").and_then(Item::as_table_like_mut) {
+        for (_, group) in groups.iter_mut() {
+            if let Some(table) = group.get_mut("dependencies").and_then(Item::as_table_like_mut) {
+                visit(table);
+            }
+        }
+    }
+}
+
+fn pep508_dependency(requirement: &str, path: &Path) -> Option<DeclaredDependency> {
+    let captures = PEP508.captures(requirement.trim())?;
+    Some(DeclaredDependency {
+        name: captures[1].to_string(),
+        requirement: captures[2].replace(' ', ""),
+        ecosystem: Ecosystem::PyPI,
+        manifest: path.to_string_lossy().to_string(),
+    })
+}
+
+fn parse_requirements_txt(path: &Path) -> Vec<DeclaredDependency> {
+    let Ok(content) = std::fs::read_to_string(path) else { return Vec::new() };
+    content.lines().filter_map(|line| pep508_dependency(line, path)).collect()
+}
+
+fn parse_pyproject(path: &Path) -> Vec<DeclaredDependency> {
+    let Some(mut doc) = parse_manifest(path) else { return Vec::new() };
+    let mut deps = Vec::new();
+    visit_pep508_arrays(&mut doc, &mut |array| {
+        deps.extend(array.iter().filter_map(|v| v.as_str()).filter_map(|r| pep508_dependency(r, path)));
+    });
+
+    let mut poetry = Vec::new();
+    visit_poetry_tables(&mut doc, &mut |table| table_dependencies(table, &mut poetry));
+    deps.extend(
+        poetry
+            .into_iter()
+            // Poetry lists the interpreter among the dependencies
+            .filter(|(name, _)| name != "python")
+            .map(|(name, requirement)| DeclaredDependency {
+                name,
+                requirement,
+                ecosystem: Ecosystem::PyPI,
+                manifest: path.to_string_lossy().to_string(),
+            }),
+    );
+    deps
+}
+
+pub fn declared_dependencies(project_path: &Path) -> Vec<DeclaredDependency> {
+    let mut deps = parse_package_json(&project_path.join("package.json"));
+    deps.extend(parse_cargo_toml(&project_path.join("Cargo.toml")));
+    deps.extend(parse_cargo_toml(&project_path.join("src-tauri").join("Cargo.toml")));
+    deps.extend(parse_requirements_txt(&project_path.join("requirements.txt")));
+    deps.extend(parse_pyproject(&project_path.join("pyproject.toml")));
+    deps
+}
+
+/// Numeric version a requirement resolves to at minimum: `^1.2` -> `1.2.0`
+fn requirement_version(requirement: &str) -> Option<String> {
+    let re = Regex::new(r"(\d+)(?:\.(\d+))?(?:\.(\d+))?").unwrap();
+    let c = re.captures(requirement)?;
+    Some(format!(
+        "{}.{}.{}",
+        &c[1],
+        c.get(2).map(|m| m.as_str()).unwrap_or("0"),
+        c.get(3).map(|m| m.as_str()).unwrap_or("0")
+    ))
+}
+
+fn parse_triplet(version: &str) -> Option<(u64, u64, u64)> {
+    let v = requirement_version(version)?;
+    let mut parts = v.split('.').map(|p| p.parse::<u64>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+pub fn classify_update(current: &str, latest: &str) -> Option<UpdateRisk> {
+    let (cur, new) = (parse_triplet(current)?, parse_triplet(latest)?);
+    if new <= cur {
+        return None;
+    }
+
+    Some(if new.0 != cur.0 || (cur.0 == 0 && new.1 != cur.1) {
+        UpdateRisk::Major
+    } else if new.1 != cur.1 {
+        UpdateRisk::Minor
+    } else {
+        UpdateRisk::Patch
+    })
+}
+
+async fn fetch_registry_info(client: &reqwest::Client, dep: &DeclaredDependency) -> Result<RegistryInfo> {
+    let url = match dep.ecosystem {
+        Ecosystem::Npm => format!("https://registry.npmjs.org/{}", dep.name),
+        Ecosystem::Cargo => format!("https://crates.io/api/v1/crates/{}", dep.name),
+        Ecosystem::PyPI => format!("https://pypi.org/pypi/{}/json", dep.name),
+    };
+
+    // crates.io rejects requests without a User-Agent
+    let response = client.get(&url)
+        .header("User-Agent", "LuciAI-Studio")
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("{} returned {}", url, response.status());
+    }
+
+    let json: serde_json::Value = response.json().await?;
+    let (latest, repository) = match dep.ecosystem {
+        Ecosystem::Npm => (
+            json["dist-tags"]["latest"].as_str(),
+            json["repository"]["url"].as_str().or_else(|| json["repository"].as_str()),
+        ),
+        Ecosystem::Cargo => (
+            json["crate"]["max_stable_version"].as_str(),
+            json["crate"]["repository"].as_str(),
+        ),
+        Ecosystem::PyPI => (
+            json["info"]["version"].as_str(),
+            json["info"]["project_urls"]["Source"].as_str()
+                .or_else(|| json["info"]["project_urls"]["Repository"].as_str())
+                .or_else(|| json["info"]["home_page"].as_str()),
+        ),
+    };
+
+    Ok(RegistryInfo {
+        latest: latest.context("Registry response has no latest version")?.to_string(),
+        repository: repository.map(String::from),
+    })
+}
+
+/// Versions pinned in package-lock.json: `packages` (lockfile v2+) or `dependencies` (v1)
+fn npm_locked_versions(lockfile: &Path) -> HashMap<String, Vec<String>> {
+    let Some(json) = std::fs::read_to_string(lockfile)
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+    else {
+        return HashMap::new();
+    };
+
+    let mut versions = HashMap::new();
+    if let Some(packages) = json["packages"].as_object() {
+        for (path, package) in packages {
+            // Only top-level installs; nested node_modules belong to other packages
+            let Some(name) = path.strip_prefix("node_modules/").filter(|n| !n.contains("/node_modules/")) else { continue };
+            if let Some(version) = package["version"].as_str() {
+                versions.insert(name.to_string(), vec![version.to_string()]);
+            }
+        }
+    } else if let Some(dependencies) = json["dependencies"].as_object() {
+        for (name, package) in dependencies {
+            if let Some(version) = package["version"].as_str() {
+                versions.insert(name.clone(), vec![version.to_string()]);
+            }
+        }
+    }
+    versions
+}
+
+/// Every version of each crate in Cargo.lock; one crate can be locked at several
+fn cargo_locked_versions(lockfile: &Path) -> HashMap<String, Vec<String>> {
+    let Ok(content) = std::fs::read_to_string(lockfile) else { return HashMap::new() };
+    let field = |line: &str, key: &str| {
+        line.strip_prefix(key)
+            .and_then(|rest| rest.trim_start().strip_prefix('='))
+            .map(|value| value.trim().trim_matches('"').to_string())
+    };
+
+    let mut versions: HashMap<String, Vec<String>> = HashMap::new();
+    let mut name = None;
+    for line in content.lines().map(str::trim) {
+        if line == "[[package]]" {
+            name = None;
+        } else if let Some(value) = field(line, "name") {
+            name = Some(value);
+        } else if let (Some(version), Some(name)) = (field(line, "version"), &name) {
+            versions.entry(name.clone()).or_default().push(version);
+        }
+    }
+    versions
+}
+
+/// Lockfile versions for the manifest's ecosystem, looked up next to the manifest and
+/// then at the project root, where a workspace keeps it. PyPI has no standard lockfile.
+fn locked_versions(project_path: &Path, manifest: &Path, ecosystem: &Ecosystem) -> HashMap<String, Vec<String>> {
+    let lockfile = match ecosystem {
+        Ecosystem::Npm => "package-lock.json",
+        Ecosystem::Cargo => "Cargo.lock",
+        Ecosystem::PyPI => return HashMap::new(),
+    };
+    let dirs = [manifest.parent().unwrap_or(project_path), project_path];
+    let Some(path) = dirs.iter().map(|dir| dir.join(lockfile)).find(|path| path.exists()) else {
+        return HashMap::new();
+    };
+    match ecosystem {
+        Ecosystem::Npm => npm_locked_versions(&path),
+        _ => cargo_locked_versions(&path),
+    }
+}
+
+/// The installed version: the newest locked one the requirement allows, or the
+/// requirement's floor without a lockfile
+fn installed_version(dep: &DeclaredDependency, locked: &HashMap<String, Vec<String>>) -> Option<String> {
+    let floor = requirement_version(&dep.requirement)?;
+    let compatible = |version: &&String| {
+        parse_triplet(version).is_some_and(|v| {
+            let f = parse_triplet(&floor).unwrap_or_default();
+            v >= f && !matches!(classify_update(&floor, version), Some(UpdateRisk::Major))
+        })
+    };
+    let newest = locked
+        .get(&dep.name)
+        .and_then(|versions| versions.iter().filter(compatible).max_by_key(|v| parse_triplet(v)))
+        .cloned();
+    Some(newest.unwrap_or(floor))
+}
+
+pub async fn check_outdated(project_path: &Path) -> Result<OutdatedReport> {
+    use futures::StreamExt;
+
+    let deps = declared_dependencies(project_path);
+    let client = crate::network::client();
+
+    let mut locks: HashMap<String, HashMap<String, Vec<String>>> = HashMap::new();
+    for dep in &deps {
+        locks
+            .entry(dep.manifest.clone())
+            .or_insert_with(|| locked_versions(project_path, Path::new(&dep.manifest), &dep.ecosystem));
+    }
+
+    let results: Vec<_> = futures::stream::iter(deps)
+        .map(|dep| {
+            let client = &client;
+            async move {
+                let result = fetch_registry_info(client, &dep).await;
+                (dep, result)
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_LOOKUPS)
+        .collect()
+        .await;
+
+    let mut report = OutdatedReport::default();
+
+    for (dep, result) in results {
+        let info = match result {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::warn!("Could not look up {}: {}", dep.name, e);
+                report.unresolved.push(dep.name);
+                continue;
+            }
+        };
+
+        let Some(current) = installed_version(&dep, &locks[&dep.manifest]) else { continue };
+        let Some(risk) = classify_update(&current, &info.latest) else { continue };
+
+        let outdated = OutdatedDependency {
+            name: dep.name,
+            ecosystem: dep.ecosystem,
+            manifest: dep.manifest,
+            requirement: dep.requirement,
+            current,
+            latest: info.latest,
+            risk: risk.clone(),
+            repository: info.repository,
+        };
+
+        match risk {
+            UpdateRisk::Major => report.major.push(outdated),
+            UpdateRisk::Minor => report.minor.push(outdated),
+            UpdateRisk::Patch => report.patch.push(outdated),
+        }
+    }
+
+    // Lookups finish in any order
+    for list in [&mut report.major, &mut report.minor, &mut report.patch] {
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    report.unresolved.sort();
+
+    Ok(report)
+}
+
+fn declares(line: &str, name: &str, ecosystem: &Ecosystem) -> bool {
+    let trimmed = line.trim().trim_start_matches(['"', '\'']);
+    let Some(rest) = trimmed.strip_prefix(name) else { return false };
+
+    match ecosystem {
+        Ecosystem::Npm => rest.starts_with('"'),
+        Ecosystem::Cargo => rest.trim_start().starts_with('='),
+        Ecosystem::PyPI => rest.starts_with(['=', '>', '~', '[', ' ']),
+    }
+}
+
+/// Keep the requirement's operator and swap in the new version: `^1.2.0` -> `^2.0.1`
+fn upgraded_requirement(requirement: &str, latest: &str) -> String {
+    let prefix: String = requirement.chars().take_while(|c| !c.is_ascii_digit()).collect();
+    format!("{}{}", prefix, latest)
+}
+
+/// Python package names compare case-insensitively, with `-`, `_` and `.` alike
+fn same_package(a: &str, b: &str) -> bool {
+    let normalize = |name: &str| name.to_lowercase().replace(['_', '.'], "-");
+    normalize(a) == normalize(b)
+}
+
+/// Upgrade the matching entries of a table of Cargo-style entries
+fn upgrade_table(table: &mut dyn TableLike, upgrades: &[&OutdatedDependency], updated: &mut Vec<String>) {
+    for upgrade in upgrades {
+        let Some(value) = table.get_mut(&upgrade.name).and_then(version_value) else { continue };
+        if value.as_str() == Some(upgrade.requirement.as_str()) {
+            set_string(value, &upgraded_requirement(&upgrade.requirement, &upgrade.latest));
+            updated.push(upgrade.name.clone());
+        }
+    }
+}
+
+fn upgrade_cargo_toml(doc: &mut DocumentMut, upgrades: &[&OutdatedDependency]) -> Vec<String> {
+    let mut updated = Vec::new();
+    visit_cargo_tables(doc, &mut |table| upgrade_table(table, upgrades, &mut updated));
+    updated
+}
+
+fn upgrade_pyproject(doc: &mut DocumentMut, upgrades: &[&OutdatedDependency]) -> Vec<String> {
+    let mut updated = Vec::new();
+    visit_pep508_arrays(doc, &mut |array| {
+        for value in array.iter_mut() {
+            let Some(requirement) = value.as_str().map(str::to_string) else { continue };
+            let Some(declared) = PEP508.captures(requirement.trim()) else { continue };
+            let upgrade = upgrades.iter().find(|u| {
+                same_package(&declared[1], &u.name) && declared[2].replace(' ', "") == u.requirement
+            });
+            if let Some(upgrade) = upgrade {
+                let version = declared[2].trim_start_matches(['=', '>', '~', ' ']).to_string();
+                set_string(value, &requirement.replacen(&version, &upgrade.latest, 1));
+                updated.push(upgrade.name.clone());
+            }
+        }
+    });
+    visit_poetry_tables(doc, &mut |table| upgrade_table(table, upgrades, &mut updated));
+    updated
+}
+
+/// Cargo.toml and pyproject.toml are edited as TOML so comments, layout and entries
+/// in target-specific or optional tables survive; other manifests line by line
+pub fn apply_to_manifest(manifest: &Path, upgrades: &[&OutdatedDependency]) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(manifest)?;
+    let file_name = manifest.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    if matches!(file_name, "Cargo.toml" | "pyproject.toml") {
+        let mut doc: DocumentMut = content
+            .parse()
+            .with_context(|| format!("Failed to parse {}", manifest.display()))?;
+        let updated = if file_name == "Cargo.toml" {
+            upgrade_cargo_toml(&mut doc, upgrades)
+        } else {
+            upgrade_pyproject(&mut doc, upgrades)
+        };
+        crate::edit_journal::write_tracked(manifest, "dependencies", &doc.to_string())?;
+        return Ok(updated);
+    }
+
+    let mut updated = Vec::new();
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            for upgrade in upgrades {
+                if declares(line, &upgrade.name, &upgrade.ecosystem) && line.contains(&upgrade.requirement) {
+                    updated.push(upgrade.name.clone());
+                    return line.replacen(&upgrade.requirement, &upgraded_requirement(&upgrade.requirement, &upgrade.latest), 1);
+                }
+            }
+            line.to_string()
+        })
+        .collect();
+
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    crate::edit_journal::write_tracked(manifest, "dependencies", &output)?;
+
+    Ok(updated)
+}
+
+fn install_command(project_path: &Path, manifest: &Path, ecosystem: &Ecosystem, names: &[String]) -> Vec<String> {
+    match ecosystem {
+        Ecosystem::Npm => {
+            let install = package_manager::for_project(project_path).install();
+            std::iter::once(install.program.to_string()).chain(install.args).collect()
+        }
+        Ecosystem::Cargo => {
+            let mut cmd = vec!["cargo".to_string(), "update".to_string(), "--manifest-path".to_string(),
+                manifest.to_string_lossy().to_string()];
+            for name in names {
+                cmd.push("-p".to_string());
+                cmd.push(name.clone());
+            }
+            cmd
+        }
+        Ecosystem::PyPI if manifest.ends_with("pyproject.toml") => {
+            vec!["pip".to_string(), "install".to_string(), "-e".to_string(), ".".to_string()]
+        }
+        Ecosystem::PyPI => {
+            vec!["pip".to_string(), "install".to_string(), "-r".to_string(), manifest.to_string_lossy().to_string()]
+        }
+    }
+}
+
+/// Rewrite the selected requirements in their manifests, then run the matching install
+pub fn apply_upgrades(project_path: &Path, upgrades: &[OutdatedDependency]) -> Result<Vec<UpgradeResult>> {
+    let mut manifests: Vec<PathBuf> = upgrades.iter().map(|u| PathBuf::from(&u.manifest)).collect();
+    manifests.sort();
+    manifests.dedup();
+
+    let mut results = Vec::new();
+
+    for manifest in manifests {
+        let selected: Vec<&OutdatedDependency> = upgrades.iter().filter(|u| Path::new(&u.manifest) == manifest).collect();
+        let updated = apply_to_manifest(&manifest, &selected)?;
+        if updated.is_empty() {
+            continue;
+        }
+
+        let command = install_command(project_path, &manifest, &selected[0].ecosystem, &updated);
+        let output = Command::new(&command[0])
+            .args(&command[1..])
+            .current_dir(manifest.parent().unwrap_or(project_path))
+            .output()
+            .with_context(|| format!("Failed to run {}", command[0]))?;
+
+        results.push(UpgradeResult {
+            updated,
+            install_command: command,
+            success: output.status.success(),
+            output: format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)),
+        });
+    }
+
+    Ok(results)
+}
+
+/// `https://github.com/owner/repo` from the many forms registries report
+fn github_repo(repository: &str) -> Option<String> {
+    let re = Regex::new(r"github\.com[/:]([^/]+)/([^/#?\s]+?)(?:\.git)?(?:[/#?]|$)").unwrap();
+    re.captures(repository).map(|c| format!("{}/{}", &c[1], &c[2]))
+}
+
+async fn fetch_changelog(repository: &str) -> Result<String> {
+    let repo = github_repo(repository).context("Changelogs can only be fetched from GitHub repositories")?;
+    let client = crate::network::client();
+
+    for file in ["CHANGELOG.md", "CHANGES.md", "HISTORY.md", "RELEASES.md"] {
+        let url = format!("https://raw.githubusercontent.com/{}/HEAD/{}", repo, file);
+        let response = client.get(&url).header("User-Agent", "LuciAI-Studio").send().await?;
+        if response.status().is_success() {
+            return Ok(response.text().await?);
+        }
+    }
+
+    anyhow::bail!("No changelog found in {}", repo)
+}
+
+pub async fn summarize_breaking_changes(dependency: &OutdatedDependency) -> Result<String> {
+    let repository = dependency.repository.as_deref().context("Package has no repository URL")?;
+    let mut changelog = fetch_changelog(repository).await?;
+    if changelog.len() > MAX_CHANGELOG_CHARS {
+        let cut = (0..=MAX_CHANGELOG_CHARS).rev().find(|i| changelog.is_char_boundary(*i)).unwrap_or(0);
+        changelog.truncate(cut);
+    }
+
+    let prompt = format!(
+        r#"Summarize what changed in {name} between version {current} and {latest}.
+
+CHANGELOG:
+{changelog}
+
+Provide:
+1. **Breaking changes** - Anything that requires code changes, with migration steps
+2. **Deprecations** - APIs that still work but should be replaced
+3. **Notable additions** - Features worth adopting
+
+Only use information from the changelog. If it does not cover these versions, say so."#,
+        name = dependency.name,
+        current = dependency.current,
+        latest = dependency.latest,
+        changelog = changelog,
+    );
+
+    let request = GenerationRequest {
+        model: "deepseek-coder-v2:16b".to_string(),
+        prompt,
+        system_prompt: Some("You help developers upgrade dependencies safely.".to_string()),
+        temperature: 0.3,
+        max_tokens: 2048,
+    };
+
+    let response = LLMClient::new().generate(request).await?;
+    Ok(response.text)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn check_outdated_dependencies(project_path: String) -> Result<OutdatedReport, String> {
+    check_outdated(Path::new(&project_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn apply_dependency_upgrades(
+    project_path: String,
+    upgrades: Vec<OutdatedDependency>,
+) -> Result<Vec<UpgradeResult>, WriteError> {
+    tokio::task::spawn_blocking(move || apply_upgrades(Path::new(&project_path), &upgrades))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(WriteError::from)
+}
+
+#[tauri::command]
+pub async fn summarize_dependency_changes(dependency: OutdatedDependency) -> Result<String, String> {
+    summarize_breaking_changes(&dependency)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_update() {
+        assert_eq!(classify_update("1.2.3", "2.0.0"), Some(UpdateRisk::Major));
+        assert_eq!(classify_update("0.3.1", "0.4.0"), Some(UpdateRisk::Major));
+        assert_eq!(classify_update("1.2.3", "1.4.0"), Some(UpdateRisk::Minor));
+        assert_eq!(classify_update("1.2.3", "1.2.9"), Some(UpdateRisk::Patch));
+        assert_eq!(classify_update("1.2.3", "1.2.3"), None);
+    }
+
+    #[test]
+    fn test_installed_version_from_lockfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let lockfile = dir.path().join("Cargo.lock");
+        std::fs::write(
+            &lockfile,
+            "version = 3\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.197\"\n\n[[package]]\nname = \"serde\"\nversion = \"0.9.15\"\n",
+        )
+        .unwrap();
+        let locked = cargo_locked_versions(&lockfile);
+        let dep = |name: &str, requirement: &str| DeclaredDependency {
+            name: name.to_string(),
+            requirement: requirement.to_string(),
+            ecosystem: Ecosystem::Cargo,
+            manifest: String::new(),
+        };
+        assert_eq!(installed_version(&dep("serde", "1.0"), &locked).as_deref(), Some("1.0.197"));
+        assert_eq!(installed_version(&dep("regex", "1.10"), &locked).as_deref(), Some("1.10.0"));
+
+        let lockfile = dir.path().join("package-lock.json");
+        std::fs::write(
+            &lockfile,
+            r#"{"packages": {"": {}, "node_modules/react": {"version": "18.3.1"}, "node_modules/a/node_modules/react": {"version": "17.0.2"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(npm_locked_versions(&lockfile)["react"], vec!["18.3.1".to_string()]);
+    }
+
+    #[test]
+    fn test_apply_to_cargo_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::edit_journal::use_dir_in_tests(dir.path().join("journal"));
+        let path = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &path,
+            "[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] } # keep\nregex = \"1.10\"\nlocal = { path = \"../local\" }\n\n\
+             [target.'cfg(unix)'.dependencies]\nlibc = \"0.2\"\n",
+        )
+        .unwrap();
+
+        let deps = parse_cargo_toml(&path);
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["serde", "regex", "libc"]);
+
+        let upgrade = OutdatedDependency {
+            name: "serde".to_string(),
+            ecosystem: Ecosystem::Cargo,
+            manifest: path.to_string_lossy().to_string(),
+            requirement: "1.0".to_string(),
+            current: "1.0.0".to_string(),
+            latest: "2.0.0".to_string(),
+            risk: UpdateRisk::Major,
+            repository: None,
+        };
+
+        assert_eq!(apply_to_manifest(&path, &[&upgrade]).unwrap(), vec!["serde"]);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("serde = { version = \"2.0.0\", features = [\"derive\"] } # keep"));
+        assert!(content.contains("regex = \"1.10\""));
+
+        let pyproject = dir.path().join("pyproject.toml");
+        std::fs::write(
+            &pyproject,
+            "[project]\ndependencies = [\n  \"requests[socks]>=2.31\",  # http\n  \"rich==13.7.0\",\n]\n\n\
+             [project.optional-dependencies]\ndev = [\"pytest>=8.0\"]\n\n[tool.poetry.dependencies]\npython = \"^3.11\"\n",
+        )
+        .unwrap();
+        let deps = parse_pyproject(&pyproject);
+        let declared: Vec<(&str, &str)> = deps.iter().map(|d| (d.name.as_str(), d.requirement.as_str())).collect();
+        assert_eq!(declared, vec![("requests", ">=2.31"), ("rich", "==13.7.0"), ("pytest", ">=8.0")]);
+
+        let upgrade = OutdatedDependency {
+            name: "Requests".to_string(),
+            ecosystem: Ecosystem::PyPI,
+            manifest: pyproject.to_string_lossy().to_string(),
+            requirement: ">=2.31".to_string(),
+            current: "2.31.0".to_string(),
+            latest: "2.32.3".to_string(),
+            risk: UpdateRisk::Minor,
+            repository: None,
+        };
+        assert_eq!(apply_to_manifest(&pyproject, &[&upgrade]).unwrap(), vec!["Requests"]);
+        let content = std::fs::read_to_string(&pyproject).unwrap();
+        assert!(content.contains("  \"requests[socks]>=2.32.3\",  # http\n"));
+        assert!(content.contains("\"rich==13.7.0\""));
+    }
+}
@@ -0,0 +1,496 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::package_manager;
+use crate::terminal::shell::find_in_path;
+
+lazy_static::lazy_static! {
+    static ref TRAILING_COMMA: Regex = Regex::new(r",(\s*[}\]])").unwrap();
+}
+
+/// Where the devcontainer spec looks for a configuration, in order
+const CONFIG_LOCATIONS: &[&str] = &[".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Stack {
+    Node,
+    Python,
+    Rust,
+    Go,
+    Java,
+    DotNet,
+}
+
+impl Stack {
+    /// Prebuilt development image used when this is the project's main stack
+    fn base_image(&self) -> &'static str {
+        match self {
+            Stack::Node => "mcr.microsoft.com/devcontainers/javascript-node:20",
+            Stack::Python => "mcr.microsoft.com/devcontainers/python:3.12",
+            Stack::Rust => "mcr.microsoft.com/devcontainers/rust:1",
+            Stack::Go => "mcr.microsoft.com/devcontainers/go:1.22",
+            Stack::Java => "mcr.microsoft.com/devcontainers/java:21",
+            Stack::DotNet => "mcr.microsoft.com/devcontainers/dotnet:8.0",
+        }
+    }
+
+    /// Devcontainer feature that adds this stack on top of another stack's image
+    fn feature(&self) -> &'static str {
+        match self {
+            Stack::Node => "ghcr.io/devcontainers/features/node:1",
+            Stack::Python => "ghcr.io/devcontainers/features/python:1",
+            Stack::Rust => "ghcr.io/devcontainers/features/rust:1",
+            Stack::Go => "ghcr.io/devcontainers/features/go:1",
+            Stack::Java => "ghcr.io/devcontainers/features/java:1",
+            Stack::DotNet => "ghcr.io/devcontainers/features/dotnet:2",
+        }
+    }
+
+    fn ports(&self) -> &'static [u16] {
+        match self {
+            Stack::Node => &[3000, 5173],
+            Stack::Python => &[8000],
+            Stack::Rust => &[],
+            Stack::Go | Stack::Java => &[8080],
+            Stack::DotNet => &[5000],
+        }
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Stack::Node => &["dbaeumer.vscode-eslint", "esbenp.prettier-vscode"],
+            Stack::Python => &["ms-python.python"],
+            Stack::Rust => &["rust-lang.rust-analyzer"],
+            Stack::Go => &["golang.go"],
+            Stack::Java => &["vscjava.vscode-java-pack"],
+            Stack::DotNet => &["ms-dotnettools.csharp"],
+        }
+    }
+}
+
+/// Files that mark each stack, checked in the project root
+fn has_any(project: &Path, names: &[&str]) -> bool {
+    names.iter().any(|name| project.join(name).exists())
+}
+
+fn has_extension(project: &Path, extension: &str) -> bool {
+    std::fs::read_dir(project)
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|e| e.path().extension().is_some_and(|ext| ext == extension))
+        })
+        .unwrap_or(false)
+}
+
+/// Stacks used by the project, most significant first. A Tauri app is a Node
+/// project with a Rust backend, so `src-tauri/Cargo.toml` counts as Rust.
+pub fn detect_stacks(project: &Path) -> Vec<Stack> {
+    let mut stacks = Vec::new();
+    if has_any(project, &["package.json"]) {
+        stacks.push(Stack::Node);
+    }
+    if has_any(project, &["Cargo.toml", "src-tauri/Cargo.toml"]) {
+        stacks.push(Stack::Rust);
+    }
+    if has_any(project, &["pyproject.toml", "requirements.txt", "setup.py", "Pipfile"]) {
+        stacks.push(Stack::Python);
+    }
+    if has_any(project, &["go.mod"]) {
+        stacks.push(Stack::Go);
+    }
+    if has_any(project, &["pom.xml", "build.gradle", "build.gradle.kts"]) {
+        stacks.push(Stack::Java);
+    }
+    if has_extension(project, "csproj") || has_extension(project, "sln") {
+        stacks.push(Stack::DotNet);
+    }
+    stacks
+}
+
+/// Commands that fetch dependencies once the container exists
+fn post_create_commands(project: &Path, stacks: &[Stack]) -> Vec<String> {
+    stacks
+        .iter()
+        .filter_map(|stack| match stack {
+            // Only what the project pins: the container doesn't share the host's preference
+            Stack::Node => {
+                let manager = package_manager::detect(project).map(|info| info.manager).unwrap_or_default();
+                Some(manager.install().to_string())
+            }
+            Stack::Python if project.join("requirements.txt").exists() => Some("pip install -r requirements.txt".to_string()),
+            Stack::Python if project.join("pyproject.toml").exists() => Some("pip install -e .".to_string()),
+            Stack::Python => None,
+            Stack::Rust if project.join("Cargo.toml").exists() => Some("cargo fetch".to_string()),
+            Stack::Rust => Some("cargo fetch --manifest-path src-tauri/Cargo.toml".to_string()),
+            Stack::Go => Some("go mod download".to_string()),
+            Stack::Java if project.join("pom.xml").exists() => Some("mvn -q dependency:resolve".to_string()),
+            Stack::Java => None,
+            Stack::DotNet => Some("dotnet restore".to_string()),
+        })
+        .collect()
+}
+
+pub fn dockerfile(stacks: &[Stack]) -> String {
+    let image = stacks.first().map(Stack::base_image).unwrap_or("mcr.microsoft.com/devcontainers/base:bookworm");
+    let mut dockerfile = format!("FROM {}\n", image);
+
+    // Tauri and other desktop Rust projects link against the webview and GTK
+    if stacks.contains(&Stack::Rust) && stacks.contains(&Stack::Node) {
+        dockerfile.push_str(
+            "\nRUN apt-get update \\\n    && apt-get install -y --no-install-recommends libwebkit2gtk-4.1-dev libgtk-3-dev librsvg2-dev libayatana-appindicator3-dev \\\n    && rm -rf /var/lib/apt/lists/*\n",
+        );
+    }
+    dockerfile
+}
+
+pub fn devcontainer_json(name: &str, project: &Path, stacks: &[Stack]) -> Value {
+    let features: serde_json::Map<String, Value> = stacks
+        .iter()
+        .skip(1)
+        .map(|stack| (stack.feature().to_string(), json!({})))
+        .collect();
+
+    let mut ports: Vec<u16> = stacks.iter().flat_map(|s| s.ports().iter().copied()).collect();
+    ports.sort_unstable();
+    ports.dedup();
+
+    let extensions: Vec<&str> = stacks.iter().flat_map(|s| s.extensions().iter().copied()).collect();
+
+    let mut config = json!({
+        "name": name,
+        "build": { "dockerfile": "Dockerfile", "context": ".." },
+        "features": features,
+        "forwardPorts": ports,
+        "customizations": { "vscode": { "extensions": extensions } },
+    });
+
+    let commands = post_create_commands(project, stacks);
+    if !commands.is_empty() {
+        config["postCreateCommand"] = json!(commands.join(" && "));
+    }
+    config
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedDevcontainer {
+    pub stacks: Vec<Stack>,
+    pub files: Vec<String>,
+}
+
+/// Write `.devcontainer/devcontainer.json` and a Dockerfile for the detected stack
+pub fn generate(project: &Path) -> Result<GeneratedDevcontainer> {
+    if let Some(existing) = find_config(project) {
+        anyhow::bail!("{} already has a devcontainer at {}", project.display(), existing.display());
+    }
+
+    let stacks = detect_stacks(project);
+    let name = project
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "dev".to_string());
+
+    let dir = project.join(".devcontainer");
+    std::fs::create_dir_all(&dir)?;
+
+    let config_path = dir.join("devcontainer.json");
+    let dockerfile_path = dir.join("Dockerfile");
+    let config = serde_json::to_string_pretty(&devcontainer_json(&name, project, &stacks))?;
+    std::fs::write(&config_path, config + "\n")?;
+    std::fs::write(&dockerfile_path, dockerfile(&stacks))?;
+
+    Ok(GeneratedDevcontainer {
+        stacks,
+        files: vec![
+            config_path.to_string_lossy().to_string(),
+            dockerfile_path.to_string_lossy().to_string(),
+        ],
+    })
+}
+
+/// devcontainer.json allows comments and trailing commas
+fn parse_jsonc(content: &str) -> Result<Value> {
+    let mut stripped = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    Ok(serde_json::from_str(&TRAILING_COMMA.replace_all(&stripped, "$1"))?)
+}
+
+fn find_config(project: &Path) -> Option<PathBuf> {
+    CONFIG_LOCATIONS
+        .iter()
+        .map(|location| project.join(location))
+        .find(|path| path.is_file())
+        .or_else(|| {
+            // Named configurations: .devcontainer/<name>/devcontainer.json
+            let mut named: Vec<PathBuf> = std::fs::read_dir(project.join(".devcontainer"))
+                .ok()?
+                .flatten()
+                .map(|e| e.path().join("devcontainer.json"))
+                .filter(|path| path.is_file())
+                .collect();
+            named.sort();
+            named.into_iter().next()
+        })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevcontainerInfo {
+    pub config_path: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    /// Absolute path of the Dockerfile when the container is built
+    pub dockerfile: Option<String>,
+    pub build_context: Option<String>,
+    /// Compose-based configurations are detected but not launched
+    pub compose: bool,
+    pub forward_ports: Vec<u16>,
+    pub post_create_command: Option<Value>,
+    pub remote_user: Option<String>,
+    pub workspace_folder: String,
+    pub container_env: Vec<(String, String)>,
+    pub run_args: Vec<String>,
+}
+
+pub fn detect(project: &Path) -> Result<Option<DevcontainerInfo>> {
+    let Some(config_path) = find_config(project) else {
+        return Ok(None);
+    };
+    let content = std::fs::read_to_string(&config_path)?;
+    let config = parse_jsonc(&content).with_context(|| format!("Invalid {}", config_path.display()))?;
+    let config_dir = config_path.parent().unwrap_or(project);
+
+    let string = |value: &Value| value.as_str().map(String::from);
+    let dockerfile = string(&config["build"]["dockerfile"]).or_else(|| string(&config["dockerFile"]));
+    let build_context = dockerfile
+        .as_ref()
+        .map(|_| string(&config["build"]["context"]).or_else(|| string(&config["context"])).unwrap_or_else(|| ".".to_string()));
+    let folder_name = project.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    Ok(Some(DevcontainerInfo {
+        config_path: config_path.to_string_lossy().to_string(),
+        name: string(&config["name"]),
+        image: string(&config["image"]),
+        dockerfile: dockerfile.map(|d| config_dir.join(d).to_string_lossy().to_string()),
+        build_context: build_context.map(|c| config_dir.join(c).to_string_lossy().to_string()),
+        compose: !config["dockerComposeFile"].is_null(),
+        forward_ports: config["forwardPorts"]
+            .as_array()
+            .map(|ports| ports.iter().filter_map(|p| p.as_u64()).filter_map(|p| u16::try_from(p).ok()).collect())
+            .unwrap_or_default(),
+        post_create_command: Some(config["postCreateCommand"].clone()).filter(|c| !c.is_null()),
+        remote_user: string(&config["remoteUser"]),
+        workspace_folder: string(&config["workspaceFolder"]).unwrap_or_else(|| format!("/workspaces/{}", folder_name)),
+        container_env: config["containerEnv"]
+            .as_object()
+            .map(|env| env.iter().filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string()))).collect())
+            .unwrap_or_default(),
+        run_args: config["runArgs"]
+            .as_array()
+            .map(|args| args.iter().filter_map(string).collect())
+            .unwrap_or_default(),
+    }))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevcontainerSession {
+    pub container_id: String,
+    pub image: String,
+    pub workspace_folder: String,
+    pub forward_ports: Vec<u16>,
+    /// False when an existing container for the project was restarted
+    pub created: bool,
+}
+
+fn docker(args: &[&str]) -> Result<String> {
+    let docker = find_in_path("docker").context("Docker was not found on PATH; install Docker Desktop or Docker Engine")?;
+    let output = Command::new(docker)
+        .args(args)
+        .envs(crate::network::proxy_env())
+        .output()
+        .context("Failed to run docker")?;
+    if !output.status.success() {
+        anyhow::bail!("docker {} failed: {}", args.first().unwrap_or(&""), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// postCreateCommand may be a shell string, an argv array, or an object of either
+fn post_create_invocations(command: &Value) -> Vec<Vec<String>> {
+    match command {
+        Value::String(script) => vec![vec!["sh".to_string(), "-c".to_string(), script.clone()]],
+        Value::Array(argv) => vec![argv.iter().filter_map(|a| a.as_str().map(String::from)).collect()],
+        Value::Object(commands) => commands.values().flat_map(post_create_invocations).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Build or pull the configured image and start a container with the project
+/// mounted, reusing the project's container from an earlier launch if there is one
+pub fn launch(project: &Path) -> Result<DevcontainerSession> {
+    let info = detect(project)?.with_context(|| format!("No devcontainer configuration in {}", project.display()))?;
+    if info.compose {
+        anyhow::bail!("Docker Compose devcontainers are not supported yet; start them with `docker compose up`");
+    }
+
+    let local_folder = project.canonicalize()?.to_string_lossy().to_string();
+    let label = format!("devcontainer.local_folder={}", local_folder);
+
+    let existing = docker(&["ps", "-aq", "--filter", &format!("label={}", label)])?;
+    if let Some(container_id) = existing.lines().next() {
+        docker(&["start", container_id])?;
+        let image = docker(&["inspect", "--format", "{{.Config.Image}}", container_id])?;
+        return Ok(DevcontainerSession {
+            container_id: container_id.to_string(),
+            image,
+            workspace_folder: info.workspace_folder,
+            forward_ports: info.forward_ports,
+            created: false,
+        });
+    }
+
+    let image = match (&info.image, &info.dockerfile) {
+        (Some(image), _) => {
+            docker(&["pull", image])?;
+            image.clone()
+        }
+        (None, Some(dockerfile)) => {
+            let folder = project.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+            let folder: String = folder.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect();
+            let tag = format!("sai-ide-devcontainer-{}", folder.trim_matches('-'));
+            let context = info.build_context.clone().unwrap_or_else(|| local_folder.clone());
+            docker(&["build", "-f", dockerfile, "-t", &tag, &context])?;
+            tag
+        }
+        (None, None) => anyhow::bail!("{} sets neither an image nor a Dockerfile", info.config_path),
+    };
+
+    let mut args: Vec<String> = vec![
+        "run".into(),
+        "-d".into(),
+        "--label".into(),
+        label,
+        "-v".into(),
+        format!("{}:{}", local_folder, info.workspace_folder),
+        "-w".into(),
+        info.workspace_folder.clone(),
+    ];
+    for port in &info.forward_ports {
+        args.extend(["-p".to_string(), format!("{}:{}", port, port)]);
+    }
+    for (key, value) in &info.container_env {
+        args.extend(["-e".to_string(), format!("{}={}", key, value)]);
+    }
+    if let Some(user) = &info.remote_user {
+        args.extend(["-u".to_string(), user.clone()]);
+    }
+    args.extend(info.run_args.iter().cloned());
+    // Keep the container alive for terminals and exec sessions
+    args.extend([image.clone(), "sleep".to_string(), "infinity".to_string()]);
+
+    let container_id = docker(&args.iter().map(String::as_str).collect::<Vec<_>>())?;
+
+    for invocation in info.post_create_command.as_ref().map(post_create_invocations).unwrap_or_default() {
+        let mut exec = vec!["exec", "-w", info.workspace_folder.as_str(), container_id.as_str()];
+        exec.extend(invocation.iter().map(String::as_str));
+        docker(&exec)?;
+    }
+
+    Ok(DevcontainerSession {
+        container_id,
+        image,
+        workspace_folder: info.workspace_folder,
+        forward_ports: info.forward_ports,
+        created: true,
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn generate_devcontainer(project_path: String) -> Result<GeneratedDevcontainer, String> {
+    generate(Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn detect_devcontainer(project_path: String) -> Result<Option<DevcontainerInfo>, String> {
+    detect(Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn launch_devcontainer(project_path: String) -> Result<DevcontainerSession, String> {
+    tokio::task::spawn_blocking(move || launch(Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_and_detect() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(project.path().join("package.json"), "{}").unwrap();
+        std::fs::create_dir(project.path().join("src-tauri")).unwrap();
+        std::fs::write(project.path().join("src-tauri/Cargo.toml"), "[package]").unwrap();
+
+        let generated = generate(project.path()).unwrap();
+        assert_eq!(generated.stacks, vec![Stack::Node, Stack::Rust]);
+        assert!(generate(project.path()).is_err());
+
+        let info = detect(project.path()).unwrap().unwrap();
+        assert!(info.image.is_none());
+        assert!(info.dockerfile.unwrap().ends_with("Dockerfile"));
+        assert_eq!(info.forward_ports, vec![3000, 5173]);
+        assert_eq!(
+            info.post_create_command,
+            Some(json!("npm install && cargo fetch --manifest-path src-tauri/Cargo.toml"))
+        );
+
+        let config = parse_jsonc("{\n  // base image\n  \"image\": \"debian\", /* pinned */\n  \"url\": \"http://x\",\n}").unwrap();
+        assert_eq!(config["image"], "debian");
+        assert_eq!(config["url"], "http://x");
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::Emitter;
+
+/// Current diagnostics keyed by the source that published them, e.g. `task:build`
+static STORE: Mutex<Option<HashMap<String, Vec<Diagnostic>>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.to_lowercase().as_str() {
+            "error" | "fatal" | "e" => Some(Severity::Error),
+            "warning" | "warn" | "w" => Some(Severity::Warning),
+            "info" | "note" | "hint" | "i" => Some(Severity::Info),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Diagnostic {
+    pub file: String,
+    /// 1-based
+    pub line: u32,
+    pub column: Option<u32>,
+    /// Exclusive, on the same line; None when only the start is known
+    #[serde(default)]
+    pub end_column: Option<u32>,
+    pub severity: Severity,
+    pub message: String,
+    /// Task or tool that reported it
+    pub source: String,
+    /// Replacements the editor can offer as quick fixes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsUpdate {
+    pub source: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Replace everything `source` previously reported and notify the frontend
+pub fn publish(app: Option<&tauri::AppHandle>, source: &str, diagnostics: Vec<Diagnostic>) {
+    {
+        let mut store = STORE.lock().unwrap_or_else(|e| e.into_inner());
+        let store = store.get_or_insert_with(HashMap::new);
+        if diagnostics.is_empty() {
+            store.remove(source);
+        } else {
+            store.insert(source.to_string(), diagnostics.clone());
+        }
+    }
+    if let Some(app) = app {
+        app.emit("diagnostics-updated", DiagnosticsUpdate { source: source.to_string(), diagnostics }).ok();
+    }
+}
+
+/// All current diagnostics, optionally only those for one file
+pub fn current(file: Option<&str>) -> Vec<Diagnostic> {
+    let store = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut diagnostics: Vec<Diagnostic> = store
+        .iter()
+        .flat_map(|s| s.values().flatten())
+        .filter(|d| file.map_or(true, |f| d.file == f))
+        .cloned()
+        .collect();
+    diagnostics.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+    diagnostics
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_diagnostics(file: Option<String>) -> Result<Vec<Diagnostic>, String> {
+    Ok(current(file.as_deref()))
+}
+
+/// Clear one source's diagnostics, or all of them
+#[tauri::command]
+pub async fn clear_diagnostics(app: tauri::AppHandle, source: Option<String>) -> Result<(), String> {
+    match source {
+        Some(source) => publish(Some(&app), &source, Vec::new()),
+        None => {
+            let sources: Vec<String> = STORE
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .as_ref()
+                .map(|s| s.keys().cloned().collect())
+                .unwrap_or_default();
+            for source in sources {
+                publish(Some(&app), &source, Vec::new());
+            }
+        }
+    }
+    Ok(())
+}
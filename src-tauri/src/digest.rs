@@ -0,0 +1,415 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+use crate::agent::generation_report;
+use crate::board::parse_todo;
+use crate::edit_journal::EditJournal;
+use crate::llm::{GenerationRequest, LLMClient};
+use crate::notifications::{self, NotificationCategory};
+use crate::project::{load_project_config, save_project_config, ProjectManager};
+use crate::testing::{self, TestRunRecord};
+use crate::time_tracking::{self, ReportPeriod};
+
+pub const CONFIG_FILE: &str = "digest.json";
+/// Separates `git log` fields; doesn't occur in names or subjects
+const FIELD: char = '\x1f';
+
+/// `.luciai/digest.json`: where a delivered digest goes
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DigestConfig {
+    /// Post a notification with the headline numbers
+    #[serde(default)]
+    pub notify: bool,
+    /// POSTed `{"text": markdown, "digest": ...}`, which Slack-style incoming webhooks accept
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestCommit {
+    pub short_hash: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AiActivity {
+    /// `generate_full_project` runs
+    pub generations: usize,
+    pub generated_files: usize,
+    /// Agent edits that weren't undone
+    pub agent_edits: usize,
+    pub edited_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TestActivity {
+    pub runs: usize,
+    pub failing_runs: usize,
+    /// The most recent run in the period
+    pub latest: Option<TestRunRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityDigest {
+    pub project_id: String,
+    pub period: ReportPeriod,
+    pub since: String,
+    pub until: String,
+    pub commits: Vec<DigestCommit>,
+    pub ai: AiActivity,
+    pub tests: TestActivity,
+    pub tracked_seconds: i64,
+    /// Tracked time per feature; the empty name is time without one
+    pub features: BTreeMap<String, i64>,
+    /// Text of TODO comments removed by the period's commits and not added back
+    pub resolved_todos: Vec<String>,
+    pub markdown: String,
+    /// The Markdown was rewritten by the model
+    pub polished: bool,
+    /// Delivery targets that failed, with why
+    pub delivery_errors: Vec<String>,
+}
+
+fn span(period: ReportPeriod) -> Duration {
+    match period {
+        ReportPeriod::Day => Duration::days(1),
+        ReportPeriod::Week => Duration::weeks(1),
+    }
+}
+
+fn git(project_path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(project_path)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_commits(output: &str) -> Vec<DigestCommit> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(FIELD).collect();
+            let [short_hash, author, date, subject] = fields[..] else { return None };
+            Some(DigestCommit {
+                short_hash: short_hash.to_string(),
+                author: author.to_string(),
+                date: date.to_string(),
+                subject: subject.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// TODO comments a patch removes without adding back; a moved or reworded-in-place
+/// TODO isn't resolved
+pub fn resolved_todos(patch: &str) -> Vec<String> {
+    let mut removed = Vec::new();
+    let mut added = HashSet::new();
+    for line in patch.lines() {
+        if line.starts_with("---") || line.starts_with("+++") {
+            continue;
+        }
+        if let Some(removed_line) = line.strip_prefix('-') {
+            if let Some((tag, text)) = parse_todo(removed_line) {
+                removed.push(format!("{}: {}", tag, text));
+            }
+        } else if let Some(added_line) = line.strip_prefix('+') {
+            if let Some((tag, text)) = parse_todo(added_line) {
+                added.insert(format!("{}: {}", tag, text));
+            }
+        }
+    }
+    let mut seen = HashSet::new();
+    removed.retain(|todo| !added.contains(todo) && seen.insert(todo.clone()));
+    removed
+}
+
+fn ai_activity(project_path: &Path, since: DateTime<Utc>) -> AiActivity {
+    let project = project_path.to_string_lossy().to_string();
+    let reports: Vec<_> = generation_report::history(Some(&project))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| DateTime::parse_from_rfc3339(&r.started_at).is_ok_and(|t| t >= since))
+        .collect();
+
+    let journal = EditJournal::new().ok();
+    let edits: Vec<_> = journal
+        .iter()
+        .flat_map(|journal| journal.sessions().into_iter().flat_map(|s| journal.load(&s).unwrap_or_default()))
+        .filter(|e| e.source == "agent" && e.undone_at.is_none() && e.applied_at >= since)
+        .filter(|e| Path::new(&e.file).starts_with(project_path))
+        .collect();
+    let mut edited_files: Vec<String> = edits
+        .iter()
+        .map(|e| Path::new(&e.file).strip_prefix(project_path).unwrap_or(Path::new(&e.file)).to_string_lossy().replace('\\', "/"))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    edited_files.sort();
+
+    AiActivity {
+        generations: reports.len(),
+        generated_files: reports.iter().map(|r| r.files.len()).sum(),
+        agent_edits: edits.len(),
+        edited_files,
+    }
+}
+
+fn test_activity(runs: Vec<TestRunRecord>, since: DateTime<Utc>) -> TestActivity {
+    let runs: Vec<TestRunRecord> = runs
+        .into_iter()
+        .filter(|r| DateTime::parse_from_rfc3339(&r.ran_at).is_ok_and(|t| t >= since))
+        .collect();
+    TestActivity {
+        runs: runs.len(),
+        failing_runs: runs.iter().filter(|r| r.failed > 0).count(),
+        latest: runs.last().cloned(),
+    }
+}
+
+fn hours(seconds: i64) -> String {
+    format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60)
+}
+
+pub fn to_markdown(digest: &ActivityDigest) -> String {
+    let title = match digest.period {
+        ReportPeriod::Day => "Daily",
+        ReportPeriod::Week => "Weekly",
+    };
+    let mut out = format!(
+        "# {} digest: {}\n\n{} to {}\n\n- {} commits\n- {} AI generations, {} agent edits\n- {} test runs ({} failing)\n- {} tracked\n- {} TODOs resolved\n",
+        title,
+        digest.project_id,
+        digest.since,
+        digest.until,
+        digest.commits.len(),
+        digest.ai.generations,
+        digest.ai.agent_edits,
+        digest.tests.runs,
+        digest.tests.failing_runs,
+        hours(digest.tracked_seconds),
+        digest.resolved_todos.len()
+    );
+
+    if !digest.commits.is_empty() {
+        out.push_str("\n## Commits\n\n");
+        for commit in &digest.commits {
+            out.push_str(&format!("- `{}` {} ({})\n", commit.short_hash, commit.subject, commit.author));
+        }
+    }
+
+    if digest.ai.generations + digest.ai.agent_edits > 0 {
+        out.push_str(&format!(
+            "\n## AI work\n\n{} generations produced {} files; the agent edited {} files\n",
+            digest.ai.generations,
+            digest.ai.generated_files,
+            digest.ai.edited_files.len()
+        ));
+        for file in &digest.ai.edited_files {
+            out.push_str(&format!("- `{}`\n", file));
+        }
+    }
+
+    if let Some(latest) = &digest.tests.latest {
+        out.push_str(&format!(
+            "\n## Tests\n\nLatest run: {} passed, {} failed, {} skipped of {}\n",
+            latest.passed, latest.failed, latest.skipped, latest.total_tests
+        ));
+    }
+
+    if !digest.features.is_empty() {
+        out.push_str("\n## Time\n\n| Feature | Time |\n|---|---:|\n");
+        for (feature, seconds) in &digest.features {
+            let name = if feature.is_empty() { "(no feature)" } else { feature.as_str() };
+            out.push_str(&format!("| {} | {} |\n", name, hours(*seconds)));
+        }
+    }
+
+    if !digest.resolved_todos.is_empty() {
+        out.push_str("\n## Resolved TODOs\n\n");
+        for todo in &digest.resolved_todos {
+            out.push_str(&format!("- {}\n", todo));
+        }
+    }
+    out
+}
+
+/// Gather the last day or week of activity. `project_id` is the id used for time
+/// tracking, or a project path.
+pub fn collect(project_id: &str, period: ReportPeriod, now: DateTime<Utc>) -> Result<ActivityDigest> {
+    let project_path = ProjectManager::new()?.project_dir(project_id);
+    let since = now - span(period);
+    let since_arg = format!("--since={}", since.to_rfc3339());
+
+    let (commits, resolved) = if project_path.join(".git").exists() {
+        let format = format!("--format={}", ["%h", "%an", "%aI", "%s"].join(&FIELD.to_string()));
+        let commits = parse_commits(&git(&project_path, &["log", "--no-merges", &since_arg, &format])?);
+        let patch = git(&project_path, &["log", "--no-merges", &since_arg, "--format=", "-p", "-U0"])?;
+        (commits, resolved_todos(&patch))
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    let db = crate::database::open()?;
+    // Close an idle session so its time shows up
+    time_tracking::current(&db, now)?;
+    let mut features = BTreeMap::new();
+    for session in db.list_work_sessions(Some(project_id), &since.to_rfc3339())? {
+        *features.entry(session.feature.unwrap_or_default()).or_insert(0) += session.active_seconds;
+    }
+
+    let mut digest = ActivityDigest {
+        project_id: project_id.to_string(),
+        period,
+        since: since.to_rfc3339(),
+        until: now.to_rfc3339(),
+        commits,
+        ai: ai_activity(&project_path, since),
+        tests: test_activity(testing::recorded_runs(&project_path), since),
+        tracked_seconds: features.values().sum(),
+        features,
+        resolved_todos: resolved,
+        markdown: String::new(),
+        polished: false,
+        delivery_errors: Vec::new(),
+    };
+    digest.markdown = to_markdown(&digest);
+    Ok(digest)
+}
+
+/// Rewrite the digest as a short readable summary; the figures are kept as they are
+async fn polish(markdown: &str) -> Result<String> {
+    let request = GenerationRequest {
+        model: "deepseek-coder-v2:16b".to_string(),
+        prompt: format!(
+            "Rewrite this developer activity digest as a concise Markdown summary for a teammate. \
+             Keep every number, commit and file name accurate and don't add anything that isn't in it.\n\n{}",
+            markdown
+        ),
+        system_prompt: Some("You write clear, factual engineering status updates.".to_string()),
+        temperature: 0.3,
+        max_tokens: 1024,
+    };
+    let text = LLMClient::new().generate(request).await?.text.trim().to_string();
+    if text.is_empty() {
+        anyhow::bail!("The model returned nothing");
+    }
+    Ok(text)
+}
+
+async fn deliver(config: &DigestConfig, digest: &ActivityDigest) -> Vec<String> {
+    let mut errors = Vec::new();
+    if config.notify {
+        notifications::info(
+            "Activity digest",
+            &format!(
+                "{} commits, {} test runs, {} tracked",
+                digest.commits.len(),
+                digest.tests.runs,
+                hours(digest.tracked_seconds)
+            ),
+            NotificationCategory::Project,
+        );
+    }
+    if let Some(url) = &config.webhook_url {
+        let body = serde_json::json!({ "text": digest.markdown, "digest": digest });
+        match crate::network::client().post(url).json(&body).send().await {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => errors.push(format!("Webhook: HTTP {}", response.status())),
+            Err(e) => errors.push(format!("Webhook: {}", e)),
+        }
+    }
+    errors
+}
+
+// Tauri commands
+
+/// Summary of the past day or week. With `polish` the model rewrites the Markdown
+/// (the plain version is kept if it fails); with `deliver` it goes to the targets
+/// in `.luciai/digest.json`.
+#[tauri::command]
+pub async fn generate_activity_digest(
+    project_id: String,
+    period: ReportPeriod,
+    polish: Option<bool>,
+    deliver: Option<bool>,
+) -> Result<ActivityDigest, String> {
+    let id = project_id.clone();
+    let mut digest = tokio::task::spawn_blocking(move || collect(&id, period, Utc::now()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    if polish.unwrap_or(false) {
+        match self::polish(&digest.markdown).await {
+            Ok(markdown) => {
+                digest.markdown = markdown;
+                digest.polished = true;
+            }
+            Err(e) => tracing::warn!("Digest polishing failed, keeping the plain version: {}", e),
+        }
+    }
+
+    if deliver.unwrap_or(false) {
+        let project_path = ProjectManager::new().map_err(|e| e.to_string())?.project_dir(&project_id);
+        let config: DigestConfig = load_project_config(&project_path, CONFIG_FILE).map_err(|e| e.to_string())?;
+        digest.delivery_errors = self::deliver(&config, &digest).await;
+    }
+    Ok(digest)
+}
+
+#[tauri::command]
+pub async fn get_digest_config(project_path: String) -> Result<DigestConfig, String> {
+    load_project_config(Path::new(&project_path), CONFIG_FILE).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_digest_config(project_path: String, config: DigestConfig) -> Result<(), String> {
+    save_project_config(Path::new(&project_path), CONFIG_FILE, &config).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolved_todos_and_markdown() {
+        let patch = "diff --git a/src/api.ts b/src/api.ts\n--- a/src/api.ts\n+++ b/src/api.ts\n@@ -3 +3 @@\n\
+                     -  // TODO: retry on 503\n+  return retry(fetch, 3);\n@@ -20 +20 @@\n\
+                     -// FIXME: move to config\n+// FIXME: move to config\n@@ -40 +40 @@\n-  const todo_list = [];\n";
+        assert_eq!(resolved_todos(patch), vec!["TODO: retry on 503"]);
+
+        let commits = parse_commits(&["a1b2c3d", "Ada", "2026-10-14T09:00:00+00:00", "Add retries"].join("\x1f"));
+        let mut digest = ActivityDigest {
+            project_id: "shop".to_string(),
+            period: ReportPeriod::Week,
+            since: "2026-10-08T00:00:00+00:00".to_string(),
+            until: "2026-10-15T00:00:00+00:00".to_string(),
+            commits,
+            ai: AiActivity { generations: 1, generated_files: 4, agent_edits: 2, edited_files: vec!["src/api.ts".to_string()] },
+            tests: TestActivity::default(),
+            tracked_seconds: 5400,
+            features: BTreeMap::from([("checkout".to_string(), 5400)]),
+            resolved_todos: vec!["TODO: retry on 503".to_string()],
+            markdown: String::new(),
+            polished: false,
+            delivery_errors: Vec::new(),
+        };
+        digest.markdown = to_markdown(&digest);
+        assert!(digest.markdown.starts_with("# Weekly digest: shop"));
+        assert!(digest.markdown.contains("- `a1b2c3d` Add retries (Ada)"));
+        assert!(digest.markdown.contains("| checkout | 1h 30m |"));
+        assert!(digest.markdown.contains("- 1 TODOs resolved"));
+    }
+}
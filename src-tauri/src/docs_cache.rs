@@ -0,0 +1,544 @@
+use rusqlite::{Connection, params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tauri::Emitter;
+
+use crate::agent::web_fetch::readable_text;
+
+/// devdocs.io publishes each documentation set as an entry index plus a path → HTML map
+const DEVDOCS_BASE: &str = "https://documents.devdocs.io";
+const DEVDOCS_SITE: &str = "https://devdocs.io";
+/// Docsets offered in the UI; any other devdocs slug can be downloaded too
+pub const KNOWN_DOCSETS: &[(&str, &str)] = &[
+    ("javascript", "MDN JavaScript"),
+    ("dom", "MDN Web APIs"),
+    ("css", "MDN CSS"),
+    ("html", "MDN HTML"),
+    ("python~3.12", "Python 3.12"),
+    ("rust", "Rust standard library"),
+];
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const SNIPPET_TOKENS: i32 = 24;
+/// Characters of each cited page included in agent prompts
+const CITATION_CHARS: usize = 1500;
+/// A docset being (re)indexed lives under `<slug>~indexing` until it is swapped in
+const STAGING_SUFFIX: &str = "~indexing";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Docset {
+    pub slug: String,
+    pub name: String,
+    pub entry_count: i64,
+    pub page_count: i64,
+    pub downloaded_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocSearchResult {
+    pub id: i64,
+    pub docset: String,
+    pub name: String,
+    pub entry_type: String,
+    pub url: String,
+    /// Matching text with hits wrapped in `[` `]`
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocEntry {
+    pub id: i64,
+    pub docset: String,
+    pub name: String,
+    pub entry_type: String,
+    pub url: String,
+    /// Readable text of the page the entry lives on
+    pub content: String,
+}
+
+/// A cached page quoted in an agent prompt, so answers can point to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocCitation {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsDownloadProgress {
+    pub docset: String,
+    /// "downloading", "indexing" or "done"
+    pub stage: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct DevdocsIndex {
+    entries: Vec<DevdocsEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DevdocsEntry {
+    name: String,
+    path: String,
+    #[serde(rename = "type", default)]
+    entry_type: String,
+}
+
+pub struct DocsCache {
+    db_path: PathBuf,
+}
+
+impl DocsCache {
+    pub fn new() -> Result<Self> {
+        let app_dir = dirs::data_dir()
+            .context("Failed to get data directory")?
+            .join(".sai-ide");
+
+        std::fs::create_dir_all(&app_dir)?;
+
+        // Kept apart from the main database, which is backed up and much smaller
+        Ok(Self { db_path: app_dir.join("docs-cache.db") })
+    }
+
+    fn open(&self) -> Result<Connection> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS docsets (
+                slug TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                downloaded_at TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS doc_pages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                docset TEXT NOT NULL,
+                path TEXT NOT NULL,
+                content TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS doc_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                docset TEXT NOT NULL,
+                name TEXT NOT NULL,
+                entry_type TEXT NOT NULL,
+                path TEXT NOT NULL,
+                page_id INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_doc_entries_docset ON doc_entries(docset);
+            CREATE INDEX IF NOT EXISTS idx_doc_pages_docset ON doc_pages(docset);
+            -- rowid is the entry id; page text is indexed on the first entry of each page only
+            CREATE VIRTUAL TABLE IF NOT EXISTS doc_fts USING fts5(
+                name, content, docset UNINDEXED
+            );",
+        )?;
+        Ok(conn)
+    }
+
+    pub fn has_docsets(&self) -> bool {
+        self.db_path.exists()
+            && self.list_docsets().map(|docsets| !docsets.is_empty()).unwrap_or(false)
+    }
+
+    pub fn list_docsets(&self) -> Result<Vec<Docset>> {
+        let conn = self.open()?;
+        let mut stmt = conn.prepare(
+            "SELECT d.slug, d.name, d.downloaded_at,
+                    (SELECT COUNT(*) FROM doc_entries e WHERE e.docset = d.slug),
+                    (SELECT COUNT(*) FROM doc_pages p WHERE p.docset = d.slug)
+             FROM docsets d ORDER BY d.name",
+        )?;
+        let docsets = stmt
+            .query_map([], |row| {
+                Ok(Docset {
+                    slug: row.get(0)?,
+                    name: row.get(1)?,
+                    downloaded_at: row.get(2)?,
+                    entry_count: row.get(3)?,
+                    page_count: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(docsets)
+    }
+
+    pub fn remove_docset(&self, slug: &str) -> Result<()> {
+        let mut conn = self.open()?;
+        let tx = conn.transaction()?;
+        delete_docset_rows(&tx, slug)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Replace `slug` with the given entries and HTML pages, converting pages to readable text.
+    /// The new version is indexed under a staging name and swapped in at the end, so searches
+    /// keep finding the old one meanwhile and a failed index leaves it untouched.
+    pub fn store_docset(
+        &self,
+        slug: &str,
+        name: &str,
+        entries: &[(String, String, String)],
+        pages: &HashMap<String, String>,
+        on_progress: &dyn Fn(usize, usize),
+    ) -> Result<()> {
+        let staging = format!("{}{}", slug, STAGING_SUFFIX);
+        // Left behind by an interrupted run
+        self.remove_docset(&staging)?;
+        if let Err(e) = self.index_docset(&staging, entries, pages, on_progress) {
+            self.remove_docset(&staging).ok();
+            return Err(e);
+        }
+
+        let mut conn = self.open()?;
+        let tx = conn.transaction()?;
+        delete_docset_rows(&tx, slug)?;
+        tx.execute("UPDATE doc_pages SET docset = ?1 WHERE docset = ?2", params![slug, staging])?;
+        tx.execute("UPDATE doc_entries SET docset = ?1 WHERE docset = ?2", params![slug, staging])?;
+        tx.execute("UPDATE doc_fts SET docset = ?1 WHERE docset = ?2", params![slug, staging])?;
+        tx.execute(
+            "INSERT INTO docsets (slug, name, downloaded_at) VALUES (?1, ?2, ?3)",
+            params![slug, name, chrono::Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        on_progress(entries.len(), entries.len());
+
+        tracing::info!("Indexed {} entries from docset {}", entries.len(), slug);
+        Ok(())
+    }
+
+    fn index_docset(
+        &self,
+        slug: &str,
+        entries: &[(String, String, String)],
+        pages: &HashMap<String, String>,
+        on_progress: &dyn Fn(usize, usize),
+    ) -> Result<()> {
+        let mut conn = self.open()?;
+        let tx = conn.transaction()?;
+        let mut page_ids: HashMap<&str, i64> = HashMap::new();
+        let mut indexed_pages: HashSet<i64> = HashSet::new();
+
+        for (done, (entry_name, entry_type, path)) in entries.iter().enumerate() {
+            // Entries can point at an anchor within a page
+            let page_path = path.split('#').next().unwrap_or(path);
+            let Some(html) = pages.get(page_path) else {
+                continue;
+            };
+
+            let page_id = match page_ids.get(page_path) {
+                Some(id) => *id,
+                None => {
+                    let (_, text) = readable_text(html);
+                    tx.execute(
+                        "INSERT INTO doc_pages (docset, path, content) VALUES (?1, ?2, ?3)",
+                        params![slug, page_path, text],
+                    )?;
+                    let id = tx.last_insert_rowid();
+                    page_ids.insert(page_path, id);
+                    id
+                }
+            };
+
+            tx.execute(
+                "INSERT INTO doc_entries (docset, name, entry_type, path, page_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![slug, entry_name, entry_type, path, page_id],
+            )?;
+            let entry_id = tx.last_insert_rowid();
+
+            let content: String = if indexed_pages.insert(page_id) {
+                tx.query_row("SELECT content FROM doc_pages WHERE id = ?1", params![page_id], |row| row.get(0))?
+            } else {
+                String::new()
+            };
+            tx.execute(
+                "INSERT INTO doc_fts (rowid, name, content, docset) VALUES (?1, ?2, ?3, ?4)",
+                params![entry_id, entry_name, content, slug],
+            )?;
+
+            if done % 500 == 0 {
+                on_progress(done, entries.len());
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn search(&self, query: &str, docset: Option<&str>, limit: usize) -> Result<Vec<DocSearchResult>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let conn = self.open()?;
+        // Name hits outrank body hits
+        let mut stmt = conn.prepare(
+            "SELECT e.id, e.docset, e.name, e.entry_type, e.path,
+                    snippet(doc_fts, -1, '[', ']', '…', ?4)
+             FROM doc_fts JOIN doc_entries e ON e.id = doc_fts.rowid
+             WHERE doc_fts MATCH ?1 AND (?2 IS NULL OR doc_fts.docset = ?2)
+               AND e.docset IN (SELECT slug FROM docsets)
+             ORDER BY bm25(doc_fts, 10.0, 1.0)
+             LIMIT ?3",
+        )?;
+        let results = stmt
+            .query_map(params![fts_query, docset, limit as i64, SNIPPET_TOKENS], |row| {
+                let docset: String = row.get(1)?;
+                let path: String = row.get(4)?;
+                Ok(DocSearchResult {
+                    id: row.get(0)?,
+                    url: entry_url(&docset, &path),
+                    docset,
+                    name: row.get(2)?,
+                    entry_type: row.get(3)?,
+                    snippet: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(results)
+    }
+
+    pub fn get_entry(&self, id: i64) -> Result<Option<DocEntry>> {
+        let conn = self.open()?;
+        let entry = conn
+            .query_row(
+                "SELECT e.id, e.docset, e.name, e.entry_type, e.path, p.content
+                 FROM doc_entries e JOIN doc_pages p ON p.id = e.page_id
+                 WHERE e.id = ?1",
+                params![id],
+                |row| {
+                    let docset: String = row.get(1)?;
+                    let path: String = row.get(4)?;
+                    Ok(DocEntry {
+                        id: row.get(0)?,
+                        url: entry_url(&docset, &path),
+                        docset,
+                        name: row.get(2)?,
+                        entry_type: row.get(3)?,
+                        content: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(entry)
+    }
+
+    /// Top cached pages for `query`, trimmed for use in a prompt
+    pub fn cite(&self, query: &str, limit: usize) -> Result<Vec<DocCitation>> {
+        let mut citations = Vec::new();
+        let mut seen_urls = HashSet::new();
+        for result in self.search(query, None, limit * 3)? {
+            let page_url = result.url.split('#').next().unwrap_or(&result.url).to_string();
+            if !seen_urls.insert(page_url) {
+                continue;
+            }
+            let Some(entry) = self.get_entry(result.id)? else { continue };
+            citations.push(DocCitation {
+                id: entry.id,
+                name: entry.name,
+                url: entry.url,
+                excerpt: entry.content.chars().take(CITATION_CHARS).collect(),
+            });
+            if citations.len() >= limit {
+                break;
+            }
+        }
+        Ok(citations)
+    }
+}
+
+fn delete_docset_rows(conn: &Connection, slug: &str) -> Result<()> {
+    conn.execute("DELETE FROM doc_fts WHERE docset = ?1", params![slug])?;
+    conn.execute("DELETE FROM doc_entries WHERE docset = ?1", params![slug])?;
+    conn.execute("DELETE FROM doc_pages WHERE docset = ?1", params![slug])?;
+    conn.execute("DELETE FROM docsets WHERE slug = ?1", params![slug])?;
+    Ok(())
+}
+
+fn entry_url(docset: &str, path: &str) -> String {
+    format!("{}/{}/{}", DEVDOCS_SITE, docset, path)
+}
+
+/// Words from free text as prefix terms, quoted so FTS syntax in the query can't break it
+pub fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .take(12)
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+/// Numbered references for an agent prompt, or None when nothing relevant is cached
+pub fn format_citations(citations: &[DocCitation]) -> Option<String> {
+    if citations.is_empty() {
+        return None;
+    }
+    Some(
+        citations
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("[{}] {} ({})\n{}", i + 1, c.name, c.url, c.excerpt))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    )
+}
+
+/// Download and index a devdocs documentation set, replacing any cached copy
+pub async fn download_docset(slug: &str, on_progress: impl Fn(&DocsDownloadProgress)) -> Result<()> {
+    let progress = |stage: &str, done: usize, total: usize| {
+        on_progress(&DocsDownloadProgress { docset: slug.to_string(), stage: stage.to_string(), done, total });
+    };
+
+    progress("downloading", 0, 2);
+    let client = crate::network::client();
+    let index: DevdocsIndex = client
+        .get(format!("{}/{}/index.json", DEVDOCS_BASE, slug))
+        .header("User-Agent", "SAI-IDE")
+        .send()
+        .await?
+        .error_for_status()
+        .with_context(|| format!("Unknown docset '{}'", slug))?
+        .json()
+        .await?;
+    progress("downloading", 1, 2);
+    let pages: HashMap<String, String> = client
+        .get(format!("{}/{}/db.json", DEVDOCS_BASE, slug))
+        .header("User-Agent", "SAI-IDE")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    progress("downloading", 2, 2);
+
+    let name = KNOWN_DOCSETS
+        .iter()
+        .find(|(known, _)| *known == slug)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| slug.to_string());
+    let entries: Vec<(String, String, String)> = index
+        .entries
+        .into_iter()
+        .map(|e| (e.name, e.entry_type, e.path))
+        .collect();
+
+    let slug_owned = slug.to_string();
+    let on_index = move |done: usize, total: usize| progress("indexing", done, total);
+    // Indexing is CPU and disk bound; run it off the async runtime but report progress from here
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<(usize, usize)>();
+    let task = tokio::task::spawn_blocking(move || {
+        DocsCache::new()?.store_docset(&slug_owned, &name, &entries, &pages, &|done, total| {
+            sender.send((done, total)).ok();
+        })
+    });
+    while let Some((done, total)) = receiver.recv().await {
+        on_index(done, total);
+    }
+    task.await??;
+
+    on_progress(&DocsDownloadProgress { docset: slug.to_string(), stage: "done".to_string(), done: 1, total: 1 });
+    Ok(())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_available_docsets() -> Result<Vec<(String, String)>, String> {
+    Ok(KNOWN_DOCSETS.iter().map(|(slug, name)| (slug.to_string(), name.to_string())).collect())
+}
+
+#[tauri::command]
+pub async fn list_cached_docsets() -> Result<Vec<Docset>, String> {
+    DocsCache::new()
+        .and_then(|cache| cache.list_docsets())
+        .map_err(|e| e.to_string())
+}
+
+/// Download a docset for offline use. Emits `docs-download-progress`.
+#[tauri::command]
+pub async fn download_docs(app: tauri::AppHandle, docset: String) -> Result<(), String> {
+    download_docset(&docset, |progress| {
+        app.emit("docs-download-progress", progress).ok();
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_cached_docset(docset: String) -> Result<(), String> {
+    DocsCache::new()
+        .and_then(|cache| cache.remove_docset(&docset))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn search_docs(query: String, docset: Option<String>, limit: Option<usize>) -> Result<Vec<DocSearchResult>, String> {
+    tokio::task::spawn_blocking(move || {
+        DocsCache::new()?.search(&query, docset.as_deref(), limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_doc_entry(id: i64) -> Result<Option<DocEntry>, String> {
+    DocsCache::new()
+        .and_then(|cache| cache.get_entry(id))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_and_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DocsCache { db_path: dir.path().join("docs.db") };
+
+        let entries = vec![
+            ("Array.prototype.map()".to_string(), "Array".to_string(), "global_objects/array/map".to_string()),
+            ("Array.prototype.flatMap()".to_string(), "Array".to_string(), "global_objects/array/flatmap".to_string()),
+            ("Map".to_string(), "Map".to_string(), "global_objects/map".to_string()),
+            ("Map.prototype.get()".to_string(), "Map".to_string(), "global_objects/map#get".to_string()),
+        ];
+        let mut pages = HashMap::new();
+        pages.insert("global_objects/array/map".to_string(), "<h1>map</h1><p>Creates a new array populated with the results of calling a function.</p>".to_string());
+        pages.insert("global_objects/array/flatmap".to_string(), "<p>Maps each element, then flattens the result.</p>".to_string());
+        pages.insert("global_objects/map".to_string(), "<p>Holds key-value pairs and remembers insertion order.</p>".to_string());
+        cache.store_docset("javascript", "MDN JavaScript", &entries, &pages, &|_, _| {}).unwrap();
+
+        let docsets = cache.list_docsets().unwrap();
+        assert_eq!(docsets[0].entry_count, 4);
+        assert_eq!(docsets[0].page_count, 3);
+
+        let results = cache.search("insertion order", Some("javascript"), 10).unwrap();
+        assert_eq!(results[0].name, "Map");
+        assert!(results[0].snippet.contains("[insertion]"));
+        assert!(cache.search("map", Some("python"), 10).unwrap().is_empty());
+        assert!(cache.search("\"); DROP", None, 10).unwrap().is_empty());
+
+        let entry = cache.get_entry(results[0].id).unwrap().unwrap();
+        assert_eq!(entry.url, "https://devdocs.io/javascript/global_objects/map");
+        assert_eq!(entry.content, "Holds key-value pairs and remembers insertion order.");
+
+        let get = cache.search("get", None, 10).unwrap();
+        assert_eq!(get[0].name, "Map.prototype.get()");
+        assert_eq!(cache.get_entry(get[0].id).unwrap().unwrap().content, entry.content);
+
+        // Re-indexing replaces the old version rather than adding to it
+        let mut updated = pages.clone();
+        updated.insert("global_objects/map".to_string(), "<p>Replaced</p>".to_string());
+        cache.store_docset("javascript", "MDN JavaScript", &entries, &updated, &|_, _| {}).unwrap();
+        assert!(cache.search("insertion order", None, 10).unwrap().is_empty());
+        assert_eq!(cache.search("replaced", None, 10).unwrap().len(), 1);
+        assert_eq!(cache.list_docsets().unwrap()[0].entry_count, 4);
+
+        cache.remove_docset("javascript").unwrap();
+        assert!(cache.search("map", None, 10).unwrap().is_empty());
+        assert!(!cache.has_docsets());
+    }
+}
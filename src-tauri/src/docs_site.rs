@@ -0,0 +1,462 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::path::Path;
+use tauri::Emitter;
+use tree_sitter::Node;
+
+use crate::edit_journal::write_tracked;
+use crate::filesystem::collect_source_files;
+use crate::llm::{GenerationRequest, LLMClient};
+use crate::markdown::{self, html_escape, MarkdownRenderOptions};
+use crate::preferences::PreferencesManager;
+use crate::preview::{serve_directory, PreviewServerInfo};
+use crate::readonly::WriteError;
+use crate::syntax::{detect_language, parser_for, SyntaxLanguage, SUPPORTED_EXTENSIONS};
+
+/// Longest signature shown before it's cut off
+const MAX_SIGNATURE_CHARS: usize = 240;
+/// Source sent to the model alongside the API listing
+const MAX_SOURCE_CHARS: usize = 6000;
+
+/// One exported function, type or constant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiItem {
+    pub kind: String,
+    pub name: String,
+    pub signature: String,
+    pub doc: Option<String>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleApi {
+    pub path: String,
+    pub language: String,
+    pub items: Vec<ApiItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsSite {
+    pub modules: usize,
+    pub items: usize,
+    /// Per-module Markdown, relative to the project
+    pub markdown_dir: String,
+    pub site_dir: String,
+    pub preview: Option<PreviewServerInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DocsProgress {
+    module: String,
+    current: usize,
+    total: usize,
+}
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+/// Declaration text up to its body, with whitespace collapsed
+fn signature(node: Node, source: &str) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .or_else(|| node.child_by_field_name("value").and_then(|v| v.child_by_field_name("body")))
+        .map(|body| body.start_byte())
+        .unwrap_or(node.end_byte());
+    let head = source[node.start_byte()..end].split_whitespace().collect::<Vec<_>>().join(" ");
+    let head = head.trim_end_matches(|c| c == '{' || c == ':' || c == ';' || c == '=' || c == ' ').to_string();
+
+    if head.chars().count() > MAX_SIGNATURE_CHARS {
+        format!("{}…", head.chars().take(MAX_SIGNATURE_CHARS).collect::<String>())
+    } else {
+        head
+    }
+}
+
+/// Comment block directly above a declaration, without comment markers
+fn doc_comment(node: Node, source: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut expected_row = node.start_position().row;
+    let mut current = node.prev_sibling();
+
+    while let Some(sibling) = current {
+        if sibling.end_position().row + 1 < expected_row {
+            break;
+        }
+        match sibling.kind() {
+            "comment" | "line_comment" | "block_comment" => lines.push(text(sibling, source)),
+            // Attributes and decorators sit between a Rust/Python doc comment and its item
+            "attribute_item" | "decorator" => {}
+            _ => break,
+        }
+        expected_row = sibling.start_position().row;
+        current = sibling.prev_sibling();
+    }
+    lines.reverse();
+
+    let cleaned: Vec<String> = lines
+        .iter()
+        .flat_map(|comment| comment.lines())
+        .map(|line| {
+            line.trim()
+                .trim_start_matches("/**")
+                .trim_start_matches("/*")
+                .trim_end_matches("*/")
+                .trim_start_matches("//!")
+                .trim_start_matches("///")
+                .trim_start_matches("//")
+                .trim_start_matches('*')
+                .trim()
+                .to_string()
+        })
+        .collect();
+    let doc = cleaned.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+fn python_docstring(node: Node, source: &str) -> Option<String> {
+    let body = node.child_by_field_name("body")?;
+    let first = body.named_child(0)?;
+    let string = (first.kind() == "expression_statement").then(|| first.named_child(0)).flatten()?;
+    if string.kind() != "string" {
+        return None;
+    }
+    let doc = text(string, source).trim_matches(|c| c == '"' || c == '\'').trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+fn item(kind: &str, name: &str, decl: Node, doc_anchor: Node, source: &str) -> ApiItem {
+    ApiItem {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        signature: signature(decl, source),
+        doc: doc_comment(doc_anchor, source),
+        line: decl.start_position().row + 1,
+    }
+}
+
+fn typescript_items(root: Node, source: &str) -> Vec<ApiItem> {
+    let mut items = Vec::new();
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor).filter(|n| n.kind() == "export_statement") {
+        let Some(decl) = statement.child_by_field_name("declaration") else { continue };
+        let kind = match decl.kind() {
+            "function_declaration" | "generator_function_declaration" | "function_signature" => "function",
+            "class_declaration" | "abstract_class_declaration" => "class",
+            "interface_declaration" => "interface",
+            "type_alias_declaration" => "type",
+            "enum_declaration" => "enum",
+            "lexical_declaration" | "variable_declaration" => {
+                let mut declarators = decl.walk();
+                for declarator in decl.named_children(&mut declarators).filter(|n| n.kind() == "variable_declarator") {
+                    if let Some(name) = declarator.child_by_field_name("name") {
+                        let mut api = item("const", text(name, source), declarator, statement, source);
+                        api.signature = format!("export const {}", api.signature);
+                        items.push(api);
+                    }
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if let Some(name) = decl.child_by_field_name("name") {
+            let mut api = item(kind, text(name, source), decl, statement, source);
+            api.signature = format!("export {}", api.signature);
+            items.push(api);
+        }
+    }
+    items
+}
+
+fn python_items(root: Node, source: &str) -> Vec<ApiItem> {
+    let mut items = Vec::new();
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        let decl = match statement.kind() {
+            "decorated_definition" => match statement.child_by_field_name("definition") {
+                Some(definition) => definition,
+                None => continue,
+            },
+            _ => statement,
+        };
+        let kind = match decl.kind() {
+            "function_definition" => "function",
+            "class_definition" => "class",
+            _ => continue,
+        };
+        let Some(name) = decl.child_by_field_name("name").map(|n| text(n, source)) else { continue };
+        if name.starts_with('_') {
+            continue;
+        }
+        let mut api = item(kind, name, decl, statement, source);
+        api.doc = python_docstring(decl, source).or(api.doc);
+        items.push(api);
+    }
+    items
+}
+
+fn rust_items(root: Node, source: &str) -> Vec<ApiItem> {
+    let mut items = Vec::new();
+    let mut cursor = root.walk();
+    for decl in root.named_children(&mut cursor) {
+        let kind = match decl.kind() {
+            "function_item" => "fn",
+            "struct_item" => "struct",
+            "enum_item" => "enum",
+            "trait_item" => "trait",
+            "type_item" => "type",
+            "const_item" => "const",
+            "static_item" => "static",
+            _ => continue,
+        };
+        // Only plain `pub`; pub(crate) isn't part of the public API
+        let mut children = decl.walk();
+        let public = decl
+            .children(&mut children)
+            .any(|c| c.kind() == "visibility_modifier" && text(c, source) == "pub");
+        if !public {
+            continue;
+        }
+        if let Some(name) = decl.child_by_field_name("name") {
+            items.push(item(kind, text(name, source), decl, decl, source));
+        }
+    }
+    items
+}
+
+/// Public API of one source file
+pub fn extract_api(path: &Path, source: &str) -> Result<Vec<ApiItem>> {
+    let language = detect_language(path).ok_or_else(|| anyhow::anyhow!("Unsupported file type"))?;
+    let mut parser = parser_for(language)?;
+    let tree = parser.parse(source, None).ok_or_else(|| anyhow::anyhow!("Failed to parse {}", path.display()))?;
+
+    Ok(match language {
+        SyntaxLanguage::TypeScript | SyntaxLanguage::Tsx => typescript_items(tree.root_node(), source),
+        SyntaxLanguage::Python => python_items(tree.root_node(), source),
+        SyntaxLanguage::Rust => rust_items(tree.root_node(), source),
+    })
+}
+
+fn fence_language(path: &str) -> &'static str {
+    match path.rsplit('.').next().unwrap_or("") {
+        "ts" | "tsx" => "typescript",
+        "py" => "python",
+        "rs" => "rust",
+        _ => "javascript",
+    }
+}
+
+/// Modules with at least one public item, skipping tests
+pub fn collect_modules(project_path: &Path) -> Vec<ModuleApi> {
+    collect_source_files(project_path, SUPPORTED_EXTENSIONS)
+        .into_iter()
+        .filter_map(|path| {
+            let relative = path.strip_prefix(project_path).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            if relative.contains(".test.") || relative.contains(".spec.") || relative.starts_with("tests/") || relative.ends_with(".d.ts") {
+                return None;
+            }
+            let source = std::fs::read_to_string(&path).ok()?;
+            let items = extract_api(&path, &source).ok()?;
+            (!items.is_empty()).then(|| ModuleApi {
+                language: fence_language(&relative).to_string(),
+                path: relative,
+                items,
+            })
+        })
+        .collect()
+}
+
+fn slug(path: &str) -> String {
+    path.chars().map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' }).collect()
+}
+
+pub fn module_markdown(module: &ModuleApi, description: Option<&str>) -> String {
+    let mut out = format!("# `{}`\n\n", module.path);
+    if let Some(description) = description {
+        out.push_str(description.trim());
+        out.push_str("\n\n");
+    }
+    out.push_str("## API\n\n");
+    for item in &module.items {
+        out.push_str(&format!(
+            "### `{}` ({})\n\n```{}\n{}\n```\n\n",
+            item.name, item.kind, module.language, item.signature
+        ));
+        if let Some(doc) = &item.doc {
+            out.push_str(doc);
+            out.push_str("\n\n");
+        }
+        out.push_str(&format!("_Defined at line {}._\n\n", item.line));
+    }
+    out
+}
+
+fn index_markdown(project_name: &str, modules: &[ModuleApi], extension: &str) -> String {
+    let mut out = format!("# {} API reference\n\n| Module | Items |\n| --- | --- |\n", project_name);
+    for module in modules {
+        out.push_str(&format!("| [{}]({}.{}) | {} |\n", module.path, slug(&module.path), extension, module.items.len()));
+    }
+    out
+}
+
+fn page(title: &str, body: &str, modules: &[ModuleApi]) -> String {
+    let nav: String = modules
+        .iter()
+        .map(|m| format!("<li><a href=\"{}.html\">{}</a></li>", slug(&m.path), html_escape(&m.path)))
+        .collect();
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ margin: 0; display: flex; font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; color: #24292f; }}
+nav {{ width: 280px; min-height: 100vh; padding: 1rem; background: #f6f8fa; border-right: 1px solid #d0d7de; box-sizing: border-box; }}
+nav ul {{ list-style: none; padding: 0; font-size: 0.85rem; }}
+nav li {{ margin: 0.3rem 0; word-break: break-all; }}
+main {{ flex: 1; max-width: 900px; padding: 2rem; }}
+pre {{ padding: 0.75rem; border-radius: 6px; overflow-x: auto; }}
+a {{ color: #0969da; text-decoration: none; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #d0d7de; padding: 0.3rem 0.6rem; }}
+</style>
+</head>
+<body>
+<nav><a href="index.html"><strong>Overview</strong></a><ul>{nav}</ul></nav>
+<main>{body}</main>
+</body>
+</html>
+"#,
+        title = html_escape(title),
+        nav = nav,
+        body = body
+    )
+}
+
+/// A short model-written overview of a module; None when the model is unavailable
+async fn describe(client: &LLMClient, model: &str, module: &ModuleApi, source: &str) -> Option<String> {
+    let api = module.items.iter().map(|i| format!("- {} {}", i.kind, i.signature)).collect::<Vec<_>>().join("\n");
+    let prompt = format!(
+        "Write a short description (one or two paragraphs of Markdown, no headings) of what the module `{}` does and when to use it.\n\nPublic API:\n{}\n\nSource:\n```{}\n{}\n```",
+        module.path,
+        api,
+        module.language,
+        source.chars().take(MAX_SOURCE_CHARS).collect::<String>()
+    );
+    let request = GenerationRequest {
+        model: model.to_string(),
+        prompt,
+        system_prompt: Some("You are a technical writer documenting a codebase for its developers. Be accurate and concise.".to_string()),
+        temperature: 0.3,
+        max_tokens: 400,
+    };
+
+    match client.generate(request).await {
+        Ok(response) => Some(response.text),
+        Err(e) => {
+            tracing::warn!("Skipping description for {}: {}", module.path, e);
+            None
+        }
+    }
+}
+
+/// Write `docs/api/*.md` and a static HTML site in `docs/site`
+pub async fn generate(project_path: &Path, describe_modules: bool, on_progress: impl Fn(&str, usize, usize)) -> Result<(usize, usize)> {
+    let root = project_path.to_path_buf();
+    let modules = tokio::task::spawn_blocking(move || collect_modules(&root)).await?;
+    if modules.is_empty() {
+        anyhow::bail!("No public API found in {}", project_path.display());
+    }
+
+    let prefs = PreferencesManager::new()?.load()?;
+    let client = LLMClient::with_url(prefs.llm.base_url.clone());
+    let markdown_dir = project_path.join("docs").join("api");
+    let site_dir = project_path.join("docs").join("site");
+
+    let project_name = project_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let options = MarkdownRenderOptions::default();
+
+    for (index, module) in modules.iter().enumerate() {
+        on_progress(&module.path, index + 1, modules.len());
+
+        let description = if describe_modules {
+            let source = std::fs::read_to_string(project_path.join(&module.path)).unwrap_or_default();
+            describe(&client, &prefs.llm.default_model, module, &source).await
+        } else {
+            None
+        };
+
+        let content = module_markdown(module, description.as_deref());
+        write_tracked(&markdown_dir.join(format!("{}.md", slug(&module.path))), "docs", &content)?;
+        let html = markdown::render(&content, &options)?;
+        write_tracked(&site_dir.join(format!("{}.html", slug(&module.path))), "docs", &page(&module.path, &html, &modules))?;
+    }
+
+    write_tracked(&markdown_dir.join("README.md"), "docs", &index_markdown(&project_name, &modules, "md"))?;
+    let index = markdown::render(&index_markdown(&project_name, &modules, "html"), &options)?;
+    write_tracked(&site_dir.join("index.html"), "docs", &page(&project_name, &index, &modules))?;
+
+    let items = modules.iter().map(|m| m.items.len()).sum();
+    Ok((modules.len(), items))
+}
+
+// Tauri commands
+
+/// Build API docs for a project and serve them through the preview server.
+/// Emits `docs-site-progress` for each module.
+#[tauri::command]
+pub async fn generate_docs_site(
+    app: tauri::AppHandle,
+    project_path: String,
+    describe: Option<bool>,
+    serve: Option<bool>,
+) -> Result<DocsSite, WriteError> {
+    let root = Path::new(&project_path);
+    let (modules, items) = generate(root, describe.unwrap_or(true), |module, current, total| {
+        app.emit("docs-site-progress", DocsProgress { module: module.to_string(), current, total }).ok();
+    })
+    .await?;
+
+    let site_dir = root.join("docs").join("site");
+    let preview = if serve.unwrap_or(true) {
+        let info = serve_directory(&site_dir).await.map_err(|e| e.to_string())?;
+        app.emit("preview-ready", &info).ok();
+        Some(info)
+    } else {
+        None
+    };
+
+    Ok(DocsSite {
+        modules,
+        items,
+        markdown_dir: "docs/api".to_string(),
+        site_dir: "docs/site".to_string(),
+        preview,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_api() {
+        let ts = "import x from 'x';\n/** Formats a number */\nexport function format(n: number): string {\n  return `${n}`;\n}\nexport const double = (n: number) => n * 2;\nfunction hidden() {}\n";
+        let items = extract_api(Path::new("a.ts"), ts).unwrap();
+        assert_eq!(items.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(), ["format", "double"]);
+        assert_eq!(items[0].signature, "export function format(n: number): string");
+        assert_eq!(items[0].doc.as_deref(), Some("Formats a number"));
+
+        let py = "def load(path):\n    \"\"\"Read the file.\"\"\"\n    return open(path)\n\ndef _private():\n    pass\n";
+        let items = extract_api(Path::new("a.py"), py).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].signature, "def load(path)");
+        assert_eq!(items[0].doc.as_deref(), Some("Read the file."));
+
+        let rs = "/// Parses input\n#[inline]\npub fn parse(s: &str) -> u32 { 0 }\npub(crate) fn internal() {}\n";
+        let items = extract_api(Path::new("a.rs"), rs).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].doc.as_deref(), Some("Parses input"));
+    }
+}
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use sysinfo::Disks;
+
+use crate::database;
+use crate::llm::OllamaClient;
+use crate::preferences::{CloudProvider, PreferencesManager};
+use crate::terminal::shell::find_in_path;
+
+const GB: u64 = 1024 * 1024 * 1024;
+/// Local models are several GB each
+const LOW_DISK_WARNING: u64 = 10 * GB;
+const LOW_DISK_ERROR: u64 = GB;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorCheck {
+    pub id: String,
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub version: Option<String>,
+    /// Suggested fix the onboarding UI can show next to a failed check
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+    pub errors: usize,
+    pub warnings: usize,
+    pub generated_at: String,
+}
+
+impl DoctorCheck {
+    fn new(id: &str, name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+            version: None,
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// First line of `<tool> --version`, or None when the tool isn't installed
+fn tool_version(names: &[&str]) -> Option<String> {
+    let executable = names.iter().find_map(|name| find_in_path(name))?;
+    let output = Command::new(executable).arg("--version").output().ok()?;
+    let text = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+    Some(String::from_utf8_lossy(&text).lines().next().unwrap_or("").trim().to_string())
+}
+
+fn check_tool(id: &str, name: &str, executables: &[&str], required: bool, install_hint: &str) -> DoctorCheck {
+    match tool_version(executables) {
+        Some(version) => {
+            let mut check = DoctorCheck::new(id, name, CheckStatus::Ok, format!("{} is installed", name));
+            check.version = Some(version);
+            check
+        }
+        None => {
+            let status = if required { CheckStatus::Error } else { CheckStatus::Warning };
+            DoctorCheck::new(id, name, status, format!("{} was not found on PATH", name)).with_fix(install_hint)
+        }
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / GB as f64)
+}
+
+fn check_disk_space(dir: &Path) -> DoctorCheck {
+    let disks = Disks::new_with_refreshed_list();
+    // The disk holding the directory is the one with the longest matching mount point
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|d| dir.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len());
+
+    let Some(disk) = disk else {
+        return DoctorCheck::new("disk", "Disk space", CheckStatus::Warning, format!("Could not determine the disk for {:?}", dir));
+    };
+
+    let available = disk.available_space();
+    let detail = format!("{} free on {:?}", format_size(available), disk.mount_point());
+
+    if available < LOW_DISK_ERROR {
+        DoctorCheck::new("disk", "Disk space", CheckStatus::Error, detail)
+            .with_fix("Free up disk space; the app needs room for its database and downloaded models")
+    } else if available < LOW_DISK_WARNING {
+        DoctorCheck::new("disk", "Disk space", CheckStatus::Warning, detail)
+            .with_fix("Free up at least 10 GB before downloading local models")
+    } else {
+        DoctorCheck::new("disk", "Disk space", CheckStatus::Ok, detail)
+    }
+}
+
+fn check_database() -> DoctorCheck {
+    let result = database::open().and_then(|db| db.integrity_check());
+
+    match result {
+        Ok(problems) if problems.is_empty() => {
+            DoctorCheck::new("database", "Database", CheckStatus::Ok, "Integrity check passed")
+        }
+        Ok(problems) => DoctorCheck::new("database", "Database", CheckStatus::Error, problems.join("; "))
+            .with_fix("Back up and delete sai-ide.db in the app data folder; it is recreated on the next launch"),
+        Err(e) => DoctorCheck::new("database", "Database", CheckStatus::Error, format!("Failed to open database: {}", e))
+            .with_fix("Check that the app data folder is writable"),
+    }
+}
+
+async fn check_ollama() -> Vec<DoctorCheck> {
+    let prefs = PreferencesManager::new().and_then(|m| m.load()).unwrap_or_default();
+    // Ollama is optional when a cloud provider is configured
+    let missing_status = if prefs.llm.cloud_provider == CloudProvider::Ollama {
+        CheckStatus::Error
+    } else {
+        CheckStatus::Warning
+    };
+
+    let client = OllamaClient::new(prefs.llm.base_url.clone());
+    let models = match client.list_models().await {
+        Ok(models) => models,
+        Err(e) => {
+            return vec![DoctorCheck::new("ollama", "Ollama", missing_status, format!("Not reachable at {}: {}", prefs.llm.base_url, e))
+                .with_fix("Install Ollama from https://ollama.com and start it with `ollama serve`")];
+        }
+    };
+
+    let mut checks = vec![DoctorCheck::new(
+        "ollama",
+        "Ollama",
+        CheckStatus::Ok,
+        format!("Reachable at {} with {} model(s) installed", prefs.llm.base_url, models.len()),
+    )];
+
+    let default_model = &prefs.llm.default_model;
+    // "llama3.2" and "llama3.2:latest" refer to the same model
+    let installed = models.iter().any(|m| m == default_model || m.strip_suffix(":latest") == Some(default_model.as_str()));
+
+    checks.push(if installed {
+        DoctorCheck::new("model", "Default model", CheckStatus::Ok, format!("{} is installed", default_model))
+    } else {
+        DoctorCheck::new("model", "Default model", missing_status, format!("{} is not installed", default_model))
+            .with_fix(format!("Pull the model with `ollama pull {}`", default_model))
+    });
+
+    checks
+}
+
+/// (id, name, executables, required, install hint)
+const TOOL_CHECKS: &[(&str, &str, &[&str], bool, &str)] = &[
+    ("git", "Git", &["git"], true, "Install git from https://git-scm.com/downloads"),
+    ("node", "Node.js", &["node"], false, "Install Node.js LTS from https://nodejs.org"),
+    ("npm", "npm", &["npm"], false, "npm ships with Node.js; reinstall Node.js from https://nodejs.org"),
+    ("python", "Python", &["python3", "python"], false, "Install Python 3 from https://www.python.org/downloads"),
+    ("cargo", "Cargo", &["cargo"], false, "Install Rust with rustup from https://rustup.rs"),
+];
+
+/// Re-run the check for one tool, e.g. after installing it. Ollama is checked by
+/// its binary here, since a fresh install may not be serving yet.
+pub fn check_tool_by_id(id: &str) -> Option<DoctorCheck> {
+    if id == "ollama" {
+        return Some(check_tool("ollama", "Ollama", &["ollama"], false, "Install Ollama from https://ollama.com"));
+    }
+    TOOL_CHECKS
+        .iter()
+        .find(|(check_id, ..)| *check_id == id)
+        .map(|(id, name, executables, required, hint)| check_tool(id, name, executables, *required, hint))
+}
+
+pub async fn run_checks() -> DoctorReport {
+    let tools = tokio::task::spawn_blocking(|| {
+        let data_dir = dirs::data_dir().unwrap_or_default().join(".sai-ide");
+        let mut checks: Vec<DoctorCheck> = TOOL_CHECKS
+            .iter()
+            .map(|(id, name, executables, required, hint)| check_tool(id, name, executables, *required, hint))
+            .collect();
+        checks.push(check_disk_space(&data_dir));
+        checks.push(check_database());
+        checks
+    });
+
+    let (tools, ollama) = tokio::join!(tools, check_ollama());
+
+    let mut checks = tools.unwrap_or_default();
+    checks.extend(ollama);
+
+    DoctorReport {
+        errors: checks.iter().filter(|c| c.status == CheckStatus::Error).count(),
+        warnings: checks.iter().filter(|c| c.status == CheckStatus::Warning).count(),
+        checks,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn run_doctor() -> Result<DoctorReport, String> {
+    Ok(run_checks().await)
+}
@@ -0,0 +1,359 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::i18n::t_args;
+
+/// Open editor buffers, keyed by normalized file path
+static DOCUMENTS: Mutex<Option<HashMap<String, Document>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    pub path: String,
+    /// Incremented by the editor on every change; stale updates are rejected
+    pub version: u64,
+    pub content: String,
+    pub dirty: bool,
+    /// Disk mtime (ms) when the buffer was last loaded or saved
+    pub disk_modified: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSummary {
+    pub path: String,
+    pub version: u64,
+    pub dirty: bool,
+}
+
+/// A change in LSP form: replace the range with `text`, or the whole buffer when `range` is absent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextDelta {
+    pub range: Option<TextRange>,
+    pub text: String,
+}
+
+/// Zero-based line and character positions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextRange {
+    pub start_line: usize,
+    pub start_character: usize,
+    pub end_line: usize,
+    pub end_character: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentConflict {
+    pub path: String,
+    pub dirty: bool,
+    pub disk_modified: Option<u64>,
+    pub buffer_disk_modified: Option<u64>,
+}
+
+fn with_documents<T>(f: impl FnOnce(&mut HashMap<String, Document>) -> T) -> T {
+    let mut guard = DOCUMENTS.lock().unwrap();
+    f(guard.get_or_insert_with(HashMap::new))
+}
+
+fn disk_modified(path: &Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+}
+
+/// Map key for a path, so `./src/a.rs`, `src/../src/a.rs` and a symlinked
+/// parent all find the same buffer. Files that don't exist yet resolve through
+/// their parent directory.
+pub fn normalize_path(path: &str) -> String {
+    let path = Path::new(path);
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.to_path_buf())
+    };
+
+    let mut lexical = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                lexical.pop();
+            }
+            other => lexical.push(other),
+        }
+    }
+
+    let resolved = lexical.canonicalize().ok().or_else(|| {
+        let parent = lexical.parent()?.canonicalize().ok()?;
+        Some(parent.join(lexical.file_name()?))
+    });
+    let normalized = resolved.unwrap_or(lexical).to_string_lossy().to_string();
+    // canonicalize() adds the verbatim prefix on Windows
+    normalized.strip_prefix(r"\\?\").map(String::from).unwrap_or(normalized)
+}
+
+/// Byte offset in `line` of an LSP character position (UTF-16 code units), clamped to the line
+pub fn utf16_to_byte(line: &str, character: usize) -> usize {
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units >= character {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+    line.len()
+}
+
+/// Byte offset of a line/character position, clamped to the buffer. As in LSP,
+/// `character` counts UTF-16 code units.
+fn offset_of(content: &str, line: usize, character: usize) -> usize {
+    let mut offset = 0;
+    for (idx, text) in content.split_inclusive('\n').enumerate() {
+        if idx == line {
+            return offset + utf16_to_byte(text.trim_end_matches('\n'), character);
+        }
+        offset += text.len();
+    }
+    content.len()
+}
+
+pub fn apply_delta(content: &mut String, delta: &TextDelta) {
+    match &delta.range {
+        None => *content = delta.text.clone(),
+        Some(range) => {
+            let start = offset_of(content, range.start_line, range.start_character);
+            let end = offset_of(content, range.end_line, range.end_character).max(start);
+            content.replace_range(start..end, &delta.text);
+        }
+    }
+}
+
+/// Buffer content when the file is open in the editor, disk content otherwise.
+/// Analysis code should read through this so it sees unsaved edits.
+pub fn read_document(path: &Path) -> std::io::Result<String> {
+    let key = normalize_path(&path.to_string_lossy());
+    if let Some(content) = with_documents(|docs| docs.get(&key).map(|d| d.content.clone())) {
+        return Ok(content);
+    }
+    std::fs::read_to_string(path)
+}
+
+/// Open buffers under `root`, for syncing to a language server
+pub fn open_under(root: &Path) -> Vec<(String, Document)> {
+    let root = normalize_path(&root.to_string_lossy());
+    with_documents(|docs| {
+        docs.iter()
+            .filter(|(key, _)| Path::new(key).starts_with(&root))
+            .map(|(key, document)| (key.clone(), document.clone()))
+            .collect()
+    })
+}
+
+pub fn open(path: &str, content: Option<String>) -> Result<Document> {
+    let key = normalize_path(path);
+    if let Some(existing) = with_documents(|docs| docs.get(&key).cloned()) {
+        return Ok(existing);
+    }
+
+    let content = match content {
+        Some(content) => content,
+        None => std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?,
+    };
+
+    let document = Document {
+        path: path.to_string(),
+        version: 0,
+        content,
+        dirty: false,
+        disk_modified: disk_modified(Path::new(path)),
+    };
+
+    with_documents(|docs| docs.insert(key, document.clone()));
+    Ok(document)
+}
+
+pub fn update(path: &str, version: u64, deltas: &[TextDelta]) -> Result<DocumentSummary> {
+    let key = normalize_path(path);
+    with_documents(|docs| {
+        let document = docs.get_mut(&key).with_context(|| t_args("document-not-open", &[("path", &path)]))?;
+
+        if version <= document.version {
+            anyhow::bail!("Stale update for {}: version {} is not newer than {}", path, version, document.version);
+        }
+
+        for delta in deltas {
+            apply_delta(&mut document.content, delta);
+        }
+        document.version = version;
+        document.dirty = true;
+
+        Ok(DocumentSummary { path: path.to_string(), version, dirty: true })
+    })
+}
+
+/// Whether the file changed on disk since the buffer last loaded or saved it
+pub fn conflict(path: &str) -> Option<DocumentConflict> {
+    let document = with_documents(|docs| docs.get(&normalize_path(path)).cloned())?;
+    let on_disk = disk_modified(Path::new(path));
+
+    if on_disk == document.disk_modified {
+        return None;
+    }
+
+    Some(DocumentConflict {
+        path: path.to_string(),
+        dirty: document.dirty,
+        disk_modified: on_disk,
+        buffer_disk_modified: document.disk_modified,
+    })
+}
+
+/// Write the buffer to disk. Fails on a conflict unless `force` is set.
+pub fn save(path: &str, force: bool) -> Result<DocumentSummary> {
+    if !force {
+        if let Some(conflict) = conflict(path) {
+            anyhow::bail!(t_args("document-conflict", &[("path", &conflict.path)]));
+        }
+    }
+
+    let key = normalize_path(path);
+    let document = with_documents(|docs| docs.get(&key).cloned())
+        .with_context(|| t_args("document-not-open", &[("path", &path)]))?;
+
+    if let Some(parent) = Path::new(path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, &document.content)?;
+
+    let modified = disk_modified(Path::new(path));
+    with_documents(|docs| {
+        if let Some(doc) = docs.get_mut(&key) {
+            doc.disk_modified = modified;
+            // Edits that arrived while writing keep the buffer dirty
+            doc.dirty = doc.version != document.version;
+        }
+    });
+
+    Ok(DocumentSummary { path: path.to_string(), version: document.version, dirty: false })
+}
+
+/// Replace the buffer with disk content, discarding unsaved edits
+pub fn reload(path: &str) -> Result<Document> {
+    let content = std::fs::read_to_string(path)?;
+    let modified = disk_modified(Path::new(path));
+
+    with_documents(|docs| {
+        let document = docs
+            .get_mut(&normalize_path(path))
+            .with_context(|| t_args("document-not-open", &[("path", &path)]))?;
+        document.content = content;
+        document.version += 1;
+        document.dirty = false;
+        document.disk_modified = modified;
+        Ok(document.clone())
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn open_document(path: String, content: Option<String>) -> Result<Document, String> {
+    open(&path, content).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_document(path: String, version: u64, deltas: Vec<TextDelta>) -> Result<DocumentSummary, String> {
+    update(&path, version, &deltas).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_document(path: String, force: Option<bool>) -> Result<DocumentSummary, String> {
+    save(&path, force.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reload_document(path: String) -> Result<Document, String> {
+    reload(&path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn close_document(path: String) -> Result<(), String> {
+    with_documents(|docs| docs.remove(&normalize_path(&path)));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_document(path: String) -> Result<Option<Document>, String> {
+    Ok(with_documents(|docs| docs.get(&normalize_path(&path)).cloned()))
+}
+
+#[tauri::command]
+pub async fn list_open_documents() -> Result<Vec<DocumentSummary>, String> {
+    Ok(with_documents(|docs| {
+        docs.values()
+            .map(|d| DocumentSummary { path: d.path.clone(), version: d.version, dirty: d.dirty })
+            .collect()
+    }))
+}
+
+/// Open documents whose files were changed outside the editor
+#[tauri::command]
+pub async fn check_document_conflicts() -> Result<Vec<DocumentConflict>, String> {
+    let paths: Vec<String> = with_documents(|docs| docs.keys().cloned().collect());
+    Ok(paths.iter().filter_map(|p| conflict(p)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_delta() {
+        let mut content = "fn main() {\n    println!(\"hi\");\n}\n".to_string();
+
+        apply_delta(&mut content, &TextDelta {
+            range: Some(TextRange { start_line: 1, start_character: 14, end_line: 1, end_character: 16 }),
+            text: "héllo".to_string(),
+        });
+        assert_eq!(content, "fn main() {\n    println!(\"héllo\");\n}\n");
+
+        // Multi-line replacement across the non-ASCII character
+        apply_delta(&mut content, &TextDelta {
+            range: Some(TextRange { start_line: 0, start_character: 3, end_line: 1, end_character: 16 }),
+            text: "run() { \"".to_string(),
+        });
+        assert_eq!(content, "fn run() { \"llo\");\n}\n");
+
+        // An astral character is two UTF-16 code units
+        let mut content = "let s = \"😀\";\n".to_string();
+        apply_delta(&mut content, &TextDelta {
+            range: Some(TextRange { start_line: 0, start_character: 12, end_line: 0, end_character: 13 }),
+            text: ".to_string();".to_string(),
+        });
+        assert_eq!(content, "let s = \"😀\".to_string();\n");
+    }
+
+    #[test]
+    fn test_save_detects_disk_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one").unwrap();
+        let key = path.to_string_lossy().to_string();
+        let alias = dir.path().join(".").join("a.txt").to_string_lossy().to_string();
+        assert_eq!(normalize_path(&alias), normalize_path(&key));
+
+        open(&alias, None).unwrap();
+        update(&key, 1, &[TextDelta { range: None, text: "two".to_string() }]).unwrap();
+        assert!(update(&key, 1, &[]).is_err());
+        assert_eq!(read_document(&path).unwrap(), "two");
+
+        // Simulate an external edit with a different mtime
+        with_documents(|docs| docs.get_mut(&normalize_path(&key)).unwrap().disk_modified = Some(0));
+        assert!(save(&key, false).is_err());
+        save(&key, true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "two");
+    }
+}
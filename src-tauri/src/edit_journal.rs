@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::documents;
+use crate::file_locks;
+use crate::readonly;
+
+lazy_static::lazy_static! {
+    /// One session per app launch; edits from earlier launches stay on disk under their own id
+    static ref SESSION_ID: String = format!("{}-{}", Utc::now().format("%Y%m%d-%H%M%S"), &uuid::Uuid::new_v4().to_string()[..8]);
+}
+
+/// Journal files are read, changed and rewritten whole
+static WRITE: Mutex<()> = Mutex::new(());
+
+/// A change the backend made to a file on the user's behalf (agent, refactor, auto-fix),
+/// with both versions kept so it can be undone after the editor has reloaded the file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendEdit {
+    pub id: String,
+    pub session_id: String,
+    pub file: String,
+    /// `agent`, `refactor`, ...; the same labels as file locks
+    pub source: String,
+    pub applied_at: DateTime<Utc>,
+    /// None when the edit created the file
+    pub before: Option<String>,
+    pub after: String,
+    #[serde(default)]
+    pub undone_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendEditSummary {
+    pub id: String,
+    pub file: String,
+    pub source: String,
+    pub applied_at: DateTime<Utc>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub created_file: bool,
+    pub undone: bool,
+}
+
+impl BackendEdit {
+    pub fn summary(&self) -> BackendEditSummary {
+        let diff = similar::TextDiff::from_lines(self.before.as_deref().unwrap_or(""), &self.after);
+        let (mut lines_added, mut lines_removed) = (0, 0);
+        for change in diff.iter_all_changes() {
+            match change.tag() {
+                similar::ChangeTag::Insert => lines_added += 1,
+                similar::ChangeTag::Delete => lines_removed += 1,
+                similar::ChangeTag::Equal => {}
+            }
+        }
+        BackendEditSummary {
+            id: self.id.clone(),
+            file: self.file.clone(),
+            source: self.source.clone(),
+            applied_at: self.applied_at,
+            lines_added,
+            lines_removed,
+            created_file: self.before.is_none(),
+            undone: self.undone_at.is_some(),
+        }
+    }
+}
+
+pub fn current_session() -> &'static str {
+    &SESSION_ID
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_DIR: std::cell::RefCell<Option<PathBuf>> = std::cell::RefCell::new(None);
+}
+
+/// Journal this thread's edits under `dir` rather than the data directory, so tests of
+/// features that record edits don't write to the real journal
+#[cfg(test)]
+pub fn use_dir_in_tests(dir: PathBuf) {
+    TEST_DIR.with(|test_dir| *test_dir.borrow_mut() = Some(dir));
+}
+
+pub struct EditJournal {
+    dir: PathBuf,
+}
+
+impl EditJournal {
+    pub fn new() -> Result<Self> {
+        #[cfg(test)]
+        if let Some(dir) = TEST_DIR.with(|test_dir| test_dir.borrow().clone()) {
+            return Ok(Self::at(dir));
+        }
+        let dir = dirs::data_dir()
+            .context("Failed to get data directory")?
+            .join(".sai-ide")
+            .join("edit_journal");
+        Ok(Self::at(dir))
+    }
+
+    pub fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn session_file(&self, session_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", session_id))
+    }
+
+    /// Oldest first
+    pub fn load(&self, session_id: &str) -> Result<Vec<BackendEdit>> {
+        let path = self.session_file(session_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let json = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&json).with_context(|| format!("Invalid edit journal: {:?}", path))
+    }
+
+    fn save(&self, session_id: &str, edits: &[BackendEdit]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.session_file(session_id), serde_json::to_string(edits)?)?;
+        Ok(())
+    }
+
+    /// Sessions on disk, newest first
+    pub fn sessions(&self) -> Vec<String> {
+        let mut sessions: Vec<String> = std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|e| e.file_name().to_string_lossy().strip_suffix(".json").map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        sessions.sort_by(|a, b| b.cmp(a));
+        sessions
+    }
+
+    pub fn record(&self, session_id: &str, file: &Path, source: &str, before: Option<&str>, after: &str) -> Result<Option<String>> {
+        if before == Some(after) {
+            return Ok(None);
+        }
+        let _write = WRITE.lock().unwrap_or_else(|e| e.into_inner());
+        let mut edits = self.load(session_id)?;
+        let id = uuid::Uuid::new_v4().to_string();
+        edits.push(BackendEdit {
+            id: id.clone(),
+            session_id: session_id.to_string(),
+            file: file.to_string_lossy().to_string(),
+            source: source.to_string(),
+            applied_at: Utc::now(),
+            before: before.map(String::from),
+            after: after.to_string(),
+            undone_at: None,
+        });
+        self.save(session_id, &edits)?;
+        Ok(Some(id))
+    }
+
+    /// Put the file back as it was before the edit. Refused when the file has changed
+    /// since, so later work isn't thrown away.
+    pub fn undo(&self, session_id: &str, edit_id: &str) -> Result<BackendEdit> {
+        let _write = WRITE.lock().unwrap_or_else(|e| e.into_inner());
+        let mut edits = self.load(session_id)?;
+        let edit = edits.iter_mut().find(|e| e.id == edit_id).context("Edit not found in this session")?;
+        if edit.undone_at.is_some() {
+            anyhow::bail!("This edit was already undone");
+        }
+
+        let path = PathBuf::from(&edit.file);
+        readonly::check_writable(&path)?;
+        let _lock = file_locks::acquire(&path, "undo", file_locks::WRITE_TTL_SECS)?;
+        let current = std::fs::read_to_string(&path).ok();
+        if current.as_deref() != Some(edit.after.as_str()) {
+            anyhow::bail!("{} has changed since this edit; undo the later edits first or revert by hand", edit.file);
+        }
+        match &edit.before {
+            Some(before) => std::fs::write(&path, before)?,
+            None => std::fs::remove_file(&path)?,
+        }
+        if documents::reload(&edit.file).is_err() {
+            tracing::debug!("{} is not open in the editor", edit.file);
+        }
+
+        edit.undone_at = Some(Utc::now());
+        let undone = edit.clone();
+        self.save(session_id, &edits)?;
+        Ok(undone)
+    }
+}
+
+/// Record an edit in this session's journal. Failing to record never fails the edit itself.
+pub fn record(file: &Path, source: &str, before: Option<&str>, after: &str) -> Option<String> {
+    let recorded = EditJournal::new().and_then(|journal| journal.record(current_session(), file, source, before, after));
+    match recorded {
+        Ok(id) => id,
+        Err(e) => {
+            tracing::warn!("Failed to journal edit to {}: {}", file.display(), e);
+            None
+        }
+    }
+}
+
+/// Write a file on the user's behalf: refuses read-only paths, holds the file lock
+/// for the write and journals the change so it can be undone
+pub fn write_tracked(path: &Path, source: &str, content: &str) -> Result<()> {
+    readonly::check_writable(path)?;
+    let _lock = file_locks::acquire(path, source, file_locks::WRITE_TTL_SECS)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let before = std::fs::read_to_string(path).ok();
+    std::fs::write(path, content)?;
+    record(path, source, before.as_deref(), content);
+    Ok(())
+}
+
+// Tauri commands
+
+/// This session's backend edits, newest first; only those to `file` when given
+#[tauri::command]
+pub async fn list_backend_edits(file: Option<String>) -> Result<Vec<BackendEditSummary>, String> {
+    let journal = EditJournal::new().map_err(|e| e.to_string())?;
+    let edits = journal.load(current_session()).map_err(|e| e.to_string())?;
+    Ok(edits
+        .iter()
+        .rev()
+        .filter(|edit| file.as_deref().map_or(true, |f| Path::new(&edit.file) == Path::new(f)))
+        .map(BackendEdit::summary)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn undo_backend_edit(edit_id: String) -> Result<BackendEditSummary, String> {
+    tokio::task::spawn_blocking(move || EditJournal::new()?.undo(current_session(), &edit_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|edit| edit.summary())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_undo() {
+        let data = tempfile::tempdir().unwrap();
+        let journal = EditJournal::at(data.path().join("journal"));
+        let file = data.path().join("app.ts");
+        let created = data.path().join("new.ts");
+
+        std::fs::write(&file, "a\nb\n").unwrap();
+        std::fs::write(&file, "a\nc\nd\n").unwrap();
+        let edit = journal.record("s1", &file, "agent", Some("a\nb\n"), "a\nc\nd\n").unwrap().unwrap();
+        std::fs::write(&created, "x\n").unwrap();
+        let creation = journal.record("s1", &created, "agent", None, "x\n").unwrap().unwrap();
+        assert!(journal.record("s1", &file, "agent", Some("same"), "same").unwrap().is_none());
+
+        let summary = journal.load("s1").unwrap()[0].summary();
+        assert_eq!((summary.lines_added, summary.lines_removed), (2, 1));
+
+        journal.undo("s1", &edit).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "a\nb\n");
+        assert!(journal.undo("s1", &edit).is_err());
+
+        std::fs::write(&created, "x\nedited by hand\n").unwrap();
+        assert!(journal.undo("s1", &creation).is_err());
+        std::fs::write(&created, "x\n").unwrap();
+        journal.undo("s1", &creation).unwrap();
+        assert!(!created.exists());
+        assert_eq!(journal.sessions(), vec!["s1"]);
+    }
+}
@@ -0,0 +1,338 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use crate::agent::estimate::estimate_tokens;
+use crate::agent::personas::PROMPT_PLACEHOLDER;
+use crate::database::{self, PromptExperiment};
+use crate::llm::{GenerationRequest, LLMClient};
+
+/// Rated comparisons a model needs before it can be recommended
+const MIN_COMPARISONS: usize = 5;
+
+/// One side of an experiment: a model plus an optional prompt template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentVariant {
+    pub model: String,
+    /// Wraps the prompt; must contain `{prompt}`
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+}
+
+fn default_temperature() -> f32 {
+    0.7
+}
+
+fn default_max_tokens() -> u32 {
+    2048
+}
+
+impl ExperimentVariant {
+    fn render(&self, prompt: &str) -> Result<String> {
+        match &self.template {
+            Some(template) if !template.contains(PROMPT_PLACEHOLDER) => {
+                anyhow::bail!("Prompt template must contain {}", PROMPT_PLACEHOLDER)
+            }
+            Some(template) => Ok(template.replace(PROMPT_PLACEHOLDER, prompt)),
+            None => Ok(prompt.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantResult {
+    pub variant: ExperimentVariant,
+    pub output: String,
+    pub duration_ms: u64,
+    /// Estimated from text length
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Set instead of `output` when the model failed
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Preference {
+    A,
+    B,
+    Tie,
+}
+
+impl Preference {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Preference::A => "a",
+            Preference::B => "b",
+            Preference::Tie => "tie",
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "a" => Some(Preference::A),
+            "b" => Some(Preference::B),
+            "tie" => Some(Preference::Tie),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Experiment {
+    pub id: String,
+    pub prompt: String,
+    pub a: VariantResult,
+    pub b: VariantResult,
+    pub preference: Option<Preference>,
+    pub created_at: String,
+}
+
+impl Experiment {
+    fn from_record(record: PromptExperiment) -> Result<Self> {
+        let [a, b]: [VariantResult; 2] = serde_json::from_str(&record.results)?;
+        Ok(Experiment {
+            id: record.id,
+            prompt: record.prompt,
+            a,
+            b,
+            preference: record.preference.as_deref().and_then(Preference::parse),
+            created_at: record.created_at,
+        })
+    }
+}
+
+/// Wins, losses and ties from rated experiments that compared two different models
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ModelScore {
+    pub model: String,
+    pub comparisons: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub ties: usize,
+    /// Ties count as half a win
+    pub win_rate: f64,
+    pub avg_duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentStats {
+    pub rated: usize,
+    pub models: Vec<ModelScore>,
+    /// Best win rate among models with enough comparisons
+    pub recommended_model: Option<String>,
+}
+
+async fn run_variant(client: &LLMClient, prompt: &str, variant: ExperimentVariant) -> Result<VariantResult> {
+    let rendered = variant.render(prompt)?;
+    let input = format!("{}{}", variant.system_prompt.as_deref().unwrap_or(""), rendered);
+    let request = GenerationRequest {
+        model: variant.model.clone(),
+        prompt: rendered,
+        system_prompt: variant.system_prompt.clone(),
+        temperature: variant.temperature,
+        max_tokens: variant.max_tokens,
+    };
+
+    let started = Instant::now();
+    let response = client.generate(request).await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (output, error) = match response {
+        Ok(response) => (response.text, None),
+        Err(e) => (String::new(), Some(e.to_string())),
+    };
+    Ok(VariantResult {
+        input_tokens: estimate_tokens(&input),
+        output_tokens: estimate_tokens(&output),
+        variant,
+        output,
+        duration_ms,
+        error,
+    })
+}
+
+/// Run both variants one after the other, so they don't compete for the GPU
+/// and skew each other's timing, then store the pair
+pub async fn run(prompt: &str, a: ExperimentVariant, b: ExperimentVariant) -> Result<Experiment> {
+    if prompt.trim().is_empty() {
+        anyhow::bail!("Prompt is empty");
+    }
+    let client = LLMClient::new();
+    let a = run_variant(&client, prompt, a).await?;
+    let b = run_variant(&client, prompt, b).await?;
+
+    let experiment = Experiment {
+        id: uuid::Uuid::new_v4().to_string(),
+        prompt: prompt.to_string(),
+        a,
+        b,
+        preference: None,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    database::open()?.add_prompt_experiment(&PromptExperiment {
+        id: experiment.id.clone(),
+        prompt: experiment.prompt.clone(),
+        results: serde_json::to_string(&[&experiment.a, &experiment.b])?,
+        preference: None,
+        created_at: experiment.created_at.clone(),
+    })?;
+    Ok(experiment)
+}
+
+pub fn set_preference(experiment_id: &str, preference: Option<Preference>) -> Result<Experiment> {
+    let db = database::open()?;
+    db.set_experiment_preference(experiment_id, preference.map(|p| p.as_str()))?;
+    let record = db.get_prompt_experiment(experiment_id)?.context("Experiment not found")?;
+    Experiment::from_record(record)
+}
+
+pub fn list(limit: Option<i64>) -> Result<Vec<Experiment>> {
+    let records = database::open()?.list_prompt_experiments(limit)?;
+    Ok(records.into_iter().filter_map(|r| Experiment::from_record(r).ok()).collect())
+}
+
+/// Per-model scores. Template-only experiments (same model on both sides) say
+/// nothing about which model is better and are left out.
+pub fn model_stats(experiments: &[Experiment]) -> ExperimentStats {
+    let mut scores: BTreeMap<String, (ModelScore, u64)> = BTreeMap::new();
+    let mut rated = 0;
+
+    for experiment in experiments {
+        let Some(preference) = experiment.preference else { continue };
+        rated += 1;
+        if experiment.a.variant.model == experiment.b.variant.model {
+            continue;
+        }
+
+        for (result, won, lost) in [
+            (&experiment.a, preference == Preference::A, preference == Preference::B),
+            (&experiment.b, preference == Preference::B, preference == Preference::A),
+        ] {
+            let (score, total_ms) = scores
+                .entry(result.variant.model.clone())
+                .or_insert_with(|| (ModelScore { model: result.variant.model.clone(), ..Default::default() }, 0));
+            score.comparisons += 1;
+            score.wins += won as usize;
+            score.losses += lost as usize;
+            score.ties += (preference == Preference::Tie) as usize;
+            *total_ms += result.duration_ms;
+        }
+    }
+
+    let mut models: Vec<ModelScore> = scores
+        .into_values()
+        .map(|(mut score, total_ms)| {
+            score.win_rate = (score.wins as f64 + score.ties as f64 / 2.0) / score.comparisons as f64;
+            score.avg_duration_ms = total_ms / score.comparisons as u64;
+            score
+        })
+        .collect();
+    models.sort_by(|a, b| b.win_rate.total_cmp(&a.win_rate).then(b.comparisons.cmp(&a.comparisons)));
+
+    ExperimentStats {
+        rated,
+        recommended_model: models.iter().find(|m| m.comparisons >= MIN_COMPARISONS).map(|m| m.model.clone()),
+        models,
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn run_prompt_experiment(
+    prompt: String,
+    variant_a: ExperimentVariant,
+    variant_b: ExperimentVariant,
+) -> Result<Experiment, String> {
+    run(&prompt, variant_a, variant_b).await.map_err(|e| e.to_string())
+}
+
+/// `preference` of None clears an earlier rating
+#[tauri::command]
+pub async fn record_experiment_preference(
+    experiment_id: String,
+    preference: Option<Preference>,
+) -> Result<Experiment, String> {
+    set_preference(&experiment_id, preference).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_prompt_experiments(limit: Option<i64>) -> Result<Vec<Experiment>, String> {
+    list(limit).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_prompt_experiment(experiment_id: String) -> Result<(), String> {
+    database::open()
+        .and_then(|db| db.delete_prompt_experiment(&experiment_id))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_experiment_model_stats() -> Result<ExperimentStats, String> {
+    list(None).map(|experiments| model_stats(&experiments)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model: &str, duration_ms: u64) -> VariantResult {
+        VariantResult {
+            variant: ExperimentVariant {
+                model: model.to_string(),
+                template: None,
+                system_prompt: None,
+                temperature: 0.7,
+                max_tokens: 100,
+            },
+            output: String::new(),
+            duration_ms,
+            input_tokens: 0,
+            output_tokens: 0,
+            error: None,
+        }
+    }
+
+    fn experiment(a: &str, b: &str, preference: Option<Preference>) -> Experiment {
+        Experiment {
+            id: String::new(),
+            prompt: "p".to_string(),
+            a: result(a, 1000),
+            b: result(b, 3000),
+            preference,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_model_stats() {
+        let mut experiments = vec![
+            experiment("small", "large", Some(Preference::B)),
+            experiment("large", "small", Some(Preference::Tie)),
+            experiment("small", "small", Some(Preference::A)),
+            experiment("small", "large", None),
+        ];
+        let stats = model_stats(&experiments);
+        assert_eq!(stats.rated, 3);
+        assert_eq!(stats.models[0].model, "large");
+        assert_eq!((stats.models[0].wins, stats.models[0].ties, stats.models[0].win_rate), (1, 1, 0.75));
+        assert_eq!(stats.models[1].avg_duration_ms, 2000);
+        assert_eq!(stats.recommended_model, None);
+
+        experiments.extend((0..3).map(|_| experiment("small", "large", Some(Preference::A))));
+        let stats = model_stats(&experiments);
+        assert_eq!(stats.recommended_model.as_deref(), Some("small"));
+
+        let templated = ExperimentVariant { template: Some("Explain briefly".to_string()), ..result("m", 0).variant };
+        assert!(templated.render("x").is_err());
+    }
+}
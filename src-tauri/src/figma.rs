@@ -0,0 +1,461 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use crate::secrets;
+
+const API_BASE: &str = "https://api.figma.com/v1";
+const TOKEN_KEY: &str = "figma-token";
+/// Layouts are cut off past this many nodes; deeper detail rarely changes the generated code
+const MAX_NODES: usize = 400;
+
+lazy_static::lazy_static! {
+    /// `figma.com/file/<key>/...` or `figma.com/design/<key>/...`, optionally `?node-id=12-34`
+    static ref FILE_URL: regex::Regex =
+        regex::Regex::new(r"figma\.com/(?:file|design|proto)/([A-Za-z0-9]+)").unwrap();
+    static ref NODE_PARAM: regex::Regex = regex::Regex::new(r"[?&]node-id=([0-9]+[-:%3A]+[0-9]+)").unwrap();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FigmaAccount {
+    pub handle: String,
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+// The subset of Figma's node JSON the layout description needs
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FigmaNode {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    node_type: String,
+    #[serde(default = "default_true")]
+    visible: bool,
+    #[serde(default)]
+    children: Vec<FigmaNode>,
+    absolute_bounding_box: Option<FigmaBox>,
+    layout_mode: Option<String>,
+    item_spacing: Option<f32>,
+    padding_left: Option<f32>,
+    padding_right: Option<f32>,
+    padding_top: Option<f32>,
+    padding_bottom: Option<f32>,
+    primary_axis_align_items: Option<String>,
+    counter_axis_align_items: Option<String>,
+    #[serde(default)]
+    fills: Vec<FigmaPaint>,
+    #[serde(default)]
+    strokes: Vec<FigmaPaint>,
+    stroke_weight: Option<f32>,
+    corner_radius: Option<f32>,
+    opacity: Option<f32>,
+    characters: Option<String>,
+    style: Option<FigmaTextStyle>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FigmaBox {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FigmaPaint {
+    #[serde(rename = "type")]
+    paint_type: String,
+    #[serde(default = "default_true")]
+    visible: bool,
+    color: Option<FigmaColor>,
+    opacity: Option<f32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FigmaColor {
+    r: f32,
+    g: f32,
+    b: f32,
+    a: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FigmaTextStyle {
+    font_family: Option<String>,
+    font_weight: Option<f32>,
+    font_size: Option<f32>,
+    line_height_px: Option<f32>,
+    text_align_horizontal: Option<String>,
+}
+
+/// A design node reduced to what matters for writing UI code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutNode {
+    pub name: String,
+    /// FRAME, TEXT, RECTANGLE, INSTANCE, ...
+    pub kind: String,
+    pub width: f32,
+    pub height: f32,
+    /// Position relative to the parent, for frames without auto layout
+    pub x: f32,
+    pub y: f32,
+    /// "row" or "column" for auto layout frames
+    pub direction: Option<String>,
+    pub gap: Option<f32>,
+    /// Top, right, bottom, left
+    pub padding: Option<[f32; 4]>,
+    pub align: Option<String>,
+    pub background: Option<String>,
+    pub border: Option<String>,
+    pub radius: Option<f32>,
+    pub text: Option<String>,
+    pub font: Option<String>,
+    pub children: Vec<LayoutNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FigmaLayout {
+    pub file_key: String,
+    pub node_id: Option<String>,
+    pub root: LayoutNode,
+    pub colors: Vec<String>,
+    pub fonts: Vec<String>,
+    /// Nodes left out past `MAX_NODES`
+    pub truncated: usize,
+}
+
+/// File key and node id (in API form, `12:34`) from a Figma share link
+pub fn parse_url(url: &str) -> Result<(String, Option<String>)> {
+    let key = FILE_URL.captures(url).context("Not a Figma file link")?[1].to_string();
+    let node = NODE_PARAM
+        .captures(url)
+        .map(|c| c[1].replace("%3A", ":").replace('-', ":"));
+    Ok((key, node))
+}
+
+fn token() -> Result<String> {
+    secrets::get_secret(TOKEN_KEY)?.context("Connect Figma with a personal access token first")
+}
+
+async fn get<T: serde::de::DeserializeOwned>(token: &str, path: &str) -> Result<T> {
+    let response = crate::network::client()
+        .get(format!("{}{}", API_BASE, path))
+        .header("X-Figma-Token", token)
+        .send()
+        .await?;
+    let status = response.status();
+    if status == reqwest::StatusCode::FORBIDDEN {
+        anyhow::bail!("Figma rejected the token, or it has no access to this file");
+    }
+    if !status.is_success() {
+        anyhow::bail!("Figma API returned {}: {}", status, response.text().await.unwrap_or_default());
+    }
+    Ok(response.json().await?)
+}
+
+/// Check a personal access token and store it in the OS keychain
+pub async fn connect(token: &str) -> Result<FigmaAccount> {
+    let token = token.trim();
+    let account: FigmaAccount = get(token, "/me").await?;
+    secrets::store_secret(TOKEN_KEY, token)?;
+    Ok(account)
+}
+
+async fn fetch_node(file_key: &str, node_id: Option<&str>) -> Result<FigmaNode> {
+    let token = token()?;
+    match node_id {
+        Some(node_id) => {
+            #[derive(Deserialize)]
+            struct NodesResponse {
+                nodes: std::collections::HashMap<String, Option<NodeEntry>>,
+            }
+            #[derive(Deserialize)]
+            struct NodeEntry {
+                document: FigmaNode,
+            }
+            let response: NodesResponse =
+                get(&token, &format!("/files/{}/nodes?ids={}&geometry=omit", file_key, node_id)).await?;
+            response
+                .nodes
+                .into_values()
+                .flatten()
+                .next()
+                .map(|entry| entry.document)
+                .with_context(|| format!("Node {} not found in the file", node_id))
+        }
+        None => {
+            // Without a node, the first frame of the first page
+            #[derive(Deserialize)]
+            struct FileResponse {
+                document: FigmaNode,
+            }
+            let response: FileResponse = get(&token, &format!("/files/{}?geometry=omit", file_key)).await?;
+            response
+                .document
+                .children
+                .into_iter()
+                .flat_map(|page| page.children)
+                .find(|node| node.node_type == "FRAME" || node.node_type == "COMPONENT")
+                .context("The file has no frames")
+        }
+    }
+}
+
+fn hex(color: &FigmaColor, opacity: f32) -> String {
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let alpha = color.a * opacity;
+    if alpha < 0.999 {
+        format!("rgba({}, {}, {}, {:.2})", channel(color.r), channel(color.g), channel(color.b), alpha)
+    } else {
+        format!("#{:02X}{:02X}{:02X}", channel(color.r), channel(color.g), channel(color.b))
+    }
+}
+
+fn paint(paints: &[FigmaPaint]) -> Option<String> {
+    let paint = paints.iter().rev().find(|p| p.visible)?;
+    match (paint.paint_type.as_str(), &paint.color) {
+        ("SOLID", Some(color)) => Some(hex(color, paint.opacity.unwrap_or(1.0))),
+        (kind, _) if kind.starts_with("GRADIENT") => Some("gradient".to_string()),
+        ("IMAGE", _) => Some("image".to_string()),
+        _ => None,
+    }
+}
+
+struct Converter {
+    remaining: usize,
+    truncated: usize,
+    colors: BTreeSet<String>,
+    fonts: BTreeSet<String>,
+}
+
+impl Converter {
+    fn convert(&mut self, node: &FigmaNode, parent: Option<&FigmaBox>) -> Option<LayoutNode> {
+        if !node.visible || node.opacity == Some(0.0) {
+            return None;
+        }
+        if self.remaining == 0 {
+            self.truncated += 1;
+            return None;
+        }
+        self.remaining -= 1;
+
+        let bounds = node.absolute_bounding_box.as_ref();
+        let (x, y) = match (bounds, parent) {
+            (Some(b), Some(p)) => (b.x - p.x, b.y - p.y),
+            _ => (0.0, 0.0),
+        };
+        let direction = match node.layout_mode.as_deref() {
+            Some("HORIZONTAL") => Some("row".to_string()),
+            Some("VERTICAL") => Some("column".to_string()),
+            _ => None,
+        };
+        let padding = [node.padding_top, node.padding_right, node.padding_bottom, node.padding_left];
+        let padding = padding
+            .iter()
+            .any(|p| p.unwrap_or(0.0) > 0.0)
+            .then(|| padding.map(|p| p.unwrap_or(0.0)));
+        let align = direction.as_ref().and(match (&node.primary_axis_align_items, &node.counter_axis_align_items) {
+            (None, None) => None,
+            (main, cross) => Some(format!(
+                "main {} cross {}",
+                main.as_deref().unwrap_or("MIN").to_lowercase(),
+                cross.as_deref().unwrap_or("MIN").to_lowercase()
+            )),
+        });
+
+        let is_text = node.node_type == "TEXT";
+        let color = paint(&node.fills);
+        if let Some(color) = color.as_ref().filter(|c| c.starts_with('#') || c.starts_with("rgba")) {
+            self.colors.insert(color.clone());
+        }
+        let font = node.style.as_ref().filter(|_| is_text).map(|style| {
+            let family = style.font_family.clone().unwrap_or_default();
+            self.fonts.insert(family.clone());
+            let mut font = format!(
+                "{} {} {}px",
+                family,
+                style.font_weight.unwrap_or(400.0),
+                style.font_size.unwrap_or(16.0)
+            );
+            if let Some(line_height) = style.line_height_px {
+                let _ = write!(font, "/{}px", line_height.round());
+            }
+            if let Some(align) = style.text_align_horizontal.as_deref().filter(|a| *a != "LEFT") {
+                let _ = write!(font, " {}", align.to_lowercase());
+            }
+            font
+        });
+
+        let children = node
+            .children
+            .iter()
+            .filter_map(|child| self.convert(child, bounds))
+            .collect();
+
+        Some(LayoutNode {
+            name: node.name.clone(),
+            kind: node.node_type.clone(),
+            width: bounds.map(|b| b.width).unwrap_or(0.0),
+            height: bounds.map(|b| b.height).unwrap_or(0.0),
+            x,
+            y,
+            direction,
+            gap: node.item_spacing.filter(|g| *g > 0.0),
+            padding,
+            align,
+            // Text fills are the text color, carried in `font` below
+            background: if is_text { None } else { color.clone() },
+            border: paint(&node.strokes).map(|c| format!("{}px {}", node.stroke_weight.unwrap_or(1.0), c)),
+            radius: node.corner_radius.filter(|r| *r > 0.0),
+            text: node.characters.clone(),
+            font: font.map(|f| match color.filter(|_| is_text) {
+                Some(color) => format!("{} {}", f, color),
+                None => f,
+            }),
+            children,
+        })
+    }
+}
+
+fn convert(file_key: &str, node_id: Option<String>, node: &FigmaNode) -> Result<FigmaLayout> {
+    let mut converter = Converter { remaining: MAX_NODES, truncated: 0, colors: BTreeSet::new(), fonts: BTreeSet::new() };
+    let root = converter.convert(node, None).context("The selected node is hidden")?;
+    Ok(FigmaLayout {
+        file_key: file_key.to_string(),
+        node_id,
+        root,
+        colors: converter.colors.into_iter().collect(),
+        fonts: converter.fonts.into_iter().filter(|f| !f.is_empty()).collect(),
+        truncated: converter.truncated,
+    })
+}
+
+/// Fetch a frame from a Figma link and reduce it to a layout tree
+pub async fn import(url: &str) -> Result<FigmaLayout> {
+    let (file_key, node_id) = parse_url(url)?;
+    let node = fetch_node(&file_key, node_id.as_deref()).await?;
+    convert(&file_key, node_id, &node)
+}
+
+fn describe_node(node: &LayoutNode, depth: usize, out: &mut String) {
+    let _ = write!(out, "{}{} \"{}\" {}x{}", "  ".repeat(depth), node.kind, node.name, node.width.round(), node.height.round());
+    if depth > 0 && node.direction.is_none() && node.kind != "TEXT" {
+        let _ = write!(out, " at {},{}", node.x.round(), node.y.round());
+    }
+    if let Some(direction) = &node.direction {
+        let _ = write!(out, " {}", direction);
+    }
+    if let Some(gap) = node.gap {
+        let _ = write!(out, " gap {}", gap);
+    }
+    if let Some([top, right, bottom, left]) = node.padding {
+        let _ = write!(out, " padding {} {} {} {}", top, right, bottom, left);
+    }
+    if let Some(align) = &node.align {
+        let _ = write!(out, " align {}", align);
+    }
+    if let Some(background) = &node.background {
+        let _ = write!(out, " bg {}", background);
+    }
+    if let Some(border) = &node.border {
+        let _ = write!(out, " border {}", border);
+    }
+    if let Some(radius) = node.radius {
+        let _ = write!(out, " radius {}", radius);
+    }
+    if let Some(font) = &node.font {
+        let _ = write!(out, " font {}", font);
+    }
+    if let Some(text) = &node.text {
+        let _ = write!(out, " text {:?}", text);
+    }
+    out.push('\n');
+    for child in &node.children {
+        describe_node(child, depth + 1, out);
+    }
+}
+
+/// The layout as indented text for a prompt, one node per line
+pub fn describe(layout: &FigmaLayout) -> String {
+    let mut out = String::new();
+    describe_node(&layout.root, 0, &mut out);
+    if layout.truncated > 0 {
+        let _ = writeln!(out, "({} more nodes omitted)", layout.truncated);
+    }
+    if !layout.colors.is_empty() {
+        let _ = writeln!(out, "\nColors: {}", layout.colors.join(", "));
+    }
+    if !layout.fonts.is_empty() {
+        let _ = writeln!(out, "Fonts: {}", layout.fonts.join(", "));
+    }
+    out
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn connect_figma(token: String) -> Result<FigmaAccount, String> {
+    connect(&token).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn disconnect_figma() -> Result<(), String> {
+    secrets::delete_secret(TOKEN_KEY).map_err(|e| e.to_string())
+}
+
+/// The connected account, or None when no token is stored
+#[tauri::command]
+pub async fn get_figma_account() -> Result<Option<FigmaAccount>, String> {
+    let Some(token) = secrets::get_secret(TOKEN_KEY).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    get(&token, "/me").await.map(Some).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_figma_layout(url: String) -> Result<FigmaLayout, String> {
+    import(&url).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_describe() {
+        let (key, node) = parse_url("https://www.figma.com/design/AbC123xyz/Login?node-id=12-34&t=x").unwrap();
+        assert_eq!((key.as_str(), node.as_deref()), ("AbC123xyz", Some("12:34")));
+        assert!(parse_url("https://example.com/design/x").is_err());
+
+        let node: FigmaNode = serde_json::from_value(serde_json::json!({
+            "name": "Card", "type": "FRAME", "layoutMode": "VERTICAL", "itemSpacing": 8,
+            "paddingTop": 16, "paddingRight": 16, "paddingBottom": 16, "paddingLeft": 16,
+            "cornerRadius": 12, "absoluteBoundingBox": {"x": 100, "y": 100, "width": 320, "height": 120},
+            "fills": [{"type": "SOLID", "color": {"r": 1, "g": 1, "b": 1, "a": 1}}],
+            "children": [
+                {"name": "Title", "type": "TEXT", "characters": "Welcome back",
+                 "absoluteBoundingBox": {"x": 116, "y": 116, "width": 200, "height": 32},
+                 "style": {"fontFamily": "Inter", "fontWeight": 600, "fontSize": 24},
+                 "fills": [{"type": "SOLID", "color": {"r": 0, "g": 0, "b": 0, "a": 1}}]},
+                {"name": "Hidden", "type": "RECTANGLE", "visible": false}
+            ]
+        }))
+        .unwrap();
+        let layout = convert("k", None, &node).unwrap();
+        assert_eq!(
+            describe(&layout),
+            "FRAME \"Card\" 320x120 column gap 8 padding 16 16 16 16 bg #FFFFFF radius 12\n  \
+             TEXT \"Title\" 200x32 font Inter 600 24px #000000 text \"Welcome back\"\n\
+             \nColors: #000000, #FFFFFF\nFonts: Inter\n"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Held while a single save or edit set is written
+pub const WRITE_TTL_SECS: i64 = 30;
+/// Held by a window or agent run that asked for a lock and didn't say for how long
+pub const DEFAULT_TTL_SECS: i64 = 300;
+
+/// Advisory locks, keyed by path. Every writer in the app goes through here, so a
+/// window saving a file the agent is rewriting gets a conflict instead of clobbering it.
+static LOCKS: Mutex<Option<HashMap<PathBuf, FileLock>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileLock {
+    pub path: String,
+    /// Who holds it, e.g. `window:main`, `agent` or `refactor`
+    pub owner: String,
+    pub acquired_at: DateTime<Utc>,
+    /// Locks are dropped once expired, so a crashed writer can't hold a file forever
+    pub expires_at: DateTime<Utc>,
+    #[serde(skip)]
+    token: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockStatus {
+    pub path: String,
+    pub lock: Option<FileLock>,
+}
+
+/// Another owner holds the lock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockConflict {
+    pub path: String,
+    pub owner: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl std::fmt::Display for LockConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is being edited by {}; try again when it finishes", self.path, self.owner)
+    }
+}
+
+impl std::error::Error for LockConflict {}
+
+/// Releases the lock when dropped. A guard taken while the same owner already held
+/// the lock leaves that outer lock in place.
+#[derive(Debug)]
+pub struct LockGuard {
+    path: PathBuf,
+    token: Option<u64>,
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        let Some(token) = self.token else { return };
+        let mut locks = LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(map) = locks.as_mut() {
+            if map.get(&self.path).is_some_and(|lock| lock.token == token) {
+                map.remove(&self.path);
+            }
+        }
+    }
+}
+
+fn next_token() -> u64 {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Lock key for a path, so `./src/a.ts`, `src/../src/a.ts` and a symlinked parent
+/// all contend for the same lock
+fn key(path: &Path) -> PathBuf {
+    PathBuf::from(crate::documents::normalize_path(&path.to_string_lossy()))
+}
+
+fn live_lock(map: &mut HashMap<PathBuf, FileLock>, path: &Path) -> Option<FileLock> {
+    match map.get(path) {
+        Some(lock) if lock.expires_at > Utc::now() => Some(lock.clone()),
+        Some(_) => {
+            map.remove(path);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Take the lock on `path` for `ttl_secs`, or extend it when `owner` already holds it
+pub fn acquire(path: &Path, owner: &str, ttl_secs: i64) -> Result<LockGuard, LockConflict> {
+    let path = &key(path);
+    let mut locks = LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    let map = locks.get_or_insert_with(HashMap::new);
+    let expires_at = Utc::now() + Duration::seconds(ttl_secs);
+
+    match live_lock(map, path) {
+        Some(lock) if lock.owner != owner => Err(LockConflict {
+            path: lock.path,
+            owner: lock.owner,
+            expires_at: lock.expires_at,
+        }),
+        Some(_) => {
+            if let Some(lock) = map.get_mut(path) {
+                lock.expires_at = lock.expires_at.max(expires_at);
+            }
+            Ok(LockGuard { path: path.to_path_buf(), token: None })
+        }
+        None => {
+            let token = next_token();
+            map.insert(
+                path.to_path_buf(),
+                FileLock {
+                    path: path.to_string_lossy().to_string(),
+                    owner: owner.to_string(),
+                    acquired_at: Utc::now(),
+                    expires_at,
+                    token,
+                },
+            );
+            Ok(LockGuard { path: path.to_path_buf(), token: Some(token) })
+        }
+    }
+}
+
+/// Lock every path or none, e.g. for a refactor touching several files
+pub fn acquire_all(paths: &[PathBuf], owner: &str, ttl_secs: i64) -> Result<Vec<LockGuard>, LockConflict> {
+    let mut sorted: Vec<PathBuf> = paths.iter().map(|path| key(path)).collect();
+    sorted.sort();
+    sorted.dedup();
+    // Guards taken so far are dropped, and so released, on the first conflict
+    sorted.iter().map(|path| acquire(path, owner, ttl_secs)).collect()
+}
+
+/// Drop `owner`'s lock on `path` before it expires
+pub fn release(path: &Path, owner: &str) -> bool {
+    let path = &key(path);
+    let mut locks = LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(map) = locks.as_mut() else { return false };
+    if map.get(path).is_some_and(|lock| lock.owner == owner) {
+        map.remove(path);
+        true
+    } else {
+        false
+    }
+}
+
+pub fn status(path: &Path) -> LockStatus {
+    let path = &key(path);
+    let mut locks = LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    let lock = locks.as_mut().and_then(|map| live_lock(map, path));
+    LockStatus { path: path.to_string_lossy().to_string(), lock }
+}
+
+/// Owner label for a window's saves
+pub fn window_owner(window: &tauri::Window) -> String {
+    format!("window:{}", window.label())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_lock_status(path: String) -> Result<LockStatus, String> {
+    Ok(status(Path::new(&path)))
+}
+
+/// Hold a file while the window edits it; saves from other windows and agent edits
+/// are refused until it is released or expires
+#[tauri::command]
+pub async fn acquire_file_lock(window: tauri::Window, path: String, ttl_secs: Option<i64>) -> Result<FileLock, String> {
+    let guard = acquire(Path::new(&path), &window_owner(&window), ttl_secs.unwrap_or(DEFAULT_TTL_SECS))
+        .map_err(|e| e.to_string())?;
+    // Kept until `release_file_lock` or expiry rather than the end of this call
+    std::mem::forget(guard);
+    status(Path::new(&path)).lock.ok_or_else(|| "Lock expired immediately".to_string())
+}
+
+#[tauri::command]
+pub async fn release_file_lock(window: tauri::Window, path: String) -> Result<bool, String> {
+    Ok(release(Path::new(&path), &window_owner(&window)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advisory_locks() {
+        let a = PathBuf::from("/tmp/file_locks_test/a.ts");
+        let b = PathBuf::from("/tmp/file_locks_test/b.ts");
+
+        let guard = acquire(&a, "agent", 60).unwrap();
+        let conflict = acquire(&a, "window:main", 60).unwrap_err();
+        assert_eq!(conflict.owner, "agent");
+        // Another spelling of the same path is the same lock
+        assert!(acquire(Path::new("/tmp/file_locks_test/../file_locks_test/./a.ts"), "window:main", 60).is_err());
+        // Re-entrant for the same owner, and the inner guard doesn't release the outer lock
+        drop(acquire(&a, "agent", 60).unwrap());
+        assert_eq!(status(&a).lock.unwrap().owner, "agent");
+
+        // All or nothing
+        assert!(acquire_all(&[b.clone(), a.clone()], "refactor", 60).is_err());
+        assert!(status(&b).lock.is_none());
+
+        drop(guard);
+        assert!(status(&a).lock.is_none());
+        let guards = acquire_all(&[a.clone(), b.clone()], "refactor", 60).unwrap();
+        assert_eq!(guards.len(), 2);
+        drop(guards);
+
+        // Expired locks don't block
+        std::mem::forget(acquire(&b, "window:other", -1).unwrap());
+        assert!(acquire(&b, "window:main", 60).is_ok());
+        assert!(!release(&a, "agent"));
+    }
+}
@@ -0,0 +1,462 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Emitter;
+
+/// Ids of running transfers that have been asked to stop
+static CANCELLED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// What to do when a file already exists at the destination
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    #[default]
+    Overwrite,
+    Skip,
+    /// Keep both, copying to `name (1).ext`
+    Rename,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CopyOptions {
+    pub conflict: ConflictPolicy,
+    pub preserve_permissions: bool,
+    pub preserve_times: bool,
+    /// File or directory names to leave out anywhere in the tree, e.g. "node_modules"
+    pub exclude: Vec<String>,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self {
+            conflict: ConflictPolicy::Overwrite,
+            preserve_permissions: true,
+            preserve_times: true,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferProgress {
+    pub transfer_id: String,
+    /// Source file just handled
+    pub current: String,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransferReport {
+    pub transfer_id: String,
+    pub files_copied: usize,
+    pub files_skipped: usize,
+    pub bytes_copied: u64,
+    /// Stopped by `cancel_transfer`; files already copied are left in place
+    pub cancelled: bool,
+}
+
+/// Everything under the source, directories before their contents. Symlinks are
+/// recreated as links rather than followed, so a link to a directory outside the tree
+/// isn't copied in whole and a link cycle can't recurse forever.
+struct Plan {
+    dirs: Vec<PathBuf>,
+    files: Vec<(PathBuf, u64)>,
+    links: Vec<PathBuf>,
+}
+
+fn plan(source: &Path, exclude: &[String]) -> Result<Plan> {
+    let mut plan = Plan { dirs: Vec::new(), files: Vec::new(), links: Vec::new() };
+    let metadata = fs::metadata(source).with_context(|| format!("Failed to read {:?}", source))?;
+    if !metadata.is_dir() {
+        plan.files.push((PathBuf::new(), metadata.len()));
+        return Ok(plan);
+    }
+
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        plan.dirs.push(relative.clone());
+        let mut entries: Vec<_> = fs::read_dir(source.join(&relative))?.collect::<std::io::Result<_>>()?;
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let name = entry.file_name();
+            if exclude.iter().any(|x| name.to_string_lossy() == x.as_str()) {
+                continue;
+            }
+            let child = relative.join(&name);
+            let metadata = fs::symlink_metadata(entry.path())?;
+            if metadata.file_type().is_symlink() {
+                plan.links.push(child);
+            } else if metadata.is_dir() {
+                pending.push(child);
+            } else {
+                plan.files.push((child, metadata.len()));
+            }
+        }
+    }
+    Ok(plan)
+}
+
+/// `relative` under `base`; the empty path is `base` itself, for single-file transfers
+fn under(base: &Path, relative: &Path) -> PathBuf {
+    if relative.as_os_str().is_empty() {
+        base.to_path_buf()
+    } else {
+        base.join(relative)
+    }
+}
+
+/// `name (1).ext`, `name (2).ext`, ... for the first free name
+fn free_name(path: &Path) -> PathBuf {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !occupied(candidate))
+        .expect("unbounded range")
+}
+
+/// Opened only to change its times. Unix lets the owner do that through a read-only
+/// handle; Windows needs the attribute-write right, and backup semantics for directories.
+/// Neither needs write access, so this works on files `fs::copy` made read-only.
+fn open_for_times(path: &Path) -> std::io::Result<fs::File> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        const FILE_WRITE_ATTRIBUTES: u32 = 0x0100;
+        const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+        fs::File::options()
+            .access_mode(FILE_WRITE_ATTRIBUTES)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)
+    }
+    #[cfg(not(windows))]
+    fs::File::open(path)
+}
+
+fn copy_attributes(from: &Path, to: &Path, options: &CopyOptions) -> Result<()> {
+    let metadata = fs::metadata(from)?;
+    // Times before permissions, so a permission change can't get in the way
+    if options.preserve_times {
+        if let Ok(modified) = metadata.modified() {
+            open_for_times(to)
+                .and_then(|file| file.set_modified(modified))
+                .with_context(|| format!("Failed to set the modification time of {:?}", to))?;
+        }
+    }
+    if options.preserve_permissions {
+        fs::set_permissions(to, metadata.permissions())?;
+    }
+    Ok(())
+}
+
+fn copy_link(from: &Path, to: &Path) -> Result<()> {
+    let target = fs::read_link(from).with_context(|| format!("Failed to read link {:?}", from))?;
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&target, to)?;
+    #[cfg(windows)]
+    {
+        // Relative targets resolve against the link's directory
+        let resolved = from.parent().map(|dir| dir.join(&target)).unwrap_or_else(|| target.clone());
+        if resolved.is_dir() {
+            std::os::windows::fs::symlink_dir(&target, to)?;
+        } else {
+            std::os::windows::fs::symlink_file(&target, to)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether anything, including a dangling link, is at `path`
+fn occupied(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
+/// Where to write `to` under `policy`; `None` to skip it
+fn resolve_conflict(to: PathBuf, policy: ConflictPolicy) -> Result<Option<PathBuf>> {
+    if !occupied(&to) {
+        return Ok(Some(to));
+    }
+    match policy {
+        ConflictPolicy::Overwrite => Ok(Some(to)),
+        ConflictPolicy::Skip => Ok(None),
+        ConflictPolicy::Rename => Ok(Some(free_name(&to))),
+        ConflictPolicy::Fail => anyhow::bail!("{:?} already exists", to),
+    }
+}
+
+fn is_cancelled(transfer_id: &str) -> bool {
+    CANCELLED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map_or(false, |ids| ids.contains(transfer_id))
+}
+
+fn clear_cancelled(transfer_id: &str) {
+    if let Some(ids) = CANCELLED.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        ids.remove(transfer_id);
+    }
+}
+
+pub fn cancel(transfer_id: &str) {
+    CANCELLED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashSet::new)
+        .insert(transfer_id.to_string());
+}
+
+/// Copy a file or directory tree to `destination`. Existing directories are merged into;
+/// existing files follow `options.conflict`. With `remove_source`, each source file is
+/// deleted once copied, which is how `move_path` works across filesystems.
+fn transfer(
+    transfer_id: &str,
+    source: &Path,
+    destination: &Path,
+    options: &CopyOptions,
+    remove_source: bool,
+    on_progress: &dyn Fn(&TransferProgress),
+) -> Result<TransferReport> {
+    let result = run_transfer(transfer_id, source, destination, options, remove_source, on_progress);
+    // Also on failure, so a later transfer reusing the id doesn't start out cancelled
+    clear_cancelled(transfer_id);
+    result
+}
+
+fn run_transfer(
+    transfer_id: &str,
+    source: &Path,
+    destination: &Path,
+    options: &CopyOptions,
+    remove_source: bool,
+    on_progress: &dyn Fn(&TransferProgress),
+) -> Result<TransferReport> {
+    let source = source.canonicalize().with_context(|| format!("Source not found: {:?}", source))?;
+    if let Ok(destination) = destination.canonicalize() {
+        if destination.starts_with(&source) {
+            anyhow::bail!("Cannot copy {:?} into itself", source);
+        }
+    }
+
+    let plan = plan(&source, &options.exclude)?;
+    let bytes_total = plan.files.iter().map(|(_, size)| size).sum();
+    let mut report = TransferReport { transfer_id: transfer_id.to_string(), ..Default::default() };
+    let mut bytes_done = 0;
+
+    for dir in &plan.dirs {
+        fs::create_dir_all(under(destination, dir))?;
+    }
+
+    for (index, (relative, size)) in plan.files.iter().enumerate() {
+        if is_cancelled(transfer_id) {
+            report.cancelled = true;
+            break;
+        }
+
+        let from = under(&source, relative);
+        let to = under(destination, relative);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if let Some(to) = resolve_conflict(to, options.conflict)? {
+            // `fs::copy` would write through a link at the destination
+            if fs::symlink_metadata(&to).map_or(false, |m| m.file_type().is_symlink()) {
+                fs::remove_file(&to)?;
+            }
+            fs::copy(&from, &to).with_context(|| format!("Failed to copy {:?}", from))?;
+            copy_attributes(&from, &to, options)?;
+            report.files_copied += 1;
+            report.bytes_copied += size;
+            if remove_source {
+                fs::remove_file(&from)?;
+            }
+        } else {
+            report.files_skipped += 1;
+        }
+
+        bytes_done += size;
+        on_progress(&TransferProgress {
+            transfer_id: transfer_id.to_string(),
+            current: from.to_string_lossy().to_string(),
+            files_done: index + 1,
+            files_total: plan.files.len(),
+            bytes_done,
+            bytes_total,
+        });
+    }
+
+    for relative in &plan.links {
+        if report.cancelled {
+            break;
+        }
+        let from = under(&source, relative);
+        if let Some(to) = resolve_conflict(under(destination, relative), options.conflict)? {
+            if occupied(&to) {
+                fs::remove_file(&to).with_context(|| format!("Failed to replace {:?}", to))?;
+            }
+            copy_link(&from, &to)?;
+            if remove_source {
+                fs::remove_file(&from)?;
+            }
+        }
+    }
+
+    // Deepest first, after their contents are written
+    for dir in plan.dirs.iter().rev() {
+        copy_attributes(&under(&source, dir), &under(destination, dir), options).ok();
+        if remove_source {
+            // Only succeeds once empty, so skipped and excluded files keep their directories
+            fs::remove_dir(under(&source, dir)).ok();
+        }
+    }
+
+    Ok(report)
+}
+
+pub fn copy_tree(
+    transfer_id: &str,
+    source: &Path,
+    destination: &Path,
+    options: &CopyOptions,
+    on_progress: &dyn Fn(&TransferProgress),
+) -> Result<TransferReport> {
+    transfer(transfer_id, source, destination, options, false, on_progress)
+}
+
+/// Rename when possible, otherwise copy and delete file by file
+pub fn move_tree(
+    transfer_id: &str,
+    source: &Path,
+    destination: &Path,
+    options: &CopyOptions,
+    on_progress: &dyn Fn(&TransferProgress),
+) -> Result<TransferReport> {
+    if !destination.exists() && options.exclude.is_empty() {
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::rename(source, destination).is_ok() {
+            let (files, bytes) = plan(destination, &[])
+                .map(|p| (p.files.len(), p.files.iter().map(|(_, size)| size).sum()))
+                .unwrap_or_default();
+            return Ok(TransferReport {
+                transfer_id: transfer_id.to_string(),
+                files_copied: files,
+                bytes_copied: bytes,
+                ..Default::default()
+            });
+        }
+    }
+    transfer(transfer_id, source, destination, options, true, on_progress)
+}
+
+// Tauri commands
+
+/// Copy a file or directory, emitting `transfer-progress` per file. Pass a
+/// `transfer_id` to be able to stop it with `cancel_transfer`.
+#[tauri::command]
+pub async fn copy_path(
+    app: tauri::AppHandle,
+    source: String,
+    destination: String,
+    options: Option<CopyOptions>,
+    transfer_id: Option<String>,
+) -> Result<TransferReport, String> {
+    crate::readonly::check_writable(Path::new(&destination)).map_err(|e| e.to_string())?;
+    let transfer_id = transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        copy_tree(&transfer_id, Path::new(&source), Path::new(&destination), &options, &|progress| {
+            app.emit("transfer-progress", progress).ok();
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn move_path(
+    app: tauri::AppHandle,
+    source: String,
+    destination: String,
+    options: Option<CopyOptions>,
+    transfer_id: Option<String>,
+) -> Result<TransferReport, String> {
+    for path in [&source, &destination] {
+        crate::readonly::check_writable(Path::new(path)).map_err(|e| e.to_string())?;
+    }
+    let transfer_id = transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        move_tree(&transfer_id, Path::new(&source), Path::new(&destination), &options, &|progress| {
+            app.emit("transfer-progress", progress).ok();
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn cancel_transfer(transfer_id: String) -> Result<(), String> {
+    cancel(&transfer_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_tree_with_conflicts_and_excludes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src");
+        fs::create_dir_all(source.join("lib/node_modules/pkg")).unwrap();
+        fs::write(source.join("a.txt"), "new").unwrap();
+        fs::write(source.join("lib/b.bin"), [0u8, 159, 146, 150]).unwrap();
+        fs::write(source.join("lib/node_modules/pkg/index.js"), "x").unwrap();
+
+        let destination = dir.path().join("dest");
+        fs::create_dir_all(&destination).unwrap();
+        fs::write(destination.join("a.txt"), "old").unwrap();
+
+        let options = CopyOptions {
+            conflict: ConflictPolicy::Rename,
+            exclude: vec!["node_modules".to_string()],
+            ..Default::default()
+        };
+        let report = copy_tree("t1", &source, &destination, &options, &|_| {}).unwrap();
+
+        assert_eq!(report.files_copied, 2);
+        assert_eq!(fs::read_to_string(destination.join("a.txt")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(destination.join("a (1).txt")).unwrap(), "new");
+        assert_eq!(fs::read(destination.join("lib/b.bin")).unwrap(), vec![0u8, 159, 146, 150]);
+        assert!(!destination.join("lib/node_modules").exists());
+        assert!(copy_tree("t2", &source, &source.join("lib"), &options, &|_| {}).is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path(), source.join("lib/up")).unwrap();
+            let linked = dir.path().join("linked");
+            copy_tree("t4", &source, &linked, &CopyOptions::default(), &|_| {}).unwrap();
+            assert!(fs::symlink_metadata(linked.join("lib/up")).unwrap().file_type().is_symlink());
+            assert_eq!(fs::read_link(linked.join("lib/up")).unwrap(), dir.path());
+            fs::remove_dir_all(&linked).unwrap();
+        }
+
+        let moved = dir.path().join("moved");
+        move_tree("t3", &source, &moved, &CopyOptions::default(), &|_| {}).unwrap();
+        assert!(!source.exists());
+        assert!(moved.join("lib/node_modules/pkg/index.js").exists());
+    }
+}
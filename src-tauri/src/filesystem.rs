@@ -3,6 +3,14 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use tauri::command;
 
+use crate::bookmarks;
+use crate::documents;
+use crate::file_locks;
+use crate::git;
+use crate::ignore_files;
+use crate::readonly::{self, WriteError};
+use crate::remote;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileInfo {
     pub name: String,
@@ -10,6 +18,12 @@ pub struct FileInfo {
     pub is_directory: bool,
     pub size: u64,
     pub modified: u64,
+    /// Matched by the project's ignore rules; the tree shows these dimmed
+    #[serde(default)]
+    pub ignored: bool,
+    /// A Git LFS pointer whose content hasn't been pulled; not worth opening as text
+    #[serde(default)]
+    pub lfs_pointer: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,15 +37,33 @@ pub struct DirectoryListing {
 pub async fn read_file(path: String) -> Result<String, String> {
     tracing::info!("Reading file: {}", path);
     
+    if let Some(remote_path) = remote::parse_remote_path(&path) {
+        return remote::read_file(&remote_path).await
+            .map_err(|e| format!("Failed to read file: {}", e));
+    }
+    
+    if git::lfs::is_pointer_file(Path::new(&path)) {
+        return Err(format!("{} is a Git LFS pointer; pull the LFS files to get its content", path));
+    }
+    
     fs::read_to_string(&path)
         .map_err(|e| format!("Failed to read file: {}", e))
 }
 
 /// Write content to a file
 #[command]
-pub async fn write_file(path: String, content: String) -> Result<(), String> {
+pub async fn write_file(app: tauri::AppHandle, window: tauri::Window, path: String, content: String) -> Result<(), WriteError> {
     tracing::info!("Writing file: {}", path);
     
+    if let Some(remote_path) = remote::parse_remote_path(&path) {
+        return remote::write_file(&remote_path, &content).await
+            .map_err(|e| format!("Failed to write file: {}", e).into());
+    }
+    
+    readonly::check_writable(Path::new(&path))?;
+    let _lock = file_locks::acquire(Path::new(&path), &file_locks::window_owner(&window), file_locks::WRITE_TTL_SECS)
+        .map_err(|e| e.to_string())?;
+    
     // Create parent directories if they don't exist
     if let Some(parent) = Path::new(&path).parent() {
         fs::create_dir_all(parent)
@@ -39,16 +71,29 @@ pub async fn write_file(path: String, content: String) -> Result<(), String> {
     }
     
     fs::write(&path, content)
-        .map_err(|e| format!("Failed to write file: {}", e))
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    
+    bookmarks::reconcile_file(&app, Path::new(&path));
+    Ok(())
 }
 
 /// Delete a file
 #[command]
-pub async fn delete_file(path: String) -> Result<(), String> {
+pub async fn delete_file(app: tauri::AppHandle, path: String) -> Result<(), WriteError> {
     tracing::info!("Deleting file: {}", path);
     
+    if let Some(remote_path) = remote::parse_remote_path(&path) {
+        return remote::delete_file(&remote_path).await
+            .map_err(|e| format!("Failed to delete file: {}", e).into());
+    }
+    
+    readonly::check_writable(Path::new(&path))?;
+    
     fs::remove_file(&path)
-        .map_err(|e| format!("Failed to delete file: {}", e))
+        .map_err(|e| format!("Failed to delete file: {}", e))?;
+    
+    bookmarks::reconcile_file(&app, Path::new(&path));
+    Ok(())
 }
 
 /// List directory contents
@@ -56,9 +101,15 @@ pub async fn delete_file(path: String) -> Result<(), String> {
 pub async fn list_directory(path: String) -> Result<DirectoryListing, String> {
     tracing::info!("Listing directory: {}", path);
     
+    if let Some(remote_path) = remote::parse_remote_path(&path) {
+        return remote::list_directory(&remote_path).await
+            .map_err(|e| format!("Failed to read directory: {}", e));
+    }
+    
     let entries = fs::read_dir(&path)
         .map_err(|e| format!("Failed to read directory: {}", e))?;
     
+    let rules = ignore_files::rules_for(Path::new(&path));
     let mut files = Vec::new();
     
     for entry in entries {
@@ -78,6 +129,8 @@ pub async fn list_directory(path: String) -> Result<DirectoryListing, String> {
             is_directory: metadata.is_dir(),
             size: metadata.len(),
             modified,
+            ignored: rules.is_ignored(&entry.path(), metadata.is_dir()),
+            lfs_pointer: git::lfs::is_pointer_file(&entry.path()),
         });
     }
     
@@ -98,28 +151,49 @@ pub async fn list_directory(path: String) -> Result<DirectoryListing, String> {
 pub async fn create_directory(path: String) -> Result<(), String> {
     tracing::info!("Creating directory: {}", path);
     
+    if let Some(remote_path) = remote::parse_remote_path(&path) {
+        return remote::create_directory(&remote_path).await
+            .map_err(|e| format!("Failed to create directory: {}", e));
+    }
+    
     fs::create_dir_all(&path)
         .map_err(|e| format!("Failed to create directory: {}", e))
 }
 
 /// Delete a directory
 #[command]
-pub async fn delete_directory(path: String) -> Result<(), String> {
+pub async fn delete_directory(path: String) -> Result<(), WriteError> {
     tracing::info!("Deleting directory: {}", path);
     
+    if let Some(remote_path) = remote::parse_remote_path(&path) {
+        return remote::delete_directory(&remote_path).await
+            .map_err(|e| format!("Failed to delete directory: {}", e).into());
+    }
+    
+    readonly::check_tree_writable(Path::new(&path))?;
+    
     fs::remove_dir_all(&path)
-        .map_err(|e| format!("Failed to delete directory: {}", e))
+        .map_err(|e| format!("Failed to delete directory: {}", e).into())
 }
 
 /// Check if a path exists
 #[command]
 pub async fn path_exists(path: String) -> Result<bool, String> {
+    if let Some(remote_path) = remote::parse_remote_path(&path) {
+        return remote::path_exists(&remote_path).await.map_err(|e| e.to_string());
+    }
+    
     Ok(Path::new(&path).exists())
 }
 
 /// Get file/directory metadata
 #[command]
 pub async fn get_metadata(path: String) -> Result<FileInfo, String> {
+    if let Some(remote_path) = remote::parse_remote_path(&path) {
+        return remote::get_metadata(&remote_path).await
+            .map_err(|e| format!("Failed to read metadata: {}", e));
+    }
+    
     let metadata = fs::metadata(&path)
         .map_err(|e| format!("Failed to read metadata: {}", e))?;
     
@@ -139,16 +213,29 @@ pub async fn get_metadata(path: String) -> Result<FileInfo, String> {
         is_directory: metadata.is_dir(),
         size: metadata.len(),
         modified,
+        ignored: ignore_files::is_path_ignored(Path::new(&path)),
+        lfs_pointer: git::lfs::is_pointer_file(Path::new(&path)),
     })
 }
 
 /// Rename/move a file or directory
 #[command]
-pub async fn rename_path(old_path: String, new_path: String) -> Result<(), String> {
+pub async fn rename_path(old_path: String, new_path: String) -> Result<(), WriteError> {
     tracing::info!("Renaming {} to {}", old_path, new_path);
     
+    if let Some(remote_path) = remote::parse_remote_path(&old_path) {
+        return remote::rename_path(&remote_path, &new_path).await
+            .map_err(|e| format!("Failed to rename: {}", e).into());
+    }
+    if remote::parse_remote_path(&new_path).is_some() {
+        return Err(format!("Failed to rename: {} is remote and {} is local", new_path, old_path).into());
+    }
+    
+    readonly::check_tree_writable(Path::new(&old_path))?;
+    readonly::check_writable(Path::new(&new_path))?;
+    
     fs::rename(&old_path, &new_path)
-        .map_err(|e| format!("Failed to rename: {}", e))
+        .map_err(|e| format!("Failed to rename: {}", e).into())
 }
 
 /// Copy a file
@@ -156,6 +243,14 @@ pub async fn rename_path(old_path: String, new_path: String) -> Result<(), Strin
 pub async fn copy_file(source: String, destination: String) -> Result<(), String> {
     tracing::info!("Copying {} to {}", source, destination);
     
+    if let Some(remote_path) = remote::parse_remote_path(&source) {
+        return remote::copy_file(&remote_path, &destination).await
+            .map_err(|e| format!("Failed to copy file: {}", e));
+    }
+    if remote::parse_remote_path(&destination).is_some() {
+        return Err(format!("Failed to copy file: {} is remote and {} is local", destination, source));
+    }
+    
     // Create parent directories if they don't exist
     if let Some(parent) = Path::new(&destination).parent() {
         fs::create_dir_all(parent)
@@ -178,12 +273,18 @@ pub async fn search_files(
     tracing::info!("Searching for '{}' in {}", pattern, directory);
     
     let max_results = max_results.unwrap_or(100);
+    if let Some(remote_path) = remote::parse_remote_path(&directory) {
+        return remote::search_files(&remote_path, &pattern, max_results).await
+            .map_err(|e| format!("Failed to search files: {}", e));
+    }
+    
     let mut results = Vec::new();
     let pattern_lower = pattern.to_lowercase();
     
     fn search_recursive(
         dir: &Path,
         pattern: &str,
+        rules: &ignore_files::IgnoreRules,
         results: &mut Vec<FileInfo>,
         max_results: usize,
     ) -> Result<(), String> {
@@ -203,8 +304,8 @@ pub async fn search_files(
             let path = entry.path();
             let name = entry.file_name().to_string_lossy().to_string();
             
-            // Skip hidden files and common ignore patterns
-            if name.starts_with('.') || name == "node_modules" || name == "target" {
+            // Skip hidden files and anything the project ignores
+            if name.starts_with('.') || rules.is_ignored(&path, path.is_dir()) {
                 continue;
             }
             
@@ -225,19 +326,22 @@ pub async fn search_files(
                     is_directory: metadata.is_dir(),
                     size: metadata.len(),
                     modified,
+                    ignored: false,
+                    lfs_pointer: false,
                 });
             }
             
             // Recurse into directories
             if path.is_dir() {
-                let _ = search_recursive(&path, pattern, results, max_results);
+                let _ = search_recursive(&path, pattern, rules, results, max_results);
             }
         }
         
         Ok(())
     }
     
-    search_recursive(Path::new(&directory), &pattern_lower, &mut results, max_results)?;
+    let rules = ignore_files::rules_for(Path::new(&directory));
+    search_recursive(Path::new(&directory), &pattern_lower, &rules, &mut results, max_results)?;
     
     Ok(results)
 }
@@ -292,6 +396,7 @@ pub async fn search_in_project(
     fn search_content_recursive(
         dir: &Path,
         regex: &regex::Regex,
+        rules: &ignore_files::IgnoreRules,
         results: &mut Vec<SearchResult>,
         max_results: usize,
     ) -> Result<(), String> {
@@ -311,13 +416,12 @@ pub async fn search_in_project(
             let path = entry.path();
             let name = entry.file_name().to_string_lossy().to_string();
             
-            // Basic exclusion
-            if name.starts_with('.') || name == "node_modules" || name == "target" || name == "dist" || name == "build" {
+            if name.starts_with('.') || rules.is_ignored(&path, path.is_dir()) {
                 continue;
             }
             
             if path.is_dir() {
-                search_content_recursive(&path, regex, results, max_results)?;
+                search_content_recursive(&path, regex, rules, results, max_results)?;
             } else {
                 // Only search text files (basic heuristic)
                 // In a real app, we'd check mime type or extension
@@ -327,7 +431,8 @@ pub async fn search_in_project(
                     continue;
                 }
 
-                match fs::read_to_string(&path) {
+                // Open editor buffers take precedence so unsaved edits are searchable
+                match documents::read_document(&path) {
                     Ok(content) => {
                         for (line_idx, line) in content.lines().enumerate() {
                             for mat in regex.find_iter(line) {
@@ -351,11 +456,52 @@ pub async fn search_in_project(
         Ok(())
     }
 
-    search_content_recursive(Path::new(&project_path), &regex, &mut results, max_results)?;
+    let rules = ignore_files::rules_for(Path::new(&project_path));
+    search_content_recursive(Path::new(&project_path), &regex, &rules, &mut results, max_results)?;
     
     Ok(results)
 }
 
+/// Recursively collect project files with one of the given extensions,
+/// skipping hidden folders and anything the project's ignore rules exclude
+pub fn collect_source_files(root: &Path, extensions: &[&str]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let rules = ignore_files::rules_for(root);
+
+    fn walk(dir: &Path, extensions: &[&str], rules: &ignore_files::IgnoreRules, files: &mut Vec<PathBuf>) {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = path.is_dir();
+
+            if name.starts_with('.') || rules.is_ignored(&path, is_dir) {
+                continue;
+            }
+
+            if is_dir {
+                // Symlinked directories can point back up the tree
+                if !entry.file_type().map(|t| t.is_symlink()).unwrap_or(true) {
+                    walk(&path, extensions, rules, files);
+                }
+            } else {
+                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+                if extensions.is_empty() || extensions.contains(&ext.as_str()) {
+                    files.push(path);
+                }
+            }
+        }
+    }
+
+    walk(root, extensions, &rules, &mut files);
+    files.sort();
+    files
+}
+
 /// Reveal file in OS explorer
 #[command]
 pub async fn reveal_in_explorer(path: String) -> Result<(), String> {
@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Mutex;
+use tauri::Emitter;
+
+use crate::database::{self, FocusSession};
+use crate::notifications::{self, NotificationCategory};
+use crate::preferences::{FocusPreferences, PreferencesManager};
+
+static TIMER: Mutex<Option<FocusTimer>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusPhase {
+    #[default]
+    Idle,
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl FocusPhase {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FocusPhase::Idle => "idle",
+            FocusPhase::Work => "work",
+            FocusPhase::ShortBreak => "short_break",
+            FocusPhase::LongBreak => "long_break",
+        }
+    }
+
+    fn seconds(&self, prefs: &FocusPreferences) -> i64 {
+        let minutes = match self {
+            FocusPhase::Idle => 0,
+            FocusPhase::Work => prefs.work_minutes,
+            FocusPhase::ShortBreak => prefs.short_break_minutes,
+            FocusPhase::LongBreak => prefs.long_break_minutes,
+        };
+        minutes.max(1) as i64 * 60
+    }
+}
+
+/// Sent as `focus-state` on every change and once a second while running
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FocusState {
+    pub phase: FocusPhase,
+    /// False when paused, or when the next phase waits to be started
+    pub running: bool,
+    pub remaining_seconds: i64,
+    pub phase_seconds: i64,
+    /// Work intervals finished since the last long break
+    pub completed_intervals: u32,
+    pub project_id: Option<String>,
+    pub do_not_disturb: bool,
+}
+
+#[derive(Default)]
+struct FocusTimer {
+    state: FocusState,
+    /// When the running phase ends
+    ends_at: Option<DateTime<Utc>>,
+    phase_started_at: Option<DateTime<Utc>>,
+    /// Bumped on every pause, stop or restart so a stale ticker exits
+    generation: u64,
+}
+
+/// The phase after `phase` and the updated count of work intervals
+pub fn next_phase(phase: FocusPhase, completed: u32, prefs: &FocusPreferences) -> (FocusPhase, u32) {
+    match phase {
+        FocusPhase::Work if completed + 1 >= prefs.intervals_before_long_break.max(1) => {
+            (FocusPhase::LongBreak, completed + 1)
+        }
+        FocusPhase::Work => (FocusPhase::ShortBreak, completed + 1),
+        FocusPhase::ShortBreak => (FocusPhase::Work, completed),
+        FocusPhase::LongBreak | FocusPhase::Idle => (FocusPhase::Work, 0),
+    }
+}
+
+fn preferences() -> FocusPreferences {
+    PreferencesManager::new()
+        .and_then(|manager| manager.load())
+        .map(|prefs| prefs.focus)
+        .unwrap_or_default()
+}
+
+impl FocusTimer {
+    fn remaining(&self, now: DateTime<Utc>) -> i64 {
+        match self.ends_at {
+            Some(ends_at) => (ends_at - now).num_seconds().max(0),
+            None => self.state.remaining_seconds,
+        }
+    }
+
+    /// Enter `phase`, running now or waiting for the user
+    fn enter(&mut self, phase: FocusPhase, running: bool, now: DateTime<Utc>, prefs: &FocusPreferences) {
+        let seconds = phase.seconds(prefs);
+        self.generation += 1;
+        self.state.phase = phase;
+        self.state.running = running && phase != FocusPhase::Idle;
+        self.state.phase_seconds = seconds;
+        self.state.remaining_seconds = seconds;
+        self.ends_at = self.state.running.then(|| now + Duration::seconds(seconds));
+        self.phase_started_at = self.state.running.then_some(now);
+        self.state.do_not_disturb = prefs.do_not_disturb && phase == FocusPhase::Work && self.state.running;
+        notifications::set_do_not_disturb(self.state.do_not_disturb);
+    }
+
+    /// History entry for the phase being left, if it ever ran
+    fn record(&self, completed: bool, now: DateTime<Utc>) -> Option<FocusSession> {
+        let started_at = self.phase_started_at?;
+        Some(FocusSession {
+            id: 0,
+            project_id: self.state.project_id.clone(),
+            phase: self.state.phase.as_str().to_string(),
+            planned_seconds: self.state.phase_seconds,
+            actual_seconds: self.state.phase_seconds - self.remaining(now),
+            completed,
+            started_at: started_at.to_rfc3339(),
+            ended_at: now.to_rfc3339(),
+        })
+    }
+
+    /// Finish the current phase and move to the next one
+    fn advance(&mut self, completed: bool, now: DateTime<Utc>, prefs: &FocusPreferences) -> Option<FocusSession> {
+        let record = self.record(completed, now);
+        let (next, count) = next_phase(self.state.phase, self.state.completed_intervals, prefs);
+        self.state.completed_intervals = count;
+        let auto_start = if next == FocusPhase::Work { prefs.auto_start_work } else { prefs.auto_start_breaks };
+        self.enter(next, auto_start, now, prefs);
+        record
+    }
+}
+
+fn save(record: Option<FocusSession>) {
+    if let Some(session) = record {
+        let result = database::open().and_then(|db| db.add_focus_session(&session));
+        if let Err(e) = result {
+            tracing::warn!("Failed to save focus session: {}", e);
+        }
+    }
+}
+
+fn announce(phase: FocusPhase) {
+    let (title, message) = match phase {
+        FocusPhase::Work => ("Back to work", "The break is over"),
+        FocusPhase::ShortBreak => ("Take a short break", "Work interval complete"),
+        FocusPhase::LongBreak => ("Take a long break", "You finished a full set of work intervals"),
+        FocusPhase::Idle => return,
+    };
+    notifications::info(title, message, NotificationCategory::System);
+}
+
+fn with_timer<T>(f: impl FnOnce(&mut FocusTimer) -> T) -> T {
+    let mut timer = TIMER.lock().unwrap_or_else(|e| e.into_inner());
+    f(timer.get_or_insert_with(FocusTimer::default))
+}
+
+fn snapshot(timer: &FocusTimer, now: DateTime<Utc>) -> FocusState {
+    FocusState { remaining_seconds: timer.remaining(now), ..timer.state.clone() }
+}
+
+/// Emit the state every second until the phase ends or the timer changes hands
+fn spawn_ticker(app: tauri::AppHandle) {
+    let generation = with_timer(|timer| timer.generation);
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            let now = Utc::now();
+            let prefs = preferences();
+            let step = with_timer(|timer| {
+                if timer.generation != generation || !timer.state.running {
+                    return None;
+                }
+                let finished = timer.remaining(now) == 0;
+                let record = finished.then(|| timer.advance(true, now, &prefs)).flatten();
+                Some((finished, record, snapshot(timer, now)))
+            });
+            let Some((finished, record, state)) = step else {
+                return;
+            };
+
+            app.emit("focus-state", &state).ok();
+            if finished {
+                save(record);
+                announce(state.phase);
+                // The next phase gets its own ticker if it started on its own
+                if state.running {
+                    spawn_ticker(app.clone());
+                }
+                return;
+            }
+        }
+    });
+}
+
+fn publish(app: &tauri::AppHandle, state: &FocusState) {
+    app.emit("focus-state", state).ok();
+}
+
+// Tauri commands
+
+/// Start a work interval, abandoning whatever phase was in progress
+#[tauri::command]
+pub async fn start_focus(app: tauri::AppHandle, project_id: Option<String>) -> Result<FocusState, String> {
+    let now = Utc::now();
+    let prefs = preferences();
+    let (record, state) = with_timer(|timer| {
+        let record = timer.record(false, now);
+        timer.state.project_id = project_id;
+        timer.enter(FocusPhase::Work, true, now, &prefs);
+        (record, snapshot(timer, now))
+    });
+    save(record);
+    spawn_ticker(app.clone());
+    publish(&app, &state);
+    Ok(state)
+}
+
+#[tauri::command]
+pub async fn pause_focus(app: tauri::AppHandle) -> Result<FocusState, String> {
+    let now = Utc::now();
+    let state = with_timer(|timer| {
+        if timer.state.running {
+            timer.state.remaining_seconds = timer.remaining(now);
+            timer.state.running = false;
+            timer.ends_at = None;
+            timer.generation += 1;
+            timer.state.do_not_disturb = false;
+            notifications::set_do_not_disturb(false);
+        }
+        snapshot(timer, now)
+    });
+    publish(&app, &state);
+    Ok(state)
+}
+
+/// Resume a paused phase, or start the phase that is waiting
+#[tauri::command]
+pub async fn resume_focus(app: tauri::AppHandle) -> Result<FocusState, String> {
+    let now = Utc::now();
+    let prefs = preferences();
+    let state = with_timer(|timer| {
+        if !timer.state.running && timer.state.phase != FocusPhase::Idle {
+            timer.ends_at = Some(now + Duration::seconds(timer.state.remaining_seconds));
+            timer.phase_started_at.get_or_insert(now);
+            timer.state.running = true;
+            timer.state.do_not_disturb = prefs.do_not_disturb && timer.state.phase == FocusPhase::Work;
+            notifications::set_do_not_disturb(timer.state.do_not_disturb);
+        }
+        snapshot(timer, now)
+    });
+    if state.running {
+        spawn_ticker(app.clone());
+    }
+    publish(&app, &state);
+    Ok(state)
+}
+
+/// End the current phase early and move on to the next
+#[tauri::command]
+pub async fn skip_focus_phase(app: tauri::AppHandle) -> Result<FocusState, String> {
+    let now = Utc::now();
+    let prefs = preferences();
+    let (record, state) = with_timer(|timer| {
+        let record = timer.advance(false, now, &prefs);
+        (record, snapshot(timer, now))
+    });
+    save(record);
+    if state.running {
+        spawn_ticker(app.clone());
+    }
+    publish(&app, &state);
+    Ok(state)
+}
+
+#[tauri::command]
+pub async fn stop_focus(app: tauri::AppHandle) -> Result<FocusState, String> {
+    let now = Utc::now();
+    let prefs = preferences();
+    let (record, state) = with_timer(|timer| {
+        let record = timer.record(false, now);
+        timer.state.completed_intervals = 0;
+        timer.enter(FocusPhase::Idle, false, now, &prefs);
+        (record, snapshot(timer, now))
+    });
+    save(record);
+    publish(&app, &state);
+    Ok(state)
+}
+
+#[tauri::command]
+pub async fn get_focus_state() -> Result<FocusState, String> {
+    Ok(with_timer(|timer| snapshot(timer, Utc::now())))
+}
+
+#[tauri::command]
+pub async fn get_focus_history(project_id: Option<String>, limit: Option<i64>) -> Result<Vec<FocusSession>, String> {
+    database::open()
+        .and_then(|db| db.list_focus_sessions(project_id.as_deref(), limit.unwrap_or(100)))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_cycle() {
+        let prefs = FocusPreferences { intervals_before_long_break: 2, ..FocusPreferences::default() };
+        assert_eq!(next_phase(FocusPhase::Idle, 0, &prefs), (FocusPhase::Work, 0));
+        assert_eq!(next_phase(FocusPhase::Work, 0, &prefs), (FocusPhase::ShortBreak, 1));
+        assert_eq!(next_phase(FocusPhase::ShortBreak, 1, &prefs), (FocusPhase::Work, 1));
+        assert_eq!(next_phase(FocusPhase::Work, 1, &prefs), (FocusPhase::LongBreak, 2));
+        assert_eq!(next_phase(FocusPhase::LongBreak, 2, &prefs), (FocusPhase::Work, 0));
+
+        let now = Utc::now();
+        let mut timer = FocusTimer::default();
+        timer.enter(FocusPhase::Work, true, now, &prefs);
+        assert!(timer.state.do_not_disturb);
+        assert_eq!(timer.remaining(now + Duration::seconds(60)), 24 * 60);
+
+        // Breaks start on their own by default; work waits for the user
+        let record = timer.advance(true, now + Duration::seconds(25 * 60), &prefs).unwrap();
+        assert_eq!((record.phase.as_str(), record.actual_seconds, record.completed), ("work", 25 * 60, true));
+        assert_eq!((timer.state.phase, timer.state.running), (FocusPhase::ShortBreak, true));
+        assert!(!notifications::do_not_disturb());
+        timer.advance(true, now + Duration::seconds(30 * 60), &prefs);
+        assert_eq!((timer.state.phase, timer.state.running), (FocusPhase::Work, false));
+    }
+}
@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+
+use crate::llm::{LLMClient, GenerationRequest};
+use super::commitlint::parse_conventional_commit;
+
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+/// Section order and headings for conventional commit types
+const SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("build", "Build System"),
+    ("ci", "Continuous Integration"),
+    ("chore", "Chores"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    pub hash: String,
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub description: String,
+    pub breaking: bool,
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangelogResult {
+    pub version: String,
+    pub markdown: String,
+    pub entries: Vec<ChangelogEntry>,
+    pub written: bool,
+    pub changelog_path: String,
+}
+
+fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Base URL used to link `#123` references, derived from the origin remote
+fn reference_base_url(repo_path: &Path) -> Option<String> {
+    let url = git(repo_path, &["remote", "get-url", "origin"]).ok()?;
+    let url = url.trim().trim_end_matches(".git");
+
+    // git@github.com:owner/repo -> https://github.com/owner/repo
+    let https = if let Some(rest) = url.strip_prefix("git@") {
+        format!("https://{}", rest.replacen(':', "/", 1))
+    } else if url.starts_with("https://") || url.starts_with("http://") {
+        url.to_string()
+    } else {
+        return None;
+    };
+
+    if https.contains("gitlab") {
+        Some(format!("{}/-/issues/", https))
+    } else {
+        Some(format!("{}/issues/", https))
+    }
+}
+
+fn previous_tag(repo_path: &Path, to: &str) -> Option<String> {
+    git(repo_path, &["describe", "--tags", "--abbrev=0", &format!("{}^", to)])
+        .ok()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+lazy_static::lazy_static! {
+    static ref REFERENCE: Regex = Regex::new(r"#(\d+)").unwrap();
+    // Trailing "(#123)" that forges append on squash merges
+    static ref TRAILING_REFERENCE: Regex = Regex::new(r"\s*\(#\d+\)\s*$").unwrap();
+}
+
+pub fn collect_entries(repo_path: &Path, from: Option<&str>, to: &str) -> Result<Vec<ChangelogEntry>> {
+    let range = match from {
+        Some(from) => format!("{}..{}", from, to),
+        None => to.to_string(),
+    };
+
+    let log = git(repo_path, &["log", "--no-merges", "--format=%H%x1f%s%x1f%b%x1e", &range])?;
+
+    let entries = log
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            let hash = fields.next()?.to_string();
+            let subject = fields.next()?;
+            let body = fields.next().unwrap_or("").trim();
+
+            let message = if body.is_empty() { subject.to_string() } else { format!("{}\n\n{}", subject, body) };
+            let commit = parse_conventional_commit(&message)?;
+
+            // First mention wins; the subject and body often repeat the same issue
+            let mut seen = HashSet::new();
+            let references: Vec<String> = REFERENCE
+                .captures_iter(&message)
+                .map(|c| c[1].to_string())
+                .filter(|r| seen.insert(r.clone()))
+                .collect();
+
+            let description = TRAILING_REFERENCE
+                .replace(&commit.subject, "")
+                .to_string();
+
+            Some(ChangelogEntry {
+                hash,
+                commit_type: commit.commit_type,
+                scope: commit.scope,
+                description,
+                breaking: commit.breaking,
+                references,
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn format_entry(entry: &ChangelogEntry, base_url: Option<&str>) -> String {
+    let mut line = String::from("- ");
+    if let Some(scope) = &entry.scope {
+        line.push_str(&format!("**{}:** ", scope));
+    }
+    line.push_str(&entry.description);
+
+    for reference in &entry.references {
+        match base_url {
+            Some(base) => line.push_str(&format!(" ([#{}]({}{}))", reference, base, reference)),
+            None => line.push_str(&format!(" (#{})", reference)),
+        }
+    }
+
+    line.push_str(&format!(" ({})", &entry.hash[..entry.hash.len().min(7)]));
+    line
+}
+
+pub fn render_section(version: &str, entries: &[ChangelogEntry], base_url: Option<&str>) -> String {
+    let mut markdown = format!("## {} ({})\n", version, chrono::Local::now().format("%Y-%m-%d"));
+
+    let breaking: Vec<_> = entries.iter().filter(|e| e.breaking).collect();
+    if !breaking.is_empty() {
+        markdown.push_str("\n### ⚠ BREAKING CHANGES\n\n");
+        for entry in breaking {
+            markdown.push_str(&format_entry(entry, base_url));
+            markdown.push('\n');
+        }
+    }
+
+    for (commit_type, heading) in SECTIONS {
+        let section: Vec<_> = entries.iter().filter(|e| e.commit_type == *commit_type).collect();
+        if section.is_empty() {
+            continue;
+        }
+
+        markdown.push_str(&format!("\n### {}\n\n", heading));
+        for entry in section {
+            markdown.push_str(&format_entry(entry, base_url));
+            markdown.push('\n');
+        }
+    }
+
+    markdown
+}
+
+/// Insert a section below the title, replacing an existing section for the same version
+pub fn merge_into_changelog(existing: &str, version: &str, section: &str) -> String {
+    let mut kept = Vec::new();
+    let mut skipping = false;
+
+    for line in existing.lines() {
+        // The date after the version is optional
+        if let Some(heading) = line.strip_prefix("## ") {
+            skipping = heading.split_whitespace().next() == Some(version);
+        }
+        if !skipping {
+            kept.push(line);
+        }
+    }
+
+    let body = kept.join("\n");
+    let (title, rest) = match body.find("\n## ") {
+        Some(idx) => (body[..idx].trim_end().to_string(), body[idx + 1..].to_string()),
+        None if body.trim().is_empty() => ("# Changelog".to_string(), String::new()),
+        None => (body.trim_end().to_string(), String::new()),
+    };
+
+    let mut merged = format!("{}\n\n{}", title, section.trim_end());
+    if !rest.trim().is_empty() {
+        merged.push_str("\n\n");
+        merged.push_str(rest.trim_end());
+    }
+    merged.push('\n');
+    merged
+}
+
+async fn polish(markdown: &str) -> Result<String> {
+    let prompt = format!(
+        r#"Polish the wording of this changelog section for end users.
+
+{markdown}
+
+Rules:
+1. Keep every bullet, heading, link and commit hash exactly as-is
+2. Only rewrite bullet descriptions into clear, past-tense sentences
+3. Do not add or remove entries
+
+Return ONLY the markdown."#,
+        markdown = markdown
+    );
+
+    let request = GenerationRequest {
+        model: "llama3.2:3b".to_string(),
+        prompt,
+        system_prompt: Some("You are a technical writer who edits release notes.".to_string()),
+        temperature: 0.3,
+        max_tokens: 4096,
+    };
+
+    let response = LLMClient::new().generate(request).await?;
+    let text = response.text.trim();
+
+    // Reject answers that dropped content
+    if text.matches("\n- ").count() < markdown.matches("\n- ").count() {
+        return Ok(markdown.to_string());
+    }
+    Ok(text.trim_start_matches("```markdown").trim_start_matches("```").trim_end_matches("```").trim().to_string() + "\n")
+}
+
+// Tauri commands
+
+/// Build changelog notes for `from_tag..to_tag` (defaults: previous tag..HEAD).
+/// Nothing is written unless `write` is set, so the frontend can preview first.
+#[tauri::command]
+pub async fn generate_changelog(
+    repo_path: String,
+    from_tag: Option<String>,
+    to_tag: Option<String>,
+    polish_with_ai: Option<bool>,
+    write: Option<bool>,
+) -> Result<ChangelogResult, String> {
+    let repo = Path::new(&repo_path);
+    let to = to_tag.clone().unwrap_or_else(|| "HEAD".to_string());
+    let from = from_tag.or_else(|| previous_tag(repo, &to));
+
+    let entries = collect_entries(repo, from.as_deref(), &to).map_err(|e| e.to_string())?;
+    let version = to_tag.unwrap_or_else(|| "Unreleased".to_string());
+    let base_url = reference_base_url(repo);
+
+    let mut markdown = render_section(&version, &entries, base_url.as_deref());
+    if polish_with_ai.unwrap_or(false) {
+        markdown = polish(&markdown).await.map_err(|e| e.to_string())?;
+    }
+
+    let changelog_path = repo.join(CHANGELOG_FILE);
+    let write = write.unwrap_or(false);
+
+    if write {
+        let existing = std::fs::read_to_string(&changelog_path).unwrap_or_default();
+        std::fs::write(&changelog_path, merge_into_changelog(&existing, &version, &markdown))
+            .map_err(|e| e.to_string())?;
+        tracing::info!("Updated {:?} with {} entries", changelog_path, entries.len());
+    }
+
+    Ok(ChangelogResult {
+        version,
+        markdown,
+        entries,
+        written: write,
+        changelog_path: changelog_path.to_string_lossy().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_replaces_existing_version() {
+        let existing = "# Changelog\n\n## v1.1.0 (2024-01-01)\n\n- old\n\n## v1.0.0 (2023-12-01)\n\n- first\n";
+        let merged = merge_into_changelog(existing, "v1.1.0", "## v1.1.0 (2024-02-01)\n\n- new\n");
+
+        assert!(merged.starts_with("# Changelog\n\n## v1.1.0 (2024-02-01)"));
+        assert!(!merged.contains("- old"));
+        assert!(merged.contains("## v1.0.0 (2023-12-01)\n\n- first"));
+
+        let undated = merge_into_changelog("# Changelog\n\n## v1.1.0\n\n- old\n", "v1.1.0", "## v1.1.0 (2024-02-01)\n\n- new\n");
+        assert_eq!(undated, "# Changelog\n\n## v1.1.0 (2024-02-01)\n\n- new\n");
+        assert!(merge_into_changelog(existing, "v1.1", "## v1.1 (2024-02-01)\n").contains("- old"));
+    }
+
+    #[test]
+    fn test_merge_into_empty_changelog() {
+        let merged = merge_into_changelog("", "v0.1.0", "## v0.1.0 (2024-01-01)\n\n- init\n");
+        assert_eq!(merged, "# Changelog\n\n## v0.1.0 (2024-01-01)\n\n- init\n");
+    }
+}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use tauri::Emitter;
+use tokio::io::AsyncReadExt;
+use tokio::sync::watch;
+
+lazy_static::lazy_static! {
+    /// `Receiving objects:  45% (450/1000), 1.20 MiB | 1.00 MiB/s`, with or without `remote: `
+    static ref PROGRESS_LINE: Regex = Regex::new(r"^(?:remote:\s*)?([A-Za-z][A-Za-z ]+?):\s+(\d+)%\s+\((\d+)/(\d+)\)").unwrap();
+}
+
+/// Clones in progress, keyed by clone id; sending `true` cancels
+static RUNNING: Mutex<Option<HashMap<String, watch::Sender<bool>>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CloneOptions {
+    /// Only the last `depth` commits
+    #[serde(default)]
+    pub depth: Option<u32>,
+    /// Only `branch` (or the default branch); implied by `depth`
+    #[serde(default)]
+    pub single_branch: bool,
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Check out only these folders; blobs elsewhere are never downloaded
+    #[serde(default)]
+    pub sparse_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitProgress {
+    /// Clone id, or whatever id the caller gave a long-running git command
+    pub id: String,
+    /// As git names it: `Counting objects`, `Receiving objects`, `Resolving deltas`, ...
+    pub phase: String,
+    pub percent: u32,
+    pub current: u64,
+    pub total: u64,
+    /// The line as git wrote it, with transfer size and speed
+    pub line: String,
+}
+
+pub fn parse_progress(id: &str, line: &str) -> Option<GitProgress> {
+    let captures = PROGRESS_LINE.captures(line.trim())?;
+    Some(GitProgress {
+        id: id.to_string(),
+        phase: captures[1].trim().to_string(),
+        percent: captures[2].parse().ok()?,
+        current: captures[3].parse().ok()?,
+        total: captures[4].parse().ok()?,
+        line: line.trim().to_string(),
+    })
+}
+
+pub fn clone_args(url: &str, destination: &Path, options: &CloneOptions) -> Vec<String> {
+    let mut args = vec!["clone".to_string(), "--progress".to_string()];
+    if let Some(depth) = options.depth {
+        args.push(format!("--depth={}", depth));
+    }
+    if options.single_branch {
+        args.push("--single-branch".to_string());
+    }
+    if let Some(branch) = &options.branch {
+        args.extend(["--branch".to_string(), branch.clone()]);
+    }
+    if !options.sparse_paths.is_empty() {
+        // Blobless + sparse: only the blobs of checked-out paths are fetched
+        args.extend(["--filter=blob:none".to_string(), "--sparse".to_string()]);
+    }
+    args.extend(["--".to_string(), url.to_string(), destination.to_string_lossy().to_string()]);
+    args
+}
+
+/// Run git, reporting its progress lines (split on `\r` as well as newlines) until it
+/// exits or `stop` fires. Returns the last lines of stderr for error messages.
+pub(crate) async fn run_with_progress(
+    args: &[String],
+    cwd: Option<&Path>,
+    id: &str,
+    stop: &mut watch::Receiver<bool>,
+    on_progress: &(dyn Fn(GitProgress) + Send + Sync),
+) -> Result<std::result::Result<(), String>> {
+    let mut command = tokio::process::Command::new("git");
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        // Credential prompts would hang with nowhere to answer them
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .kill_on_drop(true);
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let mut child = command.spawn().context("Failed to run git")?;
+    let mut stderr = child.stderr.take().context("No stderr")?;
+
+    let mut tail: Vec<String> = Vec::new();
+    let mut pending: Vec<u8> = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let stopped = loop {
+        tokio::select! {
+            read = stderr.read(&mut buffer) => {
+                let n = read?;
+                if n == 0 {
+                    break false;
+                }
+                pending.extend_from_slice(&buffer[..n]);
+                while let Some(end) = pending.iter().position(|b| *b == b'\r' || *b == b'\n') {
+                    let bytes: Vec<u8> = pending.drain(..=end).collect();
+                    let line = String::from_utf8_lossy(&bytes);
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    match parse_progress(id, line) {
+                        Some(progress) => on_progress(progress),
+                        None => {
+                            tail.push(line.to_string());
+                            if tail.len() > 20 {
+                                tail.remove(0);
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(()) = stop.changed() => break true,
+        }
+    };
+
+    if stopped {
+        child.kill().await.ok();
+        anyhow::bail!("Cancelled");
+    }
+    let status = child.wait().await?;
+    Ok(if status.success() { Ok(()) } else { Err(tail.join("\n")) })
+}
+
+pub async fn clone(
+    url: &str,
+    destination: &Path,
+    options: &CloneOptions,
+    id: &str,
+    stop: &mut watch::Receiver<bool>,
+    on_progress: &(dyn Fn(GitProgress) + Send + Sync),
+) -> Result<()> {
+    let existed = destination.exists();
+    let outcome = run_with_progress(&clone_args(url, destination, options), None, id, stop, on_progress).await;
+
+    let failure = match outcome {
+        Ok(Ok(())) => None,
+        Ok(Err(stderr)) => Some(anyhow::anyhow!("Git clone failed: {}", stderr)),
+        Err(e) => Some(e),
+    };
+    if let Some(e) = failure {
+        // Don't leave a half-written checkout behind, but never remove a folder that was already there
+        if !existed && destination.exists() {
+            std::fs::remove_dir_all(destination).ok();
+        }
+        return Err(e);
+    }
+
+    if !options.sparse_paths.is_empty() {
+        let mut args = vec!["sparse-checkout".to_string(), "set".to_string(), "--".to_string()];
+        args.extend(options.sparse_paths.iter().cloned());
+        if let Err(stderr) = run_with_progress(&args, Some(destination), id, stop, on_progress).await? {
+            anyhow::bail!("Cloned, but setting sparse paths failed: {}", stderr);
+        }
+    }
+
+    tracing::info!("Cloned {} to {:?}", url, destination);
+    Ok(())
+}
+
+fn with_running<T>(f: impl FnOnce(&mut HashMap<String, watch::Sender<bool>>) -> T) -> T {
+    let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    f(running.get_or_insert_with(HashMap::new))
+}
+
+// Tauri commands
+
+/// Clone with `options`, emitting `git-clone-progress` as objects arrive and deltas
+/// resolve. Pass `clone_id` to be able to cancel with `cancel_git_clone`.
+#[tauri::command]
+pub async fn git_clone(
+    app: tauri::AppHandle,
+    url: String,
+    destination: String,
+    options: Option<CloneOptions>,
+    clone_id: Option<String>,
+) -> Result<(), String> {
+    let id = clone_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let (stop_tx, mut stop_rx) = watch::channel(false);
+    with_running(|running| running.insert(id.clone(), stop_tx));
+
+    let on_progress = move |progress: GitProgress| {
+        app.emit("git-clone-progress", &progress).ok();
+    };
+    let result = clone(&url, &PathBuf::from(destination), &options.unwrap_or_default(), &id, &mut stop_rx, &on_progress).await;
+
+    with_running(|running| running.remove(&id));
+    result.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn cancel_git_clone(clone_id: String) -> Result<bool, String> {
+    Ok(with_running(|running| running.get(&clone_id).map(|stop| stop.send(true).is_ok())).unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_args_and_progress() {
+        let options = CloneOptions {
+            depth: Some(1),
+            single_branch: true,
+            branch: Some("main".to_string()),
+            sparse_paths: vec!["docs".to_string()],
+        };
+        assert_eq!(
+            clone_args("https://example.com/r.git", Path::new("/tmp/r"), &options).join(" "),
+            "clone --progress --depth=1 --single-branch --branch main --filter=blob:none --sparse -- https://example.com/r.git /tmp/r"
+        );
+        assert_eq!(
+            clone_args("u", Path::new("d"), &CloneOptions::default()).join(" "),
+            "clone --progress -- u d"
+        );
+
+        let progress = parse_progress("c1", "Receiving objects:  45% (450/1000), 1.20 MiB | 1.00 MiB/s").unwrap();
+        assert_eq!((progress.phase.as_str(), progress.percent, progress.current, progress.total), ("Receiving objects", 45, 450, 1000));
+        let remote = parse_progress("c1", "remote: Counting objects: 100% (12/12), done.").unwrap();
+        assert_eq!(remote.phase, "Counting objects");
+        assert!(parse_progress("c1", "Cloning into 'r'...").is_none());
+    }
+}
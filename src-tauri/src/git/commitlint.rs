@@ -0,0 +1,404 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use regex::Regex;
+use std::path::Path;
+
+use super::hooks::SHIM_MARKER;
+use crate::project::load_project_config;
+
+pub const CONFIG_FILE: &str = "commitlint.json";
+
+/// Marks hooks written by the IDE so user hooks are never overwritten silently
+pub const HOOK_MARKER: &str = "# Managed by LuciAI Studio";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitlintConfig {
+    pub types: Vec<String>,
+    /// Allowed scopes; empty means any scope
+    pub scopes: Vec<String>,
+    pub require_scope: bool,
+    pub header_max_length: usize,
+    pub subject_min_length: usize,
+    pub body_max_line_length: usize,
+}
+
+impl Default for CommitlintConfig {
+    fn default() -> Self {
+        Self {
+            types: ["feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert"]
+                .iter()
+                .map(|t| t.to_string())
+                .collect(),
+            scopes: Vec::new(),
+            require_scope: false,
+            header_max_length: 72,
+            subject_min_length: 3,
+            body_max_line_length: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LintLevel {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintIssue {
+    pub rule: String,
+    pub level: LintLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConventionalCommit {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub subject: String,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitLintResult {
+    pub valid: bool,
+    pub issues: Vec<LintIssue>,
+    pub parsed: Option<ConventionalCommit>,
+}
+
+/// Parse `type(scope)!: subject` plus optional body
+pub fn parse_conventional_commit(message: &str) -> Option<ConventionalCommit> {
+    let header_re = Regex::new(r"^(\w+)(?:\(([^)]+)\))?(!)?: (.*)$").unwrap();
+
+    let mut lines = message.lines();
+    let header = lines.next()?.trim_end();
+    let captures = header_re.captures(header)?;
+
+    let body = message
+        .splitn(2, '\n')
+        .nth(1)
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty());
+
+    let breaking = captures.get(3).is_some()
+        || body.as_deref().map(|b| b.contains("BREAKING CHANGE:") || b.contains("BREAKING-CHANGE:")).unwrap_or(false);
+
+    Some(ConventionalCommit {
+        commit_type: captures[1].to_string(),
+        scope: captures.get(2).map(|s| s.as_str().to_string()),
+        breaking,
+        subject: captures[4].to_string(),
+        body,
+    })
+}
+
+/// Commits git creates itself are exempt from linting
+fn is_generated_message(header: &str) -> bool {
+    header.starts_with("Merge ") || header.starts_with("Revert \"")
+        || header.starts_with("fixup! ") || header.starts_with("squash! ")
+}
+
+pub fn lint(message: &str, config: &CommitlintConfig) -> CommitLintResult {
+    // Drop comment lines git leaves in the message template
+    let message = message
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let message = message.trim();
+
+    let mut issues = Vec::new();
+    let mut issue = |rule: &str, level: LintLevel, message: String| {
+        issues.push(LintIssue { rule: rule.to_string(), level, message });
+    };
+
+    let header = message.lines().next().unwrap_or("");
+
+    if header.is_empty() {
+        issue("header-empty", LintLevel::Error, "Commit message is empty".to_string());
+        return CommitLintResult { valid: false, issues, parsed: None };
+    }
+
+    if is_generated_message(header) {
+        return CommitLintResult { valid: true, issues, parsed: None };
+    }
+
+    if header.chars().count() > config.header_max_length {
+        issue("header-max-length", LintLevel::Error, format!(
+            "Header is {} characters; the limit is {}",
+            header.chars().count(),
+            config.header_max_length
+        ));
+    }
+
+    let parsed = parse_conventional_commit(message);
+
+    match &parsed {
+        None => issue("header-format", LintLevel::Error,
+            "Header must look like `type(scope): subject`".to_string()),
+        Some(commit) => {
+            if !config.types.contains(&commit.commit_type) {
+                issue("type-enum", LintLevel::Error, format!(
+                    "Type '{}' is not one of: {}",
+                    commit.commit_type,
+                    config.types.join(", ")
+                ));
+            }
+
+            match &commit.scope {
+                None if config.require_scope => {
+                    issue("scope-empty", LintLevel::Error, "A scope is required".to_string());
+                }
+                Some(scope) if !config.scopes.is_empty() && !config.scopes.contains(scope) => {
+                    issue("scope-enum", LintLevel::Error, format!(
+                        "Scope '{}' is not one of: {}",
+                        scope,
+                        config.scopes.join(", ")
+                    ));
+                }
+                _ => {}
+            }
+
+            let subject = commit.subject.trim();
+            if subject.chars().count() < config.subject_min_length {
+                issue("subject-min-length", LintLevel::Error, "Subject is too short".to_string());
+            }
+            if subject.ends_with('.') {
+                issue("subject-full-stop", LintLevel::Warning, "Subject should not end with a period".to_string());
+            }
+            if subject.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
+                issue("subject-case", LintLevel::Warning, "Subject should start with a lowercase letter".to_string());
+            }
+        }
+    }
+
+    let mut lines = message.lines().skip(1);
+    if let Some(second) = lines.next() {
+        if !second.trim().is_empty() {
+            issue("body-leading-blank", LintLevel::Error, "Leave a blank line between header and body".to_string());
+        }
+    }
+
+    for (idx, line) in message.lines().enumerate().skip(2) {
+        // URLs can't be wrapped
+        if line.chars().count() > config.body_max_line_length && !line.contains("://") {
+            issue("body-max-line-length", LintLevel::Warning, format!(
+                "Line {} is longer than {} characters",
+                idx + 1,
+                config.body_max_line_length
+            ));
+        }
+    }
+
+    let valid = !issues.iter().any(|i| i.level == LintLevel::Error);
+    CommitLintResult { valid, issues, parsed }
+}
+
+/// Escape a literal for a POSIX extended regex inside a single-quoted shell string
+fn ere_literal(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' | '.' | '[' | ']' | '(' | ')' | '*' | '+' | '?' | '{' | '}' | '|' | '^' | '$' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\'' => escaped.push_str(r"'\''"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render a standalone commit-msg hook enforcing the header rules from the config
+pub fn render_hook(config: &CommitlintConfig) -> String {
+    let alternatives = |items: &[String]| items.iter().map(|i| ere_literal(i)).collect::<Vec<_>>().join("|");
+
+    let scope = if config.scopes.is_empty() {
+        r"\([^)]+\)".to_string()
+    } else {
+        format!(r"\(({})\)", alternatives(&config.scopes))
+    };
+    let scope = if config.require_scope { scope } else { format!("({})?", scope) };
+
+    format!(
+        r#"#!/bin/sh
+{marker}
+# Rules come from .luciai/commitlint.json; reinstall the hook from the IDE after editing it.
+
+# The hook this check replaced runs first, unless the check is itself that backup
+backup="$(dirname "$0")/commit-msg.backup"
+if [ "$(basename "$0")" != "commit-msg.backup" ] && [ -x "$backup" ]; then
+    "$backup" "$@" || exit $?
+fi
+
+header=$(grep -v '^#' "$1" | head -n 1)
+
+case "$header" in
+    "Merge "*|"Revert \""*|"fixup! "*|"squash! "*) exit 0 ;;
+esac
+
+if ! printf '%s\n' "$header" | grep -Eq '^({types}){scope}!?: .{{{min},}}$'; then
+    echo "commit-msg: header must look like 'type(scope): subject'" >&2
+    echo '  allowed types: {types_list}' >&2
+    exit 1
+fi
+
+if [ "${{#header}}" -gt {max} ]; then
+    echo "commit-msg: header is longer than {max} characters" >&2
+    exit 1
+fi
+"#,
+        marker = HOOK_MARKER,
+        types = alternatives(&config.types),
+        types_list = config.types.join(", ").replace('\'', r"'\''"),
+        scope = scope,
+        min = config.subject_min_length,
+        max = config.header_max_length,
+    )
+}
+
+/// Install the commit-msg hook. An existing hook not written by the IDE is kept as
+/// `commit-msg.backup` and still runs first; under the hooks shim the check becomes that backup.
+pub fn install_hook(project_path: &Path) -> Result<String> {
+    let config: CommitlintConfig = load_project_config(project_path, CONFIG_FILE)?;
+    let dir = super::git_path(project_path, "hooks")?;
+    std::fs::create_dir_all(&dir)?;
+
+    let hook = dir.join("commit-msg");
+    let backup = dir.join("commit-msg.backup");
+    let existing = std::fs::read_to_string(&hook).unwrap_or_default();
+    let is_ours = |path: &Path| std::fs::read_to_string(path).map(|c| c.contains(HOOK_MARKER)).unwrap_or(false);
+
+    let path = if existing.contains(SHIM_MARKER) {
+        // The shim already runs commit-msg.backup before its own commands
+        if backup.exists() && !is_ours(&backup) {
+            anyhow::bail!("commit-msg already has a backup at {:?}; remove one of them first", backup);
+        }
+        backup
+    } else {
+        if hook.exists() && !existing.contains(HOOK_MARKER) {
+            if backup.exists() {
+                anyhow::bail!("commit-msg already has a backup at {:?}; remove one of them first", backup);
+            }
+            std::fs::rename(&hook, &backup)?;
+        }
+        hook
+    };
+
+    std::fs::write(&path, render_hook(&config))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    tracing::info!("Installed commit-msg hook in {:?}", project_path);
+    Ok(path.to_string_lossy().to_string())
+}
+
+pub fn uninstall_hook(project_path: &Path) -> Result<()> {
+    let dir = super::git_path(project_path, "hooks")?;
+    let hook = dir.join("commit-msg");
+    let backup = dir.join("commit-msg.backup");
+    if !hook.exists() {
+        return Ok(());
+    }
+
+    let existing = std::fs::read_to_string(&hook).unwrap_or_default();
+    let backup_is_ours = std::fs::read_to_string(&backup).map(|c| c.contains(HOOK_MARKER)).unwrap_or(false);
+
+    if existing.contains(SHIM_MARKER) && backup_is_ours {
+        std::fs::remove_file(&backup)?;
+        return Ok(());
+    }
+    if !existing.contains(HOOK_MARKER) {
+        anyhow::bail!("The commit-msg hook was not installed by the IDE");
+    }
+
+    std::fs::remove_file(&hook)?;
+    if backup.exists() {
+        std::fs::rename(backup, &hook)?;
+    }
+
+    Ok(())
+}
+
+// Tauri commands
+
+/// Lint a message with an explicit config, or the project's `.luciai/commitlint.json`
+#[tauri::command]
+pub async fn lint_commit_message(
+    message: String,
+    config: Option<CommitlintConfig>,
+    project_path: Option<String>,
+) -> Result<CommitLintResult, String> {
+    let config = match (config, project_path) {
+        (Some(config), _) => config,
+        (None, Some(path)) => load_project_config(Path::new(&path), CONFIG_FILE).map_err(|e| e.to_string())?,
+        (None, None) => CommitlintConfig::default(),
+    };
+
+    Ok(lint(&message, &config))
+}
+
+#[tauri::command]
+pub async fn install_commit_msg_hook(project_path: String) -> Result<String, String> {
+    install_hook(Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn uninstall_commit_msg_hook(project_path: String) -> Result<(), String> {
+    uninstall_hook(Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_conventional_commit() {
+        let result = lint("feat(editor): add minimap\n\nRenders a scaled overview.", &CommitlintConfig::default());
+        assert!(result.valid, "{:?}", result.issues);
+
+        let parsed = result.parsed.unwrap();
+        assert_eq!(parsed.commit_type, "feat");
+        assert_eq!(parsed.scope.as_deref(), Some("editor"));
+        assert!(!parsed.breaking);
+    }
+
+    #[test]
+    fn test_invalid_commits() {
+        let config = CommitlintConfig {
+            require_scope: true,
+            ..Default::default()
+        };
+
+        assert!(!lint("added stuff", &config).valid);
+        assert!(!lint("feature: add minimap", &config).valid);
+        assert!(!lint("feat: add minimap", &config).valid);
+        assert!(!lint("feat(ui): add minimap\nno blank line", &config).valid);
+        assert!(lint("Merge branch 'main' into dev", &config).valid);
+    }
+
+    #[test]
+    fn test_breaking_change_detection() {
+        assert!(parse_conventional_commit("feat(api)!: drop v1 routes").unwrap().breaking);
+        assert!(parse_conventional_commit("fix: rename field\n\nBREAKING CHANGE: `id` is now `uuid`").unwrap().breaking);
+    }
+
+    #[test]
+    fn test_hook_escapes_types_and_scopes() {
+        let config = CommitlintConfig {
+            types: vec!["feat".to_string(), "c++".to_string()],
+            scopes: vec!["api.v1".to_string(), "it's".to_string()],
+            ..Default::default()
+        };
+
+        let hook = render_hook(&config);
+        assert!(hook.contains(r"'^(feat|c\+\+)(\((api\.v1|it'\''s)\))?!?: "));
+    }
+}
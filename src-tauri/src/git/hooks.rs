@@ -0,0 +1,378 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::Emitter;
+
+use crate::project::{load_project_config, save_project_config};
+use crate::remote::shell_quote;
+use super::git_path;
+
+pub const CONFIG_FILE: &str = "hooks.json";
+
+/// Set by the IDE (or the user) to make installed shims exit immediately
+pub const SKIP_ENV: &str = "LUCIAI_SKIP_HOOKS";
+
+/// CLI flag the shims use to call back into the IDE binary
+pub const CLI_FLAG: &str = "--run-git-hook";
+
+/// Marks the shims written here; distinct from the commitlint hook's marker so the
+/// two features never remove each other's hooks
+pub const SHIM_MARKER: &str = "# LuciAI Studio hook shim";
+
+pub const SUPPORTED_HOOKS: &[&str] = &["pre-commit", "commit-msg", "pre-push", "post-merge", "post-checkout"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookCommand {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+/// `.luciai/hooks.json`: hook name -> commands run in order
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HooksConfig {
+    #[serde(default)]
+    pub hooks: BTreeMap<String, Vec<HookCommand>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookOutput {
+    pub hook: String,
+    pub command: String,
+    pub stream: String, // "stdout" or "stderr"
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookCommandResult {
+    pub name: String,
+    pub exit_code: i32,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookRunResult {
+    pub hook: String,
+    pub success: bool,
+    pub commands: Vec<HookCommandResult>,
+}
+
+type OutputSink = Arc<dyn Fn(HookOutput) + Send + Sync>;
+
+fn render_shim(hook: &str, exe: &Path) -> String {
+    format!(
+        r#"#!/bin/sh
+{marker}
+# Commands are configured in .luciai/hooks.json. Set {skip}=1 to bypass.
+[ -n "${skip}" ] && exit 0
+# The hook this shim replaced (a user hook or the commitlint check) runs first
+backup="$(dirname "$0")/{hook}.backup"
+if [ -x "$backup" ]; then
+    "$backup" "$@" || exit $?
+fi
+exec "{exe}" {flag} {hook} "$@"
+"#,
+        marker = SHIM_MARKER,
+        skip = SKIP_ENV,
+        exe = exe.display(),
+        flag = CLI_FLAG,
+        hook = hook,
+    )
+}
+
+/// Write shims for every configured hook; user-written hooks are backed up first
+pub fn install_hooks(repo_path: &Path) -> Result<Vec<String>> {
+    let config: HooksConfig = load_project_config(repo_path, CONFIG_FILE)?;
+    let exe = std::env::current_exe().context("Failed to locate IDE executable")?;
+    let dir = git_path(repo_path, "hooks")?;
+    std::fs::create_dir_all(&dir)?;
+
+    let mut installed = Vec::new();
+
+    for hook in config.hooks.keys() {
+        if !SUPPORTED_HOOKS.contains(&hook.as_str()) {
+            tracing::warn!("Skipping unsupported git hook '{}'", hook);
+            continue;
+        }
+
+        let path = dir.join(hook);
+        if path.exists() {
+            let existing = std::fs::read_to_string(&path).unwrap_or_default();
+            let backup = dir.join(format!("{}.backup", hook));
+            if !existing.contains(SHIM_MARKER) {
+                if backup.exists() {
+                    anyhow::bail!("{} already has a backup at {:?}; remove one of them first", hook, backup);
+                }
+                std::fs::rename(&path, backup)?;
+            }
+        }
+
+        std::fs::write(&path, render_shim(hook, &exe))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        }
+
+        installed.push(hook.clone());
+    }
+
+    tracing::info!("Installed git hooks {:?} in {:?}", installed, repo_path);
+    Ok(installed)
+}
+
+/// Remove IDE-managed hooks and restore any backups
+pub fn uninstall_hooks(repo_path: &Path) -> Result<()> {
+    let dir = git_path(repo_path, "hooks")?;
+
+    for hook in SUPPORTED_HOOKS {
+        let path = dir.join(hook);
+        let managed = std::fs::read_to_string(&path)
+            .map(|c| c.contains(SHIM_MARKER))
+            .unwrap_or(false);
+
+        if managed {
+            std::fs::remove_file(&path)?;
+            let backup = dir.join(format!("{}.backup", hook));
+            if backup.exists() {
+                std::fs::rename(backup, &path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn forward_lines<R: Read + Send + 'static>(
+    stream: R,
+    hook: String,
+    command: String,
+    stream_name: &'static str,
+    sink: OutputSink,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(|l| l.ok()) {
+            sink(HookOutput {
+                hook: hook.clone(),
+                command: command.clone(),
+                stream: stream_name.to_string(),
+                line,
+            });
+        }
+    })
+}
+
+/// Run a hook's configured commands with `sh`, as git runs hooks, streaming output to `sink`.
+/// Hook arguments are the positional parameters (`$1` is the commit message file for
+/// commit-msg) and, shell-quoted, `$HOOK_ARGS`.
+pub fn run_hook(repo_path: &Path, hook: &str, args: &[String], sink: OutputSink) -> Result<HookRunResult> {
+    let config: HooksConfig = load_project_config(repo_path, CONFIG_FILE)?;
+    let commands = config.hooks.get(hook).cloned().unwrap_or_default();
+    let quoted_args = args.iter().map(|a| shell_quote(a)).collect::<Vec<_>>().join(" ");
+
+    let mut result = HookRunResult {
+        hook: hook.to_string(),
+        success: true,
+        commands: Vec::new(),
+    };
+
+    for hook_command in commands {
+        let started = Instant::now();
+
+        // Not the user's login shell: profile output and job control would end up in the hook
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&hook_command.command)
+            .arg(hook)
+            .args(args)
+            .current_dir(repo_path)
+            .env("HOOK_ARGS", &quoted_args)
+            .env(SKIP_ENV, "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run hook command '{}'", hook_command.name))?;
+
+        let readers = [
+            child.stdout.take().map(|s| forward_lines(s, hook.to_string(), hook_command.name.clone(), "stdout", sink.clone())),
+            child.stderr.take().map(|s| forward_lines(s, hook.to_string(), hook_command.name.clone(), "stderr", sink.clone())),
+        ];
+
+        let status = child.wait()?;
+        for reader in readers.into_iter().flatten() {
+            reader.join().ok();
+        }
+
+        let exit_code = status.code().unwrap_or(-1);
+        result.commands.push(HookCommandResult {
+            name: hook_command.name.clone(),
+            exit_code,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+
+        if !status.success() && !hook_command.continue_on_error {
+            result.success = false;
+            break;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Entry point for `<exe> --run-git-hook <hook> [args]`, invoked by the installed shims.
+/// Returns the process exit code, or None when the IDE was started normally.
+pub fn run_from_cli() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(|a| a.as_str()) != Some(CLI_FLAG) {
+        return None;
+    }
+
+    // Never fall through to starting the GUI from inside a git hook
+    let Some(hook) = args.get(2).cloned() else {
+        eprintln!("{} needs a hook name", CLI_FLAG);
+        return Some(2);
+    };
+    let repo_path = match std::env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("{} hook error: {}", hook, e);
+            return Some(1);
+        }
+    };
+
+    let sink: OutputSink = Arc::new(|output: HookOutput| {
+        if output.stream == "stderr" {
+            eprintln!("[{}] {}", output.command, output.line);
+        } else {
+            println!("[{}] {}", output.command, output.line);
+        }
+    });
+
+    match run_hook(&repo_path, &hook, &args[3..], sink) {
+        Ok(result) if result.success => Some(0),
+        Ok(result) => {
+            eprintln!("{} hook failed. Use {}=1 or --no-verify to bypass.", result.hook, SKIP_ENV);
+            Some(1)
+        }
+        Err(e) => {
+            eprintln!("{} hook error: {}", hook, e);
+            Some(1)
+        }
+    }
+}
+
+fn event_sink(app: tauri::AppHandle) -> OutputSink {
+    Arc::new(move |output: HookOutput| {
+        app.emit("git-hook-output", output).ok();
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_git_hooks_config(repo_path: String) -> Result<HooksConfig, String> {
+    load_project_config(Path::new(&repo_path), CONFIG_FILE).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_git_hooks_config(repo_path: String, config: HooksConfig) -> Result<(), String> {
+    save_project_config(Path::new(&repo_path), CONFIG_FILE, &config).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn install_git_hooks(repo_path: String) -> Result<Vec<String>, String> {
+    install_hooks(Path::new(&repo_path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn uninstall_git_hooks(repo_path: String) -> Result<(), String> {
+    uninstall_hooks(Path::new(&repo_path)).map_err(|e| e.to_string())
+}
+
+/// Run a hook from the IDE; output arrives as `git-hook-output` events
+#[tauri::command]
+pub async fn run_git_hook(app: tauri::AppHandle, repo_path: String, hook: String) -> Result<HookRunResult, String> {
+    tokio::task::spawn_blocking(move || run_hook(Path::new(&repo_path), &hook, &[], event_sink(app)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Commit after running the pre-commit and commit-msg hooks with streamed output.
+/// `bypass_hooks` skips every hook, like `git commit --no-verify`.
+#[tauri::command]
+pub async fn git_commit_with_hooks(
+    app: tauri::AppHandle,
+    repo_path: String,
+    message: String,
+    bypass_hooks: bool,
+) -> Result<String, String> {
+    if !bypass_hooks {
+        let path = repo_path.clone();
+        let msg = message.clone();
+
+        let failed = tokio::task::spawn_blocking(move || -> Result<Option<String>> {
+            let repo = Path::new(&path);
+            let sink = event_sink(app);
+
+            if !run_hook(repo, "pre-commit", &[], sink.clone())?.success {
+                return Ok(Some("pre-commit".to_string()));
+            }
+
+            let message_file = git_path(repo, "COMMIT_EDITMSG")?;
+            std::fs::write(&message_file, &msg)?;
+            let args = [message_file.to_string_lossy().to_string()];
+
+            if !run_hook(repo, "commit-msg", &args, sink)?.success {
+                return Ok(Some("commit-msg".to_string()));
+            }
+
+            Ok(None)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+        if let Some(hook) = failed {
+            return Err(format!("{} hook failed; commit aborted", hook));
+        }
+    }
+
+    // Managed hooks already ran above; SKIP_ENV stops the shims repeating them,
+    // while hooks the IDE doesn't manage still run unless bypassed
+    let mut args = vec!["commit", "-m", message.as_str()];
+    if bypass_hooks {
+        args.push("--no-verify");
+    }
+    args.extend(super::signing::commit_args(Path::new(&repo_path)));
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(&repo_path)
+        .env(SKIP_ENV, "1")
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Git commit failed: {}",
+            super::signing::explain_commit_failure(&String::from_utf8_lossy(&output.stderr))
+        ));
+    }
+
+    let hash = Command::new("git")
+        .args(&["rev-parse", "HEAD"])
+        .current_dir(&repo_path)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    Ok(String::from_utf8_lossy(&hash.stdout).trim().to_string())
+}
@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+use tauri::Emitter;
+use tokio::sync::watch;
+
+use super::clone::{run_with_progress, GitProgress};
+use crate::filesystem::collect_source_files;
+
+/// First line of every LFS pointer file
+const POINTER_HEADER: &str = "version https://git-lfs.github.com/spec/v1";
+/// Pointers are around 130 bytes; anything much bigger is real content
+const MAX_POINTER_BYTES: u64 = 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LfsStatus {
+    /// `.gitattributes` routes some paths through the LFS filter
+    pub uses_lfs: bool,
+    /// `git lfs version`, when the extension is installed
+    pub version: Option<String>,
+    pub tracked_patterns: Vec<String>,
+    /// Checked out as pointers because their content was never downloaded
+    pub pointer_files: Vec<String>,
+    /// Set when the repository needs LFS but it isn't installed
+    pub warning: Option<String>,
+}
+
+/// True when `path` holds an LFS pointer rather than the file's content
+pub fn is_pointer_file(path: &Path) -> bool {
+    let Ok(metadata) = path.metadata() else { return false };
+    if !metadata.is_file() || metadata.len() > MAX_POINTER_BYTES {
+        return false;
+    }
+    let mut head = [0u8; POINTER_HEADER.len()];
+    std::fs::File::open(path).and_then(|mut file| file.read_exact(&mut head)).is_ok() && head == POINTER_HEADER.as_bytes()
+}
+
+/// Patterns `.gitattributes` sends through `filter=lfs`
+pub fn tracked_patterns(repo_path: &Path) -> Vec<String> {
+    std::fs::read_to_string(repo_path.join(".gitattributes"))
+        .map(|content| {
+            content
+                .lines()
+                .filter(|line| !line.trim_start().starts_with('#'))
+                .filter(|line| line.split_whitespace().skip(1).any(|attr| attr == "filter=lfs"))
+                .filter_map(|line| line.split_whitespace().next().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn installed_version() -> Option<String> {
+    let output = Command::new("git").args(["lfs", "version"]).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+pub fn status(repo_path: &Path) -> LfsStatus {
+    let tracked_patterns = tracked_patterns(repo_path);
+    let version = installed_version();
+    let pointer_files: Vec<String> = if tracked_patterns.is_empty() {
+        Vec::new()
+    } else {
+        collect_source_files(repo_path, &[])
+            .into_iter()
+            .filter(|path| is_pointer_file(path))
+            .map(|path| path.strip_prefix(repo_path).unwrap_or(&path).to_string_lossy().replace('\\', "/"))
+            .collect()
+    };
+    let uses_lfs = !tracked_patterns.is_empty();
+    let warning = (uses_lfs && version.is_none()).then(|| {
+        "This repository stores large files with Git LFS, which isn't installed. Install it from \
+         https://git-lfs.com and run `git lfs install`, then pull the LFS files."
+            .to_string()
+    });
+    LfsStatus { uses_lfs, version, tracked_patterns, pointer_files, warning }
+}
+
+fn require_lfs() -> Result<()> {
+    installed_version()
+        .map(|_| ())
+        .context("Git LFS isn't installed. Install it from https://git-lfs.com and run `git lfs install`.")
+}
+
+/// Track `pattern` with LFS; `.gitattributes` is updated and needs committing
+pub fn track(repo_path: &Path, pattern: &str) -> Result<Vec<String>> {
+    require_lfs()?;
+    let output = Command::new("git")
+        .args(["lfs", "track", "--", pattern])
+        .current_dir(repo_path)
+        .output()
+        .context("Failed to run git lfs track")?;
+    if !output.status.success() {
+        anyhow::bail!("git lfs track failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(tracked_patterns(repo_path))
+}
+
+pub async fn pull(
+    repo_path: &Path,
+    id: &str,
+    stop: &mut watch::Receiver<bool>,
+    on_progress: &(dyn Fn(GitProgress) + Send + Sync),
+) -> Result<LfsStatus> {
+    require_lfs()?;
+    let args = ["lfs".to_string(), "pull".to_string()];
+    if let Err(stderr) = run_with_progress(&args, Some(repo_path), id, stop, on_progress).await? {
+        anyhow::bail!("git lfs pull failed: {}", stderr);
+    }
+    Ok(status(repo_path))
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn git_lfs_status(repo_path: String) -> Result<LfsStatus, String> {
+    tokio::task::spawn_blocking(move || status(Path::new(&repo_path)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn git_lfs_track(repo_path: String, pattern: String) -> Result<Vec<String>, String> {
+    track(Path::new(&repo_path), &pattern).map_err(|e| e.to_string())
+}
+
+/// Download LFS content, emitting `git-lfs-progress` as objects arrive
+#[tauri::command]
+pub async fn git_lfs_pull(app: tauri::AppHandle, repo_path: String) -> Result<LfsStatus, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    // Not cancellable from the UI; the sender only has to outlive the pull
+    let (_stop_tx, mut stop_rx) = watch::channel(false);
+    let on_progress = move |progress: GitProgress| {
+        app.emit("git-lfs-progress", &progress).ok();
+    };
+    pull(Path::new(&repo_path), &id, &mut stop_rx, &on_progress)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pointers_and_patterns() {
+        let repo = tempfile::tempdir().unwrap();
+        let root = repo.path();
+        std::fs::write(
+            root.join(".gitattributes"),
+            "# assets\n*.psd filter=lfs diff=lfs merge=lfs -text\n*.sh text eol=lf\ndata/** filter=lfs diff=lfs merge=lfs -text\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("cover.psd"),
+            "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("logo.psd"), "real image bytes").unwrap();
+
+        assert_eq!(tracked_patterns(root), vec!["*.psd", "data/**"]);
+        assert!(is_pointer_file(&root.join("cover.psd")));
+        assert!(!is_pointer_file(&root.join("logo.psd")));
+
+        let status = status(root);
+        assert!(status.uses_lfs);
+        assert_eq!(status.pointer_files, vec!["cover.psd"]);
+        assert_eq!(status.warning.is_some(), status.version.is_none());
+    }
+}
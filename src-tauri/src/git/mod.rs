@@ -1,8 +1,32 @@
+pub mod commitlint;
+pub mod changelog;
+pub mod hooks;
+pub mod release;
+pub mod clone;
+pub mod lfs;
+pub mod signing;
+pub mod push_safety;
+
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Where git keeps `name` (`hooks`, `COMMIT_EDITMSG`, ...) for the repository at
+/// `repo_path`, following worktrees, submodules and `core.hooksPath`
+pub fn git_path(repo_path: &Path, name: &str) -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", name])
+        .current_dir(repo_path)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!("Not a git repository: {:?}", repo_path);
+    }
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(if path.is_absolute() { path } else { repo_path.join(path) })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitRepository {
     pub path: PathBuf,
@@ -139,12 +163,13 @@ impl GitManager {
     pub fn commit(&self, message: &str) -> Result<String> {
         let output = Command::new("git")
             .args(&["commit", "-m", message])
+            .args(signing::commit_args(&self.repo_path))
             .current_dir(&self.repo_path)
             .output()
             .context("Failed to commit")?;
         
         if !output.status.success() {
-            anyhow::bail!("Git commit failed: {}", String::from_utf8_lossy(&output.stderr));
+            anyhow::bail!("Git commit failed: {}", signing::explain_commit_failure(&String::from_utf8_lossy(&output.stderr)));
         }
         
         let commit_output = String::from_utf8_lossy(&output.stdout);
@@ -305,21 +330,6 @@ impl GitManager {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
     
-    /// Clone a repository
-    pub fn clone(url: &str, destination: &PathBuf) -> Result<()> {
-        let output = Command::new("git")
-            .args(&["clone", url, &destination.to_string_lossy()])
-            .output()
-            .context("Failed to clone repository")?;
-        
-        if !output.status.success() {
-            anyhow::bail!("Git clone failed: {}", String::from_utf8_lossy(&output.stderr));
-        }
-        
-        tracing::info!("Cloned {} to {:?}", url, destination);
-        Ok(())
-    }
-    
     // Helper methods
     
     fn create_default_gitignore(&self) -> Result<()> {
@@ -536,8 +546,11 @@ pub async fn git_pull(repo_path: String, remote: String, branch: String) -> Resu
 
 #[tauri::command]
 pub async fn git_push(repo_path: String, remote: String, branch: String) -> Result<String, String> {
-    let manager = GitManager::new(PathBuf::from(repo_path));
-    manager.push(&remote, &branch).map_err(|e| e.to_string())
+    // Refuses, with what to do instead, when the remote has commits the branch doesn't
+    tokio::task::spawn_blocking(move || push_safety::safe_push(Path::new(&repo_path), &remote, &branch))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -551,8 +564,3 @@ pub async fn git_diff(repo_path: String, file: Option<String>) -> Result<String,
     let manager = GitManager::new(PathBuf::from(repo_path));
     manager.diff(file.as_deref()).map_err(|e| e.to_string())
 }
-
-#[tauri::command]
-pub async fn git_clone(url: String, destination: String) -> Result<(), String> {
-    GitManager::clone(&url, &PathBuf::from(destination)).map_err(|e| e.to_string())
-}
@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::path::Path;
+use std::process::Command;
+
+use crate::project::{load_project_config, save_project_config};
+
+pub const CONFIG_FILE: &str = "git.json";
+
+/// `.luciai/git.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushProtectionConfig {
+    /// Branch names or `*` patterns that can't be force-pushed, e.g. `release/*`
+    #[serde(default = "default_protected")]
+    pub protected_branches: Vec<String>,
+}
+
+fn default_protected() -> Vec<String> {
+    vec!["main".to_string(), "master".to_string(), "release/*".to_string()]
+}
+
+impl Default for PushProtectionConfig {
+    fn default() -> Self {
+        Self { protected_branches: default_protected() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PushOption {
+    Push,
+    /// Pull (merge or rebase) the remote commits, then push
+    PullFirst,
+    /// `git_push_force_with_lease`; never offered for protected branches
+    ForceWithLease,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushCheck {
+    pub remote: String,
+    pub branch: String,
+    pub protected: bool,
+    /// Whether the remote branch exists yet
+    pub remote_exists: bool,
+    /// The remote commit this check compared against; a force-push may only replace this one
+    pub remote_sha: Option<String>,
+    /// Local commits the remote doesn't have
+    pub ahead: usize,
+    /// Remote commits the local branch doesn't have
+    pub behind: usize,
+    /// The fetch before checking failed (e.g. offline), so counts are from the last fetch
+    pub stale: bool,
+    /// What the user can do, safest first; just `Push` when a plain push will go through
+    pub options: Vec<PushOption>,
+    pub message: String,
+}
+
+/// `*` matches any run of characters, `/` included
+pub fn matches_pattern(pattern: &str, branch: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == branch,
+        Some((prefix, rest)) => {
+            let Some(remaining) = branch.strip_prefix(prefix) else { return false };
+            (0..=remaining.len())
+                .filter(|i| remaining.is_char_boundary(*i))
+                .any(|i| matches_pattern(rest, &remaining[i..]))
+        }
+    }
+}
+
+pub fn is_protected(repo_path: &Path, branch: &str) -> bool {
+    let config: PushProtectionConfig = load_project_config(repo_path, CONFIG_FILE).unwrap_or_default();
+    config.protected_branches.iter().any(|pattern| matches_pattern(pattern, branch))
+}
+
+fn git(repo_path: &Path, args: &[&str]) -> Result<std::process::Output> {
+    Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .context("Failed to run git")
+}
+
+fn decide(check: &mut PushCheck) {
+    let target = format!("{}/{}", check.remote, check.branch);
+    let (options, message) = match (check.ahead, check.behind) {
+        (_, 0) => (vec![PushOption::Push], format!("Ready to push {} commits to {}", check.ahead, target)),
+        (0, behind) => (
+            vec![PushOption::PullFirst, PushOption::Cancel],
+            format!("{} is {} commits ahead of your branch and you have nothing to push; pull instead", target, behind),
+        ),
+        (ahead, behind) if check.protected => (
+            vec![PushOption::PullFirst, PushOption::Cancel],
+            format!(
+                "Your branch and {} have diverged ({} local, {} remote commits). {} is protected, so pull first.",
+                target, ahead, behind, check.branch
+            ),
+        ),
+        (ahead, behind) => (
+            vec![PushOption::PullFirst, PushOption::ForceWithLease, PushOption::Cancel],
+            format!(
+                "Your branch and {} have diverged ({} local, {} remote commits). Pull first, or force-push \
+                 to replace the remote commits.",
+                target, ahead, behind
+            ),
+        ),
+    };
+    check.options = options;
+    check.message = message;
+}
+
+fn resolve(repo_path: &Path, reference: &str) -> Result<Option<String>> {
+    let output = git(repo_path, &["rev-parse", "--verify", "--quiet", reference])?;
+    Ok(output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string()))
+}
+
+/// Fetch `remote/branch` and compare it with the local `branch`, which needn't be checked out
+pub fn check(repo_path: &Path, remote: &str, branch: &str) -> Result<PushCheck> {
+    let local_ref = format!("refs/heads/{}", branch);
+    if resolve(repo_path, &local_ref)?.is_none() {
+        anyhow::bail!("There is no local branch named {}", branch);
+    }
+    let stale = !git(repo_path, &["fetch", "--quiet", remote, branch])?.status.success();
+    let remote_ref = format!("refs/remotes/{}/{}", remote, branch);
+    let remote_sha = resolve(repo_path, &remote_ref)?;
+
+    let (ahead, behind) = if remote_sha.is_some() {
+        let range = format!("{}...{}", local_ref, remote_ref);
+        let output = git(repo_path, &["rev-list", "--left-right", "--count", &range])?;
+        let counts = String::from_utf8_lossy(&output.stdout);
+        let mut numbers = counts.split_whitespace().map(|n| n.parse::<usize>().unwrap_or(0));
+        (numbers.next().unwrap_or(0), numbers.next().unwrap_or(0))
+    } else {
+        let output = git(repo_path, &["rev-list", "--count", &local_ref])?;
+        (String::from_utf8_lossy(&output.stdout).trim().parse().unwrap_or(0), 0)
+    };
+
+    let mut check = PushCheck {
+        remote: remote.to_string(),
+        branch: branch.to_string(),
+        protected: is_protected(repo_path, branch),
+        remote_exists: remote_sha.is_some(),
+        remote_sha: remote_sha.clone(),
+        ahead,
+        behind,
+        stale: stale && remote_sha.is_some(),
+        options: Vec::new(),
+        message: String::new(),
+    };
+    decide(&mut check);
+    Ok(check)
+}
+
+/// Push only when `check` allows a plain push; otherwise the error is the check's message
+pub fn safe_push(repo_path: &Path, remote: &str, branch: &str) -> Result<String> {
+    let check = check(repo_path, remote, branch)?;
+    if check.options != [PushOption::Push] {
+        anyhow::bail!("{}", check.message);
+    }
+    let refspec = format!("refs/heads/{}:refs/heads/{}", branch, branch);
+    let output = git(repo_path, &["push", remote, &refspec])?;
+    if !output.status.success() {
+        anyhow::bail!("Git push failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    tracing::info!("Pushed to {}/{}", remote, branch);
+    Ok(String::from_utf8_lossy(&output.stderr).to_string())
+}
+
+/// Force-push, but only over `expected_sha`, the remote commit the user reviewed in
+/// `check`: if anyone pushed (or a background fetch moved the tracking ref) since, git
+/// refuses instead of discarding their work. Never allowed on protected branches.
+pub fn force_with_lease(repo_path: &Path, remote: &str, branch: &str, expected_sha: &str) -> Result<String> {
+    if is_protected(repo_path, branch) {
+        anyhow::bail!("{} is a protected branch and can't be force-pushed", branch);
+    }
+    let lease = format!("--force-with-lease=refs/heads/{}:{}", branch, expected_sha);
+    let refspec = format!("refs/heads/{}:refs/heads/{}", branch, branch);
+    let output = git(repo_path, &["push", &lease, remote, &refspec])?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("stale info") {
+            anyhow::bail!("{}/{} changed since you reviewed it; check it again before forcing", remote, branch);
+        }
+        anyhow::bail!("Git push failed: {}", stderr);
+    }
+    tracing::warn!("Force-pushed to {}/{}", remote, branch);
+    Ok(String::from_utf8_lossy(&output.stderr).to_string())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn check_push_safety(repo_path: String, remote: String, branch: String) -> Result<PushCheck, String> {
+    tokio::task::spawn_blocking(move || check(Path::new(&repo_path), &remote, &branch))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn git_push_force_with_lease(
+    repo_path: String,
+    remote: String,
+    branch: String,
+    expected_sha: String,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || force_with_lease(Path::new(&repo_path), &remote, &branch, &expected_sha))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_push_protection(repo_path: String) -> Result<PushProtectionConfig, String> {
+    load_project_config(Path::new(&repo_path), CONFIG_FILE).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_push_protection(repo_path: String, config: PushProtectionConfig) -> Result<(), String> {
+    save_project_config(Path::new(&repo_path), CONFIG_FILE, &config).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_and_decisions() {
+        assert!(matches_pattern("release/*", "release/1.2"));
+        assert!(matches_pattern("*-stable", "v2-stable"));
+        assert!(!matches_pattern("release/*", "feature/release"));
+        assert!(!matches_pattern("main", "main2"));
+
+        let mut check = PushCheck {
+            remote: "origin".to_string(),
+            branch: "main".to_string(),
+            protected: true,
+            remote_exists: true,
+            remote_sha: Some("4b825dc".to_string()),
+            ahead: 2,
+            behind: 0,
+            stale: false,
+            options: Vec::new(),
+            message: String::new(),
+        };
+        decide(&mut check);
+        assert_eq!(check.options, vec![PushOption::Push]);
+
+        check.behind = 3;
+        decide(&mut check);
+        assert_eq!(check.options, vec![PushOption::PullFirst, PushOption::Cancel]);
+
+        check.protected = false;
+        decide(&mut check);
+        assert_eq!(check.options, vec![PushOption::PullFirst, PushOption::ForceWithLease, PushOption::Cancel]);
+    }
+}
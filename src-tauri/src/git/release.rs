@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_edit::DocumentMut;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BumpLevel {
+    Major,
+    Minor,
+    Patch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestVersion {
+    pub path: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionBump {
+    pub previous_version: String,
+    pub new_version: String,
+    pub updated_files: Vec<String>,
+    pub commit: String,
+    pub tag: String,
+}
+
+const MANIFESTS: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "pyproject.toml",
+    "tauri.conf.json",
+    "src-tauri/Cargo.toml",
+    "src-tauri/tauri.conf.json",
+];
+
+fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// Tables whose `version` key is the project version; dependency versions are left alone
+const TOML_VERSION_TABLES: &[&[&str]] = &[&["package"], &["project"], &["tool", "poetry"]];
+
+lazy_static::lazy_static! {
+    static ref JSON_VERSION: Regex = Regex::new(r#"("version"\s*:\s*")([^"]+)(")"#).unwrap();
+    // package-lock.json v2+ repeats the version on the root entry of `packages`
+    static ref LOCKFILE_ROOT_VERSION: Regex =
+        Regex::new(r#"("packages"\s*:\s*\{\s*""\s*:\s*\{[^{}]*?"version"\s*:\s*")([^"]+)(")"#).unwrap();
+}
+
+/// The table holding the project version and its current value
+fn toml_version(doc: &DocumentMut) -> Option<(&'static [&'static str], String)> {
+    TOML_VERSION_TABLES.iter().find_map(|path| {
+        let mut item = doc.as_item();
+        for key in path.iter() {
+            item = item.get(*key)?;
+        }
+        item.get("version")?.as_str().map(|v| (*path, v.to_string()))
+    })
+}
+
+fn replace_json_version(pattern: &Regex, content: &str, version: &str) -> String {
+    pattern
+        .replacen(content, 1, |c: &regex::Captures| format!("{}{}{}", &c[1], version, &c[3]))
+        .into_owned()
+}
+
+pub fn read_version(manifest: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(manifest).ok()?;
+    let file_name = manifest.file_name()?.to_string_lossy().to_string();
+
+    if file_name.ends_with(".json") {
+        JSON_VERSION.captures(&content).map(|c| c[2].to_string())
+    } else {
+        toml_version(&content.parse().ok()?).map(|(_, version)| version)
+    }
+}
+
+// Manifests are edited in place so formatting and comments survive the bump
+fn write_version(manifest: &Path, version: &str) -> Result<()> {
+    let content = std::fs::read_to_string(manifest)?;
+    let file_name = manifest.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    let updated = if file_name.ends_with(".json") {
+        let updated = replace_json_version(&JSON_VERSION, &content, version);
+        if file_name == "package-lock.json" {
+            replace_json_version(&LOCKFILE_ROOT_VERSION, &updated, version)
+        } else {
+            updated
+        }
+    } else {
+        let mut doc: DocumentMut = content
+            .parse()
+            .with_context(|| format!("Failed to parse {}", manifest.display()))?;
+        let (path, _) = toml_version(&doc).with_context(|| format!("No version found in {}", manifest.display()))?;
+
+        let mut table = doc.as_item_mut();
+        for key in path {
+            table = &mut table[*key];
+        }
+        // Keep whatever comment or spacing surrounded the old value
+        let decor = table["version"].as_value().map(|v| v.decor().clone());
+        table["version"] = toml_edit::value(version);
+        if let (Some(decor), Some(value)) = (decor, table["version"].as_value_mut()) {
+            *value.decor_mut() = decor;
+        }
+        doc.to_string()
+    };
+
+    std::fs::write(manifest, updated)?;
+    Ok(())
+}
+
+/// Put the manifests back and drop the release commit after a failed bump
+fn rollback(project_path: &Path, originals: &[(PathBuf, String)], head: Option<&str>) {
+    let reset = match head {
+        Some(head) => git(project_path, &["reset", "-q", head]),
+        None => git(project_path, &["reset", "-q"]),
+    };
+    if let Err(e) = reset {
+        tracing::warn!("Failed to reset {:?} after a failed version bump: {}", project_path, e);
+    }
+
+    for (path, content) in originals {
+        if let Err(e) = std::fs::write(path, content) {
+            tracing::warn!("Failed to restore {:?}: {}", path, e);
+        }
+    }
+}
+
+pub fn detect_manifests(project_path: &Path) -> Vec<ManifestVersion> {
+    MANIFESTS
+        .iter()
+        .map(|m| project_path.join(m))
+        .filter_map(|path| {
+            // tauri.conf.json may point at package.json ("../package.json") instead of a version
+            read_version(&path).filter(|v| v.starts_with(|c: char| c.is_ascii_digit())).map(|version| ManifestVersion {
+                path: path.to_string_lossy().to_string(),
+                version,
+            })
+        })
+        .collect()
+}
+
+pub fn next_version(current: &str, level: &BumpLevel) -> Result<String> {
+    // Pre-release and build suffixes are dropped on bump
+    let core = current.split(['-', '+']).next().unwrap_or(current);
+    let parts: Vec<u64> = core
+        .split('.')
+        .map(|p| p.parse::<u64>())
+        .collect::<std::result::Result<_, _>>()
+        .with_context(|| format!("'{}' is not a semantic version", current))?;
+
+    if parts.len() != 3 {
+        anyhow::bail!("'{}' is not a semantic version", current);
+    }
+
+    let (major, minor, patch) = (parts[0], parts[1], parts[2]);
+    Ok(match level {
+        BumpLevel::Major => format!("{}.0.0", major + 1),
+        BumpLevel::Minor => format!("{}.{}.0", major, minor + 1),
+        BumpLevel::Patch => format!("{}.{}.{}", major, minor, patch + 1),
+    })
+}
+
+/// Bump every detected manifest, then commit and create an annotated `v<version>` tag
+pub fn bump_project_version(project_path: &Path, level: &BumpLevel) -> Result<VersionBump> {
+    let dirty = git(project_path, &["status", "--porcelain"])?;
+    if !dirty.trim().is_empty() {
+        anyhow::bail!("Working tree has uncommitted changes; commit or stash them before bumping the version");
+    }
+
+    let manifests = detect_manifests(project_path);
+    let first = manifests.first().context("No package.json, Cargo.toml, pyproject.toml or tauri.conf.json with a version found")?;
+
+    let mismatched: Vec<_> = manifests.iter().filter(|m| m.version != first.version).collect();
+    if !mismatched.is_empty() {
+        let listing = manifests.iter().map(|m| format!("{} ({})", m.path, m.version)).collect::<Vec<_>>().join(", ");
+        anyhow::bail!("Manifest versions disagree: {}", listing);
+    }
+
+    let previous_version = first.version.clone();
+    let new_version = next_version(&previous_version, level)?;
+    let tag = format!("v{}", new_version);
+
+    if !git(project_path, &["tag", "--list", &tag])?.trim().is_empty() {
+        anyhow::bail!("Tag {} already exists", tag);
+    }
+
+    let mut updated_files: Vec<String> = manifests.iter().map(|m| m.path.clone()).collect();
+
+    // package-lock.json repeats the root version; Cargo.lock is refreshed by the next build
+    let lockfile = project_path.join("package-lock.json");
+    if read_version(&lockfile).as_deref() == Some(previous_version.as_str()) {
+        updated_files.push(lockfile.to_string_lossy().to_string());
+    }
+
+    let originals = updated_files
+        .iter()
+        .map(|f| Ok((PathBuf::from(f), std::fs::read_to_string(f)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let head = git(project_path, &["rev-parse", "--verify", "HEAD"]).ok().map(|h| h.trim().to_string());
+
+    let release = || -> Result<()> {
+        for (path, _) in &originals {
+            write_version(path, &new_version)?;
+        }
+
+        let mut add_args = vec!["add", "--"];
+        add_args.extend(updated_files.iter().map(|f| f.as_str()));
+        git(project_path, &add_args)?;
+
+        let message = format!("chore(release): {}", tag);
+        git(project_path, &["commit", "-m", &message])?;
+        git(project_path, &["tag", "-a", &tag, "-m", &format!("Release {}", tag)])?;
+        Ok(())
+    };
+
+    if let Err(e) = release() {
+        rollback(project_path, &originals, head.as_deref());
+        return Err(e);
+    }
+
+    let commit = git(project_path, &["rev-parse", "HEAD"])?.trim().to_string();
+    tracing::info!("Bumped {:?} from {} to {}", project_path, previous_version, new_version);
+
+    Ok(VersionBump {
+        previous_version,
+        new_version,
+        updated_files,
+        commit,
+        tag,
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_project_versions(project_path: String) -> Result<Vec<ManifestVersion>, String> {
+    Ok(detect_manifests(Path::new(&project_path)))
+}
+
+#[tauri::command]
+pub async fn bump_version(project_path: String, level: BumpLevel) -> Result<VersionBump, String> {
+    tokio::task::spawn_blocking(move || bump_project_version(Path::new(&project_path), &level))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_version() {
+        assert_eq!(next_version("1.2.3", &BumpLevel::Patch).unwrap(), "1.2.4");
+        assert_eq!(next_version("1.2.3", &BumpLevel::Minor).unwrap(), "1.3.0");
+        assert_eq!(next_version("1.2.3-beta.1", &BumpLevel::Major).unwrap(), "2.0.0");
+        assert!(next_version("1.2", &BumpLevel::Patch).is_err());
+    }
+
+    #[test]
+    fn test_toml_version_ignores_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        std::fs::write(&path, "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { version = \"1.0\" }\n").unwrap();
+
+        write_version(&path, "0.2.0").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("version = \"0.2.0\""));
+        assert!(content.contains("serde = { version = \"1.0\" }"));
+
+        // A `[` before `version` inside the table must not hide it
+        std::fs::write(&path, "[package]\nname = \"app\"\nauthors = [\"a\"]\nversion = \"0.1.0\" # bumped by release\n").unwrap();
+        assert_eq!(read_version(&path).as_deref(), Some("0.1.0"));
+        write_version(&path, "0.2.0").unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("version = \"0.2.0\" # bumped by release"));
+    }
+
+    #[test]
+    fn test_lockfile_root_package_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package-lock.json");
+        std::fs::write(
+            &path,
+            "{\n  \"name\": \"app\",\n  \"version\": \"1.0.0\",\n  \"packages\": {\n    \"\": {\n      \"name\": \"app\",\n      \"version\": \"1.0.0\"\n    },\n    \"node_modules/a\": {\n      \"version\": \"1.0.0\"\n    }\n  }\n}\n",
+        )
+        .unwrap();
+
+        write_version(&path, "1.1.0").unwrap();
+        let lock: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(lock["version"], "1.1.0");
+        assert_eq!(lock["packages"][""]["version"], "1.1.0");
+        assert_eq!(lock["packages"]["node_modules/a"]["version"], "1.0.0");
+    }
+}
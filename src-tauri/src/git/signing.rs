@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::path::Path;
+use std::process::Command;
+
+/// Separates `git log` fields; doesn't occur in names or subjects
+const FIELD: char = '\x1f';
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SigningFormat {
+    /// GPG keys, git's `openpgp` format
+    Gpg,
+    Ssh,
+}
+
+/// The repository's own signing settings (`git config --local`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningConfig {
+    /// `commit.gpgsign`
+    pub enabled: bool,
+    pub format: SigningFormat,
+    /// GPG key id, or the path of an SSH public key
+    pub key: Option<String>,
+    /// `gpg.ssh.allowedSignersFile`, which SSH signatures are verified against
+    #[serde(default)]
+    pub allowed_signers_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningKey {
+    pub format: SigningFormat,
+    /// What goes in `user.signingkey`
+    pub key: String,
+    /// User id for GPG, comment for SSH
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    Good,
+    /// Good signature from a key that isn't trusted
+    UnknownValidity,
+    Bad,
+    ExpiredSignature,
+    ExpiredKey,
+    RevokedKey,
+    /// Signed, but the key isn't available to check it
+    CannotCheck,
+    Unsigned,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitSignature {
+    pub hash: String,
+    pub short_hash: String,
+    pub subject: String,
+    pub status: SignatureStatus,
+    pub signer: Option<String>,
+    pub key: Option<String>,
+}
+
+fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// A local config value; unset is None rather than an error
+fn local_config(repo_path: &Path, key: &str) -> Option<String> {
+    git(repo_path, &["config", "--local", "--get", key])
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn set_local_config(repo_path: &Path, key: &str, value: Option<&str>) -> Result<()> {
+    match value {
+        Some(value) => git(repo_path, &["config", "--local", key, value]).map(|_| ()),
+        // Unsetting a key that isn't set exits with 5; nothing to do then
+        None => {
+            if local_config(repo_path, key).is_some() {
+                git(repo_path, &["config", "--local", "--unset", key])?;
+            }
+            Ok(())
+        }
+    }
+}
+
+pub fn get_config(repo_path: &Path) -> SigningConfig {
+    SigningConfig {
+        enabled: local_config(repo_path, "commit.gpgsign").is_some_and(|v| v == "true"),
+        format: match local_config(repo_path, "gpg.format").as_deref() {
+            Some("ssh") => SigningFormat::Ssh,
+            _ => SigningFormat::Gpg,
+        },
+        key: local_config(repo_path, "user.signingkey"),
+        allowed_signers_file: local_config(repo_path, "gpg.ssh.allowedSignersFile"),
+    }
+}
+
+pub fn configure(repo_path: &Path, config: &SigningConfig) -> Result<SigningConfig> {
+    if config.enabled && config.key.is_none() {
+        anyhow::bail!("Choose a signing key before turning signing on");
+    }
+    if let (SigningFormat::Ssh, Some(key)) = (config.format, &config.key) {
+        if !Path::new(key).exists() && !key.starts_with("ssh-") && !key.starts_with("key::") {
+            anyhow::bail!("SSH signing key not found: {}", key);
+        }
+    }
+
+    let format = match config.format {
+        SigningFormat::Gpg => None,
+        SigningFormat::Ssh => Some("ssh"),
+    };
+    set_local_config(repo_path, "gpg.format", format)?;
+    set_local_config(repo_path, "user.signingkey", config.key.as_deref())?;
+    set_local_config(repo_path, "commit.gpgsign", config.enabled.then_some("true"))?;
+    set_local_config(repo_path, "gpg.ssh.allowedSignersFile", config.allowed_signers_file.as_deref())?;
+    Ok(get_config(repo_path))
+}
+
+/// Secret GPG keys and SSH public keys in `~/.ssh`
+pub fn list_keys() -> Vec<SigningKey> {
+    let mut keys = Vec::new();
+
+    if let Ok(output) = Command::new("gpg").args(["--list-secret-keys", "--with-colons"]).output() {
+        let listing = String::from_utf8_lossy(&output.stdout);
+        let mut current: Option<String> = None;
+        for fields in listing.lines().map(|line| line.split(':').collect::<Vec<_>>()) {
+            match fields.first().copied() {
+                Some("sec") => current = fields.get(4).map(|id| id.to_string()),
+                Some("uid") => {
+                    if let (Some(key), Some(uid)) = (current.take(), fields.get(9)) {
+                        keys.push(SigningKey { format: SigningFormat::Gpg, key, label: uid.to_string() });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let ssh_dir = dirs::home_dir().map(|home| home.join(".ssh")).unwrap_or_default();
+    if let Ok(entries) = std::fs::read_dir(&ssh_dir) {
+        let mut ssh_keys: Vec<SigningKey> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "pub"))
+            .filter_map(|path| {
+                let content = std::fs::read_to_string(&path).ok()?;
+                let label = content.split_whitespace().nth(2).unwrap_or("").to_string();
+                Some(SigningKey { format: SigningFormat::Ssh, key: path.to_string_lossy().to_string(), label })
+            })
+            .collect();
+        ssh_keys.sort_by(|a, b| a.key.cmp(&b.key));
+        keys.extend(ssh_keys);
+    }
+    keys
+}
+
+/// Extra `git commit` arguments for the repository's signing settings
+pub fn commit_args(repo_path: &Path) -> Vec<&'static str> {
+    if get_config(repo_path).enabled {
+        vec!["--gpg-sign"]
+    } else {
+        Vec::new()
+    }
+}
+
+/// A commit failure message, with what to do about it when signing was the cause
+pub fn explain_commit_failure(stderr: &str) -> String {
+    let lower = stderr.to_lowercase();
+    if lower.contains("gpg failed to sign") || (lower.contains("failed to write commit object") && lower.contains("sign")) {
+        format!(
+            "Signing the commit failed. Check that the signing key is available (for GPG, that the \
+             agent can prompt for its passphrase) or turn signing off for this repository.\n{}",
+            stderr.trim()
+        )
+    } else {
+        stderr.trim().to_string()
+    }
+}
+
+/// Status letter from git's `%G?`
+pub fn parse_status(code: &str) -> SignatureStatus {
+    match code {
+        "G" => SignatureStatus::Good,
+        "U" => SignatureStatus::UnknownValidity,
+        "B" => SignatureStatus::Bad,
+        "X" => SignatureStatus::ExpiredSignature,
+        "Y" => SignatureStatus::ExpiredKey,
+        "R" => SignatureStatus::RevokedKey,
+        "E" => SignatureStatus::CannotCheck,
+        _ => SignatureStatus::Unsigned,
+    }
+}
+
+fn parse_log(output: &str) -> Vec<CommitSignature> {
+    let non_empty = |value: &str| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(FIELD).collect();
+            let [hash, short_hash, code, signer, key, subject] = fields[..] else { return None };
+            Some(CommitSignature {
+                hash: hash.to_string(),
+                short_hash: short_hash.to_string(),
+                subject: subject.to_string(),
+                status: parse_status(code),
+                signer: non_empty(signer),
+                key: non_empty(key),
+            })
+        })
+        .collect()
+}
+
+/// Signature status of each commit in `range` (e.g. `main..HEAD`, or the last 50 when None)
+pub fn verify(repo_path: &Path, range: Option<&str>) -> Result<Vec<CommitSignature>> {
+    let format = ["%H", "%h", "%G?", "%GS", "%GK", "%s"].join(&FIELD.to_string());
+    let format = format!("--format={}", format);
+    let mut args = vec!["log", format.as_str()];
+    match range {
+        Some(range) => args.push(range),
+        None => args.push("-50"),
+    }
+    Ok(parse_log(&git(repo_path, &args)?))
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_commit_signing_config(repo_path: String) -> Result<SigningConfig, String> {
+    Ok(get_config(Path::new(&repo_path)))
+}
+
+#[tauri::command]
+pub async fn configure_commit_signing(repo_path: String, config: SigningConfig) -> Result<SigningConfig, String> {
+    configure(Path::new(&repo_path), &config).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_signing_keys() -> Result<Vec<SigningKey>, String> {
+    tokio::task::spawn_blocking(list_keys).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn verify_commit_signatures(repo_path: String, range: Option<String>) -> Result<Vec<CommitSignature>, String> {
+    tokio::task::spawn_blocking(move || verify(Path::new(&repo_path), range.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_signatures() {
+        let log = [
+            ["a1b2c3d4", "a1b2c3d", "G", "Ada <ada@example.com>", "4AEE18F83AFDEB23", "Sign release"].join("\x1f"),
+            ["e5f6a7b8", "e5f6a7b", "N", "", "", "Unsigned fix"].join("\x1f"),
+            ["c9d0e1f2", "c9d0e1f", "E", "", "SHA256:abc", "Key not in allowed signers"].join("\x1f"),
+        ]
+        .join("\n");
+        let signatures = parse_log(&log);
+        assert_eq!(signatures.len(), 3);
+        assert_eq!(signatures[0].status, SignatureStatus::Good);
+        assert_eq!(signatures[0].signer.as_deref(), Some("Ada <ada@example.com>"));
+        assert_eq!(signatures[1].status, SignatureStatus::Unsigned);
+        assert_eq!(signatures[1].key, None);
+        assert_eq!(signatures[2].status, SignatureStatus::CannotCheck);
+
+        assert!(explain_commit_failure("error: gpg failed to sign the data").starts_with("Signing the commit failed"));
+        assert_eq!(explain_commit_failure("nothing to commit\n"), "nothing to commit");
+    }
+}
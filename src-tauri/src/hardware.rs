@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::Mutex;
+use sysinfo::System;
+
+use crate::terminal::shell::find_in_path;
+
+const MB: u64 = 1024 * 1024;
+
+/// Model tiers by the memory available to the model: (minimum MB, model, approximate download GB)
+pub const MODEL_TIERS: &[(u64, &str, f32)] = &[
+    (20 * 1024, "deepseek-coder-v2:16b", 8.9),
+    (10 * 1024, "qwen2.5-coder:7b", 4.7),
+    (5 * 1024, "llama3.2:3b", 2.0),
+    (0, "gemma2:2b", 1.6),
+];
+
+/// Ollama offloads at most this many layers; anything larger means "all of them"
+const ALL_GPU_LAYERS: u32 = 999;
+
+/// GPU probing shells out, so the profile is detected once per run
+static PROFILE: Mutex<Option<HardwareProfile>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub vendor: String,
+    pub name: String,
+    pub vram_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareProfile {
+    pub cpu_brand: String,
+    pub physical_cores: usize,
+    pub logical_cpus: usize,
+    pub total_memory_mb: u64,
+    pub available_memory_mb: u64,
+    pub gpus: Vec<GpuInfo>,
+    /// Apple Silicon shares system memory with the GPU
+    pub unified_memory: bool,
+    pub os: String,
+    pub arch: String,
+}
+
+/// Local model defaults derived from the hardware profile; the LLM preferences can
+/// override the runtime fields
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmSettings {
+    pub model: String,
+    pub download_gb: f32,
+    pub context_length: u32,
+    /// Ollama `num_gpu`: layers to offload, 0 for CPU-only
+    pub num_gpu: u32,
+    /// Ollama `num_thread`
+    pub num_thread: u32,
+    /// Smaller models that will also run, largest first
+    pub alternatives: Vec<String>,
+}
+
+impl HardwareProfile {
+    /// Memory a local model can realistically occupy: the largest GPU's VRAM,
+    /// most of unified memory, or half of system RAM for CPU-only inference
+    pub fn model_memory_mb(&self) -> u64 {
+        if self.unified_memory {
+            return self.total_memory_mb * 3 / 4;
+        }
+        match self.gpus.iter().filter_map(|g| g.vram_mb).max() {
+            Some(vram) => vram,
+            None => self.total_memory_mb / 2,
+        }
+    }
+
+    /// Whether inference can run on a GPU with known memory
+    pub fn has_usable_gpu(&self) -> bool {
+        self.unified_memory || self.gpus.iter().any(|g| g.vram_mb.map_or(false, |v| v >= 2048))
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let executable = find_in_path(program)?;
+    let output = Command::new(executable).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// NVIDIA GPUs through NVML, falling back to nvidia-smi when the library can't be loaded
+fn nvidia_gpus() -> Vec<GpuInfo> {
+    if let Ok(nvml) = nvml_wrapper::Nvml::init() {
+        let count = nvml.device_count().unwrap_or(0);
+        let gpus: Vec<GpuInfo> = (0..count)
+            .filter_map(|i| nvml.device_by_index(i).ok())
+            .map(|device| GpuInfo {
+                vendor: "NVIDIA".to_string(),
+                name: device.name().unwrap_or_default(),
+                vram_mb: device.memory_info().ok().map(|m| m.total / MB),
+            })
+            .collect();
+        if !gpus.is_empty() {
+            return gpus;
+        }
+    }
+
+    run("nvidia-smi", &["--query-gpu=name,memory.total", "--format=csv,noheader,nounits"])
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (name, memory) = line.rsplit_once(',')?;
+            Some(GpuInfo {
+                vendor: "NVIDIA".to_string(),
+                name: name.trim().to_string(),
+                vram_mb: memory.trim().parse().ok(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn vendor_name(pci_vendor_id: &str) -> Option<&'static str> {
+    match pci_vendor_id.trim().to_lowercase().as_str() {
+        "0x10de" => Some("NVIDIA"),
+        "0x1002" => Some("AMD"),
+        "0x8086" => Some("Intel"),
+        _ => None,
+    }
+}
+
+/// AMD and Intel GPUs from sysfs; amdgpu reports VRAM size there
+#[cfg(target_os = "linux")]
+fn other_gpus() -> Vec<GpuInfo> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+
+    let mut gpus = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // card0, card1... but not connectors like card0-HDMI-A-1
+        if !name.starts_with("card") || name.contains('-') {
+            continue;
+        }
+
+        let device = entry.path().join("device");
+        let Some(vendor) = std::fs::read_to_string(device.join("vendor")).ok().and_then(|v| vendor_name(&v)) else {
+            continue;
+        };
+        if vendor == "NVIDIA" {
+            continue;
+        }
+
+        gpus.push(GpuInfo {
+            vendor: vendor.to_string(),
+            name: format!("{} GPU ({})", vendor, name),
+            vram_mb: std::fs::read_to_string(device.join("mem_info_vram_total"))
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(|bytes| bytes / MB),
+        });
+    }
+    gpus
+}
+
+/// Non-NVIDIA adapters from WMI. AdapterRAM is a 32-bit field, so it caps at 4 GB.
+#[cfg(target_os = "windows")]
+fn other_gpus() -> Vec<GpuInfo> {
+    let Some(json) = run("powershell", &[
+        "-NoProfile",
+        "-Command",
+        "Get-CimInstance Win32_VideoController | Select-Object Name, AdapterCompatibility, AdapterRAM | ConvertTo-Json",
+    ]) else {
+        return Vec::new();
+    };
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap_or_default();
+    // A single adapter is serialized as an object rather than an array
+    let adapters = match value {
+        serde_json::Value::Array(items) => items,
+        other => vec![other],
+    };
+
+    adapters
+        .iter()
+        .filter_map(|adapter| {
+            let vendor = adapter["AdapterCompatibility"].as_str()?.to_string();
+            if vendor.contains("NVIDIA") {
+                return None;
+            }
+            Some(GpuInfo {
+                vendor,
+                name: adapter["Name"].as_str().unwrap_or_default().to_string(),
+                vram_mb: adapter["AdapterRAM"].as_u64().map(|bytes| bytes / MB),
+            })
+        })
+        .collect()
+}
+
+/// Discrete GPUs on Intel Macs; Apple Silicon is handled as unified memory
+#[cfg(target_os = "macos")]
+fn other_gpus() -> Vec<GpuInfo> {
+    if cfg!(target_arch = "aarch64") {
+        return vec![GpuInfo {
+            vendor: "Apple".to_string(),
+            name: "Apple Silicon GPU".to_string(),
+            vram_mb: None,
+        }];
+    }
+
+    let Some(json) = run("system_profiler", &["SPDisplaysDataType", "-json"]) else {
+        return Vec::new();
+    };
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap_or_default();
+
+    value["SPDisplaysDataType"]
+        .as_array()
+        .map(|displays| {
+            displays
+                .iter()
+                .map(|display| {
+                    let vram = display["spdisplays_vram"]
+                        .as_str()
+                        .or_else(|| display["spdisplays_vram_shared"].as_str())
+                        .and_then(|v| v.split_whitespace().next()?.parse::<u64>().ok())
+                        .map(|gb| gb * 1024);
+                    GpuInfo {
+                        vendor: display["spdisplays_vendor"].as_str().unwrap_or("Unknown").replace("sppci_vendor_", ""),
+                        name: display["sppci_model"].as_str().unwrap_or_default().to_string(),
+                        vram_mb: vram,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn other_gpus() -> Vec<GpuInfo> {
+    Vec::new()
+}
+
+pub fn detect() -> HardwareProfile {
+    let mut system = System::new();
+    system.refresh_memory();
+    system.refresh_cpu();
+
+    let mut gpus = nvidia_gpus();
+    gpus.extend(other_gpus());
+
+    let profile = HardwareProfile {
+        cpu_brand: system.cpus().first().map(|c| c.brand().trim().to_string()).unwrap_or_default(),
+        physical_cores: system.physical_core_count().unwrap_or_else(|| system.cpus().len()),
+        logical_cpus: system.cpus().len(),
+        total_memory_mb: system.total_memory() / MB,
+        available_memory_mb: system.available_memory() / MB,
+        gpus,
+        unified_memory: cfg!(all(target_os = "macos", target_arch = "aarch64")),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+    };
+
+    *PROFILE.lock().unwrap() = Some(profile.clone());
+    profile
+}
+
+/// Cached profile, detected on first use
+pub fn profile() -> HardwareProfile {
+    if let Some(profile) = PROFILE.lock().unwrap().clone() {
+        return profile;
+    }
+    detect()
+}
+
+pub fn recommend_llm_settings(profile: &HardwareProfile) -> LlmSettings {
+    let memory_mb = profile.model_memory_mb();
+    let index = MODEL_TIERS.iter().position(|(min, _, _)| memory_mb >= *min).unwrap_or(MODEL_TIERS.len() - 1);
+    let (_, model, download_gb) = MODEL_TIERS[index];
+
+    // The KV cache grows with context; leave headroom on smaller machines
+    let context_length = match memory_mb {
+        m if m >= 16 * 1024 => 8192,
+        m if m >= 8 * 1024 => 4096,
+        _ => 2048,
+    };
+
+    LlmSettings {
+        model: model.to_string(),
+        download_gb,
+        context_length,
+        num_gpu: if profile.has_usable_gpu() { ALL_GPU_LAYERS } else { 0 },
+        // Hyperthreads slow llama.cpp down, so use physical cores
+        num_thread: profile.physical_cores.max(1) as u32,
+        alternatives: MODEL_TIERS[index + 1..].iter().map(|(_, m, _)| m.to_string()).collect(),
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_hardware_profile(refresh: Option<bool>) -> Result<HardwareProfile, String> {
+    let refresh = refresh.unwrap_or(false);
+    tokio::task::spawn_blocking(move || if refresh { detect() } else { profile() })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_recommended_llm_settings() -> Result<LlmSettings, String> {
+    let profile = tokio::task::spawn_blocking(profile).await.map_err(|e| e.to_string())?;
+    Ok(recommend_llm_settings(&profile))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_only_settings() {
+        let profile = HardwareProfile {
+            cpu_brand: String::new(),
+            physical_cores: 6,
+            logical_cpus: 12,
+            total_memory_mb: 32 * 1024,
+            available_memory_mb: 16 * 1024,
+            gpus: vec![GpuInfo { vendor: "Intel".into(), name: "iGPU".into(), vram_mb: None }],
+            unified_memory: false,
+            os: "linux".into(),
+            arch: "x86_64".into(),
+        };
+
+        let settings = recommend_llm_settings(&profile);
+        assert_eq!(settings.model, "qwen2.5-coder:7b");
+        assert_eq!(settings.num_gpu, 0);
+        assert_eq!(settings.num_thread, 6);
+        assert_eq!(settings.context_length, 8192);
+    }
+}
@@ -0,0 +1,471 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::dependencies::OutdatedReport;
+use crate::package_manager::{self, PackageManager};
+use crate::project::PROJECT_CONFIG_DIR;
+use crate::statistics::{ChurnEntry, GrowthPoint, LanguageStats, ProjectStatistics};
+use crate::testing::{Coverage, TestResult, TestRunner};
+
+/// Snapshots go under `.luciai/health/`, one JSON file per report
+const HISTORY_DIR: &str = "health";
+pub const EXPORT_FILE: &str = "HEALTH.md";
+/// Months of git growth kept in the report
+const ACTIVITY_MONTHS: usize = 6;
+const COVERAGE_TARGET: f32 = 80.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeMetrics {
+    pub total_files: usize,
+    pub total_lines: usize,
+    pub languages: Vec<LanguageStats>,
+    /// Share of non-blank lines that are comments, 0-100
+    pub comment_ratio: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestSummary {
+    pub total: u32,
+    pub passed: u32,
+    pub failed: u32,
+    pub skipped: u32,
+    pub coverage: Option<Coverage>,
+}
+
+/// Vulnerability counts by severity from npm, pnpm or cargo audit
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct AuditSummary {
+    pub tool: String,
+    pub critical: usize,
+    pub high: usize,
+    pub moderate: usize,
+    pub low: usize,
+}
+
+impl AuditSummary {
+    pub fn total(&self) -> usize {
+        self.critical + self.high + self.moderate + self.low
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DependencyHealth {
+    pub declared: usize,
+    pub outdated_major: usize,
+    pub outdated_minor: usize,
+    pub outdated_patch: usize,
+    pub audits: Vec<AuditSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TodoSummary {
+    pub total: usize,
+    /// TODO, FIXME, HACK and XXX counts
+    pub by_tag: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GitActivity {
+    pub total_commits: usize,
+    pub contributors: usize,
+    /// Most recent months, oldest first
+    pub recent_months: Vec<GrowthPoint>,
+    pub hotspots: Vec<ChurnEntry>,
+}
+
+/// Each section is None when it couldn't be gathered; `errors` says why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectHealthReport {
+    pub project_path: String,
+    pub generated_at: String,
+    /// 0-100, see `notes` for what lowered it
+    pub score: u32,
+    pub notes: Vec<String>,
+    pub metrics: Option<CodeMetrics>,
+    pub tests: Option<TestSummary>,
+    pub dependencies: Option<DependencyHealth>,
+    pub todos: TodoSummary,
+    pub git: Option<GitActivity>,
+    pub errors: Vec<String>,
+}
+
+fn metrics_from(stats: &ProjectStatistics) -> CodeMetrics {
+    let code: usize = stats.languages.iter().map(|l| l.code).sum();
+    let comments: usize = stats.languages.iter().map(|l| l.comments).sum();
+    CodeMetrics {
+        total_files: stats.total_files,
+        total_lines: stats.total_lines,
+        languages: stats.languages.clone(),
+        comment_ratio: if code + comments == 0 { 0.0 } else { comments as f32 * 100.0 / (code + comments) as f32 },
+    }
+}
+
+fn activity_from(stats: &ProjectStatistics) -> GitActivity {
+    let skip = stats.growth.len().saturating_sub(ACTIVITY_MONTHS);
+    GitActivity {
+        total_commits: stats.growth.iter().map(|g| g.commits).sum(),
+        contributors: stats.contributors.len(),
+        recent_months: stats.growth[skip..].to_vec(),
+        hotspots: stats.churn_hotspots.iter().take(5).cloned().collect(),
+    }
+}
+
+pub fn test_summary(result: &TestResult) -> TestSummary {
+    TestSummary {
+        total: result.total_tests,
+        passed: result.passed,
+        failed: result.failed,
+        skipped: result.skipped,
+        coverage: result.coverage.clone(),
+    }
+}
+
+/// Count vulnerabilities in `npm audit --json` / `pnpm audit --json` or `cargo audit --json` output
+pub fn parse_audit(tool: &str, output: &str) -> Option<AuditSummary> {
+    let json: serde_json::Value = serde_json::from_str(output).ok()?;
+    let mut summary = AuditSummary { tool: tool.to_string(), ..Default::default() };
+
+    if let Some(counts) = json["metadata"]["vulnerabilities"].as_object() {
+        let count = |key: &str| counts.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        summary.critical = count("critical");
+        summary.high = count("high");
+        summary.moderate = count("moderate");
+        summary.low = count("low") + count("info");
+        return Some(summary);
+    }
+
+    // cargo-audit only rates advisories with a CVSS vector; count unrated ones as moderate
+    let list = json["vulnerabilities"]["list"].as_array()?;
+    for vulnerability in list {
+        match vulnerability["advisory"]["severity"].as_str().unwrap_or("") {
+            "critical" => summary.critical += 1,
+            "high" => summary.high += 1,
+            "low" | "none" => summary.low += 1,
+            _ => summary.moderate += 1,
+        }
+    }
+    Some(summary)
+}
+
+fn run_audits(project_path: &Path) -> (Vec<AuditSummary>, Vec<String>) {
+    let mut tools: Vec<(&str, &[&str])> = Vec::new();
+    // Yarn and bun report audits in formats of their own
+    match package_manager::detect(project_path).map(|info| info.manager) {
+        Some(PackageManager::Pnpm) => tools.push(("pnpm", &["audit", "--json"])),
+        Some(PackageManager::Npm) => tools.push(("npm", &["audit", "--json"])),
+        _ => {}
+    }
+    if project_path.join("Cargo.lock").exists() {
+        tools.push(("cargo", &["audit", "--json"]));
+    }
+
+    let mut audits = Vec::new();
+    let mut errors = Vec::new();
+    for (program, args) in tools {
+        // Audits exit non-zero when they find something, so only the output matters
+        let output = Command::new(program).args(args).current_dir(project_path).output();
+        match output {
+            Ok(output) => match parse_audit(program, &String::from_utf8_lossy(&output.stdout)) {
+                Some(summary) => audits.push(summary),
+                None => errors.push(format!("{} audit: {}", program, String::from_utf8_lossy(&output.stderr).trim())),
+            },
+            Err(e) => errors.push(format!("{} audit: {}", program, e)),
+        }
+    }
+    (audits, errors)
+}
+
+fn dependency_health(project_path: &Path, outdated: Option<&OutdatedReport>, audits: Vec<AuditSummary>) -> DependencyHealth {
+    DependencyHealth {
+        declared: crate::dependencies::declared_dependencies(project_path).len(),
+        outdated_major: outdated.map_or(0, |o| o.major.len()),
+        outdated_minor: outdated.map_or(0, |o| o.minor.len()),
+        outdated_patch: outdated.map_or(0, |o| o.patch.len()),
+        audits,
+    }
+}
+
+fn todo_summary(project_path: &Path) -> TodoSummary {
+    let mut summary = TodoSummary::default();
+    for todo in crate::board::scan_todos(project_path) {
+        summary.total += 1;
+        *summary.by_tag.entry(todo.tag).or_default() += 1;
+    }
+    summary
+}
+
+/// Start at 100 and deduct for failing tests, low coverage, vulnerabilities,
+/// major version lag and TODO density. Missing sections cost nothing.
+pub fn score(report: &ProjectHealthReport) -> (u32, Vec<String>) {
+    let mut penalty = 0.0f32;
+    let mut notes = Vec::new();
+
+    if let Some(tests) = &report.tests {
+        if tests.failed > 0 {
+            penalty += 20.0;
+            notes.push(format!("{} failing test(s)", tests.failed));
+        }
+        if let Some(coverage) = tests.coverage.as_ref().filter(|c| c.lines < COVERAGE_TARGET) {
+            penalty += (COVERAGE_TARGET - coverage.lines) / 4.0;
+            notes.push(format!("Line coverage {:.1}% is below {:.0}%", coverage.lines, COVERAGE_TARGET));
+        }
+    }
+
+    if let Some(dependencies) = &report.dependencies {
+        let severe: usize = dependencies.audits.iter().map(|a| a.critical + a.high).sum();
+        let minor: usize = dependencies.audits.iter().map(|a| a.moderate + a.low).sum();
+        if severe + minor > 0 {
+            penalty += (severe as f32 * 10.0).min(30.0) + (minor as f32 * 2.0).min(10.0);
+            notes.push(format!("{} critical/high and {} other vulnerabilities", severe, minor));
+        }
+        if dependencies.outdated_major > 0 {
+            penalty += (dependencies.outdated_major as f32 * 2.0).min(15.0);
+            notes.push(format!("{} dependencies a major version behind", dependencies.outdated_major));
+        }
+    }
+
+    if let Some(metrics) = report.metrics.as_ref().filter(|m| m.total_lines > 0) {
+        let per_kloc = report.todos.total as f32 * 1000.0 / metrics.total_lines as f32;
+        if per_kloc > 5.0 {
+            penalty += (per_kloc - 5.0).min(10.0);
+            notes.push(format!("{:.1} TODO comments per 1000 lines", per_kloc));
+        }
+    }
+
+    ((100.0 - penalty).clamp(0.0, 100.0).round() as u32, notes)
+}
+
+pub async fn generate(project_path: &Path, run_tests: bool) -> Result<ProjectHealthReport> {
+    if !project_path.is_dir() {
+        anyhow::bail!("{:?} is not a directory", project_path);
+    }
+    let mut errors = Vec::new();
+
+    let path = project_path.to_path_buf();
+    let statistics = tokio::task::spawn_blocking(move || crate::statistics::project_statistics(&path)).await?;
+    let (metrics, git) = match statistics {
+        Ok(stats) => (Some(metrics_from(&stats)), project_path.join(".git").exists().then(|| activity_from(&stats))),
+        Err(e) => {
+            errors.push(format!("Code metrics: {}", e));
+            (None, None)
+        }
+    };
+
+    let tests = if run_tests {
+        let result = match TestRunner::detect_framework(&project_path.to_path_buf()) {
+            Ok(framework) => TestRunner::new(framework, project_path.to_path_buf()).run_tests().await,
+            Err(e) => Err(e),
+        };
+        result.map(|r| test_summary(&r)).map_err(|e| errors.push(format!("Tests: {}", e))).ok()
+    } else {
+        None
+    };
+
+    let outdated = crate::dependencies::check_outdated(project_path)
+        .await
+        .map_err(|e| errors.push(format!("Outdated dependencies: {}", e)))
+        .ok();
+    let path = project_path.to_path_buf();
+    let (audits, todos) = tokio::task::spawn_blocking(move || (run_audits(&path), todo_summary(&path))).await?;
+    let (audits, audit_errors) = audits;
+    errors.extend(audit_errors);
+
+    let mut report = ProjectHealthReport {
+        project_path: project_path.to_string_lossy().to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        score: 0,
+        notes: Vec::new(),
+        metrics,
+        tests,
+        dependencies: Some(dependency_health(project_path, outdated.as_ref(), audits)),
+        todos,
+        git,
+        errors,
+    };
+    (report.score, report.notes) = score(&report);
+    Ok(report)
+}
+
+pub fn to_markdown(report: &ProjectHealthReport) -> String {
+    let mut out = format!("# Project health\n\nGenerated {}\n\n**Score: {}/100**\n", report.generated_at, report.score);
+    for note in &report.notes {
+        out.push_str(&format!("- {}\n", note));
+    }
+
+    if let Some(metrics) = &report.metrics {
+        out.push_str(&format!(
+            "\n## Code\n\n{} files, {} lines, {:.1}% comments\n\n| Language | Files | Code | Comments |\n|---|---:|---:|---:|\n",
+            metrics.total_files, metrics.total_lines, metrics.comment_ratio
+        ));
+        for language in &metrics.languages {
+            out.push_str(&format!("| {} | {} | {} | {} |\n", language.language, language.files, language.code, language.comments));
+        }
+    }
+
+    if let Some(tests) = &report.tests {
+        out.push_str(&format!(
+            "\n## Tests\n\n{} passed, {} failed, {} skipped of {}\n",
+            tests.passed, tests.failed, tests.skipped, tests.total
+        ));
+        if let Some(coverage) = &tests.coverage {
+            out.push_str(&format!(
+                "\nCoverage: {:.1}% lines, {:.1}% functions, {:.1}% branches\n",
+                coverage.lines, coverage.functions, coverage.branches
+            ));
+        }
+    }
+
+    if let Some(dependencies) = &report.dependencies {
+        out.push_str(&format!(
+            "\n## Dependencies\n\n{} declared; outdated: {} major, {} minor, {} patch\n",
+            dependencies.declared, dependencies.outdated_major, dependencies.outdated_minor, dependencies.outdated_patch
+        ));
+        for audit in &dependencies.audits {
+            out.push_str(&format!(
+                "- {} audit: {} critical, {} high, {} moderate, {} low\n",
+                audit.tool, audit.critical, audit.high, audit.moderate, audit.low
+            ));
+        }
+    }
+
+    out.push_str(&format!("\n## TODOs\n\n{} total", report.todos.total));
+    let tags: Vec<String> = report.todos.by_tag.iter().map(|(tag, n)| format!("{} {}", n, tag)).collect();
+    if !tags.is_empty() {
+        out.push_str(&format!(" ({})", tags.join(", ")));
+    }
+    out.push('\n');
+
+    if let Some(git) = &report.git {
+        out.push_str(&format!("\n## Git activity\n\n{} commits by {} contributors\n", git.total_commits, git.contributors));
+        if !git.recent_months.is_empty() {
+            out.push_str("\n| Month | Commits | Added | Removed |\n|---|---:|---:|---:|\n");
+            for month in &git.recent_months {
+                out.push_str(&format!("| {} | {} | {} | {} |\n", month.month, month.commits, month.lines_added, month.lines_removed));
+            }
+        }
+        if !git.hotspots.is_empty() {
+            out.push_str("\nMost changed files:\n");
+            for hotspot in &git.hotspots {
+                out.push_str(&format!("- `{}` ({} commits)\n", hotspot.file, hotspot.commits));
+            }
+        }
+    }
+
+    if !report.errors.is_empty() {
+        out.push_str("\n## Not collected\n\n");
+        for error in &report.errors {
+            out.push_str(&format!("- {}\n", error));
+        }
+    }
+
+    out
+}
+
+fn history_dir(project_path: &Path) -> PathBuf {
+    project_path.join(PROJECT_CONFIG_DIR).join(HISTORY_DIR)
+}
+
+/// Keep a snapshot so the score can be tracked over time
+pub fn save_snapshot(project_path: &Path, report: &ProjectHealthReport) -> Result<PathBuf> {
+    let dir = history_dir(project_path);
+    std::fs::create_dir_all(&dir)?;
+    let name = format!("{}.json", chrono::Utc::now().format("%Y%m%dT%H%M%S"));
+    let path = dir.join(name);
+    std::fs::write(&path, serde_json::to_string_pretty(report)?)
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(path)
+}
+
+/// Saved reports, oldest first
+pub fn history(project_path: &Path) -> Result<Vec<ProjectHealthReport>> {
+    let Ok(entries) = std::fs::read_dir(history_dir(project_path)) else {
+        return Ok(Vec::new());
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().map_or(false, |ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut reports = Vec::new();
+    for file in files {
+        match std::fs::read_to_string(&file).map_err(anyhow::Error::from).and_then(|c| Ok(serde_json::from_str(&c)?)) {
+            Ok(report) => reports.push(report),
+            Err(e) => tracing::warn!("Skipping health report {:?}: {}", file, e),
+        }
+    }
+    Ok(reports)
+}
+
+// Tauri commands
+
+/// Running the test suite is slow, so it's opt-in. With `export_markdown`
+/// the report is also written to HEALTH.md at the project root.
+#[tauri::command]
+pub async fn generate_project_health_report(
+    project_path: String,
+    run_tests: Option<bool>,
+    export_markdown: Option<bool>,
+) -> Result<ProjectHealthReport, String> {
+    let path = Path::new(&project_path);
+    let report = generate(path, run_tests.unwrap_or(false)).await.map_err(|e| e.to_string())?;
+
+    save_snapshot(path, &report).map_err(|e| e.to_string())?;
+    if export_markdown.unwrap_or(false) {
+        std::fs::write(path.join(EXPORT_FILE), to_markdown(&report)).map_err(|e| e.to_string())?;
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn list_project_health_reports(project_path: String) -> Result<Vec<ProjectHealthReport>, String> {
+    history(Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_score_and_markdown() {
+        let npm = r#"{"metadata":{"vulnerabilities":{"info":0,"low":1,"moderate":0,"high":2,"critical":0,"total":3}}}"#;
+        let audit = parse_audit("npm", npm).unwrap();
+        assert_eq!((audit.high, audit.low, audit.total()), (2, 1, 3));
+
+        let cargo = r#"{"vulnerabilities":{"found":true,"count":1,"list":[{"advisory":{"id":"RUSTSEC-0000-0000"}}]}}"#;
+        assert_eq!(parse_audit("cargo", cargo).unwrap().moderate, 1);
+
+        let mut report = ProjectHealthReport {
+            project_path: "/repo".to_string(),
+            generated_at: "2024-01-01T00:00:00Z".to_string(),
+            score: 0,
+            notes: Vec::new(),
+            metrics: Some(CodeMetrics { total_files: 3, total_lines: 1000, languages: Vec::new(), comment_ratio: 10.0 }),
+            tests: Some(TestSummary {
+                total: 10,
+                passed: 9,
+                failed: 1,
+                skipped: 0,
+                coverage: Some(Coverage { lines: 60.0, functions: 70.0, branches: 50.0, statements: 60.0 }),
+            }),
+            dependencies: Some(DependencyHealth { declared: 5, outdated_major: 1, audits: vec![audit], ..Default::default() }),
+            todos: TodoSummary { total: 2, by_tag: BTreeMap::from([("TODO".to_string(), 2)]) },
+            git: None,
+            errors: vec!["Outdated dependencies: offline".to_string()],
+        };
+        // 20 failing + 5 coverage + 20 high + 2 low + 2 major
+        (report.score, report.notes) = score(&report);
+        assert_eq!(report.score, 51);
+        assert_eq!(report.notes.len(), 4);
+
+        let markdown = to_markdown(&report);
+        assert!(markdown.contains("**Score: 51/100**"));
+        assert!(markdown.contains("9 passed, 1 failed"));
+        assert!(markdown.contains("2 total (2 TODO)"));
+        assert!(markdown.contains("- Outdated dependencies: offline"));
+        assert!(!markdown.contains("## Git activity"));
+    }
+}
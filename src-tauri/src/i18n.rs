@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use unic_langid::LanguageIdentifier;
+
+use crate::preferences::PreferencesManager;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Message catalogs compiled into the binary, in Fluent syntax
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+    ("fr", include_str!("../locales/fr.ftl")),
+];
+
+type Bundle = FluentBundle<FluentResource>;
+
+static BUNDLES: Mutex<Option<HashMap<String, Arc<Bundle>>>> = Mutex::new(None);
+/// Active locale; read from preferences on first use
+static CURRENT_LOCALE: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocaleCatalog {
+    pub locale: String,
+    /// Raw Fluent source, for frontends that format with @fluent/bundle
+    pub source: String,
+    /// Every message formatted without arguments; placeables render as `{$name}`.
+    /// Messages missing from this locale are filled in from English.
+    pub messages: BTreeMap<String, String>,
+    pub missing: Vec<String>,
+}
+
+/// Match "es-MX" to "es" when there's no regional catalog
+fn resolve_locale(locale: &str) -> &'static str {
+    let base = locale.split(['-', '_']).next().unwrap_or(locale).to_lowercase();
+    CATALOGS
+        .iter()
+        .find(|(id, _)| id.eq_ignore_ascii_case(locale))
+        .or_else(|| CATALOGS.iter().find(|(id, _)| *id == base))
+        .map(|(id, _)| *id)
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+fn catalog_source(locale: &str) -> &'static str {
+    CATALOGS.iter().find(|(id, _)| *id == locale).map(|(_, source)| *source).unwrap_or("")
+}
+
+fn message_ids(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter(|line| line.chars().next().map_or(false, |c| c.is_ascii_alphabetic()))
+        .filter_map(|line| line.split_once('=').map(|(id, _)| id.trim().to_string()))
+        .collect()
+}
+
+fn build_bundle(locale: &str) -> Result<Bundle> {
+    let resource = FluentResource::try_new(catalog_source(locale).to_string())
+        .map_err(|(_, errors)| anyhow::anyhow!("Invalid {} catalog: {:?}", locale, errors))?;
+    let langid: LanguageIdentifier = locale.parse().context("Invalid locale")?;
+
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Unicode isolation marks show up as stray characters in logs and native dialogs
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .map_err(|errors| anyhow::anyhow!("Duplicate messages in {} catalog: {:?}", locale, errors))?;
+    Ok(bundle)
+}
+
+fn bundle(locale: &str) -> Option<Arc<Bundle>> {
+    let mut guard = BUNDLES.lock().unwrap();
+    let bundles = guard.get_or_insert_with(HashMap::new);
+
+    if let Some(bundle) = bundles.get(locale) {
+        return Some(bundle.clone());
+    }
+
+    match build_bundle(locale) {
+        Ok(bundle) => {
+            let bundle = Arc::new(bundle);
+            bundles.insert(locale.to_string(), bundle.clone());
+            Some(bundle)
+        }
+        Err(e) => {
+            tracing::error!("{}", e);
+            None
+        }
+    }
+}
+
+fn format_message(locale: &str, key: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = bundle(locale)?;
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = Vec::new();
+    Some(bundle.format_pattern(pattern, args, &mut errors).into_owned())
+}
+
+pub fn current_locale() -> String {
+    CURRENT_LOCALE
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| {
+            let preferred = PreferencesManager::new()
+                .and_then(|manager| manager.load())
+                .map(|prefs| prefs.ui.locale)
+                .unwrap_or_else(|_| DEFAULT_LOCALE.to_string());
+            resolve_locale(&preferred).to_string()
+        })
+        .clone()
+}
+
+pub fn set_current_locale(locale: &str) -> String {
+    let resolved = resolve_locale(locale).to_string();
+    *CURRENT_LOCALE.lock().unwrap() = Some(resolved.clone());
+    resolved
+}
+
+/// Translate `key` into the active locale, falling back to English and then to the key itself
+pub fn t(key: &str) -> String {
+    t_args(key, &[])
+}
+
+pub fn t_args(key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, FluentValue::from(value.to_string()));
+    }
+
+    let locale = current_locale();
+    format_message(&locale, key, Some(&fluent_args))
+        .or_else(|| format_message(DEFAULT_LOCALE, key, Some(&fluent_args)))
+        .unwrap_or_else(|| key.to_string())
+}
+
+pub fn catalog(locale: &str) -> LocaleCatalog {
+    let locale = resolve_locale(locale);
+    let source = catalog_source(locale);
+    let own_ids = message_ids(source);
+
+    let mut messages = BTreeMap::new();
+    let mut missing = Vec::new();
+
+    for id in message_ids(catalog_source(DEFAULT_LOCALE)) {
+        match format_message(locale, &id, None) {
+            Some(text) => {
+                messages.insert(id, text);
+            }
+            None => {
+                if let Some(text) = format_message(DEFAULT_LOCALE, &id, None) {
+                    messages.insert(id.clone(), text);
+                }
+                missing.push(id);
+            }
+        }
+    }
+
+    // Messages only present in this locale still belong in its catalog
+    for id in own_ids {
+        if !messages.contains_key(&id) {
+            if let Some(text) = format_message(locale, &id, None) {
+                messages.insert(id, text);
+            }
+        }
+    }
+
+    LocaleCatalog {
+        locale: locale.to_string(),
+        source: source.to_string(),
+        messages,
+        missing,
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_locale_catalog(lang: Option<String>) -> Result<LocaleCatalog, String> {
+    Ok(catalog(&lang.unwrap_or_else(current_locale)))
+}
+
+#[tauri::command]
+pub async fn list_locales() -> Result<Vec<String>, String> {
+    Ok(CATALOGS.iter().map(|(id, _)| id.to_string()).collect())
+}
+
+/// Switch the backend locale and remember it in preferences; returns the locale actually used
+#[tauri::command]
+pub async fn set_locale(lang: String) -> Result<String, String> {
+    let resolved = set_current_locale(&lang);
+
+    let manager = PreferencesManager::new().map_err(|e| e.to_string())?;
+    let mut prefs = manager.load().map_err(|e| e.to_string())?;
+    prefs.ui.locale = resolved.clone();
+    manager.save(&prefs).map_err(|e| e.to_string())?;
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_cover_english() {
+        for (locale, _) in CATALOGS {
+            let catalog = catalog(locale);
+            assert!(catalog.missing.is_empty(), "{} is missing {:?}", locale, catalog.missing);
+        }
+    }
+
+    #[test]
+    fn test_format_with_args_and_fallback() {
+        assert_eq!(resolve_locale("es-MX"), "es");
+        assert_eq!(resolve_locale("xx"), "en");
+
+        let mut args = FluentArgs::new();
+        args.set("path", "a.ts");
+        args.set("index", 1);
+        args.set("total", 3);
+        assert_eq!(format_message("de", "pipeline-file", Some(&args)).unwrap(), "a.ts wird erzeugt (1/3)");
+        assert!(format_message("de", "no-such-key", None).is_none());
+    }
+}
@@ -0,0 +1,419 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Always excluded unless a project rule re-includes them with `!`
+const DEFAULT_PATTERNS: &[&str] = &[
+    ".git/",
+    "node_modules/",
+    "target/",
+    "dist/",
+    "build/",
+    "venv/",
+    ".venv/",
+    "__pycache__/",
+];
+
+/// Parsed rules per project root, reloaded when either ignore file changes
+static RULES: Mutex<Option<HashMap<PathBuf, CachedRules>>> = Mutex::new(None);
+
+struct CachedRules {
+    stamps: Vec<Option<SystemTime>>,
+    rules: Arc<IgnoreRules>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IgnoreFile {
+    /// `.gitignore` at the project root, shared with git
+    Gitignore,
+    /// `.luciai/ignore`, for exclusions that only apply inside the IDE
+    Luciai,
+}
+
+impl IgnoreFile {
+    const ALL: [IgnoreFile; 2] = [IgnoreFile::Gitignore, IgnoreFile::Luciai];
+
+    pub fn path(&self, root: &Path) -> PathBuf {
+        match self {
+            IgnoreFile::Gitignore => root.join(".gitignore"),
+            IgnoreFile::Luciai => root.join(".luciai").join("ignore"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+}
+
+/// Translate a gitignore glob into a regex over `/`-separated relative paths
+fn glob_to_regex(glob: &str) -> Result<String> {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut re = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                let at_segment_start = i == 0 || chars[i - 1] == '/';
+                match chars.get(i + 2) {
+                    Some('/') if at_segment_start => {
+                        re.push_str("(?:.*/)?");
+                        i += 3;
+                    }
+                    None if at_segment_start => {
+                        re.push_str(".*");
+                        i += 2;
+                    }
+                    // `**` inside a name behaves like `*`
+                    _ => {
+                        re.push_str("[^/]*");
+                        i += 2;
+                    }
+                }
+            }
+            '*' => {
+                re.push_str("[^/]*");
+                i += 1;
+            }
+            '?' => {
+                re.push_str("[^/]");
+                i += 1;
+            }
+            '[' => {
+                // A `]` straight after the opening bracket is part of the class
+                let start = i + 1;
+                let mut end = start;
+                if matches!(chars.get(end), Some('!') | Some('^')) {
+                    end += 1;
+                }
+                if chars.get(end) == Some(&']') {
+                    end += 1;
+                }
+                while end < chars.len() && chars[end] != ']' {
+                    end += 1;
+                }
+                if end >= chars.len() {
+                    anyhow::bail!("Unclosed character class in '{}'", glob);
+                }
+
+                re.push('[');
+                for (offset, c) in chars[start..end].iter().enumerate() {
+                    match c {
+                        '!' if offset == 0 => re.push('^'),
+                        '[' | '\\' => {
+                            re.push('\\');
+                            re.push(*c);
+                        }
+                        c => re.push(*c),
+                    }
+                }
+                re.push(']');
+                i = end + 1;
+            }
+            '\\' => {
+                let escaped = chars.get(i + 1).with_context(|| format!("Trailing backslash in '{}'", glob))?;
+                re.push_str(&regex::escape(&escaped.to_string()));
+                i += 2;
+            }
+            c => {
+                re.push_str(&regex::escape(&c.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    Ok(re)
+}
+
+/// `None` for blank lines and comments
+fn parse_rule(line: &str) -> Result<Option<Rule>> {
+    let line = line.trim_end();
+    if line.trim().is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let (negated, pattern) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (dir_only, pattern) = match pattern.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, pattern),
+    };
+    // A slash anywhere but the end ties the pattern to the root; otherwise it matches at any depth
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+    if pattern.is_empty() {
+        anyhow::bail!("Pattern '{}' matches nothing", line);
+    }
+
+    let body = glob_to_regex(pattern)?;
+    let regex = if anchored {
+        format!("^{}$", body)
+    } else {
+        format!("^(?:.*/)?{}$", body)
+    };
+
+    Ok(Some(Rule {
+        regex: Regex::new(&regex).with_context(|| format!("Invalid pattern '{}'", line))?,
+        negated,
+        dir_only,
+    }))
+}
+
+pub fn validate_pattern(pattern: &str) -> Result<()> {
+    if pattern.contains('\n') || pattern.contains('\r') {
+        anyhow::bail!("Pattern must be a single line");
+    }
+    match parse_rule(pattern)? {
+        Some(_) => Ok(()),
+        None => anyhow::bail!("Pattern is empty or a comment"),
+    }
+}
+
+/// The defaults plus the root `.gitignore` and `.luciai/ignore`, in that order,
+/// so later files can override earlier ones
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    root: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl IgnoreRules {
+    pub fn load(root: &Path) -> Self {
+        let mut rules: Vec<Rule> = DEFAULT_PATTERNS
+            .iter()
+            .filter_map(|pattern| parse_rule(pattern).ok().flatten())
+            .collect();
+
+        for file in IgnoreFile::ALL {
+            let path = file.path(root);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            for line in content.lines() {
+                match parse_rule(line) {
+                    Ok(Some(rule)) => rules.push(rule),
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Skipping pattern in {:?}: {}", path, e),
+                }
+            }
+        }
+
+        Self { root: root.to_path_buf(), rules }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The last matching rule wins, as in git
+    fn decision(&self, relative: &str, is_dir: bool) -> Option<bool> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| (!rule.dir_only || is_dir) && rule.regex.is_match(relative))
+            .map(|rule| !rule.negated)
+    }
+
+    /// `path` may be absolute or relative to the root. Anything inside an
+    /// ignored directory is ignored, even if a later rule re-includes it.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        if relative.is_absolute() {
+            return false;
+        }
+
+        let components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        for depth in 1..components.len() {
+            if self.decision(&components[..depth].join("/"), true) == Some(true) {
+                return true;
+            }
+        }
+        !components.is_empty() && self.decision(&components.join("/"), is_dir) == Some(true)
+    }
+}
+
+/// Nearest ancestor that looks like a project root
+pub fn find_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists() || dir.join(".luciai").is_dir() || dir.join(".gitignore").is_file())
+        .map(Path::to_path_buf)
+}
+
+fn stamps(root: &Path) -> Vec<Option<SystemTime>> {
+    IgnoreFile::ALL
+        .iter()
+        .map(|file| std::fs::metadata(file.path(root)).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// Rules for the project containing `path`, falling back to the defaults
+/// rooted at `path` itself when no project root is found
+pub fn rules_for(path: &Path) -> Arc<IgnoreRules> {
+    let root = find_root(path).unwrap_or_else(|| path.to_path_buf());
+    let current = stamps(&root);
+
+    let mut cache = RULES.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some(cached) = cache.get(&root) {
+        if cached.stamps == current {
+            return cached.rules.clone();
+        }
+    }
+
+    let rules = Arc::new(IgnoreRules::load(&root));
+    cache.insert(root, CachedRules { stamps: current, rules: rules.clone() });
+    rules
+}
+
+/// Whether the indexer, search, file tree and walkers should leave `path` out
+pub fn is_path_ignored(path: &Path) -> bool {
+    match find_root(path) {
+        Some(root) => rules_for(&root).is_ignored(path, path.is_dir()),
+        None => false,
+    }
+}
+
+fn invalidate(root: &Path) {
+    let mut cache = RULES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cache) = cache.as_mut() {
+        cache.remove(root);
+    }
+}
+
+pub fn read_patterns(root: &Path, file: IgnoreFile) -> Result<Vec<String>> {
+    let path = file.path(root);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    Ok(content
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Append `pattern`; returns false if it was already listed
+pub fn add_pattern(root: &Path, file: IgnoreFile, pattern: &str) -> Result<bool> {
+    let pattern = pattern.trim_end();
+    validate_pattern(pattern)?;
+    if read_patterns(root, file)?.iter().any(|p| p == pattern) {
+        return Ok(false);
+    }
+
+    let path = file.path(root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut content = std::fs::read_to_string(&path).unwrap_or_default();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(pattern);
+    content.push('\n');
+    std::fs::write(&path, content).with_context(|| format!("Failed to write {:?}", path))?;
+
+    invalidate(root);
+    Ok(true)
+}
+
+/// Remove every line equal to `pattern`, keeping comments and order; returns false if none matched
+pub fn remove_pattern(root: &Path, file: IgnoreFile, pattern: &str) -> Result<bool> {
+    let pattern = pattern.trim_end();
+    let path = file.path(root);
+    if !path.exists() {
+        return Ok(false);
+    }
+
+    let content = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let kept: Vec<&str> = content.lines().filter(|line| line.trim_end() != pattern).collect();
+    if kept.len() == content.lines().count() {
+        return Ok(false);
+    }
+
+    let mut updated = kept.join("\n");
+    if !updated.is_empty() {
+        updated.push('\n');
+    }
+    std::fs::write(&path, updated).with_context(|| format!("Failed to write {:?}", path))?;
+
+    invalidate(root);
+    Ok(true)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_ignore_patterns(project_path: String, file: IgnoreFile) -> Result<Vec<String>, String> {
+    read_patterns(Path::new(&project_path), file).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_ignore_pattern(project_path: String, file: IgnoreFile, pattern: String) -> Result<bool, String> {
+    add_pattern(Path::new(&project_path), file, &pattern).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_ignore_pattern(project_path: String, file: IgnoreFile, pattern: String) -> Result<bool, String> {
+    remove_pattern(Path::new(&project_path), file, &pattern).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn validate_ignore_pattern(pattern: String) -> Result<(), String> {
+    validate_pattern(&pattern).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn check_path_ignored(path: String) -> Result<bool, String> {
+    Ok(is_path_ignored(Path::new(&path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join(".gitignore"), "# build output\n*.log\n/out/\ndocs/**/*.tmp\n").unwrap();
+
+        assert!(add_pattern(root, IgnoreFile::Luciai, "!important.log").unwrap());
+        assert!(!add_pattern(root, IgnoreFile::Luciai, "!important.log").unwrap());
+        assert!(add_pattern(root, IgnoreFile::Luciai, "!build/").unwrap());
+        assert!(add_pattern(root, IgnoreFile::Luciai, "data[0-9].csv").unwrap());
+        assert!(add_pattern(root, IgnoreFile::Luciai, "[abc").is_err());
+        assert!(add_pattern(root, IgnoreFile::Luciai, "# note").is_err());
+
+        let rules = rules_for(root);
+        assert!(rules.is_ignored(Path::new("src/server.log"), false));
+        assert!(!rules.is_ignored(Path::new("logs/important.log"), false));
+        assert!(rules.is_ignored(&root.join("out"), true));
+        assert!(!rules.is_ignored(Path::new("src/out"), true));
+        assert!(rules.is_ignored(Path::new("out/deep/file.rs"), false));
+        assert!(rules.is_ignored(Path::new("docs/a/b/page.tmp"), false));
+        assert!(rules.is_ignored(Path::new("web/node_modules/react/index.js"), false));
+        assert!(!rules.is_ignored(Path::new("build/main.rs"), false));
+        assert!(rules.is_ignored(Path::new("data7.csv"), false));
+        assert!(!rules.is_ignored(Path::new("data77.csv"), false));
+
+        assert!(remove_pattern(root, IgnoreFile::Gitignore, "*.log").unwrap());
+        assert_eq!(read_patterns(root, IgnoreFile::Gitignore).unwrap(), vec!["/out/", "docs/**/*.tmp"]);
+        assert!(std::fs::read_to_string(root.join(".gitignore")).unwrap().starts_with("# build output\n"));
+        assert!(!rules_for(root).is_ignored(Path::new("src/server.log"), false));
+    }
+}
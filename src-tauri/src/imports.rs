@@ -0,0 +1,655 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::Node;
+
+use crate::filesystem::collect_source_files;
+use crate::readonly::WriteError;
+use crate::syntax::{detect_language, parser_for, SyntaxLanguage, SUPPORTED_EXTENSIONS};
+
+// Python from-imports longer than this are wrapped in parentheses
+const PYTHON_LINE_LENGTH: usize = 88;
+
+const NODE_BUILTINS: &[&str] = &[
+    "assert", "buffer", "child_process", "crypto", "events", "fs", "http", "https", "net", "os",
+    "path", "process", "stream", "url", "util", "worker_threads", "zlib",
+];
+
+const PYTHON_STDLIB: &[&str] = &[
+    "abc", "argparse", "asyncio", "base64", "collections", "contextlib", "copy", "csv", "dataclasses",
+    "datetime", "enum", "functools", "glob", "hashlib", "http", "io", "itertools", "json", "logging",
+    "math", "os", "pathlib", "pickle", "random", "re", "shutil", "socket", "sqlite3", "string",
+    "subprocess", "sys", "tempfile", "threading", "time", "typing", "unittest", "urllib", "uuid",
+];
+
+lazy_static::lazy_static! {
+    static ref TS_SIDE_EFFECT: Regex = Regex::new(r#"^import\s+(['"])([^'"]+)['"]"#).unwrap();
+    static ref TS_IMPORT: Regex = Regex::new(r#"^import\s+(type\s+)?([\s\S]*?)\s+from\s+(['"])([^'"]+)['"]"#).unwrap();
+    static ref PY_FROM_IMPORT: Regex = Regex::new(r"^from\s+(\S+)\s+import\s+\(?(.*?)\)?$").unwrap();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizeImportsResult {
+    pub file_path: String,
+    pub changed: bool,
+    /// Imported names dropped because nothing in the file references them
+    pub removed: Vec<String>,
+    /// Unified diff of the import block; empty when unchanged
+    pub diff: String,
+    pub skipped_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizeImportsReport {
+    pub dry_run: bool,
+    pub files: Vec<OrganizeImportsResult>,
+    pub changed_count: usize,
+}
+
+fn is_import_node(kind: &str, language: SyntaxLanguage) -> bool {
+    match language {
+        SyntaxLanguage::TypeScript | SyntaxLanguage::Tsx => kind == "import_statement",
+        SyntaxLanguage::Python => matches!(kind, "import_statement" | "import_from_statement" | "future_import_statement"),
+        SyntaxLanguage::Rust => kind == "use_declaration",
+    }
+}
+
+fn is_decoration_node(kind: &str) -> bool {
+    matches!(kind, "comment" | "line_comment" | "block_comment" | "attribute_item")
+}
+
+/// One import in the block. `start..end` also covers the comments and attributes
+/// directly above it and a comment trailing it on the same line.
+struct ImportRange {
+    start: usize,
+    node_start: usize,
+    node_end: usize,
+    end: usize,
+}
+
+/// An import split into its statement and the decorations that move with it
+#[derive(PartialEq)]
+struct ImportStatement<'a> {
+    leading: &'a str,
+    text: &'a str,
+    trailing: &'a str,
+}
+
+impl ImportStatement<'_> {
+    fn decorate(&self, rendered: &str) -> String {
+        format!("{}{}{}", self.leading, rendered, self.trailing)
+    }
+}
+
+/// Byte range of the first contiguous run of top-level imports. A comment that
+/// isn't attached to an import ends the run, since sorting couldn't place it.
+fn import_block(root: Node, language: SyntaxLanguage) -> Option<(usize, usize, Vec<ImportRange>)> {
+    let mut cursor = root.walk();
+    let mut statements: Vec<ImportRange> = Vec::new();
+    let mut last_row = 0;
+    // Start byte and last row of the decorations seen since the previous node
+    let mut pending: Option<(usize, usize)> = None;
+
+    for child in root.named_children(&mut cursor) {
+        let row = child.start_position().row;
+
+        if is_decoration_node(child.kind()) {
+            match (statements.last_mut(), pending) {
+                (Some(last), None) if row == last_row => last.end = child.end_byte(),
+                (_, Some((start, end_row))) if row <= end_row + 1 => pending = Some((start, child.end_position().row)),
+                _ => pending = Some((child.start_byte(), child.end_position().row)),
+            }
+            continue;
+        }
+
+        if is_import_node(child.kind(), language) {
+            let start = match pending.take() {
+                Some((start, end_row)) if row <= end_row + 1 => start,
+                Some(_) if !statements.is_empty() => break,
+                _ => child.start_byte(),
+            };
+            last_row = child.end_position().row;
+            statements.push(ImportRange { start, node_start: child.start_byte(), node_end: child.end_byte(), end: child.end_byte() });
+        } else if !statements.is_empty() {
+            break;
+        } else {
+            pending = None;
+        }
+    }
+
+    let start = statements.first()?.start;
+    let end = statements.last()?.end;
+    Some((start, end, statements))
+}
+
+/// Every identifier-like token outside the import block
+fn referenced_names(root: Node, source: &str, block: (usize, usize)) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut stack = vec![root];
+
+    while let Some(node) = stack.pop() {
+        if node.start_byte() >= block.0 && node.end_byte() <= block.1 {
+            continue;
+        }
+
+        if node.kind().ends_with("identifier") {
+            if let Ok(text) = node.utf8_text(source.as_bytes()) {
+                names.insert(text.to_string());
+            }
+        }
+
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+
+    names
+}
+
+// ---------- TypeScript / JavaScript ----------
+
+#[derive(Debug, Clone)]
+struct TsImport {
+    type_only: bool,
+    default: Option<String>,
+    namespace: Option<String>,
+    named: Vec<String>,
+    source: String,
+    quote: char,
+    side_effect: bool,
+}
+
+fn specifier_binding(specifier: &str) -> &str {
+    let specifier = specifier.trim_start_matches("type ").trim();
+    specifier.rsplit(" as ").next().unwrap_or(specifier).trim()
+}
+
+fn parse_ts_import(statement: &str) -> Option<TsImport> {
+    if let Some(c) = TS_SIDE_EFFECT.captures(statement) {
+        return Some(TsImport {
+            type_only: false,
+            default: None,
+            namespace: None,
+            named: Vec::new(),
+            source: c[2].to_string(),
+            quote: c[1].chars().next().unwrap_or('\''),
+            side_effect: true,
+        });
+    }
+
+    let c = TS_IMPORT.captures(statement)?;
+    let clause = c[2].trim();
+
+    let (head, named) = match (clause.find('{'), clause.rfind('}')) {
+        (Some(open), Some(close)) => (
+            &clause[..open],
+            clause[open + 1..close]
+                .split(',')
+                .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|s| !s.is_empty())
+                .collect(),
+        ),
+        _ => (clause, Vec::new()),
+    };
+
+    let mut import = TsImport {
+        type_only: c.get(1).is_some(),
+        default: None,
+        namespace: None,
+        named,
+        source: c[4].to_string(),
+        quote: c[3].chars().next().unwrap_or('\''),
+        side_effect: false,
+    };
+
+    for part in head.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        if let Some(ns) = part.strip_prefix('*') {
+            import.namespace = Some(ns.trim().trim_start_matches("as").trim().to_string());
+        } else {
+            import.default = Some(part.to_string());
+        }
+    }
+
+    Some(import)
+}
+
+fn render_ts_import(import: &TsImport, semicolon: bool) -> String {
+    let end = if semicolon { ";" } else { "" };
+    let source = format!("{q}{s}{q}", q = import.quote, s = import.source);
+
+    if import.side_effect {
+        return format!("import {}{}", source, end);
+    }
+
+    let mut parts = Vec::new();
+    if let Some(default) = &import.default {
+        parts.push(default.clone());
+    }
+    if let Some(ns) = &import.namespace {
+        parts.push(format!("* as {}", ns));
+    }
+    if !import.named.is_empty() {
+        let mut named = import.named.clone();
+        named.sort_by_key(|s| specifier_binding(s).to_lowercase());
+        parts.push(format!("{{ {} }}", named.join(", ")));
+    }
+
+    let type_prefix = if import.type_only { "type " } else { "" };
+    format!("import {}{} from {}{}", type_prefix, parts.join(", "), source, end)
+}
+
+fn ts_group(source: &str) -> u8 {
+    let bare = source.trim_start_matches("node:");
+    if source.starts_with("node:") || NODE_BUILTINS.contains(&bare.split('/').next().unwrap_or(bare)) {
+        0
+    } else if source.starts_with('.') {
+        3
+    } else if source.starts_with("@/") || source.starts_with("~/") || source.starts_with('#') {
+        2
+    } else {
+        1
+    }
+}
+
+fn organize_ts(statements: &[ImportStatement], used: &HashSet<String>, removed: &mut Vec<String>) -> Option<String> {
+    let semicolon = statements.iter().any(|s| s.text.trim_end().ends_with(';'));
+    let mut side_effects = Vec::new();
+    let mut imports = Vec::new();
+
+    for statement in statements {
+        let mut import = parse_ts_import(statement.text.trim())?;
+
+        if import.side_effect {
+            // Order can matter (CSS, polyfills), so these stay first and unsorted
+            side_effects.push(statement.decorate(&render_ts_import(&import, semicolon)));
+            continue;
+        }
+
+        if let Some(default) = import.default.take() {
+            if used.contains(&default) { import.default = Some(default) } else { removed.push(default) }
+        }
+        if let Some(ns) = import.namespace.take() {
+            if used.contains(&ns) { import.namespace = Some(ns) } else { removed.push(ns) }
+        }
+        import.named.retain(|s| {
+            let keep = used.contains(specifier_binding(s));
+            if !keep {
+                removed.push(specifier_binding(s).to_string());
+            }
+            keep
+        });
+
+        if import.default.is_some() || import.namespace.is_some() || !import.named.is_empty() {
+            imports.push((import, statement));
+        }
+    }
+
+    imports.sort_by(|(a, _), (b, _)| (ts_group(&a.source), a.source.to_lowercase()).cmp(&(ts_group(&b.source), b.source.to_lowercase())));
+
+    let mut blocks = Vec::new();
+    if !side_effects.is_empty() {
+        blocks.push(side_effects.join("\n"));
+    }
+    for group in 0..4 {
+        let lines: Vec<String> = imports.iter()
+            .filter(|(i, _)| ts_group(&i.source) == group)
+            .map(|(i, statement)| statement.decorate(&render_ts_import(i, semicolon)))
+            .collect();
+        if !lines.is_empty() {
+            blocks.push(lines.join("\n"));
+        }
+    }
+
+    Some(blocks.join("\n\n"))
+}
+
+// ---------- Python ----------
+
+#[derive(Debug, Clone)]
+enum PyImport {
+    /// `import module [as alias]`
+    Module { module: String, alias: Option<String> },
+    /// `from module import a, b as c`
+    From { module: String, names: Vec<String> },
+}
+
+impl PyImport {
+    fn module(&self) -> &str {
+        match self {
+            PyImport::Module { module, .. } | PyImport::From { module, .. } => module,
+        }
+    }
+}
+
+fn parse_py_import(statement: &str) -> Option<Vec<PyImport>> {
+    let statement = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if let Some(rest) = statement.strip_prefix("import ") {
+        return Some(rest.split(',').map(|part| {
+            let mut pieces = part.trim().splitn(2, " as ");
+            PyImport::Module {
+                module: pieces.next().unwrap_or("").trim().to_string(),
+                alias: pieces.next().map(|a| a.trim().to_string()),
+            }
+        }).collect());
+    }
+
+    let c = PY_FROM_IMPORT.captures(&statement)?;
+    Some(vec![PyImport::From {
+        module: c[1].to_string(),
+        names: c[2].split(',').map(|n| n.trim().to_string()).filter(|n| !n.is_empty()).collect(),
+    }])
+}
+
+fn py_group(module: &str) -> u8 {
+    let top = module.split('.').next().unwrap_or(module);
+    if module == "__future__" {
+        0
+    } else if module.starts_with('.') {
+        3
+    } else if PYTHON_STDLIB.contains(&top) {
+        1
+    } else {
+        2
+    }
+}
+
+fn render_py_import(import: &PyImport) -> String {
+    match import {
+        PyImport::Module { module, alias: Some(alias) } => format!("import {} as {}", module, alias),
+        PyImport::Module { module, alias: None } => format!("import {}", module),
+        PyImport::From { module, names } => {
+            let mut names = names.clone();
+            names.sort_by_key(|n| n.to_lowercase());
+
+            let line = format!("from {} import {}", module, names.join(", "));
+            if line.len() <= PYTHON_LINE_LENGTH {
+                return line;
+            }
+            let body = names.iter().map(|n| format!("    {},\n", n)).collect::<String>();
+            format!("from {} import (\n{})", module, body)
+        }
+    }
+}
+
+fn organize_python(statements: &[ImportStatement], used: &HashSet<String>, keep_all: bool, removed: &mut Vec<String>) -> Option<String> {
+    let mut imports = Vec::new();
+
+    for statement in statements {
+        // Comments inside a parenthesized import have nowhere to go once it's rewritten
+        if statement.text.contains('#') {
+            return None;
+        }
+
+        let mut kept = Vec::new();
+        for import in parse_py_import(statement.text)? {
+            match import {
+                PyImport::Module { ref module, ref alias } => {
+                    let binding = alias.clone().unwrap_or_else(|| module.split('.').next().unwrap_or(module).to_string());
+                    if keep_all || used.contains(&binding) {
+                        kept.push(import);
+                    } else {
+                        removed.push(binding);
+                    }
+                }
+                PyImport::From { module, mut names } => {
+                    if module != "__future__" && !keep_all {
+                        names.retain(|n| {
+                            let binding = n.rsplit(" as ").next().unwrap_or(n).trim();
+                            let keep = n == "*" || used.contains(binding);
+                            if !keep {
+                                removed.push(binding.to_string());
+                            }
+                            keep
+                        });
+                    }
+                    if !names.is_empty() {
+                        kept.push(PyImport::From { module, names });
+                    }
+                }
+            }
+        }
+
+        // `import os, sys` splits in two: comments above go with the first part
+        // that survives, a trailing `# noqa` with each
+        for (i, import) in kept.into_iter().enumerate() {
+            let leading = if i == 0 { statement.leading } else { "" };
+            imports.push((import, leading, statement.trailing));
+        }
+    }
+
+    // isort order: plain imports before from-imports within each section
+    imports.sort_by(|(a, ..), (b, ..)| {
+        let key = |i: &PyImport| (py_group(i.module()), matches!(i, PyImport::From { .. }), i.module().to_lowercase());
+        key(a).cmp(&key(b))
+    });
+
+    let blocks: Vec<String> = (0..4)
+        .map(|group| imports.iter()
+            .filter(|(i, ..)| py_group(i.module()) == group)
+            .map(|(i, leading, trailing)| format!("{}{}{}", leading, render_py_import(i), trailing))
+            .collect::<Vec<_>>()
+            .join("\n"))
+        .filter(|b| !b.is_empty())
+        .collect();
+
+    Some(blocks.join("\n\n"))
+}
+
+// ---------- Rust ----------
+
+fn rust_group(statement: &str) -> u8 {
+    let path = statement.trim_start_matches("pub ").trim_start_matches("use ").trim();
+    let root = path.trim_start_matches("::").split("::").next().unwrap_or("");
+
+    if statement.starts_with("pub") {
+        3
+    } else if matches!(root, "std" | "core" | "alloc") {
+        0
+    } else if matches!(root, "crate" | "super" | "self") {
+        2
+    } else {
+        1
+    }
+}
+
+/// Rust imports are only sorted and grouped: trait imports look unused to a
+/// syntax tree, so removal is left to `cargo fix`
+fn organize_rust(statements: &[ImportStatement]) -> Option<String> {
+    let mut uses: Vec<&ImportStatement> = statements.iter().collect();
+    uses.sort_by_key(|s| (rust_group(s.text), s.text.to_lowercase()));
+    uses.dedup();
+
+    let blocks: Vec<String> = (0..4)
+        .map(|group| uses.iter()
+            .filter(|s| rust_group(s.text) == group)
+            .map(|s| s.decorate(s.text.trim()))
+            .collect::<Vec<_>>()
+            .join("\n"))
+        .filter(|b| !b.is_empty())
+        .collect();
+
+    Some(blocks.join("\n\n"))
+}
+
+fn block_diff(file_path: &str, before: &str, after: &str) -> String {
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", file_path, file_path);
+    for line in before.lines() {
+        diff.push_str(&format!("-{}\n", line));
+    }
+    for line in after.lines() {
+        diff.push_str(&format!("+{}\n", line));
+    }
+    diff
+}
+
+pub fn organize_file(path: &Path, dry_run: bool) -> Result<OrganizeImportsResult> {
+    let file_path = path.to_string_lossy().to_string();
+    let mut result = OrganizeImportsResult {
+        file_path: file_path.clone(),
+        changed: false,
+        removed: Vec::new(),
+        diff: String::new(),
+        skipped_reason: None,
+    };
+
+    let language = detect_language(path).context("Unsupported file type for organizing imports")?;
+    let source = std::fs::read_to_string(path)?;
+
+    let mut parser = parser_for(language)?;
+    let tree = parser.parse(&source, None).context("Failed to parse file")?;
+    let root = tree.root_node();
+
+    let Some((start, end, ranges)) = import_block(root, language) else {
+        return Ok(result);
+    };
+
+    // Anything between statements other than whitespace would be lost by the rewrite
+    let between_is_blank = ranges.windows(2).all(|w| source[w[0].end..w[1].start].trim().is_empty());
+    if !between_is_blank {
+        result.skipped_reason = Some("Comments between imports".to_string());
+        return Ok(result);
+    }
+
+    let statements: Vec<ImportStatement> = ranges
+        .iter()
+        .map(|r| ImportStatement {
+            leading: &source[r.start..r.node_start],
+            text: &source[r.node_start..r.node_end],
+            trailing: &source[r.node_end..r.end],
+        })
+        .collect();
+    let mut used = referenced_names(root, &source, (start, end));
+
+    // The classic JSX runtime needs React in scope without naming it
+    if language == SyntaxLanguage::Tsx && (source.contains("</") || source.contains("/>")) {
+        used.insert("React".to_string());
+    }
+
+    // Re-export modules bind names for other files
+    let keep_all = path.file_name().map(|n| n == "__init__.py").unwrap_or(false) || source.contains("__all__");
+
+    let organized = match language {
+        SyntaxLanguage::TypeScript | SyntaxLanguage::Tsx => organize_ts(&statements, &used, &mut result.removed),
+        SyntaxLanguage::Python => organize_python(&statements, &used, keep_all, &mut result.removed),
+        SyntaxLanguage::Rust => organize_rust(&statements),
+    };
+
+    let Some(organized) = organized else {
+        result.skipped_reason = Some("Import syntax not supported".to_string());
+        return Ok(result);
+    };
+
+    let original = &source[start..end];
+    if organized == original {
+        return Ok(result);
+    }
+
+    result.changed = true;
+    result.diff = block_diff(&file_path, original, &organized);
+
+    if !dry_run {
+        let mut updated = String::with_capacity(source.len());
+        updated.push_str(&source[..start]);
+        updated.push_str(&organized);
+        updated.push_str(&source[end..]);
+        crate::edit_journal::write_tracked(path, "organize_imports", &updated)?;
+    }
+
+    Ok(result)
+}
+
+pub fn organize_project(project_path: &Path, dry_run: bool) -> OrganizeImportsReport {
+    let files: Vec<OrganizeImportsResult> = collect_source_files(project_path, SUPPORTED_EXTENSIONS)
+        .iter()
+        .filter_map(|file| match organize_file(file, dry_run) {
+            Ok(result) => Some(result),
+            Err(e) => {
+                tracing::warn!("Skipping {:?}: {}", file, e);
+                None
+            }
+        })
+        .collect();
+
+    OrganizeImportsReport {
+        dry_run,
+        changed_count: files.iter().filter(|f| f.changed).count(),
+        files,
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn organize_imports(file_path: String, dry_run: Option<bool>) -> Result<OrganizeImportsResult, WriteError> {
+    Ok(organize_file(Path::new(&file_path), dry_run.unwrap_or(false))?)
+}
+
+/// Organize every supported file in the project. Defaults to a dry run that only reports diffs.
+#[tauri::command]
+pub async fn organize_project_imports(project_path: String, dry_run: Option<bool>) -> Result<OrganizeImportsReport, String> {
+    let dry_run = dry_run.unwrap_or(true);
+    tokio::task::spawn_blocking(move || organize_project(Path::new(&project_path), dry_run))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_organize_typescript_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::edit_journal::use_dir_in_tests(dir.path().join("journal"));
+        let path = dir.path().join("App.tsx");
+        std::fs::write(&path, concat!(
+            "// eslint-disable-next-line import/no-cycle\n",
+            "import { b } from './b';\n",
+            "import React, { useState, useEffect } from 'react'; // 18.x\n",
+            "import fs from 'fs';\n",
+            "\n",
+            "export function App() { const [x] = useState(fs); return <div>{b}</div>; }\n",
+        )).unwrap();
+
+        let result = organize_file(&path, false).unwrap();
+        assert!(result.changed);
+        assert_eq!(result.removed, vec!["useEffect"]);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with(concat!(
+            "import fs from 'fs';\n\n",
+            "import React, { useState } from 'react'; // 18.x\n\n",
+            "// eslint-disable-next-line import/no-cycle\n",
+            "import { b } from './b';\n",
+        )));
+    }
+
+    #[test]
+    fn test_organize_rust_attributes_move_with_use() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::edit_journal::use_dir_in_tests(dir.path().join("journal"));
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "use crate::a;\n#[cfg(unix)]\nuse std::os::unix::fs::PermissionsExt;\nuse std::fs;\n\nfn main() {}\n").unwrap();
+
+        organize_file(&path, false).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("use std::fs;\n#[cfg(unix)]\nuse std::os::unix::fs::PermissionsExt;\n\nuse crate::a;\n"));
+    }
+
+    #[test]
+    fn test_organize_python_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.py");
+        std::fs::write(&path, "import requests\nfrom . import utils\nimport os, sys\n\nprint(os.getcwd(), utils, requests)\n").unwrap();
+
+        let result = organize_file(&path, true).unwrap();
+        assert!(result.changed);
+        assert_eq!(result.removed, vec!["sys"]);
+        assert!(result.diff.contains("+import os\n+\n+import requests\n+\n+from . import utils\n"));
+
+        // Dry run leaves the file alone
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with("import requests"));
+    }
+}
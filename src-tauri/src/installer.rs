@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::process::Stdio;
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::doctor::{self, CheckStatus, DoctorCheck};
+use crate::terminal::shell::find_in_path;
+
+/// Lines of installer output kept for the result; the rest is only streamed
+const MAX_LOG_LINES: usize = 200;
+
+const RUSTUP_SCRIPT: &str = "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- -y";
+const OLLAMA_SCRIPT: &str = "curl -fsSL https://ollama.com/install.sh | sh";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Manager {
+    Winget,
+    Choco,
+    Brew,
+    Apt,
+    /// Vendor install script piped to `sh`
+    Script,
+}
+
+impl Manager {
+    /// Executable that must be on PATH for this strategy to be usable
+    fn program(&self) -> &'static str {
+        match self {
+            Manager::Winget => "winget",
+            Manager::Choco => "choco",
+            Manager::Brew => "brew",
+            Manager::Apt => "apt-get",
+            Manager::Script => "curl",
+        }
+    }
+
+    fn command(&self, package: &str) -> Vec<String> {
+        let args: Vec<&str> = match self {
+            Manager::Winget => vec![
+                "winget", "install", "--id", package, "--exact", "--silent",
+                "--accept-package-agreements", "--accept-source-agreements",
+            ],
+            Manager::Choco => vec!["choco", "install", package, "-y"],
+            Manager::Brew => vec!["brew", "install", package],
+            // No terminal to type a password into: pkexec asks through the desktop,
+            // and `sudo -n` fails fast instead of hanging
+            Manager::Apt if find_in_path("pkexec").is_some() => {
+                vec!["pkexec", "sh", "-c", "apt-get update && apt-get install -y \"$@\"", "sh"]
+            }
+            Manager::Apt => vec!["sudo", "-n", "sh", "-c", "apt-get update && apt-get install -y \"$@\"", "sh"],
+            Manager::Script => vec!["sh", "-c", package],
+        };
+        let mut command: Vec<String> = args.into_iter().map(String::from).collect();
+        if *self == Manager::Apt {
+            command.extend(package.split_whitespace().map(String::from));
+        }
+        command
+    }
+}
+
+struct InstallableTool {
+    id: &'static str,
+    /// Doctor check that verifies the install
+    check_id: &'static str,
+    windows: &'static [(Manager, &'static str)],
+    macos: &'static [(Manager, &'static str)],
+    linux: &'static [(Manager, &'static str)],
+}
+
+const TOOLS: &[InstallableTool] = &[
+    InstallableTool {
+        id: "node",
+        check_id: "node",
+        windows: &[(Manager::Winget, "OpenJS.NodeJS.LTS"), (Manager::Choco, "nodejs-lts")],
+        macos: &[(Manager::Brew, "node")],
+        linux: &[(Manager::Apt, "nodejs npm")],
+    },
+    InstallableTool {
+        id: "python",
+        check_id: "python",
+        windows: &[(Manager::Winget, "Python.Python.3.12"), (Manager::Choco, "python")],
+        macos: &[(Manager::Brew, "python")],
+        linux: &[(Manager::Apt, "python3 python3-pip python3-venv")],
+    },
+    InstallableTool {
+        id: "rust",
+        check_id: "cargo",
+        windows: &[(Manager::Winget, "Rustlang.Rustup"), (Manager::Choco, "rustup.install")],
+        macos: &[(Manager::Script, RUSTUP_SCRIPT), (Manager::Brew, "rustup")],
+        linux: &[(Manager::Script, RUSTUP_SCRIPT)],
+    },
+    InstallableTool {
+        id: "git",
+        check_id: "git",
+        windows: &[(Manager::Winget, "Git.Git"), (Manager::Choco, "git")],
+        macos: &[(Manager::Brew, "git")],
+        linux: &[(Manager::Apt, "git")],
+    },
+    InstallableTool {
+        id: "ollama",
+        check_id: "ollama",
+        windows: &[(Manager::Winget, "Ollama.Ollama"), (Manager::Choco, "ollama")],
+        macos: &[(Manager::Brew, "ollama")],
+        linux: &[(Manager::Script, OLLAMA_SCRIPT)],
+    },
+];
+
+fn find_tool(id: &str) -> Result<&'static InstallableTool> {
+    let id = id.to_lowercase();
+    // Accept the doctor's ids too ("cargo" for Rust)
+    TOOLS
+        .iter()
+        .find(|t| t.id == id || t.check_id == id)
+        .with_context(|| format!("Don't know how to install '{}'", id))
+}
+
+fn strategies(tool: &InstallableTool) -> &'static [(Manager, &'static str)] {
+    if cfg!(target_os = "windows") {
+        tool.windows
+    } else if cfg!(target_os = "macos") {
+        tool.macos
+    } else {
+        tool.linux
+    }
+}
+
+/// First strategy whose package manager is installed, as a full command line
+pub fn install_command(tool_id: &str) -> Result<Vec<String>> {
+    let tool = find_tool(tool_id)?;
+    let candidates = strategies(tool);
+    candidates
+        .iter()
+        .find(|(manager, _)| find_in_path(manager.program()).is_some())
+        .map(|(manager, package)| manager.command(package))
+        .with_context(|| {
+            let managers: Vec<&str> = candidates.iter().map(|(m, _)| m.program()).collect();
+            let hint = doctor::check_tool_by_id(tool.check_id).and_then(|c| c.fix).unwrap_or_default();
+            format!("None of {} is available to install {}. {}", managers.join(", "), tool.id, hint)
+        })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallOutputLine {
+    pub tool: String,
+    /// "stdout" or "stderr"
+    pub stream: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInstallResult {
+    pub tool: String,
+    pub command: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    /// Last lines of installer output
+    pub log: Vec<String>,
+    /// Doctor check re-run after the installer finished
+    pub check: Option<DoctorCheck>,
+}
+
+fn forward_lines<R: AsyncRead + Unpin + Send + 'static>(
+    app: tauri::AppHandle,
+    tool: String,
+    stream: &'static str,
+    reader: R,
+) -> tokio::task::JoinHandle<Vec<String>> {
+    tokio::spawn(async move {
+        let mut log = Vec::new();
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            app.emit("tool-install-output", InstallOutputLine {
+                tool: tool.clone(),
+                stream: stream.to_string(),
+                line: line.clone(),
+            })
+            .ok();
+            log.push(line);
+            if log.len() > MAX_LOG_LINES {
+                log.remove(0);
+            }
+        }
+        log
+    })
+}
+
+/// Run the platform installer for `tool_id`, streaming its output as
+/// `tool-install-output` events, then verify with the doctor check
+pub async fn install(app: tauri::AppHandle, tool_id: &str) -> Result<ToolInstallResult> {
+    let tool = find_tool(tool_id)?;
+    let command = install_command(tool.id)?;
+    let program = find_in_path(&command[0]).with_context(|| format!("{} was not found on PATH", command[0]))?;
+
+    let mut child = tokio::process::Command::new(program)
+        .args(&command[1..])
+        .envs(crate::network::proxy_env())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start {}", command[0]))?;
+
+    let stdout = child.stdout.take().context("Failed to read installer output")?;
+    let stderr = child.stderr.take().context("Failed to read installer output")?;
+    let stdout = forward_lines(app.clone(), tool.id.to_string(), "stdout", stdout);
+    let stderr = forward_lines(app.clone(), tool.id.to_string(), "stderr", stderr);
+
+    let status = child.wait().await?;
+    let mut log = stdout.await.unwrap_or_default();
+    log.extend(stderr.await.unwrap_or_default());
+    let overflow = log.len().saturating_sub(MAX_LOG_LINES);
+    log.drain(..overflow);
+
+    let check_id = tool.check_id;
+    let check = tokio::task::spawn_blocking(move || doctor::check_tool_by_id(check_id))
+        .await
+        .ok()
+        .flatten()
+        .map(|mut check| {
+            if status.success() && check.status != CheckStatus::Ok {
+                // Installers update PATH for new processes, not this one
+                check.detail.push_str("; restart the IDE if the installer just added it to PATH");
+            }
+            check
+        });
+
+    Ok(ToolInstallResult {
+        tool: tool.id.to_string(),
+        command: command.join(" "),
+        success: status.success(),
+        exit_code: status.code(),
+        log,
+        check,
+    })
+}
+
+// Tauri commands
+
+/// Install node, python, rust, git or ollama with the platform's package manager
+#[tauri::command]
+pub async fn install_missing_tool(app: tauri::AppHandle, tool: String) -> Result<ToolInstallResult, String> {
+    install(app, &tool).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_tool_install_command(tool: String) -> Result<String, String> {
+    install_command(&tool).map(|command| command.join(" ")).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_lookup_and_commands() {
+        assert_eq!(find_tool("cargo").unwrap().id, "rust");
+        assert_eq!(find_tool("Node").unwrap().id, "node");
+        assert!(find_tool("cobol").is_err());
+
+        assert_eq!(Manager::Brew.command("node"), vec!["brew", "install", "node"]);
+        let apt = Manager::Apt.command("python3 python3-pip");
+        assert_eq!(&apt[apt.len() - 2..], ["python3", "python3-pip"]);
+        assert_eq!(Manager::Script.command(OLLAMA_SCRIPT), vec!["sh", "-c", OLLAMA_SCRIPT]);
+    }
+}
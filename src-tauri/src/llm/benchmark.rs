@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Instant;
+use tauri::Emitter;
+
+use crate::agent::guardrails::strip_fences;
+use crate::database;
+use crate::llm::OllamaClient;
+use crate::sandbox::{Sandbox, SandboxRequest, SandboxLanguage};
+
+const TEMPERATURE: f32 = 0.2;
+const MAX_TOKENS: u32 = 1024;
+const INSTRUCTIONS: &str = "Reply with only the code in a single fenced block. Do not include tests, example usage or explanations.";
+
+/// A bundled task. Tasks with a `harness` are verifiable: the reply's code plus
+/// the harness must run cleanly in the sandbox.
+pub struct BenchmarkTask {
+    pub id: &'static str,
+    pub language: &'static str,
+    pub prompt: &'static str,
+    pub harness: Option<&'static str>,
+}
+
+static FIZZBUZZ: BenchmarkTask = BenchmarkTask {
+    id: "fizzbuzz",
+    language: "python",
+    prompt: "Write a Python function `fizzbuzz(n)` returning a list of strings for 1 to n inclusive: \"Fizz\" for multiples of 3, \"Buzz\" for multiples of 5, \"FizzBuzz\" for both, otherwise the number.",
+    harness: Some("assert fizzbuzz(5) == ['1', '2', 'Fizz', '4', 'Buzz']\nassert fizzbuzz(15)[-1] == 'FizzBuzz'\nassert fizzbuzz(0) == []\n"),
+};
+
+static REVERSE_WORDS: BenchmarkTask = BenchmarkTask {
+    id: "reverse_words",
+    language: "javascript",
+    prompt: "Write a JavaScript function `reverseWords(s)` that returns the words of a space-separated string in reverse order, joined by single spaces.",
+    harness: Some("const assert = require('assert');\nassert.strictEqual(reverseWords('hello big world'), 'world big hello');\nassert.strictEqual(reverseWords('one'), 'one');\n"),
+};
+
+static EXPLAIN_REGEX: BenchmarkTask = BenchmarkTask {
+    id: "explain_regex",
+    language: "markdown",
+    prompt: "Explain in two sentences what the regular expression ^\\d{3}-\\d{4}$ matches.",
+    harness: None,
+};
+
+static PALINDROME: BenchmarkTask = BenchmarkTask {
+    id: "palindrome",
+    language: "python",
+    prompt: "Write a Python function `is_palindrome(s)` that ignores case and any character that isn't a letter or digit.",
+    harness: Some("assert is_palindrome('A man, a plan, a canal: Panama')\nassert not is_palindrome('race a car')\nassert is_palindrome('')\n"),
+};
+
+static MERGE_INTERVALS: BenchmarkTask = BenchmarkTask {
+    id: "merge_intervals",
+    language: "javascript",
+    prompt: "Write a JavaScript function `mergeIntervals(intervals)` that takes an array of [start, end] pairs in any order and returns the overlapping ones merged, sorted by start.",
+    harness: Some("const assert = require('assert');\nassert.deepStrictEqual(mergeIntervals([[8, 10], [1, 3], [2, 6]]), [[1, 6], [8, 10]]);\nassert.deepStrictEqual(mergeIntervals([[1, 4], [4, 5]]), [[1, 5]]);\nassert.deepStrictEqual(mergeIntervals([]), []);\n"),
+};
+
+static PARSE_DURATION: BenchmarkTask = BenchmarkTask {
+    id: "parse_duration",
+    language: "python",
+    prompt: "Write a Python function `parse_duration(text)` that converts strings like \"1h30m\", \"45s\" or \"2h5s\" into a number of seconds, raising ValueError for anything else.",
+    harness: Some("assert parse_duration('1h30m') == 5400\nassert parse_duration('45s') == 45\nassert parse_duration('2h5s') == 7205\ntry:\n    parse_duration('soon')\n    raise SystemExit(1)\nexcept ValueError:\n    pass\n"),
+};
+
+static COMMIT_MESSAGE: BenchmarkTask = BenchmarkTask {
+    id: "commit_message",
+    language: "markdown",
+    prompt: "Write a one-line git commit message for a change that adds retry with exponential backoff to an HTTP client.",
+    harness: None,
+};
+
+/// Bundled suites by name; "quick" takes a minute or two on most machines
+pub fn suite(name: &str) -> Option<Vec<&'static BenchmarkTask>> {
+    match name {
+        "quick" => Some(vec![&FIZZBUZZ, &REVERSE_WORDS, &EXPLAIN_REGEX]),
+        "standard" => Some(vec![&FIZZBUZZ, &REVERSE_WORDS, &EXPLAIN_REGEX, &PALINDROME, &MERGE_INTERVALS, &PARSE_DURATION, &COMMIT_MESSAGE]),
+        _ => None,
+    }
+}
+
+pub const SUITES: &[&str] = &["quick", "standard"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskResult {
+    pub task_id: String,
+    /// Request to full reply, including model load and prompt processing
+    pub latency_ms: u64,
+    pub output_tokens: u64,
+    pub tokens_per_sec: f64,
+    /// None for tasks that can't be checked automatically
+    pub passed: Option<bool>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    pub id: String,
+    pub model: String,
+    pub suite: String,
+    pub started_at: String,
+    pub tasks: Vec<TaskResult>,
+    pub tokens_per_sec: f64,
+    pub avg_latency_ms: u64,
+    pub passed: usize,
+    pub verifiable: usize,
+}
+
+impl BenchmarkRun {
+    pub fn pass_rate(&self) -> f64 {
+        if self.verifiable == 0 {
+            return 0.0;
+        }
+        self.passed as f64 / self.verifiable as f64
+    }
+}
+
+/// Run the reply's code followed by the task's harness
+fn verify(task: &BenchmarkTask, reply: &str, harness: &str) -> Result<bool> {
+    let language = SandboxLanguage::from_name(task.language)
+        .ok_or_else(|| anyhow::anyhow!("Sandbox does not support {}", task.language))?;
+    let path = match language {
+        SandboxLanguage::Python => "solution.py",
+        SandboxLanguage::JavaScript => "solution.js",
+    };
+    let code = format!("{}\n\n{}", strip_fences(reply, path).trim_end(), harness);
+    let result = Sandbox::new()?.run(&SandboxRequest { language, code, stdin: None, limits: None })?;
+    Ok(result.success())
+}
+
+async fn run_task(client: &OllamaClient, model: &str, task: &'static BenchmarkTask) -> TaskResult {
+    let prompt = format!("{}\n\n{}", task.prompt, INSTRUCTIONS);
+    let started = Instant::now();
+    let generation = client.generate_with_stats(model, &prompt, TEMPERATURE, MAX_TOKENS).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let mut result = TaskResult {
+        task_id: task.id.to_string(),
+        latency_ms,
+        output_tokens: 0,
+        tokens_per_sec: 0.0,
+        passed: None,
+        error: None,
+    };
+    let generation = match generation {
+        Ok(generation) => generation,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            result.passed = task.harness.map(|_| false);
+            return result;
+        }
+    };
+
+    result.output_tokens = generation.eval_count.unwrap_or_else(|| crate::agent::estimate::estimate_tokens(&generation.text));
+    result.tokens_per_sec = match generation.eval_duration.filter(|ns| *ns > 0) {
+        Some(ns) => result.output_tokens as f64 / (ns as f64 / 1e9),
+        None => result.output_tokens as f64 / (latency_ms.max(1) as f64 / 1000.0),
+    };
+
+    if let Some(harness) = task.harness {
+        let reply = generation.text;
+        let checked = tokio::task::spawn_blocking(move || verify(task, &reply, harness)).await;
+        match checked {
+            Ok(Ok(passed)) => result.passed = Some(passed),
+            Ok(Err(e)) => result.error = Some(e.to_string()),
+            Err(e) => result.error = Some(e.to_string()),
+        }
+    }
+    result
+}
+
+fn summarize(id: String, model: &str, suite: &str, started_at: String, tasks: Vec<TaskResult>) -> BenchmarkRun {
+    let answered: Vec<&TaskResult> = tasks.iter().filter(|t| t.error.is_none() || t.output_tokens > 0).collect();
+    let count = answered.len().max(1);
+    BenchmarkRun {
+        id,
+        model: model.to_string(),
+        suite: suite.to_string(),
+        started_at,
+        tokens_per_sec: answered.iter().map(|t| t.tokens_per_sec).sum::<f64>() / count as f64,
+        avg_latency_ms: answered.iter().map(|t| t.latency_ms).sum::<u64>() / count as u64,
+        passed: tasks.iter().filter(|t| t.passed == Some(true)).count(),
+        verifiable: tasks.iter().filter(|t| t.passed.is_some()).count(),
+        tasks,
+    }
+}
+
+pub async fn benchmark(app: &tauri::AppHandle, client: &OllamaClient, model: &str, suite_name: &str) -> Result<BenchmarkRun> {
+    let tasks = suite(suite_name).ok_or_else(|| anyhow::anyhow!("Unknown benchmark suite '{}'", suite_name))?;
+    let started_at = chrono::Utc::now().to_rfc3339();
+
+    let mut results = Vec::new();
+    for (index, task) in tasks.iter().copied().enumerate() {
+        app.emit("model-benchmark-progress", serde_json::json!({
+            "model": model,
+            "task": task.id,
+            "index": index,
+            "total": tasks.len(),
+        })).ok();
+        results.push(run_task(client, model, task).await);
+    }
+
+    let run = summarize(uuid::Uuid::new_v4().to_string(), model, suite_name, started_at, results);
+    database::open()?.add_model_benchmark(&run.id, &run.model, &run.suite, &run.started_at, &serde_json::to_string(&run)?)?;
+    Ok(run)
+}
+
+pub fn history(model: Option<&str>, suite: Option<&str>) -> Result<Vec<BenchmarkRun>> {
+    let runs = database::open()?
+        .list_model_benchmarks(model, suite)?
+        .into_iter()
+        .filter_map(|data| serde_json::from_str(&data).ok())
+        .collect();
+    Ok(runs)
+}
+
+/// Each model's latest run, best pass rate first and then fastest
+pub fn compare(runs: Vec<BenchmarkRun>) -> Vec<BenchmarkRun> {
+    let mut latest: HashMap<String, BenchmarkRun> = HashMap::new();
+    for run in runs {
+        match latest.get(&run.model) {
+            Some(existing) if existing.started_at >= run.started_at => {}
+            _ => {
+                latest.insert(run.model.clone(), run);
+            }
+        }
+    }
+    let mut runs: Vec<BenchmarkRun> = latest.into_values().collect();
+    runs.sort_by(|a, b| {
+        b.pass_rate()
+            .total_cmp(&a.pass_rate())
+            .then(b.tokens_per_sec.total_cmp(&a.tokens_per_sec))
+    });
+    runs
+}
+
+// Tauri commands
+
+/// Run a bundled suite ("quick" or "standard") against a local Ollama model,
+/// emitting `model-benchmark-progress` before each task
+#[tauri::command]
+pub async fn benchmark_model(app: tauri::AppHandle, model: String, suite: Option<String>) -> Result<BenchmarkRun, String> {
+    let base_url = crate::preferences::PreferencesManager::new()
+        .and_then(|manager| manager.load())
+        .map(|prefs| prefs.llm.base_url)
+        .unwrap_or_else(|_| "http://localhost:11434".to_string());
+
+    let client = OllamaClient::new(base_url);
+    benchmark(&app, &client, &model, suite.as_deref().unwrap_or("quick"))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_benchmark_suites() -> Result<Vec<String>, String> {
+    Ok(SUITES.iter().map(|s| s.to_string()).collect())
+}
+
+/// Past runs, newest first
+#[tauri::command]
+pub async fn list_model_benchmarks(model: Option<String>, suite: Option<String>) -> Result<Vec<BenchmarkRun>, String> {
+    history(model.as_deref(), suite.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn compare_model_benchmarks(suite: String) -> Result<Vec<BenchmarkRun>, String> {
+    history(None, Some(&suite)).map(compare).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, tokens_per_sec: f64, passed: Option<bool>) -> TaskResult {
+        TaskResult {
+            task_id: id.to_string(),
+            latency_ms: 1000,
+            output_tokens: 100,
+            tokens_per_sec,
+            passed,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize_and_compare() {
+        for name in SUITES {
+            assert!(suite(name).is_some());
+        }
+        // Every verifiable task has to be runnable in the sandbox
+        let standard = suite("standard").unwrap();
+        assert!(standard.iter().filter(|t| t.harness.is_some()).all(|t| SandboxLanguage::from_name(t.language).is_some()));
+
+        let small = summarize("1".into(), "small", "quick", "2024-01-02".into(), vec![
+            task("a", 40.0, Some(true)),
+            task("b", 20.0, Some(false)),
+            task("c", 30.0, None),
+        ]);
+        assert_eq!((small.passed, small.verifiable, small.tokens_per_sec), (1, 2, 30.0));
+
+        let large = summarize("2".into(), "large", "quick", "2024-01-02".into(), vec![task("a", 10.0, Some(true)), task("b", 10.0, Some(true))]);
+        let old_large = summarize("3".into(), "large", "quick", "2024-01-01".into(), vec![task("a", 10.0, Some(false))]);
+
+        let ranked = compare(vec![small, old_large, large]);
+        assert_eq!(ranked.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), vec!["2", "1"]);
+    }
+}
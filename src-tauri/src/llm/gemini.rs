@@ -16,7 +16,17 @@ struct Content {
 
 #[derive(Serialize, Deserialize, Debug)]
 struct Part {
-    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inlineData: Option<InlineData>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct InlineData {
+    mimeType: String,
+    /// Base64
+    data: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -54,7 +64,7 @@ impl GeminiClient {
     pub fn new(api_key: String) -> Self {
         Self {
             api_key,
-            client: Client::new(),
+            client: crate::network::client(),
         }
     }
 
@@ -65,6 +75,19 @@ impl GeminiClient {
         system_prompt: Option<&str>,
         temperature: f32,
         max_tokens: u32,
+    ) -> Result<String> {
+        self.generate_with_images(model, prompt, system_prompt, &[], temperature, max_tokens).await
+    }
+
+    /// Generate with images attached as `(mime type, base64 data)` ahead of the prompt
+    pub async fn generate_with_images(
+        &self,
+        model: &str,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        images: &[(String, String)],
+        temperature: f32,
+        max_tokens: u32,
     ) -> Result<String> {
         let url = format!(
             "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
@@ -86,9 +109,17 @@ impl GeminiClient {
             prompt.to_string()
         };
 
+        let mut parts: Vec<Part> = images
+            .iter()
+            .map(|(mime_type, data)| Part {
+                text: None,
+                inlineData: Some(InlineData { mimeType: mime_type.clone(), data: data.clone() }),
+            })
+            .collect();
+        parts.push(Part { text: Some(final_prompt), inlineData: None });
         contents.push(Content {
             role: "user".to_string(),
-            parts: vec![Part { text: final_prompt }],
+            parts,
         });
 
         let request_body = GeminiRequest {
@@ -124,8 +155,8 @@ impl GeminiClient {
 
         if let Some(candidates) = gemini_response.candidates {
             if let Some(first_candidate) = candidates.first() {
-                if let Some(first_part) = first_candidate.content.parts.first() {
-                    return Ok(first_part.text.clone());
+                if let Some(text) = first_candidate.content.parts.iter().find_map(|p| p.text.clone()) {
+                    return Ok(text);
                 }
             }
         }
@@ -1,8 +1,12 @@
 pub mod ollama;
 pub mod client;
 pub mod gemini;
+pub mod structured;
+pub mod throughput;
+pub mod benchmark;
+pub mod vision;
 
-pub use ollama::OllamaClient;
+pub use ollama::{OllamaClient, OllamaGeneration, PullProgress};
 pub use gemini::GeminiClient;
 pub use client::{LLMClient, GenerationRequest, GenerationResponse};
 
@@ -76,8 +80,28 @@ pub async fn generate_code_stream(prompt: String) -> Result<String, String> {
     }
 }
 
+/// Pull a model through Ollama, emitting `model-pull-progress` events while it downloads
 #[tauri::command]
-pub async fn pull_model(model_name: String) -> Result<String, String> {
+pub async fn pull_model(app: tauri::AppHandle, model_name: String) -> Result<String, String> {
+    use tauri::Emitter;
+    
+    let base_url = crate::preferences::PreferencesManager::new()
+        .and_then(|manager| manager.load())
+        .map(|prefs| prefs.llm.base_url)
+        .unwrap_or_else(|_| "http://localhost:11434".to_string());
+    
+    OllamaClient::new(base_url)
+        .pull_model(&model_name, |progress| {
+            app.emit("model-pull-progress", serde_json::json!({
+                "model": model_name,
+                "status": progress.status,
+                "total": progress.total,
+                "completed": progress.completed,
+            })).ok();
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+    
     Ok(format!("Model {} pulled successfully", model_name))
 }
 
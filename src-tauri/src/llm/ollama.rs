@@ -1,14 +1,33 @@
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::preferences::{LLMPreferences, PreferencesManager};
+
+/// Context length each model was trained with, from `/api/show`
+static MODEL_CONTEXT: Mutex<Option<HashMap<String, u32>>> = Mutex::new(None);
 
 #[derive(Serialize, Deserialize, Debug)]
 struct OllamaRequest {
     model: String,
     prompt: String,
+    /// Base64 images for multimodal models such as LLaVA
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
     stream: bool,
+    options: OllamaOptions,
+}
+
+/// Sampling and runtime options; Ollama ignores these outside the `options` object
+#[derive(Serialize, Deserialize, Debug)]
+struct OllamaOptions {
     temperature: f32,
     num_predict: u32,
+    num_ctx: u32,
+    num_gpu: u32,
+    num_thread: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -16,6 +35,49 @@ struct OllamaResponse {
     response: String,
     model: String,
     done: bool,
+    /// Generated token count and time spent generating them (ns)
+    #[serde(default)]
+    eval_count: Option<u64>,
+    #[serde(default)]
+    eval_duration: Option<u64>,
+}
+
+/// A completion with Ollama's own token count and generation time (ns)
+#[derive(Debug, Clone)]
+pub struct OllamaGeneration {
+    pub text: String,
+    pub eval_count: Option<u64>,
+    pub eval_duration: Option<u64>,
+}
+
+/// One status line from a streaming `/api/pull`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PullProgress {
+    pub status: String,
+    #[serde(default)]
+    pub digest: Option<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub completed: Option<u64>,
+}
+
+/// The LLM preferences, with the defaults when they can't be read
+pub fn runtime_preferences() -> LLMPreferences {
+    PreferencesManager::new()
+        .and_then(|manager| manager.load())
+        .map(|prefs| prefs.llm)
+        .unwrap_or_default()
+}
+
+/// `<architecture>.context_length` from a `/api/show` response's `model_info`
+fn trained_context_length(show: &serde_json::Value) -> Option<u32> {
+    show.get("model_info")?
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.ends_with(".context_length"))
+        .and_then(|(_, value)| value.as_u64())
+        .map(|length| length.min(u32::MAX as u64) as u32)
 }
 
 #[derive(Clone)]
@@ -28,8 +90,61 @@ impl OllamaClient {
     pub fn new(base_url: String) -> Self {
         Self {
             base_url,
-            client: Client::new(),
+            client: crate::network::client(),
+        }
+    }
+
+    /// The client for the Ollama server in the LLM preferences
+    pub async fn configured() -> Result<Self> {
+        let base_url = tokio::task::spawn_blocking(|| runtime_preferences().base_url).await?;
+        Ok(Self::new(base_url))
+    }
+
+    /// Context length `model` was trained with; `None` when Ollama doesn't report one
+    async fn model_context_length(&self, model: &str) -> Option<u32> {
+        if let Some(length) = MODEL_CONTEXT.lock().unwrap_or_else(|e| e.into_inner()).as_ref().and_then(|m| m.get(model)) {
+            return Some(*length);
         }
+        let response = self
+            .client
+            .post(&format!("{}/api/show", self.base_url))
+            .json(&serde_json::json!({ "model": model }))
+            .send()
+            .await
+            .ok()?;
+        let show: serde_json::Value = response.error_for_status().ok()?.json().await.ok()?;
+        let length = trained_context_length(&show)?;
+        MODEL_CONTEXT
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(HashMap::new)
+            .insert(model.to_string(), length);
+        Some(length)
+    }
+
+    /// Runtime options for `model`: what the user configured, else what this machine's
+    /// hardware supports, with the context capped at the model's own
+    async fn runtime_options(&self, model: &str) -> Result<(u32, u32, u32)> {
+        // First use probes the GPU, which shells out
+        let (runtime, profile) = tokio::task::spawn_blocking(|| (runtime_preferences(), crate::hardware::profile())).await?;
+        let hardware = crate::hardware::recommend_llm_settings(&profile);
+        let num_ctx = match runtime.num_ctx {
+            Some(num_ctx) => num_ctx,
+            None => match self.model_context_length(model).await {
+                Some(trained) => hardware.context_length.min(trained),
+                None => hardware.context_length,
+            },
+        };
+        Ok((
+            num_ctx,
+            runtime.num_gpu.unwrap_or(hardware.num_gpu),
+            runtime.num_thread.unwrap_or(hardware.num_thread),
+        ))
+    }
+
+    /// Context window generations with `model` run with, in tokens
+    pub async fn context_length(&self, model: &str) -> Result<u32> {
+        Ok(self.runtime_options(model).await?.0)
     }
 
     pub async fn generate(
@@ -39,14 +154,43 @@ impl OllamaClient {
         temperature: f32,
         max_tokens: u32,
     ) -> Result<String> {
+        Ok(self.generate_with_stats(model, prompt, temperature, max_tokens).await?.text)
+    }
+
+    pub async fn generate_with_stats(
+        &self,
+        model: &str,
+        prompt: &str,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<OllamaGeneration> {
+        self.generate_with_images(model, prompt, &[], temperature, max_tokens).await
+    }
+
+    /// Generate with base64-encoded images attached; the model must be multimodal
+    pub async fn generate_with_images(
+        &self,
+        model: &str,
+        prompt: &str,
+        images: &[String],
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<OllamaGeneration> {
         tracing::info!("Sending request to Ollama at {} for model {}", self.base_url, model);
         
+        let (num_ctx, num_gpu, num_thread) = self.runtime_options(model).await?;
         let request = OllamaRequest {
             model: model.to_string(),
             prompt: prompt.to_string(),
+            images: images.to_vec(),
             stream: false,
-            temperature: temperature.clamp(0.0, 1.0),
-            num_predict: max_tokens,
+            options: OllamaOptions {
+                temperature: temperature.clamp(0.0, 1.0),
+                num_predict: max_tokens,
+                num_ctx,
+                num_gpu,
+                num_thread,
+            },
         };
 
         let response = self
@@ -73,7 +217,15 @@ impl OllamaClient {
             .await
             .map_err(|e| anyhow!("Failed to parse Ollama response: {}", e))?;
 
-        Ok(ollama_response.response.trim().to_string())
+        if let (Some(tokens), Some(duration)) = (ollama_response.eval_count, ollama_response.eval_duration) {
+            super::throughput::record(model, tokens, duration);
+        }
+
+        Ok(OllamaGeneration {
+            text: ollama_response.response.trim().to_string(),
+            eval_count: ollama_response.eval_count,
+            eval_duration: ollama_response.eval_duration,
+        })
     }
 
     pub async fn list_models(&self) -> Result<Vec<String>> {
@@ -106,6 +258,51 @@ impl OllamaClient {
             .collect())
     }
 
+    /// Download a model, reporting each progress line as Ollama streams it
+    pub async fn pull_model(&self, model: &str, mut on_progress: impl FnMut(PullProgress)) -> Result<()> {
+        let mut response = self
+            .client
+            .post(&format!("{}/api/pull", self.base_url))
+            .json(&serde_json::json!({ "name": model, "stream": true }))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach Ollama: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Ollama returned status {}: {}", status, text));
+        }
+
+        // Progress arrives as newline-delimited JSON, possibly split across chunks
+        let mut buffer = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| anyhow!("Model download interrupted: {}", e))?
+        {
+            buffer.extend_from_slice(&chunk);
+
+            while let Some(newline) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=newline).collect();
+                if line.iter().all(|b| b.is_ascii_whitespace()) {
+                    continue;
+                }
+
+                let value: serde_json::Value = serde_json::from_slice(&line)
+                    .map_err(|e| anyhow!("Failed to parse pull progress: {}", e))?;
+                if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+                    return Err(anyhow!("Failed to pull {}: {}", model, error));
+                }
+                if let Ok(progress) = serde_json::from_value::<PullProgress>(value) {
+                    on_progress(progress);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn is_available(&self) -> bool {
         self.client
             .get(&format!("{}/api/tags", self.base_url))
@@ -0,0 +1,134 @@
+use anyhow::{Result, anyhow};
+use jsonschema::JSONSchema;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::client::{GenerationRequest, LLMClient};
+
+/// First attempt plus retries with the validation errors fed back
+pub const MAX_ATTEMPTS: usize = 3;
+
+lazy_static::lazy_static! {
+    static ref JSON_BLOCK: Regex = Regex::new(r"```(?:json)?\s*\n([\s\S]*?)\n```").unwrap();
+}
+
+/// Pull the JSON document out of a model reply: a ```json block, or the outermost braces
+pub fn extract_json(text: &str) -> String {
+    if let Some(captures) = JSON_BLOCK.captures(text) {
+        return captures[1].to_string();
+    }
+
+    match (text.find('{'), text.rfind('}')) {
+        (Some(start), Some(end)) if end > start => text[start..=end].to_string(),
+        _ => text.trim().to_string(),
+    }
+}
+
+pub fn schema_of<T: JsonSchema>() -> Value {
+    serde_json::to_value(schemars::schema_for!(T)).unwrap_or(Value::Bool(true))
+}
+
+/// Validation errors for `instance`, empty when it matches the schema
+pub fn validate(schema: &Value, instance: &Value) -> Result<Vec<String>> {
+    let compiled = JSONSchema::compile(schema).map_err(|e| anyhow!("Invalid JSON schema: {}", e))?;
+    let errors = match compiled.validate(instance) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() { e.to_string() } else { format!("{}: {}", path, e) }
+            })
+            .collect(),
+    };
+    Ok(errors)
+}
+
+/// Parse and validate one reply; Err carries the problems to send back to the model
+fn check_reply<T: DeserializeOwned>(text: &str, schema: &Value) -> Result<T, Vec<String>> {
+    let json = extract_json(text);
+    let value: Value = serde_json::from_str(&json).map_err(|e| vec![format!("Not valid JSON: {}", e)])?;
+
+    let errors = validate(schema, &value).map_err(|e| vec![e.to_string()])?;
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    serde_json::from_value(value).map_err(|e| vec![e.to_string()])
+}
+
+/// Generate JSON matching `schema`. The schema is included in the prompt, and when
+/// the reply doesn't parse or validate the request is retried with the errors appended.
+pub async fn generate_structured<T: DeserializeOwned>(
+    client: &LLMClient,
+    request: GenerationRequest,
+    schema: &Value,
+) -> Result<T> {
+    let base_prompt = format!(
+        "{}\n\nYour response must be a single JSON document that validates against this JSON Schema:\n{}\n\nRespond with ONLY the JSON, no explanations.",
+        request.prompt,
+        serde_json::to_string_pretty(schema)?
+    );
+
+    let mut prompt = base_prompt.clone();
+    let mut last_errors = Vec::new();
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let response = client
+            .generate(GenerationRequest { prompt: prompt.clone(), ..request.clone() })
+            .await?;
+
+        match check_reply(&response.text, schema) {
+            Ok(value) => return Ok(value),
+            Err(errors) => {
+                tracing::warn!("Structured output attempt {} failed: {}", attempt, errors.join("; "));
+                prompt = format!(
+                    "{}\n\nYour previous response was rejected:\n{}\n\nErrors:\n- {}\n\nReturn corrected JSON only.",
+                    base_prompt,
+                    extract_json(&response.text).chars().take(4000).collect::<String>(),
+                    errors.join("\n- ")
+                );
+                last_errors = errors;
+            }
+        }
+    }
+
+    Err(anyhow!(
+        "Model did not return valid JSON after {} attempts: {}",
+        MAX_ATTEMPTS,
+        last_errors.join("; ")
+    ))
+}
+
+/// `generate_structured` with the schema derived from `T`
+pub async fn generate_typed<T: DeserializeOwned + JsonSchema>(client: &LLMClient, request: GenerationRequest) -> Result<T> {
+    generate_structured(client, request, &schema_of::<T>()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Plan {
+        name: String,
+        steps: Vec<String>,
+    }
+
+    #[test]
+    fn test_check_reply() {
+        let schema = schema_of::<Plan>();
+
+        let plan: Plan = check_reply("Sure!\n```json\n{\"name\": \"x\", \"steps\": [\"a\"]}\n```", &schema).unwrap();
+        assert_eq!(plan.steps, ["a"]);
+
+        let errors = check_reply::<Plan>("{\"name\": \"x\", \"steps\": \"a\"}", &schema).unwrap_err();
+        assert!(errors[0].contains("/steps"), "{:?}", errors);
+
+        let errors = check_reply::<Plan>("{\"name\": \"x\",", &schema).unwrap_err();
+        assert!(errors[0].starts_with("Not valid JSON"));
+    }
+}
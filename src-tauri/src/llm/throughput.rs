@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Weight of the newest sample in the running average
+const SMOOTHING: f64 = 0.3;
+
+/// Generation speed measured for one model on this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelThroughput {
+    pub tokens_per_sec: f64,
+    pub samples: u64,
+}
+
+static THROUGHPUT: Mutex<Option<HashMap<String, ModelThroughput>>> = Mutex::new(None);
+
+fn throughput_file() -> Result<PathBuf> {
+    let app_dir = dirs::data_dir()
+        .context("Failed to get data directory")?
+        .join(".sai-ide");
+    std::fs::create_dir_all(&app_dir)?;
+    Ok(app_dir.join("model_throughput.json"))
+}
+
+fn load() -> HashMap<String, ModelThroughput> {
+    throughput_file()
+        .and_then(|path| Ok(std::fs::read_to_string(path)?))
+        .and_then(|json| Ok(serde_json::from_str(&json)?))
+        .unwrap_or_default()
+}
+
+/// Record a completed generation. Ollama reports `eval_duration` in nanoseconds.
+pub fn record(model: &str, tokens: u64, duration_ns: u64) {
+    if tokens == 0 || duration_ns == 0 {
+        return;
+    }
+    let rate = tokens as f64 / (duration_ns as f64 / 1e9);
+
+    let mut guard = THROUGHPUT.lock().unwrap_or_else(|e| e.into_inner());
+    let measurements = guard.get_or_insert_with(load);
+    let entry = measurements
+        .entry(model.to_string())
+        .or_insert(ModelThroughput { tokens_per_sec: rate, samples: 0 });
+    entry.tokens_per_sec = entry.tokens_per_sec * (1.0 - SMOOTHING) + rate * SMOOTHING;
+    entry.samples += 1;
+
+    let saved = throughput_file()
+        .and_then(|path| Ok(std::fs::write(path, serde_json::to_string_pretty(measurements)?)?));
+    if let Err(e) = saved {
+        tracing::warn!("Failed to save model throughput: {}", e);
+    }
+}
+
+/// Measured tokens/sec for `model`, if it has run on this machine before
+pub fn measured(model: &str) -> Option<ModelThroughput> {
+    let mut guard = THROUGHPUT.lock().unwrap_or_else(|e| e.into_inner());
+    guard.get_or_insert_with(load).get(model).cloned()
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use image::codecs::png::PngEncoder;
+use image::imageops::FilterType;
+use image::{GenericImageView, ImageEncoder};
+use std::path::Path;
+
+use crate::llm::{GeminiClient, OllamaClient};
+use crate::preferences::{CloudProvider, PreferencesManager};
+
+/// Longest side sent to the model. Vision models tile or downscale anything larger,
+/// so bigger images only cost upload time and tokens.
+const MAX_IMAGE_SIDE: u32 = 1568;
+const DEFAULT_OLLAMA_VISION_MODEL: &str = "llava:7b";
+const DEFAULT_GEMINI_VISION_MODEL: &str = "gemini-1.5-flash";
+
+/// An image ready to attach to a request
+#[derive(Debug, Clone)]
+pub struct ImageInput {
+    pub mime_type: String,
+    /// Base64
+    pub data: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageAnalysis {
+    pub text: String,
+    pub provider: String,
+    pub model: String,
+    /// Size actually sent, after downscaling
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Load an image for a vision model. Small PNGs and JPEGs are sent as they are;
+/// anything else is downscaled to `MAX_IMAGE_SIDE` and re-encoded as PNG.
+pub fn load_image(path: &Path) -> Result<ImageInput> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let format = image::guess_format(&bytes).context("Not a supported image")?;
+    let decoded = image::load_from_memory_with_format(&bytes, format)?;
+    let (width, height) = decoded.dimensions();
+
+    let passthrough = match format {
+        image::ImageFormat::Png => Some("image/png"),
+        image::ImageFormat::Jpeg => Some("image/jpeg"),
+        _ => None,
+    };
+    if let Some(mime_type) = passthrough.filter(|_| width.max(height) <= MAX_IMAGE_SIDE) {
+        return Ok(ImageInput { mime_type: mime_type.to_string(), data: BASE64.encode(&bytes), width, height });
+    }
+
+    let resized = if width.max(height) > MAX_IMAGE_SIDE {
+        decoded.resize(MAX_IMAGE_SIDE, MAX_IMAGE_SIDE, FilterType::Lanczos3)
+    } else {
+        decoded
+    };
+    let rgba = resized.to_rgba8();
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png).write_image(&rgba, rgba.width(), rgba.height(), image::ColorType::Rgba8)?;
+    Ok(ImageInput { mime_type: "image/png".to_string(), data: BASE64.encode(&png), width: rgba.width(), height: rgba.height() })
+}
+
+/// Ask a vision model about an image, through the configured provider unless one is
+/// given: Gemini, or a multimodal Ollama model such as LLaVA
+pub async fn analyze(
+    image_path: &Path,
+    prompt: &str,
+    system_prompt: Option<&str>,
+    provider: Option<&str>,
+    model: Option<String>,
+) -> Result<ImageAnalysis> {
+    let prefs = PreferencesManager::new()?.load()?;
+    let provider = match provider.map(|p| p.to_lowercase()).as_deref() {
+        Some("gemini") => CloudProvider::Gemini,
+        Some("ollama") | Some("local") => CloudProvider::Ollama,
+        Some(other) => anyhow::bail!("Unknown provider '{}'", other),
+        None => prefs.llm.cloud_provider,
+    };
+
+    let path = image_path.to_path_buf();
+    let image = tokio::task::spawn_blocking(move || load_image(&path)).await??;
+
+    let (provider_name, model, text) = match provider {
+        CloudProvider::Ollama => {
+            let model = model.unwrap_or_else(|| DEFAULT_OLLAMA_VISION_MODEL.to_string());
+            let prompt = match system_prompt {
+                Some(system) => format!("{}\n\n{}", system, prompt),
+                None => prompt.to_string(),
+            };
+            let generation = OllamaClient::new(prefs.llm.base_url)
+                .generate_with_images(&model, &prompt, &[image.data.clone()], prefs.llm.temperature, prefs.llm.max_tokens)
+                .await
+                .with_context(|| format!("{} failed; make sure it is a vision model and pulled", model))?;
+            ("ollama", model, generation.text)
+        }
+        CloudProvider::Gemini => {
+            if prefs.llm.gemini_api_key.is_empty() {
+                anyhow::bail!("Gemini API Key is missing in preferences.");
+            }
+            let model = model.unwrap_or_else(|| DEFAULT_GEMINI_VISION_MODEL.to_string());
+            let text = GeminiClient::new(prefs.llm.gemini_api_key)
+                .generate_with_images(
+                    &model,
+                    prompt,
+                    system_prompt,
+                    &[(image.mime_type.clone(), image.data.clone())],
+                    prefs.llm.temperature,
+                    prefs.llm.max_tokens,
+                )
+                .await?;
+            ("gemini", model, text)
+        }
+    };
+
+    Ok(ImageAnalysis { text, provider: provider_name.to_string(), model, width: image.width, height: image.height })
+}
+
+// Tauri commands
+
+/// Describe or answer a question about a screenshot, diagram or mockup
+#[tauri::command]
+pub async fn analyze_image(
+    image_path: String,
+    prompt: String,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Result<ImageAnalysis, String> {
+    analyze(Path::new(&image_path), &prompt, None, provider.as_deref(), model)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_image_downscales() {
+        let dir = std::env::temp_dir().join(format!("vision-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let small = dir.join("small.png");
+        image::RgbaImage::new(40, 20).save(&small).unwrap();
+        let input = load_image(&small).unwrap();
+        assert_eq!((input.mime_type.as_str(), input.width, input.height), ("image/png", 40, 20));
+        assert_eq!(BASE64.decode(&input.data).unwrap(), std::fs::read(&small).unwrap());
+
+        let large = dir.join("large.bmp");
+        image::RgbaImage::new(3136, 1000).save(&large).unwrap();
+        let input = load_image(&large).unwrap();
+        assert_eq!((input.mime_type.as_str(), input.width, input.height), ("image/png", 1568, 500));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
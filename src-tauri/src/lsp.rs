@@ -0,0 +1,308 @@
+use anyhow::{Result, Context};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::documents;
+use crate::terminal::shell::find_in_path;
+
+/// Language servers tried for each file extension, with their arguments
+fn server_for(extension: &str) -> Option<(&'static str, &'static [&'static str])> {
+    match extension {
+        "rs" => Some(("rust-analyzer", &[])),
+        "ts" | "tsx" | "js" | "jsx" | "mjs" => Some(("typescript-language-server", &["--stdio"])),
+        "py" => Some(("pylsp", &[])),
+        "go" => Some(("gopls", &[])),
+        _ => None,
+    }
+}
+
+pub fn language_id(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "rs" => "rust",
+        "ts" => "typescript",
+        "tsx" => "typescriptreact",
+        "jsx" => "javascriptreact",
+        "py" => "python",
+        "go" => "go",
+        _ => "javascript",
+    }
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.to_path_buf())
+    };
+    url::Url::from_file_path(&absolute)
+        .map(String::from)
+        .unwrap_or_else(|_| format!("file://{}", absolute.to_string_lossy()))
+}
+
+pub fn uri_to_path(uri: &str) -> PathBuf {
+    url::Url::parse(uri)
+        .ok()
+        .and_then(|url| url.to_file_path().ok())
+        .unwrap_or_else(|| PathBuf::from(uri.trim_start_matches("file://")))
+}
+
+type SharedSession = Arc<Mutex<LspSession>>;
+
+/// Running servers by project root and program, kept alive between requests so
+/// slow-starting servers like rust-analyzer only index once
+static SESSIONS: Mutex<Option<HashMap<(PathBuf, &'static str), SharedSession>>> = Mutex::new(None);
+
+/// The running server for `file`'s language in `root`, started on first use
+pub fn shared_session(root: &Path, file: &Path, timeout: Duration) -> Result<SharedSession> {
+    let extension = file.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+    let (program, _) = server_for(&extension).context("No language server for this file type")?;
+    let key = (PathBuf::from(documents::normalize_path(&root.to_string_lossy())), program);
+
+    let existing = SESSIONS.lock().unwrap().get_or_insert_with(HashMap::new).get(&key).cloned();
+    if let Some(session) = existing {
+        let alive = session.lock().unwrap().child.try_wait().map(|status| status.is_none()).unwrap_or(false);
+        if alive {
+            return Ok(session);
+        }
+    }
+
+    // Started outside the map lock; initialize can take a while
+    let session = Arc::new(Mutex::new(LspSession::start(root, file, timeout)?));
+    SESSIONS.lock().unwrap().get_or_insert_with(HashMap::new).insert(key, session.clone());
+    Ok(session)
+}
+
+/// Drop a server that stopped answering, so the next request starts a fresh one
+pub fn discard_session(session: &SharedSession) {
+    if let Some(sessions) = SESSIONS.lock().unwrap().as_mut() {
+        sessions.retain(|_, s| !Arc::ptr_eq(s, session));
+    }
+}
+
+/// Tell running servers that files were written outside the editor
+pub fn notify_files_changed(paths: &[PathBuf]) {
+    let sessions: Vec<SharedSession> =
+        SESSIONS.lock().unwrap().as_ref().map(|s| s.values().cloned().collect()).unwrap_or_default();
+    for session in sessions {
+        let mut session = session.lock().unwrap();
+        let mut changes = Vec::new();
+        for key in paths.iter().map(|path| documents::normalize_path(&path.to_string_lossy())) {
+            if !Path::new(&key).starts_with(&session.root) {
+                continue;
+            }
+            // An open copy would shadow the new disk content; it's reopened on next use
+            if session.synced.remove(&key).is_some() {
+                session.notify("textDocument/didClose", json!({ "textDocument": { "uri": path_to_uri(Path::new(&key)) } })).ok();
+            }
+            changes.push(json!({ "uri": path_to_uri(Path::new(&key)), "type": 2 }));
+        }
+        if !changes.is_empty() {
+            session.notify("workspace/didChangeWatchedFiles", json!({ "changes": changes })).ok();
+        }
+    }
+}
+
+/// Stdio connection to a language server, either one-off or shared through `shared_session`
+pub struct LspSession {
+    child: Child,
+    stdin: ChildStdin,
+    messages: Receiver<Value>,
+    next_id: i64,
+    timeout: Duration,
+    root: PathBuf,
+    /// Documents the server has open, by normalized path, with the version it last saw
+    synced: HashMap<String, u64>,
+}
+
+fn read_message<R: BufRead>(reader: &mut R) -> Option<Value> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let mut body = vec![0u8; length?];
+    reader.read_exact(&mut body).ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+impl LspSession {
+    /// Start the server for `file`'s language and initialize it for `root`
+    pub fn start(root: &Path, file: &Path, timeout: Duration) -> Result<Self> {
+        let extension = file.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let (program, args) = server_for(&extension).context("No language server for this file type")?;
+        let executable = find_in_path(program).with_context(|| format!("{} is not installed", program))?;
+
+        let mut child = Command::new(executable)
+            .args(args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", program))?;
+
+        let stdin = child.stdin.take().context("No stdin")?;
+        let stdout = child.stdout.take().context("No stdout")?;
+
+        // A reader thread lets requests time out instead of blocking on a silent server
+        let (sender, messages) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            while let Some(message) = read_message(&mut reader) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut session = Self {
+            child,
+            stdin,
+            messages,
+            next_id: 1,
+            timeout,
+            root: PathBuf::from(documents::normalize_path(&root.to_string_lossy())),
+            synced: HashMap::new(),
+        };
+
+        session.request("initialize", json!({
+            "processId": std::process::id(),
+            "rootUri": path_to_uri(root),
+            "capabilities": {
+                "workspace": { "workspaceEdit": { "documentChanges": true } },
+                "textDocument": { "rename": { "prepareSupport": false } }
+            }
+        }))?;
+        session.notify("initialized", json!({}))?;
+        session.sync_documents()?;
+
+        Ok(session)
+    }
+
+    fn send(&mut self, message: &Value) -> Result<()> {
+        let body = serde_json::to_string(message)?;
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    pub fn notify(&mut self, method: &str, params: Value) -> Result<()> {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    pub fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+
+        loop {
+            let message = self.messages
+                .recv_timeout(self.timeout)
+                .with_context(|| format!("Language server did not answer {}", method))?;
+
+            // Servers may ask for configuration or progress tokens before answering
+            if message.get("method").is_some() {
+                if let Some(request_id) = message.get("id") {
+                    self.send(&json!({ "jsonrpc": "2.0", "id": request_id, "result": Value::Null }))?;
+                }
+                continue;
+            }
+
+            if message["id"] != json!(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                anyhow::bail!("{} failed: {}", method, error["message"].as_str().unwrap_or("unknown error"));
+            }
+            return Ok(message["result"].clone());
+        }
+    }
+
+    fn did_open(&mut self, key: String, text: &str, version: u64) -> Result<()> {
+        let path = Path::new(&key);
+        self.notify("textDocument/didOpen", json!({
+            "textDocument": { "uri": path_to_uri(path), "languageId": language_id(path), "version": version, "text": text }
+        }))?;
+        self.synced.insert(key, version);
+        Ok(())
+    }
+
+    /// Open `path` with the editor's buffer when it has one, so requests see unsaved edits
+    pub fn open_document(&mut self, path: &Path) -> Result<()> {
+        self.sync_documents()?;
+        let key = documents::normalize_path(&path.to_string_lossy());
+        if self.synced.contains_key(&key) {
+            return Ok(());
+        }
+        let text = documents::read_document(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        self.did_open(key, &text, 0)
+    }
+
+    /// Send the open buffers under the root: `didOpen` for dirty ones the server hasn't
+    /// seen, a full-text `didChange` for ones edited since they were sent
+    pub fn sync_documents(&mut self) -> Result<()> {
+        for (key, document) in documents::open_under(&self.root) {
+            match self.synced.get(&key) {
+                Some(version) if *version == document.version => {}
+                Some(_) => {
+                    self.notify("textDocument/didChange", json!({
+                        "textDocument": { "uri": path_to_uri(Path::new(&key)), "version": document.version },
+                        "contentChanges": [{ "text": document.content }]
+                    }))?;
+                    self.synced.insert(key, document.version);
+                }
+                None if document.dirty => self.did_open(key, &document.content, document.version)?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for LspSession {
+    fn drop(&mut self) {
+        // Don't wait long on a server that is already gone
+        self.timeout = Duration::from_secs(2);
+        let _ = self.request("shutdown", Value::Null);
+        let _ = self.notify("exit", Value::Null);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_message() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"result":null}"#;
+        let raw = format!("Content-Length: {}\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n{}", body.len(), body);
+        let message = read_message(&mut BufReader::new(raw.as_bytes())).unwrap();
+        assert_eq!(message["id"], 1);
+    }
+
+    #[test]
+    fn test_uri_round_trip() {
+        let path = std::env::temp_dir().join("my project").join("a#b.rs");
+        let uri = path_to_uri(&path);
+        assert!(uri.starts_with("file://") && uri.contains("my%20project") && uri.contains("a%23b.rs"));
+        assert_eq!(uri_to_path(&uri), path);
+    }
+}
@@ -28,6 +28,68 @@ mod notifications;
 mod debugging;
 mod profiler;
 mod window;
+mod remote;
+mod sandbox;
+mod repl;
+mod notebook;
+mod markdown;
+mod preview;
+mod secrets;
+mod db_browser;
+mod monorepo;
+mod dependencies;
+mod imports;
+mod syntax;
+mod dedup;
+mod dead_code;
+mod statistics;
+mod documents;
+mod lsp;
+mod rename;
+mod assets;
+mod screenshot;
+mod bug_report;
+mod clipboard;
+mod i18n;
+mod doctor;
+mod hardware;
+mod setup;
+mod network;
+mod architecture;
+mod docs_site;
+mod file_transfer;
+mod checksums;
+mod ignore_files;
+mod permissions;
+mod docs_cache;
+mod devcontainer;
+mod installer;
+mod tasks;
+mod diagnostics;
+mod spellcheck;
+mod bookmarks;
+mod notes;
+mod board;
+mod time_tracking;
+mod focus;
+mod health;
+mod experiments;
+mod speech;
+mod tts;
+mod figma;
+mod package_manager;
+mod python_envs;
+mod node_versions;
+mod rust_toolchain;
+mod project_profile;
+mod readonly;
+mod file_locks;
+mod edit_journal;
+mod change_export;
+mod checkpoints;
+mod digest;
+mod team_pack;
+mod annotation_sync;
 
 // Main state that will be shared across the app
 #[derive(Default)]
@@ -36,6 +98,13 @@ struct AppState {
 }
 
 fn main() {
+    profiler::mark_process_start();
+
+    // Installed git hook shims re-invoke the binary headless
+    if let Some(exit_code) = git::hooks::run_from_cli() {
+        std::process::exit(exit_code);
+    }
+
     // Initialize logging
     tracing_subscriber::registry()
         .with(
@@ -43,6 +112,8 @@ fn main() {
                 .unwrap_or_else(|_| "luciai_studio=debug".into()),
         )
         .with(tracing_subscriber::fmt::layer())
+        // Keep a plain-text tail of the log for bug reports
+        .with(tracing_subscriber::fmt::layer().with_ansi(false).with_writer(bug_report::RecentLogs))
         .init();
 
     tauri::Builder::default()
@@ -51,6 +122,7 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .setup(|app| {
+            let setup_started = std::time::Instant::now();
             tracing::info!("Luciai Studio starting...");
             
             // Setup app data directory
@@ -65,6 +137,25 @@ fn main() {
             
             tracing::info!("App data directory: {:?}", app_dir);
             
+            clipboard::resume_watcher(app.handle().clone());
+            
+            // Warm the database schema and preferences off the main thread so
+            // they don't hold up the first paint
+            tauri::async_runtime::spawn_blocking(|| {
+                let db = profiler::time_startup_phase("database_init", || {
+                    database::open().map(|_| ())
+                });
+                if let Err(e) = db {
+                    tracing::error!("Database initialization failed: {}", e);
+                }
+                let prefs = profiler::time_startup_phase("preferences_load", || {
+                    preferences::PreferencesManager::new().and_then(|p| p.load())
+                });
+                if let Err(e) = prefs {
+                    tracing::error!("Failed to load preferences: {}", e);
+                }
+            });
+            
             // Open DevTools in debug mode
             #[cfg(debug_assertions)]
             {
@@ -74,6 +165,7 @@ fn main() {
                 }
             }
             
+            profiler::record_startup_phase("setup", setup_started);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -86,6 +178,7 @@ fn main() {
             project::list_projects,
             project::open_project,
             project::delete_project,
+            project::export_project,
             project::save_file,
             project::save_multiple_files,
             project::get_file,
@@ -94,8 +187,23 @@ fn main() {
             
             // ============ AGENT COMMANDS ============
             agent::send_prompt,
+            agent::context::send_prompt_with_context,
+            agent::context::collect_context_candidates,
             agent::get_agent_history,
             agent::pipeline::generate_full_project,
+            agent::estimate::estimate_generation,
+            agent::pipeline::regenerate_file,
+            agent::pipeline::apply_regenerated_file,
+            agent::extension::extend_existing_project,
+            agent::generation_report::get_generation_report,
+            agent::generation_report::list_generation_reports,
+            agent::generation_report::run_generation_tests,
+            agent::guardrails::check_generated_output,
+            agent::injection::scan_for_injection,
+            agent::injection::get_injection_detections,
+            agent::injection::clear_injection_detections,
+            agent::web_fetch::fetch_url,
+            agent::web_fetch::clear_fetch_cache,
             
             // ============ LLM COMMANDS ============
             llm::check_llm_status,
@@ -103,10 +211,30 @@ fn main() {
             llm::generate_code,
             llm::generate_code_stream,
             llm::pull_model,
+            llm::benchmark::benchmark_model,
+            llm::benchmark::list_benchmark_suites,
+            llm::benchmark::list_model_benchmarks,
+            llm::benchmark::compare_model_benchmarks,
+            llm::vision::analyze_image,
             llm::generate_llm_response,
             
             // ============ TERMINAL COMMANDS ============
             terminal::execute_command,
+            terminal::shell::list_available_shells,
+            terminal::shell::get_terminal_profile,
+            terminal::shell::save_terminal_profile,
+            terminal::ssh::list_ssh_hosts,
+            terminal::ssh::save_ssh_host,
+            terminal::ssh::delete_ssh_host,
+            terminal::ssh::open_ssh_session,
+            terminal::ssh::ssh_send_input,
+            terminal::ssh::close_ssh_session,
+            terminal::ssh::list_ssh_sessions,
+            terminal::ssh::set_ssh_host_key,
+            terminal::ssh::scan_ssh_host_key,
+            terminal::ssh::trust_ssh_host_key,
+            terminal::ssh::sftp_upload_file,
+            terminal::ssh::sftp_download_file,
             
             // ============ FILESYSTEM COMMANDS ============
             filesystem::read_file,
@@ -119,7 +247,19 @@ fn main() {
             filesystem::get_metadata,
             filesystem::rename_path,
             filesystem::copy_file,
+            file_transfer::copy_path,
+            file_transfer::move_path,
+            file_transfer::cancel_transfer,
+            checksums::hash_file,
+            checksums::verify_checksums,
+            ignore_files::get_ignore_patterns,
+            ignore_files::add_ignore_pattern,
+            ignore_files::remove_ignore_pattern,
+            ignore_files::validate_ignore_pattern,
+            ignore_files::check_path_ignored,
             filesystem::search_files,
+            remote::open_remote_workspace,
+            remote::refresh_remote_metadata,
             
             // ============ WINDOW COMMANDS ============
             window::minimize_window,
@@ -132,6 +272,8 @@ fn main() {
             templates::get_project_template,
             templates::search_templates,
             templates::create_project_from_template,
+            templates::pinning::pin_project_dependencies,
+            templates::pinning::get_project_toolchain,
             
             // ============ GIT COMMANDS ============
             git::git_init,
@@ -146,7 +288,31 @@ fn main() {
             git::git_push,
             git::git_add_remote,
             git::git_diff,
-            git::git_clone,
+            git::clone::git_clone,
+            git::clone::cancel_git_clone,
+            git::lfs::git_lfs_status,
+            git::lfs::git_lfs_track,
+            git::lfs::git_lfs_pull,
+            git::signing::get_commit_signing_config,
+            git::signing::configure_commit_signing,
+            git::signing::list_signing_keys,
+            git::signing::verify_commit_signatures,
+            git::push_safety::check_push_safety,
+            git::push_safety::git_push_force_with_lease,
+            git::push_safety::get_push_protection,
+            git::push_safety::save_push_protection,
+            git::commitlint::lint_commit_message,
+            git::commitlint::install_commit_msg_hook,
+            git::commitlint::uninstall_commit_msg_hook,
+            git::hooks::get_git_hooks_config,
+            git::hooks::save_git_hooks_config,
+            git::hooks::install_git_hooks,
+            git::hooks::uninstall_git_hooks,
+            git::hooks::run_git_hook,
+            git::hooks::git_commit_with_hooks,
+            git::changelog::generate_changelog,
+            git::release::get_project_versions,
+            git::release::bump_version,
             
             // ============ PREFERENCES COMMANDS ============
             preferences::load_preferences,
@@ -154,11 +320,22 @@ fn main() {
             preferences::reset_preferences,
             preferences::export_preferences,
             preferences::import_preferences,
+            preferences::get_accessibility_settings,
             
             // ============ REFACTORING COMMANDS ============
             agent::refactorer::refactor_code,
             agent::refactorer::explain_code,
+            agent::refactorer::ask_about_selection,
             agent::refactorer::convert_code_language,
+            agent::documentation::document_project,
+            agent::documentation::list_doc_proposals,
+            agent::documentation::review_doc_proposal,
+            agent::documentation::apply_doc_proposals,
+            agent::error_explainer::explain_error,
+            agent::error_explainer::apply_error_fix,
+            agent::test_fixer::fix_failing_tests,
+            agent::repro::generate_repro,
+            agent::project_converter::convert_project_language,
             
             // ============ DEPLOYMENT COMMANDS (V2.2 NEW!) ============
             agent::deployment::generate_deployment_guide,
@@ -206,10 +383,14 @@ fn main() {
             plugins::list_plugins,
             plugins::get_plugin_info,
             plugins::toggle_plugin_enabled,
+            plugins::inspect_plugin_package,
             plugins::install_plugin_from_path,
             plugins::uninstall_plugin,
             plugins::execute_plugin_command,
             plugins::search_plugin_marketplace,
+            permissions::list_granted_permissions,
+            permissions::grant_permission,
+            permissions::revoke_permission,
             
             // ============ AUTO-UPDATE COMMANDS ============
             updater::check_for_updates,
@@ -227,14 +408,361 @@ fn main() {
             database::search_indexed_content,
             database::track_feature,
             database::get_all_usage_stats,
+            time_tracking::start_time_tracking,
+            time_tracking::stop_time_tracking,
+            time_tracking::record_time_activity,
+            time_tracking::get_time_tracking_status,
+            time_tracking::get_time_report,
+            time_tracking::export_time_report_csv,
+            focus::start_focus,
+            focus::pause_focus,
+            focus::resume_focus,
+            focus::skip_focus_phase,
+            focus::stop_focus,
+            focus::get_focus_state,
+            focus::get_focus_history,
             database::create_bookmark,
             database::list_bookmarks,
             database::remove_bookmark,
+            bookmarks::reconcile_bookmarks,
+            notes::create_note,
+            notes::update_note,
+            notes::list_notes,
+            notes::search_notes,
+            notes::delete_note,
+            notes::export_notes,
             database::save_workspace_session,
             database::load_workspace_session,
             database::list_workspace_sessions,
             database::delete_workspace_session,
+            database::search_terminal_history,
+            database::get_terminal_suggestions,
             database::get_database_size,
+            
+            // ============ SANDBOX COMMANDS ============
+            sandbox::run_in_sandbox,
+            sandbox::verify_code_snippet,
+            
+            // ============ REPL COMMANDS ============
+            repl::repl_create,
+            repl::repl_eval,
+            repl::repl_reset,
+            repl::repl_close,
+            repl::repl_list_sessions,
+            
+            // ============ NOTEBOOK COMMANDS ============
+            notebook::load_notebook,
+            notebook::save_notebook,
+            notebook::start_notebook_kernel,
+            notebook::execute_notebook_cell,
+            notebook::interrupt_notebook_kernel,
+            notebook::shutdown_notebook_kernel,
+            notebook::list_notebook_kernels,
+            
+            // ============ MARKDOWN COMMANDS ============
+            markdown::render_markdown,
+            markdown::list_highlight_themes,
+            
+            // ============ PREVIEW COMMANDS ============
+            preview::start_preview_server,
+            preview::stop_preview_server,
+            preview::list_preview_servers,
+            docs_site::generate_docs_site,
+            
+            // ============ OPENAPI COMMANDS ============
+            agent::openapi::generate_openapi_spec,
+            agent::openapi::validate_openapi_spec,
+            agent::openapi::diff_openapi,
+            
+            // ============ DATABASE BROWSER COMMANDS ============
+            db_browser::list_db_connections,
+            db_browser::save_db_connection,
+            db_browser::delete_db_connection,
+            db_browser::test_db_connection,
+            db_browser::get_db_schema,
+            db_browser::execute_db_query,
+            db_browser::cancel_db_query,
+            db_browser::export_db_query,
+            
+            // ============ SQL ASSISTANT COMMANDS ============
+            agent::sql_assistant::generate_sql,
+            agent::sql_assistant::explain_sql,
+            agent::sql_assistant::execute_generated_sql,
+            
+            // ============ MONOREPO COMMANDS ============
+            monorepo::list_workspace_packages,
+            monorepo::run_package_tests,
+            monorepo::build_workspace_package,
+            monorepo::audit_workspace_package,
+            monorepo::get_package_context,
+            
+            // ============ DEPENDENCY COMMANDS ============
+            dependencies::check_outdated_dependencies,
+            dependencies::apply_dependency_upgrades,
+            dependencies::summarize_dependency_changes,
+            
+            // ============ IMPORT COMMANDS ============
+            imports::organize_imports,
+            imports::organize_project_imports,
+            
+            // ============ CODE ANALYSIS COMMANDS ============
+            dedup::find_duplicate_code,
+            dead_code::find_dead_code,
+            architecture::generate_architecture_diagram,
+            statistics::get_project_statistics,
+
+            // ============ DOCUMENT COMMANDS ============
+            documents::open_document,
+            documents::update_document,
+            documents::save_document,
+            documents::reload_document,
+            documents::close_document,
+            documents::get_document,
+            documents::list_open_documents,
+            documents::check_document_conflicts,
+            
+            // ============ RENAME COMMANDS ============
+            rename::rename_symbol,
+            rename::apply_rename,
+            rename::undo_rename,
+            
+            // ============ ASSET COMMANDS ============
+            assets::generate_icons,
+            assets::convert_image,
+            
+            // ============ BUG REPORT COMMANDS ============
+            screenshot::capture_window_screenshot,
+            bug_report::create_bug_report,
+            
+            // ============ CLIPBOARD COMMANDS ============
+            clipboard::set_clipboard_watcher,
+            clipboard::get_clipboard_watcher_status,
+            clipboard::list_clipboard_history,
+            clipboard::pin_clipboard_entry,
+            clipboard::delete_clipboard_entry,
+            clipboard::clear_clipboard_history,
+            clipboard::copy_clipboard_entry,
+            
+            // ============ I18N COMMANDS ============
+            i18n::get_locale_catalog,
+            i18n::list_locales,
+            i18n::set_locale,
+            
+            // ============ THEME COMMANDS ============
+            themes::list_all_themes,
+            themes::get_theme_by_id,
+            themes::export_theme_json,
+            themes::import_theme_json,
+            themes::add_theme,
+            themes::validate_theme_contrast,
+            
+            // ============ DOCTOR COMMANDS ============
+            doctor::run_doctor,
+            installer::install_missing_tool,
+            installer::get_tool_install_command,
+            
+            // ============ SETUP COMMANDS ============
+            setup::get_setup_status,
+            setup::detect_setup_hardware,
+            setup::start_setup_model_pull,
+            setup::create_default_project_directory,
+            setup::complete_setup,
+            
+            // ============ HARDWARE COMMANDS ============
+            hardware::get_hardware_profile,
+            hardware::get_recommended_llm_settings,
+            
+            // ============ NETWORK COMMANDS ============
+            network::test_network_settings,
+            
+            // ============ CHAT COMMANDS ============
+            agent::chat::create_chat_session,
+            agent::chat::list_chat_sessions,
+            agent::chat::get_chat_session,
+            agent::chat::list_chat_branches,
+            agent::chat::edit_chat_message,
+            agent::chat::delete_chat_message,
+            agent::chat::pin_chat_message,
+            agent::chat::list_pinned_chat_messages,
+            agent::chat::delete_chat_session,
+            agent::chat::export_chat_session,
+            agent::compaction::get_session_summary,
+            agent::memory::list_agent_memories,
+            agent::memory::add_agent_memory,
+            agent::memory::update_agent_memory,
+            agent::memory::delete_agent_memory,
+            agent::memory::list_memory_proposals,
+            agent::memory::confirm_memory_proposal,
+            agent::memory::dismiss_memory_proposal,
+            agent::mockup::generate_ui_from_mockup,
+            agent::mockup::generate_ui_from_figma,
+            
+            // ============ PERSONA COMMANDS ============
+            agent::personas::list_personas,
+            agent::personas::clone_persona,
+            agent::personas::save_persona,
+            agent::personas::delete_persona,
+            agent::personas::set_session_persona,
+            
+            // ============ DEBUGGING COMMANDS ============
+            debugging::create_debug_session,
+            debugging::start_debug,
+            debugging::pause_debug,
+            debugging::stop_debug,
+            debugging::continue_debug,
+            debugging::update_debug_state,
+            debugging::debug_step_over,
+            debugging::debug_step_into,
+            debugging::debug_step_out,
+            debugging::add_debug_breakpoint,
+            debugging::remove_debug_breakpoint,
+            debugging::toggle_debug_breakpoint,
+            debugging::list_debug_breakpoints,
+            debugging::get_debug_variables,
+            debugging::evaluate_debug_expression,
+            debugging::get_debug_configurations,
+            debugging::add_debug_configuration,
+            debugging::get_default_debug_configs,
+            agent::debug_assistant::ask_ai_about_debug_state,
+            
+            // ============ PROFILER COMMANDS ============
+            profiler::get_startup_report,
+            profiler::report_first_paint,
+            
+            // ============ DOCS CACHE COMMANDS ============
+            docs_cache::list_available_docsets,
+            docs_cache::list_cached_docsets,
+            docs_cache::download_docs,
+            docs_cache::remove_cached_docset,
+            docs_cache::search_docs,
+            docs_cache::get_doc_entry,
+            
+            // ============ DEVCONTAINER COMMANDS ============
+            devcontainer::generate_devcontainer,
+            devcontainer::detect_devcontainer,
+            devcontainer::launch_devcontainer,
+            
+            // ============ TASK COMMANDS ============
+            tasks::list_tasks,
+            tasks::save_tasks_config,
+            tasks::run_project_task,
+            tasks::stop_project_task,
+            tasks::list_running_tasks,
+            
+            // ============ DIAGNOSTICS COMMANDS ============
+            diagnostics::get_diagnostics,
+            diagnostics::clear_diagnostics,
+            terminal::problem_matchers::list_builtin_problem_matchers,
+            terminal::problem_matchers::parse_problems,
+            
+            // ============ SPELLCHECK COMMANDS ============
+            spellcheck::check_spelling,
+            spellcheck::add_to_dictionary,
+            spellcheck::list_spellcheck_languages,
+            
+            // ============ TASK BOARD COMMANDS ============
+            board::get_task_board,
+            board::add_board_column,
+            board::update_board_column,
+            board::delete_board_column,
+            board::create_board_card,
+            board::update_board_card,
+            board::move_board_card,
+            board::delete_board_card,
+            board::scan_project_todos,
+            board::import_todos_to_board,
+            
+            // ============ PROJECT HEALTH COMMANDS ============
+            health::generate_project_health_report,
+            health::list_project_health_reports,
+            
+            // ============ PROMPT EXPERIMENT COMMANDS ============
+            experiments::run_prompt_experiment,
+            experiments::record_experiment_preference,
+            experiments::list_prompt_experiments,
+            experiments::delete_prompt_experiment,
+            experiments::get_experiment_model_stats,
+            
+            // ============ SPEECH INPUT COMMANDS ============
+            speech::get_speech_status,
+            speech::download_whisper_model,
+            speech::start_speech_input,
+            speech::stop_speech_input,
+            speech::cancel_speech_input,
+            
+            // ============ TEXT TO SPEECH COMMANDS ============
+            tts::speak_text,
+            tts::pause_speech,
+            tts::resume_speech,
+            tts::stop_speech,
+            tts::get_speech_session,
+            tts::list_tts_voices,
+            
+            // ============ FIGMA COMMANDS ============
+            figma::connect_figma,
+            figma::disconnect_figma,
+            figma::get_figma_account,
+            figma::import_figma_layout,
+            
+            // ============ PACKAGE MANAGER COMMANDS ============
+            package_manager::detect_package_manager,
+            package_manager::install_project_packages,
+            
+            // ============ PYTHON ENVIRONMENT COMMANDS ============
+            python_envs::list_python_interpreters,
+            python_envs::get_python_env,
+            python_envs::select_python_interpreter,
+            python_envs::create_python_venv,
+            python_envs::install_python_requirements,
+            
+            // ============ NODE VERSION COMMANDS ============
+            node_versions::check_node_version,
+            node_versions::install_project_node_version,
+            
+            // ============ RUST TOOLCHAIN COMMANDS ============
+            rust_toolchain::get_rust_workspace,
+            rust_toolchain::run_cargo_check,
+            rust_toolchain::add_cargo_dependency,
+            
+            // ============ PROJECT PROFILE COMMANDS ============
+            project_profile::get_project_profile,
+            project_profile::refresh_project_profile,
+            
+            // ============ READ-ONLY PATH COMMANDS ============
+            readonly::set_path_readonly,
+            readonly::list_readonly_paths,
+            
+            // ============ FILE LOCK COMMANDS ============
+            file_locks::get_lock_status,
+            file_locks::acquire_file_lock,
+            file_locks::release_file_lock,
+            
+            // ============ BACKEND EDIT JOURNAL COMMANDS ============
+            edit_journal::list_backend_edits,
+            edit_journal::undo_backend_edit,
+            change_export::export_ai_changes,
+            change_export::list_edit_sessions,
+            
+            // ============ CHECKPOINT COMMANDS ============
+            checkpoints::create_checkpoint,
+            checkpoints::list_checkpoints,
+            checkpoints::restore_checkpoint,
+            
+            // ============ ACTIVITY DIGEST COMMANDS ============
+            digest::generate_activity_digest,
+            digest::get_digest_config,
+            digest::save_digest_config,
+            
+            // ============ TEAM PACK COMMANDS ============
+            team_pack::export_team_pack,
+            team_pack::inspect_team_pack,
+            team_pack::import_team_pack,
+            
+            // ============ ANNOTATION SYNC COMMANDS ============
+            annotation_sync::configure_annotation_sync,
+            annotation_sync::sync_annotations,
+            annotation_sync::get_annotation_sync_status,
+            annotation_sync::resolve_annotation_conflict,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
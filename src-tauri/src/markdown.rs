@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag};
+use std::path::Path;
+use std::sync::OnceLock;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+
+const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkdownRenderOptions {
+    /// Directory relative image paths are resolved against
+    pub base_path: Option<String>,
+    #[serde(default)]
+    pub mdx: bool,
+    #[serde(default = "default_true")]
+    pub highlight: bool,
+    pub theme: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for MarkdownRenderOptions {
+    fn default() -> Self {
+        Self {
+            base_path: None,
+            mdx: false,
+            highlight: true,
+            theme: None,
+        }
+    }
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Same encoding as `encodeURIComponent`, which the frontend's convertFileSrc uses
+fn encode_uri_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9'
+            | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Map a local file to a URL the webview can load through the asset protocol
+pub fn asset_url(path: &Path) -> String {
+    let encoded = encode_uri_component(&path.to_string_lossy());
+    if cfg!(target_os = "windows") {
+        format!("http://asset.localhost/{}", encoded)
+    } else {
+        format!("asset://localhost/{}", encoded)
+    }
+}
+
+fn resolve_asset(dest: &str, base_path: Option<&str>) -> Option<String> {
+    let is_remote = dest.contains("://") || dest.starts_with("data:") || dest.starts_with('#');
+    if is_remote || dest.is_empty() {
+        return None;
+    }
+
+    let path = Path::new(dest);
+    let resolved = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        Path::new(base_path?).join(path)
+    };
+
+    Some(asset_url(&resolved))
+}
+
+/// Drop MDX module syntax; JSX components fall through as HTML and get sanitized
+fn strip_mdx(content: &str) -> String {
+    let mut in_fence = false;
+
+    content
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+            }
+            in_fence || !(line.starts_with("import ") || line.starts_with("export "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn highlight_code(code: &str, lang: &str, theme: &str) -> String {
+    let syntaxes = syntax_set();
+    let syntax = syntaxes
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+
+    let themes = theme_set();
+    let theme = themes.themes.get(theme)
+        .unwrap_or_else(|| &themes.themes[DEFAULT_THEME]);
+
+    syntect::html::highlighted_html_for_string(code, syntaxes, syntax, theme)
+        .unwrap_or_else(|_| format!("<pre><code>{}</code></pre>", html_escape(code)))
+}
+
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn sanitize(html: &str) -> String {
+    ammonia::Builder::default()
+        .add_url_schemes(&["asset"])
+        .add_tag_attributes("code", &["class"])
+        .clean(html)
+        .to_string()
+}
+
+pub fn render(content: &str, options: &MarkdownRenderOptions) -> Result<String> {
+    let source = if options.mdx { strip_mdx(content) } else { content.to_string() };
+    let theme = options.theme.clone().unwrap_or_else(|| DEFAULT_THEME.to_string());
+
+    let mut parser_options = Options::empty();
+    parser_options.insert(Options::ENABLE_TABLES);
+    parser_options.insert(Options::ENABLE_STRIKETHROUGH);
+    parser_options.insert(Options::ENABLE_TASKLISTS);
+    parser_options.insert(Options::ENABLE_FOOTNOTES);
+
+    let mut events = Vec::new();
+    let mut code_block: Option<(String, String)> = None; // (language, code)
+    // Highlighted blocks carry inline colors, which user HTML may not, so they stand in
+    // as placeholders while sanitizing; they're generated from escaped code
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    let placeholder = |index: usize| format!("luciai-code-{}-{}-", nonce, index);
+    let mut highlighted = Vec::new();
+
+    for event in Parser::new_ext(&source, parser_options) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) if options.highlight => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) => lang.split_whitespace().next().unwrap_or("").to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((lang, String::new()));
+            }
+            Event::Text(text) if code_block.is_some() => {
+                if let Some((_, code)) = code_block.as_mut() {
+                    code.push_str(&text);
+                }
+            }
+            Event::End(Tag::CodeBlock(_)) if code_block.is_some() => {
+                let (lang, code) = code_block.take().unwrap_or_default();
+                events.push(Event::Html(CowStr::from(format!("{}\n", placeholder(highlighted.len())))));
+                highlighted.push(highlight_code(&code, &lang, &theme));
+            }
+            Event::Start(Tag::Image(link_type, dest, title)) => {
+                let dest = resolve_asset(&dest, options.base_path.as_deref())
+                    .map(CowStr::from)
+                    .unwrap_or(dest);
+                events.push(Event::Start(Tag::Image(link_type, dest, title)));
+            }
+            other => events.push(other),
+        }
+    }
+
+    let mut output = String::new();
+    html::push_html(&mut output, events.into_iter());
+
+    let mut output = sanitize(&output);
+    for (index, block) in highlighted.iter().enumerate() {
+        output = output.replacen(&placeholder(index), block, 1);
+    }
+    Ok(output)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn render_markdown(content: String, options: Option<MarkdownRenderOptions>) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || render(&content, &options))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_highlight_themes() -> Result<Vec<String>, String> {
+    Ok(theme_set().themes.keys().cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_are_sanitized() {
+        let html = render("Hello <script>alert(1)</script> **world**", &MarkdownRenderOptions::default()).unwrap();
+        assert!(!html.contains("<script"));
+        assert!(html.contains("<strong>world</strong>"));
+
+        // Inline styles only survive on highlighted code
+        let html = render("<span style=\"position:fixed\">x</span>\n\n```rust\nfn main() {}\n```", &MarkdownRenderOptions::default()).unwrap();
+        assert!(!html.contains("position:fixed"));
+        assert!(html.contains("<pre style="));
+        assert!(!html.contains("luciai-code-"));
+    }
+
+    #[test]
+    fn test_relative_images_use_asset_protocol() {
+        let options = MarkdownRenderOptions {
+            base_path: Some("/docs".to_string()),
+            ..Default::default()
+        };
+
+        let html = render("![logo](img/logo.png) ![remote](https://example.com/a.png)", &options).unwrap();
+        assert!(html.contains(&asset_url(Path::new("/docs/img/logo.png"))));
+        assert!(html.contains("https://example.com/a.png"));
+    }
+
+    #[test]
+    fn test_strip_mdx_keeps_code_fences() {
+        let source = "import Chart from './Chart'\n# Title\n```js\nimport x from 'y'\n```";
+        let stripped = strip_mdx(source);
+        assert!(!stripped.contains("./Chart"));
+        assert!(stripped.contains("import x from 'y'"));
+    }
+}
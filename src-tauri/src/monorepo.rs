@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_edit::DocumentMut;
+
+use crate::filesystem::collect_source_files;
+use crate::package_manager;
+use crate::testing::{TestResult, TestRunner};
+
+// Upper bound on the source text returned for AI context
+const MAX_CONTEXT_CHARS: usize = 60_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum WorkspaceKind {
+    Pnpm,
+    NpmWorkspaces,
+    Cargo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceInfo {
+    pub root: String,
+    pub kinds: Vec<WorkspaceKind>,
+    /// Turborepo runs on top of a pnpm/npm workspace
+    pub turborepo: bool,
+    pub packages: Vec<WorkspacePackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspacePackage {
+    pub name: String,
+    pub version: Option<String>,
+    /// Path relative to the workspace root
+    pub path: String,
+    pub kind: WorkspaceKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageCommandOutput {
+    pub command: String,
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageContext {
+    pub package: String,
+    pub files: Vec<String>,
+    pub context: String,
+    pub truncated: bool,
+}
+
+/// Expand workspace globs like `packages/*`, `apps/**` or `crates/core`.
+/// Only trailing wildcards are supported, which covers the common layouts.
+fn expand_patterns(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let excluded: HashSet<PathBuf> = patterns
+        .iter()
+        .filter_map(|p| p.strip_prefix('!'))
+        .map(|p| root.join(p.trim_end_matches('/')))
+        .collect();
+
+    let mut dirs = Vec::new();
+
+    for pattern in patterns.iter().filter(|p| !p.starts_with('!')) {
+        let pattern = pattern.trim_end_matches('/');
+
+        if let Some(base) = pattern.strip_suffix("/**").or_else(|| pattern.strip_suffix("/*")) {
+            let recursive = pattern.ends_with("/**");
+            let mut stack = vec![root.join(base)];
+
+            while let Some(dir) = stack.pop() {
+                let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if !path.is_dir() || name.starts_with('.') || name == "node_modules" {
+                        continue;
+                    }
+                    dirs.push(path.clone());
+                    if recursive {
+                        stack.push(path);
+                    }
+                }
+            }
+        } else {
+            dirs.push(root.join(pattern));
+        }
+    }
+
+    dirs.retain(|d| !excluded.contains(d));
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// `packages:` entries from pnpm-workspace.yaml
+fn pnpm_patterns(content: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with(' ') && !line.starts_with('-') && !trimmed.is_empty() {
+            in_packages = trimmed.starts_with("packages:");
+            continue;
+        }
+        if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                patterns.push(item.trim().trim_matches(|c| c == '\'' || c == '"').to_string());
+            }
+        }
+    }
+
+    patterns
+}
+
+/// `workspaces` from package.json, either an array or `{ "packages": [...] }`
+fn npm_patterns(package_json: &serde_json::Value) -> Vec<String> {
+    let workspaces = &package_json["workspaces"];
+    let list = workspaces.as_array().or_else(|| workspaces["packages"].as_array());
+
+    list.map(|items| items.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+/// `members` from the `[workspace]` table of Cargo.toml
+fn cargo_patterns(content: &str) -> Vec<String> {
+    content
+        .parse::<DocumentMut>()
+        .ok()
+        .and_then(|doc| {
+            let members = doc.get("workspace")?.get("members")?.as_array()?;
+            Some(members.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        })
+        .unwrap_or_default()
+}
+
+fn cargo_package_field(doc: &DocumentMut, field: &str) -> Option<String> {
+    doc.get("package")?.get(field)?.as_str().map(String::from)
+}
+
+fn read_node_package(root: &Path, dir: &Path) -> Option<WorkspacePackage> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+
+    Some(WorkspacePackage {
+        name: json["name"].as_str()?.to_string(),
+        version: json["version"].as_str().map(String::from),
+        path: dir.strip_prefix(root).unwrap_or(dir).to_string_lossy().to_string(),
+        kind: WorkspaceKind::NpmWorkspaces,
+    })
+}
+
+fn read_cargo_package(root: &Path, dir: &Path) -> Option<WorkspacePackage> {
+    let doc: DocumentMut = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?.parse().ok()?;
+
+    Some(WorkspacePackage {
+        name: cargo_package_field(&doc, "name")?,
+        version: cargo_package_field(&doc, "version"),
+        path: dir.strip_prefix(root).unwrap_or(dir).to_string_lossy().to_string(),
+        kind: WorkspaceKind::Cargo,
+    })
+}
+
+pub fn detect_workspace(root: &Path) -> Result<WorkspaceInfo> {
+    let mut info = WorkspaceInfo {
+        root: root.to_string_lossy().to_string(),
+        kinds: Vec::new(),
+        turborepo: root.join("turbo.json").exists(),
+        packages: Vec::new(),
+    };
+
+    let pnpm_file = root.join("pnpm-workspace.yaml");
+    let node_patterns = if pnpm_file.exists() {
+        info.kinds.push(WorkspaceKind::Pnpm);
+        pnpm_patterns(&std::fs::read_to_string(&pnpm_file)?)
+    } else {
+        let patterns = std::fs::read_to_string(root.join("package.json"))
+            .ok()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+            .map(|json| npm_patterns(&json))
+            .unwrap_or_default();
+        if !patterns.is_empty() {
+            info.kinds.push(WorkspaceKind::NpmWorkspaces);
+        }
+        patterns
+    };
+
+    for dir in expand_patterns(root, &node_patterns) {
+        if let Some(mut package) = read_node_package(root, &dir) {
+            if info.kinds.contains(&WorkspaceKind::Pnpm) {
+                package.kind = WorkspaceKind::Pnpm;
+            }
+            info.packages.push(package);
+        }
+    }
+
+    let cargo_members = std::fs::read_to_string(root.join("Cargo.toml"))
+        .map(|c| cargo_patterns(&c))
+        .unwrap_or_default();
+    if !cargo_members.is_empty() {
+        info.kinds.push(WorkspaceKind::Cargo);
+        for dir in expand_patterns(root, &cargo_members) {
+            if let Some(package) = read_cargo_package(root, &dir) {
+                info.packages.push(package);
+            }
+        }
+    }
+
+    info.packages.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(info)
+}
+
+fn find_package(root: &Path, package: &str) -> Result<WorkspacePackage> {
+    detect_workspace(root)?
+        .packages
+        .into_iter()
+        .find(|p| p.name == package || p.path == package)
+        .with_context(|| format!("Package '{}' is not part of the workspace", package))
+}
+
+fn run(dir: &Path, program: &str, args: &[&str]) -> Result<PackageCommandOutput> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+
+    Ok(PackageCommandOutput {
+        command: format!("{} {}", program, args.join(" ")),
+        success: output.status.success(),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
+
+/// Build one package. Turborepo builds go through `turbo` so dependent packages build first.
+pub fn build_package(root: &Path, package: &WorkspacePackage) -> Result<PackageCommandOutput> {
+    match package.kind {
+        WorkspaceKind::Cargo => run(root, "cargo", &["build", "-p", &package.name]),
+        _ if root.join("turbo.json").exists() => {
+            let args = ["run".to_string(), "build".to_string(), format!("--filter={}", package.name)];
+            let turbo = package_manager::for_project(root).exec("turbo", &args);
+            run(root, turbo.program, &turbo.arg_refs())
+        }
+        _ => {
+            let build = package_manager::for_project(root).run("build", &[]);
+            run(&root.join(&package.path), build.program, &build.arg_refs())
+        }
+    }
+}
+
+/// Audit only the dependencies a package actually uses
+pub fn audit_package(root: &Path, package: &WorkspacePackage) -> Result<PackageCommandOutput> {
+    match package.kind {
+        WorkspaceKind::Pnpm => {
+            let filter = format!("--filter={}", package.name);
+            run(root, "pnpm", &["audit", "--json", &filter])
+        }
+        WorkspaceKind::NpmWorkspaces => {
+            let workspace = format!("--workspace={}", package.name);
+            run(root, "npm", &["audit", "--json", &workspace])
+        }
+        WorkspaceKind::Cargo => {
+            // cargo-audit reads the shared lockfile, so narrow the report to this crate's dependency tree
+            let tree = run(root, "cargo", &["tree", "-p", &package.name, "--prefix", "none", "-e", "normal,build"])?;
+            let used: HashSet<&str> = tree.stdout.lines().filter_map(|l| l.split_whitespace().next()).collect();
+
+            let mut audit = run(root, "cargo", &["audit", "--json"])?;
+            if let Ok(mut report) = serde_json::from_str::<serde_json::Value>(&audit.stdout) {
+                if let Some(list) = report["vulnerabilities"]["list"].as_array_mut() {
+                    list.retain(|v| v["package"]["name"].as_str().map(|n| used.contains(n)).unwrap_or(false));
+                    let found = list.len();
+                    report["vulnerabilities"]["count"] = serde_json::json!(found);
+                    report["vulnerabilities"]["found"] = serde_json::json!(found > 0);
+                    audit.success = found == 0;
+                }
+                audit.stdout = serde_json::to_string_pretty(&report)?;
+            }
+            Ok(audit)
+        }
+    }
+}
+
+/// Concatenated sources of one package, for prompts that should not see the whole repo
+pub fn package_context(root: &Path, package: &WorkspacePackage, max_chars: usize) -> PackageContext {
+    let dir = root.join(&package.path);
+    let extensions = ["rs", "ts", "tsx", "js", "jsx", "py", "go", "json", "toml", "md"];
+
+    let mut context = String::new();
+    let mut files = Vec::new();
+    let mut truncated = false;
+
+    for file in collect_source_files(&dir, &extensions) {
+        let Ok(content) = std::fs::read_to_string(&file) else { continue };
+        let relative = file.strip_prefix(root).unwrap_or(&file).to_string_lossy().to_string();
+
+        // Lockfiles are large and carry no useful context
+        if relative.ends_with("lock.json") {
+            continue;
+        }
+
+        let content = crate::agent::injection::sanitize(&content, &relative).text;
+        let section = format!("// File: {}\n{}\n\n", relative, content);
+        if context.len() + section.len() > max_chars {
+            truncated = true;
+            break;
+        }
+
+        context.push_str(&section);
+        files.push(relative);
+    }
+
+    PackageContext {
+        package: package.name.clone(),
+        files,
+        context,
+        truncated,
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_workspace_packages(root: String) -> Result<WorkspaceInfo, String> {
+    detect_workspace(Path::new(&root)).map_err(|e| e.to_string())
+}
+
+/// Run a single package's tests with the framework detected in that package
+#[tauri::command]
+pub async fn run_package_tests(root: String, package: String) -> Result<TestResult, String> {
+    let root = PathBuf::from(root);
+    let package = find_package(&root, &package).map_err(|e| e.to_string())?;
+    let dir = root.join(&package.path);
+
+    let framework = TestRunner::detect_framework(&dir).map_err(|e| e.to_string())?;
+    TestRunner::new(framework, dir).run_tests().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn build_workspace_package(root: String, package: String) -> Result<PackageCommandOutput, String> {
+    tokio::task::spawn_blocking(move || {
+        let root = PathBuf::from(root);
+        let package = find_package(&root, &package)?;
+        build_package(&root, &package)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn audit_workspace_package(root: String, package: String) -> Result<PackageCommandOutput, String> {
+    tokio::task::spawn_blocking(move || {
+        let root = PathBuf::from(root);
+        let package = find_package(&root, &package)?;
+        audit_package(&root, &package)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_package_context(
+    root: String,
+    package: String,
+    max_chars: Option<usize>,
+) -> Result<PackageContext, String> {
+    let root = PathBuf::from(root);
+    let package = find_package(&root, &package).map_err(|e| e.to_string())?;
+    Ok(package_context(&root, &package, max_chars.unwrap_or(MAX_CONTEXT_CHARS)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_pnpm_and_cargo_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        std::fs::write(root.join("pnpm-workspace.yaml"), "packages:\n  - 'apps/*'\n  - \"!apps/legacy\"\n").unwrap();
+        for (name, path) in [("web", "apps/web"), ("legacy", "apps/legacy")] {
+            std::fs::create_dir_all(root.join(path)).unwrap();
+            std::fs::write(root.join(path).join("package.json"), format!(r#"{{"name": "{}", "version": "1.0.0"}}"#, name)).unwrap();
+        }
+
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\nresolver = \"2\"\nexclude = [\"vendor\"]\nmembers = [\"crates/core\"]\n").unwrap();
+        std::fs::create_dir_all(root.join("crates/core")).unwrap();
+        std::fs::write(root.join("crates/core/Cargo.toml"), "[package]\nauthors = [\"a\"]\nname = \"core\"\nversion = \"0.1.0\"\n").unwrap();
+
+        let info = detect_workspace(root).unwrap();
+        assert_eq!(info.kinds, vec![WorkspaceKind::Pnpm, WorkspaceKind::Cargo]);
+
+        let names: Vec<_> = info.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["web", "core"]);
+    }
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use reqwest::{Certificate, Client, ClientBuilder, NoProxy, Proxy};
+use std::time::{Duration, Instant};
+
+use crate::preferences::{NetworkPreferences, PreferencesManager, ProxyMode, PROXY_PASSWORD_KEY};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionTest {
+    pub url: String,
+    pub success: bool,
+    pub status: Option<u16>,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+fn current_settings() -> NetworkPreferences {
+    PreferencesManager::new()
+        .and_then(|manager| manager.load())
+        .map(|prefs| prefs.network)
+        .unwrap_or_default()
+}
+
+/// Split a PEM bundle into individual certificates; reqwest only parses one at a time
+fn load_ca_bundle(path: &str) -> Result<Vec<Certificate>> {
+    let pem = std::fs::read_to_string(path).with_context(|| format!("Failed to read CA bundle {}", path))?;
+    const END: &str = "-----END CERTIFICATE-----";
+
+    let mut certificates = Vec::new();
+    let mut rest = pem.as_str();
+    while let Some(start) = rest.find("-----BEGIN CERTIFICATE-----") {
+        let end = rest[start..].find(END).context("Unterminated certificate in CA bundle")? + start + END.len();
+        let certificate = Certificate::from_pem(rest[start..end].as_bytes())
+            .with_context(|| format!("Invalid certificate in {}", path))?;
+        certificates.push(certificate);
+        rest = &rest[end..];
+    }
+
+    if certificates.is_empty() {
+        anyhow::bail!("No certificates found in {}", path);
+    }
+    Ok(certificates)
+}
+
+/// The password typed into unsaved settings, else the one in the credential store
+fn proxy_password(settings: &NetworkPreferences) -> Result<String> {
+    if !settings.proxy_password.is_empty() || !settings.has_proxy_password {
+        return Ok(settings.proxy_password.clone());
+    }
+    Ok(crate::secrets::get_secret(PROXY_PASSWORD_KEY)?.unwrap_or_default())
+}
+
+/// Percent-encode everything but unreserved characters, for the user info part of a URL
+fn encode_userinfo(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn manual_proxy(settings: &NetworkPreferences, url: &str, https: bool) -> Result<Proxy> {
+    let proxy = if https { Proxy::https(url) } else { Proxy::http(url) }
+        .with_context(|| format!("Invalid proxy URL {}", url))?;
+    let proxy = if settings.proxy_username.is_empty() {
+        proxy
+    } else {
+        proxy.basic_auth(&settings.proxy_username, &proxy_password(settings)?)
+    };
+    Ok(proxy.no_proxy(NoProxy::from_string(&settings.no_proxy)))
+}
+
+/// Apply proxy and CA settings to a builder, failing on anything misconfigured
+pub fn configure(mut builder: ClientBuilder, settings: &NetworkPreferences) -> Result<ClientBuilder> {
+    match settings.proxy_mode {
+        // reqwest reads the proxy environment variables by default
+        ProxyMode::System => {}
+        ProxyMode::None => builder = builder.no_proxy(),
+        ProxyMode::Manual => {
+            let https_proxy = if settings.https_proxy.is_empty() { &settings.http_proxy } else { &settings.https_proxy };
+            if settings.http_proxy.is_empty() && https_proxy.is_empty() {
+                anyhow::bail!("Manual proxy mode needs a proxy URL");
+            }
+            if !settings.http_proxy.is_empty() {
+                builder = builder.proxy(manual_proxy(settings, &settings.http_proxy, false)?);
+            }
+            if !https_proxy.is_empty() {
+                builder = builder.proxy(manual_proxy(settings, https_proxy, true)?);
+            }
+        }
+    }
+
+    if let Some(path) = settings.ca_bundle_path.as_deref().filter(|p| !p.is_empty()) {
+        for certificate in load_ca_bundle(path)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// Builder with the user's proxy and CA settings applied. Every HTTP client in
+/// the app should start here. Broken settings are logged and skipped so a typo
+/// in preferences can't take down features that don't need the network.
+pub fn client_builder() -> ClientBuilder {
+    let settings = current_settings();
+    match configure(Client::builder(), &settings) {
+        Ok(builder) => builder,
+        Err(e) => {
+            tracing::warn!("Ignoring invalid network settings: {:#}", e);
+            Client::builder()
+        }
+    }
+}
+
+pub fn client() -> Client {
+    client_builder().build().unwrap_or_else(|e| {
+        tracing::warn!("Failed to build HTTP client: {}", e);
+        Client::new()
+    })
+}
+
+/// Environment variables that pass the proxy and CA settings on to child
+/// processes such as npm, pip and curl
+pub fn proxy_env() -> Vec<(String, String)> {
+    let settings = current_settings();
+    let mut env = Vec::new();
+
+    if settings.proxy_mode == ProxyMode::Manual {
+        let password = proxy_password(&settings).unwrap_or_else(|e| {
+            tracing::warn!("Failed to read the proxy password: {:#}", e);
+            String::new()
+        });
+        let with_auth = |url: &str| match (settings.proxy_username.is_empty(), url.split_once("://")) {
+            (false, Some((scheme, host))) => format!(
+                "{}://{}:{}@{}",
+                scheme,
+                encode_userinfo(&settings.proxy_username),
+                encode_userinfo(&password),
+                host
+            ),
+            _ => url.to_string(),
+        };
+        let https_proxy = if settings.https_proxy.is_empty() { &settings.http_proxy } else { &settings.https_proxy };
+
+        for (name, value) in [("HTTP_PROXY", &settings.http_proxy), ("HTTPS_PROXY", https_proxy)] {
+            if !value.is_empty() {
+                env.push((name.to_string(), with_auth(value)));
+                env.push((name.to_lowercase(), with_auth(value)));
+            }
+        }
+        env.push(("NO_PROXY".to_string(), settings.no_proxy.clone()));
+        env.push(("no_proxy".to_string(), settings.no_proxy.clone()));
+    }
+
+    if let Some(path) = settings.ca_bundle_path.filter(|p| !p.is_empty()) {
+        for name in ["NODE_EXTRA_CA_CERTS", "REQUESTS_CA_BUNDLE", "PIP_CERT", "CURL_CA_BUNDLE", "SSL_CERT_FILE"] {
+            env.push((name.to_string(), path.clone()));
+        }
+    }
+
+    env
+}
+
+// Tauri commands
+
+/// Try a request with the given (unsaved) settings so the preferences UI can validate them
+#[tauri::command]
+pub async fn test_network_settings(settings: NetworkPreferences, url: Option<String>) -> Result<ConnectionTest, String> {
+    let url = url.unwrap_or_else(|| "https://www.google.com/generate_204".to_string());
+    let client = configure(Client::builder(), &settings)
+        .and_then(|builder| Ok(builder.timeout(Duration::from_secs(15)).build()?))
+        .map_err(|e| format!("{:#}", e))?;
+
+    let started = Instant::now();
+    let result = client.get(&url).send().await;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    Ok(match result {
+        Ok(response) => ConnectionTest {
+            url,
+            success: !response.status().is_server_error(),
+            status: Some(response.status().as_u16()),
+            elapsed_ms,
+            error: None,
+        },
+        Err(e) => ConnectionTest {
+            url,
+            success: false,
+            status: None,
+            elapsed_ms,
+            error: Some(e.to_string()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_proxy_requires_url() {
+        let mut settings = NetworkPreferences::default();
+        settings.proxy_mode = ProxyMode::Manual;
+        assert!(configure(Client::builder(), &settings).is_err());
+
+        settings.http_proxy = "http://proxy.example:8080".to_string();
+        settings.proxy_username = "user".to_string();
+        assert!(configure(Client::builder(), &settings).is_ok());
+
+        settings.ca_bundle_path = Some("/nonexistent/ca.pem".to_string());
+        assert!(configure(Client::builder(), &settings).is_err());
+
+        assert_eq!(encode_userinfo("DOMAIN\\j.doe"), "DOMAIN%5Cj.doe");
+        assert_eq!(encode_userinfo("p@ss:w/rd%"), "p%40ss%3Aw%2Frd%25");
+    }
+}
@@ -0,0 +1,307 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::preferences::PreferencesManager;
+use crate::templates::{pinning, runner};
+use crate::terminal::shell::find_in_path;
+
+/// LTS codenames `.nvmrc` files use, e.g. `lts/iron`
+const LTS_CODENAMES: &[(&str, u64)] = &[
+    ("argon", 4),
+    ("boron", 6),
+    ("carbon", 8),
+    ("dubnium", 10),
+    ("erbium", 12),
+    ("fermium", 14),
+    ("gallium", 16),
+    ("hydrogen", 18),
+    ("iron", 20),
+    ("jod", 22),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeRequirement {
+    /// As written: `20`, `v20.11.0`, `lts/iron` or an engines range such as `>=18 <21`
+    pub spec: String,
+    /// `.nvmrc`, `.node-version`, `volta` or `engines`
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionManager {
+    Fnm,
+    Nvm,
+    Volta,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeVersionCheck {
+    pub required: Option<NodeRequirement>,
+    /// `node --version` in the project, without the `v`
+    pub active: Option<String>,
+    /// None when there is no requirement or it can't be compared, e.g. `lts/*`
+    pub satisfied: Option<bool>,
+    /// Installed managers, preferred first
+    pub managers: Vec<VersionManager>,
+}
+
+impl VersionManager {
+    fn as_str(&self) -> &'static str {
+        match self {
+            VersionManager::Fnm => "fnm",
+            VersionManager::Nvm => "nvm",
+            VersionManager::Volta => "volta",
+        }
+    }
+}
+
+/// nvm is a shell function on macOS and Linux, sourced from `nvm.sh`
+fn nvm_script() -> Option<PathBuf> {
+    let dir = std::env::var_os("NVM_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".nvm")))?;
+    Some(dir.join("nvm.sh")).filter(|script| script.exists())
+}
+
+/// Volta pins per project and fnm switches per directory, so both beat nvm's shell-wide `use`
+pub fn installed_managers() -> Vec<VersionManager> {
+    let mut managers = Vec::new();
+    if find_in_path("volta").is_some() || find_in_path("volta.exe").is_some() {
+        managers.push(VersionManager::Volta);
+    }
+    if find_in_path("fnm").is_some() || find_in_path("fnm.exe").is_some() {
+        managers.push(VersionManager::Fnm);
+    }
+    // nvm-windows is a real executable
+    let nvm_windows = cfg!(target_os = "windows") && find_in_path("nvm.exe").is_some();
+    if nvm_windows || (!cfg!(target_os = "windows") && nvm_script().is_some()) {
+        managers.push(VersionManager::Nvm);
+    }
+    managers
+}
+
+/// What the project asks for. Version files win over package.json, as they do for the managers.
+pub fn required_version(project_path: &Path) -> Option<NodeRequirement> {
+    for file in [".nvmrc", ".node-version"] {
+        if let Ok(content) = std::fs::read_to_string(project_path.join(file)) {
+            // nvm allows comments after the version
+            let spec = content.lines().next().unwrap_or("").split('#').next().unwrap_or("").trim();
+            if !spec.is_empty() {
+                return Some(NodeRequirement { spec: spec.to_string(), source: file.to_string() });
+            }
+        }
+    }
+
+    let package: serde_json::Value = std::fs::read_to_string(project_path.join("package.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())?;
+    [("volta", &package["volta"]["node"]), ("engines", &package["engines"]["node"])]
+        .into_iter()
+        .find_map(|(source, value)| {
+            value.as_str().filter(|s| !s.trim().is_empty()).map(|spec| NodeRequirement {
+                spec: spec.trim().to_string(),
+                source: source.to_string(),
+            })
+        })
+}
+
+/// The spec as a range `pinning::satisfies` reads: `lts/iron` becomes `20`,
+/// `v20.11.0` stays exact. None for aliases that move, like `lts/*` or `node`.
+fn as_range(spec: &str) -> Option<String> {
+    let spec = spec.trim();
+    if let Some(codename) = spec.strip_prefix("lts/") {
+        let codename = codename.to_lowercase();
+        return LTS_CODENAMES.iter().find(|(name, _)| *name == codename).map(|(_, major)| major.to_string());
+    }
+    if matches!(spec, "node" | "stable" | "latest" | "current") {
+        return None;
+    }
+    Some(spec.trim_start_matches('v').to_string())
+}
+
+/// What to hand a manager's `install`: the spec itself when it names a version,
+/// else the newest LTS major an engines range allows
+pub fn install_spec(requirement: &NodeRequirement) -> Option<String> {
+    if requirement.source != "engines" {
+        return Some(requirement.spec.clone());
+    }
+    let range = as_range(&requirement.spec)?;
+    LTS_CODENAMES.iter().rev().map(|(_, major)| *major).find_map(|major| {
+        let fits = [format!("{}.0.0", major), format!("{}.999.0", major)]
+            .iter()
+            .any(|probe| pinning::satisfies(probe, &range) == Some(true));
+        fits.then(|| major.to_string())
+    })
+}
+
+/// How to switch on Node's permission model for a given `node`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PermissionModel {
+    /// `--experimental-permission` became `--permission` in 22.13 and 23.5
+    pub flag: &'static str,
+    /// Node 25 denies network access unless `--allow-net` is passed
+    pub restricts_network: bool,
+}
+
+pub fn permission_model_for(version: &str) -> Option<PermissionModel> {
+    let mut parts = version.trim().trim_start_matches('v').split('.').map(|p| p.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().flatten().unwrap_or(0);
+
+    let flag = match (major, minor) {
+        (0..=19, _) => return None,
+        (20 | 21, _) | (22, 0..=12) | (23, 0..=4) => "--experimental-permission",
+        _ => "--permission",
+    };
+    Some(PermissionModel { flag, restricts_network: major >= 25 })
+}
+
+/// The permission model of `node`. Fails when it has none, so callers never run
+/// confined code unconfined.
+pub fn permission_model(node: &Path) -> Result<PermissionModel> {
+    let output = Command::new(node).arg("--version").output().context("Failed to run node")?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    permission_model_for(&version)
+        .with_context(|| format!("Node {} has no permission model; Node 20 or newer is required", version))
+}
+
+pub fn active_version(project_path: &Path) -> Option<String> {
+    let output = runner::run("node", &["--version"], project_path).ok()?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().trim_start_matches('v').to_string();
+    Some(version).filter(|v| !v.is_empty())
+}
+
+pub fn check(project_path: &Path) -> NodeVersionCheck {
+    let required = required_version(project_path);
+    let active = active_version(project_path);
+    let satisfied = match (&required, &active) {
+        (Some(required), Some(active)) => as_range(&required.spec).and_then(|range| pinning::satisfies(active, &range)),
+        (Some(_), None) => Some(false),
+        _ => None,
+    };
+    NodeVersionCheck { required, active, satisfied, managers: installed_managers() }
+}
+
+fn missing_manager_error(check: &NodeVersionCheck, spec: &str) -> anyhow::Error {
+    let active = check.active.as_deref().map(|v| format!("Node {} is active", v)).unwrap_or_else(|| "Node is not installed".to_string());
+    let source = check.required.as_ref().map(|r| r.source.as_str()).unwrap_or("project");
+    anyhow::anyhow!(
+        "This project needs Node {} ({}), but {}. No version manager was found: install fnm \
+         (https://github.com/Schniz/fnm), nvm (https://github.com/nvm-sh/nvm) or Volta \
+         (https://volta.sh), or install Node {} yourself.",
+        spec,
+        source,
+        active,
+        spec
+    )
+}
+
+/// Install the required version with the first available manager
+pub fn install_required(project_path: &Path) -> Result<NodeVersionCheck> {
+    let current = check(project_path);
+    let requirement = current.required.as_ref().context("The project doesn't specify a Node version")?;
+    let spec = install_spec(requirement)
+        .with_context(|| format!("Can't pick a Node version to install for '{}'", requirement.spec))?;
+    let manager = *current.managers.first().ok_or_else(|| missing_manager_error(&current, &spec))?;
+
+    match manager {
+        VersionManager::Fnm => runner::run("fnm", &["install", &spec], project_path),
+        VersionManager::Volta => runner::run("volta", &["fetch", &format!("node@{}", spec)], project_path),
+        VersionManager::Nvm if cfg!(target_os = "windows") => runner::run("nvm", &["install", &spec], project_path),
+        VersionManager::Nvm => {
+            let script = format!(". \"$NVM_DIR/nvm.sh\" && nvm install {}", spec);
+            runner::run("bash", &["-c", &script], project_path)
+        }
+    }
+    .map_err(|e| anyhow::anyhow!(e))?;
+
+    tracing::info!("Installed Node {} with {}", spec, manager.as_str());
+    Ok(check(project_path))
+}
+
+/// Wrap a command line so it runs on the project's Node version. Unchanged when the active
+/// version already fits or the project doesn't say; an error when it doesn't fit and no
+/// manager can switch.
+pub fn wrap_command(project_path: &Path, command_line: &str) -> Result<String> {
+    let current = check(project_path);
+    let (Some(requirement), Some(false)) = (&current.required, current.satisfied) else {
+        return Ok(command_line.to_string());
+    };
+    let spec = install_spec(requirement).unwrap_or_else(|| requirement.spec.clone());
+    let manager = current
+        .managers
+        .iter()
+        // nvm-windows switches the whole machine and can't run a single command
+        .find(|m| !(cfg!(target_os = "windows") && **m == VersionManager::Nvm))
+        .ok_or_else(|| missing_manager_error(&current, &spec))?;
+
+    Ok(match manager {
+        VersionManager::Fnm => format!("fnm exec --using={} -- {}", spec, command_line),
+        VersionManager::Volta => format!("volta run --node {} {}", spec, command_line),
+        VersionManager::Nvm => format!(". \"$NVM_DIR/nvm.sh\" && nvm exec {} {}", spec, command_line),
+    })
+}
+
+/// `wrap_command` when the user turned on switching, else the command as it is
+pub fn prepare_command(project_path: &Path, command_line: &str) -> Result<String> {
+    let switch = PreferencesManager::new()
+        .and_then(|manager| manager.load())
+        .map(|prefs| prefs.projects.switch_node_version)
+        .unwrap_or(false);
+    if switch && project_path.join("package.json").exists() {
+        wrap_command(project_path, command_line)
+    } else {
+        Ok(command_line.to_string())
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn check_node_version(project_path: String) -> Result<NodeVersionCheck, String> {
+    tokio::task::spawn_blocking(move || check(Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn install_project_node_version(project_path: String) -> Result<NodeVersionCheck, String> {
+    tokio::task::spawn_blocking(move || install_required(Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_version() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(project.path().join("package.json"), r#"{"engines":{"node":">=18 <21"}}"#).unwrap();
+        let requirement = required_version(project.path()).unwrap();
+        assert_eq!((requirement.spec.as_str(), requirement.source.as_str()), (">=18 <21", "engines"));
+        assert_eq!(install_spec(&requirement).as_deref(), Some("20"));
+
+        std::fs::write(project.path().join(".nvmrc"), "lts/iron # team default\n").unwrap();
+        let requirement = required_version(project.path()).unwrap();
+        assert_eq!((requirement.spec.as_str(), requirement.source.as_str()), ("lts/iron", ".nvmrc"));
+        assert_eq!(as_range(&requirement.spec).as_deref(), Some("20"));
+        assert_eq!(pinning::satisfies("20.11.0", &as_range("v20.11.0").unwrap()), Some(true));
+        assert_eq!(as_range("lts/*"), None);
+    }
+
+    #[test]
+    fn test_permission_model_flags() {
+        assert_eq!(permission_model_for("v18.19.0"), None);
+        assert_eq!(permission_model_for("v20.11.0").unwrap().flag, "--experimental-permission");
+        assert_eq!(permission_model_for("v22.12.0").unwrap().flag, "--experimental-permission");
+        assert_eq!(permission_model_for("v22.13.0").unwrap().flag, "--permission");
+        assert!(!permission_model_for("v24.1.0").unwrap().restricts_network);
+        assert!(permission_model_for("v25.0.0").unwrap().restricts_network);
+    }
+}
@@ -0,0 +1,502 @@
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use anyhow::{Result, Context};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::terminal::shell::find_in_path;
+
+const KERNEL_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+const CELL_TIMEOUT: Duration = Duration::from_secs(600);
+
+// Talks to the kernel over ZeroMQ through jupyter_client and relays iopub
+// messages as JSON lines. Outputs are already in nbformat shape, with images
+// base64 encoded, so they can be written straight back into the .ipynb.
+const KERNEL_DRIVER: &str = r#"import sys, json, queue, threading
+from jupyter_client.manager import KernelManager
+
+def emit(obj):
+    sys.stdout.write(json.dumps(obj) + "\n")
+    sys.stdout.flush()
+
+km = KernelManager(kernel_name=sys.argv[1])
+try:
+    km.start_kernel(cwd=sys.argv[2])
+    kc = km.client()
+    kc.start_channels()
+    kc.wait_for_ready(timeout=60)
+except Exception as e:
+    emit({"type": "failed", "error": str(e)})
+    sys.exit(1)
+
+emit({"type": "ready"})
+requests = queue.Queue()
+
+def read_stdin():
+    for line in sys.stdin:
+        try:
+            req = json.loads(line)
+        except Exception:
+            continue
+        if req.get("op") == "interrupt":
+            km.interrupt_kernel()
+        else:
+            requests.put(req)
+    requests.put({"op": "shutdown"})
+
+threading.Thread(target=read_stdin, daemon=True).start()
+
+while True:
+    req = requests.get()
+    if req["op"] == "shutdown":
+        break
+    cell_id = req["cell_id"]
+    msg_id = kc.execute(req["code"])
+    count = None
+    while True:
+        msg = kc.get_iopub_msg()
+        if msg["parent_header"].get("msg_id") != msg_id:
+            continue
+        kind, content = msg["msg_type"], msg["content"]
+        output = None
+        if kind == "execute_input":
+            count = content.get("execution_count")
+        elif kind == "stream":
+            output = {"output_type": "stream", "name": content["name"], "text": content["text"]}
+        elif kind in ("execute_result", "display_data"):
+            output = {"output_type": kind, "data": content.get("data", {}), "metadata": content.get("metadata", {})}
+            if kind == "execute_result":
+                output["execution_count"] = content.get("execution_count")
+        elif kind == "error":
+            output = {"output_type": "error", "ename": content["ename"], "evalue": content["evalue"], "traceback": content["traceback"]}
+        elif kind == "status" and content["execution_state"] == "idle":
+            break
+        if output is not None:
+            emit({"type": "output", "cell_id": cell_id, "output": output})
+    emit({"type": "done", "cell_id": cell_id, "execution_count": count})
+
+kc.stop_channels()
+km.shutdown_kernel(now=True)
+"#;
+
+/// nbformat v4 notebook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notebook {
+    pub cells: Vec<NotebookCell>,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+    pub nbformat: u32,
+    pub nbformat_minor: u32,
+}
+
+/// Serialized by hand: nbformat requires `outputs` and `execution_count` (possibly
+/// null) on code cells and forbids them on other cells
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotebookCell {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub cell_type: String, // "code", "markdown" or "raw"
+    #[serde(deserialize_with = "multiline_text")]
+    pub source: String,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+    #[serde(default)]
+    pub outputs: Option<Vec<serde_json::Value>>,
+    #[serde(default)]
+    pub execution_count: Option<u32>,
+    /// Fields this type doesn't model, such as `attachments`, kept as they were
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Serialize for NotebookCell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(id) = &self.id {
+            map.serialize_entry("id", id)?;
+        }
+        map.serialize_entry("cell_type", &self.cell_type)?;
+        map.serialize_entry("source", &self.source)?;
+        map.serialize_entry("metadata", &self.metadata)?;
+        if self.cell_type == "code" {
+            map.serialize_entry("outputs", self.outputs.as_deref().unwrap_or_default())?;
+            map.serialize_entry("execution_count", &self.execution_count)?;
+        }
+        for (key, value) in &self.extra {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// .ipynb stores text either as a string or as a list of lines
+fn multiline_text<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Text {
+        Single(String),
+        Lines(Vec<String>),
+    }
+
+    Ok(match Text::deserialize(deserializer)? {
+        Text::Single(s) => s,
+        Text::Lines(lines) => lines.concat(),
+    })
+}
+
+impl Notebook {
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let mut notebook: Notebook = serde_json::from_str(&json)
+            .with_context(|| format!("Invalid notebook: {:?}", path))?;
+
+        // nbformat < 4.5 has no cell ids; the frontend needs them to route outputs
+        for cell in &mut notebook.cells {
+            if cell.id.is_none() {
+                cell.id = Some(uuid::Uuid::new_v4().to_string());
+            }
+        }
+
+        Ok(notebook)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut notebook = self.clone();
+        notebook.nbformat = 4;
+        notebook.nbformat_minor = notebook.nbformat_minor.max(5);
+
+        std::fs::write(path, serde_json::to_string_pretty(&notebook)? + "\n")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KernelInfo {
+    pub kernel_id: String,
+    pub kernel_name: String,
+    pub notebook_path: String,
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellOutputEvent {
+    pub kernel_id: String,
+    pub cell_id: String,
+    pub output: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellExecutionResult {
+    pub cell_id: String,
+    pub execution_count: Option<u32>,
+    pub outputs: Vec<serde_json::Value>,
+    pub timed_out: bool,
+}
+
+enum DriverEvent {
+    Ready,
+    Failed(String),
+    Done(CellExecutionResult),
+}
+
+struct KernelSession {
+    info: KernelInfo,
+    child: Child,
+    stdin: ChildStdin,
+    events: Arc<Mutex<Receiver<DriverEvent>>>,
+}
+
+static KERNELS: Mutex<Option<HashMap<String, KernelSession>>> = Mutex::new(None);
+
+fn with_kernels<T>(f: impl FnOnce(&mut HashMap<String, KernelSession>) -> T) -> T {
+    let mut kernels = KERNELS.lock().unwrap();
+    f(kernels.get_or_insert_with(HashMap::new))
+}
+
+fn driver_path() -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .context("Failed to get data directory")?
+        .join(".sai-ide")
+        .join("notebook");
+
+    std::fs::create_dir_all(&dir)?;
+
+    let driver = dir.join("kernel_driver.py");
+    std::fs::write(&driver, KERNEL_DRIVER)?;
+    Ok(driver)
+}
+
+fn spawn_driver_reader<R: Read + Send + 'static>(
+    app: tauri::AppHandle,
+    kernel_id: String,
+    stream: R,
+    events: Sender<DriverEvent>,
+) {
+    std::thread::spawn(move || {
+        let mut outputs: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+
+        for line in BufReader::new(stream).lines() {
+            let line = match line {
+                Ok(l) => l,
+                Err(_) => break,
+            };
+
+            let message: serde_json::Value = match serde_json::from_str(&line) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+
+            let cell_id = message["cell_id"].as_str().unwrap_or_default().to_string();
+
+            match message["type"].as_str() {
+                Some("ready") => {
+                    events.send(DriverEvent::Ready).ok();
+                }
+                Some("failed") => {
+                    let error = message["error"].as_str().unwrap_or("Kernel failed to start");
+                    events.send(DriverEvent::Failed(error.to_string())).ok();
+                }
+                Some("output") => {
+                    let output = message["output"].clone();
+                    outputs.entry(cell_id.clone()).or_default().push(output.clone());
+
+                    app.emit("notebook-output", CellOutputEvent {
+                        kernel_id: kernel_id.clone(),
+                        cell_id,
+                        output,
+                    }).ok();
+                }
+                Some("done") => {
+                    events.send(DriverEvent::Done(CellExecutionResult {
+                        outputs: outputs.remove(&cell_id).unwrap_or_default(),
+                        cell_id,
+                        execution_count: message["execution_count"].as_u64().map(|c| c as u32),
+                        timed_out: false,
+                    })).ok();
+                }
+                _ => {}
+            }
+        }
+
+        events.send(DriverEvent::Failed("Kernel process exited".to_string())).ok();
+    });
+}
+
+fn send_request(kernel_id: &str, request: serde_json::Value) -> Result<Arc<Mutex<Receiver<DriverEvent>>>> {
+    with_kernels(|kernels| {
+        let kernel = kernels.get_mut(kernel_id).context("Kernel not found")?;
+        kernel.stdin.write_all(format!("{}\n", request).as_bytes())?;
+        kernel.stdin.flush()?;
+        Ok(kernel.events.clone())
+    })
+}
+
+pub fn start_kernel(app: &tauri::AppHandle, notebook_path: &str, kernel_name: &str) -> Result<KernelInfo> {
+    let cwd = Path::new(notebook_path)
+        .parent()
+        .filter(|p| p.exists())
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| std::env::temp_dir());
+
+    // The driver and kernel run in the project's venv when it has one
+    let python = crate::python_envs::interpreter_for(&cwd)
+        .or_else(|| find_in_path("python3"))
+        .or_else(|| find_in_path("python"))
+        .context("Python is not installed")?;
+
+    let mut child = Command::new(&python)
+        .arg("-u")
+        .arg(driver_path()?)
+        .arg(kernel_name)
+        .arg(&cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start kernel driver")?;
+
+    let stdin = child.stdin.take().context("Failed to open kernel stdin")?;
+    let stdout = child.stdout.take().context("Failed to open kernel stdout")?;
+    let mut stderr = child.stderr.take().context("Failed to open kernel stderr")?;
+
+    let kernel_id = uuid::Uuid::new_v4().to_string();
+    let (tx, rx) = channel();
+    spawn_driver_reader(app.clone(), kernel_id.clone(), stdout, tx);
+
+    match rx.recv_timeout(KERNEL_STARTUP_TIMEOUT) {
+        Ok(DriverEvent::Ready) => {}
+        Ok(DriverEvent::Failed(error)) => {
+            let mut details = String::new();
+            child.wait().ok();
+            stderr.read_to_string(&mut details).ok();
+
+            if details.contains("No module named 'jupyter_client'") {
+                anyhow::bail!(
+                    "jupyter_client is not installed. Run: {} -m pip install jupyter_client ipykernel",
+                    python.display()
+                );
+            }
+            anyhow::bail!("{} {}", error, details.trim());
+        }
+        _ => {
+            child.kill().ok();
+            anyhow::bail!("Timed out waiting for kernel to start");
+        }
+    }
+
+    // Kernel tracebacks are already relayed as error outputs
+    std::thread::spawn(move || std::io::copy(&mut stderr, &mut std::io::sink()).ok());
+
+    let info = KernelInfo {
+        kernel_id: kernel_id.clone(),
+        kernel_name: kernel_name.to_string(),
+        notebook_path: notebook_path.to_string(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    with_kernels(|kernels| {
+        kernels.insert(kernel_id, KernelSession {
+            info: info.clone(),
+            child,
+            stdin,
+            events: Arc::new(Mutex::new(rx)),
+        });
+    });
+
+    tracing::info!("Started {} kernel for {}", kernel_name, notebook_path);
+    Ok(info)
+}
+
+pub fn execute_cell(kernel_id: &str, cell_id: &str, code: &str) -> Result<CellExecutionResult> {
+    let events = send_request(kernel_id, serde_json::json!({
+        "op": "execute",
+        "cell_id": cell_id,
+        "code": code,
+    }))?;
+
+    let events = events.lock().unwrap();
+    loop {
+        match events.recv_timeout(CELL_TIMEOUT) {
+            Ok(DriverEvent::Done(result)) if result.cell_id == cell_id => return Ok(result),
+            Ok(DriverEvent::Failed(error)) => anyhow::bail!(error),
+            Ok(_) => continue,
+            Err(_) => {
+                return Ok(CellExecutionResult {
+                    cell_id: cell_id.to_string(),
+                    execution_count: None,
+                    outputs: Vec::new(),
+                    timed_out: true,
+                })
+            }
+        }
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn load_notebook(path: String) -> Result<Notebook, String> {
+    Notebook::load(Path::new(&path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_notebook(path: String, notebook: Notebook) -> Result<(), String> {
+    notebook.save(Path::new(&path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn start_notebook_kernel(
+    app: tauri::AppHandle,
+    notebook_path: String,
+    kernel_name: Option<String>,
+) -> Result<KernelInfo, String> {
+    let kernel_name = kernel_name.unwrap_or_else(|| "python3".to_string());
+
+    tokio::task::spawn_blocking(move || start_kernel(&app, &notebook_path, &kernel_name))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Run a cell; outputs are also streamed as `notebook-output` events
+#[tauri::command]
+pub async fn execute_notebook_cell(kernel_id: String, cell_id: String, code: String) -> Result<CellExecutionResult, String> {
+    tokio::task::spawn_blocking(move || execute_cell(&kernel_id, &cell_id, &code))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn interrupt_notebook_kernel(kernel_id: String) -> Result<(), String> {
+    send_request(&kernel_id, serde_json::json!({ "op": "interrupt" }))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn shutdown_notebook_kernel(kernel_id: String) -> Result<(), String> {
+    let kernel = with_kernels(|kernels| kernels.remove(&kernel_id));
+
+    if let Some(mut kernel) = kernel {
+        // Closing stdin lets the driver shut the kernel down cleanly
+        drop(kernel.stdin);
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(5));
+            kernel.child.kill().ok();
+            kernel.child.wait().ok();
+        });
+        tracing::info!("Shut down kernel {}", kernel_id);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_notebook_kernels() -> Result<Vec<KernelInfo>, String> {
+    Ok(with_kernels(|kernels| kernels.values().map(|k| k.info.clone()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notebook_source_formats() {
+        let json = r##"{
+            "cells": [
+                {"cell_type": "code", "source": ["import pandas as pd\n", "df = pd.DataFrame()"], "metadata": {}, "outputs": [], "execution_count": null},
+                {"cell_type": "markdown", "id": "intro", "source": "# Title", "metadata": {}}
+            ],
+            "metadata": {},
+            "nbformat": 4,
+            "nbformat_minor": 4
+        }"##;
+
+        let notebook: Notebook = serde_json::from_str(json).unwrap();
+        assert_eq!(notebook.cells[0].source, "import pandas as pd\ndf = pd.DataFrame()");
+        assert_eq!(notebook.cells[1].source, "# Title");
+        assert!(notebook.cells[1].outputs.is_none());
+
+        let mut cells = serde_json::to_value(&notebook).unwrap()["cells"].clone();
+        assert_eq!(cells[0]["execution_count"], serde_json::Value::Null);
+        assert!(cells[0].as_object().unwrap().contains_key("execution_count"));
+        assert!(cells[1].get("outputs").is_none());
+
+        let with_attachment = r#"{"cell_type": "markdown", "source": "![x](attachment:x.png)", "metadata": {},
+            "attachments": {"x.png": {"image/png": "iVBORw0KGgo="}}}"#;
+        let cell: NotebookCell = serde_json::from_str(with_attachment).unwrap();
+        cells = serde_json::to_value(&cell).unwrap();
+        assert_eq!(cells["attachments"]["x.png"]["image/png"], "iVBORw0KGgo=");
+    }
+}
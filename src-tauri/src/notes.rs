@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::database::{self, Note, NoteSearchResult};
+
+pub const EXPORT_FILE: &str = "NOTES.md";
+const SEARCH_LIMIT: usize = 50;
+
+/// What a note is attached to; the line range is 1-based and inclusive
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NoteTarget {
+    #[serde(default)]
+    pub file_path: Option<String>,
+    #[serde(default)]
+    pub start_line: Option<i32>,
+    #[serde(default)]
+    pub end_line: Option<i32>,
+}
+
+impl NoteTarget {
+    fn validate(&self) -> Result<()> {
+        match (&self.file_path, self.start_line, self.end_line) {
+            (None, Some(_), _) | (None, _, Some(_)) => anyhow::bail!("A line range needs a file"),
+            (_, None, Some(_)) => anyhow::bail!("A line range needs a start line"),
+            (_, Some(start), _) if start < 1 => anyhow::bail!("Lines start at 1"),
+            (_, Some(start), Some(end)) if end < start => anyhow::bail!("The range ends before it starts"),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn tags_json(tags: &[String]) -> Result<String> {
+    let mut tags: Vec<String> = tags.iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+    tags.sort();
+    tags.dedup();
+    Ok(serde_json::to_string(&tags)?)
+}
+
+pub fn note_tags(note: &Note) -> Vec<String> {
+    serde_json::from_str(&note.tags).unwrap_or_default()
+}
+
+pub fn create(project_id: &str, target: NoteTarget, title: &str, content: &str, tags: &[String]) -> Result<Note> {
+    target.validate()?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut note = Note {
+        id: 0,
+        project_id: project_id.to_string(),
+        file_path: target.file_path,
+        start_line: target.start_line,
+        end_line: target.end_line.or(target.start_line),
+        title: title.trim().to_string(),
+        content: content.to_string(),
+        tags: tags_json(tags)?,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    note.id = database::open()?.add_note(&note)?;
+    Ok(note)
+}
+
+pub fn update(note_id: i64, target: NoteTarget, title: &str, content: &str, tags: &[String]) -> Result<Note> {
+    target.validate()?;
+    let db = database::open()?;
+    let mut note = db.get_note(note_id)?.context("Note not found")?;
+    note.file_path = target.file_path;
+    note.start_line = target.start_line;
+    note.end_line = target.end_line.or(target.start_line);
+    note.title = title.trim().to_string();
+    note.content = content.to_string();
+    note.tags = tags_json(tags)?;
+    note.updated_at = chrono::Utc::now().to_rfc3339();
+    db.update_note(&note)?;
+    Ok(note)
+}
+
+fn relative_path(path: &str, project_path: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(project_path)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn note_heading(note: &Note) -> String {
+    let title = if note.title.is_empty() { "Untitled" } else { note.title.as_str() };
+    match (note.start_line, note.end_line) {
+        (Some(start), Some(end)) if end > start => format!("{} (lines {}–{})", title, start, end),
+        (Some(start), _) => format!("{} (line {})", title, start),
+        _ => title.to_string(),
+    }
+}
+
+/// Project notes first, then one section per file in path order
+pub fn to_markdown(notes: &[Note], project_path: &Path) -> String {
+    let mut project_notes = Vec::new();
+    let mut by_file: BTreeMap<String, Vec<&Note>> = BTreeMap::new();
+    for note in notes {
+        match &note.file_path {
+            Some(path) => by_file.entry(relative_path(path, project_path)).or_default().push(note),
+            None => project_notes.push(note),
+        }
+    }
+
+    let mut out = String::from("# Notes\n");
+    let sections = (!project_notes.is_empty())
+        .then(|| ("Project".to_string(), project_notes))
+        .into_iter()
+        .chain(by_file.into_iter().map(|(path, notes)| (format!("`{}`", path), notes)));
+
+    for (heading, mut notes) in sections {
+        notes.sort_by_key(|n| (n.start_line, n.created_at.clone()));
+        out.push_str(&format!("\n## {}\n", heading));
+        for note in notes {
+            out.push_str(&format!("\n### {}\n\n", note_heading(note)));
+            let tags = note_tags(note);
+            if !tags.is_empty() {
+                let tags: Vec<String> = tags.iter().map(|t| format!("`#{}`", t)).collect();
+                out.push_str(&format!("Tags: {}  \n", tags.join(" ")));
+            }
+            out.push_str(&format!("Updated: {}\n\n", note.updated_at));
+            // Notes are Markdown already; demote their headings below ours
+            let mut in_code = false;
+            for line in note.content.trim_end().lines() {
+                if line.trim_start().starts_with("```") {
+                    in_code = !in_code;
+                }
+                if !in_code && line.starts_with('#') {
+                    out.push_str("###");
+                }
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+
+    out
+}
+
+/// Write all of a project's notes to NOTES.md at the project root
+pub fn export(project_id: &str, project_path: &Path) -> Result<String> {
+    let notes = database::open()?.list_notes(project_id, None)?;
+    let path = project_path.join(EXPORT_FILE);
+    std::fs::write(&path, to_markdown(&notes, project_path))
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn create_note(
+    project_id: String,
+    target: Option<NoteTarget>,
+    title: String,
+    content: String,
+    tags: Option<Vec<String>>,
+) -> Result<Note, String> {
+    create(&project_id, target.unwrap_or_default(), &title, &content, &tags.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_note(
+    note_id: i64,
+    target: Option<NoteTarget>,
+    title: String,
+    content: String,
+    tags: Option<Vec<String>>,
+) -> Result<Note, String> {
+    update(note_id, target.unwrap_or_default(), &title, &content, &tags.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// A project's notes, optionally only one file's or only those with `tag`
+#[tauri::command]
+pub async fn list_notes(project_id: String, file_path: Option<String>, tag: Option<String>) -> Result<Vec<Note>, String> {
+    let notes = database::open()
+        .and_then(|db| db.list_notes(&project_id, file_path.as_deref()))
+        .map_err(|e| e.to_string())?;
+    Ok(match tag {
+        Some(tag) => notes.into_iter().filter(|n| note_tags(n).contains(&tag)).collect(),
+        None => notes,
+    })
+}
+
+#[tauri::command]
+pub async fn search_notes(query: String, project_id: Option<String>) -> Result<Vec<NoteSearchResult>, String> {
+    let Some(fts_query) = crate::docs_cache::fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+    database::open()
+        .and_then(|db| db.search_notes(&fts_query, project_id.as_deref(), SEARCH_LIMIT))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_note(note_id: i64) -> Result<(), String> {
+    database::open().and_then(|db| db.delete_note(note_id)).map_err(|e| e.to_string())
+}
+
+/// Returns the path of the written NOTES.md
+#[tauri::command]
+pub async fn export_notes(project_id: String, project_path: String) -> Result<String, String> {
+    export(&project_id, Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(file_path: Option<&str>, lines: Option<(i32, i32)>, title: &str, content: &str, tags: &[&str]) -> Note {
+        Note {
+            id: 0,
+            project_id: "p".to_string(),
+            file_path: file_path.map(String::from),
+            start_line: lines.map(|l| l.0),
+            end_line: lines.map(|l| l.1),
+            title: title.to_string(),
+            content: content.to_string(),
+            tags: serde_json::to_string(tags).unwrap(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-02T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_notes_markdown() {
+        let notes = vec![
+            note(Some("/repo/src/main.rs"), Some((10, 14)), "Startup order", "# Why\nDatabase first.", &["arch"]),
+            note(None, None, "Release checklist", "- bump version", &[]),
+            note(Some("/repo/src/main.rs"), Some((3, 3)), "Imports", "Sorted by hand.", &[]),
+        ];
+        let markdown = to_markdown(&notes, Path::new("/repo"));
+
+        let project = markdown.find("## Project").unwrap();
+        let file = markdown.find("## `src/main.rs`").unwrap();
+        assert!(project < file);
+        assert!(markdown.find("### Imports (line 3)").unwrap() < markdown.find("### Startup order (lines 10–14)").unwrap());
+        assert!(markdown.contains("Tags: `#arch`"));
+        assert!(markdown.contains("\n#### Why\n"));
+
+        assert!(NoteTarget { file_path: None, start_line: Some(1), end_line: None }.validate().is_err());
+        let range = NoteTarget { file_path: Some("a.rs".to_string()), start_line: Some(5), end_line: Some(2) };
+        assert!(range.validate().is_err());
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set during focus intervals; see focus.rs
+static DO_NOT_DISTURB: AtomicBool = AtomicBool::new(false);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notification {
@@ -12,6 +16,9 @@ pub struct Notification {
     pub timestamp: String,
     pub read: bool,
     pub actions: Vec<NotificationAction>,
+    /// Arrived during do-not-disturb: listed, but not shown as a toast
+    #[serde(default)]
+    pub silent: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -53,7 +60,12 @@ impl NotificationManager {
         }
     }
     
-    pub fn add(&mut self, notification: Notification) {
+    pub fn add(&mut self, mut notification: Notification) {
+        // Errors still get through
+        if do_not_disturb() && notification.level != NotificationLevel::Error {
+            notification.silent = true;
+        }
+        
         // Add to front
         self.notifications.push_front(notification);
         
@@ -111,6 +123,7 @@ impl NotificationManager {
             timestamp: chrono::Utc::now().to_rfc3339(),
             read: false,
             actions: vec![],
+            silent: false,
         });
     }
     
@@ -124,6 +137,7 @@ impl NotificationManager {
             timestamp: chrono::Utc::now().to_rfc3339(),
             read: false,
             actions: vec![],
+            silent: false,
         });
     }
     
@@ -137,6 +151,7 @@ impl NotificationManager {
             timestamp: chrono::Utc::now().to_rfc3339(),
             read: false,
             actions: vec![],
+            silent: false,
         });
     }
     
@@ -150,10 +165,24 @@ impl NotificationManager {
             timestamp: chrono::Utc::now().to_rfc3339(),
             read: false,
             actions: vec![],
+            silent: false,
         });
     }
 }
 
+pub fn set_do_not_disturb(enabled: bool) {
+    DO_NOT_DISTURB.store(enabled, Ordering::Relaxed);
+}
+
+pub fn do_not_disturb() -> bool {
+    DO_NOT_DISTURB.load(Ordering::Relaxed)
+}
+
+/// Post an info notification from backend code
+pub fn info(title: &str, message: &str, category: NotificationCategory) {
+    get_manager().notify_info(title, message, category);
+}
+
 // Global notification manager
 static mut NOTIFICATION_MANAGER: Option<NotificationManager> = None;
 
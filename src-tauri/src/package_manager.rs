@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::fmt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use crate::preferences::PreferencesManager;
+use crate::templates::runner;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    #[default]
+    Npm,
+    Pnpm,
+    Yarn,
+    Bun,
+}
+
+/// Why a project uses the package manager it does, strongest signal first
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectedFrom {
+    /// The `packageManager` field in package.json (Corepack)
+    PackageJson,
+    Lockfile,
+    Preference,
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageManagerInfo {
+    pub manager: PackageManager,
+    pub detected_from: DetectedFrom,
+    /// Version pinned by the `packageManager` field
+    pub version: Option<String>,
+    /// Directory the signal was found in; a workspace root for monorepo packages
+    pub root: Option<String>,
+    pub installed: bool,
+}
+
+/// A program and its arguments, ready to run or to show to the user
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageCommand {
+    pub program: &'static str,
+    pub args: Vec<String>,
+}
+
+impl PackageCommand {
+    fn new(program: &'static str, args: &[&str]) -> Self {
+        Self { program, args: args.iter().map(|a| a.to_string()).collect() }
+    }
+
+    fn with<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, args: I) -> Self {
+        self.args.extend(args.into_iter().map(|a| a.as_ref().to_string()));
+        self
+    }
+
+    pub fn arg_refs(&self) -> Vec<&str> {
+        self.args.iter().map(String::as_str).collect()
+    }
+
+    pub fn command(&self, dir: &Path) -> Result<Command, String> {
+        runner::command(self.program, &self.arg_refs(), dir)
+    }
+
+    pub fn run(&self, dir: &Path) -> Result<Output, String> {
+        runner::run(self.program, &self.arg_refs(), dir)
+    }
+}
+
+impl fmt::Display for PackageCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lockfiles in the order they are checked; a repo mid-migration keeps its old lockfile around
+const LOCKFILES: &[(&str, PackageManager)] = &[
+    ("pnpm-lock.yaml", PackageManager::Pnpm),
+    ("pnpm-workspace.yaml", PackageManager::Pnpm),
+    ("bun.lock", PackageManager::Bun),
+    ("bun.lockb", PackageManager::Bun),
+    ("yarn.lock", PackageManager::Yarn),
+    ("package-lock.json", PackageManager::Npm),
+    ("npm-shrinkwrap.json", PackageManager::Npm),
+];
+
+impl PackageManager {
+    pub fn program(&self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Bun => "bun",
+        }
+    }
+
+    pub fn lockfile(&self) -> &'static str {
+        match self {
+            PackageManager::Npm => "package-lock.json",
+            PackageManager::Pnpm => "pnpm-lock.yaml",
+            PackageManager::Yarn => "yarn.lock",
+            PackageManager::Bun => "bun.lock",
+        }
+    }
+
+    /// `pnpm@9.1.0+sha512.abc` becomes pnpm and `9.1.0`
+    pub fn parse_field(field: &str) -> Option<(Self, Option<String>)> {
+        let (name, version) = match field.split_once('@') {
+            Some((name, version)) => (name, Some(version.split('+').next().unwrap_or(version).to_string())),
+            None => (field, None),
+        };
+        let manager = match name.trim() {
+            "npm" => PackageManager::Npm,
+            "pnpm" => PackageManager::Pnpm,
+            "yarn" => PackageManager::Yarn,
+            "bun" => PackageManager::Bun,
+            _ => return None,
+        };
+        Some((manager, version.filter(|v| !v.is_empty())))
+    }
+
+    pub fn is_installed(&self) -> bool {
+        runner::ensure_tool(self.program()).is_ok()
+    }
+
+    pub fn install(&self) -> PackageCommand {
+        PackageCommand::new(self.program(), &["install"])
+    }
+
+    /// Install exactly what the lockfile says, failing if it is out of date
+    pub fn clean_install(&self) -> PackageCommand {
+        match self {
+            PackageManager::Npm => PackageCommand::new("npm", &["ci"]),
+            _ => PackageCommand::new(self.program(), &["install", "--frozen-lockfile"]),
+        }
+    }
+
+    /// Resolve dependencies into the lockfile without downloading them or running
+    /// install scripts. Yarn 1 has no such mode.
+    pub fn lockfile_only(&self) -> Option<PackageCommand> {
+        match self {
+            PackageManager::Npm => Some(PackageCommand::new(
+                "npm",
+                &["install", "--package-lock-only", "--ignore-scripts", "--no-audit", "--no-fund"],
+            )),
+            PackageManager::Pnpm => Some(PackageCommand::new("pnpm", &["install", "--lockfile-only", "--ignore-scripts"])),
+            PackageManager::Bun => Some(PackageCommand::new("bun", &["install", "--lockfile-only", "--ignore-scripts"])),
+            PackageManager::Yarn => None,
+        }
+    }
+
+    pub fn add(&self, packages: &[String], dev: bool) -> PackageCommand {
+        let (verb, dev_flag) = match self {
+            PackageManager::Npm => ("install", "--save-dev"),
+            PackageManager::Bun => ("add", "--dev"),
+            PackageManager::Pnpm | PackageManager::Yarn => ("add", "-D"),
+        };
+        let command = PackageCommand::new(self.program(), &[verb]);
+        let command = if dev { command.with([dev_flag]) } else { command };
+        command.with(packages)
+    }
+
+    /// Run a package.json script. Only npm needs `--` before arguments meant for the script.
+    pub fn run(&self, script: &str, args: &[String]) -> PackageCommand {
+        let command = PackageCommand::new(self.program(), &["run", script]);
+        match self {
+            PackageManager::Npm if !args.is_empty() => command.with(["--"]).with(args),
+            _ => command.with(args),
+        }
+    }
+
+    /// Run a package's binary without adding it to the project
+    pub fn exec(&self, package: &str, args: &[String]) -> PackageCommand {
+        match self {
+            PackageManager::Pnpm => PackageCommand::new("pnpm", &["dlx", package]).with(args),
+            PackageManager::Bun => PackageCommand::new("bunx", &[package]).with(args),
+            // Yarn 1 has no dlx, and npx always ships with the Node.js that yarn runs on
+            PackageManager::Npm | PackageManager::Yarn => PackageCommand::new("npx", &["--yes", package]).with(args),
+        }
+    }
+
+    /// `npm create vite@latest app -- --template vue-ts` and its equivalents
+    pub fn create(&self, initializer: &str, name: &str, args: &[String]) -> PackageCommand {
+        match self {
+            PackageManager::Npm => {
+                let command = PackageCommand::new("npm", &["create", initializer, name]);
+                if args.is_empty() { command } else { command.with(["--"]).with(args) }
+            }
+            // Yarn 1 always fetches the latest initializer and rejects a version suffix
+            PackageManager::Yarn => {
+                let initializer = initializer.strip_suffix("@latest").unwrap_or(initializer);
+                PackageCommand::new("yarn", &["create", initializer, name]).with(args)
+            }
+            _ => PackageCommand::new(self.program(), &["create", initializer, name]).with(args),
+        }
+    }
+
+    /// Rewrite npm/npx invocations in a shell command line for this package manager.
+    /// Anything else, including npm commands with no equivalent, is left as written.
+    pub fn translate(&self, command_line: &str) -> String {
+        if *self == PackageManager::Npm {
+            return command_line.to_string();
+        }
+        command_line.split(" && ").map(|part| self.translate_one(part)).collect::<Vec<_>>().join(" && ")
+    }
+
+    fn translate_one(&self, command_line: &str) -> String {
+        let words: Vec<&str> = command_line.split_whitespace().collect();
+        let rest = |words: &[&str]| -> Vec<String> {
+            words.iter().skip_while(|w| **w == "--").map(|w| w.to_string()).collect()
+        };
+        let translated = match words.as_slice() {
+            ["npm", "install" | "i"] => self.install(),
+            ["npm", "ci"] => self.clean_install(),
+            ["npm", "install" | "i" | "add", args @ ..] => {
+                let dev = args.iter().any(|a| matches!(*a, "-D" | "--save-dev"));
+                if args.iter().any(|a| a.starts_with('-') && !matches!(*a, "-D" | "--save-dev" | "-S" | "--save")) {
+                    return command_line.to_string();
+                }
+                let packages: Vec<String> = args.iter().filter(|a| !a.starts_with('-')).map(|a| a.to_string()).collect();
+                if packages.is_empty() {
+                    return command_line.to_string();
+                }
+                self.add(&packages, dev)
+            }
+            ["npm", "run" | "run-script", script, args @ ..] => self.run(script, &rest(args)),
+            ["npm", script @ ("test" | "start"), args @ ..] => self.run(script, &rest(args)),
+            ["npx", args @ ..] => {
+                let mut args = args.iter().skip_while(|a| matches!(**a, "--yes" | "-y"));
+                match args.next() {
+                    Some(package) => self.exec(package, &args.map(|a| a.to_string()).collect::<Vec<_>>()),
+                    None => return command_line.to_string(),
+                }
+            }
+            _ => return command_line.to_string(),
+        };
+        translated.to_string()
+    }
+}
+
+/// What the project itself says: the `packageManager` field, else a lockfile. Looks
+/// in `project` and then its parents up to the repository root, so packages inside a
+/// workspace follow the workspace.
+pub fn detect(project: &Path) -> Option<PackageManagerInfo> {
+    for dir in project.ancestors() {
+        let field = std::fs::read_to_string(dir.join("package.json"))
+            .ok()
+            .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+            .and_then(|json| json.get("packageManager").and_then(|v| v.as_str()).and_then(PackageManager::parse_field));
+        let found = match field {
+            Some((manager, version)) => Some((manager, DetectedFrom::PackageJson, version)),
+            None => LOCKFILES
+                .iter()
+                .find(|(file, _)| dir.join(file).exists())
+                .map(|(_, manager)| (*manager, DetectedFrom::Lockfile, None)),
+        };
+        if let Some((manager, detected_from, version)) = found {
+            return Some(PackageManagerInfo {
+                manager,
+                detected_from,
+                version,
+                root: Some(dir.to_string_lossy().to_string()),
+                installed: manager.is_installed(),
+            });
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// The user's choice for projects that don't say, if it is installed; npm otherwise
+pub fn preferred() -> PackageManagerInfo {
+    let preference = PreferencesManager::new()
+        .and_then(|manager| manager.load())
+        .ok()
+        .and_then(|prefs| prefs.projects.package_manager);
+    match preference {
+        Some(manager) if manager.is_installed() => PackageManagerInfo {
+            manager,
+            detected_from: DetectedFrom::Preference,
+            version: None,
+            root: None,
+            installed: true,
+        },
+        _ => PackageManagerInfo {
+            manager: PackageManager::Npm,
+            detected_from: DetectedFrom::Default,
+            version: None,
+            root: None,
+            installed: PackageManager::Npm.is_installed(),
+        },
+    }
+}
+
+/// The package manager to use in `project`
+pub fn resolve(project: &Path) -> PackageManagerInfo {
+    detect(project).unwrap_or_else(preferred)
+}
+
+pub fn for_project(project: &Path) -> PackageManager {
+    resolve(project).manager
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn detect_package_manager(project_path: String) -> Result<PackageManagerInfo, String> {
+    tokio::task::spawn_blocking(move || resolve(Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Install the project's dependencies, or add `packages` to it
+#[tauri::command]
+pub async fn install_project_packages(
+    project_path: String,
+    packages: Vec<String>,
+    dev: Option<bool>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let project = Path::new(&project_path);
+        let manager = for_project(project);
+        let command = if packages.is_empty() {
+            manager.install()
+        } else {
+            manager.add(&packages, dev.unwrap_or(false))
+        };
+        command.run(project)?;
+        Ok(command.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_translate() {
+        let dir = std::env::temp_dir().join(format!("pm-test-{}", uuid::Uuid::new_v4()));
+        let app = dir.join("packages/app");
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::create_dir(dir.join(".git")).unwrap();
+        std::fs::write(app.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        std::fs::write(dir.join("package.json"), r#"{"packageManager":"pnpm@9.1.0+sha512.abc"}"#).unwrap();
+        std::fs::write(dir.join("yarn.lock"), "").unwrap();
+
+        let info = detect(&app).unwrap();
+        assert_eq!((info.manager, info.detected_from, info.version.as_deref()), (PackageManager::Pnpm, DetectedFrom::PackageJson, Some("9.1.0")));
+        std::fs::write(dir.join("package.json"), "{}").unwrap();
+        assert_eq!(detect(&app).unwrap().manager, PackageManager::Yarn);
+        std::fs::remove_file(dir.join("yarn.lock")).unwrap();
+        assert!(detect(&app).is_none());
+
+        let pnpm = PackageManager::Pnpm;
+        assert_eq!(pnpm.translate("npm install && npm run build -- --watch"), "pnpm install && pnpm run build --watch");
+        assert_eq!(pnpm.translate("npm install -D vitest"), "pnpm add -D vitest");
+        assert_eq!(PackageManager::Bun.translate("npx --yes prisma generate"), "bunx prisma generate");
+        assert_eq!(PackageManager::Yarn.translate("npm install --legacy-peer-deps"), "npm install --legacy-peer-deps");
+        assert_eq!(PackageManager::Npm.run("test", &["--json".to_string()]).to_string(), "npm run test -- --json");
+        assert_eq!(PackageManager::Yarn.create("vite@latest", "app", &[]).to_string(), "yarn create vite app");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
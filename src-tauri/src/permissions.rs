@@ -0,0 +1,272 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use anyhow::{Result, Context};
+
+use crate::plugins::PluginManager;
+use crate::preferences::{PermissionPreferences, PreferencesManager};
+
+/// What a plugin or agent tool may do through the host
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ReadProject,
+    /// Implies `ReadProject`
+    #[serde(alias = "FileSystem")]
+    WriteProject,
+    #[serde(alias = "Network")]
+    Network,
+    /// Running processes, including git
+    #[serde(alias = "Terminal", alias = "Git")]
+    Terminal,
+    #[serde(alias = "LLM")]
+    Llm,
+    /// Stored API keys and credentials in the environment
+    Secrets,
+}
+
+/// Skip permissions this version doesn't know, so older and newer plugin manifests still load
+pub fn deserialize_permissions<'de, D>(deserializer: D) -> Result<Vec<Permission>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values: Vec<serde_json::Value> = Vec::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|value| match serde_json::from_value(value.clone()) {
+            Ok(permission) => Some(permission),
+            Err(_) => {
+                tracing::warn!("Ignoring unknown permission {}", value);
+                None
+            }
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum Grantee {
+    Plugin(String),
+    AgentTool(String),
+}
+
+impl std::fmt::Display for Grantee {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Grantee::Plugin(id) => write!(f, "plugin '{}'", id),
+            Grantee::AgentTool(id) => write!(f, "agent tool '{}'", id),
+        }
+    }
+}
+
+/// Built-in agent tools and the permissions they hold until the user revokes them
+const AGENT_TOOLS: &[(&str, &[Permission])] = &[
+    ("error_explainer", &[Permission::ReadProject, Permission::Llm]),
+    ("error_fix", &[Permission::WriteProject]),
+    ("test_fixer", &[Permission::WriteProject, Permission::Terminal, Permission::Llm]),
+    ("repro", &[Permission::WriteProject, Permission::Terminal, Permission::Llm]),
+    ("documentation", &[Permission::WriteProject, Permission::Llm]),
+    ("debug_assistant", &[Permission::ReadProject, Permission::Llm]),
+    ("web_fetch", &[Permission::Network]),
+    ("memory", &[Permission::WriteProject]),
+];
+
+fn tool_defaults(tool: &str) -> Option<&'static [Permission]> {
+    AGENT_TOOLS.iter().find(|(name, _)| *name == tool).map(|(_, permissions)| *permissions)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionGrant {
+    pub grantee: Grantee,
+    pub name: String,
+    /// Everything the grantee asks for; only these can be granted
+    pub requested: Vec<Permission>,
+    pub granted: Vec<Permission>,
+}
+
+pub fn allows(granted: &[Permission], permission: Permission) -> bool {
+    granted.contains(&permission)
+        || (permission == Permission::ReadProject && granted.contains(&Permission::WriteProject))
+}
+
+pub fn granted(prefs: &PermissionPreferences, grantee: &Grantee) -> Vec<Permission> {
+    match grantee {
+        Grantee::Plugin(id) => prefs.plugins.get(id).cloned().unwrap_or_default(),
+        Grantee::AgentTool(tool) => prefs
+            .agent_tools
+            .get(tool)
+            .cloned()
+            .unwrap_or_else(|| tool_defaults(tool).unwrap_or_default().to_vec()),
+    }
+}
+
+fn set_granted(prefs: &mut PermissionPreferences, grantee: &Grantee, permissions: Vec<Permission>) {
+    match grantee {
+        Grantee::Plugin(id) => prefs.plugins.insert(id.clone(), permissions),
+        Grantee::AgentTool(tool) => prefs.agent_tools.insert(tool.clone(), permissions),
+    };
+}
+
+/// Add `permission`, which must be one the grantee requested
+pub fn grant(prefs: &mut PermissionPreferences, grantee: &Grantee, requested: &[Permission], permission: Permission) -> Result<()> {
+    if !requested.contains(&permission) {
+        anyhow::bail!("{} does not request {:?}", grantee, permission);
+    }
+    let mut permissions = granted(prefs, grantee);
+    if !permissions.contains(&permission) {
+        permissions.push(permission);
+    }
+    set_granted(prefs, grantee, permissions);
+    Ok(())
+}
+
+/// Remove `permission`; revoking read also revokes write, since write implies read
+pub fn revoke(prefs: &mut PermissionPreferences, grantee: &Grantee, permission: Permission) {
+    let permissions = granted(prefs, grantee)
+        .into_iter()
+        .filter(|p| *p != permission && !(permission == Permission::ReadProject && *p == Permission::WriteProject))
+        .collect();
+    set_granted(prefs, grantee, permissions);
+}
+
+pub fn check(prefs: &PermissionPreferences, grantee: &Grantee, needed: &[Permission]) -> Result<()> {
+    let granted = granted(prefs, grantee);
+    let missing: Vec<String> = needed
+        .iter()
+        .filter(|p| !allows(&granted, **p))
+        .map(|p| format!("{:?}", p))
+        .collect();
+    if !missing.is_empty() {
+        anyhow::bail!("Permission denied: {} has not been granted {}", grantee, missing.join(", "));
+    }
+    Ok(())
+}
+
+fn load() -> Result<(PreferencesManager, crate::preferences::UserPreferences)> {
+    let manager = PreferencesManager::new()?;
+    let prefs = manager.load()?;
+    Ok((manager, prefs))
+}
+
+/// Enforce at the host boundary that `grantee` holds every permission in `needed`
+pub fn require(grantee: &Grantee, needed: &[Permission]) -> Result<()> {
+    let (_, prefs) = load()?;
+    check(&prefs.permissions, grantee, needed)
+}
+
+/// What `grantee` currently holds
+pub fn granted_to(grantee: &Grantee) -> Result<Vec<Permission>> {
+    let (_, prefs) = load()?;
+    Ok(granted(&prefs.permissions, grantee))
+}
+
+/// Enforce that a built-in agent tool still holds the permissions it runs with
+pub fn require_tool(tool: &str) -> Result<()> {
+    let needed = tool_defaults(tool).with_context(|| format!("Unknown agent tool '{}'", tool))?;
+    require(&Grantee::AgentTool(tool.to_string()), needed)
+}
+
+/// Record what the user approved in the install dialog; only permissions the plugin
+/// requested can be approved
+pub fn grant_approved(plugin_id: &str, requested: &[Permission], approved: &[Permission]) -> Result<()> {
+    let grantee = Grantee::Plugin(plugin_id.to_string());
+    let (manager, mut prefs) = load()?;
+    prefs.permissions.plugins.insert(plugin_id.to_string(), Vec::new());
+    for permission in approved {
+        grant(&mut prefs.permissions, &grantee, requested, *permission)?;
+    }
+    manager.save(&prefs)
+}
+
+pub fn forget_plugin(plugin_id: &str) -> Result<()> {
+    let (manager, mut prefs) = load()?;
+    if prefs.permissions.plugins.remove(plugin_id).is_some() {
+        manager.save(&prefs)?;
+    }
+    Ok(())
+}
+
+fn requested(grantee: &Grantee) -> Result<Vec<Permission>> {
+    match grantee {
+        Grantee::Plugin(id) => Ok(PluginManager::new()?
+            .get_plugin(id)?
+            .with_context(|| format!("Plugin '{}' is not installed", id))?
+            .permissions),
+        Grantee::AgentTool(tool) => Ok(tool_defaults(tool)
+            .with_context(|| format!("Unknown agent tool '{}'", tool))?
+            .to_vec()),
+    }
+}
+
+pub fn list_grants() -> Result<Vec<PermissionGrant>> {
+    let (_, prefs) = load()?;
+    let mut grants = Vec::new();
+
+    for plugin in PluginManager::new()?.load_plugins()? {
+        let grantee = Grantee::Plugin(plugin.id.clone());
+        grants.push(PermissionGrant {
+            granted: granted(&prefs.permissions, &grantee),
+            grantee,
+            name: plugin.name,
+            requested: plugin.permissions,
+        });
+    }
+    for (tool, defaults) in AGENT_TOOLS {
+        let grantee = Grantee::AgentTool(tool.to_string());
+        grants.push(PermissionGrant {
+            granted: granted(&prefs.permissions, &grantee),
+            grantee,
+            name: tool.replace('_', " "),
+            requested: defaults.to_vec(),
+        });
+    }
+
+    Ok(grants)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_granted_permissions() -> Result<Vec<PermissionGrant>, String> {
+    list_grants().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn grant_permission(grantee: Grantee, permission: Permission) -> Result<(), String> {
+    let requested = requested(&grantee).map_err(|e| e.to_string())?;
+    let (manager, mut prefs) = load().map_err(|e| e.to_string())?;
+    grant(&mut prefs.permissions, &grantee, &requested, permission).map_err(|e| e.to_string())?;
+    manager.save(&prefs).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn revoke_permission(grantee: Grantee, permission: Permission) -> Result<(), String> {
+    requested(&grantee).map_err(|e| e.to_string())?;
+    let (manager, mut prefs) = load().map_err(|e| e.to_string())?;
+    revoke(&mut prefs.permissions, &grantee, permission);
+    manager.save(&prefs).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grants() {
+        let mut prefs = PermissionPreferences::default();
+        let tool = Grantee::AgentTool("test_fixer".to_string());
+        assert!(check(&prefs, &tool, &[Permission::ReadProject, Permission::Terminal]).is_ok());
+
+        revoke(&mut prefs, &tool, Permission::ReadProject);
+        assert_eq!(granted(&prefs, &tool), vec![Permission::Terminal, Permission::Llm]);
+        assert!(check(&prefs, &tool, &[Permission::ReadProject]).is_err());
+
+        let plugin = Grantee::Plugin("formatter".to_string());
+        let requested = [Permission::ReadProject, Permission::Network];
+        assert!(check(&prefs, &plugin, &[Permission::Network]).is_err());
+        grant(&mut prefs, &plugin, &requested, Permission::Network).unwrap();
+        assert!(check(&prefs, &plugin, &[Permission::Network]).is_ok());
+        assert!(grant(&mut prefs, &plugin, &requested, Permission::Secrets).is_err());
+
+        let manifest: Vec<Permission> = deserialize_permissions(serde_json::json!(["FileSystem", "UI", "git", "network", "LLM"])).unwrap();
+        assert_eq!(manifest, vec![Permission::WriteProject, Permission::Network, Permission::Llm]);
+    }
+}
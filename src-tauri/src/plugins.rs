@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,15 +17,13 @@ pub struct Plugin {
     pub last_updated: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum Permission {
-    FileSystem,
-    Network,
-    LLM,
-    UI,
-    Terminal,
-    Git,
-}
+pub use crate::permissions::Permission;
+use crate::node_versions;
+use crate::permissions::{self, Grantee};
+use crate::terminal::shell::find_in_path;
+
+/// Environment variables whose names suggest a credential; hidden from plugins without `Secrets`
+const SECRET_ENV_MARKERS: &[&str] = &["KEY", "TOKEN", "SECRET", "PASSWORD", "CREDENTIAL"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginManifest {
@@ -34,8 +32,12 @@ pub struct PluginManifest {
     pub author: String,
     pub description: String,
     pub entry: String,
+    #[serde(deserialize_with = "permissions::deserialize_permissions")]
     pub permissions: Vec<Permission>,
     pub dependencies: HashMap<String, String>,
+    /// SHA-256 of package files by relative path, checked before install
+    #[serde(default)]
+    pub checksums: HashMap<String, String>,
 }
 
 pub struct PluginManager {
@@ -145,9 +147,9 @@ impl PluginManager {
         Ok(())
     }
     
-    /// Install plugin from path
-    pub fn install_plugin(&self, plugin_path: &PathBuf) -> Result<Plugin> {
-        // Read manifest
+    /// Read and verify a plugin package's manifest without installing it, so the user
+    /// can review the permissions it requests
+    pub fn inspect_package(&self, plugin_path: &PathBuf) -> Result<PluginManifest> {
         let manifest_path = plugin_path.join("plugin.json");
         if !manifest_path.exists() {
             anyhow::bail!("Invalid plugin: manifest not found");
@@ -156,14 +158,44 @@ impl PluginManager {
         let manifest_str = std::fs::read_to_string(&manifest_path)?;
         let manifest: PluginManifest = serde_json::from_str(&manifest_str)?;
         
+        if !manifest.checksums.is_empty() {
+            let report = crate::checksums::verify(&crate::checksums::ChecksumManifest {
+                base_dir: Some(plugin_path.to_string_lossy().to_string()),
+                entries: manifest.checksums.iter()
+                    .map(|(path, expected)| crate::checksums::ChecksumEntry {
+                        path: path.clone(),
+                        algorithm: crate::checksums::HashAlgorithm::Sha256,
+                        expected: expected.clone(),
+                    })
+                    .collect(),
+            });
+            let failed: Vec<&str> = report.results.iter().filter(|r| !r.ok).map(|r| r.path.as_str()).collect();
+            if !failed.is_empty() {
+                anyhow::bail!("Plugin files failed checksum verification: {}", failed.join(", "));
+            }
+        }
+        
+        Ok(manifest)
+    }
+    
+    /// Install plugin from path, granting only the permissions in `approved`; the rest
+    /// stay requested until the user grants them
+    pub fn install_plugin(&self, plugin_path: &PathBuf, approved: &[Permission]) -> Result<Plugin> {
+        let manifest = self.inspect_package(plugin_path)?;
+        if let Some(permission) = approved.iter().find(|p| !manifest.permissions.contains(p)) {
+            anyhow::bail!("{} does not request {:?}", manifest.name, permission);
+        }
+        
         // Generate plugin ID
         let plugin_id = self.generate_plugin_id(&manifest.name);
         let dest_dir = self.plugins_dir.join(&plugin_id);
         
         // Copy plugin files
-        self.copy_dir(plugin_path, &dest_dir)?;
+        let options = crate::file_transfer::CopyOptions::default();
+        crate::file_transfer::copy_tree(&plugin_id, plugin_path, &dest_dir, &options, &|_| {})?;
         
         tracing::info!("Installed plugin: {}", manifest.name);
+        permissions::grant_approved(&plugin_id, &manifest.permissions, approved)?;
         
         self.load_plugin(&dest_dir)
     }
@@ -176,12 +208,19 @@ impl PluginManager {
             std::fs::remove_dir_all(&plugin_dir)?;
             tracing::info!("Uninstalled plugin: {}", plugin_id);
         }
+        permissions::forget_plugin(plugin_id)?;
         
         Ok(())
     }
     
     /// Execute plugin command
-    pub async fn execute_plugin(&self, plugin_id: &str, command: &str, args: Vec<String>) -> Result<String> {
+    pub async fn execute_plugin(
+        &self,
+        plugin_id: &str,
+        command: &str,
+        args: Vec<String>,
+        project_path: Option<&Path>,
+    ) -> Result<String> {
         let plugin = self.get_plugin(plugin_id)?
             .context("Plugin not found")?;
         
@@ -196,13 +235,54 @@ impl PluginManager {
             anyhow::bail!("Plugin entry point not found");
         }
         
-        // Execute plugin (example: run as Node.js script)
-        let output = std::process::Command::new("node")
+        // The plugin runs with whatever subset the user granted, confined to it by
+        // Node's permission model
+        let grantee = Grantee::Plugin(plugin_id.to_string());
+        let granted = permissions::granted_to(&grantee)?;
+        
+        let node = find_in_path("node").context("Node.js is not installed")?;
+        let model = node_versions::permission_model(&node)?;
+        if !model.restricts_network && !granted.contains(&Permission::Network) {
+            anyhow::bail!(
+                "{} can't run without network access on this Node version; grant it Network or install Node 25 or newer",
+                plugin.name
+            );
+        }
+        
+        let mut process = std::process::Command::new(node);
+        process.arg(model.flag).arg(format!("--allow-fs-read={}", plugin_dir.display()));
+        if let Some(project) = project_path {
+            if permissions::allows(&granted, Permission::ReadProject) {
+                process.arg(format!("--allow-fs-read={}", project.display()));
+            }
+            if granted.contains(&Permission::WriteProject) {
+                process.arg(format!("--allow-fs-write={}", project.display()));
+            }
+        }
+        if granted.contains(&Permission::Terminal) {
+            process.arg("--allow-child-process");
+        }
+        if model.restricts_network && granted.contains(&Permission::Network) {
+            process.arg("--allow-net");
+        }
+        process
             .arg(&entry_script)
             .arg(command)
             .args(args)
             .current_dir(&plugin_dir)
-            .output()?;
+            .env(
+                "LUCIAI_PERMISSIONS",
+                granted.iter().map(|p| format!("{:?}", p)).collect::<Vec<_>>().join(","),
+            );
+        if !granted.contains(&Permission::Secrets) {
+            for (name, _) in std::env::vars_os() {
+                let upper = name.to_string_lossy().to_uppercase();
+                if SECRET_ENV_MARKERS.iter().any(|marker| upper.contains(marker)) {
+                    process.env_remove(&name);
+                }
+            }
+        }
+        let output = process.output()?;
         
         let result = String::from_utf8_lossy(&output.stdout).to_string();
         Ok(result)
@@ -250,24 +330,6 @@ impl PluginManager {
             .filter(|c| c.is_alphanumeric() || *c == '-')
             .collect()
     }
-    
-    fn copy_dir(&self, src: &PathBuf, dst: &PathBuf) -> Result<()> {
-        std::fs::create_dir_all(dst)?;
-        
-        for entry in std::fs::read_dir(src)? {
-            let entry = entry?;
-            let src_path = entry.path();
-            let dst_path = dst.join(entry.file_name());
-            
-            if src_path.is_dir() {
-                self.copy_dir(&src_path, &dst_path)?;
-            } else {
-                std::fs::copy(&src_path, &dst_path)?;
-            }
-        }
-        
-        Ok(())
-    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -310,12 +372,23 @@ pub async fn toggle_plugin_enabled(plugin_id: String, enabled: bool) -> Result<(
         .map_err(|e| e.to_string())
 }
 
+/// The manifest of a plugin package, for the install dialog to show what it requests
+#[tauri::command]
+pub async fn inspect_plugin_package(path: String) -> Result<PluginManifest, String> {
+    let manager = PluginManager::new()
+        .map_err(|e| e.to_string())?;
+    
+    manager.inspect_package(&PathBuf::from(path))
+        .map_err(|e| e.to_string())
+}
+
+/// Install a plugin with the permissions the user approved after `inspect_plugin_package`
 #[tauri::command]
-pub async fn install_plugin_from_path(path: String) -> Result<Plugin, String> {
+pub async fn install_plugin_from_path(path: String, approved_permissions: Vec<Permission>) -> Result<Plugin, String> {
     let manager = PluginManager::new()
         .map_err(|e| e.to_string())?;
     
-    manager.install_plugin(&PathBuf::from(path))
+    manager.install_plugin(&PathBuf::from(path), &approved_permissions)
         .map_err(|e| e.to_string())
 }
 
@@ -332,12 +405,13 @@ pub async fn uninstall_plugin(plugin_id: String) -> Result<(), String> {
 pub async fn execute_plugin_command(
     plugin_id: String,
     command: String,
-    args: Vec<String>
+    args: Vec<String>,
+    project_path: Option<String>,
 ) -> Result<String, String> {
     let manager = PluginManager::new()
         .map_err(|e| e.to_string())?;
     
-    manager.execute_plugin(&plugin_id, &command, args)
+    manager.execute_plugin(&plugin_id, &command, args, project_path.as_deref().map(Path::new))
         .await
         .map_err(|e| e.to_string())
 }
@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::package_manager::PackageManager;
+use crate::permissions::Permission;
+
+/// Credential store key of the manual proxy's password
+pub const PROXY_PASSWORD_KEY: &str = "network.proxy_password";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPreferences {
     pub editor: EditorPreferences,
@@ -9,6 +16,19 @@ pub struct UserPreferences {
     pub ui: UIPreferences,
     pub git: GitPreferences,
     pub projects: ProjectPreferences,
+    #[serde(default)]
+    pub accessibility: AccessibilityPreferences,
+    #[serde(default)]
+    pub network: NetworkPreferences,
+    #[serde(default)]
+    pub permissions: PermissionPreferences,
+    #[serde(default)]
+    pub web_fetch: WebFetchPreferences,
+    #[serde(default)]
+    pub focus: FocusPreferences,
+    /// Set once the first-run wizard has finished
+    #[serde(default)]
+    pub setup_complete: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +64,17 @@ pub struct LLMPreferences {
     pub streaming: bool,
     pub auto_validate: bool,
     pub auto_test: bool,
+    /// Ask the model whether retrieved files and pages contain prompt injection, on top of the pattern rules
+    #[serde(default)]
+    pub injection_classifier: bool,
+    /// Ollama runtime options; unset uses what `get_recommended_llm_settings` reports for
+    /// this machine, with the context capped at the model's trained length
+    #[serde(default)]
+    pub num_ctx: Option<u32>,
+    #[serde(default)]
+    pub num_gpu: Option<u32>,
+    #[serde(default)]
+    pub num_thread: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +86,13 @@ pub struct UIPreferences {
     pub show_status_bar: bool,
     pub show_minimap: bool,
     pub font_scale: f32,
+    /// UI and backend message language, e.g. "en" or "es"
+    #[serde(default = "default_locale")]
+    pub locale: String,
+}
+
+fn default_locale() -> String {
+    crate::i18n::DEFAULT_LOCALE.to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,12 +104,108 @@ pub struct GitPreferences {
     pub show_inline_diff: bool,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccessibilityPreferences {
+    /// None follows the operating system setting
+    pub reduced_motion: Option<bool>,
+    pub large_fonts: bool,
+    pub high_contrast: bool,
+}
+
+/// Accessibility preferences resolved against the OS, for the frontend to apply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessibilitySettings {
+    pub reduced_motion: bool,
+    pub system_reduced_motion: Option<bool>,
+    pub large_fonts: bool,
+    pub high_contrast: bool,
+    pub font_scale: f32,
+    pub editor_font_size: u32,
+    /// High-contrast theme matching the current light/dark choice, when enabled
+    pub theme: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// Use HTTP_PROXY / HTTPS_PROXY / NO_PROXY from the environment
+    #[default]
+    System,
+    /// Connect directly, ignoring the environment
+    None,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkPreferences {
+    pub proxy_mode: ProxyMode,
+    /// e.g. "http://proxy.corp:8080" or "socks5h://127.0.0.1:1080"
+    pub http_proxy: String,
+    /// Falls back to `http_proxy` when empty
+    pub https_proxy: String,
+    pub proxy_username: String,
+    /// Only accepted from the UI: saving moves it to the OS credential store and it is
+    /// never written to preferences.json. Empty keeps the stored password.
+    #[serde(skip_serializing)]
+    pub proxy_password: String,
+    /// Whether a proxy password is in the credential store
+    pub has_proxy_password: bool,
+    /// Comma-separated hosts, domains and CIDRs that bypass the proxy
+    pub no_proxy: String,
+    /// PEM bundle trusted in addition to the built-in roots
+    pub ca_bundle_path: Option<String>,
+}
+
+/// Limits on pages the agent may fetch for documentation lookups
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebFetchPreferences {
+    /// Hosts the agent may fetch from; subdomains are included
+    pub allowed_domains: Vec<String>,
+    /// Largest response body read, in bytes
+    pub max_bytes: usize,
+    /// Extracted text beyond this is cut off before it reaches a prompt
+    pub max_chars: usize,
+    pub timeout_secs: u64,
+    pub cache_minutes: u64,
+}
+
+/// Focus timer intervals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusPreferences {
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    /// Work intervals before a long break instead of a short one
+    pub intervals_before_long_break: u32,
+    pub auto_start_breaks: bool,
+    pub auto_start_work: bool,
+    /// Silence non-error notifications while a work interval runs
+    pub do_not_disturb: bool,
+}
+
+/// Grants by plugin id and agent tool name. Agent tools missing here keep their defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermissionPreferences {
+    pub plugins: HashMap<String, Vec<Permission>>,
+    pub agent_tools: HashMap<String, Vec<Permission>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectPreferences {
     pub default_directory: String,
     pub auto_init_git: bool,
     pub auto_install_deps: bool,
     pub default_license: String,
+    /// For projects whose files don't already say; None means npm
+    #[serde(default)]
+    pub package_manager: Option<PackageManager>,
+    /// Run project tasks through fnm, nvm or Volta when the active Node doesn't match `.nvmrc`/`engines`
+    #[serde(default)]
+    pub switch_node_version: bool,
 }
 
 impl Default for UserPreferences {
@@ -82,6 +216,12 @@ impl Default for UserPreferences {
             ui: UIPreferences::default(),
             git: GitPreferences::default(),
             projects: ProjectPreferences::default(),
+            accessibility: AccessibilityPreferences::default(),
+            network: NetworkPreferences::default(),
+            permissions: PermissionPreferences::default(),
+            web_fetch: WebFetchPreferences::default(),
+            focus: FocusPreferences::default(),
+            setup_complete: false,
         }
     }
 }
@@ -117,6 +257,10 @@ impl Default for LLMPreferences {
             streaming: true,
             auto_validate: true,
             auto_test: false,
+            injection_classifier: false,
+            num_ctx: None,
+            num_gpu: None,
+            num_thread: None,
         }
     }
 }
@@ -131,6 +275,7 @@ impl Default for UIPreferences {
             show_status_bar: true,
             show_minimap: true,
             font_scale: 1.0,
+            locale: default_locale(),
         }
     }
 }
@@ -147,6 +292,66 @@ impl Default for GitPreferences {
     }
 }
 
+impl Default for NetworkPreferences {
+    fn default() -> Self {
+        Self {
+            proxy_mode: ProxyMode::System,
+            http_proxy: String::new(),
+            https_proxy: String::new(),
+            proxy_username: String::new(),
+            proxy_password: String::new(),
+            has_proxy_password: false,
+            // Local Ollama should never go through a corporate proxy
+            no_proxy: "localhost,127.0.0.1,::1".to_string(),
+            ca_bundle_path: None,
+        }
+    }
+}
+
+impl Default for FocusPreferences {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            intervals_before_long_break: 4,
+            auto_start_breaks: true,
+            auto_start_work: false,
+            do_not_disturb: true,
+        }
+    }
+}
+
+impl Default for WebFetchPreferences {
+    fn default() -> Self {
+        Self {
+            allowed_domains: [
+                "developer.mozilla.org",
+                "docs.rs",
+                "doc.rust-lang.org",
+                "docs.python.org",
+                "nodejs.org",
+                "react.dev",
+                "typescriptlang.org",
+                "pkg.go.dev",
+                "crates.io",
+                "npmjs.com",
+                "pypi.org",
+                "github.com",
+                "raw.githubusercontent.com",
+                "stackoverflow.com",
+            ]
+            .iter()
+            .map(|domain| domain.to_string())
+            .collect(),
+            max_bytes: 2 * 1024 * 1024,
+            max_chars: 40_000,
+            timeout_secs: 20,
+            cache_minutes: 60,
+        }
+    }
+}
+
 impl Default for ProjectPreferences {
     fn default() -> Self {
         Self {
@@ -158,6 +363,8 @@ impl Default for ProjectPreferences {
             auto_init_git: true,
             auto_install_deps: false,
             default_license: "MIT".to_string(),
+            package_manager: None,
+            switch_node_version: false,
         }
     }
 }
@@ -181,20 +388,45 @@ impl PreferencesManager {
     
     pub fn load(&self) -> Result<UserPreferences> {
         if !self.config_path.exists() {
-            let default = UserPreferences::default();
+            let mut default = UserPreferences::default();
+            // Start with a model this machine can actually run
+            default.llm.default_model = crate::hardware::recommend_llm_settings(&crate::hardware::profile()).model;
             self.save(&default)?;
             return Ok(default);
         }
         
         let json = std::fs::read_to_string(&self.config_path)?;
-        let prefs: UserPreferences = serde_json::from_str(&json)?;
+        let mut prefs: UserPreferences = serde_json::from_str(&json)?;
+
+        // Older versions kept the proxy password in this file; move it to the credential store
+        if !prefs.network.proxy_password.is_empty() {
+            self.save(&prefs)?;
+            prefs.network.has_proxy_password = true;
+            prefs.network.proxy_password.clear();
+        }
         
         Ok(prefs)
     }
     
     pub fn save(&self, preferences: &UserPreferences) -> Result<()> {
-        let json = serde_json::to_string_pretty(preferences)?;
-        std::fs::write(&self.config_path, json)?;
+        let mut preferences = preferences.clone();
+        let network = &mut preferences.network;
+        if network.proxy_username.is_empty() {
+            if network.has_proxy_password {
+                crate::secrets::delete_secret(PROXY_PASSWORD_KEY)?;
+            }
+            network.has_proxy_password = false;
+        } else if !network.proxy_password.is_empty() {
+            crate::secrets::store_secret(PROXY_PASSWORD_KEY, &network.proxy_password)?;
+            network.has_proxy_password = true;
+        }
+
+        let json = serde_json::to_string_pretty(&preferences)?;
+        
+        // Write then rename so a crash never leaves a half-written preferences file
+        let tmp_path = self.config_path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, json)?;
+        std::fs::rename(&tmp_path, &self.config_path)?;
         
         tracing::info!("Saved preferences to {:?}", self.config_path);
         Ok(())
@@ -225,6 +457,73 @@ impl PreferencesManager {
     }
 }
 
+const LARGE_FONT_SCALE: f32 = 1.25;
+const LARGE_EDITOR_FONT_SIZE: u32 = 18;
+
+/// The OS "reduce motion" setting, when it can be read
+fn system_reduced_motion() -> Option<bool> {
+    let read = |program: &str, args: &[&str]| {
+        std::process::Command::new(program)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    };
+
+    #[cfg(target_os = "macos")]
+    {
+        read("defaults", &["read", "com.apple.universalaccess", "reduceMotion"]).map(|v| v == "1")
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // UserPreferencesMask bit 0x02 of byte 4 (client area animation) is cleared when animations are off
+        read("powershell", &[
+            "-NoProfile",
+            "-Command",
+            "(Get-ItemProperty 'HKCU:\\Control Panel\\Desktop').UserPreferencesMask[4]",
+        ])
+        .and_then(|v| v.parse::<u8>().ok())
+        .map(|byte| byte & 0x02 == 0)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        read("gsettings", &["get", "org.gnome.desktop.interface", "enable-animations"]).map(|v| v == "false")
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = read;
+        None
+    }
+}
+
+pub fn resolve_accessibility(prefs: &UserPreferences, system_reduced_motion: Option<bool>) -> AccessibilitySettings {
+    let a11y = &prefs.accessibility;
+
+    let (font_scale, editor_font_size) = if a11y.large_fonts {
+        (prefs.ui.font_scale.max(LARGE_FONT_SCALE), prefs.editor.font_size.max(LARGE_EDITOR_FONT_SIZE))
+    } else {
+        (prefs.ui.font_scale, prefs.editor.font_size)
+    };
+
+    let theme = a11y.high_contrast.then(|| {
+        if prefs.ui.theme == "light" { "high-contrast-light" } else { "high-contrast-dark" }.to_string()
+    });
+
+    AccessibilitySettings {
+        reduced_motion: a11y.reduced_motion.or(system_reduced_motion).unwrap_or(false),
+        system_reduced_motion,
+        large_fonts: a11y.large_fonts,
+        high_contrast: a11y.high_contrast,
+        font_scale,
+        editor_font_size,
+        theme,
+    }
+}
+
 // Tauri commands
 #[tauri::command]
 pub async fn load_preferences() -> Result<UserPreferences, String> {
@@ -270,3 +569,13 @@ pub async fn import_preferences(path: String) -> Result<UserPreferences, String>
     manager.import_from_file(&PathBuf::from(path))
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub async fn get_accessibility_settings() -> Result<AccessibilitySettings, String> {
+    let manager = PreferencesManager::new()
+        .map_err(|e| e.to_string())?;
+    let prefs = manager.load()
+        .map_err(|e| e.to_string())?;
+    
+    Ok(resolve_accessibility(&prefs, system_reduced_motion()))
+}
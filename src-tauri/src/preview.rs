@@ -0,0 +1,489 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// Build output folders checked in order before falling back to the project root
+const BUILD_DIRS: &[&str] = &["dist", "build", "out", "public", "_site"];
+
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+const DEV_SERVER_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PreviewMode {
+    Static,
+    DevServer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewServerInfo {
+    pub id: String,
+    pub project_path: String,
+    pub root: String,
+    pub url: String,
+    pub mode: PreviewMode,
+    pub started_at: String,
+}
+
+enum PreviewHandle {
+    Static(tokio::task::JoinHandle<()>),
+    /// The dev server, and the forwarder in front of it when a port or HTTPS was requested
+    DevServer(tokio::process::Child, Option<tokio::task::JoinHandle<()>>),
+}
+
+struct PreviewServer {
+    info: PreviewServerInfo,
+    handle: PreviewHandle,
+}
+
+static PREVIEW_SERVERS: Mutex<Option<HashMap<String, PreviewServer>>> = Mutex::new(None);
+
+fn with_servers<T>(f: impl FnOnce(&mut HashMap<String, PreviewServer>) -> T) -> T {
+    let mut servers = PREVIEW_SERVERS.lock().unwrap();
+    f(servers.get_or_insert_with(HashMap::new))
+}
+
+/// Pick the directory to serve: a build output folder if present, else the project root
+pub fn find_static_root(project_path: &Path) -> PathBuf {
+    BUILD_DIRS
+        .iter()
+        .map(|dir| project_path.join(dir))
+        .find(|dir| dir.join("index.html").exists())
+        .unwrap_or_else(|| project_path.to_path_buf())
+}
+
+/// Bind the requested port, falling back to any free port if it's taken
+async fn bind_listener(port: Option<u16>) -> Result<TcpListener> {
+    if let Some(port) = port {
+        match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) => tracing::warn!("Port {} unavailable ({}), picking a free port", port, e),
+        }
+    }
+
+    TcpListener::bind(("127.0.0.1", 0)).await.context("Failed to bind preview server")
+}
+
+fn tls_acceptor() -> Result<tokio_rustls::TlsAcceptor> {
+    use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string(), "127.0.0.1".to_string()])?;
+
+    let config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![Certificate(cert.serialize_der()?)],
+            PrivateKey(cert.serialize_private_key_der()),
+        )?;
+
+    Ok(tokio_rustls::TlsAcceptor::from(Arc::new(config)))
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" | "map" => "application/json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "wasm" => "application/wasm",
+        "txt" | "md" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 3 <= bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(b) = u8::from_str_radix(hex, 16) {
+                decoded.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Map a request path to a file under root, with SPA fallback to index.html
+fn resolve_request(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let path = percent_decode(request_path.split(['?', '#']).next().unwrap_or("/"));
+    let relative = path.trim_start_matches('/');
+
+    let mut candidate = root.join(relative);
+    if candidate.is_dir() {
+        candidate = candidate.join("index.html");
+    }
+
+    let root = root.canonicalize().ok()?;
+    if let Ok(resolved) = candidate.canonicalize() {
+        // Reject anything that escapes the served directory
+        return resolved.starts_with(&root).then_some(resolved);
+    }
+
+    // Client-side routes like /about have no extension
+    let is_route = Path::new(relative).extension().is_none();
+    let index = root.join("index.html");
+    (is_route && index.exists()).then_some(index)
+}
+
+async fn handle_connection<S: AsyncRead + AsyncWrite + Unpin>(stream: S, root: Arc<PathBuf>) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        head.push_str(&line);
+        if head.len() > MAX_REQUEST_HEAD {
+            anyhow::bail!("Request header too large");
+        }
+    }
+
+    let mut parts = head.lines().next().unwrap_or("").split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let request_path = parts.next().unwrap_or("/");
+
+    let mut stream = reader.into_inner();
+
+    if method != "GET" && method != "HEAD" {
+        stream.write_all(b"HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await?;
+        return Ok(());
+    }
+
+    let (status, mime, body) = match resolve_request(&root, request_path) {
+        Some(file) => match tokio::fs::read(&file).await {
+            Ok(body) => ("200 OK", content_type(&file), body),
+            Err(_) => ("500 Internal Server Error", "text/plain", b"Failed to read file".to_vec()),
+        },
+        None => ("404 Not Found", "text/plain", b"Not found".to_vec()),
+    };
+
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        status,
+        mime,
+        body.len()
+    );
+
+    stream.write_all(header.as_bytes()).await?;
+    if method == "GET" {
+        stream.write_all(&body).await?;
+    }
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn serve(listener: TcpListener, root: PathBuf, tls: Option<tokio_rustls::TlsAcceptor>) {
+    let root = Arc::new(root);
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!("Preview server accept failed: {}", e);
+                continue;
+            }
+        };
+
+        let root = root.clone();
+        let tls = tls.clone();
+
+        tokio::spawn(async move {
+            let result = match tls {
+                Some(acceptor) => match acceptor.accept(stream).await {
+                    Ok(stream) => handle_connection(stream, root).await,
+                    Err(e) => Err(e.into()),
+                },
+                None => handle_connection(stream, root).await,
+            };
+
+            if let Err(e) = result {
+                tracing::debug!("Preview request failed: {}", e);
+            }
+        });
+    }
+}
+
+pub async fn start_static(project_path: &str, port: Option<u16>, https: bool) -> Result<(PreviewServerInfo, tokio::task::JoinHandle<()>)> {
+    let root = find_static_root(Path::new(project_path));
+    if !root.exists() {
+        anyhow::bail!("Project path does not exist: {}", project_path);
+    }
+
+    let listener = bind_listener(port).await?;
+    let port = listener.local_addr()?.port();
+    let tls = if https { Some(tls_acceptor()?) } else { None };
+
+    let handle = tokio::spawn(serve(listener, root.clone(), tls));
+
+    let info = PreviewServerInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_path: project_path.to_string(),
+        root: root.to_string_lossy().to_string(),
+        url: format!("{}://localhost:{}/", if https { "https" } else { "http" }, port),
+        mode: PreviewMode::Static,
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    Ok((info, handle))
+}
+
+/// Pipe each connection, after TLS if enabled, to the dev server at `target`.
+/// Raw bytes are forwarded so HMR websockets work too.
+async fn forward(listener: TcpListener, target: String, tls: Option<tokio_rustls::TlsAcceptor>) {
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!("Preview forwarder accept failed: {}", e);
+                continue;
+            }
+        };
+
+        let target = target.clone();
+        let tls = tls.clone();
+
+        tokio::spawn(async move {
+            let result = async {
+                let mut upstream = tokio::net::TcpStream::connect(&target).await?;
+                match tls {
+                    Some(acceptor) => {
+                        let mut stream = acceptor.accept(stream).await?;
+                        tokio::io::copy_bidirectional(&mut stream, &mut upstream).await?;
+                    }
+                    None => {
+                        let mut stream = stream;
+                        tokio::io::copy_bidirectional(&mut stream, &mut upstream).await?;
+                    }
+                }
+                Ok::<_, anyhow::Error>(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                tracing::debug!("Preview forward failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Run the project's dev command and wait for it to print its local URL. With a `port` or
+/// `https`, the preview is served from a forwarder on that port in front of the dev server.
+async fn start_dev_server(
+    project_path: &str,
+    command: &str,
+    port: Option<u16>,
+    https: bool,
+) -> Result<(PreviewServerInfo, PreviewHandle)> {
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut c = tokio::process::Command::new("cmd");
+        c.args(&["/C", command]);
+        c
+    } else {
+        let mut c = tokio::process::Command::new("sh");
+        c.args(&["-c", command]);
+        c
+    };
+    // Its own process group, so stopping the preview reaches the server the shell started
+    #[cfg(unix)]
+    cmd.process_group(0);
+    #[cfg(windows)]
+    cmd.creation_flags(0x0000_0200); // CREATE_NEW_PROCESS_GROUP
+
+    let mut child = cmd
+        .current_dir(project_path)
+        .env("BROWSER", "none") // Stop CRA/Vite from opening a browser tab
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start dev server")?;
+
+    let stdout = child.stdout.take().context("Failed to read dev server output")?;
+    let url_pattern = regex::Regex::new(r"https?://(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):\d+/?")?;
+    let ansi_pattern = regex::Regex::new(r"\x1b\[[0-9;]*m")?;
+
+    let mut lines = BufReader::new(stdout).lines();
+    let url = tokio::time::timeout(DEV_SERVER_TIMEOUT, async {
+        while let Ok(Some(line)) = lines.next_line().await {
+            // Strip ANSI colors before matching
+            let plain = ansi_pattern.replace_all(&line, "");
+            if let Some(m) = url_pattern.find(&plain) {
+                return Some(m.as_str().replace("0.0.0.0", "localhost"));
+            }
+        }
+        None
+    })
+    .await
+    .ok()
+    .flatten();
+    let Some(mut url) = url else {
+        crate::terminal::kill_process_tree(&mut child).await;
+        anyhow::bail!("Dev server did not report a URL");
+    };
+
+    // Keep draining output so the process doesn't block on a full pipe
+    tokio::spawn(async move { while let Ok(Some(_)) = lines.next_line().await {} });
+
+    let mut forwarder = None;
+    if port.is_some() || https {
+        let setup = async {
+            let target = reqwest::Url::parse(&url)?;
+            let target_port = target.port_or_known_default().context("Dev server URL has no port")?;
+            let listener = bind_listener(port).await?;
+            let port = listener.local_addr()?.port();
+            let tls = if https { Some(tls_acceptor()?) } else { None };
+            let task = tokio::spawn(forward(listener, format!("127.0.0.1:{}", target_port), tls));
+            Ok::<_, anyhow::Error>((task, port))
+        };
+        match setup.await {
+            Ok((task, port)) => {
+                url = format!("{}://localhost:{}/", if https { "https" } else { "http" }, port);
+                forwarder = Some(task);
+            }
+            Err(e) => {
+                crate::terminal::kill_process_tree(&mut child).await;
+                return Err(e.context("Failed to serve the dev server on the requested port"));
+            }
+        }
+    }
+
+    let info = PreviewServerInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_path: project_path.to_string(),
+        root: project_path.to_string(),
+        url,
+        mode: PreviewMode::DevServer,
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    Ok((info, PreviewHandle::DevServer(child, forwarder)))
+}
+
+/// Serve an already-built directory, e.g. generated docs, and track it with the other previews
+pub async fn serve_directory(path: &Path) -> Result<PreviewServerInfo> {
+    let path = path.to_string_lossy().to_string();
+    let (info, task) = start_static(&path, None, false).await?;
+    with_servers(|servers| {
+        servers.insert(info.id.clone(), PreviewServer {
+            info: info.clone(),
+            handle: PreviewHandle::Static(task),
+        });
+    });
+    Ok(info)
+}
+
+// Tauri commands
+
+/// Serve a static build, or run `dev_command` and preview its URL.
+/// Emits `preview-ready` with the server info once the URL is loadable.
+#[tauri::command]
+pub async fn start_preview_server(
+    app: tauri::AppHandle,
+    project_path: String,
+    port: Option<u16>,
+    https: Option<bool>,
+    dev_command: Option<String>,
+) -> Result<PreviewServerInfo, String> {
+    let (info, handle) = match dev_command {
+        Some(command) => start_dev_server(&project_path, &command, port, https.unwrap_or(false))
+            .await
+            .map_err(|e| e.to_string())?,
+        None => {
+            let (info, task) = start_static(&project_path, port, https.unwrap_or(false))
+                .await
+                .map_err(|e| e.to_string())?;
+            (info, PreviewHandle::Static(task))
+        }
+    };
+
+    with_servers(|servers| {
+        servers.insert(info.id.clone(), PreviewServer {
+            info: info.clone(),
+            handle,
+        });
+    });
+
+    tracing::info!("Preview for {} available at {}", project_path, info.url);
+    app.emit("preview-ready", &info).ok();
+
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_preview_server(server_id: String) -> Result<(), String> {
+    let server = with_servers(|servers| servers.remove(&server_id));
+
+    match server.map(|s| s.handle) {
+        Some(PreviewHandle::Static(task)) => task.abort(),
+        Some(PreviewHandle::DevServer(mut child, forwarder)) => {
+            if let Some(task) = forwarder {
+                task.abort();
+            }
+            crate::terminal::kill_process_tree(&mut child).await;
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_preview_servers() -> Result<Vec<PreviewServerInfo>, String> {
+    Ok(with_servers(|servers| servers.values().map(|s| s.info.clone()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("dist");
+        std::fs::create_dir_all(root.join("assets")).unwrap();
+        std::fs::write(root.join("index.html"), "<html></html>").unwrap();
+        std::fs::write(root.join("assets").join("app.js"), "").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "").unwrap();
+
+        assert_eq!(find_static_root(dir.path()), root);
+
+        let root = root.canonicalize().unwrap();
+        assert_eq!(resolve_request(&root, "/"), Some(root.join("index.html")));
+        assert_eq!(resolve_request(&root, "/assets/app.js?v=1"), Some(root.join("assets").join("app.js")));
+        assert_eq!(resolve_request(&root, "/about"), Some(root.join("index.html")));
+        assert_eq!(resolve_request(&root, "/missing.png"), None);
+        assert_eq!(resolve_request(&root, "/../secret.txt"), None);
+        assert_eq!(resolve_request(&root, "/%2e%2e/secret.txt"), None);
+
+        assert_eq!(percent_decode("/a%20b%41"), "/a bA");
+        assert_eq!(percent_decode("/a%4"), "/a%4");
+    }
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use anyhow::Result;
 use std::time::Instant;
 use std::collections::HashMap;
+use std::sync::Mutex;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileSession {
@@ -64,6 +65,27 @@ pub struct CPUSample {
     pub usage: f32,
 }
 
+impl ProfileSession {
+    fn new(id: String, name: String) -> Self {
+        Self {
+            id,
+            name,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            duration: 0,
+            samples: Vec::new(),
+            metrics: PerformanceMetrics {
+                total_time: 0,
+                cpu_avg: 0.0,
+                cpu_max: 0.0,
+                memory_avg: 0,
+                memory_max: 0,
+                memory_min: u64::MAX,
+                function_calls: HashMap::new(),
+            },
+        }
+    }
+}
+
 pub struct PerformanceProfiler {
     sessions: HashMap<String, ProfileSession>,
     current_session: Option<String>,
@@ -81,22 +103,7 @@ impl PerformanceProfiler {
     
     pub fn start_profiling(&mut self, name: String) -> String {
         let session_id = uuid::Uuid::new_v4().to_string();
-        let session = ProfileSession {
-            id: session_id.clone(),
-            name,
-            started_at: chrono::Utc::now().to_rfc3339(),
-            duration: 0,
-            samples: Vec::new(),
-            metrics: PerformanceMetrics {
-                total_time: 0,
-                cpu_avg: 0.0,
-                cpu_max: 0.0,
-                memory_avg: 0,
-                memory_max: 0,
-                memory_min: u64::MAX,
-                function_calls: HashMap::new(),
-            },
-        };
+        let session = ProfileSession::new(session_id.clone(), name);
         
         self.sessions.insert(session_id.clone(), session);
         self.current_session = Some(session_id.clone());
@@ -273,6 +280,96 @@ impl PerformanceProfiler {
     }
 }
 
+/// Id of the profile session that collects startup phases
+pub const STARTUP_SESSION_ID: &str = "startup";
+
+lazy_static::lazy_static! {
+    /// Forced first thing in `main`, so phase offsets are from process start
+    static ref PROCESS_START: Instant = Instant::now();
+}
+
+/// Startup phases are recorded from setup, background init and the first
+/// commands at once, so they get their own lock rather than the global profiler
+static STARTUP: Mutex<Option<ProfileSession>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupPhase {
+    pub name: String,
+    /// Milliseconds from process start to the start of the phase
+    pub offset_ms: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupReport {
+    /// Process start to first window paint, once the frontend has reported it
+    pub time_to_first_paint_ms: Option<u64>,
+    pub phases: Vec<StartupPhase>,
+}
+
+pub fn mark_process_start() {
+    lazy_static::initialize(&PROCESS_START);
+}
+
+/// Record a phase that began at `started` and has just finished
+pub fn record_startup_phase(name: &str, started: Instant) {
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let offset_ms = started.saturating_duration_since(*PROCESS_START).as_millis() as u64;
+
+    let mut guard = STARTUP.lock().unwrap_or_else(|e| e.into_inner());
+    let session = guard.get_or_insert_with(|| ProfileSession::new(STARTUP_SESSION_ID.to_string(), "Startup".to_string()));
+
+    session.samples.push(ProfileSample {
+        timestamp: offset_ms,
+        cpu_usage: 0.0,
+        memory_usage: 0,
+        function_name: Some(name.to_string()),
+        duration_ms,
+    });
+    session.duration = session.duration.max(offset_ms + duration_ms);
+    session.metrics.total_time = session.duration;
+    session.metrics.function_calls.insert(name.to_string(), FunctionMetrics {
+        name: name.to_string(),
+        call_count: 1,
+        total_time: duration_ms,
+        avg_time: duration_ms,
+        max_time: duration_ms,
+        min_time: duration_ms,
+    });
+    tracing::debug!("Startup phase '{}' took {}ms", name, duration_ms);
+}
+
+/// Run `f` as a named startup phase. Lazily built managers use this on first
+/// use too, so their cost shows up in the report wherever it lands.
+pub fn time_startup_phase<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    record_startup_phase(name, started);
+    result
+}
+
+pub fn startup_report() -> StartupReport {
+    let guard = STARTUP.lock().unwrap_or_else(|e| e.into_inner());
+    let mut phases: Vec<StartupPhase> = guard
+        .iter()
+        .flat_map(|session| &session.samples)
+        .map(|sample| StartupPhase {
+            name: sample.function_name.clone().unwrap_or_default(),
+            offset_ms: sample.timestamp,
+            duration_ms: sample.duration_ms,
+        })
+        .collect();
+    phases.sort_by_key(|phase| phase.offset_ms);
+
+    StartupReport {
+        time_to_first_paint_ms: phases
+            .iter()
+            .find(|phase| phase.name == "first_paint")
+            .map(|phase| phase.offset_ms + phase.duration_ms),
+        phases,
+    }
+}
+
 // Global instance
 static mut PROFILER: Option<PerformanceProfiler> = None;
 
@@ -330,4 +427,26 @@ pub async fn get_current_memory_snapshot() -> Result<MemorySnapshot, String> {
 #[tauri::command]
 pub async fn get_session_cpu_profile(session_id: String) -> Result<CPUProfile, String> {
     get_profiler().get_cpu_profile(&session_id).map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+
+/// Called by the frontend once the main window has painted. Only the first call counts;
+/// the phase runs from the end of setup, when the window starts loading.
+#[tauri::command]
+pub async fn report_first_paint() -> Result<(), String> {
+    let report = startup_report();
+    if report.time_to_first_paint_ms.is_some() {
+        return Ok(());
+    }
+    let setup_end_ms = report
+        .phases
+        .iter()
+        .find(|phase| phase.name == "setup")
+        .map_or(0, |phase| phase.offset_ms + phase.duration_ms);
+    record_startup_phase("first_paint", *PROCESS_START + std::time::Duration::from_millis(setup_end_ms));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_startup_report() -> Result<StartupReport, String> {
+    Ok(startup_report())
+}
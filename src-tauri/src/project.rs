@@ -1,8 +1,39 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use anyhow::{Result, Context};
+use futures::StreamExt;
+use tauri::Emitter;
+
+use crate::file_transfer::{self, ConflictPolicy, CopyOptions, TransferProgress, TransferReport};
+use crate::ignore_files::{self, IgnoreRules};
+
+/// Left out of exported projects
+const EXPORT_EXCLUDES: &[&str] = &[".sai-metadata", "node_modules", "target", "dist", ".venv", "__pycache__"];
+
+/// Concurrent writes when saving a batch of files
+const SAVE_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSaveProgress {
+    pub path: String,
+    pub done: usize,
+    pub total: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSaveError {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchSaveReport {
+    pub saved: Vec<String>,
+    pub failed: Vec<FileSaveError>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -137,6 +168,16 @@ impl ProjectManager {
         Ok(projects)
     }
     
+    /// Directory of a managed project id, or the path itself for external projects
+    pub fn project_dir(&self, project_id_or_path: &str) -> PathBuf {
+        let path = PathBuf::from(project_id_or_path);
+        if path.is_dir() {
+            path
+        } else {
+            self.projects_dir.join(project_id_or_path)
+        }
+    }
+    
     pub fn open_project(&self, project_id_or_path: &str) -> Result<ProjectMetadata> {
         // 1. Check if input is an existing absolute path
         let path = PathBuf::from(project_id_or_path);
@@ -151,7 +192,16 @@ impl ProjectManager {
                     .unwrap_or_default()
                     .to_string_lossy()
                     .to_string();
-                    
+                // Unfamiliar codebase: work out what it is once and preconfigure for it
+                let frameworks = match crate::project_profile::ensure(&path) {
+                    Ok(profile) => profile.frameworks,
+                    Err(e) => {
+                        tracing::warn!("Failed to analyze {}: {}", path.display(), e);
+                        Vec::new()
+                    }
+                };
+
+
                 return Ok(ProjectMetadata {
                     project: Project {
                         id: project_id_or_path.to_string(), // Use path as ID
@@ -162,7 +212,7 @@ impl ProjectManager {
                             frontend: None, 
                             backend: None, 
                             database: None, 
-                            other: vec![] 
+                            other: frameworks,
                         },
                         created_at: Utc::now(),
                         last_modified: Utc::now(),
@@ -184,6 +234,28 @@ impl ProjectManager {
         self.load_metadata_from_path(&metadata_path)
     }
     
+    /// Copy the project's files to `destination`, leaving out IDE metadata and
+    /// dependency/build directories that can be regenerated
+    pub fn export_project(
+        &self,
+        project_id: &str,
+        destination: &Path,
+        transfer_id: &str,
+        on_progress: &dyn Fn(&TransferProgress),
+    ) -> Result<TransferReport> {
+        let project_path = self.projects_dir.join(project_id);
+        if !project_path.exists() {
+            anyhow::bail!("Project not found: {}", project_id);
+        }
+        
+        let options = CopyOptions {
+            conflict: ConflictPolicy::Fail,
+            exclude: EXPORT_EXCLUDES.iter().map(|name| name.to_string()).collect(),
+            ..Default::default()
+        };
+        file_transfer::copy_tree(transfer_id, &project_path, destination, &options, on_progress)
+    }
+    
     pub fn delete_project(&self, project_id: &str) -> Result<()> {
         let project_path = self.projects_dir.join(project_id);
         
@@ -215,14 +287,7 @@ impl ProjectManager {
     
     pub fn save_file(&self, project_id: &str, file_path: &str, content: &str) -> Result<()> {
         let project_dir = self.projects_dir.join(project_id);
-        let full_path = project_dir.join(file_path);
-        
-        // Create parent directories if they don't exist
-        if let Some(parent) = full_path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-        
-        std::fs::write(&full_path, content)?;
+        write_project_file(&project_dir, file_path, content)?;
         tracing::info!("Saved file: {}", file_path);
         
         // Update project metadata
@@ -231,15 +296,61 @@ impl ProjectManager {
         Ok(())
     }
     
-    pub fn save_multiple_files(
+    /// Write a batch of files with up to `SAVE_CONCURRENCY` writes in flight. A failed
+    /// file is reported and the rest still get written; project stats are updated once
+    /// at the end and the touched directories are synced so the batch survives a crash.
+    pub async fn save_multiple_files(
         &self,
         project_id: &str,
         files: Vec<(String, String)>, // (path, content)
-    ) -> Result<()> {
-        for (path, content) in files {
-            self.save_file(project_id, &path, &content)?;
+        on_progress: impl Fn(&FileSaveProgress),
+    ) -> Result<BatchSaveReport> {
+        let project_dir = self.projects_dir.join(project_id);
+        if !project_dir.exists() {
+            anyhow::bail!("Project not found: {}", project_id);
         }
-        Ok(())
+        
+        let total = files.len();
+        let writes = files.into_iter().map(|(path, content)| {
+            let project_dir = project_dir.clone();
+            async move {
+                let result = tokio::task::spawn_blocking({
+                    let path = path.clone();
+                    move || write_project_file(&project_dir, &path, &content)
+                })
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|r| r);
+                (path, result)
+            }
+        });
+        let mut results = futures::stream::iter(writes).buffer_unordered(SAVE_CONCURRENCY);
+        
+        let mut report = BatchSaveReport::default();
+        while let Some((path, result)) = results.next().await {
+            let error = result.err().map(|e| e.to_string());
+            on_progress(&FileSaveProgress {
+                path: path.clone(),
+                done: report.saved.len() + report.failed.len() + 1,
+                total,
+                error: error.clone(),
+            });
+            match error {
+                None => report.saved.push(path),
+                Some(error) => {
+                    tracing::warn!("Failed to save {}: {}", path, error);
+                    report.failed.push(FileSaveError { path, error });
+                }
+            }
+        }
+        
+        let saved = report.saved.clone();
+        let dir = project_dir.clone();
+        tokio::task::spawn_blocking(move || sync_directories(&dir, &saved)).await??;
+        
+        self.update_file_stats(project_id)?;
+        tracing::info!("Saved {} of {} files", report.saved.len(), total);
+        Ok(report)
     }
     
     pub fn add_prompt_entry(
@@ -284,22 +395,22 @@ impl ProjectManager {
         let mut file_count = 0;
         let mut total_lines = 0;
         
-        fn visit_dirs(dir: &PathBuf, file_count: &mut usize, total_lines: &mut usize) -> Result<()> {
+        fn visit_dirs(dir: &PathBuf, rules: &IgnoreRules, file_count: &mut usize, total_lines: &mut usize) -> Result<()> {
             if dir.is_dir() {
                 for entry in std::fs::read_dir(dir)? {
                     let entry = entry?;
                     let path = entry.path();
                     
-                    // Skip hidden and metadata dirs
+                    // Skip hidden, metadata and ignored dirs
                     if let Some(name) = path.file_name() {
                         let name_str = name.to_string_lossy();
-                        if name_str.starts_with('.') || name_str == "node_modules" {
+                        if name_str.starts_with('.') || rules.is_ignored(&path, path.is_dir()) {
                             continue;
                         }
                     }
                     
                     if path.is_dir() {
-                        visit_dirs(&path, file_count, total_lines)?;
+                        visit_dirs(&path, rules, file_count, total_lines)?;
                     } else if path.is_file() {
                         *file_count += 1;
                         
@@ -313,7 +424,8 @@ impl ProjectManager {
             Ok(())
         }
         
-        visit_dirs(dir, &mut file_count, &mut total_lines)?;
+        let rules = ignore_files::rules_for(dir);
+        visit_dirs(dir, &rules, &mut file_count, &mut total_lines)?;
         Ok((file_count, total_lines))
     }
     
@@ -331,22 +443,22 @@ impl ProjectManager {
         let project_dir = self.projects_dir.join(project_id);
         let mut files = Vec::new();
         
-        fn collect_files(dir: &PathBuf, base: &PathBuf, files: &mut Vec<String>) -> Result<()> {
+        fn collect_files(dir: &PathBuf, base: &PathBuf, rules: &IgnoreRules, files: &mut Vec<String>) -> Result<()> {
             if dir.is_dir() {
                 for entry in std::fs::read_dir(dir)? {
                     let entry = entry?;
                     let path = entry.path();
                     
-                    // Skip hidden and metadata dirs
+                    // Skip hidden, metadata and ignored dirs
                     if let Some(name) = path.file_name() {
                         let name_str = name.to_string_lossy();
-                        if name_str.starts_with('.') || name_str == "node_modules" {
+                        if name_str.starts_with('.') || rules.is_ignored(&path, path.is_dir()) {
                             continue;
                         }
                     }
                     
                     if path.is_dir() {
-                        collect_files(&path, base, files)?;
+                        collect_files(&path, base, rules, files)?;
                     } else if path.is_file() {
                         if let Ok(relative) = path.strip_prefix(base) {
                             files.push(relative.to_string_lossy().to_string());
@@ -357,13 +469,71 @@ impl ProjectManager {
             Ok(())
         }
         
-        collect_files(&project_dir, &project_dir, &mut files)?;
+        let rules = ignore_files::rules_for(&project_dir);
+        collect_files(&project_dir, &project_dir, &rules, &mut files)?;
         files.sort();
         
         Ok(files)
     }
 }
 
+/// Write `file_path` under `project_dir`, creating parent directories as needed
+fn write_project_file(project_dir: &Path, file_path: &str, content: &str) -> Result<()> {
+    let relative = Path::new(file_path);
+    if relative.is_absolute() || relative.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+        anyhow::bail!("File path must be relative to the project: {}", file_path);
+    }
+    
+    let full_path = project_dir.join(relative);
+    if let Some(parent) = full_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&full_path, content).with_context(|| format!("Failed to write {}", file_path))
+}
+
+/// Flush directory entries for the written files so new files aren't lost on a crash.
+/// Directories can't be opened for syncing on Windows, where this is a no-op.
+fn sync_directories(project_dir: &Path, files: &[String]) -> Result<()> {
+    let mut dirs: Vec<PathBuf> = files
+        .iter()
+        .filter_map(|file| project_dir.join(file).parent().map(Path::to_path_buf))
+        .collect();
+    dirs.push(project_dir.to_path_buf());
+    dirs.sort();
+    dirs.dedup();
+    
+    #[cfg(unix)]
+    for dir in dirs {
+        std::fs::File::open(&dir)
+            .and_then(|handle| handle.sync_all())
+            .with_context(|| format!("Failed to sync {:?}", dir))?;
+    }
+    #[cfg(not(unix))]
+    let _ = dirs;
+    Ok(())
+}
+
+/// Per-project IDE settings that are meant to be committed with the project
+pub const PROJECT_CONFIG_DIR: &str = ".luciai";
+
+/// Load `<project>/.luciai/<file_name>`, falling back to defaults when absent
+pub fn load_project_config<T: serde::de::DeserializeOwned + Default>(project_path: &Path, file_name: &str) -> Result<T> {
+    let path = project_path.join(PROJECT_CONFIG_DIR).join(file_name);
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let json = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&json).with_context(|| format!("Invalid config file: {:?}", path))
+}
+
+pub fn save_project_config<T: Serialize>(project_path: &Path, file_name: &str, value: &T) -> Result<()> {
+    let dir = project_path.join(PROJECT_CONFIG_DIR);
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(file_name), serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
 // Tauri commands
 
 #[tauri::command]
@@ -420,16 +590,42 @@ pub async fn save_file(
         .map_err(|e| e.to_string())
 }
 
+/// Save a batch of files, emitting `save-files-progress` per file. Files that fail are
+/// listed in the report rather than failing the whole batch.
 #[tauri::command]
 pub async fn save_multiple_files(
+    app: tauri::AppHandle,
     project_id: String,
     files: Vec<(String, String)>,
-) -> Result<(), String> {
+) -> Result<BatchSaveReport, String> {
     let manager = ProjectManager::new()
         .map_err(|e| e.to_string())?;
     
-    manager.save_multiple_files(&project_id, files)
-        .map_err(|e| e.to_string())
+    manager.save_multiple_files(&project_id, files, |progress| {
+        app.emit("save-files-progress", progress).ok();
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Export a project to `destination`, emitting `transfer-progress`; cancel with `cancel_transfer`
+#[tauri::command]
+pub async fn export_project(
+    app: tauri::AppHandle,
+    project_id: String,
+    destination: String,
+    transfer_id: Option<String>,
+) -> Result<TransferReport, String> {
+    let transfer_id = transfer_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    tokio::task::spawn_blocking(move || {
+        let manager = ProjectManager::new()?;
+        manager.export_project(&project_id, Path::new(&destination), &transfer_id, &|progress| {
+            app.emit("transfer-progress", progress).ok();
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -466,3 +662,17 @@ pub async fn add_prompt_to_history(
     manager.add_prompt_entry(&project_id, user_prompt, agent_response, files_modified)
         .map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_project_file_stays_inside_project() {
+        let dir = tempfile::tempdir().unwrap();
+        write_project_file(dir.path(), "src/components/App.tsx", "export {}").unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("src/components/App.tsx")).unwrap(), "export {}");
+        assert!(write_project_file(dir.path(), "../outside.txt", "x").is_err());
+        sync_directories(dir.path(), &["src/components/App.tsx".to_string()]).unwrap();
+    }
+}
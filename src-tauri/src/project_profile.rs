@@ -0,0 +1,429 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::filesystem::collect_source_files;
+use crate::package_manager::{self, PackageManager};
+use crate::project::{load_project_config, save_project_config, ProjectManager};
+use crate::python_envs;
+use crate::statistics;
+use crate::tasks::{self, TaskDefinition, TasksConfig};
+use crate::terminal::problem_matchers::MatcherSpec;
+use crate::testing::TestFramework;
+
+/// Kept next to `project.json`
+const PROFILE_FILE: &str = "profile.json";
+/// Enough files to rank languages; huge repos aren't walked to the end
+const MAX_SCANNED_FILES: usize = 5000;
+/// Counted by the statistics module but not what a project is "written in"
+const NON_CODE: &[&str] = &["Markdown", "JSON", "YAML", "TOML"];
+
+/// Dependency name to the framework it signals, by manifest
+const NODE_FRAMEWORKS: &[(&str, &str)] = &[
+    ("next", "Next.js"),
+    ("nuxt", "Nuxt"),
+    ("react", "React"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("@sveltejs/kit", "SvelteKit"),
+    ("@angular/core", "Angular"),
+    ("solid-js", "Solid"),
+    ("astro", "Astro"),
+    ("express", "Express"),
+    ("fastify", "Fastify"),
+    ("@nestjs/core", "NestJS"),
+    ("electron", "Electron"),
+    ("@tauri-apps/api", "Tauri"),
+    ("vite", "Vite"),
+    ("tailwindcss", "Tailwind CSS"),
+];
+const PYTHON_FRAMEWORKS: &[(&str, &str)] = &[
+    ("django", "Django"),
+    ("flask", "Flask"),
+    ("fastapi", "FastAPI"),
+    ("streamlit", "Streamlit"),
+    ("pandas", "pandas"),
+];
+const RUST_FRAMEWORKS: &[(&str, &str)] = &[
+    ("actix-web", "Actix Web"),
+    ("axum", "Axum"),
+    ("rocket", "Rocket"),
+    ("tauri", "Tauri"),
+    ("bevy", "Bevy"),
+];
+const GO_FRAMEWORKS: &[(&str, &str)] = &[
+    ("github.com/gin-gonic/gin", "Gin"),
+    ("github.com/labstack/echo", "Echo"),
+    ("github.com/gofiber/fiber", "Fiber"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageShare {
+    pub language: String,
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectScript {
+    pub name: String,
+    /// What to type to run it, e.g. `pnpm run dev` or `make build`
+    pub command: String,
+    /// `package.json`, `Makefile` or `Cargo.toml`
+    pub source: String,
+}
+
+/// What the IDE worked out about a project it didn't create,
+/// kept in `.sai-metadata/profile.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectProfile {
+    pub analyzed_at: String,
+    /// Most files first
+    pub languages: Vec<LanguageShare>,
+    pub frameworks: Vec<String>,
+    pub package_manager: Option<PackageManager>,
+    pub test_framework: Option<TestFramework>,
+    /// `.env`, `.env.example` and friends at the root; names only, never contents
+    pub env_files: Vec<String>,
+    pub scripts: Vec<ProjectScript>,
+    pub python_interpreter: Option<String>,
+    /// `.nvmrc`/`engines` requirement
+    pub node_version: Option<String>,
+    /// Tasks written to `.luciai/tasks.json` because the project had none
+    #[serde(default)]
+    pub generated_tasks: Vec<String>,
+}
+
+fn profile_path(project_path: &Path) -> PathBuf {
+    project_path.join(".sai-metadata").join(PROFILE_FILE)
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+fn languages(project_path: &Path) -> Vec<LanguageShare> {
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for file in collect_source_files(project_path, &[]).iter().take(MAX_SCANNED_FILES) {
+        let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        if let Some(language) = statistics::language_name(&ext).filter(|l| !NON_CODE.contains(l)) {
+            *counts.entry(language).or_default() += 1;
+        }
+    }
+    let mut languages: Vec<LanguageShare> =
+        counts.into_iter().map(|(language, files)| LanguageShare { language: language.to_string(), files }).collect();
+    languages.sort_by(|a, b| b.files.cmp(&a.files));
+    languages
+}
+
+fn matches_in(names: &BTreeSet<String>, table: &[(&str, &str)], frameworks: &mut Vec<String>) {
+    for (dependency, framework) in table {
+        if names.contains(*dependency) && !frameworks.iter().any(|f| f == framework) {
+            frameworks.push(framework.to_string());
+        }
+    }
+}
+
+/// Dependency names from the manifests at the root, matched against the framework tables
+fn frameworks(project_path: &Path, package_json: Option<&serde_json::Value>) -> Vec<String> {
+    let mut frameworks = Vec::new();
+
+    if let Some(package) = package_json {
+        let names: BTreeSet<String> = ["dependencies", "devDependencies"]
+            .iter()
+            .filter_map(|section| package[section].as_object())
+            .flat_map(|deps| deps.keys().cloned())
+            .collect();
+        matches_in(&names, NODE_FRAMEWORKS, &mut frameworks);
+    }
+
+    let python: String = ["requirements.txt", "pyproject.toml", "Pipfile"]
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(project_path.join(file)).ok())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .to_lowercase();
+    let python_names: BTreeSet<String> = python
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .map(String::from)
+        .collect();
+    matches_in(&python_names, PYTHON_FRAMEWORKS, &mut frameworks);
+
+    if let Ok(cargo) = std::fs::read_to_string(project_path.join("Cargo.toml")) {
+        let names: BTreeSet<String> = cargo
+            .lines()
+            .filter_map(|line| line.split_once('=').map(|(key, _)| key.trim().to_string()))
+            .collect();
+        matches_in(&names, RUST_FRAMEWORKS, &mut frameworks);
+    }
+
+    if let Ok(go_mod) = std::fs::read_to_string(project_path.join("go.mod")) {
+        let names: BTreeSet<String> = go_mod
+            .lines()
+            .filter_map(|line| line.split_whitespace().find(|w| w.contains('/')))
+            .map(|module| module.split("/v").next().unwrap_or(module).to_string())
+            .collect();
+        matches_in(&names, GO_FRAMEWORKS, &mut frameworks);
+    }
+
+    if project_path.join("pubspec.yaml").exists() {
+        frameworks.push("Flutter".to_string());
+    }
+    frameworks
+}
+
+/// Only pytest.ini is a sure sign to the test runner; an existing codebase usually says it elsewhere
+fn test_framework(project_path: &Path) -> Option<TestFramework> {
+    if let Ok(framework) = crate::testing::TestRunner::detect_framework(&project_path.to_path_buf()) {
+        return Some(framework);
+    }
+    let mentions_pytest = ["pyproject.toml", "setup.cfg", "requirements.txt", "requirements-dev.txt", "tox.ini"]
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(project_path.join(file)).ok())
+        .any(|content| content.contains("pytest"));
+    let has_junit = project_path.join("pom.xml").exists() || project_path.join("build.gradle").exists();
+    if mentions_pytest || project_path.join("conftest.py").exists() {
+        Some(TestFramework::PyTest)
+    } else if has_junit {
+        Some(TestFramework::JUnit)
+    } else {
+        None
+    }
+}
+
+fn env_files(project_path: &Path) -> Vec<String> {
+    let mut files: Vec<String> = std::fs::read_dir(project_path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_file())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name == ".env" || name.starts_with(".env."))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// Targets a person would run, from the Makefile's `name:` lines
+fn make_targets(makefile: &str) -> Vec<String> {
+    makefile
+        .lines()
+        .filter(|line| !line.starts_with(['\t', ' ', '.', '#']))
+        .filter_map(|line| line.split_once(':').filter(|(_, rest)| !rest.starts_with('=')).map(|(name, _)| name.trim()))
+        .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .map(String::from)
+        .collect()
+}
+
+fn scripts(project_path: &Path, package_json: Option<&serde_json::Value>, manager: PackageManager) -> Vec<ProjectScript> {
+    let mut scripts = Vec::new();
+    if let Some(defined) = package_json.and_then(|p| p["scripts"].as_object()) {
+        scripts.extend(defined.keys().map(|name| ProjectScript {
+            name: name.clone(),
+            command: manager.run(name, &[]).to_string(),
+            source: "package.json".to_string(),
+        }));
+    }
+    if let Ok(makefile) = std::fs::read_to_string(project_path.join("Makefile")) {
+        scripts.extend(make_targets(&makefile).into_iter().map(|name| ProjectScript {
+            command: format!("make {}", name),
+            name,
+            source: "Makefile".to_string(),
+        }));
+    }
+    if project_path.join("Cargo.toml").exists() {
+        scripts.extend(["build", "test", "run"].map(|name| ProjectScript {
+            name: name.to_string(),
+            command: format!("cargo {}", name),
+            source: "Cargo.toml".to_string(),
+        }));
+    }
+    scripts
+}
+
+pub fn analyze(project_path: &Path) -> ProjectProfile {
+    let package_json = read_json(&project_path.join("package.json"));
+    let detected_manager = package_manager::detect(project_path).map(|info| info.manager);
+    let manager = detected_manager.unwrap_or_else(|| package_manager::for_project(project_path));
+
+    ProjectProfile {
+        analyzed_at: chrono::Utc::now().to_rfc3339(),
+        languages: languages(project_path),
+        frameworks: frameworks(project_path, package_json.as_ref()),
+        package_manager: package_json.as_ref().map(|_| manager),
+        test_framework: test_framework(project_path),
+        env_files: env_files(project_path),
+        scripts: scripts(project_path, package_json.as_ref(), manager),
+        python_interpreter: python_envs::selected_interpreter(project_path).map(|(p, _)| p.to_string_lossy().to_string()),
+        node_version: crate::node_versions::required_version(project_path).map(|r| r.spec),
+        generated_tasks: Vec::new(),
+    }
+}
+
+/// Tasks for the project's scripts, with the matching problem matcher where there is one
+fn tasks_for(profile: &ProjectProfile) -> Vec<TaskDefinition> {
+    let typescript = profile.languages.iter().any(|l| l.language == "TypeScript");
+    profile
+        .scripts
+        .iter()
+        .map(|script| {
+            let matcher = match (script.source.as_str(), script.name.as_str()) {
+                ("Cargo.toml", _) => Some("$cargo"),
+                ("package.json", "build" | "typecheck") if typescript => Some("$tsc"),
+                ("package.json", "lint") => Some("$eslint"),
+                _ => None,
+            };
+            TaskDefinition {
+                name: script.name.clone(),
+                command: script.command.clone(),
+                cwd: None,
+                env: BTreeMap::new(),
+                problem_matchers: matcher.map(|m| vec![MatcherSpec::Builtin(m.to_string())]).unwrap_or_default(),
+                depends_on: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// Set up what the profile allows without overwriting anything the user configured:
+/// tasks from scripts, and the project's venv as its Python interpreter
+fn preconfigure(project_path: &Path, profile: &mut ProjectProfile) -> Result<()> {
+    let tasks_file = project_path.join(crate::project::PROJECT_CONFIG_DIR).join(tasks::CONFIG_FILE);
+    if !tasks_file.exists() && !profile.scripts.is_empty() {
+        let mut config: TasksConfig = load_project_config(project_path, tasks::CONFIG_FILE)?;
+        let mut seen = BTreeSet::new();
+        // The first script of a name wins: package.json before the Makefile
+        config.tasks = tasks_for(profile).into_iter().filter(|t| seen.insert(t.name.clone())).collect();
+        profile.generated_tasks = config.tasks.iter().map(|t| t.name.clone()).collect();
+        save_project_config(project_path, tasks::CONFIG_FILE, &config)?;
+    }
+
+    let python_config = project_path.join(crate::project::PROJECT_CONFIG_DIR).join(python_envs::CONFIG_FILE);
+    if !python_config.exists() {
+        if let Some(interpreter) = &profile.python_interpreter {
+            python_envs::select_interpreter(project_path, Some(Path::new(interpreter)))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn load(project_path: &Path) -> Option<ProjectProfile> {
+    serde_json::from_str(&std::fs::read_to_string(profile_path(project_path)).ok()?).ok()
+}
+
+fn save(project_path: &Path, profile: &ProjectProfile) -> Result<()> {
+    let path = profile_path(project_path);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(profile)?)?;
+    Ok(())
+}
+
+/// Analyze, preconfigure and save the profile
+pub fn refresh(project_path: &Path) -> Result<ProjectProfile> {
+    let mut profile = analyze(project_path);
+    preconfigure(project_path, &mut profile)?;
+    save(project_path, &profile)?;
+    Ok(profile)
+}
+
+/// The saved profile, analyzing the project the first time it is opened
+pub fn ensure(project_path: &Path) -> Result<ProjectProfile> {
+    match load(project_path) {
+        Some(profile) => Ok(profile),
+        None => refresh(project_path),
+    }
+}
+
+/// A short description of the project for the agent's system prompt
+pub fn prompt_context(profile: &ProjectProfile) -> String {
+    let mut lines = Vec::new();
+    if !profile.languages.is_empty() {
+        let languages: Vec<String> =
+            profile.languages.iter().take(5).map(|l| format!("{} ({} files)", l.language, l.files)).collect();
+        lines.push(format!("Languages: {}", languages.join(", ")));
+    }
+    if !profile.frameworks.is_empty() {
+        lines.push(format!("Frameworks: {}", profile.frameworks.join(", ")));
+    }
+    if let Some(manager) = profile.package_manager {
+        lines.push(format!("Package manager: {} (use it for install and run commands)", manager.program()));
+    }
+    if let Some(framework) = &profile.test_framework {
+        lines.push(format!("Tests: {:?}", framework));
+    }
+    if !profile.scripts.is_empty() {
+        let scripts: Vec<&str> = profile.scripts.iter().map(|s| s.command.as_str()).collect();
+        lines.push(format!("Scripts: {}", scripts.join(", ")));
+    }
+    if !profile.env_files.is_empty() {
+        lines.push(format!("Env files: {}", profile.env_files.join(", ")));
+    }
+    if let Some(node) = &profile.node_version {
+        lines.push(format!("Node version: {}", node));
+    }
+    lines.join("\n")
+}
+
+/// The profile of a project by id or path, for prompts
+pub fn prompt_for_project(project_id: &str) -> Option<String> {
+    let project_path = ProjectManager::new().ok()?.project_dir(project_id);
+    let context = prompt_context(&load(&project_path)?);
+    (!context.is_empty()).then(|| format!("About this project:\n{}", context))
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_project_profile(project_path: String) -> Result<Option<ProjectProfile>, String> {
+    Ok(load(Path::new(&project_path)))
+}
+
+/// Re-run detection, e.g. after the user adds a framework
+#[tauri::command]
+pub async fn refresh_project_profile(project_path: String) -> Result<ProjectProfile, String> {
+    tokio::task::spawn_blocking(move || refresh(Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_and_preconfigure() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.ts"), "export {}").unwrap();
+        std::fs::write(root.join("src/App.tsx"), "export {}").unwrap();
+        std::fs::write(root.join("src/legacy.js"), "").unwrap();
+        std::fs::write(root.join(".env.example"), "API_KEY=").unwrap();
+        std::fs::write(root.join("pnpm-lock.yaml"), "").unwrap();
+        std::fs::write(root.join("Makefile"), ".PHONY: deploy\nVERSION := 1\ndeploy: build\n\t./deploy.sh\n").unwrap();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"scripts":{"dev":"vite","build":"tsc && vite build"},"dependencies":{"react":"^18"},"devDependencies":{"vitest":"^1","vite":"^5"}}"#,
+        )
+        .unwrap();
+
+        let profile = refresh(root).unwrap();
+        assert_eq!(profile.languages[0].language, "TypeScript");
+        assert_eq!(profile.frameworks, vec!["React", "Vite"]);
+        assert_eq!(profile.package_manager, Some(PackageManager::Pnpm));
+        assert!(matches!(profile.test_framework, Some(TestFramework::Vitest)));
+        assert_eq!(profile.env_files, vec![".env.example"]);
+        let commands: Vec<&str> = profile.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(commands, vec!["pnpm run build", "pnpm run dev", "make deploy"]);
+
+        let tasks: TasksConfig = load_project_config(root, tasks::CONFIG_FILE).unwrap();
+        assert_eq!(tasks.tasks.len(), 3);
+        assert!(matches!(&tasks.tasks[0].problem_matchers[..], [MatcherSpec::Builtin(name)] if name == "$tsc"));
+        assert!(load(root).is_some());
+    }
+}
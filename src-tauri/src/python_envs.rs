@@ -0,0 +1,435 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::project::{load_project_config, save_project_config, PROJECT_CONFIG_DIR};
+use crate::terminal::shell::find_in_path;
+
+pub const CONFIG_FILE: &str = "python.json";
+/// Directory names checked for an existing project virtualenv, in order
+const VENV_DIRS: &[&str] = &[".venv", "venv", "env"];
+/// Versioned executables some distributions install without a plain `python3`
+const VERSIONED_NAMES: &[&str] = &["python3.13", "python3.12", "python3.11", "python3.10", "python3.9", "python3.8"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InterpreterSource {
+    /// The Windows `py` launcher's registry of installs
+    PyLauncher,
+    Pyenv,
+    System,
+    Venv,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PythonInterpreter {
+    pub path: String,
+    /// e.g. `3.12.1`; None when the executable didn't answer `--version`
+    pub version: Option<String>,
+    pub source: InterpreterSource,
+}
+
+/// `.luciai/python.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PythonEnvConfig {
+    /// Interpreter the project runs with; a venv's own python when one is active
+    #[serde(default)]
+    pub interpreter: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PythonEnvInfo {
+    pub interpreter: Option<PythonInterpreter>,
+    /// The virtualenv directory, when the interpreter belongs to one
+    pub venv: Option<String>,
+    /// Whether the choice was saved, as opposed to a venv found in the project
+    pub selected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipOutput {
+    pub project_path: String,
+    pub stream: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipInstallResult {
+    pub command: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+}
+
+/// Where a venv keeps its interpreter
+pub fn venv_python(venv: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        venv.join("Scripts").join("python.exe")
+    } else {
+        venv.join("bin").join("python")
+    }
+}
+
+/// The venv an interpreter lives in, found by the `pyvenv.cfg` every venv has at its root
+pub fn venv_of(interpreter: &Path) -> Option<PathBuf> {
+    interpreter.ancestors().skip(1).take(2).find(|dir| dir.join("pyvenv.cfg").exists()).map(Path::to_path_buf)
+}
+
+/// `Python 3.12.1` from `python --version`; Python 2 prints it to stderr
+pub fn interpreter_version(python: &Path) -> Option<String> {
+    let output = Command::new(python).arg("--version").stdin(Stdio::null()).output().ok()?;
+    let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    text.trim().strip_prefix("Python ").map(|v| v.trim().to_string())
+}
+
+/// Paths from `py -0p`, whose lines look like ` -V:3.12 *        C:\Python312\python.exe`
+fn parse_py_launcher(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| {
+            // The path starts at its drive letter; install paths may contain spaces
+            let bytes = line.as_bytes();
+            let colon = (1..bytes.len().saturating_sub(1))
+                .find(|&i| bytes[i] == b':' && bytes[i + 1] == b'\\' && bytes[i - 1].is_ascii_alphabetic())?;
+            Some(PathBuf::from(line[colon - 1..].trim()))
+        })
+        .collect()
+}
+
+fn py_launcher_interpreters() -> Vec<PathBuf> {
+    if !cfg!(target_os = "windows") {
+        return Vec::new();
+    }
+    Command::new("py")
+        .arg("-0p")
+        .stdin(Stdio::null())
+        .output()
+        .map(|output| parse_py_launcher(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+fn pyenv_interpreters() -> Vec<PathBuf> {
+    let root = std::env::var_os("PYENV_ROOT")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".pyenv")));
+    let Some(root) = root else { return Vec::new() };
+    // pyenv-win keeps its versions one level further down
+    let versions = if cfg!(target_os = "windows") { root.join("pyenv-win").join("versions") } else { root.join("versions") };
+    let Ok(entries) = std::fs::read_dir(versions) else { return Vec::new() };
+
+    let mut found: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| {
+            if cfg!(target_os = "windows") {
+                entry.path().join("python.exe")
+            } else {
+                entry.path().join("bin").join("python")
+            }
+        })
+        .filter(|path| path.exists())
+        .collect();
+    found.sort();
+    found
+}
+
+fn system_interpreters() -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = ["python3", "python"]
+        .iter()
+        .chain(VERSIONED_NAMES)
+        .filter_map(|name| find_in_path(name))
+        .collect();
+    if !cfg!(target_os = "windows") {
+        for dir in ["/usr/bin", "/usr/local/bin", "/opt/homebrew/bin"] {
+            found.extend(VERSIONED_NAMES.iter().map(|name| Path::new(dir).join(name)).filter(|p| p.exists()));
+        }
+    }
+    found
+}
+
+/// Every interpreter on the machine, plus the project's own venvs when a project is given.
+/// The same executable reached through symlinks is listed once.
+pub fn discover_interpreters(project_path: Option<&Path>) -> Vec<PythonInterpreter> {
+    let mut candidates: Vec<(PathBuf, InterpreterSource)> = Vec::new();
+    if let Some(project) = project_path {
+        candidates.extend(project_venvs(project).into_iter().map(|venv| (venv_python(&venv), InterpreterSource::Venv)));
+    }
+    candidates.extend(py_launcher_interpreters().into_iter().map(|p| (p, InterpreterSource::PyLauncher)));
+    candidates.extend(pyenv_interpreters().into_iter().map(|p| (p, InterpreterSource::Pyenv)));
+    candidates.extend(system_interpreters().into_iter().map(|p| (p, InterpreterSource::System)));
+
+    let mut seen = HashSet::new();
+    candidates
+        .into_iter()
+        // Venv pythons symlink to their base interpreter, so they are deduplicated by their own path
+        .filter(|(path, source)| {
+            let key = match source {
+                InterpreterSource::Venv => path.clone(),
+                _ => std::fs::canonicalize(path).unwrap_or_else(|_| path.clone()),
+            };
+            seen.insert(key)
+        })
+        .filter_map(|(path, source)| {
+            let version = interpreter_version(&path);
+            // A Windows Store stub answers nothing and opens the Store instead
+            if version.is_none() && source == InterpreterSource::System {
+                return None;
+            }
+            Some(PythonInterpreter { path: path.to_string_lossy().to_string(), version, source })
+        })
+        .collect()
+}
+
+/// Virtualenvs at the project root
+pub fn project_venvs(project_path: &Path) -> Vec<PathBuf> {
+    VENV_DIRS
+        .iter()
+        .map(|name| project_path.join(name))
+        .filter(|dir| dir.join("pyvenv.cfg").exists() && venv_python(dir).exists())
+        .collect()
+}
+
+/// The saved interpreter, else the first venv in the project
+pub fn selected_interpreter(project_path: &Path) -> Option<(PathBuf, bool)> {
+    let config: PythonEnvConfig = load_project_config(project_path, CONFIG_FILE).ok()?;
+    if let Some(path) = config.interpreter.map(PathBuf::from).filter(|p| p.exists()) {
+        return Some((path, true));
+    }
+    project_venvs(project_path).first().map(|venv| (venv_python(venv), false))
+}
+
+/// The interpreter for a file or directory anywhere inside a project
+pub fn interpreter_for(path: &Path) -> Option<PathBuf> {
+    for dir in path.ancestors() {
+        let has_config = dir.join(PROJECT_CONFIG_DIR).join(CONFIG_FILE).exists();
+        if has_config || !project_venvs(dir).is_empty() {
+            if let Some((interpreter, _)) = selected_interpreter(dir) {
+                return Some(interpreter);
+            }
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Environment that activates the project's venv, as its `activate` script would.
+/// Empty when the project has no venv.
+pub fn activation_env(path: &Path) -> Vec<(String, String)> {
+    let Some(venv) = interpreter_for(path).and_then(|python| venv_of(&python)) else { return Vec::new() };
+    let bin = venv_python(&venv).parent().map(Path::to_path_buf).unwrap_or_else(|| venv.clone());
+    let mut paths = vec![bin];
+    if let Some(existing) = std::env::var_os("PATH") {
+        paths.extend(std::env::split_paths(&existing));
+    }
+    let path = std::env::join_paths(paths).map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+    vec![
+        ("VIRTUAL_ENV".to_string(), venv.to_string_lossy().to_string()),
+        ("PATH".to_string(), path),
+    ]
+}
+
+pub fn select_interpreter(project_path: &Path, interpreter: Option<&Path>) -> Result<()> {
+    if let Some(interpreter) = interpreter {
+        if !interpreter.exists() {
+            anyhow::bail!("Interpreter not found: {}", interpreter.display());
+        }
+    }
+    let config = PythonEnvConfig { interpreter: interpreter.map(|p| p.to_string_lossy().to_string()) };
+    save_project_config(project_path, CONFIG_FILE, &config)
+}
+
+pub fn env_info(project_path: &Path) -> PythonEnvInfo {
+    match selected_interpreter(project_path) {
+        Some((path, selected)) => PythonEnvInfo {
+            venv: venv_of(&path).map(|v| v.to_string_lossy().to_string()),
+            interpreter: Some(PythonInterpreter {
+                version: interpreter_version(&path),
+                source: if venv_of(&path).is_some() { InterpreterSource::Venv } else { InterpreterSource::System },
+                path: path.to_string_lossy().to_string(),
+            }),
+            selected,
+        },
+        None => PythonEnvInfo { interpreter: None, venv: None, selected: false },
+    }
+}
+
+/// Create `<project>/<name>` with `python -m venv` and make it the project's interpreter
+pub fn create_venv(project_path: &Path, base: Option<&Path>, name: &str) -> Result<PythonEnvInfo> {
+    let base = match base {
+        Some(base) => base.to_path_buf(),
+        None => find_in_path("python3").or_else(|| find_in_path("python")).context("Python is not installed")?,
+    };
+    let venv = project_path.join(name);
+    if venv.exists() {
+        anyhow::bail!("{} already exists", venv.display());
+    }
+    let output = Command::new(&base)
+        .args(["-m", "venv"])
+        .arg(&venv)
+        .current_dir(project_path)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to run {}", base.display()))?;
+    if !output.status.success() {
+        anyhow::bail!("python -m venv failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    select_interpreter(project_path, Some(&venv_python(&venv)))?;
+    Ok(env_info(project_path))
+}
+
+/// `pip install` arguments for a requirements file, or the project itself when it has a pyproject.toml
+fn pip_args(project_path: &Path, requirements: Option<&str>) -> Result<Vec<String>> {
+    let mut args = vec!["-m".to_string(), "pip".to_string(), "install".to_string()];
+    match requirements {
+        Some(file) => args.extend(["-r".to_string(), file.to_string()]),
+        None if project_path.join("requirements.txt").exists() => {
+            args.extend(["-r".to_string(), "requirements.txt".to_string()])
+        }
+        None if project_path.join("pyproject.toml").exists() => args.extend(["-e".to_string(), ".".to_string()]),
+        None => anyhow::bail!("No requirements.txt or pyproject.toml in the project"),
+    }
+    Ok(args)
+}
+
+fn forward_lines<R: AsyncRead + Unpin + Send + 'static>(
+    app: tauri::AppHandle,
+    reader: R,
+    project_path: String,
+    stream: &'static str,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let output = PipOutput { project_path: project_path.clone(), stream: stream.to_string(), line };
+            app.emit("python-pip-output", output).ok();
+        }
+    })
+}
+
+/// Install requirements into the project's interpreter, streaming pip's output as
+/// `python-pip-output` events
+pub async fn install_requirements(
+    app: &tauri::AppHandle,
+    project_path: &Path,
+    requirements: Option<&str>,
+) -> Result<PipInstallResult> {
+    let (python, _) = selected_interpreter(project_path)
+        .context("No Python environment selected for this project; create or select one first")?;
+    let args = pip_args(project_path, requirements)?;
+
+    let mut child = tokio::process::Command::new(&python)
+        .args(&args)
+        .current_dir(project_path)
+        .envs(crate::network::proxy_env())
+        .env("PYTHONUNBUFFERED", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to run {}", python.display()))?;
+
+    let project = project_path.to_string_lossy().to_string();
+    let readers = [
+        child.stdout.take().map(|s| forward_lines(app.clone(), s, project.clone(), "stdout")),
+        child.stderr.take().map(|s| forward_lines(app.clone(), s, project.clone(), "stderr")),
+    ];
+    let status = child.wait().await?;
+    for reader in readers.into_iter().flatten() {
+        reader.await.ok();
+    }
+
+    Ok(PipInstallResult {
+        command: format!("{} {}", python.display(), args.join(" ")),
+        success: status.success(),
+        exit_code: status.code(),
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_python_interpreters(project_path: Option<String>) -> Result<Vec<PythonInterpreter>, String> {
+    tokio::task::spawn_blocking(move || discover_interpreters(project_path.as_deref().map(Path::new)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_python_env(project_path: String) -> Result<PythonEnvInfo, String> {
+    tokio::task::spawn_blocking(move || env_info(Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Use `interpreter` for the project, or go back to auto-detecting its venv when None
+#[tauri::command]
+pub async fn select_python_interpreter(project_path: String, interpreter: Option<String>) -> Result<PythonEnvInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        let project = Path::new(&project_path);
+        select_interpreter(project, interpreter.as_deref().map(Path::new))?;
+        Ok::<_, anyhow::Error>(env_info(project))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_python_venv(
+    project_path: String,
+    base_interpreter: Option<String>,
+    name: Option<String>,
+) -> Result<PythonEnvInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        let name = name.unwrap_or_else(|| ".venv".to_string());
+        create_venv(Path::new(&project_path), base_interpreter.as_deref().map(Path::new), &name)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn install_python_requirements(
+    app: tauri::AppHandle,
+    project_path: String,
+    requirements: Option<String>,
+) -> Result<PipInstallResult, String> {
+    install_requirements(&app, Path::new(&project_path), requirements.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_venv_selection() {
+        let project = tempfile::tempdir().unwrap();
+        let venv = project.path().join(".venv");
+        let python = venv_python(&venv);
+        std::fs::create_dir_all(python.parent().unwrap()).unwrap();
+        std::fs::write(&python, "").unwrap();
+        assert!(selected_interpreter(project.path()).is_none());
+
+        std::fs::write(venv.join("pyvenv.cfg"), "home = /usr/bin\n").unwrap();
+        assert_eq!(selected_interpreter(project.path()), Some((python.clone(), false)));
+        assert_eq!(venv_of(&python), Some(venv.clone()));
+        assert_eq!(interpreter_for(&project.path().join("src/app.py")), Some(python.clone()));
+        let env = activation_env(&project.path().join("src"));
+        assert_eq!(env[0], ("VIRTUAL_ENV".to_string(), venv.to_string_lossy().to_string()));
+
+        select_interpreter(project.path(), Some(&python)).unwrap();
+        assert_eq!(selected_interpreter(project.path()), Some((python, true)));
+
+        let launcher = " -V:3.12 *        C:\\Python312\\python.exe\n -V:3.11          C:\\Python311\\python.exe\n";
+        assert_eq!(parse_py_launcher(launcher), vec![PathBuf::from("C:\\Python312\\python.exe"), PathBuf::from("C:\\Python311\\python.exe")]);
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::path::{Component, Path, PathBuf};
+
+use crate::project::{load_project_config, save_project_config, PROJECT_CONFIG_DIR};
+
+pub const CONFIG_FILE: &str = "readonly.json";
+
+/// `.luciai/readonly.json`: paths relative to the project root, `/`-separated.
+/// A directory entry covers everything under it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ReadonlyConfig {
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadonlyPath {
+    pub path: String,
+    pub is_directory: bool,
+    /// Entries are kept when the file is deleted, e.g. a lockfile that gets regenerated
+    pub exists: bool,
+}
+
+/// A write refused because the path, or a directory above it, is marked read-only
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadOnlyError {
+    pub path: String,
+    /// The entry that covers `path`
+    pub locked_by: String,
+}
+
+impl std::fmt::Display for ReadOnlyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.ends_with(&self.locked_by) {
+            write!(f, "{} is read-only in this project", self.path)
+        } else {
+            write!(f, "{} is read-only in this project (inside {})", self.path, self.locked_by)
+        }
+    }
+}
+
+impl std::error::Error for ReadOnlyError {}
+
+/// Error for commands that write files, so the frontend can tell a read-only refusal
+/// (and offer to unlock the path) from any other failure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WriteError {
+    ReadOnly { path: String, locked_by: String, message: String },
+    Failed { message: String },
+}
+
+impl From<ReadOnlyError> for WriteError {
+    fn from(error: ReadOnlyError) -> Self {
+        WriteError::ReadOnly { message: error.to_string(), path: error.path, locked_by: error.locked_by }
+    }
+}
+
+impl From<anyhow::Error> for WriteError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<ReadOnlyError>() {
+            Ok(error) => error.into(),
+            Err(error) => WriteError::Failed { message: error.to_string() },
+        }
+    }
+}
+
+impl From<String> for WriteError {
+    fn from(message: String) -> Self {
+        WriteError::Failed { message }
+    }
+}
+
+/// `path` relative to `root`, `/`-separated with `.` and `..` resolved; None when outside
+fn relative_to(root: &Path, path: &Path) -> Option<String> {
+    let path = if path.is_absolute() { path.to_path_buf() } else { root.join(path) };
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.strip_prefix(root).ok()?.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            _ => {}
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn covers(entry: &str, relative: &str) -> bool {
+    relative == entry || relative.strip_prefix(entry).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// The nearest project above `path` that has read-only entries
+fn project_for(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join(PROJECT_CONFIG_DIR).join(CONFIG_FILE).exists())
+        .map(Path::to_path_buf)
+}
+
+/// The entry in `project_path` that makes `path` read-only
+pub fn locked_by(project_path: &Path, path: &Path) -> Option<String> {
+    let relative = relative_to(project_path, path)?;
+    let config: ReadonlyConfig = load_project_config(project_path, CONFIG_FILE).ok()?;
+    config.paths.into_iter().find(|entry| covers(entry, &relative))
+}
+
+/// Called before every IDE or agent write; paths outside any project are always writable
+pub fn check_writable(path: &Path) -> std::result::Result<(), ReadOnlyError> {
+    let Some(project_path) = project_for(path) else { return Ok(()) };
+    match locked_by(&project_path, path) {
+        Some(entry) => Err(ReadOnlyError { path: path.to_string_lossy().to_string(), locked_by: entry }),
+        None => Ok(()),
+    }
+}
+
+/// For deletes and renames: a directory is also refused when an entry inside it is read-only
+pub fn check_tree_writable(path: &Path) -> std::result::Result<(), ReadOnlyError> {
+    check_writable(path)?;
+    let Some(project_path) = project_for(path) else { return Ok(()) };
+    let Some(relative) = relative_to(&project_path, path) else { return Ok(()) };
+    let config: ReadonlyConfig = load_project_config(&project_path, CONFIG_FILE).unwrap_or_default();
+    match config.paths.into_iter().find(|entry| covers(&relative, entry)) {
+        Some(entry) => Err(ReadOnlyError { path: path.to_string_lossy().to_string(), locked_by: entry }),
+        None => Ok(()),
+    }
+}
+
+pub fn set_readonly(project_path: &Path, path: &Path, readonly: bool) -> Result<()> {
+    let relative = relative_to(project_path, path)
+        .filter(|r| !r.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("{} is not inside the project", path.display()))?;
+    let mut config: ReadonlyConfig = load_project_config(project_path, CONFIG_FILE)?;
+    config.paths.retain(|entry| entry != &relative);
+    if readonly {
+        config.paths.push(relative);
+        config.paths.sort();
+    }
+    save_project_config(project_path, CONFIG_FILE, &config)
+}
+
+pub fn list(project_path: &Path) -> Result<Vec<ReadonlyPath>> {
+    let config: ReadonlyConfig = load_project_config(project_path, CONFIG_FILE)?;
+    Ok(config
+        .paths
+        .into_iter()
+        .map(|path| {
+            let full = project_path.join(&path);
+            ReadonlyPath { is_directory: full.is_dir(), exists: full.exists(), path }
+        })
+        .collect())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn set_path_readonly(project_path: String, path: String, readonly: bool) -> Result<(), String> {
+    set_readonly(Path::new(&project_path), Path::new(&path), readonly).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_readonly_paths(project_path: String) -> Result<Vec<ReadonlyPath>, String> {
+    list(Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readonly_paths() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::create_dir_all(root.join("vendor/lib")).unwrap();
+
+        set_readonly(root, &root.join("vendor"), true).unwrap();
+        set_readonly(root, Path::new("package-lock.json"), true).unwrap();
+        assert!(set_readonly(root, Path::new("../elsewhere"), true).is_err());
+
+        let error = check_writable(&root.join("vendor/lib/a.js")).unwrap_err();
+        assert_eq!(error.locked_by, "vendor");
+        assert!(check_writable(&root.join("package-lock.json")).is_err());
+        assert!(check_writable(&root.join("vendored.js")).is_ok());
+        assert!(check_writable(&root.join("src/../vendor/b.js")).is_err());
+        assert!(check_tree_writable(&root.join("vendor/lib")).is_err());
+
+        let listed = list(root).unwrap();
+        assert_eq!(listed.len(), 2);
+        assert!(listed.iter().any(|p| p.path == "vendor" && p.is_directory));
+
+        set_readonly(root, &root.join("vendor"), false).unwrap();
+        assert!(check_writable(&root.join("vendor/lib/a.js")).is_ok());
+
+        // Deleting a directory would take the read-only entries inside it too
+        set_readonly(root, &root.join("vendor/lib"), true).unwrap();
+        assert!(check_writable(&root.join("vendor")).is_ok());
+        assert_eq!(check_tree_writable(&root.join("vendor")).unwrap_err().locked_by, "vendor/lib");
+    }
+}
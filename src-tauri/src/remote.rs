@@ -0,0 +1,449 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use futures::TryStreamExt;
+use openssh_sftp_client::error::SftpErrorKind;
+use openssh_sftp_client::metadata::MetaData;
+use openssh_sftp_client::{Sftp, SftpOptions};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::filesystem::{DirectoryListing, FileInfo};
+use crate::terminal::ssh::{run_remote_command, Connection, SshHostManager};
+
+/// Remote paths look like `ssh://<host_id>/absolute/path`
+pub const REMOTE_SCHEME: &str = "ssh://";
+
+const METADATA_TTL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteWorkspace {
+    pub host_id: String,
+    pub host_name: String,
+    pub root_uri: String,
+    pub root: DirectoryListing,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemotePath {
+    pub host_id: String,
+    pub path: String,
+}
+
+impl RemotePath {
+    pub fn to_uri(&self) -> String {
+        format!("{}{}{}", REMOTE_SCHEME, self.host_id, self.path)
+    }
+}
+
+/// Parse an `ssh://` URI, returning None for local paths
+pub fn parse_remote_path(uri: &str) -> Option<RemotePath> {
+    let rest = uri.strip_prefix(REMOTE_SCHEME)?;
+    let (host_id, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/"),
+    };
+
+    if host_id.is_empty() {
+        return None;
+    }
+
+    Some(RemotePath {
+        host_id: host_id.to_string(),
+        path: path.to_string(),
+    })
+}
+
+/// Quote a value for a POSIX shell on the remote side
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// Cached directory listings keyed by URI
+static LISTING_CACHE: Mutex<Option<HashMap<String, (Instant, DirectoryListing)>>> = Mutex::new(None);
+
+fn cached_listing(uri: &str) -> Option<DirectoryListing> {
+    let mut cache = LISTING_CACHE.lock().unwrap();
+    let cache = cache.get_or_insert_with(HashMap::new);
+
+    match cache.get(uri) {
+        Some((at, listing)) if at.elapsed() < METADATA_TTL => Some(listing.clone()),
+        _ => None,
+    }
+}
+
+fn store_listing(uri: &str, listing: &DirectoryListing) {
+    let mut cache = LISTING_CACHE.lock().unwrap();
+    cache.get_or_insert_with(HashMap::new)
+        .insert(uri.to_string(), (Instant::now(), listing.clone()));
+}
+
+/// Drop cached listings for a path and its parent directory
+fn invalidate(remote: &RemotePath) {
+    let parent = parent_of(&remote.path);
+
+    let mut cache = LISTING_CACHE.lock().unwrap();
+    if let Some(cache) = cache.as_mut() {
+        cache.remove(&remote.to_uri());
+        cache.remove(&RemotePath { host_id: remote.host_id.clone(), path: parent }.to_uri());
+    }
+}
+
+/// An SFTP session over the system ssh client, so host keys and stored identities
+/// are handled exactly as for terminals and transfers
+pub struct RemoteFileSystem {
+    host_id: String,
+    sftp: Sftp,
+    // Both must outlive the session: the ssh process and the stored key file it reads
+    _ssh: tokio::process::Child,
+    _connection: Connection,
+}
+
+fn parent_of(path: &str) -> String {
+    Path::new(path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| "/".to_string())
+}
+
+fn join(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
+fn is_not_found(error: &openssh_sftp_client::Error) -> bool {
+    matches!(error, openssh_sftp_client::Error::SftpError(SftpErrorKind::NoSuchFile, _))
+}
+
+impl RemoteFileSystem {
+    pub async fn connect(host_id: &str) -> Result<Self> {
+        let manager = SshHostManager::new()?;
+        let host = manager.get_host(host_id)?;
+        let connection = host.connection_args("-p")?;
+
+        let mut ssh = tokio::process::Command::new("ssh")
+            .args(&connection.args)
+            .arg("-s")
+            .arg(host.destination())
+            .arg("sftp")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to run ssh. Is OpenSSH installed?")?;
+
+        let stdin = ssh.stdin.take().context("Failed to open ssh stdin")?;
+        let stdout = ssh.stdout.take().context("Failed to open ssh stdout")?;
+        let sftp = Sftp::new(stdin, stdout, SftpOptions::default())
+            .await
+            .with_context(|| format!("Failed to start an SFTP session on {}", host.name))?;
+
+        Ok(Self {
+            host_id: host.id,
+            sftp,
+            _ssh: ssh,
+            _connection: connection,
+        })
+    }
+
+    fn file_info(&self, path: &str, metadata: &MetaData) -> FileInfo {
+        let remote = RemotePath { host_id: self.host_id.clone(), path: path.to_string() };
+        FileInfo {
+            name: Path::new(path).file_name().unwrap_or_default().to_string_lossy().to_string(),
+            path: remote.to_uri(),
+            is_directory: metadata.file_type().map(|t| t.is_dir()).unwrap_or(false),
+            size: metadata.len().unwrap_or(0),
+            modified: metadata.modified().map(|t| t.as_duration_since_unix_epoch().as_secs()).unwrap_or(0),
+            ignored: false,
+            lfs_pointer: false,
+        }
+    }
+
+    /// The file's bytes, unchanged
+    pub async fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        Ok(self.sftp.fs().read(path).await?.to_vec())
+    }
+
+    pub async fn write_file(&self, path: &str, content: &[u8]) -> Result<()> {
+        self.create_directory(&parent_of(path)).await?;
+        self.sftp.fs().write(path, content).await?;
+        Ok(())
+    }
+
+    /// Entries of `path` with their names, as the server reports them (symlinks aren't followed)
+    async fn read_dir(&self, path: &str) -> Result<Vec<(String, MetaData)>> {
+        let entries: Vec<_> = self.sftp.fs().open_dir(path).await?.read_dir().try_collect().await?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.filename().to_string_lossy().to_string(), entry.metadata()))
+            .filter(|(name, _)| name != "." && name != "..")
+            .collect())
+    }
+
+    pub async fn list_directory(&self, path: &str) -> Result<Vec<FileInfo>> {
+        let mut files: Vec<FileInfo> = self
+            .read_dir(path)
+            .await?
+            .iter()
+            .map(|(name, metadata)| self.file_info(&join(path, name), metadata))
+            .collect();
+
+        files.sort_by(|a, b| {
+            match (a.is_directory, b.is_directory) {
+                (true, false) => std::cmp::Ordering::Less,
+                (false, true) => std::cmp::Ordering::Greater,
+                _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            }
+        });
+
+        Ok(files)
+    }
+
+    pub async fn metadata(&self, path: &str) -> Result<FileInfo> {
+        let metadata = self.sftp.fs().metadata(path).await?;
+        Ok(self.file_info(path, &metadata))
+    }
+
+    pub async fn exists(&self, path: &str) -> Result<bool> {
+        match self.sftp.fs().metadata(path).await {
+            Ok(_) => Ok(true),
+            Err(e) if is_not_found(&e) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn delete_file(&self, path: &str) -> Result<()> {
+        match self.sftp.fs().remove_file(path).await {
+            Err(e) if !is_not_found(&e) => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Like `mkdir -p`
+    pub async fn create_directory(&self, path: &str) -> Result<()> {
+        let mut missing = Vec::new();
+        let mut current = Some(Path::new(path));
+        while let Some(dir) = current.filter(|d| !d.as_os_str().is_empty()) {
+            if self.exists(&dir.to_string_lossy()).await? {
+                break;
+            }
+            missing.push(dir.to_string_lossy().to_string());
+            current = dir.parent();
+        }
+
+        for dir in missing.iter().rev() {
+            self.sftp.fs().create_dir(dir).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove a tree bottom-up. Symlinks to directories are removed, not descended into.
+    pub async fn delete_directory(&self, path: &str) -> Result<()> {
+        let mut directories = vec![path.to_string()];
+        let mut pending = vec![path.to_string()];
+
+        while let Some(dir) = pending.pop() {
+            for (name, metadata) in self.read_dir(&dir).await? {
+                let child = join(&dir, &name);
+                if metadata.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                    directories.push(child.clone());
+                    pending.push(child);
+                } else {
+                    self.sftp.fs().remove_file(&child).await?;
+                }
+            }
+        }
+
+        // Parents were pushed before their children
+        for dir in directories.iter().rev() {
+            self.sftp.fs().remove_dir(dir).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn rename(&self, from: &str, to: &str) -> Result<()> {
+        self.sftp.fs().rename(from, to).await?;
+        Ok(())
+    }
+
+    /// SFTP has no server-side copy, so the bytes make a round trip
+    pub async fn copy_file(&self, from: &str, to: &str) -> Result<()> {
+        let content = self.read_file(from).await?;
+        self.write_file(to, &content).await
+    }
+
+    /// Entries under `path` whose name contains `pattern`, case-insensitively, skipping hidden ones
+    pub async fn search(&self, path: &str, pattern: &str, max_results: usize) -> Result<Vec<FileInfo>> {
+        let pattern = pattern.to_lowercase();
+        let mut results = Vec::new();
+        let mut pending = vec![path.to_string()];
+
+        while let Some(dir) = pending.pop() {
+            // Unreadable directories are skipped, as find would
+            let entries = match self.read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for (name, metadata) in entries {
+                if name.starts_with('.') {
+                    continue;
+                }
+                let child = join(&dir, &name);
+                if name.to_lowercase().contains(&pattern) {
+                    results.push(self.file_info(&child, &metadata));
+                    if results.len() >= max_results {
+                        return Ok(results);
+                    }
+                }
+                if metadata.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                    pending.push(child);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+// Entry points used by the filesystem and terminal commands
+
+pub async fn read_file(remote: &RemotePath) -> Result<String> {
+    let bytes = RemoteFileSystem::connect(&remote.host_id).await?.read_file(&remote.path).await?;
+    String::from_utf8(bytes).map_err(|_| anyhow::anyhow!("{} is not a UTF-8 text file", remote.to_uri()))
+}
+
+pub async fn write_file(remote: &RemotePath, content: &str) -> Result<()> {
+    RemoteFileSystem::connect(&remote.host_id).await?.write_file(&remote.path, content.as_bytes()).await?;
+    invalidate(remote);
+    Ok(())
+}
+
+pub async fn delete_file(remote: &RemotePath) -> Result<()> {
+    RemoteFileSystem::connect(&remote.host_id).await?.delete_file(&remote.path).await?;
+    invalidate(remote);
+    Ok(())
+}
+
+pub async fn create_directory(remote: &RemotePath) -> Result<()> {
+    RemoteFileSystem::connect(&remote.host_id).await?.create_directory(&remote.path).await?;
+    invalidate(remote);
+    Ok(())
+}
+
+pub async fn delete_directory(remote: &RemotePath) -> Result<()> {
+    RemoteFileSystem::connect(&remote.host_id).await?.delete_directory(&remote.path).await?;
+    invalidate(remote);
+    Ok(())
+}
+
+/// Both paths must be on the same host; moving between machines isn't supported
+fn same_host(from: &RemotePath, to: &str) -> Result<RemotePath> {
+    match parse_remote_path(to) {
+        Some(to) if to.host_id == from.host_id => Ok(to),
+        _ => anyhow::bail!("{} and {} are not on the same remote host", from.to_uri(), to),
+    }
+}
+
+pub async fn rename_path(from: &RemotePath, to: &str) -> Result<()> {
+    let to = same_host(from, to)?;
+    RemoteFileSystem::connect(&from.host_id).await?.rename(&from.path, &to.path).await?;
+    invalidate(from);
+    invalidate(&to);
+    Ok(())
+}
+
+pub async fn copy_file(from: &RemotePath, to: &str) -> Result<()> {
+    let to = same_host(from, to)?;
+    RemoteFileSystem::connect(&from.host_id).await?.copy_file(&from.path, &to.path).await?;
+    invalidate(&to);
+    Ok(())
+}
+
+pub async fn search_files(remote: &RemotePath, pattern: &str, max_results: usize) -> Result<Vec<FileInfo>> {
+    RemoteFileSystem::connect(&remote.host_id).await?.search(&remote.path, pattern, max_results).await
+}
+
+pub async fn path_exists(remote: &RemotePath) -> Result<bool> {
+    RemoteFileSystem::connect(&remote.host_id).await?.exists(&remote.path).await
+}
+
+pub async fn get_metadata(remote: &RemotePath) -> Result<FileInfo> {
+    RemoteFileSystem::connect(&remote.host_id).await?.metadata(&remote.path).await
+}
+
+pub async fn list_directory(remote: &RemotePath) -> Result<DirectoryListing> {
+    let uri = remote.to_uri();
+    if let Some(listing) = cached_listing(&uri) {
+        return Ok(listing);
+    }
+
+    let files = RemoteFileSystem::connect(&remote.host_id).await?.list_directory(&remote.path).await?;
+    let listing = DirectoryListing { path: uri.clone(), files };
+
+    store_listing(&uri, &listing);
+    Ok(listing)
+}
+
+/// Run a shell command in a remote working directory
+pub fn execute_in(remote: &RemotePath, command: &str) -> Result<std::process::Output> {
+    let manager = SshHostManager::new()?;
+    let host = manager.get_host(&remote.host_id)?;
+
+    run_remote_command(&host, &format!("cd {} && {}", shell_quote(&remote.path), command))
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn open_remote_workspace(host_id: String, root_path: String) -> Result<RemoteWorkspace, String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    let host = manager.get_host(&host_id).map_err(|e| e.to_string())?;
+
+    let remote = RemotePath {
+        host_id: host_id.clone(),
+        path: root_path,
+    };
+
+    let root = list_directory(&remote).await.map_err(|e| e.to_string())?;
+
+    tracing::info!("Opened remote workspace {}", remote.to_uri());
+
+    Ok(RemoteWorkspace {
+        host_id,
+        host_name: host.name,
+        root_uri: remote.to_uri(),
+        root,
+    })
+}
+
+#[tauri::command]
+pub async fn refresh_remote_metadata(uri: String) -> Result<(), String> {
+    let remote = parse_remote_path(&uri).ok_or("Not a remote path")?;
+    invalidate(&remote);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_path() {
+        let remote = parse_remote_path("ssh://abc/home/me/app").unwrap();
+        assert_eq!(remote.host_id, "abc");
+        assert_eq!(remote.path, "/home/me/app");
+        assert_eq!(remote.to_uri(), "ssh://abc/home/me/app");
+
+        assert_eq!(parse_remote_path("ssh://abc").unwrap().path, "/");
+        assert!(parse_remote_path("/home/me/app").is_none());
+        assert!(parse_remote_path("ssh:///etc").is_none());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
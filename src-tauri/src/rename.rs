@@ -0,0 +1,370 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tree_sitter::{Node, Point};
+
+use crate::documents::{self, read_document};
+use crate::edit_journal;
+use crate::file_locks;
+use crate::lsp::{self, uri_to_path};
+use crate::syntax::{detect_language, parser_for, SyntaxLanguage};
+
+/// Long enough for the first request to wait out indexing; the server keeps
+/// running afterwards, so later renames answer quickly
+const LSP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A file written by an edit set, for undo
+struct AppliedFile {
+    path: PathBuf,
+    original: String,
+    /// blake3 of the content written, to detect edits made since
+    written_hash: String,
+}
+
+/// The last applied edit set per owner, so an agent fix can't take over the rename undo
+static LAST_EDITS: Mutex<Option<HashMap<String, Vec<AppliedFile>>>> = Mutex::new(None);
+
+/// Replace `[start, end)` with `new_text`; zero-based lines and characters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub start_line: usize,
+    pub start_character: usize,
+    pub end_line: usize,
+    pub end_character: usize,
+    pub new_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEdit {
+    pub file: String,
+    pub edits: Vec<TextEdit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenamePlan {
+    pub old_name: String,
+    pub new_name: String,
+    /// "lsp" or "tree-sitter"
+    pub source: String,
+    pub files: Vec<FileEdit>,
+}
+
+fn identifier_at(source: &str, language: SyntaxLanguage, line: usize, character: usize) -> Result<String> {
+    let tree = parser_for(language)?.parse(source, None).context("Failed to parse file")?;
+
+    // tree-sitter columns are byte offsets
+    let line_text = source.lines().nth(line).context("Position is past the end of the file")?;
+    let column = documents::utf16_to_byte(line_text, character);
+    let point = Point::new(line, column);
+
+    let node = tree.root_node()
+        .descendant_for_point_range(point, point)
+        .context("No symbol at this position")?;
+
+    if !node.kind().ends_with("identifier") {
+        anyhow::bail!("No symbol at this position");
+    }
+    Ok(node.utf8_text(source.as_bytes())?.to_string())
+}
+
+fn identifier_edits(source: &str, language: SyntaxLanguage, old_name: &str, new_name: &str) -> Result<Vec<TextEdit>> {
+    let tree = parser_for(language)?.parse(source, None).context("Failed to parse file")?;
+    let lines: Vec<&str> = source.lines().collect();
+    let to_character = |point: Point| {
+        lines.get(point.row).map(|l| l[..point.column.min(l.len())].encode_utf16().count()).unwrap_or(point.column)
+    };
+
+    let mut edits = Vec::new();
+    let mut stack: Vec<Node> = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        if node.child_count() == 0 {
+            // Identifiers only, so strings and comments that mention the name are left alone
+            if node.kind().ends_with("identifier") && node.utf8_text(source.as_bytes()).ok() == Some(old_name) {
+                edits.push(TextEdit {
+                    start_line: node.start_position().row,
+                    start_character: to_character(node.start_position()),
+                    end_line: node.end_position().row,
+                    end_character: to_character(node.end_position()),
+                    new_text: new_name.to_string(),
+                });
+            }
+            continue;
+        }
+
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+
+    edits.sort_by_key(|e| (e.start_line, e.start_character));
+    Ok(edits)
+}
+
+/// Textual fallback: every identifier with the same name in this file only. Without
+/// a language server, same-named symbols in other files can't be told apart.
+fn tree_sitter_plan(file: &Path, line: usize, character: usize, new_name: &str) -> Result<RenamePlan> {
+    let language = detect_language(file).context("Unsupported file type for rename")?;
+    let source = read_document(file)?;
+    let old_name = identifier_at(&source, language, line, character)?;
+    let edits = identifier_edits(&source, language, &old_name, new_name)?;
+
+    Ok(RenamePlan {
+        old_name,
+        new_name: new_name.to_string(),
+        source: "tree-sitter".to_string(),
+        files: vec![FileEdit { file: file.to_string_lossy().to_string(), edits }],
+    })
+}
+
+fn parse_lsp_edit(edit: &Value) -> Option<TextEdit> {
+    let range = &edit["range"];
+    Some(TextEdit {
+        start_line: range["start"]["line"].as_u64()? as usize,
+        start_character: range["start"]["character"].as_u64()? as usize,
+        end_line: range["end"]["line"].as_u64()? as usize,
+        end_character: range["end"]["character"].as_u64()? as usize,
+        new_text: edit["newText"].as_str()?.to_string(),
+    })
+}
+
+/// Convert a WorkspaceEdit (`changes` or `documentChanges` form) to file edits
+fn parse_workspace_edit(workspace_edit: &Value) -> Vec<FileEdit> {
+    let mut files: BTreeMap<String, Vec<TextEdit>> = BTreeMap::new();
+
+    if let Some(changes) = workspace_edit["changes"].as_object() {
+        for (uri, edits) in changes {
+            let parsed = edits.as_array().into_iter().flatten().filter_map(parse_lsp_edit);
+            files.entry(uri_to_path(uri).to_string_lossy().to_string()).or_default().extend(parsed);
+        }
+    }
+
+    for change in workspace_edit["documentChanges"].as_array().into_iter().flatten() {
+        let Some(uri) = change["textDocument"]["uri"].as_str() else { continue };
+        let parsed = change["edits"].as_array().into_iter().flatten().filter_map(parse_lsp_edit);
+        files.entry(uri_to_path(uri).to_string_lossy().to_string()).or_default().extend(parsed);
+    }
+
+    files.into_iter().map(|(file, edits)| FileEdit { file, edits }).collect()
+}
+
+fn lsp_plan(project_path: &Path, file: &Path, line: usize, character: usize, new_name: &str) -> Result<RenamePlan> {
+    let source = read_document(file)?;
+    let old_name = detect_language(file)
+        .and_then(|language| identifier_at(&source, language, line, character).ok())
+        .unwrap_or_default();
+
+    let shared = lsp::shared_session(project_path, file, LSP_TIMEOUT)?;
+    let result = {
+        let mut session = shared.lock().unwrap();
+        session.open_document(file).and_then(|_| {
+            session.request("textDocument/rename", json!({
+                "textDocument": { "uri": lsp::path_to_uri(file) },
+                "position": { "line": line, "character": character },
+                "newName": new_name,
+            }))
+        })
+    };
+    // A timeout or broken pipe leaves the server in an unknown state
+    if result.is_err() {
+        lsp::discard_session(&shared);
+    }
+    let result = result?;
+
+    let files = parse_workspace_edit(&result);
+    if files.is_empty() {
+        anyhow::bail!("Language server returned no edits");
+    }
+
+    Ok(RenamePlan {
+        old_name,
+        new_name: new_name.to_string(),
+        source: "lsp".to_string(),
+        files,
+    })
+}
+
+pub fn plan_rename(project_path: &Path, file: &Path, line: usize, character: usize, new_name: &str) -> Result<RenamePlan> {
+    if new_name.is_empty() || !new_name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+        anyhow::bail!("'{}' is not a valid identifier", new_name);
+    }
+
+    match lsp_plan(project_path, file, line, character, new_name) {
+        Ok(plan) => Ok(plan),
+        Err(e) => {
+            tracing::info!("LSP rename unavailable ({}), renaming within the file only", e);
+            tree_sitter_plan(file, line, character, new_name)
+        }
+    }
+}
+
+pub fn apply_edits(content: &str, edits: &[TextEdit]) -> String {
+    let mut content = content.to_string();
+    let mut edits = edits.to_vec();
+    // Apply from the end so earlier positions stay valid
+    edits.sort_by_key(|e| std::cmp::Reverse((e.start_line, e.start_character)));
+
+    for edit in edits {
+        documents::apply_delta(&mut content, &documents::TextDelta {
+            range: Some(documents::TextRange {
+                start_line: edit.start_line,
+                start_character: edit.start_character,
+                end_line: edit.end_line,
+                end_character: edit.end_character,
+            }),
+            text: edit.new_text,
+        });
+    }
+    content
+}
+
+fn write_all(files: &[(PathBuf, String)]) -> Result<()> {
+    let result = files.iter().try_for_each(|(path, content)| {
+        std::fs::write(path, content).with_context(|| format!("Failed to write {:?}", path))?;
+        // Keep open editor buffers in step with the new disk content
+        let key = path.to_string_lossy().to_string();
+        if documents::reload(&key).is_err() {
+            tracing::debug!("{} is not open in the editor", key);
+        }
+        Ok(())
+    });
+    lsp::notify_files_changed(&files.iter().map(|(path, _)| path.clone()).collect::<Vec<_>>());
+    result
+}
+
+fn content_hash(content: &str) -> String {
+    blake3::hash(content.as_bytes()).to_hex().to_string()
+}
+
+/// Apply every file edit or none: on a failed write, already-written files are restored.
+/// Other features that change several files (e.g. agent fixes) go through here too;
+/// `undo_last` keeps one edit set per `owner` (`refactor`, `agent`). The files stay
+/// locked for `owner` until every write is done.
+pub fn apply_file_edits(files: &[FileEdit], owner: &str) -> Result<usize> {
+    let paths: Vec<PathBuf> = files.iter().map(|f| PathBuf::from(&f.file)).collect();
+    let _locks = file_locks::acquire_all(&paths, owner, file_locks::WRITE_TTL_SECS)?;
+    let mut originals = Vec::new();
+    let mut updated = Vec::new();
+
+    for file_edit in files {
+        let path = PathBuf::from(&file_edit.file);
+        crate::readonly::check_writable(&path)?;
+        let original = read_document(&path)?;
+        updated.push((path.clone(), apply_edits(&original, &file_edit.edits)));
+        originals.push((path, original));
+    }
+
+    if let Err(e) = write_all(&updated) {
+        write_all(&originals).ok();
+        return Err(e);
+    }
+    for ((path, before), (_, after)) in originals.iter().zip(&updated) {
+        edit_journal::record(path, owner, Some(before), after);
+    }
+
+    let applied = originals
+        .into_iter()
+        .zip(&updated)
+        .map(|((path, original), (_, written))| AppliedFile { path, original, written_hash: content_hash(written) })
+        .collect();
+    LAST_EDITS.lock().unwrap().get_or_insert_with(HashMap::new).insert(owner.to_string(), applied);
+    Ok(files.len())
+}
+
+pub fn apply_plan(plan: &RenamePlan) -> Result<usize> {
+    crate::checkpoints::before_file_edits(&plan.files, &format!("Rename {} to {}", plan.old_name, plan.new_name));
+    let count = apply_file_edits(&plan.files, "refactor")?;
+    tracing::info!("Renamed {} to {} in {} files", plan.old_name, plan.new_name, count);
+    Ok(count)
+}
+
+/// Restore the files `owner`'s last edit set changed. Refuses, and keeps the undo,
+/// when any of them was edited since, rather than overwrite that work.
+pub fn undo_last(owner: &str) -> Result<usize> {
+    let mut last = LAST_EDITS.lock().unwrap();
+    let applied = last.get_or_insert_with(HashMap::new).get(owner).context("Nothing to undo")?;
+
+    let changed: Vec<String> = applied
+        .iter()
+        .filter(|file| read_document(&file.path).map(|c| content_hash(&c)).ok().as_ref() != Some(&file.written_hash))
+        .map(|file| file.path.to_string_lossy().to_string())
+        .collect();
+    if !changed.is_empty() {
+        anyhow::bail!("Can't undo: changed since the edit was applied: {}", changed.join(", "));
+    }
+
+    let applied = last.as_mut().and_then(|l| l.remove(owner)).unwrap_or_default();
+    drop(last);
+    let paths: Vec<PathBuf> = applied.iter().map(|f| f.path.clone()).collect();
+    let _locks = file_locks::acquire_all(&paths, owner, file_locks::WRITE_TTL_SECS)?;
+    let originals: Vec<(PathBuf, String)> = applied.into_iter().map(|f| (f.path, f.original)).collect();
+    write_all(&originals)?;
+    Ok(originals.len())
+}
+
+// Tauri commands
+
+/// Compute the edit set for previewing; nothing is written
+#[tauri::command]
+pub async fn rename_symbol(
+    project_path: String,
+    file: String,
+    line: usize,
+    character: usize,
+    new_name: String,
+) -> Result<RenamePlan, String> {
+    tokio::task::spawn_blocking(move || {
+        plan_rename(Path::new(&project_path), Path::new(&file), line, character, &new_name)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn apply_rename(plan: RenamePlan) -> Result<usize, String> {
+    apply_plan(&plan).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn undo_rename() -> Result<usize, String> {
+    undo_last("refactor").map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_sitter_rename_skips_strings() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = edit_journal::EditJournal::at(dir.path().join("journal"));
+        edit_journal::use_dir_in_tests(dir.path().join("journal"));
+        let a = dir.path().join("a.ts");
+        let b = dir.path().join("b.ts");
+        std::fs::write(&a, "export function total(x: number) { return x; }\n").unwrap();
+        std::fs::write(&b, "import { total } from './a';\nconsole.log('total', total(1));\n").unwrap();
+
+        let plan = tree_sitter_plan(&b, 1, 22, "sum").unwrap();
+        assert_eq!(plan.old_name, "total");
+        assert_eq!(plan.files.len(), 1);
+
+        apply_plan(&plan).unwrap();
+        assert_eq!(std::fs::read_to_string(&b).unwrap(), "import { sum } from './a';\nconsole.log('total', sum(1));\n");
+        assert!(std::fs::read_to_string(&a).unwrap().contains("function total("));
+        let edits = journal.load(edit_journal::current_session()).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].source, "refactor");
+
+        // An edit made after the rename blocks the undo instead of being overwritten
+        std::fs::write(&b, "// edited\n").unwrap();
+        assert!(undo_last("refactor").is_err());
+        std::fs::write(&b, "import { sum } from './a';\nconsole.log('total', sum(1));\n").unwrap();
+        undo_last("refactor").unwrap();
+        assert!(std::fs::read_to_string(&b).unwrap().starts_with("import { total }"));
+        assert!(undo_last("refactor").is_err());
+    }
+}
@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::terminal::shell::find_in_path;
+
+/// Printed by the drivers on both stdout and stderr after every evaluation, so output
+/// on either stream is attributed to the evaluation that produced it. Code that prints
+/// without a trailing newline leaves the marker at the end of its last line.
+const DONE_MARKER: &str = "\u{0}__LUCIAI_EVAL_DONE__";
+
+const EVAL_TIMEOUT: Duration = Duration::from_secs(60);
+
+const PYTHON_DRIVER: &str = r#"import sys, json, traceback
+_globals = {"__name__": "__repl__"}
+while True:
+    line = sys.stdin.readline()
+    if not line:
+        break
+    try:
+        src = json.loads(line)["code"]
+    except Exception:
+        continue
+    try:
+        try:
+            code = compile(src, "<repl>", "eval")
+        except SyntaxError:
+            code = None
+        if code is not None:
+            result = eval(code, _globals)
+            if result is not None:
+                print(repr(result))
+        else:
+            exec(compile(src, "<repl>", "exec"), _globals)
+    except SystemExit:
+        pass
+    except BaseException:
+        traceback.print_exc()
+    sys.stdout.flush()
+    sys.stderr.flush()
+    sys.stdout.write("\x00__LUCIAI_EVAL_DONE__\n")
+    sys.stdout.flush()
+    sys.stderr.write("\x00__LUCIAI_EVAL_DONE__\n")
+    sys.stderr.flush()
+"#;
+
+// Works under both Node and Deno through the node: compatibility modules
+const JS_DRIVER: &str = r#"import vm from "node:vm";
+import readline from "node:readline";
+import util from "node:util";
+import { createRequire } from "node:module";
+
+const context = vm.createContext({
+  console, setTimeout, clearTimeout, setInterval, clearInterval, process,
+  require: createRequire(process.cwd() + "/"),
+});
+const rl = readline.createInterface({ input: process.stdin });
+let queue = Promise.resolve();
+
+rl.on("line", (line) => {
+  queue = queue.then(async () => {
+    let code;
+    try { code = JSON.parse(line).code; } catch { return; }
+    try {
+      let result = vm.runInContext(code, context, { filename: "repl" });
+      if (result && typeof result.then === "function") result = await result;
+      if (result !== undefined) console.log(util.inspect(result, { colors: false }));
+    } catch (err) {
+      console.error(err && err.stack ? err.stack : String(err));
+    }
+    process.stdout.write("\u0000__LUCIAI_EVAL_DONE__\n");
+    process.stderr.write("\u0000__LUCIAI_EVAL_DONE__\n");
+  });
+});
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ReplLanguage {
+    Node,
+    Python,
+    Deno,
+}
+
+impl ReplLanguage {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "node" | "javascript" | "js" => Some(ReplLanguage::Node),
+            "python" | "py" | "python3" => Some(ReplLanguage::Python),
+            "deno" | "typescript" | "ts" => Some(ReplLanguage::Deno),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplSessionInfo {
+    pub session_id: String,
+    pub language: ReplLanguage,
+    pub created_at: String,
+    pub eval_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplOutput {
+    pub session_id: String,
+    pub stream: String, // "stdout" or "stderr"
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ReplEvalResult {
+    pub stdout: String,
+    pub stderr: String,
+    /// The interpreter was restarted to stop the evaluation; session state is lost
+    pub timed_out: bool,
+}
+
+struct ReplProcess {
+    child: Child,
+    stdin: ChildStdin,
+    done_rx: Arc<Mutex<Receiver<ReplEvalResult>>>,
+}
+
+struct ReplSession {
+    info: ReplSessionInfo,
+    process: ReplProcess,
+}
+
+/// A line (or end-of-evaluation marker) read from one of the interpreter's streams
+enum StreamEvent {
+    Line(&'static str, String),
+    Done(&'static str),
+}
+
+// Buffers for the evaluation currently in flight
+#[derive(Default)]
+struct PendingOutput {
+    stdout: String,
+    stderr: String,
+    stdout_done: bool,
+    stderr_done: bool,
+}
+
+static REPL_SESSIONS: Mutex<Option<HashMap<String, ReplSession>>> = Mutex::new(None);
+
+fn with_sessions<T>(f: impl FnOnce(&mut HashMap<String, ReplSession>) -> T) -> T {
+    let mut sessions = REPL_SESSIONS.lock().unwrap();
+    f(sessions.get_or_insert_with(HashMap::new))
+}
+
+fn drivers_dir() -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .context("Failed to get data directory")?
+        .join(".sai-ide")
+        .join("repl");
+
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn spawn_process(app: &tauri::AppHandle, session_id: &str, language: &ReplLanguage) -> Result<ReplProcess> {
+    let dir = drivers_dir()?;
+
+    let mut cmd = match language {
+        ReplLanguage::Python => {
+            let driver = dir.join("driver.py");
+            std::fs::write(&driver, PYTHON_DRIVER)?;
+            let python = find_in_path("python3")
+                .or_else(|| find_in_path("python"))
+                .context("Python is not installed")?;
+            let mut c = Command::new(python);
+            c.arg("-u").arg(driver);
+            c
+        }
+        ReplLanguage::Node => {
+            let driver = dir.join("driver.mjs");
+            std::fs::write(&driver, JS_DRIVER)?;
+            let mut c = Command::new(find_in_path("node").context("Node.js is not installed")?);
+            c.arg(driver);
+            c
+        }
+        ReplLanguage::Deno => {
+            let driver = dir.join("driver.mjs");
+            std::fs::write(&driver, JS_DRIVER)?;
+            let mut c = Command::new(find_in_path("deno").context("Deno is not installed")?);
+            c.args(&["run", "--allow-read", "--allow-env"]).arg(driver);
+            c
+        }
+    };
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start REPL process")?;
+
+    let stdin = child.stdin.take().context("Failed to open REPL stdin")?;
+    let stdout = child.stdout.take().context("Failed to open REPL stdout")?;
+    let stderr = child.stderr.take().context("Failed to open REPL stderr")?;
+
+    let (event_tx, event_rx) = channel();
+    let (done_tx, done_rx) = channel();
+
+    spawn_reader("stdout", stdout, event_tx.clone());
+    spawn_reader("stderr", stderr, event_tx);
+    spawn_collector(app.clone(), session_id.to_string(), event_rx, done_tx);
+
+    Ok(ReplProcess {
+        child,
+        stdin,
+        done_rx: Arc::new(Mutex::new(done_rx)),
+    })
+}
+
+fn spawn_reader<R: Read + Send + 'static>(stream_name: &'static str, stream: R, events: Sender<StreamEvent>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(|l| l.ok()) {
+            let sent = match line.strip_suffix(DONE_MARKER) {
+                Some(output) => {
+                    (output.is_empty() || events.send(StreamEvent::Line(stream_name, output.to_string())).is_ok())
+                        && events.send(StreamEvent::Done(stream_name)).is_ok()
+                }
+                None => events.send(StreamEvent::Line(stream_name, line)).is_ok(),
+            };
+            if !sent {
+                break;
+            }
+        }
+    });
+}
+
+/// Streams output as `repl-output` and completes an evaluation once both streams
+/// have reached its marker
+fn spawn_collector(
+    app: tauri::AppHandle,
+    session_id: String,
+    events: Receiver<StreamEvent>,
+    done_tx: Sender<ReplEvalResult>,
+) {
+    std::thread::spawn(move || {
+        let mut pending = PendingOutput::default();
+        for event in events {
+            match event {
+                StreamEvent::Line(stream, line) => {
+                    let data = format!("{}\n", line);
+                    if stream == "stdout" {
+                        pending.stdout.push_str(&data);
+                    } else {
+                        pending.stderr.push_str(&data);
+                    }
+                    app.emit("repl-output", ReplOutput {
+                        session_id: session_id.clone(),
+                        stream: stream.to_string(),
+                        data,
+                    }).ok();
+                }
+                StreamEvent::Done(stream) => {
+                    if stream == "stdout" {
+                        pending.stdout_done = true;
+                    } else {
+                        pending.stderr_done = true;
+                    }
+                    if pending.stdout_done && pending.stderr_done {
+                        let finished = std::mem::take(&mut pending);
+                        done_tx.send(ReplEvalResult {
+                            stdout: finished.stdout,
+                            stderr: finished.stderr,
+                            timed_out: false,
+                        }).ok();
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Send code to a session and wait for the driver to report completion. An evaluation
+/// that runs past the timeout is stopped by restarting the interpreter; otherwise its
+/// late output would be read as the result of the next one.
+fn evaluate(app: &tauri::AppHandle, session_id: &str, code: &str) -> Result<ReplEvalResult> {
+    let done_rx = with_sessions(|sessions| -> Result<_> {
+        let session = sessions.get_mut(session_id).context("REPL session not found")?;
+
+        let payload = serde_json::json!({ "code": code }).to_string();
+        session.process.stdin.write_all(payload.as_bytes())?;
+        session.process.stdin.write_all(b"\n")?;
+        session.process.stdin.flush()?;
+
+        session.info.eval_count += 1;
+        Ok(session.process.done_rx.clone())
+    })?;
+
+    let received = done_rx.lock().unwrap().recv_timeout(EVAL_TIMEOUT);
+    match received {
+        Ok(result) => Ok(result),
+        Err(_) => {
+            restart(app, session_id)?;
+            Ok(ReplEvalResult {
+                timed_out: true,
+                ..Default::default()
+            })
+        }
+    }
+}
+
+fn restart(app: &tauri::AppHandle, session_id: &str) -> Result<ReplSessionInfo> {
+    with_sessions(|sessions| {
+        let session = sessions.get_mut(session_id).context("REPL session not found")?;
+        session.process.child.kill().ok();
+        session.process.child.wait().ok();
+
+        session.process = spawn_process(app, session_id, &session.info.language)?;
+        session.info.eval_count = 0;
+        Ok(session.info.clone())
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn repl_create(app: tauri::AppHandle, language: String) -> Result<ReplSessionInfo, String> {
+    let language = ReplLanguage::from_name(&language)
+        .ok_or_else(|| format!("Unsupported REPL language: {}", language))?;
+
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let process = spawn_process(&app, &session_id, &language).map_err(|e| e.to_string())?;
+
+    let info = ReplSessionInfo {
+        session_id: session_id.clone(),
+        language,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        eval_count: 0,
+    };
+
+    with_sessions(|sessions| {
+        sessions.insert(session_id, ReplSession {
+            info: info.clone(),
+            process,
+        });
+    });
+
+    tracing::info!("Created {:?} REPL session {}", info.language, info.session_id);
+    Ok(info)
+}
+
+/// Evaluate code; output is also streamed as `repl-output` events
+#[tauri::command]
+pub async fn repl_eval(app: tauri::AppHandle, session_id: String, code: String) -> Result<ReplEvalResult, String> {
+    tokio::task::spawn_blocking(move || evaluate(&app, &session_id, &code))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Restart the interpreter, discarding all session state
+#[tauri::command]
+pub async fn repl_reset(app: tauri::AppHandle, session_id: String) -> Result<ReplSessionInfo, String> {
+    restart(&app, &session_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn repl_close(session_id: String) -> Result<(), String> {
+    if let Some(mut session) = with_sessions(|sessions| sessions.remove(&session_id)) {
+        session.process.child.kill().ok();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn repl_list_sessions() -> Result<Vec<ReplSessionInfo>, String> {
+    Ok(with_sessions(|sessions| sessions.values().map(|s| s.info.clone()).collect()))
+}
@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::path::{Path, PathBuf};
+
+use crate::diagnostics::{self, Diagnostic, Severity};
+use crate::templates::runner;
+
+/// Diagnostics source for `cargo check` results
+pub const CHECK_SOURCE: &str = "cargo-check";
+
+/// `rust-toolchain.toml`, or the channel from a legacy `rust-toolchain` file
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ToolchainFile {
+    pub channel: Option<String>,
+    pub profile: Option<String>,
+    pub components: Vec<String>,
+    pub targets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrateTarget {
+    pub name: String,
+    /// `bin`, `lib`, `test`, `example`, ...
+    pub kind: Vec<String>,
+    pub src_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceCrate {
+    pub name: String,
+    pub version: String,
+    pub manifest_path: String,
+    pub edition: String,
+    pub targets: Vec<CrateTarget>,
+    /// Dependency names, dev and build dependencies included
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RustWorkspace {
+    pub root: String,
+    pub target_directory: String,
+    pub members: Vec<WorkspaceCrate>,
+    pub toolchain: Option<ToolchainFile>,
+    /// `rustc --version` in the project, which honours the toolchain file through rustup
+    pub active_toolchain: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CargoCheckResult {
+    pub success: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// `"1.77.0"` or `["rustfmt", "clippy"]` from the right-hand side of a TOML line
+fn toml_strings(value: &str) -> Vec<String> {
+    value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|item| item.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Reads the `[toolchain]` table's flat keys, which is all the format has
+pub fn parse_toolchain_file(content: &str) -> ToolchainFile {
+    let mut toolchain = ToolchainFile::default();
+    let mut in_table = false;
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.starts_with('[') && !line.contains('=') {
+            in_table = line == "[toolchain]";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=').filter(|_| in_table) else { continue };
+        let values = toml_strings(value);
+        match key.trim() {
+            "channel" => toolchain.channel = values.into_iter().next(),
+            "profile" => toolchain.profile = values.into_iter().next(),
+            "components" => toolchain.components = values,
+            "targets" => toolchain.targets = values,
+            _ => {}
+        }
+    }
+    toolchain
+}
+
+pub fn read_toolchain_file(project_path: &Path) -> Option<ToolchainFile> {
+    if let Ok(content) = std::fs::read_to_string(project_path.join("rust-toolchain.toml")) {
+        return Some(parse_toolchain_file(&content));
+    }
+    let content = std::fs::read_to_string(project_path.join("rust-toolchain")).ok()?;
+    // The legacy file is either TOML or a bare channel name
+    if content.contains("[toolchain]") {
+        Some(parse_toolchain_file(&content))
+    } else {
+        Some(ToolchainFile { channel: Some(content.trim().to_string()), ..Default::default() })
+    }
+}
+
+fn parse_metadata(json: &serde_json::Value) -> Result<(String, String, Vec<WorkspaceCrate>)> {
+    let root = json["workspace_root"].as_str().context("cargo metadata has no workspace_root")?.to_string();
+    let target_directory = json["target_directory"].as_str().unwrap_or_default().to_string();
+    let members: Vec<&str> = json["workspace_members"]
+        .as_array()
+        .map(|ids| ids.iter().filter_map(|id| id.as_str()).collect())
+        .unwrap_or_default();
+
+    let crates = json["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|package| package["id"].as_str().is_some_and(|id| members.contains(&id)))
+        .map(|package| WorkspaceCrate {
+            name: package["name"].as_str().unwrap_or_default().to_string(),
+            version: package["version"].as_str().unwrap_or_default().to_string(),
+            manifest_path: package["manifest_path"].as_str().unwrap_or_default().to_string(),
+            edition: package["edition"].as_str().unwrap_or("2015").to_string(),
+            targets: package["targets"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|target| CrateTarget {
+                    name: target["name"].as_str().unwrap_or_default().to_string(),
+                    kind: serde_json::from_value(target["kind"].clone()).unwrap_or_default(),
+                    src_path: target["src_path"].as_str().unwrap_or_default().to_string(),
+                })
+                .collect(),
+            dependencies: package["dependencies"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|dep| dep["name"].as_str().map(String::from))
+                .collect(),
+        })
+        .collect();
+    Ok((root, target_directory, crates))
+}
+
+/// The workspace as `cargo metadata` sees it, without resolving dependencies
+pub fn workspace(project_path: &Path) -> Result<RustWorkspace> {
+    let output = runner::run("cargo", &["metadata", "--format-version", "1", "--no-deps"], project_path)
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).context("Invalid cargo metadata output")?;
+    let (root, target_directory, members) = parse_metadata(&json)?;
+
+    let active_toolchain = runner::run("rustc", &["--version"], project_path)
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    Ok(RustWorkspace {
+        toolchain: read_toolchain_file(Path::new(&root)).or_else(|| read_toolchain_file(project_path)),
+        root,
+        target_directory,
+        members,
+        active_toolchain,
+    })
+}
+
+/// One `compiler-message` line from `--message-format=json`, at its primary span
+fn parse_compiler_message(line: &str, root: &Path) -> Option<Diagnostic> {
+    let json: serde_json::Value = serde_json::from_str(line).ok()?;
+    if json["reason"] != "compiler-message" {
+        return None;
+    }
+    let message = &json["message"];
+    let severity = match message["level"].as_str()? {
+        "error" | "error: internal compiler error" => Severity::Error,
+        "warning" => Severity::Warning,
+        "note" | "help" => Severity::Info,
+        // "aborting due to 2 previous errors" and friends
+        _ => return None,
+    };
+    let spans = message["spans"].as_array()?;
+    let span = spans.iter().find(|s| s["is_primary"] == true)?;
+
+    let line_start = span["line_start"].as_u64()? as u32;
+    let end_column = (span["line_end"].as_u64() == Some(line_start as u64))
+        .then(|| span["column_end"].as_u64().map(|c| c as u32))
+        .flatten();
+    let suggestions = message["children"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|child| child["spans"].as_array().into_iter().flatten())
+        .filter_map(|s| s["suggested_replacement"].as_str().map(String::from))
+        .collect();
+
+    Some(Diagnostic {
+        file: root.join(span["file_name"].as_str()?).to_string_lossy().to_string(),
+        line: line_start,
+        column: span["column_start"].as_u64().map(|c| c as u32),
+        end_column,
+        severity,
+        message: message["message"].as_str()?.to_string(),
+        source: CHECK_SOURCE.to_string(),
+        suggestions,
+    })
+}
+
+/// Run `cargo check` and publish what it finds as diagnostics
+pub fn cargo_check(app: Option<&tauri::AppHandle>, project_path: &Path) -> Result<CargoCheckResult> {
+    let output = runner::command("cargo", &["check", "--workspace", "--all-targets", "--message-format=json"], project_path)
+        .map_err(|e| anyhow::anyhow!(e))?
+        .output()
+        .context("Failed to run cargo check")?;
+
+    // Spans are relative to the workspace root, which may be above the project
+    let root = workspace(project_path).map(|w| PathBuf::from(w.root)).unwrap_or_else(|_| project_path.to_path_buf());
+    let mut found: Vec<Diagnostic> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| parse_compiler_message(line, &root))
+        .collect();
+    // A generic function used in several targets is reported once per target
+    found.dedup();
+
+    let result = CargoCheckResult {
+        success: output.status.success(),
+        errors: found.iter().filter(|d| d.severity == Severity::Error).count(),
+        warnings: found.iter().filter(|d| d.severity == Severity::Warning).count(),
+        diagnostics: found.clone(),
+    };
+    diagnostics::publish(app, CHECK_SOURCE, found);
+    Ok(result)
+}
+
+/// `cargo add`, which puts the dependency in the right table and keeps the manifest's formatting
+pub fn add_dependency(
+    project_path: &Path,
+    name: &str,
+    version: Option<&str>,
+    features: &[String],
+    dev: bool,
+) -> Result<String, String> {
+    let spec = match version {
+        Some(version) => format!("{}@{}", name, version),
+        None => name.to_string(),
+    };
+    let mut args = vec!["add".to_string(), spec];
+    if dev {
+        args.push("--dev".to_string());
+    }
+    if !features.is_empty() {
+        args.push("--features".to_string());
+        args.push(features.join(","));
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    runner::run("cargo", &args, project_path)?;
+    Ok(format!("cargo {}", args.join(" ")))
+}
+
+/// Put `name = "version"` at the end of the `[dependencies]` table. Only for when
+/// `cargo add` can't reach the registry; the entry is the same either way.
+pub fn insert_dependency(cargo_toml: &str, name: &str, version: &str) -> Result<String> {
+    let mut doc: toml_edit::DocumentMut = cargo_toml.parse().context("Failed to parse Cargo.toml")?;
+    let dependencies = doc
+        .entry("dependencies")
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .context("[dependencies] in Cargo.toml is not a table")?;
+    dependencies.insert(name, toml_edit::value(version));
+    Ok(doc.to_string())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_rust_workspace(project_path: String) -> Result<RustWorkspace, String> {
+    tokio::task::spawn_blocking(move || workspace(Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Diagnostics also arrive as a `diagnostics-updated` event from the `cargo-check` source
+#[tauri::command]
+pub async fn run_cargo_check(app: tauri::AppHandle, project_path: String) -> Result<CargoCheckResult, String> {
+    tokio::task::spawn_blocking(move || cargo_check(Some(&app), Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_cargo_dependency(
+    project_path: String,
+    name: String,
+    version: Option<String>,
+    features: Option<Vec<String>>,
+    dev: Option<bool>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        add_dependency(
+            Path::new(&project_path),
+            &name,
+            version.as_deref(),
+            &features.unwrap_or_default(),
+            dev.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toolchain_and_cargo_output() {
+        let toolchain = parse_toolchain_file(
+            "[toolchain]\nchannel = \"1.77.0\" # pinned\ncomponents = [\"rustfmt\", \"clippy\"]\nprofile = \"minimal\"\n",
+        );
+        assert_eq!(toolchain.channel.as_deref(), Some("1.77.0"));
+        assert_eq!(toolchain.components, vec!["rustfmt", "clippy"]);
+
+        let manifest = "[package]\nname = \"api\"\n\n[dependencies]\nserde = \"1\"\n\n[dev-dependencies]\n";
+        assert_eq!(
+            insert_dependency(manifest, "actix-web", "4").unwrap(),
+            "[package]\nname = \"api\"\n\n[dependencies]\nserde = \"1\"\nactix-web = \"4\"\n\n[dev-dependencies]\n"
+        );
+        assert!(insert_dependency("[package]\n", "actix-web", "4").unwrap().ends_with("[dependencies]\nactix-web = \"4\"\n"));
+        assert!(insert_dependency("dependencies = 1\n", "actix-web", "4").is_err());
+
+        let line = r#"{"reason":"compiler-message","message":{"level":"warning","message":"unused variable: `x`","spans":[{"file_name":"src/main.rs","line_start":3,"line_end":3,"column_start":9,"column_end":10,"is_primary":true}],"children":[{"spans":[{"suggested_replacement":"_x"}]}]}}"#;
+        let diagnostic = parse_compiler_message(line, Path::new("/repo")).unwrap();
+        assert_eq!((diagnostic.line, diagnostic.column, diagnostic.end_column), (3, Some(9), Some(10)));
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.suggestions, vec!["_x"]);
+        assert_eq!(diagnostic.file, Path::new("/repo").join("src/main.rs").to_string_lossy());
+    }
+}
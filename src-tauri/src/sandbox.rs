@@ -0,0 +1,385 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::node_versions;
+use crate::terminal::shell::find_in_path;
+
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SandboxLanguage {
+    JavaScript,
+    Python,
+}
+
+impl SandboxLanguage {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "javascript" | "js" | "node" => Some(SandboxLanguage::JavaScript),
+            "python" | "py" | "python3" => Some(SandboxLanguage::Python),
+            _ => None,
+        }
+    }
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            SandboxLanguage::JavaScript => "snippet.js",
+            SandboxLanguage::Python => "snippet.py",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxLimits {
+    pub timeout_ms: u64,
+    pub memory_mb: u64,
+    pub cpu_seconds: u64,
+    pub allow_network: bool,
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 10_000,
+            memory_mb: 256,
+            cpu_seconds: 5,
+            allow_network: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxRequest {
+    pub language: SandboxLanguage,
+    pub code: String,
+    pub stdin: Option<String>,
+    pub limits: Option<SandboxLimits>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxResult {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub truncated: bool,
+    pub duration_ms: u64,
+    pub network_isolated: bool,
+}
+
+impl SandboxResult {
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.exit_code == Some(0)
+    }
+}
+
+/// Runs snippets in a scratch directory with a timeout. On Linux and macOS the process
+/// also gets CPU and memory limits and, where unprivileged `unshare` works, no network.
+/// Windows only gets the timeout, a cleared environment and Node's permission model;
+/// Python snippets there run with the user's full access.
+pub struct Sandbox {
+    root: PathBuf,
+}
+
+impl Sandbox {
+    pub fn new() -> Result<Self> {
+        let root = dirs::data_dir()
+            .context("Failed to get data directory")?
+            .join(".sai-ide")
+            .join("sandbox");
+
+        std::fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Isolated virtualenv shared by all Python snippets
+    fn python_interpreter(&self) -> Result<PathBuf> {
+        let venv = self.root.join("venv");
+        let interpreter = if cfg!(target_os = "windows") {
+            venv.join("Scripts").join("python.exe")
+        } else {
+            venv.join("bin").join("python")
+        };
+
+        if !interpreter.exists() {
+            let system_python = find_in_path("python3")
+                .or_else(|| find_in_path("python"))
+                .context("Python is not installed")?;
+
+            tracing::info!("Creating sandbox virtualenv at {:?}", venv);
+            let output = Command::new(system_python)
+                .args(&["-m", "venv", "--without-pip"])
+                .arg(&venv)
+                .output()?;
+
+            if !output.status.success() {
+                anyhow::bail!("Failed to create sandbox venv: {}", String::from_utf8_lossy(&output.stderr));
+            }
+        }
+
+        Ok(interpreter)
+    }
+
+    /// The interpreter invocation, and whether the interpreter itself blocks the network
+    fn interpreter_command(
+        &self,
+        language: &SandboxLanguage,
+        script: &Path,
+        work_dir: &Path,
+        limits: &SandboxLimits,
+    ) -> Result<(Command, bool)> {
+        match language {
+            SandboxLanguage::JavaScript => {
+                let node = find_in_path("node").context("Node.js is not installed")?;
+                // Refuses Node versions without a permission model rather than run unconfined
+                let model = node_versions::permission_model(&node)?;
+                let mut cmd = Command::new(node);
+                // Permission model: only the scratch directory is readable/writable,
+                // no child processes or workers
+                // V8 reserves far more address space than it uses, so `ulimit -v`
+                // kills node at startup; cap the heap instead
+                cmd.arg(format!("--max-old-space-size={}", limits.memory_mb))
+                    .arg(model.flag)
+                    .arg(format!("--allow-fs-read={}", work_dir.display()))
+                    .arg(format!("--allow-fs-write={}", work_dir.display()));
+                if model.restricts_network && limits.allow_network {
+                    cmd.arg("--allow-net");
+                }
+                cmd.arg(script);
+                Ok((cmd, model.restricts_network && !limits.allow_network))
+            }
+            SandboxLanguage::Python => {
+                let mut cmd = Command::new(self.python_interpreter()?);
+                // -I: isolated mode, ignores PYTHON* env vars and user site-packages
+                cmd.arg("-I").arg(script);
+                Ok((cmd, false))
+            }
+        }
+    }
+
+    /// Wrap a command with OS resource limits and network isolation where available
+    fn apply_limits(&self, inner: Command, language: &SandboxLanguage, limits: &SandboxLimits) -> (Command, bool) {
+        if cfg!(target_os = "windows") {
+            // No rlimits on Windows; the wall-clock timeout still applies
+            return (inner, false);
+        }
+
+        let program = inner.get_program().to_string_lossy().to_string();
+        let args: Vec<String> = inner.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+
+        let exec_line = std::iter::once(program)
+            .chain(args)
+            .map(|a| crate::remote::shell_quote(&a))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        // Node's memory is capped by its own flag in `interpreter_command`
+        let memory = match language {
+            SandboxLanguage::Python => format!("ulimit -v {} 2>/dev/null; ", limits.memory_mb * 1024),
+            SandboxLanguage::JavaScript => String::new(),
+        };
+        let script = format!("{}ulimit -t {} 2>/dev/null; exec {}", memory, limits.cpu_seconds, exec_line);
+
+        // `unshare -rn` gives the process an empty network namespace on Linux
+        let isolate = !limits.allow_network && cfg!(target_os = "linux") && unshare_works();
+
+        let cmd = if isolate {
+            let mut c = Command::new("unshare");
+            c.args(&["-r", "-n", "sh", "-c", &script]);
+            c
+        } else {
+            let mut c = Command::new("sh");
+            c.args(&["-c", &script]);
+            c
+        };
+
+        (cmd, isolate)
+    }
+
+    pub fn run(&self, request: &SandboxRequest) -> Result<SandboxResult> {
+        let limits = request.limits.clone().unwrap_or_default();
+
+        let work_dir = self.root.join("runs").join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&work_dir)?;
+
+        let script = work_dir.join(request.language.file_name());
+        std::fs::write(&script, &request.code)?;
+
+        let (inner, interpreter_isolated) = self.interpreter_command(&request.language, &script, &work_dir, &limits)?;
+        let (mut cmd, namespace_isolated) = self.apply_limits(inner, &request.language, &limits);
+        let network_isolated = namespace_isolated || interpreter_isolated;
+
+        // Its own process group, so a timeout also kills whatever the snippet forked
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+        #[cfg(windows)]
+        std::os::windows::process::CommandExt::creation_flags(&mut cmd, 0x0000_0200); // CREATE_NEW_PROCESS_GROUP
+
+        cmd.current_dir(&work_dir)
+            .env_clear()
+            .env("PATH", std::env::var("PATH").unwrap_or_default())
+            .env("HOME", &work_dir)
+            .stdin(if request.stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // Windows processes can't start without SYSTEMROOT (Winsock and crypto need it)
+        if let Some(system_root) = std::env::var_os("SYSTEMROOT").filter(|_| cfg!(target_os = "windows")) {
+            cmd.env("SYSTEMROOT", system_root);
+        }
+
+        if !limits.allow_network && !network_isolated {
+            // Best effort: route HTTP clients to a dead proxy
+            cmd.env("HTTP_PROXY", "http://127.0.0.1:9")
+                .env("HTTPS_PROXY", "http://127.0.0.1:9")
+                .env("NO_PROXY", "");
+        }
+
+        let started = Instant::now();
+        let mut child = cmd.spawn().context("Failed to start sandboxed process")?;
+
+        // Written from its own thread: a large input would otherwise block once the
+        // child stops reading to write output nobody is draining yet
+        let stdin_writer = match (request.stdin.clone(), child.stdin.take()) {
+            (Some(input), Some(mut stdin)) => Some(std::thread::spawn(move || {
+                use std::io::Write;
+                stdin.write_all(input.as_bytes()).ok();
+            })),
+            _ => None,
+        };
+
+        let stdout_reader = read_capped(child.stdout.take());
+        let stderr_reader = read_capped(child.stderr.take());
+
+        let deadline = Duration::from_millis(limits.timeout_ms);
+        let mut timed_out = false;
+
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break Some(status);
+            }
+            if started.elapsed() >= deadline {
+                kill_process_tree(&mut child);
+                child.wait().ok();
+                timed_out = true;
+                break None;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+
+        if let Some(writer) = stdin_writer {
+            writer.join().ok();
+        }
+        let (stdout, stdout_truncated) = stdout_reader.join().unwrap_or_default();
+        let (stderr, stderr_truncated) = stderr_reader.join().unwrap_or_default();
+
+        std::fs::remove_dir_all(&work_dir).ok();
+
+        Ok(SandboxResult {
+            stdout,
+            stderr,
+            exit_code: status.and_then(|s| s.code()),
+            timed_out,
+            truncated: stdout_truncated || stderr_truncated,
+            duration_ms: started.elapsed().as_millis() as u64,
+            network_isolated,
+        })
+    }
+}
+
+/// Unprivileged user namespaces are often disabled (Debian's sysctl, hardened kernels,
+/// containers); there `unshare -r` exists but every run would fail, so probe once
+fn unshare_works() -> bool {
+    static WORKS: OnceLock<bool> = OnceLock::new();
+    *WORKS.get_or_init(|| {
+        let works = find_in_path("unshare").is_some()
+            && Command::new("unshare")
+                .args(["-r", "-n", "true"])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|s| s.success())
+                .unwrap_or(false);
+        if !works {
+            tracing::warn!("unshare can't create a network namespace; sandbox network isolation is best effort");
+        }
+        works
+    })
+}
+
+fn kill_process_tree(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    let status = Command::new("kill").args(["-KILL", "--", &format!("-{}", child.id())]).status();
+    #[cfg(windows)]
+    let status = Command::new("taskkill").args(["/T", "/F", "/PID", &child.id().to_string()]).status();
+    if let Err(e) = status {
+        tracing::warn!("Failed to stop sandbox process group {}: {}", child.id(), e);
+    }
+    child.kill().ok();
+}
+
+fn read_capped<R: Read + Send + 'static>(stream: Option<R>) -> std::thread::JoinHandle<(String, bool)> {
+    std::thread::spawn(move || {
+        let mut stream = match stream {
+            Some(s) => s,
+            None => return (String::new(), false),
+        };
+
+        let mut collected = Vec::new();
+        let mut buf = [0u8; 4096];
+        let mut truncated = false;
+
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            let room = MAX_OUTPUT_BYTES.saturating_sub(collected.len());
+            if n > room {
+                truncated = true;
+            }
+            collected.extend_from_slice(&buf[..n.min(room)]);
+        }
+
+        (String::from_utf8_lossy(&collected).to_string(), truncated)
+    })
+}
+
+/// Run a generated snippet and report whether it executed cleanly
+pub fn verify_snippet(code: &str, language: &str) -> Result<SandboxResult> {
+    let language = SandboxLanguage::from_name(language)
+        .with_context(|| format!("Sandbox does not support {}", language))?;
+
+    Sandbox::new()?.run(&SandboxRequest {
+        language,
+        code: code.to_string(),
+        stdin: None,
+        limits: None,
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn run_in_sandbox(request: SandboxRequest) -> Result<SandboxResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let sandbox = Sandbox::new()?;
+        sandbox.run(&request)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn verify_code_snippet(code: String, language: String) -> Result<SandboxResult, String> {
+    tokio::task::spawn_blocking(move || verify_snippet(&code, &language))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use xcap::image::{imageops, RgbaImage};
+
+use crate::i18n::t_args;
+
+/// Most recent capture, attached to bug reports
+static LAST_SCREENSHOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Area to capture in physical pixels, relative to the window's top-left corner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Screenshot {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub copied_to_clipboard: bool,
+    pub captured_at: String,
+}
+
+pub fn last_screenshot() -> Option<PathBuf> {
+    LAST_SCREENSHOT.lock().unwrap().clone().filter(|p| p.exists())
+}
+
+/// xcap measures macOS monitors in points and every other platform in pixels
+fn monitor_unit(scale: f64) -> f64 {
+    if cfg!(target_os = "macos") {
+        scale
+    } else {
+        1.0
+    }
+}
+
+/// Wayland doesn't tell a window where it is on screen
+fn position_unknown() -> bool {
+    cfg!(target_os = "linux")
+        && std::env::var_os("WAYLAND_DISPLAY").is_some()
+        && std::env::var("GDK_BACKEND").map(|b| b != "x11").unwrap_or(true)
+}
+
+/// Capture the monitor under `center` (xcap units) through the OS capture API and crop it
+/// to `rect` (physical pixels in screen coordinates); `None` keeps the whole monitor
+fn capture(center: (i32, i32), unit: f64, rect: Option<(i32, i32, u32, u32)>) -> Result<RgbaImage> {
+    let monitor = xcap::Monitor::from_point(center.0, center.1).context("No monitor found under the window")?;
+    let image = monitor.capture_image().context("Failed to capture the screen")?;
+
+    let (x, y, width, height) = match rect {
+        Some(rect) => rect,
+        None => return Ok(image),
+    };
+    // Captured images are always in pixels, whatever unit the monitor is measured in
+    let left = (x - (monitor.x() as f64 * unit) as i32).max(0) as u32;
+    let top = (y - (monitor.y() as f64 * unit) as i32).max(0) as u32;
+    let width = width.min(image.width().saturating_sub(left));
+    let height = height.min(image.height().saturating_sub(top));
+    if width == 0 || height == 0 {
+        anyhow::bail!("The window is not on the captured screen");
+    }
+
+    Ok(imageops::crop_imm(&image, left, top, width, height).to_image())
+}
+
+fn copy_image_to_clipboard(image: &RgbaImage) -> Result<()> {
+    arboard::Clipboard::new()?.set_image(arboard::ImageData {
+        width: image.width() as usize,
+        height: image.height() as usize,
+        bytes: image.as_raw().as_slice().into(),
+    })?;
+    Ok(())
+}
+
+// Tauri commands
+
+/// Capture a window (or a region of it). Saved to `save_path`, or the app's
+/// screenshots folder when none is given. On Wayland, where a window can't learn its
+/// position, the whole monitor it is on is captured instead.
+#[tauri::command]
+pub async fn capture_window_screenshot(
+    app: AppHandle,
+    window_label: String,
+    region: Option<CaptureRegion>,
+    save_path: Option<String>,
+    copy_to_clipboard: Option<bool>,
+) -> Result<Screenshot, String> {
+    let window = app
+        .get_webview_window(&window_label)
+        .ok_or_else(|| t_args("error-window-not-found", &[("label", &window_label)]))?;
+
+    let position = window.inner_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "The window is not on any monitor".to_string())?;
+
+    let rect = if position_unknown() {
+        if region.is_some() {
+            tracing::warn!("Capturing the whole monitor; window regions aren't available on Wayland");
+        }
+        None
+    } else {
+        Some(match region {
+            Some(region) => (
+                position.x + region.x,
+                position.y + region.y,
+                region.width.min(size.width),
+                region.height.min(size.height),
+            ),
+            None => (position.x, position.y, size.width, size.height),
+        })
+    };
+
+    let unit = monitor_unit(monitor.scale_factor());
+    let center = (
+        ((monitor.position().x + monitor.size().width as i32 / 2) as f64 / unit) as i32,
+        ((monitor.position().y + monitor.size().height as i32 / 2) as f64 / unit) as i32,
+    );
+
+    let now = chrono::Local::now();
+    let path = match save_path {
+        Some(path) => PathBuf::from(path),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("screenshots")
+            .join(format!("screenshot-{}.png", now.format("%Y%m%d-%H%M%S"))),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let capture_path = path.clone();
+    let image = tokio::task::spawn_blocking(move || {
+        let image = capture(center, unit, rect)?;
+        image.save(&capture_path).with_context(|| format!("Failed to save {:?}", capture_path))?;
+        Ok::<_, anyhow::Error>(image)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Screenshot failed: {}", e))?;
+    let (width, height) = image.dimensions();
+
+    let copied_to_clipboard = copy_to_clipboard.unwrap_or(false);
+    if copied_to_clipboard {
+        copy_image_to_clipboard(&image).map_err(|e| format!("Failed to copy screenshot: {}", e))?;
+    }
+
+    *LAST_SCREENSHOT.lock().unwrap() = Some(path.clone());
+    tracing::info!("Captured {}x{} screenshot of '{}' to {:?}", width, height, window_label, path);
+
+    Ok(Screenshot {
+        path: path.to_string_lossy().to_string(),
+        width,
+        height,
+        copied_to_clipboard,
+        captured_at: now.to_rfc3339(),
+    })
+}
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::hardware::{self, HardwareProfile};
+use crate::llm::{OllamaClient, PullProgress};
+use crate::preferences::{PreferencesManager, UserPreferences};
+
+/// Download started by the wizard, kept so the UI can resume showing it
+static SETUP_PULL: Mutex<Option<SetupPullState>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelRecommendation {
+    pub model: String,
+    pub download_gb: f32,
+    pub reason: String,
+    /// Smaller models that will also run, largest first
+    pub alternatives: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetupHardware {
+    pub hardware: HardwareProfile,
+    pub recommendation: ModelRecommendation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetupPullState {
+    pub model: String,
+    pub status: String,
+    pub completed: Option<u64>,
+    pub total: Option<u64>,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetupStatus {
+    pub setup_complete: bool,
+    pub pull: Option<SetupPullState>,
+}
+
+/// Choices collected by the wizard, applied on top of the defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetupOptions {
+    pub default_model: Option<String>,
+    pub project_directory: Option<String>,
+    pub theme: Option<String>,
+    pub locale: Option<String>,
+}
+
+pub fn recommend_model(hardware: &HardwareProfile) -> ModelRecommendation {
+    let settings = hardware::recommend_llm_settings(hardware);
+    let memory_mb = hardware.model_memory_mb();
+
+    let source = if hardware.unified_memory {
+        "unified memory"
+    } else if hardware.gpus.iter().any(|g| g.vram_mb.is_some()) {
+        "GPU memory"
+    } else {
+        "system RAM (CPU inference)"
+    };
+
+    ModelRecommendation {
+        model: settings.model,
+        download_gb: settings.download_gb,
+        reason: format!("{:.1} GB of {} available for the model", memory_mb as f64 / 1024.0, source),
+        alternatives: settings.alternatives,
+    }
+}
+
+fn update_pull(f: impl FnOnce(&mut SetupPullState)) -> Option<SetupPullState> {
+    let mut guard = SETUP_PULL.lock().unwrap();
+    let state = guard.as_mut()?;
+    f(state);
+    Some(state.clone())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_setup_status() -> Result<SetupStatus, String> {
+    let manager = PreferencesManager::new().map_err(|e| e.to_string())?;
+    let setup_complete = manager.load().map(|p| p.setup_complete).unwrap_or(false);
+
+    Ok(SetupStatus {
+        setup_complete,
+        pull: SETUP_PULL.lock().unwrap().clone(),
+    })
+}
+
+#[tauri::command]
+pub async fn detect_setup_hardware() -> Result<SetupHardware, String> {
+    let hardware = tokio::task::spawn_blocking(hardware::detect).await.map_err(|e| e.to_string())?;
+    let recommendation = recommend_model(&hardware);
+    Ok(SetupHardware { hardware, recommendation })
+}
+
+/// Start downloading a model in the background. Progress is emitted as
+/// `setup-model-pull-progress` and can be re-read with `get_setup_status`.
+#[tauri::command]
+pub async fn start_setup_model_pull(app: AppHandle, model: String) -> Result<(), String> {
+    {
+        let mut pull = SETUP_PULL.lock().unwrap();
+        if pull.as_ref().map_or(false, |p| !p.done) {
+            return Err("A model download is already in progress".to_string());
+        }
+        *pull = Some(SetupPullState {
+            model: model.clone(),
+            status: "starting".to_string(),
+            completed: None,
+            total: None,
+            done: false,
+            error: None,
+        });
+    }
+
+    let base_url = PreferencesManager::new()
+        .and_then(|m| m.load())
+        .map(|p| p.llm.base_url)
+        .unwrap_or_else(|_| "http://localhost:11434".to_string());
+
+    tokio::spawn(async move {
+        let result = OllamaClient::new(base_url)
+            .pull_model(&model, |progress: PullProgress| {
+                if let Some(state) = update_pull(|s| {
+                    s.status = progress.status;
+                    s.completed = progress.completed.or(s.completed);
+                    s.total = progress.total.or(s.total);
+                }) {
+                    app.emit("setup-model-pull-progress", &state).ok();
+                }
+            })
+            .await;
+
+        let state = update_pull(|s| {
+            s.done = true;
+            match &result {
+                Ok(()) => s.status = "success".to_string(),
+                Err(e) => s.error = Some(e.to_string()),
+            }
+        });
+        if let Some(state) = state {
+            app.emit("setup-model-pull-progress", &state).ok();
+        }
+    });
+
+    Ok(())
+}
+
+/// Create the projects folder, defaulting to the one in the current preferences
+#[tauri::command]
+pub async fn create_default_project_directory(path: Option<String>) -> Result<String, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(UserPreferences::default().projects.default_directory),
+    };
+    std::fs::create_dir_all(&path).map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Write the initial preferences in one step and mark setup as done
+#[tauri::command]
+pub async fn complete_setup(options: SetupOptions) -> Result<UserPreferences, String> {
+    let manager = PreferencesManager::new().map_err(|e| e.to_string())?;
+    let mut prefs = manager.load().unwrap_or_default();
+
+    if let Some(model) = options.default_model {
+        prefs.llm.default_model = model;
+    }
+    if let Some(directory) = options.project_directory {
+        std::fs::create_dir_all(&directory).map_err(|e| format!("Failed to create {}: {}", directory, e))?;
+        prefs.projects.default_directory = directory;
+    }
+    if let Some(theme) = options.theme {
+        prefs.ui.theme = theme;
+    }
+    if let Some(locale) = options.locale {
+        prefs.ui.locale = crate::i18n::set_current_locale(&locale);
+    }
+    prefs.setup_complete = true;
+
+    manager.save(&prefs).map_err(|e| e.to_string())?;
+    tracing::info!("First-run setup complete with model {}", prefs.llm.default_model);
+    Ok(prefs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(total_memory_mb: u64, vram_mb: Option<u64>) -> HardwareProfile {
+        HardwareProfile {
+            cpu_brand: String::new(),
+            physical_cores: 4,
+            logical_cpus: 8,
+            total_memory_mb,
+            available_memory_mb: total_memory_mb,
+            gpus: vram_mb
+                .map(|vram| vec![hardware::GpuInfo { vendor: "NVIDIA".into(), name: "RTX".into(), vram_mb: Some(vram) }])
+                .unwrap_or_default(),
+            unified_memory: false,
+            os: "linux".into(),
+            arch: "x86_64".into(),
+        }
+    }
+
+    #[test]
+    fn test_recommend_model() {
+        assert_eq!(recommend_model(&profile(8 * 1024, None)).model, "gemma2:2b");
+        assert_eq!(recommend_model(&profile(16 * 1024, None)).model, "llama3.2:3b");
+
+        let gpu = recommend_model(&profile(16 * 1024, Some(24 * 1024)));
+        assert_eq!(gpu.model, "deepseek-coder-v2:16b");
+        assert_eq!(gpu.alternatives.len(), 3);
+    }
+}
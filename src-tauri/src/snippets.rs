@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context};
 use std::path::PathBuf;
 use std::collections::HashMap;
+use std::sync::Mutex;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -350,69 +351,70 @@ export const ${1:ComponentName}: React.FC<${1:ComponentName}Props> = (props) =>
     }
 }
 
+/// Created on first use; creating it writes the default snippet file if missing
+static SNIPPET_MANAGER: Mutex<Option<SnippetManager>> = Mutex::new(None);
+
+fn with_snippets<T>(f: impl FnOnce(&SnippetManager) -> Result<T>) -> Result<T, String> {
+    let mut guard = SNIPPET_MANAGER.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.is_none() {
+        let manager = crate::profiler::time_startup_phase("snippets", SnippetManager::new);
+        *guard = Some(manager.map_err(|e| e.to_string())?);
+    }
+    f(guard.as_ref().unwrap()).map_err(|e| e.to_string())
+}
+
 // Tauri commands
 #[tauri::command]
 pub async fn create_snippet(snippet: CodeSnippet) -> Result<CodeSnippet, String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.create_snippet(snippet).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.create_snippet(snippet))
 }
 
 #[tauri::command]
 pub async fn update_snippet(snippet: CodeSnippet) -> Result<CodeSnippet, String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.update_snippet(snippet).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.update_snippet(snippet))
 }
 
 #[tauri::command]
 pub async fn delete_snippet(snippet_id: String) -> Result<(), String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.delete_snippet(&snippet_id).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.delete_snippet(&snippet_id))
 }
 
 #[tauri::command]
 pub async fn get_snippet(snippet_id: String) -> Result<Option<CodeSnippet>, String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.get_snippet(&snippet_id).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.get_snippet(&snippet_id))
 }
 
 #[tauri::command]
 pub async fn list_snippets() -> Result<Vec<CodeSnippet>, String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.list_snippets().map_err(|e| e.to_string())
+    with_snippets(|manager| manager.list_snippets())
 }
 
 #[tauri::command]
 pub async fn search_snippets(query: String) -> Result<Vec<CodeSnippet>, String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.search_snippets(&query).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.search_snippets(&query))
 }
 
 #[tauri::command]
 pub async fn filter_snippets_by_language(language: String) -> Result<Vec<CodeSnippet>, String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.filter_by_language(&language).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.filter_by_language(&language))
 }
 
 #[tauri::command]
 pub async fn increment_snippet_usage(snippet_id: String) -> Result<(), String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.increment_usage(&snippet_id).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.increment_usage(&snippet_id))
 }
 
 #[tauri::command]
 pub async fn get_most_used_snippets(limit: usize) -> Result<Vec<CodeSnippet>, String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.get_most_used(limit).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.get_most_used(limit))
 }
 
 #[tauri::command]
 pub async fn export_snippets(path: String) -> Result<(), String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.export_snippets(&PathBuf::from(path)).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.export_snippets(&PathBuf::from(path)))
 }
 
 #[tauri::command]
 pub async fn import_snippets(path: String, merge: bool) -> Result<usize, String> {
-    let manager = SnippetManager::new().map_err(|e| e.to_string())?;
-    manager.import_snippets(&PathBuf::from(path), merge).map_err(|e| e.to_string())
+    with_snippets(|manager| manager.import_snippets(&PathBuf::from(path), merge))
 }
@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::terminal::shell::find_in_path;
+
+/// whisper.cpp's CLI has been renamed a few times; packagers use all of these
+const WHISPER_BINARIES: &[&str] = &["whisper-cli", "whisper-cpp", "whisper.cpp"];
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+const DEFAULT_MODEL: &str = "base";
+pub const WHISPER_MODELS: &[&str] = &["tiny", "base", "small", "medium", "large-v3"];
+
+/// Recordings are cut off here so a forgotten mic doesn't fill the disk
+const MAX_RECORDING: Duration = Duration::from_secs(300);
+
+lazy_static::lazy_static! {
+    /// A segment line from whisper.cpp: `[00:00:00.000 --> 00:00:02.500]   Hello there`
+    static ref SEGMENT_LINE: regex::Regex =
+        regex::Regex::new(r"^\[(\d+):(\d+):(\d+)\.(\d+) --> [\d:.]+\]\s*(.*)$").unwrap();
+    static ref DETECTED_LANGUAGE: regex::Regex =
+        regex::Regex::new(r"auto-detected language: (\w+)").unwrap();
+}
+
+struct Recording {
+    child: Child,
+    path: PathBuf,
+    started: Instant,
+}
+
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeechStatus {
+    pub recorder_available: bool,
+    pub whisper_available: bool,
+    pub model: Option<String>,
+    pub recording: bool,
+}
+
+/// Emitted as `speech-transcript` while whisper works through the recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub text: String,
+    /// Everything transcribed so far, this segment included
+    pub partial_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    pub text: String,
+    /// Detected by whisper, e.g. "en"
+    pub language: Option<String>,
+    pub audio_ms: u64,
+    pub transcribe_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDownloadProgress {
+    pub model: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+fn speech_dir() -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .context("Failed to get data directory")?
+        .join(".sai-ide")
+        .join("speech");
+
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn model_path(model: &str) -> Result<PathBuf> {
+    Ok(speech_dir()?.join(format!("ggml-{}.bin", model)))
+}
+
+/// The largest downloaded model, which transcribes best
+fn installed_model() -> Result<Option<PathBuf>> {
+    for model in WHISPER_MODELS.iter().rev() {
+        let path = model_path(model)?;
+        if path.is_file() {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
+fn whisper_binary() -> Option<PathBuf> {
+    WHISPER_BINARIES.iter().find_map(|name| find_in_path(name))
+}
+
+/// ffmpeg input arguments for the default microphone
+#[cfg(target_os = "macos")]
+fn microphone_input(_ffmpeg: &Path) -> Result<Vec<String>> {
+    Ok(vec!["-f".into(), "avfoundation".into(), "-i".into(), ":default".into()])
+}
+
+#[cfg(target_os = "windows")]
+fn microphone_input(ffmpeg: &Path) -> Result<Vec<String>> {
+    // dshow has no "default" device; take the first audio device it lists
+    let output = Command::new(ffmpeg)
+        .args(["-hide_banner", "-list_devices", "true", "-f", "dshow", "-i", "dummy"])
+        .output()
+        .context("Failed to list audio devices")?;
+    let listing = String::from_utf8_lossy(&output.stderr);
+    let device = listing
+        .lines()
+        .filter(|line| line.contains("(audio)"))
+        .find_map(|line| line.split('"').nth(1))
+        .context("No microphone found")?;
+    Ok(vec!["-f".into(), "dshow".into(), "-i".into(), format!("audio={}", device)])
+}
+
+#[cfg(target_os = "linux")]
+fn microphone_input(_ffmpeg: &Path) -> Result<Vec<String>> {
+    // PulseAudio (and PipeWire's pulse shim) routes "default" to the chosen mic
+    let backend = if find_in_path("pactl").is_some() { "pulse" } else { "alsa" };
+    Ok(vec!["-f".into(), backend.into(), "-i".into(), "default".into()])
+}
+
+/// Start recording the default microphone to a 16 kHz mono WAV, the format whisper expects
+pub fn start_recording() -> Result<()> {
+    let mut recording = RECORDING.lock().unwrap_or_else(|e| e.into_inner());
+    if recording.is_some() {
+        anyhow::bail!("Already recording");
+    }
+    // Fail before recording rather than after the user has finished speaking
+    whisper_binary().context("whisper.cpp is not installed")?;
+    installed_model()?.context("No Whisper model downloaded")?;
+
+    let ffmpeg = find_in_path("ffmpeg").context("ffmpeg is needed to record audio")?;
+    let path = speech_dir()?.join(format!("{}.wav", uuid::Uuid::new_v4()));
+    let child = Command::new(&ffmpeg)
+        .args(["-hide_banner", "-loglevel", "error", "-y"])
+        .args(microphone_input(&ffmpeg)?)
+        .args(["-ac", "1", "-ar", "16000", "-t"])
+        .arg(MAX_RECORDING.as_secs().to_string())
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start recording")?;
+
+    *recording = Some(Recording { child, path, started: Instant::now() });
+    Ok(())
+}
+
+/// Stop the recorder and wait for it to finish writing the file
+fn finish_recording() -> Result<(PathBuf, u64)> {
+    let Recording { mut child, path, started } = RECORDING.lock().unwrap_or_else(|e| e.into_inner()).take().context("Not recording")?;
+    let audio_ms = started.elapsed().min(MAX_RECORDING).as_millis() as u64;
+
+    // "q" lets ffmpeg close the WAV cleanly; killing it would leave a bad header
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(b"q").ok();
+    }
+    let output = child.wait_with_output()?;
+    if !path.is_file() {
+        anyhow::bail!("Recording failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok((path, audio_ms))
+}
+
+pub fn cancel_recording() -> Result<()> {
+    let (path, _) = finish_recording()?;
+    std::fs::remove_file(path).ok();
+    Ok(())
+}
+
+fn parse_segment(line: &str) -> Option<(u64, String)> {
+    let captures = SEGMENT_LINE.captures(line.trim())?;
+    let number = |i: usize| captures[i].parse::<u64>().unwrap_or(0);
+    let start_ms = ((number(1) * 60 + number(2)) * 60 + number(3)) * 1000 + number(4);
+    Some((start_ms, captures[5].trim().to_string()))
+}
+
+/// Transcribe a WAV file, language auto-detected, calling `on_segment` as whisper
+/// prints each segment
+pub fn transcribe(path: &Path, on_segment: impl Fn(&TranscriptSegment)) -> Result<(String, Option<String>)> {
+    let whisper = whisper_binary().context("whisper.cpp is not installed")?;
+    let model = installed_model()?.context("No Whisper model downloaded")?;
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4).min(8);
+
+    let mut child = Command::new(whisper)
+        .arg("-m")
+        .arg(&model)
+        .arg("-f")
+        .arg(path)
+        .args(["-l", "auto", "-t", &threads.to_string()])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start whisper.cpp")?;
+
+    let stderr = child.stderr.take().context("Failed to open whisper stderr")?;
+    let stderr_reader = std::thread::spawn(move || {
+        let mut text = String::new();
+        for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    });
+
+    let mut text = String::new();
+    let stdout = child.stdout.take().context("Failed to open whisper stdout")?;
+    for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+        let Some((start_ms, segment)) = parse_segment(&line) else { continue };
+        if segment.is_empty() || segment == "[BLANK_AUDIO]" {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&segment);
+        on_segment(&TranscriptSegment { start_ms, text: segment, partial_text: text.clone() });
+    }
+
+    let status = child.wait()?;
+    let log = stderr_reader.join().unwrap_or_default();
+    if !status.success() {
+        anyhow::bail!("whisper.cpp failed: {}", log.lines().last().unwrap_or("unknown error"));
+    }
+    let language = DETECTED_LANGUAGE.captures(&log).map(|c| c[1].to_string());
+    Ok((text, language))
+}
+
+pub async fn download_model(model: &str, on_progress: impl Fn(&ModelDownloadProgress)) -> Result<PathBuf> {
+    if !WHISPER_MODELS.contains(&model) {
+        anyhow::bail!("Unknown Whisper model '{}'", model);
+    }
+    let path = model_path(model)?;
+    let partial = path.with_extension("part");
+
+    let mut response = crate::network::client()
+        .get(format!("{}/ggml-{}.bin", MODEL_BASE_URL, model))
+        .header("User-Agent", "SAI-IDE")
+        .send()
+        .await?
+        .error_for_status()?;
+    let total = response.content_length();
+    let mut file = tokio::fs::File::create(&partial).await?;
+    let mut downloaded = 0;
+    while let Some(chunk) = response.chunk().await? {
+        tokio::io::AsyncWriteExt::write_all(&mut file, &chunk).await?;
+        downloaded += chunk.len() as u64;
+        on_progress(&ModelDownloadProgress { model: model.to_string(), downloaded, total });
+    }
+    tokio::io::AsyncWriteExt::flush(&mut file).await?;
+    tokio::fs::rename(&partial, &path).await?;
+    Ok(path)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_speech_status() -> Result<SpeechStatus, String> {
+    let model = installed_model().map_err(|e| e.to_string())?;
+    Ok(SpeechStatus {
+        recorder_available: find_in_path("ffmpeg").is_some(),
+        whisper_available: whisper_binary().is_some(),
+        model: model.and_then(|p| p.file_stem().map(|s| s.to_string_lossy().trim_start_matches("ggml-").to_string())),
+        recording: RECORDING.lock().unwrap_or_else(|e| e.into_inner()).is_some(),
+    })
+}
+
+/// Download a whisper.cpp model (defaults to "base"). Emits `speech-model-download-progress`.
+#[tauri::command]
+pub async fn download_whisper_model(app: tauri::AppHandle, model: Option<String>) -> Result<String, String> {
+    let model = model.unwrap_or_else(|| DEFAULT_MODEL.to_string());
+    download_model(&model, |progress| {
+        app.emit("speech-model-download-progress", progress).ok();
+    })
+    .await
+    .map(|path| path.to_string_lossy().to_string())
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn start_speech_input() -> Result<(), String> {
+    tokio::task::spawn_blocking(start_recording)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Stop recording and transcribe. Emits `speech-transcript` for each segment as it's
+/// recognized; the returned text is ready to send as an agent prompt.
+#[tauri::command]
+pub async fn stop_speech_input(app: tauri::AppHandle) -> Result<Transcript, String> {
+    tokio::task::spawn_blocking(move || -> Result<Transcript> {
+        let (path, audio_ms) = finish_recording()?;
+        let started = Instant::now();
+        let result = transcribe(&path, |segment| {
+            app.emit("speech-transcript", segment).ok();
+        });
+        std::fs::remove_file(&path).ok();
+        let (text, language) = result?;
+        Ok(Transcript { text, language, audio_ms, transcribe_ms: started.elapsed().as_millis() as u64 })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn cancel_speech_input() -> Result<(), String> {
+    tokio::task::spawn_blocking(cancel_recording)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_whisper_output() {
+        assert_eq!(
+            parse_segment("[00:01:02.500 --> 00:01:05.000]   Add a login page"),
+            Some((62_500, "Add a login page".to_string()))
+        );
+        assert_eq!(parse_segment("whisper_init_from_file: loading model"), None);
+
+        let log = "whisper_full_with_state: auto-detected language: de (p = 0.93)\n";
+        assert_eq!(DETECTED_LANGUAGE.captures(log).map(|c| c[1].to_string()).as_deref(), Some("de"));
+    }
+}
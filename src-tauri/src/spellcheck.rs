@@ -0,0 +1,540 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tree_sitter::Node;
+
+use crate::diagnostics::{self, Diagnostic, Severity};
+use crate::project::PROJECT_CONFIG_DIR;
+use crate::syntax::{detect_language, parser_for};
+
+/// Hunspell `.aff`/`.dic` pairs compiled into the binary. `dictionaries/update.sh` refreshes
+/// them from SCOWL together with each language's `LICENSE-<lang>.txt`.
+const BUNDLED: &[(&str, &str, &str)] = &[(
+    "en_US",
+    include_str!("../dictionaries/en_US.aff"),
+    include_str!("../dictionaries/en_US.dic"),
+)];
+
+pub const DEFAULT_LANGUAGE: &str = "en_US";
+/// Project and user word lists, one word per line
+const WORDS_FILE: &str = "dictionary.txt";
+/// Shorter words are mostly abbreviations in code comments
+const MIN_WORD_LEN: usize = 3;
+const MAX_SUGGESTIONS: usize = 5;
+/// Two-edit suggestions grow quadratically with length
+const MAX_TWO_EDIT_LEN: usize = 12;
+
+lazy_static::lazy_static! {
+    static ref WORD: Regex = Regex::new(r"[A-Za-z]+(?:'[A-Za-z]+)*").unwrap();
+    static ref URL: Regex = Regex::new(r"(?:https?|ftp|file)://\S+|www\.\S+|[\w.+-]+@[\w-]+\.[\w.]+").unwrap();
+    static ref FENCE: Regex = Regex::new(r"^\s*(```|~~~)").unwrap();
+    static ref INLINE_CODE: Regex = Regex::new(r"`[^`\n]*`").unwrap();
+    static ref LINK_TARGET: Regex = Regex::new(r"(?m)\]\([^)\n]*\)|^\s*\[[^\]]+\]:\s*\S+").unwrap();
+    static ref HTML_TAG: Regex = Regex::new(r"<[^>\n]+>").unwrap();
+    static ref C_COMMENTS: Regex = Regex::new(r#"(?s)//[^\n]*|/\*.*?\*/|"(?:[^"\\\n]|\\.)*""#).unwrap();
+    static ref HASH_COMMENTS: Regex = Regex::new(r#"#[^\n]*|"(?:[^"\\\n]|\\.)*""#).unwrap();
+    static ref DASH_COMMENTS: Regex = Regex::new(r"--[^\n]*|'(?:[^'\\\n]|\\.)*'").unwrap();
+}
+
+static DICTIONARIES: Mutex<Option<HashMap<String, Arc<Dictionary>>>> = Mutex::new(None);
+
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Regex,
+}
+
+/// A Hunspell dictionary: stems with affix flags, plus the SFX/PFX rules those flags name
+pub struct Dictionary {
+    stems: HashMap<String, String>,
+    suffixes: HashMap<char, Vec<AffixRule>>,
+    prefixes: HashMap<char, Vec<AffixRule>>,
+    try_chars: Vec<char>,
+}
+
+impl Dictionary {
+    pub fn parse(aff: &str, dic: &str) -> Result<Self> {
+        let mut dictionary = Dictionary {
+            stems: HashMap::new(),
+            suffixes: HashMap::new(),
+            prefixes: HashMap::new(),
+            try_chars: Vec::new(),
+        };
+
+        for line in aff.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["TRY", chars, ..] => dictionary.try_chars = chars.chars().filter(|c| c.is_lowercase() || *c == '\'').collect(),
+                // Rule lines have five or more fields; the four-field line is the group header
+                [kind @ ("SFX" | "PFX"), flag, strip, add, condition, ..] => {
+                    let flag = flag.chars().next().context("Empty affix flag")?;
+                    let strip = if *strip == "0" { "" } else { strip };
+                    // Continuation flags after `/` are not supported
+                    let add = add.split('/').next().filter(|a| *a != "0").unwrap_or("");
+                    let pattern = if *kind == "SFX" { format!("{}$", condition) } else { format!("^{}", condition) };
+                    let rule = AffixRule {
+                        strip: strip.to_string(),
+                        add: add.to_string(),
+                        condition: Regex::new(&pattern).with_context(|| format!("Invalid affix condition '{}'", condition))?,
+                    };
+                    let rules = if *kind == "SFX" { &mut dictionary.suffixes } else { &mut dictionary.prefixes };
+                    rules.entry(flag).or_default().push(rule);
+                }
+                _ => {}
+            }
+        }
+
+        // The first line of a .dic file is the approximate word count
+        for line in dic.lines().skip(1).map(str::trim).filter(|l| !l.is_empty()) {
+            let (word, flags) = line.split_once('/').unwrap_or((line, ""));
+            dictionary.stems.insert(word.to_string(), flags.to_string());
+        }
+
+        Ok(dictionary)
+    }
+
+    fn has_flag(&self, stem: &str, flag: char) -> bool {
+        self.stems.get(stem).is_some_and(|flags| flags.contains(flag))
+    }
+
+    /// Exact stem or a stem plus one of its prefixes or suffixes
+    fn knows(&self, word: &str) -> bool {
+        if self.stems.contains_key(word) {
+            return true;
+        }
+        let by_suffix = self.suffixes.iter().any(|(flag, rules)| {
+            rules.iter().any(|rule| {
+                word.len() > rule.add.len()
+                    && word.ends_with(&rule.add)
+                    && {
+                        let stem = format!("{}{}", &word[..word.len() - rule.add.len()], rule.strip);
+                        rule.condition.is_match(&stem) && self.has_flag(&stem, *flag)
+                    }
+            })
+        });
+        by_suffix
+            || self.prefixes.iter().any(|(flag, rules)| {
+                rules.iter().any(|rule| {
+                    word.len() > rule.add.len()
+                        && word.starts_with(&rule.add)
+                        && {
+                            let stem = format!("{}{}", rule.strip, &word[rule.add.len()..]);
+                            rule.condition.is_match(&stem) && self.has_flag(&stem, *flag)
+                        }
+                })
+            })
+    }
+
+    /// Lowercase entries also accept Capitalized and ALL CAPS spellings
+    pub fn check(&self, word: &str) -> bool {
+        if self.knows(word) {
+            return true;
+        }
+        let lower = word.to_lowercase();
+        let mut chars = word.chars();
+        let capitalized = chars.next().is_some_and(char::is_uppercase) && chars.all(|c| !c.is_uppercase());
+        let all_caps = word.chars().all(|c| !c.is_lowercase());
+        (capitalized || all_caps) && self.knows(&lower)
+    }
+
+    fn edits(&self, word: &str) -> Vec<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut edits = Vec::new();
+        for i in 0..chars.len().saturating_sub(1) {
+            let mut swapped = chars.clone();
+            swapped.swap(i, i + 1);
+            edits.push(swapped.into_iter().collect());
+        }
+        for i in 0..chars.len() {
+            let mut deleted = chars.clone();
+            deleted.remove(i);
+            edits.push(deleted.into_iter().collect());
+        }
+        for i in 0..chars.len() {
+            for c in &self.try_chars {
+                let mut replaced = chars.clone();
+                replaced[i] = *c;
+                edits.push(replaced.into_iter().collect());
+            }
+        }
+        for i in 0..=chars.len() {
+            for c in &self.try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(i, *c);
+                edits.push(inserted.into_iter().collect());
+            }
+        }
+        edits
+    }
+
+    /// Known words one edit away, then two, closest first
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let mut seen = HashSet::new();
+        let one_edit = self.edits(&lower);
+        let mut suggestions: Vec<String> = one_edit
+            .iter()
+            .filter(|candidate| *candidate != &lower && self.knows(candidate) && seen.insert((*candidate).clone()))
+            .cloned()
+            .collect();
+
+        if suggestions.is_empty() && lower.chars().count() <= MAX_TWO_EDIT_LEN {
+            for candidate in one_edit.iter().flat_map(|edit| self.edits(edit)) {
+                if suggestions.len() >= MAX_SUGGESTIONS {
+                    break;
+                }
+                if candidate != lower && self.knows(&candidate) && seen.insert(candidate.clone()) {
+                    suggestions.push(candidate);
+                }
+            }
+        }
+
+        suggestions.truncate(MAX_SUGGESTIONS);
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        suggestions
+            .into_iter()
+            .map(|s| if capitalized { capitalize(&s) } else { s })
+            .collect()
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn user_dictionary_dir() -> Result<PathBuf> {
+    Ok(dirs::data_dir().context("Failed to get data directory")?.join(".sai-ide"))
+}
+
+/// Bundled languages, plus any `<lang>.aff`/`<lang>.dic` pair in `~/.sai-ide/dictionaries`
+pub fn available_languages() -> Vec<String> {
+    let mut languages: Vec<String> = BUNDLED.iter().map(|(lang, _, _)| lang.to_string()).collect();
+    if let Ok(entries) = user_dictionary_dir().and_then(|dir| Ok(std::fs::read_dir(dir.join("dictionaries"))?)) {
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().is_some_and(|e| e == "dic") && path.with_extension("aff").exists() {
+                if let Some(lang) = path.file_stem().map(|s| s.to_string_lossy().to_string()) {
+                    if !languages.contains(&lang) {
+                        languages.push(lang);
+                    }
+                }
+            }
+        }
+    }
+    languages
+}
+
+pub fn dictionary(language: &str) -> Result<Arc<Dictionary>> {
+    let mut cache = DICTIONARIES.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some(dictionary) = cache.get(language) {
+        return Ok(dictionary.clone());
+    }
+
+    let dictionary = match BUNDLED.iter().find(|(lang, _, _)| *lang == language) {
+        Some((_, aff, dic)) => Dictionary::parse(aff, dic)?,
+        None => {
+            let base = user_dictionary_dir()?.join("dictionaries").join(language);
+            let aff = std::fs::read_to_string(base.with_extension("aff"))
+                .with_context(|| format!("No dictionary installed for '{}'", language))?;
+            let dic = std::fs::read_to_string(base.with_extension("dic"))
+                .with_context(|| format!("No dictionary installed for '{}'", language))?;
+            Dictionary::parse(&aff, &dic)?
+        }
+    };
+    let dictionary = Arc::new(dictionary);
+    cache.insert(language.to_string(), dictionary.clone());
+    Ok(dictionary)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DictionaryScope {
+    /// `.luciai/dictionary.txt`, shared through the repository
+    Project,
+    /// `~/.sai-ide/dictionary.txt`, for every project
+    User,
+}
+
+fn words_path(scope: DictionaryScope, project_path: Option<&Path>) -> Result<PathBuf> {
+    match scope {
+        DictionaryScope::Project => Ok(project_path
+            .context("A project is needed for the project dictionary")?
+            .join(PROJECT_CONFIG_DIR)
+            .join(WORDS_FILE)),
+        DictionaryScope::User => Ok(user_dictionary_dir()?.join(WORDS_FILE)),
+    }
+}
+
+fn read_words(path: &Path) -> HashSet<String> {
+    std::fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(|l| l.to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns false when the word was already in that dictionary
+pub fn add_word(word: &str, scope: DictionaryScope, project_path: Option<&Path>) -> Result<bool> {
+    let word = word.trim();
+    if word.is_empty() || word.contains(char::is_whitespace) {
+        anyhow::bail!("'{}' is not a single word", word);
+    }
+    let path = words_path(scope, project_path)?;
+    if read_words(&path).contains(&word.to_lowercase()) {
+        return Ok(false);
+    }
+
+    let mut content = std::fs::read_to_string(&path).unwrap_or_default();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(word);
+    content.push('\n');
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, content)?;
+    Ok(true)
+}
+
+/// Byte ranges of prose worth checking: comments and strings in code, text in markdown
+fn checkable_ranges(path: &Path, text: &str) -> Vec<(usize, usize)> {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+
+    if matches!(extension.as_str(), "md" | "markdown" | "mdx") {
+        return markdown_ranges(text);
+    }
+    if matches!(extension.as_str(), "txt" | "rst" | "adoc") {
+        return vec![(0, text.len())];
+    }
+    if let Some(language) = detect_language(path) {
+        if let Some(ranges) = syntax_ranges(language, text) {
+            return ranges;
+        }
+    }
+
+    let pattern: &Regex = match extension.as_str() {
+        "go" | "java" | "c" | "h" | "cpp" | "hpp" | "cc" | "cs" | "kt" | "swift" | "scala" | "dart" | "php" | "css"
+        | "scss" | "less" | "ts" | "tsx" | "js" | "jsx" | "mjs" | "rs" => &C_COMMENTS,
+        "py" | "sh" | "bash" | "zsh" | "yml" | "yaml" | "toml" | "rb" | "r" | "pl" | "conf" | "ini" => &HASH_COMMENTS,
+        "sql" | "lua" | "hs" => &DASH_COMMENTS,
+        _ => return Vec::new(),
+    };
+    pattern.find_iter(text).map(|m| (m.start(), m.end())).collect()
+}
+
+fn syntax_ranges(language: crate::syntax::SyntaxLanguage, text: &str) -> Option<Vec<(usize, usize)>> {
+    let tree = parser_for(language).ok()?.parse(text, None)?;
+    let mut ranges = Vec::new();
+    let mut stack: Vec<Node> = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        let kind = node.kind();
+        if kind.contains("comment") {
+            ranges.push((node.start_byte(), node.end_byte()));
+            continue;
+        }
+        if matches!(kind, "string" | "template_string" | "string_literal" | "raw_string_literal") {
+            // Interpolated expressions inside the string are code, not prose
+            let mut start = node.start_byte();
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if matches!(child.kind(), "template_substitution" | "interpolation") {
+                    ranges.push((start, child.start_byte()));
+                    start = child.end_byte();
+                }
+            }
+            ranges.push((start, node.end_byte()));
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+
+    ranges.sort_unstable();
+    Some(ranges)
+}
+
+/// Everything except fenced code blocks
+fn markdown_ranges(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    let mut fence: Option<String> = None;
+
+    for line in text.split_inclusive('\n') {
+        match (&fence, FENCE.captures(line)) {
+            (None, Some(c)) => fence = Some(c[1].to_string()),
+            (Some(open), Some(c)) if c[1] == *open => fence = None,
+            (None, None) => ranges.push((offset, offset + line.len())),
+            _ => {}
+        }
+        offset += line.len();
+    }
+    ranges
+}
+
+/// Spans inside checkable text that still aren't prose: URLs, inline code, link targets, tags
+fn masked_spans(text: &str, markdown: bool) -> Vec<(usize, usize)> {
+    let mut spans: Vec<(usize, usize)> = URL.find_iter(text).map(|m| (m.start(), m.end())).collect();
+    if markdown {
+        for pattern in [&*INLINE_CODE, &*LINK_TARGET, &*HTML_TAG] {
+            spans.extend(pattern.find_iter(text).map(|m| (m.start(), m.end())));
+        }
+    }
+    spans
+}
+
+/// Identifiers and acronyms in comments are not words to check
+fn is_identifier_like(text: &str, start: usize, end: usize) -> bool {
+    let word = &text[start..end];
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    let after_next = text[end..].chars().nth(1);
+
+    word.chars().skip(1).any(char::is_uppercase)
+        || before.is_some_and(|c| c.is_ascii_digit() || "_$@\\/.:#".contains(c))
+        || after.is_some_and(|c| c.is_ascii_digit() || c == '_' || c == '(')
+        || (after == Some('.') && after_next.is_some_and(char::is_alphanumeric))
+}
+
+fn position(text: &str, line_starts: &[usize], offset: usize) -> (u32, u32) {
+    let line = line_starts.partition_point(|start| *start <= offset) - 1;
+    let column = text[line_starts[line]..offset].chars().count();
+    (line as u32 + 1, column as u32 + 1)
+}
+
+/// Misspelled words in `text` as info diagnostics with suggestions
+pub fn check_text(path: &Path, text: &str, dictionary: &Dictionary, extra_words: &HashSet<String>) -> Vec<Diagnostic> {
+    let markdown = matches!(
+        path.extension().map(|e| e.to_string_lossy().to_lowercase()).as_deref(),
+        Some("md" | "markdown" | "mdx")
+    );
+    let masked = masked_spans(text, markdown);
+    let line_starts: Vec<usize> = std::iter::once(0).chain(text.match_indices('\n').map(|(i, _)| i + 1)).collect();
+    let mut diagnostics = Vec::new();
+
+    for (range_start, range_end) in checkable_ranges(path, text) {
+        for found in WORD.find_iter(&text[range_start..range_end]) {
+            let (start, end) = (range_start + found.start(), range_start + found.end());
+            // Possessives are checked without their 's
+            let word = found.as_str().strip_suffix("'s").unwrap_or(found.as_str());
+
+            if word.chars().count() < MIN_WORD_LEN
+                || is_identifier_like(text, start, end)
+                || masked.iter().any(|(s, e)| start < *e && end > *s)
+                || extra_words.contains(&word.to_lowercase())
+                || dictionary.check(word)
+            {
+                continue;
+            }
+
+            let (line, column) = position(text, &line_starts, start);
+            diagnostics.push(Diagnostic {
+                file: path.to_string_lossy().to_string(),
+                line,
+                column: Some(column),
+                end_column: Some(column + word.chars().count() as u32),
+                severity: Severity::Info,
+                message: format!("Unknown word '{}'", word),
+                source: "spellcheck".to_string(),
+                suggestions: dictionary.suggest(word),
+            });
+        }
+    }
+
+    diagnostics
+}
+
+pub fn check_file(path: &Path, text: &str, project_path: Option<&Path>, language: &str) -> Result<Vec<Diagnostic>> {
+    let dictionary = dictionary(language)?;
+    let mut extra_words = read_words(&words_path(DictionaryScope::User, None)?);
+    if let Some(project) = project_path {
+        extra_words.extend(read_words(&words_path(DictionaryScope::Project, Some(project))?));
+    }
+    Ok(check_text(path, text, &dictionary, &extra_words))
+}
+
+// Tauri commands
+
+/// Check an open file; `content` is the editor buffer, which may be unsaved.
+/// Results are also published to the diagnostics store.
+#[tauri::command]
+pub async fn check_spelling(
+    app: tauri::AppHandle,
+    path: String,
+    content: Option<String>,
+    project_path: Option<String>,
+    language: Option<String>,
+) -> Result<Vec<Diagnostic>, String> {
+    let diagnostics = tokio::task::spawn_blocking(move || {
+        let text = match content {
+            Some(content) => content,
+            None => std::fs::read_to_string(&path)?,
+        };
+        check_file(
+            Path::new(&path),
+            &text,
+            project_path.as_deref().map(Path::new),
+            language.as_deref().unwrap_or(DEFAULT_LANGUAGE),
+        )
+        .map(|diagnostics| (path, diagnostics))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    let (path, diagnostics) = diagnostics;
+    diagnostics::publish(Some(&app), &format!("spellcheck:{}", path), diagnostics.clone());
+    Ok(diagnostics)
+}
+
+#[tauri::command]
+pub async fn add_to_dictionary(word: String, scope: DictionaryScope, project_path: Option<String>) -> Result<bool, String> {
+    add_word(&word, scope, project_path.as_deref().map(Path::new)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_spellcheck_languages() -> Result<Vec<String>, String> {
+    Ok(available_languages())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spellcheck() {
+        let dictionary = dictionary(DEFAULT_LANGUAGE).unwrap();
+        assert!(dictionary.check("receive"));
+        assert!(dictionary.check("receives") && dictionary.check("Received") && dictionary.check("RECEIVING"));
+        assert!(!dictionary.check("recieve"));
+        assert!(dictionary.suggest("recieve").contains(&"receive".to_string()));
+        assert!(dictionary.suggest("Teh").contains(&"The".to_string()));
+
+        let source = "// Retreive the user's settings\nfn load_config() -> Config {\n    let path = \"config.json\"; // see https://exmaple.com\n    helperFnName(path)\n}\n";
+        let diagnostics = check_text(Path::new("src/main.rs"), source, &dictionary, &HashSet::new());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (1, Some(4)));
+        assert!(diagnostics[0].suggestions.contains(&"Retrieve".to_string()));
+
+        let markdown = "# Setup\n\nRun `npm instal` first, it's quick.\n\n```sh\nnpm instal\n```\nThe projcet builds.\n";
+        let extra: HashSet<String> = ["npm".to_string()].into_iter().collect();
+        let words: Vec<String> = check_text(Path::new("README.md"), markdown, &dictionary, &extra)
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(words, vec!["Unknown word 'projcet'"]);
+    }
+}
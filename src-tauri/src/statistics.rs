@@ -0,0 +1,394 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+
+use crate::database::{self, DatabaseManager, FileStatsEntry};
+use crate::filesystem::collect_source_files;
+
+const MAX_HOTSPOTS: usize = 20;
+
+/// Size buckets (upper bound in lines) for the file distribution chart
+const SIZE_BUCKETS: &[(usize, &str)] = &[
+    (50, "1-50"),
+    (200, "51-200"),
+    (500, "201-500"),
+    (1000, "501-1000"),
+    (usize::MAX, "1000+"),
+];
+
+struct LanguageSyntax {
+    name: &'static str,
+    line_comment: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+}
+
+fn language_for(extension: &str) -> Option<LanguageSyntax> {
+    let c_like = |name| LanguageSyntax { name, line_comment: &["//"], block_comment: Some(("/*", "*/")) };
+    let hash = |name| LanguageSyntax { name, line_comment: &["#"], block_comment: None };
+
+    Some(match extension {
+        "rs" => c_like("Rust"),
+        "ts" | "tsx" => c_like("TypeScript"),
+        "js" | "jsx" | "mjs" | "cjs" => c_like("JavaScript"),
+        "go" => c_like("Go"),
+        "java" => c_like("Java"),
+        "kt" => c_like("Kotlin"),
+        "cs" => c_like("C#"),
+        "c" | "h" => c_like("C"),
+        "cpp" | "cc" | "hpp" => c_like("C++"),
+        "swift" => c_like("Swift"),
+        "dart" => c_like("Dart"),
+        "css" | "scss" => c_like("CSS"),
+        "py" => LanguageSyntax { name: "Python", line_comment: &["#"], block_comment: Some(("\"\"\"", "\"\"\"")) },
+        "rb" => hash("Ruby"),
+        "sh" | "bash" | "zsh" => hash("Shell"),
+        "yml" | "yaml" => hash("YAML"),
+        "toml" => hash("TOML"),
+        "sql" => LanguageSyntax { name: "SQL", line_comment: &["--"], block_comment: Some(("/*", "*/")) },
+        "html" | "vue" | "svelte" => LanguageSyntax { name: "HTML", line_comment: &[], block_comment: Some(("<!--", "-->")) },
+        "md" | "mdx" => LanguageSyntax { name: "Markdown", line_comment: &[], block_comment: None },
+        "json" => LanguageSyntax { name: "JSON", line_comment: &[], block_comment: None },
+        _ => return None,
+    })
+}
+
+/// Display name of the language files with this extension are written in
+pub fn language_name(extension: &str) -> Option<&'static str> {
+    language_for(extension).map(|syntax| syntax.name)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: usize,
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ChurnEntry {
+    pub file: String,
+    pub commits: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContributorStats {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GrowthPoint {
+    pub month: String,
+    pub commits: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Running total of added minus removed lines up to this month
+    pub net_lines: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectStatistics {
+    pub total_files: usize,
+    pub total_lines: usize,
+    pub languages: Vec<LanguageStats>,
+    pub size_distribution: Vec<(String, usize)>,
+    pub churn_hotspots: Vec<ChurnEntry>,
+    pub contributors: Vec<ContributorStats>,
+    pub growth: Vec<GrowthPoint>,
+    pub generated_at: String,
+}
+
+/// Accumulated git history, cached so refreshes only walk new commits
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct GitStats {
+    churn: HashMap<String, ChurnEntry>,
+    contributors: HashMap<String, ContributorStats>,
+    growth: BTreeMap<String, GrowthPoint>,
+}
+
+/// tokei-style code/comment/blank counts
+fn count_lines(content: &str, syntax: &LanguageSyntax) -> (usize, usize, usize) {
+    let (mut code, mut comments, mut blanks) = (0, 0, 0);
+    let mut in_block: Option<&str> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if let Some(end) = in_block {
+            comments += 1;
+            if trimmed.contains(end) {
+                in_block = None;
+            }
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            blanks += 1;
+        } else if syntax.line_comment.iter().any(|c| trimmed.starts_with(c)) {
+            comments += 1;
+        } else if let Some((start, end)) = syntax.block_comment.filter(|(s, _)| trimmed.starts_with(s)) {
+            comments += 1;
+            if !trimmed[start.len()..].contains(end) {
+                in_block = Some(end);
+            }
+        } else {
+            code += 1;
+        }
+    }
+
+    (code, comments, blanks)
+}
+
+/// Line counts for every recognised file, reusing cached rows whose mtime and size are unchanged
+fn file_stats(project_path: &Path, db: &DatabaseManager) -> Result<Vec<FileStatsEntry>> {
+    let key = project_path.to_string_lossy().to_string();
+    let cached: HashMap<String, FileStatsEntry> = db.get_file_stats(&key)?
+        .into_iter()
+        .map(|e| (e.file_path.clone(), e))
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut recounted = 0;
+
+    for file in collect_source_files(project_path, &[]) {
+        let Some(syntax) = file.extension().and_then(|e| language_for(&e.to_string_lossy())) else { continue };
+        let Ok(metadata) = std::fs::metadata(&file) else { continue };
+
+        let relative = file.strip_prefix(project_path).unwrap_or(&file).to_string_lossy().to_string();
+        let modified = metadata.modified().ok()
+            .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let size = metadata.len() as i64;
+
+        if let Some(entry) = cached.get(&relative).filter(|e| e.modified == modified && e.size == size) {
+            entries.push(entry.clone());
+            continue;
+        }
+
+        let Ok(content) = std::fs::read_to_string(&file) else { continue };
+        let (code, comments, blanks) = count_lines(&content, &syntax);
+        recounted += 1;
+
+        entries.push(FileStatsEntry {
+            file_path: relative,
+            modified,
+            size,
+            language: syntax.name.to_string(),
+            code: code as i64,
+            comments: comments as i64,
+            blanks: blanks as i64,
+        });
+    }
+
+    if recounted > 0 || entries.len() != cached.len() {
+        db.save_file_stats(&key, &entries)?;
+    }
+
+    tracing::debug!("Recounted {} of {} files in {:?}", recounted, entries.len(), project_path);
+    Ok(entries)
+}
+
+fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Fold `git log --numstat` output into the accumulated statistics
+fn apply_log(stats: &mut GitStats, log: &str) {
+    for record in log.split('\x1e').filter(|r| !r.trim().is_empty()) {
+        let mut lines = record.trim_start_matches('\n').lines();
+        let Some(header) = lines.next() else { continue };
+        let fields: Vec<&str> = header.split('\x1f').collect();
+        if fields.len() < 4 {
+            continue;
+        }
+        let (name, email, month) = (fields[1], fields[2], fields[3]);
+
+        let contributor = stats.contributors.entry(email.to_lowercase()).or_insert_with(|| ContributorStats {
+            name: name.to_string(),
+            email: email.to_string(),
+            ..Default::default()
+        });
+        contributor.commits += 1;
+
+        let point = stats.growth.entry(month.to_string()).or_insert_with(|| GrowthPoint {
+            month: month.to_string(),
+            ..Default::default()
+        });
+        point.commits += 1;
+
+        for line in lines {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() != 3 {
+                continue;
+            }
+            // Binary files report "-" for both counts
+            let added = parts[0].parse::<usize>().unwrap_or(0);
+            let removed = parts[1].parse::<usize>().unwrap_or(0);
+
+            let churn = stats.churn.entry(parts[2].to_string()).or_insert_with(|| ChurnEntry {
+                file: parts[2].to_string(),
+                ..Default::default()
+            });
+            churn.commits += 1;
+            churn.lines_added += added;
+            churn.lines_removed += removed;
+
+            contributor.lines_added += added;
+            contributor.lines_removed += removed;
+            point.lines_added += added;
+            point.lines_removed += removed;
+        }
+    }
+}
+
+fn git_stats(project_path: &Path, db: &DatabaseManager) -> Result<GitStats> {
+    let key = project_path.to_string_lossy().to_string();
+    let head = git(project_path, &["rev-parse", "HEAD"])?.trim().to_string();
+
+    let mut stats = GitStats::default();
+    let mut range = head.clone();
+
+    if let Some((last_commit, data)) = db.get_git_stats(&key)? {
+        // History rewritten (rebase, reset): start over
+        let still_ancestor = git(project_path, &["merge-base", "--is-ancestor", &last_commit, &head]).is_ok();
+        if still_ancestor {
+            if last_commit == head {
+                return Ok(serde_json::from_str(&data).unwrap_or_default());
+            }
+            stats = serde_json::from_str(&data).unwrap_or_default();
+            range = format!("{}..{}", last_commit, head);
+        }
+    }
+
+    let log = git(project_path, &[
+        "log", "--no-merges", "--numstat", "--date=format:%Y-%m",
+        "--format=%x1e%H%x1f%an%x1f%ae%x1f%ad", &range,
+    ])?;
+    apply_log(&mut stats, &log);
+
+    db.save_git_stats(&key, &head, &serde_json::to_string(&stats)?)?;
+    Ok(stats)
+}
+
+pub fn project_statistics(project_path: &Path) -> Result<ProjectStatistics> {
+    let db = database::open()?;
+
+    let files = file_stats(project_path, &db)?;
+
+    let mut languages: BTreeMap<String, LanguageStats> = BTreeMap::new();
+    let mut distribution = vec![0usize; SIZE_BUCKETS.len()];
+
+    for file in &files {
+        let stats = languages.entry(file.language.clone()).or_insert_with(|| LanguageStats {
+            language: file.language.clone(),
+            ..Default::default()
+        });
+        stats.files += 1;
+        stats.code += file.code as usize;
+        stats.comments += file.comments as usize;
+        stats.blanks += file.blanks as usize;
+
+        let lines = (file.code + file.comments + file.blanks) as usize;
+        let bucket = SIZE_BUCKETS.iter().position(|(max, _)| lines <= *max).unwrap_or(SIZE_BUCKETS.len() - 1);
+        distribution[bucket] += 1;
+    }
+
+    let mut languages: Vec<LanguageStats> = languages.into_values().collect();
+    languages.sort_by(|a, b| b.code.cmp(&a.code));
+
+    // Projects without git still get line counts
+    let git = if project_path.join(".git").exists() {
+        git_stats(project_path, &db).unwrap_or_else(|e| {
+            tracing::warn!("Skipping git statistics: {}", e);
+            GitStats::default()
+        })
+    } else {
+        GitStats::default()
+    };
+
+    let mut churn_hotspots: Vec<ChurnEntry> = git.churn.into_values()
+        .filter(|c| project_path.join(&c.file).exists())
+        .collect();
+    churn_hotspots.sort_by(|a, b| b.commits.cmp(&a.commits).then(b.lines_added.cmp(&a.lines_added)));
+    churn_hotspots.truncate(MAX_HOTSPOTS);
+
+    let mut contributors: Vec<ContributorStats> = git.contributors.into_values().collect();
+    contributors.sort_by(|a, b| b.commits.cmp(&a.commits));
+
+    let mut net = 0i64;
+    let growth = git.growth.into_values()
+        .map(|mut point| {
+            net += point.lines_added as i64 - point.lines_removed as i64;
+            point.net_lines = net;
+            point
+        })
+        .collect();
+
+    Ok(ProjectStatistics {
+        total_files: files.len(),
+        total_lines: languages.iter().map(|l| l.code + l.comments + l.blanks).sum(),
+        languages,
+        size_distribution: SIZE_BUCKETS.iter().zip(distribution).map(|((_, label), n)| (label.to_string(), n)).collect(),
+        churn_hotspots,
+        contributors,
+        growth,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn get_project_statistics(project_path: String) -> Result<ProjectStatistics, String> {
+    tokio::task::spawn_blocking(move || project_statistics(Path::new(&project_path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_lines() {
+        let rust = language_for("rs").unwrap();
+        let source = "// header\nfn main() {\n\n    /* block\n       comment */\n    println!(\"hi\");\n}\n";
+        assert_eq!(count_lines(source, &rust), (3, 3, 1));
+    }
+
+    #[test]
+    fn test_apply_log() {
+        let log = "\x1eabc\x1fAda\x1fada@example.com\x1f2024-03\n\n10\t2\tsrc/main.rs\n-\t-\tlogo.png\n\
+                   \x1edef\x1fAda\x1fADA@example.com\x1f2024-04\n\n3\t1\tsrc/main.rs\n";
+
+        let mut stats = GitStats::default();
+        apply_log(&mut stats, log);
+
+        assert_eq!(stats.churn["src/main.rs"].commits, 2);
+        assert_eq!(stats.churn["src/main.rs"].lines_added, 13);
+        assert_eq!(stats.contributors.len(), 1);
+        assert_eq!(stats.contributors["ada@example.com"].commits, 2);
+        assert_eq!(stats.growth["2024-04"].lines_removed, 1);
+    }
+}
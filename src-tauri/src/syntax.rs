@@ -0,0 +1,39 @@
+use anyhow::Result;
+use std::path::Path;
+use tree_sitter::Parser;
+
+/// Languages with a bundled tree-sitter grammar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyntaxLanguage {
+    TypeScript,
+    Tsx,
+    Python,
+    Rust,
+}
+
+/// File extensions the bundled grammars can parse
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "py", "rs"];
+
+pub fn detect_language(path: &Path) -> Option<SyntaxLanguage> {
+    match path.extension()?.to_str()? {
+        "ts" => Some(SyntaxLanguage::TypeScript),
+        // The TSX grammar also parses plain JavaScript and JSX
+        "tsx" | "js" | "jsx" | "mjs" => Some(SyntaxLanguage::Tsx),
+        "py" => Some(SyntaxLanguage::Python),
+        "rs" => Some(SyntaxLanguage::Rust),
+        _ => None,
+    }
+}
+
+pub fn parser_for(language: SyntaxLanguage) -> Result<Parser> {
+    let grammar = match language {
+        SyntaxLanguage::TypeScript => tree_sitter_typescript::language_typescript(),
+        SyntaxLanguage::Tsx => tree_sitter_typescript::language_tsx(),
+        SyntaxLanguage::Python => tree_sitter_python::language(),
+        SyntaxLanguage::Rust => tree_sitter_rust::language(),
+    };
+
+    let mut parser = Parser::new();
+    parser.set_language(grammar).map_err(|e| anyhow::anyhow!("Failed to load grammar: {:?}", e))?;
+    Ok(parser)
+}
@@ -0,0 +1,383 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::watch;
+
+use crate::diagnostics::{self, Diagnostic};
+use crate::node_versions;
+use crate::package_manager;
+use crate::python_envs;
+use crate::project::{load_project_config, save_project_config};
+use crate::terminal::problem_matchers::{self, MatcherEngine, MatcherSpec};
+use crate::terminal::shell::ShellProfileManager;
+
+pub const CONFIG_FILE: &str = "tasks.json";
+
+/// How long to keep reading output after the task exits or is stopped; a background
+/// process that inherited the pipes would otherwise hold the run open
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Runs in progress, keyed by run id; sending `true` stops the run
+static RUNNING: Mutex<Option<HashMap<String, RunningTask>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDefinition {
+    pub name: String,
+    pub command: String,
+    /// Relative to the project root
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Built-ins such as `"$cargo"`, or custom regex matchers
+    #[serde(default)]
+    pub problem_matchers: Vec<MatcherSpec>,
+    /// Tasks run first, in order; a failing dependency stops the run
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// `.luciai/tasks.json`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TasksConfig {
+    #[serde(default)]
+    pub tasks: Vec<TaskDefinition>,
+}
+
+/// `name` and everything it depends on, dependencies first, each task once
+pub fn execution_order<'a>(config: &'a TasksConfig, name: &str) -> Result<Vec<&'a TaskDefinition>> {
+    fn visit<'a>(
+        config: &'a TasksConfig,
+        name: &str,
+        path: &mut Vec<String>,
+        order: &mut Vec<&'a TaskDefinition>,
+    ) -> Result<()> {
+        if order.iter().any(|t| t.name == name) {
+            return Ok(());
+        }
+        if path.iter().any(|p| p == name) {
+            anyhow::bail!("Task dependency cycle: {} -> {}", path.join(" -> "), name);
+        }
+        let task = config
+            .tasks
+            .iter()
+            .find(|t| t.name == name)
+            .with_context(|| format!("No task named '{}'", name))?;
+
+        path.push(name.to_string());
+        for dependency in &task.depends_on {
+            visit(config, dependency, path, order)?;
+        }
+        path.pop();
+        order.push(task);
+        Ok(())
+    }
+
+    let mut order = Vec::new();
+    visit(config, name, &mut Vec::new(), &mut order)?;
+    Ok(order)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskOutput {
+    pub run_id: String,
+    pub task: String,
+    pub stream: String, // "stdout" or "stderr"
+    pub line: String,
+    pub diagnostic: Option<Diagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskResult {
+    pub name: String,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRunResult {
+    pub run_id: String,
+    pub task: String,
+    pub success: bool,
+    pub stopped: bool,
+    pub tasks: Vec<TaskResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningTaskInfo {
+    pub run_id: String,
+    pub task: String,
+    pub project_path: String,
+    pub started_at: String,
+}
+
+struct RunningTask {
+    info: RunningTaskInfo,
+    stop: watch::Sender<bool>,
+}
+
+type OutputSink = Arc<dyn Fn(TaskOutput) + Send + Sync>;
+
+fn forward_lines<R: AsyncRead + Unpin + Send + 'static>(
+    reader: R,
+    run_id: String,
+    task: String,
+    stream: &'static str,
+    mut matchers: MatcherEngine,
+    sink: OutputSink,
+) -> tokio::task::JoinHandle<Vec<Diagnostic>> {
+    tokio::spawn(async move {
+        let mut diagnostics = Vec::new();
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            // A line completes at most one problem in practice
+            let diagnostic = matchers.feed(&line).into_iter().next();
+            if let Some(diagnostic) = &diagnostic {
+                diagnostics.push(diagnostic.clone());
+            }
+            sink(TaskOutput {
+                run_id: run_id.clone(),
+                task: task.clone(),
+                stream: stream.to_string(),
+                line,
+                diagnostic,
+            });
+        }
+        diagnostics
+    })
+}
+
+async fn run_one(
+    project_path: &Path,
+    task: &TaskDefinition,
+    run_id: &str,
+    stop: &mut watch::Receiver<bool>,
+    sink: OutputSink,
+) -> Result<TaskResult> {
+    let started = Instant::now();
+    let cwd = match &task.cwd {
+        Some(cwd) => project_path.join(cwd),
+        None => project_path.to_path_buf(),
+    };
+    // Each stream gets its own engine so multi-line matches don't interleave
+    let stdout_matchers = MatcherEngine::new(&task.problem_matchers, &cwd, &task.name)?;
+    let stderr_matchers = MatcherEngine::new(&task.problem_matchers, &cwd, &task.name)?;
+
+    // A shared tasks.json says `npm run build`; run it with whatever this project uses
+    let command = package_manager::for_project(project_path).translate(&task.command);
+    let command = node_versions::prepare_command(project_path, &command)?;
+    let profile = ShellProfileManager::new()?.get_profile(None)?;
+    let mut cmd = tokio::process::Command::from(profile.build_command(&command));
+    // Its own process group, so stopping the task reaches what the shell started
+    #[cfg(unix)]
+    cmd.process_group(0);
+    #[cfg(windows)]
+    cmd.creation_flags(0x0000_0200); // CREATE_NEW_PROCESS_GROUP
+    let mut child = cmd
+        .current_dir(&cwd)
+        .envs(python_envs::activation_env(&cwd))
+        .envs(&task.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start task '{}'", task.name))?;
+
+    let readers = [
+        child.stdout.take().map(|s| forward_lines(s, run_id.to_string(), task.name.clone(), "stdout", stdout_matchers, sink.clone())),
+        child.stderr.take().map(|s| forward_lines(s, run_id.to_string(), task.name.clone(), "stderr", stderr_matchers, sink.clone())),
+    ];
+
+    let status = tokio::select! {
+        status = child.wait() => Some(status?),
+        Ok(()) = stop.changed() => {
+            crate::terminal::kill_process_tree(&mut child).await;
+            None
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+    for mut reader in readers.into_iter().flatten() {
+        match tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, &mut reader).await {
+            Ok(found) => diagnostics.extend(found.unwrap_or_default()),
+            Err(_) => {
+                tracing::warn!("Task '{}' left output open after exiting; no longer reading it", task.name);
+                reader.abort();
+            }
+        }
+    }
+
+    Ok(TaskResult {
+        name: task.name.clone(),
+        exit_code: status.and_then(|s| s.code()),
+        success: status.is_some_and(|s| s.success()),
+        duration_ms: started.elapsed().as_millis() as u64,
+        diagnostics,
+    })
+}
+
+/// Run `name` after its dependencies, stopping at the first failure or when asked to stop
+pub async fn run_task(
+    project_path: &Path,
+    name: &str,
+    run_id: &str,
+    mut stop: watch::Receiver<bool>,
+    sink: OutputSink,
+) -> Result<TaskRunResult> {
+    let config: TasksConfig = load_project_config(project_path, CONFIG_FILE)?;
+    let order = execution_order(&config, name)?;
+
+    let mut result = TaskRunResult {
+        run_id: run_id.to_string(),
+        task: name.to_string(),
+        success: true,
+        stopped: false,
+        tasks: Vec::new(),
+    };
+
+    for task in order {
+        if *stop.borrow() {
+            result.stopped = true;
+            break;
+        }
+        let task_result = run_one(project_path, task, run_id, &mut stop, sink.clone()).await?;
+        let success = task_result.success;
+        result.tasks.push(task_result);
+        if !success {
+            result.stopped = *stop.borrow();
+            break;
+        }
+    }
+
+    result.success = !result.stopped && result.tasks.iter().all(|t| t.success);
+    Ok(result)
+}
+
+fn with_running<T>(f: impl FnOnce(&mut HashMap<String, RunningTask>) -> T) -> T {
+    let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    f(running.get_or_insert_with(HashMap::new))
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_tasks(project_path: String) -> Result<Vec<TaskDefinition>, String> {
+    load_project_config::<TasksConfig>(Path::new(&project_path), CONFIG_FILE)
+        .map(|config| config.tasks)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_tasks_config(project_path: String, config: TasksConfig) -> Result<(), String> {
+    for task in &config.tasks {
+        problem_matchers::validate(&task.problem_matchers)
+            .map_err(|e| format!("Task '{}': {}", task.name, e))?;
+    }
+    save_project_config(Path::new(&project_path), CONFIG_FILE, &config).map_err(|e| e.to_string())
+}
+
+/// Start a task in the background and return its run id. Output arrives as
+/// `task-output` events and the outcome as a `task-finished` event.
+#[tauri::command]
+pub async fn run_project_task(app: tauri::AppHandle, project_path: String, name: String) -> Result<String, String> {
+    let config: TasksConfig = load_project_config(Path::new(&project_path), CONFIG_FILE).map_err(|e| e.to_string())?;
+    execution_order(&config, &name).map_err(|e| e.to_string())?;
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let (stop, stop_receiver) = watch::channel(false);
+    with_running(|running| {
+        running.insert(run_id.clone(), RunningTask {
+            info: RunningTaskInfo {
+                run_id: run_id.clone(),
+                task: name.clone(),
+                project_path: project_path.clone(),
+                started_at: chrono::Utc::now().to_rfc3339(),
+            },
+            stop,
+        });
+    });
+
+    let output_app = app.clone();
+    let sink: OutputSink = Arc::new(move |output: TaskOutput| {
+        output_app.emit("task-output", output).ok();
+    });
+    let id = run_id.clone();
+    tokio::spawn(async move {
+        let result = run_task(Path::new(&project_path), &name, &id, stop_receiver, sink).await;
+        with_running(|running| running.remove(&id));
+        match result {
+            Ok(result) => {
+                for task in &result.tasks {
+                    diagnostics::publish(Some(&app), &format!("task:{}", task.name), task.diagnostics.clone());
+                }
+                app.emit("task-finished", result).ok()
+            }
+            Err(e) => {
+                tracing::error!("Task '{}' failed to run: {}", name, e);
+                app.emit("task-finished", TaskRunResult {
+                    run_id: id.clone(),
+                    task: name.clone(),
+                    success: false,
+                    stopped: false,
+                    tasks: Vec::new(),
+                })
+                .ok()
+            }
+        };
+    });
+
+    Ok(run_id)
+}
+
+#[tauri::command]
+pub async fn stop_project_task(run_id: String) -> Result<(), String> {
+    with_running(|running| match running.get(&run_id) {
+        Some(task) => {
+            task.stop.send(true).ok();
+            Ok(())
+        }
+        None => Err(format!("No running task with id {}", run_id)),
+    })
+}
+
+#[tauri::command]
+pub async fn list_running_tasks() -> Result<Vec<RunningTaskInfo>, String> {
+    Ok(with_running(|running| running.values().map(|t| t.info.clone()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str, depends_on: &[&str]) -> TaskDefinition {
+        TaskDefinition {
+            name: name.to_string(),
+            command: format!("echo {}", name),
+            cwd: None,
+            env: BTreeMap::new(),
+            problem_matchers: Vec::new(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_execution_order() {
+        let config = TasksConfig {
+            tasks: vec![task("build", &["codegen", "lint"]), task("lint", &["codegen"]), task("codegen", &[])],
+        };
+        let order: Vec<&str> = execution_order(&config, "build").unwrap().iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(order, vec!["codegen", "lint", "build"]);
+
+        let cyclic = TasksConfig { tasks: vec![task("a", &["b"]), task("b", &["a"])] };
+        assert!(execution_order(&cyclic, "a").is_err());
+    }
+}
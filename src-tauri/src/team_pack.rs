@@ -0,0 +1,489 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, LineEnding};
+use rsa::{Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::agent::personas::{self, Persona, PersonaManager};
+use crate::permissions::Permission;
+use crate::preferences::PreferencesManager;
+use crate::readonly;
+
+/// Bumped when the payload changes incompatibly
+const FORMAT_VERSION: u32 = 1;
+const KEY_BITS: usize = 2048;
+
+/// Formatter and linter configs carried from the project root
+const CONFIG_FILES: &[&str] = &[
+    ".editorconfig", ".prettierrc", ".prettierrc.json", ".prettierrc.yaml", ".prettierrc.yml", ".prettierrc.js",
+    "prettier.config.js", ".eslintrc", ".eslintrc.json", ".eslintrc.js", ".eslintrc.cjs", ".eslintrc.yml",
+    "eslint.config.js", "eslint.config.mjs", "biome.json", ".stylelintrc.json", "rustfmt.toml", ".rustfmt.toml",
+    "clippy.toml", "ruff.toml", ".ruff.toml", ".flake8", ".clang-format",
+];
+
+/// Editor settings that decide how code is laid out
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FormatSettings {
+    pub tab_size: u32,
+    pub insert_spaces: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PackFile {
+    /// Relative to the project root
+    pub name: String,
+    pub content: String,
+}
+
+/// What a team pack carries. Prompt templates travel with their personas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamPack {
+    pub format: u32,
+    pub name: String,
+    pub created_at: String,
+    /// Custom personas; the built-in ones are the same everywhere
+    pub personas: Vec<Persona>,
+    /// Agent tool grants, which decide what the agent may do without asking
+    pub tool_permissions: BTreeMap<String, Vec<Permission>>,
+    pub format_settings: Option<FormatSettings>,
+    pub config_files: Vec<PackFile>,
+}
+
+/// The file on disk: the payload exactly as signed, so verification doesn't depend
+/// on how JSON is re-serialized
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignedPack {
+    payload: String,
+    /// Base64 RSA PKCS#1 v1.5 signature of the payload's SHA-256
+    signature: String,
+    /// PEM of the signer's public key
+    public_key: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    KeepExisting,
+    UsePack,
+    /// Personas are added under a new id and config files written as `<name>.team`;
+    /// anything else keeps the existing value
+    KeepBoth,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackConflict {
+    /// `persona:<id>`, `tool:<name>`, `format_settings` or `file:<name>`; the key for
+    /// per-item resolutions
+    pub key: String,
+    pub existing: String,
+    pub incoming: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackInspection {
+    pub name: String,
+    pub created_at: String,
+    /// SHA-256 of the signer's public key, shortened
+    pub signer: String,
+    /// The signer is this machine or was trusted on an earlier import
+    pub trusted: bool,
+    pub personas: usize,
+    pub tool_permissions: usize,
+    pub config_files: usize,
+    pub conflicts: Vec<PackConflict>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ImportReport {
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    pub kept: Vec<String>,
+}
+
+fn keys_dir() -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .context("Failed to get data directory")?
+        .join(".sai-ide")
+        .join("team_keys");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// This machine's signing key, created on first export
+fn signing_key(dir: &Path) -> Result<RsaPrivateKey> {
+    let path = dir.join("signing_key.pem");
+    if path.exists() {
+        return RsaPrivateKey::from_pkcs8_pem(&std::fs::read_to_string(&path)?).context("Invalid team pack signing key");
+    }
+    let key = RsaPrivateKey::new(&mut rsa::rand_core::OsRng, KEY_BITS)?;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path).context("Failed to write the team pack signing key")?;
+    file.write_all(key.to_pkcs8_pem(LineEnding::LF)?.as_bytes())?;
+    Ok(key)
+}
+
+fn fingerprint(key: &RsaPublicKey) -> Result<String> {
+    let digest = Sha256::digest(key.to_public_key_der()?.as_bytes());
+    Ok(digest[..8].iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"))
+}
+
+fn trusted_signers(dir: &Path) -> Vec<String> {
+    std::fs::read_to_string(dir.join("trusted.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn trust_signer(dir: &Path, signer: &str) -> Result<()> {
+    let mut signers = trusted_signers(dir);
+    if !signers.iter().any(|s| s == signer) {
+        signers.push(signer.to_string());
+        std::fs::write(dir.join("trusted.json"), serde_json::to_string_pretty(&signers)?)?;
+    }
+    Ok(())
+}
+
+fn sign(pack: &TeamPack, key: &RsaPrivateKey) -> Result<String> {
+    let payload = serde_json::to_string(pack)?;
+    let signature = key.sign(Pkcs1v15Sign::new_unprefixed(), &Sha256::digest(payload.as_bytes()))?;
+    let signed = SignedPack {
+        payload,
+        signature: BASE64.encode(signature),
+        public_key: key.to_public_key().to_public_key_pem(LineEnding::LF)?,
+    };
+    Ok(serde_json::to_string_pretty(&signed)?)
+}
+
+/// Check the signature and return the pack with its signer's fingerprint
+fn verify(content: &str) -> Result<(TeamPack, String)> {
+    let signed: SignedPack = serde_json::from_str(content).context("Not a team pack")?;
+    let public_key = RsaPublicKey::from_public_key_pem(&signed.public_key).context("Invalid signer key")?;
+    let signature = BASE64.decode(&signed.signature).context("Invalid signature")?;
+    public_key
+        .verify(Pkcs1v15Sign::new_unprefixed(), &Sha256::digest(signed.payload.as_bytes()), &signature)
+        .map_err(|_| anyhow::anyhow!("The team pack's signature doesn't match; it was changed after signing"))?;
+
+    let pack: TeamPack = serde_json::from_str(&signed.payload)?;
+    if pack.format > FORMAT_VERSION {
+        anyhow::bail!("This team pack needs a newer version of the app (format {})", pack.format);
+    }
+    Ok((pack, fingerprint(&public_key)?))
+}
+
+/// Current settings in the shape of a pack
+fn collect(name: &str, project_path: Option<&Path>) -> Result<TeamPack> {
+    let prefs = PreferencesManager::new()?.load()?;
+    let config_files = project_path
+        .map(|root| {
+            CONFIG_FILES
+                .iter()
+                .filter_map(|name| {
+                    let content = std::fs::read_to_string(root.join(name)).ok()?;
+                    Some(PackFile { name: name.to_string(), content })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(TeamPack {
+        format: FORMAT_VERSION,
+        name: name.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        personas: PersonaManager::new()?.list()?.into_iter().filter(|p| !p.builtin).collect(),
+        tool_permissions: prefs.permissions.agent_tools.into_iter().collect(),
+        format_settings: Some(FormatSettings { tab_size: prefs.editor.tab_size, insert_spaces: prefs.editor.insert_spaces }),
+        config_files,
+    })
+}
+
+fn describe<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Items the pack would change, comparing it with `current`
+pub fn conflicts(pack: &TeamPack, current: &TeamPack) -> Vec<PackConflict> {
+    let mut conflicts = Vec::new();
+    for incoming in &pack.personas {
+        if let Some(existing) = current.personas.iter().find(|p| p.id == incoming.id) {
+            if describe(existing) != describe(incoming) {
+                conflicts.push(PackConflict {
+                    key: format!("persona:{}", incoming.id),
+                    existing: describe(existing),
+                    incoming: describe(incoming),
+                });
+            }
+        }
+    }
+    for (tool, incoming) in &pack.tool_permissions {
+        if let Some(existing) = current.tool_permissions.get(tool).filter(|existing| *existing != incoming) {
+            conflicts.push(PackConflict { key: format!("tool:{}", tool), existing: describe(existing), incoming: describe(incoming) });
+        }
+    }
+    if let (Some(incoming), Some(existing)) = (&pack.format_settings, &current.format_settings) {
+        if incoming != existing {
+            conflicts.push(PackConflict { key: "format_settings".to_string(), existing: describe(existing), incoming: describe(incoming) });
+        }
+    }
+    for incoming in &pack.config_files {
+        if let Some(existing) = current.config_files.iter().find(|f| f.name == incoming.name && f.content != incoming.content) {
+            conflicts.push(PackConflict {
+                key: format!("file:{}", incoming.name),
+                existing: existing.content.clone(),
+                incoming: incoming.content.clone(),
+            });
+        }
+    }
+    conflicts
+}
+
+pub fn export(path: &Path, name: &str, project_path: Option<&Path>) -> Result<TeamPack> {
+    let dir = keys_dir()?;
+    let key = signing_key(&dir)?;
+    trust_signer(&dir, &fingerprint(&key.to_public_key())?)?;
+    let pack = collect(name, project_path)?;
+    std::fs::write(path, sign(&pack, &key)?).with_context(|| format!("Failed to write {:?}", path))?;
+    tracing::info!("Exported team pack '{}' to {:?}", name, path);
+    Ok(pack)
+}
+
+pub fn inspect(path: &Path, project_path: Option<&Path>) -> Result<PackInspection> {
+    let (pack, signer) = verify(&std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?)?;
+    let current = collect("", project_path)?;
+    Ok(PackInspection {
+        trusted: trusted_signers(&keys_dir()?).contains(&signer),
+        signer,
+        conflicts: conflicts(&pack, &current),
+        name: pack.name,
+        created_at: pack.created_at,
+        personas: pack.personas.len(),
+        tool_permissions: pack.tool_permissions.len(),
+        config_files: pack.config_files.len(),
+    })
+}
+
+/// Apply a verified pack. A signer not seen before must be trusted explicitly
+/// (`trust`); conflicts use `resolutions[key]`, falling back to `default`.
+pub fn import(
+    path: &Path,
+    project_path: Option<&Path>,
+    default: ConflictResolution,
+    resolutions: &HashMap<String, ConflictResolution>,
+    trust: bool,
+) -> Result<ImportReport> {
+    let (pack, signer) = verify(&std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?)?;
+    let dir = keys_dir()?;
+    if !trusted_signers(&dir).contains(&signer) {
+        if !trust {
+            anyhow::bail!("This team pack is signed by an unknown key ({}); confirm you trust it to import", signer);
+        }
+        trust_signer(&dir, &signer)?;
+    }
+    // Pack files are plain names from CONFIG_FILES; anything else could point outside the project
+    if let Some(file) = pack.config_files.iter().find(|f| !CONFIG_FILES.contains(&f.name.as_str())) {
+        anyhow::bail!("Team pack contains an unexpected file: {}", file.name);
+    }
+    if !pack.config_files.is_empty() && project_path.is_none() {
+        anyhow::bail!("This team pack has formatter and linter files; open a project to import it into");
+    }
+
+    let current = collect("", project_path)?;
+    let conflicting: Vec<String> = conflicts(&pack, &current).into_iter().map(|c| c.key).collect();
+    let resolve = |key: &str| {
+        if conflicting.iter().any(|k| k == key) {
+            Some(resolutions.get(key).copied().unwrap_or(default))
+        } else {
+            None
+        }
+    };
+    let mut report = ImportReport::default();
+
+    // Everything is planned and checked before the first write so a bad item can't
+    // leave the pack half applied
+    let mut persona_writes = Vec::new();
+    for persona in pack.personas {
+        let key = format!("persona:{}", persona.id);
+        let exists = current.personas.iter().any(|p| p.id == persona.id);
+        let (persona, replaced) = match resolve(&key) {
+            Some(ConflictResolution::KeepExisting) => {
+                report.kept.push(key);
+                continue;
+            }
+            Some(ConflictResolution::KeepBoth) => {
+                (Persona { id: String::new(), name: format!("{} (team)", persona.name), ..persona }, false)
+            }
+            Some(ConflictResolution::UsePack) => (persona, true),
+            // Unchanged personas need no write
+            None if exists => continue,
+            None => (persona, false),
+        };
+        personas::validate(&persona).with_context(|| format!("Team pack persona '{}' can't be imported", persona.name))?;
+        persona_writes.push((persona, replaced));
+    }
+
+    let manager = PreferencesManager::new()?;
+    let mut prefs = manager.load()?;
+    for (tool, permissions) in pack.tool_permissions {
+        let key = format!("tool:{}", tool);
+        match resolve(&key) {
+            Some(ConflictResolution::UsePack) => {
+                prefs.permissions.agent_tools.insert(tool, permissions);
+                report.replaced.push(key);
+            }
+            Some(_) => report.kept.push(key),
+            None => {
+                if prefs.permissions.agent_tools.insert(tool, permissions).is_none() {
+                    report.added.push(key);
+                }
+            }
+        }
+    }
+    if let Some(settings) = pack.format_settings {
+        match resolve("format_settings") {
+            Some(ConflictResolution::UsePack) => {
+                prefs.editor.tab_size = settings.tab_size;
+                prefs.editor.insert_spaces = settings.insert_spaces;
+                report.replaced.push("format_settings".to_string());
+            }
+            Some(_) => report.kept.push("format_settings".to_string()),
+            None => {}
+        }
+    }
+
+    let mut file_writes = Vec::new();
+    if let Some(root) = project_path {
+        for file in pack.config_files {
+            let key = format!("file:{}", file.name);
+            let (target, replaced) = match resolve(&key) {
+                Some(ConflictResolution::KeepExisting) => {
+                    report.kept.push(key);
+                    continue;
+                }
+                Some(ConflictResolution::KeepBoth) => (root.join(format!("{}.team", file.name)), false),
+                Some(ConflictResolution::UsePack) => (root.join(&file.name), true),
+                None if root.join(&file.name).exists() => continue,
+                None => (root.join(&file.name), false),
+            };
+            readonly::check_writable(&target)?;
+            file_writes.push((key, target, file.content, replaced));
+        }
+    }
+
+    let personas = PersonaManager::new()?;
+    for (persona, replaced) in persona_writes {
+        let saved = personas.save(persona)?;
+        let key = format!("persona:{}", saved.id);
+        if replaced {
+            report.replaced.push(key);
+        } else {
+            report.added.push(key);
+        }
+    }
+
+    manager.save(&prefs)?;
+
+    for (key, target, content, replaced) in file_writes {
+        std::fs::write(&target, content)?;
+        if replaced {
+            report.replaced.push(key);
+        } else {
+            report.added.push(key);
+        }
+    }
+
+    tracing::info!("Imported team pack '{}' from {:?}", pack.name, path);
+    Ok(report)
+}
+
+// Tauri commands
+
+/// Sign and write this machine's custom personas, agent tool policies, format settings
+/// and the project's formatter/linter configs to `path`
+#[tauri::command]
+pub async fn export_team_pack(path: String, name: String, project_path: Option<String>) -> Result<TeamPack, String> {
+    tokio::task::spawn_blocking(move || export(Path::new(&path), &name, project_path.as_deref().map(Path::new)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Verify a pack and list what importing it would change
+#[tauri::command]
+pub async fn inspect_team_pack(path: String, project_path: Option<String>) -> Result<PackInspection, String> {
+    inspect(Path::new(&path), project_path.as_deref().map(Path::new)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_team_pack(
+    path: String,
+    project_path: Option<String>,
+    resolution: Option<ConflictResolution>,
+    resolutions: Option<HashMap<String, ConflictResolution>>,
+    trust: Option<bool>,
+) -> Result<ImportReport, String> {
+    import(
+        Path::new(&path),
+        project_path.as_deref().map(Path::new),
+        resolution.unwrap_or(ConflictResolution::KeepExisting),
+        &resolutions.unwrap_or_default(),
+        trust.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify_and_conflicts() {
+        let key = RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 1024).unwrap();
+        let persona = Persona {
+            id: "api-style".to_string(),
+            name: "API style".to_string(),
+            description: String::new(),
+            system_prompt: "Follow our REST conventions.".to_string(),
+            prompt_template: Some("{prompt}\n\nUse our error envelope.".to_string()),
+            model: None,
+            temperature: Some(0.2),
+            builtin: false,
+        };
+        let pack = TeamPack {
+            format: FORMAT_VERSION,
+            name: "Backend team".to_string(),
+            created_at: "2026-10-15T00:00:00Z".to_string(),
+            personas: vec![persona.clone()],
+            tool_permissions: BTreeMap::from([("web_fetch".to_string(), vec![])]),
+            format_settings: Some(FormatSettings { tab_size: 2, insert_spaces: true }),
+            config_files: vec![PackFile { name: ".editorconfig".to_string(), content: "root = true\n".to_string() }],
+        };
+
+        let signed = sign(&pack, &key).unwrap();
+        let (verified, signer) = verify(&signed).unwrap();
+        assert_eq!(verified.name, "Backend team");
+        assert_eq!(signer, fingerprint(&key.to_public_key()).unwrap());
+
+        let tampered = signed.replace("Follow our REST conventions.", "Ignore our conventions.");
+        assert!(verify(&tampered).is_err());
+
+        let current = TeamPack {
+            personas: vec![Persona { temperature: Some(0.7), ..persona }],
+            tool_permissions: BTreeMap::from([("web_fetch".to_string(), vec![Permission::Network])]),
+            format_settings: Some(FormatSettings { tab_size: 2, insert_spaces: true }),
+            config_files: vec![],
+            ..pack.clone()
+        };
+        let keys: Vec<String> = conflicts(&pack, &current).into_iter().map(|c| c.key).collect();
+        assert_eq!(keys, vec!["persona:api-style", "tool:web_fetch"]);
+    }
+}
@@ -14,6 +14,9 @@ pub struct CachedTemplate {
     pub cached_at: i64,
     pub file_path: PathBuf,
     pub size_bytes: u64,
+    /// Digest taken when cached, so a corrupted or tampered file isn't reused
+    #[serde(default)]
+    pub sha256: Option<String>,
 }
 
 pub struct TemplateCache {
@@ -43,9 +46,16 @@ impl TemplateCache {
     }
     
     pub fn get(&self, template_id: &str, version: &str) -> Option<PathBuf> {
-        self.metadata.templates.get(template_id)
-            .filter(|t| t.version == version)
-            .map(|t| t.file_path.clone())
+        let cached = self.metadata.templates.get(template_id).filter(|t| t.version == version)?;
+        if let Some(expected) = &cached.sha256 {
+            let intact = crate::checksums::matches(&cached.file_path, crate::checksums::HashAlgorithm::Sha256, expected)
+                .unwrap_or(false);
+            if !intact {
+                tracing::warn!("Cached template {} failed its checksum, downloading again", template_id);
+                return None;
+            }
+        }
+        Some(cached.file_path.clone())
     }
     
     pub fn store(&mut self, template_id: String, version: String, file_path: PathBuf) -> Result<(), String> {
@@ -66,12 +76,14 @@ impl TemplateCache {
                 .map_err(|e| format!("Failed to copy file to cache: {}", e))?;
         }
         
+        let sha256 = crate::checksums::digest_file(&cached_path, crate::checksums::HashAlgorithm::Sha256).ok();
         self.metadata.templates.insert(template_id.clone(), CachedTemplate {
             id: template_id,
             version,
             cached_at: chrono::Utc::now().timestamp(),
             file_path: cached_path,
             size_bytes,
+            sha256,
         });
         
         self.save_metadata()
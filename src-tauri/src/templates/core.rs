@@ -1,10 +1,14 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Mutex;
 use tauri::{Manager, Emitter};
 use sha2::{Sha256, Digest};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use crate::templates::cache::TemplateCache;
+use crate::package_manager;
+use crate::rust_toolchain;
+use crate::templates::{pinning, runner};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectTemplate {
@@ -277,6 +281,74 @@ impl TemplateLibrary {
             thumbnail: None,
             prompt: "Create an admin panel with Vue.js and Vuetify. Include user management, role-based access control, data tables, and analytics charts.".to_string(),
         });
+
+        self.add_template(ProjectTemplate {
+            id: "go-gin".to_string(),
+            name: "Go Gin API".to_string(),
+            description: "REST API with Go and Gin".to_string(),
+            category: TemplateCategory::API,
+            tech_stack: vec!["Go".to_string(), "Gin".to_string()],
+            features: vec![
+                "Gin router".to_string(),
+                "Health check endpoint".to_string(),
+                "Go modules".to_string(),
+            ],
+            difficulty: Difficulty::Intermediate,
+            estimated_files: 4,
+            thumbnail: None,
+            prompt: "Create a REST API in Go using the Gin framework. Include routing, JSON handlers, a health check endpoint, and graceful error handling.".to_string(),
+        });
+
+        self.add_template(ProjectTemplate {
+            id: "dotnet-webapi".to_string(),
+            name: ".NET Web API".to_string(),
+            description: "ASP.NET Core Web API".to_string(),
+            category: TemplateCategory::API,
+            tech_stack: vec!["C#".to_string(), ".NET".to_string(), "ASP.NET Core".to_string()],
+            features: vec![
+                "Minimal API endpoints".to_string(),
+                "OpenAPI support".to_string(),
+                "Configuration via appsettings".to_string(),
+            ],
+            difficulty: Difficulty::Intermediate,
+            estimated_files: 8,
+            thumbnail: None,
+            prompt: "Create an ASP.NET Core Web API. Include minimal API endpoints, OpenAPI documentation, dependency injection, and appsettings-based configuration.".to_string(),
+        });
+
+        self.add_template(ProjectTemplate {
+            id: "sveltekit".to_string(),
+            name: "SvelteKit App".to_string(),
+            description: "Full-stack web app with SvelteKit".to_string(),
+            category: TemplateCategory::Web,
+            tech_stack: vec!["Svelte".to_string(), "SvelteKit".to_string(), "TypeScript".to_string(), "Vite".to_string()],
+            features: vec![
+                "File-based routing".to_string(),
+                "Server-side rendering".to_string(),
+                "TypeScript".to_string(),
+            ],
+            difficulty: Difficulty::Beginner,
+            estimated_files: 10,
+            thumbnail: None,
+            prompt: "Create a SvelteKit application with TypeScript. Include file-based routing, server load functions, and a shared layout.".to_string(),
+        });
+
+        self.add_template(ProjectTemplate {
+            id: "flutter".to_string(),
+            name: "Flutter App".to_string(),
+            description: "Cross-platform app with Flutter".to_string(),
+            category: TemplateCategory::Mobile,
+            tech_stack: vec!["Dart".to_string(), "Flutter".to_string()],
+            features: vec![
+                "Android, iOS, web and desktop targets".to_string(),
+                "Material design".to_string(),
+                "Widget tests".to_string(),
+            ],
+            difficulty: Difficulty::Intermediate,
+            estimated_files: 15,
+            thumbnail: None,
+            prompt: "Create a Flutter application with Material design. Include navigation between screens, state management, and widget tests.".to_string(),
+        });
     }
     
     pub fn add_template(&mut self, template: ProjectTemplate) {
@@ -316,24 +388,28 @@ impl TemplateLibrary {
 }
 
 // Tauri command
+/// The built-in catalogue is constructed on first use rather than at startup
+static TEMPLATE_LIBRARY: Mutex<Option<TemplateLibrary>> = Mutex::new(None);
+
+fn with_library<T>(f: impl FnOnce(&TemplateLibrary) -> T) -> T {
+    let mut guard = TEMPLATE_LIBRARY.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(|| crate::profiler::time_startup_phase("templates", TemplateLibrary::new)))
+}
+
 #[tauri::command]
 pub async fn list_project_templates() -> Result<Vec<ProjectTemplate>, String> {
-    let library = TemplateLibrary::new();
-    Ok(library.list_templates().into_iter().cloned().collect())
+    Ok(with_library(|library| library.list_templates().into_iter().cloned().collect()))
 }
 
 #[tauri::command]
 pub async fn get_project_template(template_id: String) -> Result<ProjectTemplate, String> {
-    let library = TemplateLibrary::new();
-    library.get_template(&template_id)
-        .cloned()
+    with_library(|library| library.get_template(&template_id).cloned())
         .ok_or_else(|| "Template not found".to_string())
 }
 
 #[tauri::command]
 pub async fn search_templates(query: String) -> Result<Vec<ProjectTemplate>, String> {
-    let library = TemplateLibrary::new();
-    Ok(library.search(&query).into_iter().cloned().collect())
+    Ok(with_library(|library| library.search(&query).into_iter().cloned().collect()))
 }
 
 #[tauri::command]
@@ -351,12 +427,16 @@ pub async fn get_template_cache_size(app: tauri::AppHandle) -> Result<u64, Strin
 }
 
 // Tauri command
+/// With `pin_versions`, npm ranges in package.json are pinned to exact versions
+/// before the command returns. The lockfile and toolchain versions are then recorded
+/// in the background and announced with `template-toolchain-recorded`.
 #[tauri::command]
 pub async fn create_project_from_template(
     app: tauri::AppHandle,
     template_id: String,
     project_name: String,
     location: String,
+    pin_versions: Option<bool>,
 ) -> Result<String, String> {
     use std::process::Command;
     use std::path::Path;
@@ -371,6 +451,9 @@ pub async fn create_project_from_template(
     let full_path_str = full_path.to_str().ok_or("Invalid path")?;
     
     println!("Full path: {}", full_path_str);
+
+    // New projects have no lockfile yet, so this is the user's preference
+    let manager = package_manager::preferred().manager;
     
     match template_id.as_str() {
         "react-vite" => {
@@ -551,46 +634,27 @@ export default App
 
             // 8. Install dependencies (optional)
             app.emit("template-progress", TemplateProgress::installing(0.8, "Installing dependencies...")).ok();
-            let _ = Command::new("cmd")
-                .args(&["/C", "npm", "install"])
-                .current_dir(&full_path)
-                .output();
+            if let Err(e) = manager.install().run(&full_path) {
+                tracing::warn!("{} install failed: {}", manager.program(), e);
+            }
         }
         "react-nextjs" => {
             app.emit("template-progress", TemplateProgress::downloading(0.2, "Running create-next-app...")).ok();
-            let output = Command::new("cmd")
-                .args(&["/C", "npx", "create-next-app@latest", &project_name, "--typescript", "--tailwind", "--app", "--no-git"])
-                .current_dir(&location)
-                .output()
-                .map_err(|e| format!("Failed to create Next.js project: {}", e))?;
-            
-            if !output.status.success() {
-                return Err(String::from_utf8_lossy(&output.stderr).to_string());
-            }
+            let args = [project_name.clone(), "--typescript".into(), "--tailwind".into(), "--app".into(), "--no-git".into(),
+                format!("--use-{}", manager.program())];
+            manager.exec("create-next-app@latest", &args).run(Path::new(&location))?;
         }
         "vue-vite" => {
             app.emit("template-progress", TemplateProgress::downloading(0.2, "Creating Vue project...")).ok();
-            let output = Command::new("cmd")
-                .args(&["/C", "npm", "create", "vite@latest", &project_name, "--", "--template", "vue-ts"])
-                .current_dir(&location)
-                .output()
-                .map_err(|e| format!("Failed to create Vue project: {}", e))?;
-            
-            if !output.status.success() {
-                return Err(String::from_utf8_lossy(&output.stderr).to_string());
-            }
+            manager
+                .create("vite@latest", &project_name, &["--template".to_string(), "vue-ts".to_string()])
+                .run(Path::new(&location))?;
         }
         "angular" => {
             app.emit("template-progress", TemplateProgress::downloading(0.2, "Creating Angular project...")).ok();
-            let output = Command::new("cmd")
-                .args(&["/C", "npx", "@angular/cli@latest", "new", &project_name, "--skip-git"])
-                .current_dir(&location)
-                .output()
-                .map_err(|e| format!("Failed to create Angular project: {}", e))?;
-            
-            if !output.status.success() {
-                return Err(String::from_utf8_lossy(&output.stderr).to_string());
-            }
+            let args = ["new".to_string(), project_name.clone(), "--skip-git".into(),
+                format!("--package-manager={}", manager.program())];
+            manager.exec("@angular/cli@latest", &args).run(Path::new(&location))?;
         }
         "node-express" => {
             app.emit("template-progress", TemplateProgress::downloading(0.1, "Creating project structure...")).ok();
@@ -652,7 +716,7 @@ export default App
                 .map_err(|e| format!("Failed to create .gitignore: {}", e))?;
 
             // 4. Create README.md
-            let readme = format!("# {}\n\nExpress.js API with TypeScript.\n\n## Getting Started\n\n1. Install dependencies:\n   ```bash\n   npm install\n   ```\n\n2. Run development server:\n   ```bash\n   npm run dev\n   ```\n", project_name);
+            let readme = format!("# {}\n\nExpress.js API with TypeScript.\n\n## Getting Started\n\n1. Install dependencies:\n   ```bash\n   {}\n   ```\n\n2. Run development server:\n   ```bash\n   {}\n   ```\n", project_name, manager.install(), manager.run("dev", &[]));
             std::fs::write(full_path.join("README.md"), readme)
                 .map_err(|e| format!("Failed to create README.md: {}", e))?;
 
@@ -694,13 +758,12 @@ app.listen(port, () => {
                 .map_err(|e| format!("Failed to create src/index.ts: {}", e))?;
 
             // 6. Install dependencies (optional, but good for "pre-developed" feel)
-            // We'll try to run npm install, but won't fail the whole process if it fails
+            // We'll try to install, but won't fail the whole process if it fails
             // because the user can run it manually.
             app.emit("template-progress", TemplateProgress::installing(0.8, "Installing dependencies...")).ok();
-            let _ = Command::new("cmd")
-                .args(&["/C", "npm", "install"])
-                .current_dir(&full_path)
-                .output();
+            if let Err(e) = manager.install().run(&full_path) {
+                tracing::warn!("{} install failed: {}", manager.program(), e);
+            }
         }
         "springboot" => {
             app.emit("template-progress", TemplateProgress::initializing("Creating Spring Boot project...")).ok();
@@ -716,8 +779,13 @@ app.listen(port, () => {
                 project_name, project_name, project_name, project_name
             );
             
+            // Extracted aside and moved into place, so a failed extraction leaves no
+            // half-written project and an existing folder is never overwritten
+            let staging = Path::new(&location).join(format!(".{}-extract", project_name));
+            std::fs::create_dir_all(&staging)
+                .map_err(|e| format!("Failed to create extraction directory: {}", e))?;
             let zip_file = format!("{}.zip", project_name);
-            let zip_path = Path::new(&location).join(&zip_file);
+            let zip_path = staging.join(&zip_file);
             
             println!("Download URL: {}", url);
             println!("Zip file path: {:?}", zip_path);
@@ -769,7 +837,7 @@ app.listen(port, () => {
 
                 let output = Command::new("powershell")
                     .args(&["-NoProfile", "-Command", &unzip_script])
-                    .current_dir(&location)
+                    .current_dir(&staging)
                     .output()
                     .map_err(|e| format!("Failed to execute powershell unzip: {}", e))?;
 
@@ -783,7 +851,7 @@ app.listen(port, () => {
                 // Cleanup zip
                 let _ = Command::new("powershell")
                     .args(&["-NoProfile", "-Command", &format!("Remove-Item '{}'", zip_file)])
-                    .current_dir(&location)
+                    .current_dir(&staging)
                     .output();
             }
 
@@ -792,7 +860,7 @@ app.listen(port, () => {
                 // Try unzip first (more common on Linux/Mac)
                 let unzip_result = Command::new("unzip")
                     .args(&["-o", &zip_file])
-                    .current_dir(&location)
+                    .current_dir(&staging)
                     .output();
                 
                 if unzip_result.is_err() || !unzip_result.as_ref().unwrap().status.success() {
@@ -800,7 +868,7 @@ app.listen(port, () => {
                     // Fallback to tar
                     let output = Command::new("tar")
                         .args(&["-xf", &zip_file])
-                        .current_dir(&location)
+                        .current_dir(&staging)
                         .output()
                         .map_err(|e| format!("Failed to unzip: {}", e))?;
                     
@@ -812,7 +880,21 @@ app.listen(port, () => {
                 }
                 
                 // Cleanup
-                let _ = std::fs::remove_file(Path::new(&location).join(&zip_file));
+                let _ = std::fs::remove_file(&zip_path);
+            }
+
+            let options = crate::file_transfer::CopyOptions {
+                conflict: crate::file_transfer::ConflictPolicy::Fail,
+                ..Default::default()
+            };
+            let moved = crate::file_transfer::move_tree(&project_name, &staging.join(&project_name), &full_path, &options, &|progress| {
+                let done = progress.files_done as f32 / progress.files_total.max(1) as f32;
+                app.emit("template-progress", TemplateProgress::extracting(0.6 + 0.3 * done, "Copying project files...")).ok();
+            });
+            let _ = std::fs::remove_dir_all(&staging);
+            if let Err(e) = moved {
+                app.emit("template-progress", TemplateProgress::error(format!("Failed to create project: {}", e))).ok();
+                return Err(format!("Failed to move extracted project into place: {}", e));
             }
             
             app.emit("template-progress", TemplateProgress::installing(0.9, "Verifying project structure...")).ok();
@@ -888,6 +970,7 @@ async def update_item(item_id: int, item: Item):
                 
             let _ = Command::new("cmd")
                 .args(&["/C", "venv\\Scripts\\pip", "install", "-r", "requirements.txt"])
+                .envs(crate::network::proxy_env())
                 .current_dir(&full_path)
                 .output();
         }
@@ -896,6 +979,7 @@ async def update_item(item_id: int, item: Item):
             app.emit("template-progress", TemplateProgress::installing(0.2, "Installing Django...")).ok();
             Command::new("cmd")
                 .args(&["/C", "pip", "install", "django"])
+                .envs(crate::network::proxy_env())
                 .output()
                 .map_err(|e| format!("Failed to install Django: {}", e))?;
             
@@ -909,43 +993,141 @@ async def update_item(item_id: int, item: Item):
         }
         "rust-actix" => {
             // Create Rust project
+            runner::ensure_tool("cargo")?;
             app.emit("template-progress", TemplateProgress::downloading(0.2, "Creating Cargo project...")).ok();
-            Command::new("cmd")
-                .args(&["/C", "cargo", "new", &project_name])
-                .current_dir(&location)
-                .output()
-                .map_err(|e| format!("Failed to create Rust project: {}", e))?;
-            
-            // Add actix-web to Cargo.toml
-            let cargo_toml_path = full_path.join("Cargo.toml");
-            let mut cargo_toml = std::fs::read_to_string(&cargo_toml_path)
-                .map_err(|e| format!("Failed to read Cargo.toml: {}", e))?;
-            
-            cargo_toml.push_str("\nactix-web = \"4.0\"\n");
-            
-            std::fs::write(&cargo_toml_path, cargo_toml)
-                .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
+            runner::run("cargo", &["new", &project_name], Path::new(&location))?;
+
+            app.emit("template-progress", TemplateProgress::installing(0.6, "Adding actix-web...")).ok();
+            if let Err(e) = rust_toolchain::add_dependency(&full_path, "actix-web", Some("4"), &[], false) {
+                // Offline: write the same entry cargo add would, inside [dependencies]
+                tracing::warn!("cargo add failed, editing Cargo.toml directly: {}", e);
+                let cargo_toml_path = full_path.join("Cargo.toml");
+                let cargo_toml = std::fs::read_to_string(&cargo_toml_path)
+                    .map_err(|e| format!("Failed to read Cargo.toml: {}", e))?;
+                let cargo_toml = rust_toolchain::insert_dependency(&cargo_toml, "actix-web", "4").map_err(|e| e.to_string())?;
+                std::fs::write(&cargo_toml_path, cargo_toml)
+                    .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
+            }
         }
         "tauri-react" => {
             app.emit("template-progress", TemplateProgress::downloading(0.2, "Creating Tauri project...")).ok();
-            let output = Command::new("cmd")
-                .args(&["/C", "npm", "create", "tauri-app@latest", &project_name, "--", "--template", "react-ts"])
-                .current_dir(&location)
-                .output()
-                .map_err(|e| format!("Failed to create Tauri project: {}", e))?;
-            
-            if !output.status.success() {
-                return Err(String::from_utf8_lossy(&output.stderr).to_string());
+            let args = ["--template".to_string(), "react-ts".into(), "--manager".into(), manager.program().into(), "--yes".into()];
+            manager.create("tauri-app@latest", &project_name, &args).run(Path::new(&location))?;
+        }
+        "go-gin" => {
+            runner::ensure_tool("go")?;
+            app.emit("template-progress", TemplateProgress::downloading(0.1, "Creating Go module...")).ok();
+            std::fs::create_dir_all(&full_path)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+            runner::run("go", &["mod", "init", &go_module_name(&project_name)], &full_path)?;
+
+            let main_go = "package main\n\nimport (\n\t\"net/http\"\n\n\t\"github.com/gin-gonic/gin\"\n)\n\nfunc main() {\n\trouter := gin.Default()\n\n\trouter.GET(\"/\", func(c *gin.Context) {\n\t\tc.JSON(http.StatusOK, gin.H{\"message\": \"Welcome to your Gin API!\"})\n\t})\n\n\trouter.GET(\"/health\", func(c *gin.Context) {\n\t\tc.JSON(http.StatusOK, gin.H{\"status\": \"ok\"})\n\t})\n\n\trouter.Run(\":8080\")\n}\n";
+            std::fs::write(full_path.join("main.go"), main_go)
+                .map_err(|e| format!("Failed to create main.go: {}", e))?;
+            std::fs::write(full_path.join(".gitignore"), "bin/\n*.exe\n.env\n")
+                .map_err(|e| format!("Failed to create .gitignore: {}", e))?;
+            let readme = format!("# {}\n\nGo API built with Gin.\n\n## Getting Started\n\n```bash\ngo run .\n```\n\nThe server listens on http://localhost:8080.\n", project_name);
+            std::fs::write(full_path.join("README.md"), readme)
+                .map_err(|e| format!("Failed to create README.md: {}", e))?;
+
+            app.emit("template-progress", TemplateProgress::installing(0.6, "Fetching Gin...")).ok();
+            let fetched = runner::run("go", &["get", "github.com/gin-gonic/gin"], &full_path)
+                .and_then(|_| runner::run("go", &["mod", "tidy"], &full_path));
+            if let Err(e) = fetched {
+                // The project is usable once the network is back; `go mod tidy` will finish the job
+                tracing::warn!("Go dependencies not installed: {}", e);
             }
         }
+        "dotnet-webapi" => {
+            runner::ensure_tool("dotnet")?;
+            app.emit("template-progress", TemplateProgress::downloading(0.2, "Running dotnet new webapi...")).ok();
+            runner::run("dotnet", &["new", "webapi", "--name", &project_name, "--output", full_path_str], Path::new(&location))?;
+
+            app.emit("template-progress", TemplateProgress::installing(0.8, "Adding .gitignore...")).ok();
+            if let Err(e) = runner::run("dotnet", &["new", "gitignore"], &full_path) {
+                tracing::warn!("Failed to add .gitignore: {}", e);
+            }
+        }
+        "sveltekit" => {
+            app.emit("template-progress", TemplateProgress::downloading(0.2, "Creating SvelteKit project...")).ok();
+            let args = ["create", project_name.as_str(), "--template", "minimal", "--types", "ts", "--no-add-ons", "--no-install"]
+                .map(String::from);
+            manager.exec("sv", &args).run(Path::new(&location))?;
+
+            app.emit("template-progress", TemplateProgress::installing(0.7, "Installing dependencies...")).ok();
+            if let Err(e) = manager.install().run(&full_path) {
+                tracing::warn!("{} install failed: {}", manager.program(), e);
+            }
+        }
+        "flutter" => {
+            runner::ensure_tool("flutter")?;
+            app.emit("template-progress", TemplateProgress::downloading(0.2, "Running flutter create...")).ok();
+            // The directory keeps the user's name; the Dart package needs a lowercase identifier
+            runner::run(
+                "flutter",
+                &["create", "--org", "com.example", "--project-name", &dart_package_name(&project_name), &project_name],
+                Path::new(&location),
+            )?;
+        }
         _ => return Err(format!("Unknown template: {}", template_id)),
     }
     
+    let pin_versions = pin_versions.unwrap_or(false) && full_path.join("package.json").exists();
+    let pinned = if pin_versions {
+        app.emit("template-progress", TemplateProgress::installing(0.9, "Pinning dependency versions...")).ok();
+        pinning::pin_package_json(&full_path).await.map_err(|e| format!("Failed to pin dependencies: {}", e))?
+    } else {
+        Vec::new()
+    };
+    let (record_app, record_path) = (app.clone(), full_path.clone());
+    tokio::task::spawn_blocking(move || {
+        match pinning::record_toolchain(&record_path, Some(template_id), pinned, pin_versions) {
+            Ok(record) => {
+                record_app.emit("template-toolchain-recorded", serde_json::json!({
+                    "path": record_path,
+                    "record": record,
+                })).ok();
+            }
+            Err(e) => tracing::warn!("Failed to record toolchain: {}", e),
+        }
+    });
+    
     app.emit("template-progress", TemplateProgress::complete("Project created successfully!")).ok();
     println!("Project created successfully at {}", full_path_str);
     Ok(full_path_str.to_string())
 }
 
+/// Go module path from a project name, e.g. "My API" -> "my-api"
+fn go_module_name(project_name: &str) -> String {
+    let name: String = project_name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-' { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-').to_string();
+    if name.is_empty() { "app".to_string() } else { name }
+}
+
+/// Dart package name: lowercase letters, digits and underscores, not starting with a digit
+fn dart_package_name(project_name: &str) -> String {
+    let mut name: String = project_name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    while name.contains("__") {
+        name = name.replace("__", "_");
+    }
+    let name = name.trim_matches('_').to_string();
+    match name.chars().next() {
+        None => "app".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("app_{}", name),
+        Some(_) => name,
+    }
+}
+
 fn download_springboot(url: &str, zip_path: &std::path::Path, app: &tauri::AppHandle) -> Result<(), String> {
     use crate::templates::network::{retry_with_backoff, RetryConfig};
     use std::process::Command;
@@ -965,6 +1147,7 @@ fn download_springboot(url: &str, zip_path: &std::path::Path, app: &tauri::AppHa
             || {
                 let output = Command::new("powershell")
                     .args(&["-NoProfile", "-Command", &download_script])
+                    .envs(crate::network::proxy_env())
                     .output()
                     .map_err(|e| format!("Failed to execute powershell: {}", e))?;
 
@@ -986,6 +1169,7 @@ fn download_springboot(url: &str, zip_path: &std::path::Path, app: &tauri::AppHa
             || {
                 let output = Command::new("curl")
                     .args(&["-L", "-o", &zip_path.to_string_lossy(), url])
+                    .envs(crate::network::proxy_env())
                     .output()
                     .map_err(|e| format!("Failed to execute curl: {}", e))?;
                 
@@ -1002,3 +1186,21 @@ fn download_springboot(url: &str, zip_path: &std::path::Path, app: &tauri::AppHa
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaffold_names() {
+        assert_eq!(go_module_name("My API"), "my-api");
+        assert_eq!(go_module_name("  !!  "), "app");
+        assert_eq!(dart_package_name("My-Cool App"), "my_cool_app");
+        assert_eq!(dart_package_name("2048 game"), "app_2048_game");
+
+        let library = TemplateLibrary::new();
+        for id in ["go-gin", "dotnet-webapi", "sveltekit", "flutter"] {
+            assert!(library.get_template(id).is_some(), "missing catalog entry for {}", id);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 // Module declarations
 pub mod network;
 pub mod cache;
+pub mod runner;
+pub mod pinning;
 mod core;
 
 use serde::{Deserialize, Serialize};
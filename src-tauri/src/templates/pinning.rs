@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use futures::StreamExt;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::package_manager;
+use crate::templates::runner;
+
+const NPM_REGISTRY: &str = "https://registry.npmjs.org";
+/// Registry lookups in flight at once
+const LOOKUP_CONCURRENCY: usize = 8;
+const DEPENDENCY_SECTIONS: &[&str] = &["dependencies", "devDependencies", "optionalDependencies"];
+
+/// How a created project was built, kept in `.sai-metadata/toolchain.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolchainRecord {
+    pub template_id: Option<String>,
+    pub recorded_at: String,
+    /// Tool name to the version it reported, e.g. "node" -> "v20.11.0"
+    pub tools: BTreeMap<String, String>,
+    pub pinned: Vec<PinnedDependency>,
+    /// Lockfile written at creation time, relative to the project
+    pub lockfile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinnedDependency {
+    pub name: String,
+    pub range: String,
+    /// None when the range couldn't be resolved and was left as it was
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+struct Version(u64, u64, u64);
+
+impl Version {
+    /// Release versions only; prereleases are never picked for a range
+    fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim_start_matches('v').split('.');
+        let version = Version(
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+        );
+        parts.next().is_none().then_some(version)
+    }
+}
+
+/// The `(min, max_exclusive)` a range allows. Handles what scaffolders write —
+/// `^1.2.3`, `~1.2`, `1.2.3`, `*`, `latest` — and gives up on the rest.
+fn range_bounds(range: &str) -> Option<(Version, Option<Version>)> {
+    let range = range.trim();
+    if range.is_empty() || range == "*" || range == "latest" {
+        return Some((Version(0, 0, 0), None));
+    }
+    let (operator, rest) = match range.chars().next()? {
+        '^' | '~' => (&range[..1], &range[1..]),
+        c if c.is_ascii_digit() => ("", range),
+        _ => return None,
+    };
+    let parts: Vec<u64> = rest
+        .split('.')
+        .take_while(|p| !matches!(*p, "x" | "*"))
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    let min = Version(parts[0], *parts.get(1).unwrap_or(&0), *parts.get(2).unwrap_or(&0));
+    let Version(major, minor, patch) = min;
+    let max = match operator {
+        // ^ allows changes that don't touch the left-most non-zero part
+        "^" if major > 0 || parts.len() == 1 => Version(major + 1, 0, 0),
+        "^" if minor > 0 || parts.len() == 2 => Version(0, minor + 1, 0),
+        "^" => Version(0, 0, patch + 1),
+        "~" if parts.len() == 1 => Version(major + 1, 0, 0),
+        "~" => Version(major, minor + 1, 0),
+        // Bare versions: 1.2.3 is exact, 1.2 and 1 mean 1.2.x and 1.x
+        _ => match parts.len() {
+            3 => Version(major, minor, patch + 1),
+            2 => Version(major, minor + 1, 0),
+            _ => Version(major + 1, 0, 0),
+        },
+    };
+    Some((min, Some(max)))
+}
+
+/// Whether a release is in an `engines`-style range: `||` alternatives of space-separated
+/// comparators such as `>=18 <21`, each read with `range_bounds`. None if any part can't be read.
+pub(crate) fn satisfies(version: &str, range: &str) -> Option<bool> {
+    let version = Version::parse(version)?;
+    let mut any = false;
+    for alternative in range.split("||") {
+        let mut all = true;
+        for comparator in alternative.split_whitespace() {
+            let (operator, rest) = match comparator.find(|c: char| c.is_ascii_digit() || c == '^' || c == '~') {
+                Some(i) => comparator.split_at(i),
+                None if matches!(comparator, "*" | "latest" | "x") => ("", comparator),
+                None => return None,
+            };
+            let (min, max) = range_bounds(rest)?;
+            let below_max = max.map_or(true, |max| version < max);
+            all &= match operator {
+                "" | "=" => version >= min && below_max,
+                ">=" => version >= min,
+                // >18 means 19 and up, while >18.1.2 means 18.1.3 and up
+                ">" => !below_max,
+                "<" => version < min,
+                "<=" => below_max,
+                _ => return None,
+            };
+        }
+        any |= all;
+    }
+    Some(any)
+}
+
+/// Highest published release in `range`
+fn resolve(range: &str, published: &[String]) -> Option<String> {
+    let (min, max) = range_bounds(range)?;
+    published
+        .iter()
+        .filter_map(|v| Some((Version::parse(v)?, v)))
+        .filter(|(v, _)| *v >= min && max.map_or(true, |max| *v < max))
+        .max_by_key(|(v, _)| *v)
+        .map(|(_, text)| text.clone())
+}
+
+async fn published_versions(client: &reqwest::Client, name: &str) -> Result<Vec<String>> {
+    #[derive(Deserialize)]
+    struct Packument {
+        versions: BTreeMap<String, serde_json::Value>,
+    }
+    // Scoped names keep their @ but the slash must be escaped
+    let url = format!("{}/{}", NPM_REGISTRY, name.replace('/', "%2F"));
+    let packument: Packument = client
+        .get(url)
+        // The abbreviated document is a fraction of the full one
+        .header("Accept", "application/vnd.npm.install-v1+json")
+        .send()
+        .await?
+        .error_for_status()
+        .with_context(|| format!("{} not found on npm", name))?
+        .json()
+        .await?;
+    Ok(packument.versions.into_keys().collect())
+}
+
+/// Replace `"name": "range"` with the pinned version, leaving the rest of the file
+/// (key order, formatting) as the template wrote it
+fn rewrite(package_json: &str, name: &str, range: &str, version: &str) -> String {
+    let pattern = format!(r#""{}"(\s*:\s*)"{}""#, regex::escape(name), regex::escape(range));
+    match regex::Regex::new(&pattern) {
+        Ok(re) => re
+            .replace_all(package_json, |c: &regex::Captures| format!(r#""{}"{}"{}""#, name, &c[1], version))
+            .to_string(),
+        Err(_) => package_json.to_string(),
+    }
+}
+
+/// Pin every dependency range in `package.json` to the exact version npm resolves it to today
+pub async fn pin_package_json(project_path: &Path) -> Result<Vec<PinnedDependency>> {
+    let path = project_path.join("package.json");
+    let mut text = std::fs::read_to_string(&path).context("No package.json in the project")?;
+    let manifest: serde_json::Value = serde_json::from_str(&text)?;
+
+    let mut ranges: Vec<(String, String)> = Vec::new();
+    for section in DEPENDENCY_SECTIONS {
+        if let Some(deps) = manifest.get(section).and_then(|d| d.as_object()) {
+            ranges.extend(deps.iter().filter_map(|(name, range)| Some((name.clone(), range.as_str()?.to_string()))));
+        }
+    }
+
+    let client = crate::network::client();
+    let lookups = ranges.into_iter().map(|(name, range)| {
+        let client = client.clone();
+        async move {
+            // Already exact, or something like a git URL or workspace: protocol
+            let needs_lookup = range_bounds(&range).is_some() && Version::parse(&range).is_none();
+            let version = if needs_lookup {
+                match published_versions(&client, &name).await {
+                    Ok(published) => resolve(&range, &published),
+                    Err(e) => {
+                        tracing::warn!("Could not resolve {}@{}: {}", name, range, e);
+                        None
+                    }
+                }
+            } else {
+                Version::parse(&range).map(|_| range.clone())
+            };
+            PinnedDependency { name, range, version }
+        }
+    });
+    let pinned: Vec<PinnedDependency> = futures::stream::iter(lookups).buffer_unordered(LOOKUP_CONCURRENCY).collect().await;
+
+    for dependency in &pinned {
+        if let Some(version) = dependency.version.as_ref().filter(|v| **v != dependency.range) {
+            text = rewrite(&text, &dependency.name, &dependency.range, version);
+        }
+    }
+    std::fs::write(&path, text)?;
+
+    let mut pinned = pinned;
+    pinned.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(pinned)
+}
+
+/// Write the project's lockfile without downloading packages or running install scripts
+pub fn generate_lockfile(project_path: &Path) -> Result<String, String> {
+    let manager = package_manager::for_project(project_path);
+    let command = manager
+        .lockfile_only()
+        .ok_or_else(|| format!("{} cannot write a lockfile without installing; run `{}`", manager.program(), manager.install()))?;
+    command.run(project_path)?;
+    Ok(manager.lockfile().to_string())
+}
+
+/// Versions of the toolchains a project's files say it uses
+pub fn toolchain_versions(project_path: &Path) -> BTreeMap<String, String> {
+    let has = |name: &str| project_path.join(name).exists();
+    let mut tools: Vec<(&str, &[&str])> = Vec::new();
+    if has("package.json") {
+        let manager = package_manager::for_project(project_path).program();
+        tools.extend([("node", &["--version"][..]), (manager, &["--version"][..])]);
+    }
+    if has("Cargo.toml") || has("src-tauri") {
+        tools.extend([("cargo", &["--version"][..]), ("rustc", &["--version"][..])]);
+    }
+    if has("go.mod") {
+        tools.push(("go", &["version"][..]));
+    }
+    if has("pubspec.yaml") {
+        tools.push(("flutter", &["--version"][..]));
+    }
+    if has("requirements.txt") || has("pyproject.toml") {
+        tools.push(("python", &["--version"][..]));
+    }
+    let is_dotnet = std::fs::read_dir(project_path)
+        .map(|entries| entries.flatten().any(|e| e.path().extension().map_or(false, |x| x == "csproj")))
+        .unwrap_or(false);
+    if is_dotnet {
+        tools.push(("dotnet", &["--version"][..]));
+    }
+
+    tools
+        .into_iter()
+        .filter_map(|(tool, args)| {
+            let output = runner::run(tool, args, project_path).ok()?;
+            // Python 2 and some Flutter versions print to stderr
+            let text = [output.stdout, output.stderr].concat();
+            let first_line = String::from_utf8_lossy(&text).lines().next()?.trim().to_string();
+            Some((tool.to_string(), first_line))
+        })
+        .collect()
+}
+
+fn record_path(project_path: &Path) -> std::path::PathBuf {
+    project_path.join(".sai-metadata").join("toolchain.json")
+}
+
+pub fn save_record(project_path: &Path, record: &ToolchainRecord) -> Result<()> {
+    let path = record_path(project_path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(record)?)?;
+    Ok(())
+}
+
+pub fn load_record(project_path: &Path) -> Result<Option<ToolchainRecord>> {
+    let path = record_path(project_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&std::fs::read_to_string(path)?)?))
+}
+
+/// Write the lockfile when asked, look up toolchain versions and save the record.
+/// Runs package managers and compilers, so call it off the async runtime.
+pub fn record_toolchain(
+    project_path: &Path,
+    template_id: Option<String>,
+    pinned: Vec<PinnedDependency>,
+    write_lockfile: bool,
+) -> Result<ToolchainRecord> {
+    let lockfile = if write_lockfile && project_path.join("package.json").exists() {
+        match generate_lockfile(project_path) {
+            Ok(lockfile) => Some(lockfile),
+            Err(e) => {
+                tracing::warn!("Lockfile not generated: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let record = ToolchainRecord {
+        template_id,
+        recorded_at: chrono::Utc::now().to_rfc3339(),
+        tools: toolchain_versions(project_path),
+        pinned,
+        lockfile,
+    };
+    save_record(project_path, &record)?;
+    Ok(record)
+}
+
+// Tauri commands
+
+/// Pin an existing project's npm dependencies to exact versions and regenerate its lockfile
+#[tauri::command]
+pub async fn pin_project_dependencies(project_path: String) -> Result<ToolchainRecord, String> {
+    let path = std::path::PathBuf::from(&project_path);
+    let template_id = load_record(&path).ok().flatten().and_then(|r| r.template_id);
+    let pinned = pin_package_json(&path).await.map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || record_toolchain(&path, template_id, pinned, true))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_project_toolchain(project_path: String) -> Result<Option<ToolchainRecord>, String> {
+    load_record(Path::new(&project_path)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_ranges() {
+        let published: Vec<String> = ["0.2.1", "0.2.9", "0.3.0", "1.4.0", "1.9.2", "2.0.0-rc.1", "2.1.0"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        assert_eq!(resolve("^1.4.0", &published).as_deref(), Some("1.9.2"));
+        assert_eq!(resolve("~1.4.0", &published).as_deref(), Some("1.4.0"));
+        assert_eq!(resolve("^0.2.1", &published).as_deref(), Some("0.2.9"));
+        assert_eq!(resolve("latest", &published).as_deref(), Some("2.1.0"));
+        assert_eq!(resolve("^2", &published).as_deref(), Some("2.1.0"));
+        assert_eq!(resolve("github:user/repo", &published), None);
+        assert_eq!(satisfies("20.11.0", ">=18 <21"), Some(true));
+        assert_eq!(satisfies("16.20.2", "^18 || ^20"), Some(false));
+        assert_eq!(satisfies("v18.1.3", ">18.1.2"), Some(true));
+
+        let json = "{\n  \"dependencies\": {\n    \"react\": \"^18.2.0\"\n  }\n}";
+        assert_eq!(rewrite(json, "react", "^18.2.0", "18.3.1"), "{\n  \"dependencies\": {\n    \"react\": \"18.3.1\"\n  }\n}");
+    }
+}
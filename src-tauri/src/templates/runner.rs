@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use crate::terminal::shell::find_in_path;
+
+/// Toolchains the scaffolders shell out to, with where to get them
+const TOOLS: &[(&str, &str, &str)] = &[
+    ("go", "Go", "https://go.dev/dl/"),
+    ("dotnet", "the .NET SDK", "https://dotnet.microsoft.com/download"),
+    ("flutter", "the Flutter SDK", "https://docs.flutter.dev/get-started/install"),
+    ("node", "Node.js", "https://nodejs.org/"),
+    ("npm", "npm (bundled with Node.js)", "https://nodejs.org/"),
+    ("npx", "npx (bundled with Node.js)", "https://nodejs.org/"),
+    ("pnpm", "pnpm", "https://pnpm.io/installation"),
+    ("yarn", "Yarn", "https://yarnpkg.com/getting-started/install"),
+    ("bun", "Bun", "https://bun.sh/"),
+    ("bunx", "bunx (bundled with Bun)", "https://bun.sh/"),
+    ("cargo", "Rust", "https://rustup.rs/"),
+    ("python", "Python", "https://www.python.org/downloads/"),
+];
+
+fn resolve(program: &str) -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        // Node and Flutter also ship extensionless shell scripts next to the real launchers
+        for ext in ["exe", "cmd", "bat"] {
+            if let Some(path) = find_in_path(&format!("{}.{}", program, ext)) {
+                return Some(path);
+            }
+        }
+    }
+    find_in_path(program)
+}
+
+/// Resolve `program` on PATH, or explain what to install
+pub fn ensure_tool(program: &str) -> Result<PathBuf, String> {
+    resolve(program).ok_or_else(|| {
+        let (name, url) = TOOLS
+            .iter()
+            .find(|(tool, _, _)| *tool == program)
+            .map(|(_, name, url)| (*name, *url))
+            .unwrap_or((program, ""));
+        let mut message = format!("`{}` was not found on PATH. Install {}", program, name);
+        if !url.is_empty() {
+            message.push_str(&format!(" from {}", url));
+        }
+        message.push_str(", then restart the IDE so it picks up the new PATH.");
+        message
+    })
+}
+
+/// Build a command for `program` that works the same on every OS: the program is
+/// resolved to its full path, so Windows `.cmd` shims such as npm run without `cmd /C`
+pub fn command(program: &str, args: &[&str], dir: &Path) -> Result<Command, String> {
+    let executable = ensure_tool(program)?;
+    let mut command = Command::new(executable);
+    command
+        .args(args)
+        .current_dir(dir)
+        .envs(crate::network::proxy_env());
+    Ok(command)
+}
+
+/// Run to completion, turning a failed exit into an error carrying the tool's own output
+pub fn run(program: &str, args: &[&str], dir: &Path) -> Result<Output, String> {
+    let output = command(program, args, dir)?
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let detail = if stderr.trim().is_empty() { stdout } else { stderr };
+        return Err(format!("`{} {}` failed: {}", program, args.join(" "), detail.trim()));
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,170 @@
+pub mod shell;
+pub mod ssh;
+pub mod problem_matchers;
+
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use std::process::Stdio;
+use std::time::Instant;
+
+use shell::{ShellProfile, ShellProfileManager};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandRequest {
+    pub command: String,
+    pub args: Vec<String>,
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandResponse {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+    pub success: bool,
+}
+
+/// The command line as typed: what runs, and what history records, so `git commit`
+/// and `git push` aren't both recorded as `git`
+fn command_line(request: &CommandRequest) -> String {
+    let mut line = request.command.clone();
+    for arg in &request.args {
+        line.push(' ');
+        if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || "'\"$`\\|&;<>()*?#~".contains(c)) {
+            line.push_str(&crate::remote::shell_quote(arg));
+        } else {
+            line.push_str(arg);
+        }
+    }
+    line
+}
+
+pub struct TerminalExecutor {
+    profile: ShellProfile,
+}
+
+impl TerminalExecutor {
+    pub fn new() -> Self {
+        Self {
+            profile: ShellProfile::default(),
+        }
+    }
+
+    /// Use the shell profile configured for a project
+    pub fn for_project(project_id: Option<&str>) -> Result<Self> {
+        let manager = ShellProfileManager::new()?;
+        Ok(Self {
+            profile: manager.get_profile(project_id)?,
+        })
+    }
+
+    pub fn execute(&self, request: CommandRequest) -> Result<CommandResponse> {
+        let mut cmd = self.profile.build_command(&command_line(&request));
+
+        // Set working directory if provided, with the project's Python venv active
+        if let Some(dir) = request.working_dir {
+            cmd.envs(crate::python_envs::activation_env(std::path::Path::new(&dir)));
+            cmd.current_dir(dir);
+        }
+
+        // Execute command
+        let output = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let exit_code = output.status.code().unwrap_or(-1);
+
+        Ok(CommandResponse {
+            stdout,
+            stderr,
+            exit_code,
+            success: output.status.success(),
+        })
+    }
+}
+
+/// Kill a child started in its own process group along with everything it forked;
+/// killing the child alone only stops the shell
+pub async fn kill_process_tree(child: &mut tokio::process::Child) {
+    if let Some(pid) = child.id() {
+        #[cfg(unix)]
+        let status = tokio::process::Command::new("kill").args(["-TERM", "--", &format!("-{}", pid)]).status().await;
+        #[cfg(windows)]
+        let status = tokio::process::Command::new("taskkill").args(["/T", "/F", "/PID", &pid.to_string()]).status().await;
+        if let Err(e) = status {
+            tracing::warn!("Failed to stop process group {}: {}", pid, e);
+        }
+    }
+    child.kill().await.ok();
+}
+
+// Tauri commands
+
+/// Record an executed command in the history database
+fn record_history(request: &CommandRequest, response: &CommandResponse, duration_ms: i64) {
+    use crate::database::{DatabaseManager, TerminalHistoryEntry};
+
+    let entry = TerminalHistoryEntry {
+        id: 0,
+        project_id: request.project_id.clone(),
+        command: command_line(request),
+        working_dir: request.working_dir.clone(),
+        exit_code: response.exit_code,
+        duration_ms,
+        executed_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let result = DatabaseManager::new().and_then(|db| db.add_terminal_history(&entry));
+    if let Err(e) = result {
+        tracing::warn!("Failed to record terminal history: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn execute_command(request: CommandRequest) -> Result<CommandResponse, String> {
+    let executor = TerminalExecutor::for_project(request.project_id.as_deref())
+        .map_err(|e| e.to_string())?;
+
+    let started = Instant::now();
+    let remote_dir = request.working_dir.as_deref().and_then(crate::remote::parse_remote_path);
+
+    let response = if let Some(remote_dir) = remote_dir {
+        let output = crate::remote::execute_in(&remote_dir, &command_line(&request))
+            .map_err(|e| e.to_string())?;
+
+        CommandResponse {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            exit_code: output.status.code().unwrap_or(-1),
+            success: output.status.success(),
+        }
+    } else {
+        executor.execute(request.clone())
+            .map_err(|e| e.to_string())?
+    };
+
+    record_history(&request, &response, started.elapsed().as_millis() as i64);
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line_keeps_arguments() {
+        let request = CommandRequest {
+            command: "git".to_string(),
+            args: vec!["commit".to_string(), "-m".to_string(), "fix: it's done".to_string()],
+            working_dir: None,
+            project_id: None,
+        };
+        assert_eq!(command_line(&request), "git commit -m 'fix: it'\\''s done'");
+    }
+}
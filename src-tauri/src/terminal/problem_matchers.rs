@@ -0,0 +1,296 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::diagnostics::{Diagnostic, Severity};
+
+lazy_static::lazy_static! {
+    static ref ANSI: Regex = Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap();
+}
+
+pub const BUILTINS: &[&str] = &["tsc", "eslint", "cargo", "pytest"];
+
+/// Regexes with named groups `file`, `line` and optionally `column`, `severity`
+/// and `message`. A multi-line matcher spreads the groups over consecutive lines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProblemMatcher {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Consecutive lines, e.g. rustc's message line followed by its `-->` location
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+    /// Used when there is no `severity` group or it doesn't parse
+    #[serde(default)]
+    pub severity: Severity,
+    /// Keep matching the last pattern after a problem is reported, for output that
+    /// lists several problems under one file heading (eslint's stylish format)
+    #[serde(default)]
+    pub loop_last: bool,
+}
+
+/// A built-in referenced as `"$tsc"`, or a custom matcher
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MatcherSpec {
+    Builtin(String),
+    Custom(ProblemMatcher),
+}
+
+fn single(pattern: &str) -> ProblemMatcher {
+    ProblemMatcher { pattern: Some(pattern.to_string()), patterns: Vec::new(), severity: Severity::Error, loop_last: false }
+}
+
+fn multi(patterns: &[&str], loop_last: bool) -> ProblemMatcher {
+    ProblemMatcher {
+        pattern: None,
+        patterns: patterns.iter().map(|p| p.to_string()).collect(),
+        severity: Severity::Error,
+        loop_last,
+    }
+}
+
+pub fn builtin(name: &str) -> Option<Vec<ProblemMatcher>> {
+    let matchers = match name.trim_start_matches('$') {
+        "tsc" => vec![
+            // src/app.ts(12,5): error TS2322: ...
+            single(r"^(?P<file>[^\s(][^(]*)\((?P<line>\d+),(?P<column>\d+)\): (?P<severity>error|warning|info) TS\d+: (?P<message>.*)$"),
+            // --pretty: src/app.ts:12:5 - error TS2322: ...
+            single(r"^(?P<file>(?:[A-Za-z]:)?[^\s:][^:]*):(?P<line>\d+):(?P<column>\d+) - (?P<severity>error|warning|info) TS\d+: (?P<message>.*)$"),
+        ],
+        "eslint" => vec![
+            // --format unix
+            single(r"^(?P<file>(?:[A-Za-z]:)?[^\s:][^:]*):(?P<line>\d+):(?P<column>\d+): (?P<message>.*?)(?: \[(?P<severity>Error|Warning)/[^\]]*\])?$"),
+            // Default stylish format: the file on its own line, then indented problems
+            multi(
+                &[
+                    r"^(?P<file>(?:[A-Za-z]:)?[/\\]\S.*?)\s*$",
+                    r"^\s+(?P<line>\d+):(?P<column>\d+)\s+(?P<severity>error|warning)\s+(?P<message>.+?)(?:\s{2,}[\w@/-]+)?\s*$",
+                ],
+                true,
+            ),
+        ],
+        "cargo" => vec![multi(
+            &[
+                r"^(?P<severity>error|warning)(?:\[\w+\])?: (?P<message>.+)$",
+                r"^\s*--> (?P<file>(?:[A-Za-z]:)?[^:]+):(?P<line>\d+):(?P<column>\d+)$",
+            ],
+            false,
+        )],
+        // Traceback locations: tests/test_api.py:42: AssertionError
+        "pytest" => vec![single(r"^(?P<file>[^\s:]+\.py):(?P<line>\d+): (?P<message>.+)$")],
+        _ => return None,
+    };
+    Some(matchers)
+}
+
+fn expand(specs: &[MatcherSpec]) -> Result<Vec<ProblemMatcher>> {
+    let mut matchers = Vec::new();
+    for spec in specs {
+        match spec {
+            MatcherSpec::Builtin(name) => matchers.extend(builtin(name).with_context(|| {
+                format!("Unknown problem matcher '{}'; built-ins are ${}", name, BUILTINS.join(", $"))
+            })?),
+            MatcherSpec::Custom(matcher) => matchers.push(matcher.clone()),
+        }
+    }
+    Ok(matchers)
+}
+
+struct CompiledMatcher {
+    patterns: Vec<Regex>,
+    severity: Severity,
+    loop_last: bool,
+}
+
+impl ProblemMatcher {
+    fn compile(&self) -> Result<CompiledMatcher> {
+        let sources: Vec<&String> = self.pattern.iter().chain(self.patterns.iter()).collect();
+        if sources.is_empty() {
+            anyhow::bail!("Problem matcher needs a `pattern` or `patterns`");
+        }
+        let patterns = sources
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("Invalid problem matcher '{}'", p)))
+            .collect::<Result<Vec<_>>>()?;
+        for group in ["file", "line"] {
+            if !patterns.iter().any(|r| r.capture_names().flatten().any(|name| name == group)) {
+                anyhow::bail!("Problem matcher '{}' needs a named `{}` group", sources[0], group);
+            }
+        }
+        Ok(CompiledMatcher { patterns, severity: self.severity, loop_last: self.loop_last })
+    }
+}
+
+/// Check matcher specs without running anything, e.g. before saving tasks.json
+pub fn validate(specs: &[MatcherSpec]) -> Result<()> {
+    for matcher in expand(specs)? {
+        matcher.compile()?;
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct MatchState {
+    /// Index of the pattern the next line has to match; 0 when idle
+    next: usize,
+    captures: HashMap<String, String>,
+}
+
+/// Turns a stream of output lines into diagnostics. Keep one engine per stream,
+/// since multi-line matchers carry state from one line to the next.
+pub struct MatcherEngine {
+    matchers: Vec<CompiledMatcher>,
+    states: Vec<MatchState>,
+    cwd: PathBuf,
+    source: String,
+}
+
+impl MatcherEngine {
+    /// Relative paths in the output resolve against `cwd`
+    pub fn new(specs: &[MatcherSpec], cwd: &Path, source: &str) -> Result<Self> {
+        let matchers = expand(specs)?.iter().map(ProblemMatcher::compile).collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            states: matchers.iter().map(|_| MatchState::default()).collect(),
+            matchers,
+            cwd: cwd.to_path_buf(),
+            source: source.to_string(),
+        })
+    }
+
+    fn diagnostic(&self, captures: &HashMap<String, String>, default_severity: Severity) -> Option<Diagnostic> {
+        let file = captures.get("file")?.trim();
+        let file = if Path::new(file).is_absolute() { PathBuf::from(file) } else { self.cwd.join(file) };
+        Some(Diagnostic {
+            file: file.to_string_lossy().to_string(),
+            line: captures.get("line")?.parse().ok()?,
+            column: captures.get("column").and_then(|c| c.parse().ok()),
+            end_column: None,
+            severity: captures
+                .get("severity")
+                .and_then(|s| Severity::parse(s))
+                .unwrap_or(default_severity),
+            message: captures.get("message").map(|m| m.trim().to_string()).unwrap_or_default(),
+            source: self.source.clone(),
+            suggestions: Vec::new(),
+        })
+    }
+
+    pub fn feed(&mut self, line: &str) -> Vec<Diagnostic> {
+        let line = ANSI.replace_all(line, "");
+        let mut diagnostics = Vec::new();
+
+        for i in 0..self.matchers.len() {
+            let matcher = &self.matchers[i];
+            let last = matcher.patterns.len() - 1;
+            let mut state = std::mem::take(&mut self.states[i]);
+
+            // Continue a multi-line match in progress, or start over from the first pattern
+            let continued = state.next > 0 && accumulate(&matcher.patterns[state.next], &line, &mut state.captures);
+            if !continued {
+                state = MatchState::default();
+                if !accumulate(&matcher.patterns[0], &line, &mut state.captures) {
+                    self.states[i] = state;
+                    continue;
+                }
+            }
+
+            if state.next == last {
+                diagnostics.extend(self.diagnostic(&state.captures, matcher.severity));
+                if !(matcher.loop_last && last > 0) {
+                    state = MatchState::default();
+                }
+            } else {
+                state.next += 1;
+            }
+            self.states[i] = state;
+        }
+
+        diagnostics
+    }
+}
+
+fn accumulate(pattern: &Regex, line: &str, captures: &mut HashMap<String, String>) -> bool {
+    let Some(found) = pattern.captures(line) else {
+        return false;
+    };
+    for name in pattern.capture_names().flatten() {
+        if let Some(value) = found.name(name) {
+            captures.insert(name.to_string(), value.as_str().to_string());
+        }
+    }
+    true
+}
+
+/// Run matchers over complete output, e.g. a finished terminal command
+pub fn parse_output(specs: &[MatcherSpec], output: &str, cwd: &Path, source: &str) -> Result<Vec<Diagnostic>> {
+    let mut engine = MatcherEngine::new(specs, cwd, source)?;
+    Ok(output.lines().flat_map(|line| engine.feed(line)).collect())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_builtin_problem_matchers() -> Result<Vec<String>, String> {
+    Ok(BUILTINS.iter().map(|name| format!("${}", name)).collect())
+}
+
+/// Parse tool output into diagnostics, optionally publishing them under `source`
+#[tauri::command]
+pub async fn parse_problems(
+    app: tauri::AppHandle,
+    output: String,
+    matchers: Vec<MatcherSpec>,
+    cwd: String,
+    source: String,
+    publish: bool,
+) -> Result<Vec<Diagnostic>, String> {
+    let diagnostics = parse_output(&matchers, &output, Path::new(&cwd), &source).map_err(|e| e.to_string())?;
+    if publish {
+        crate::diagnostics::publish(Some(&app), &source, diagnostics.clone());
+    }
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str, output: &str) -> Vec<Diagnostic> {
+        parse_output(&[MatcherSpec::Builtin(name.to_string())], output, Path::new("/repo"), name).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_matchers() {
+        let cargo = parse(
+            "$cargo",
+            "   Compiling app v0.1.0\nwarning: unused variable: `x`\n --> src/main.rs:2:9\n  |\nerror[E0308]: mismatched types\n  --> src/lib.rs:10:5\nerror: could not compile `app`\n",
+        );
+        assert_eq!(cargo.len(), 2);
+        assert_eq!(cargo[0].severity, Severity::Warning);
+        assert_eq!(cargo[0].message, "unused variable: `x`");
+        assert_eq!((cargo[1].line, cargo[1].column), (10, Some(5)));
+        assert_eq!(cargo[1].file, Path::new("/repo").join("src/lib.rs").to_string_lossy());
+
+        let eslint = parse(
+            "$eslint",
+            "\n/repo/src/a.js\n  1:10  error    'x' is defined but never used  no-unused-vars\n  3:1   warning  Unexpected console statement   no-console\n\n✖ 2 problems\n",
+        );
+        assert_eq!(eslint.len(), 2);
+        assert_eq!(eslint[0].file, "/repo/src/a.js");
+        assert_eq!(eslint[0].message, "'x' is defined but never used");
+        assert_eq!(eslint[1].severity, Severity::Warning);
+
+        let tsc = parse("$tsc", "\x1b[96msrc/app.ts\x1b[0m:4:7 - \x1b[91merror\x1b[0m TS2322: Type 'string' is not assignable to type 'number'.\n");
+        assert_eq!(tsc.len(), 1);
+        assert_eq!(tsc[0].line, 4);
+
+        let pytest = parse("$pytest", "    assert total == 3\nE   assert 2 == 3\ntests/test_cart.py:14: AssertionError\n");
+        assert_eq!(pytest[0].message, "AssertionError");
+
+        assert!(validate(&[MatcherSpec::Builtin("$gcc".to_string())]).is_err());
+        let custom = ProblemMatcher { pattern: Some(r"(?P<file>\S+)".to_string()), ..single("") };
+        assert!(validate(&[MatcherSpec::Custom(custom)]).is_err());
+    }
+}
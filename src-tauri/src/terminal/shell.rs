@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ShellKind {
+    PowerShell,
+    Cmd,
+    Bash,
+    Zsh,
+    Fish,
+    Sh,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellInfo {
+    pub kind: ShellKind,
+    pub name: String,
+    pub path: String,
+    pub is_default: bool,
+}
+
+/// Shell configuration used by the terminal for a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellProfile {
+    pub shell: ShellKind,
+    pub shell_path: Option<String>, // Falls back to the detected executable
+    pub startup_args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub source_user_profile: bool, // Opt-in: load ~/.bashrc, $PROFILE etc. so nvm/pyenv/cargo are on PATH
+}
+
+impl Default for ShellProfile {
+    /// `sh -c` / `cmd /C` without user profiles, so commands run as they always have
+    /// until the user picks a shell
+    fn default() -> Self {
+        Self {
+            shell: if cfg!(target_os = "windows") { ShellKind::Cmd } else { ShellKind::Sh },
+            shell_path: None,
+            startup_args: Vec::new(),
+            env: HashMap::new(),
+            source_user_profile: false,
+        }
+    }
+}
+
+impl ShellProfile {
+    /// Build a process that runs `command_line` through this shell
+    pub fn build_command(&self, command_line: &str) -> Command {
+        let program = self.shell_path.clone()
+            .unwrap_or_else(|| executable_name(&self.shell).to_string());
+
+        let mut cmd = Command::new(program);
+        cmd.args(&self.startup_args);
+
+        match self.shell {
+            ShellKind::PowerShell => {
+                if !self.source_user_profile {
+                    cmd.arg("-NoProfile");
+                }
+                cmd.args(&["-NoLogo", "-Command", command_line]);
+            }
+            ShellKind::Cmd => {
+                cmd.args(&["/C", command_line]);
+            }
+            ShellKind::Bash => {
+                if self.source_user_profile {
+                    // Login + interactive so both .bash_profile and .bashrc are read
+                    cmd.args(&["-l", "-i", "-c", command_line]);
+                } else {
+                    cmd.args(&["--noprofile", "--norc", "-c", command_line]);
+                }
+            }
+            ShellKind::Zsh => {
+                if self.source_user_profile {
+                    cmd.args(&["-l", "-i", "-c", command_line]);
+                } else {
+                    cmd.args(&["-f", "-c", command_line]);
+                }
+            }
+            ShellKind::Fish => {
+                if self.source_user_profile {
+                    cmd.args(&["-l", "-c", command_line]);
+                } else {
+                    cmd.args(&["--no-config", "-c", command_line]);
+                }
+            }
+            ShellKind::Sh => {
+                if self.source_user_profile {
+                    cmd.args(&["-l", "-c", command_line]);
+                } else {
+                    cmd.args(&["-c", command_line]);
+                }
+            }
+        }
+
+        cmd.envs(&self.env);
+        cmd
+    }
+}
+
+/// The platform's usual interactive shell, marked as the default in the shell picker
+fn default_shell_kind() -> ShellKind {
+    if cfg!(target_os = "windows") {
+        ShellKind::PowerShell
+    } else if cfg!(target_os = "macos") {
+        ShellKind::Zsh
+    } else {
+        ShellKind::Bash
+    }
+}
+
+fn executable_name(kind: &ShellKind) -> &'static str {
+    match kind {
+        ShellKind::PowerShell => if cfg!(target_os = "windows") { "powershell.exe" } else { "pwsh" },
+        ShellKind::Cmd => "cmd.exe",
+        ShellKind::Bash => "bash",
+        ShellKind::Zsh => "zsh",
+        ShellKind::Fish => "fish",
+        ShellKind::Sh => "sh",
+    }
+}
+
+/// Look up an executable on PATH
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+
+    for dir in std::env::split_paths(&path_var) {
+        let candidate = dir.join(name);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+
+        if cfg!(target_os = "windows") && Path::new(name).extension().is_none() {
+            // npm, yarn and friends are installed as .cmd shims
+            for ext in ["exe", "cmd"] {
+                let candidate = dir.join(format!("{}.{}", name, ext));
+                if candidate.is_file() {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Detect shells installed on this machine
+pub fn detect_shells() -> Vec<ShellInfo> {
+    let default_kind = default_shell_kind();
+    let mut shells = Vec::new();
+
+    let candidates: Vec<(ShellKind, &str, Vec<&str>)> = vec![
+        (ShellKind::PowerShell, "PowerShell", vec!["pwsh", "powershell.exe"]),
+        (ShellKind::Cmd, "Command Prompt", vec!["cmd.exe"]),
+        (ShellKind::Bash, "Bash", vec!["bash"]),
+        (ShellKind::Zsh, "Zsh", vec!["zsh"]),
+        (ShellKind::Fish, "Fish", vec!["fish"]),
+        (ShellKind::Sh, "sh", vec!["sh"]),
+    ];
+
+    for (kind, name, executables) in candidates {
+        let found = executables.iter().find_map(|exe| find_in_path(exe));
+
+        // Git for Windows ships bash outside PATH
+        let found = found.or_else(|| {
+            if cfg!(target_os = "windows") && kind == ShellKind::Bash {
+                let git_bash = PathBuf::from(r"C:\Program Files\Git\bin\bash.exe");
+                if git_bash.exists() {
+                    return Some(git_bash);
+                }
+            }
+            None
+        });
+
+        if let Some(path) = found {
+            shells.push(ShellInfo {
+                is_default: kind == default_kind,
+                kind,
+                name: name.to_string(),
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    shells
+}
+
+/// Persists shell profiles per project
+pub struct ShellProfileManager {
+    profiles_path: PathBuf,
+}
+
+impl ShellProfileManager {
+    pub fn new() -> Result<Self> {
+        let app_dir = dirs::data_dir()
+            .context("Failed to get data directory")?
+            .join(".sai-ide");
+
+        std::fs::create_dir_all(&app_dir)?;
+
+        Ok(Self {
+            profiles_path: app_dir.join("terminal_profiles.json"),
+        })
+    }
+
+    fn load_all(&self) -> Result<HashMap<String, ShellProfile>> {
+        if !self.profiles_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let json = std::fs::read_to_string(&self.profiles_path)?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse {}", self.profiles_path.display()))
+    }
+
+    /// Get the profile for a project, or the default profile
+    pub fn get_profile(&self, project_id: Option<&str>) -> Result<ShellProfile> {
+        let profiles = self.load_all()?;
+
+        let profile = project_id
+            .and_then(|id| profiles.get(id))
+            .or_else(|| profiles.get("default"))
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(profile)
+    }
+
+    pub fn save_profile(&self, project_id: &str, profile: ShellProfile) -> Result<()> {
+        let mut profiles = self.load_all()?;
+        profiles.insert(project_id.to_string(), profile);
+
+        let json = serde_json::to_string_pretty(&profiles)?;
+        std::fs::write(&self.profiles_path, json)?;
+
+        tracing::info!("Saved terminal profile for {}", project_id);
+        Ok(())
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_available_shells() -> Result<Vec<ShellInfo>, String> {
+    Ok(detect_shells())
+}
+
+#[tauri::command]
+pub async fn get_terminal_profile(project_id: Option<String>) -> Result<ShellProfile, String> {
+    let manager = ShellProfileManager::new()
+        .map_err(|e| e.to_string())?;
+
+    manager.get_profile(project_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_terminal_profile(project_id: String, profile: ShellProfile) -> Result<(), String> {
+    let manager = ShellProfileManager::new()
+        .map_err(|e| e.to_string())?;
+
+    manager.save_profile(&project_id, profile)
+        .map_err(|e| e.to_string())
+}
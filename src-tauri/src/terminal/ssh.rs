@@ -0,0 +1,546 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::Mutex;
+use tauri::Emitter;
+
+use crate::secrets;
+
+/// A saved remote host. Authentication is key-based; passwords are never stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshHost {
+    pub id: String,
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub identity_file: Option<String>, // Defaults to the ssh agent / ~/.ssh keys
+    /// A private key saved with `set_ssh_host_key` is in the OS credential store; it
+    /// takes precedence over `identity_file`
+    #[serde(default)]
+    pub has_stored_key: bool,
+}
+
+/// Whether the host's key is in known_hosts, with the fingerprints the host presents now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostKeyStatus {
+    pub known: bool,
+    pub fingerprints: Vec<String>,
+}
+
+/// A stored private key written out for one ssh invocation; removed on drop
+struct StoredIdentity(PathBuf);
+
+impl StoredIdentity {
+    fn write(key: &str) -> Result<Self> {
+        let dir = dirs::data_dir().context("Failed to get data directory")?.join(".sai-ide").join("ssh-identities");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(uuid::Uuid::new_v4().to_string());
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            // ssh refuses keys other users can read
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path).context("Failed to write the stored SSH key")?;
+        let identity = Self(path);
+        file.write_all(key.trim_end().as_bytes())?;
+        file.write_all(b"\n")?;
+        Ok(identity)
+    }
+}
+
+impl Drop for StoredIdentity {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
+
+/// Arguments for one ssh/sftp invocation. Keep it alive until the process exits:
+/// it owns the temporary file of a stored key.
+pub struct Connection {
+    pub args: Vec<String>,
+    _identity: Option<StoredIdentity>,
+}
+
+fn key_secret(host_id: &str) -> String {
+    format!("ssh-key:{}", host_id)
+}
+
+impl SshHost {
+    pub fn destination(&self) -> String {
+        format!("{}@{}", self.username, self.host)
+    }
+
+    /// How the host appears in known_hosts
+    fn known_hosts_name(&self) -> String {
+        if self.port == 22 {
+            self.host.clone()
+        } else {
+            format!("[{}]:{}", self.host, self.port)
+        }
+    }
+
+    /// Common options for ssh/sftp invocations. Fails until the host key has been
+    /// confirmed with `trust_ssh_host_key`; unknown keys are never accepted silently.
+    pub fn connection_args(&self, port_flag: &str) -> Result<Connection> {
+        if !host_key_known(self)? {
+            anyhow::bail!("The host key of {} hasn't been confirmed yet", self.host);
+        }
+
+        let mut args = vec![
+            port_flag.to_string(),
+            self.port.to_string(),
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            "StrictHostKeyChecking=yes".to_string(),
+        ];
+
+        let stored = if self.has_stored_key {
+            let key = secrets::get_secret(&key_secret(&self.id))?.context("The stored SSH key is missing")?;
+            Some(StoredIdentity::write(&key)?)
+        } else {
+            None
+        };
+        let identity = stored.as_ref().map(|s| s.0.to_string_lossy().to_string()).or_else(|| self.identity_file.clone());
+        if let Some(identity) = identity {
+            args.push("-i".to_string());
+            args.push(identity);
+            args.push("-o".to_string());
+            args.push("IdentitiesOnly=yes".to_string());
+        }
+
+        Ok(Connection { args, _identity: stored })
+    }
+}
+
+fn host_key_known(host: &SshHost) -> Result<bool> {
+    let output = Command::new("ssh-keygen")
+        .args(["-F", &host.known_hosts_name()])
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run ssh-keygen. Is OpenSSH installed?")?;
+    // Exit 0 with a match; 1 when the host isn't listed or there is no known_hosts file
+    Ok(output.status.success() && !output.stdout.is_empty())
+}
+
+/// The host's public keys in known_hosts form, straight from the server
+fn scan_host_keys(host: &SshHost) -> Result<String> {
+    let output = Command::new("ssh-keyscan")
+        .args(["-p", &host.port.to_string(), "-T", "10", &host.host])
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run ssh-keyscan")?;
+    let keys = String::from_utf8_lossy(&output.stdout).to_string();
+    if keys.lines().all(|l| l.trim().is_empty() || l.starts_with('#')) {
+        anyhow::bail!("{} did not present a host key: {}", host.host, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(keys)
+}
+
+fn fingerprints(keys: &str) -> Result<Vec<String>> {
+    let mut child = Command::new("ssh-keygen")
+        .args(["-l", "-f", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to run ssh-keygen")?;
+    child.stdin.take().context("Failed to open ssh-keygen stdin")?.write_all(keys.as_bytes())?;
+    let output = child.wait_with_output()?;
+
+    // "256 SHA256:abc… host (ED25519)" -> "ED25519 SHA256:abc…"
+    let mut fingerprints: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.nth(1)?;
+            let kind = line.rsplit_once('(')?.1.trim_end_matches(')');
+            Some(format!("{} {}", kind, hash))
+        })
+        .collect();
+    fingerprints.sort();
+    fingerprints.dedup();
+    Ok(fingerprints)
+}
+
+pub fn host_key_status(host: &SshHost) -> Result<HostKeyStatus> {
+    Ok(HostKeyStatus { known: host_key_known(host)?, fingerprints: fingerprints(&scan_host_keys(host)?)? })
+}
+
+/// Add the host's keys to ~/.ssh/known_hosts once the user has confirmed `expected`.
+/// Keys are scanned again and must still match what the user saw.
+pub fn trust_host_key(host: &SshHost, expected: &[String]) -> Result<()> {
+    let keys = scan_host_keys(host)?;
+    let mut expected = expected.to_vec();
+    expected.sort();
+    if fingerprints(&keys)? != expected {
+        anyhow::bail!("The host key of {} changed since it was shown; check it again", host.host);
+    }
+
+    let ssh_dir = dirs::home_dir().context("Failed to get home directory")?.join(".ssh");
+    std::fs::create_dir_all(&ssh_dir)?;
+    let mut known_hosts = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(ssh_dir.join("known_hosts"))
+        .context("Failed to open known_hosts")?;
+    for line in keys.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#')) {
+        writeln!(known_hosts, "{}", line)?;
+    }
+    tracing::info!("Trusted host key of {}", host.host);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshSessionInfo {
+    pub session_id: String,
+    pub host_id: String,
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshOutput {
+    pub session_id: String,
+    pub stream: String, // "stdout" or "stderr"
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshSessionClosed {
+    pub session_id: String,
+    pub exit_code: Option<i32>,
+}
+
+struct SshSession {
+    info: SshSessionInfo,
+    child: Child,
+    stdin: ChildStdin,
+    _connection: Connection,
+}
+
+// Live interactive sessions
+static SSH_SESSIONS: Mutex<Option<HashMap<String, SshSession>>> = Mutex::new(None);
+
+fn with_sessions<T>(f: impl FnOnce(&mut HashMap<String, SshSession>) -> T) -> T {
+    let mut sessions = SSH_SESSIONS.lock().unwrap();
+    f(sessions.get_or_insert_with(HashMap::new))
+}
+
+pub struct SshHostManager {
+    hosts_path: PathBuf,
+}
+
+impl SshHostManager {
+    pub fn new() -> Result<Self> {
+        let app_dir = dirs::data_dir()
+            .context("Failed to get data directory")?
+            .join(".sai-ide");
+
+        std::fs::create_dir_all(&app_dir)?;
+
+        Ok(Self {
+            hosts_path: app_dir.join("ssh_hosts.json"),
+        })
+    }
+
+    pub fn list_hosts(&self) -> Result<Vec<SshHost>> {
+        if !self.hosts_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = std::fs::read_to_string(&self.hosts_path)?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", self.hosts_path.display()))
+    }
+
+    pub fn get_host(&self, host_id: &str) -> Result<SshHost> {
+        self.list_hosts()?
+            .into_iter()
+            .find(|h| h.id == host_id)
+            .context("SSH host not found")
+    }
+
+    pub fn save_host(&self, mut host: SshHost) -> Result<SshHost> {
+        if host.id.is_empty() {
+            host.id = uuid::Uuid::new_v4().to_string();
+        }
+
+        let mut hosts = self.list_hosts()?;
+        // The stored key flag is only changed through set_key
+        host.has_stored_key = hosts.iter().any(|h| h.id == host.id && h.has_stored_key);
+        hosts.retain(|h| h.id != host.id);
+        hosts.push(host.clone());
+
+        std::fs::write(&self.hosts_path, serde_json::to_string_pretty(&hosts)?)?;
+        Ok(host)
+    }
+
+    pub fn delete_host(&self, host_id: &str) -> Result<()> {
+        let mut hosts = self.list_hosts()?;
+        hosts.retain(|h| h.id != host_id);
+        std::fs::write(&self.hosts_path, serde_json::to_string_pretty(&hosts)?)?;
+        secrets::delete_secret(&key_secret(host_id))?;
+        Ok(())
+    }
+
+    /// Keep the host's private key in the OS credential store, or remove it with `None`
+    pub fn set_key(&self, host_id: &str, private_key: Option<&str>) -> Result<SshHost> {
+        let mut hosts = self.list_hosts()?;
+        let host = hosts.iter_mut().find(|h| h.id == host_id).context("SSH host not found")?;
+        match private_key.map(str::trim).filter(|k| !k.is_empty()) {
+            Some(key) => {
+                if !key.starts_with("-----BEGIN") {
+                    anyhow::bail!("Paste the private key in OpenSSH or PEM format");
+                }
+                secrets::store_secret(&key_secret(host_id), key)?;
+                host.has_stored_key = true;
+            }
+            None => {
+                secrets::delete_secret(&key_secret(host_id))?;
+                host.has_stored_key = false;
+            }
+        }
+        let host = host.clone();
+        std::fs::write(&self.hosts_path, serde_json::to_string_pretty(&hosts)?)?;
+        Ok(host)
+    }
+}
+
+/// Run a single non-interactive command on the remote host
+pub fn run_remote_command(host: &SshHost, command: &str) -> Result<std::process::Output> {
+    let connection = host.connection_args("-p")?;
+    let output = Command::new("ssh")
+        .args(&connection.args)
+        .arg(host.destination())
+        .arg(command)
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run ssh. Is OpenSSH installed?")?;
+
+    Ok(output)
+}
+
+/// Quote a path for an sftp batch file
+fn sftp_quote(path: &str) -> String {
+    format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Run sftp commands from a batch on stdin; any failing command aborts the batch
+fn run_sftp(host: &SshHost, batch: &str) -> Result<std::process::Output> {
+    let connection = host.connection_args("-P")?;
+    let mut child = Command::new("sftp")
+        .args(&connection.args)
+        .args(["-b", "-"])
+        .arg(host.destination())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run sftp. Is OpenSSH installed?")?;
+    child.stdin.take().context("Failed to open sftp stdin")?.write_all(batch.as_bytes())?;
+    Ok(child.wait_with_output()?)
+}
+
+/// Upload a single file over SFTP
+pub fn upload_file(host: &SshHost, local_path: &str, remote_path: &str) -> Result<()> {
+    let output = run_sftp(host, &format!("put {} {}\n", sftp_quote(local_path), sftp_quote(remote_path)))?;
+
+    if !output.status.success() {
+        anyhow::bail!("Upload failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    tracing::info!("Uploaded {} to {}:{}", local_path, host.host, remote_path);
+    Ok(())
+}
+
+/// Download a single file over SFTP
+pub fn download_file(host: &SshHost, remote_path: &str, local_path: &str) -> Result<()> {
+    if let Some(parent) = Path::new(local_path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let output = run_sftp(host, &format!("get {} {}\n", sftp_quote(remote_path), sftp_quote(local_path)))?;
+
+    if !output.status.success() {
+        anyhow::bail!("Download failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    tracing::info!("Downloaded {}:{} to {}", host.host, remote_path, local_path);
+    Ok(())
+}
+
+fn spawn_reader<R: Read + Send + 'static>(
+    app: tauri::AppHandle,
+    session_id: String,
+    stream: &'static str,
+    mut reader: R,
+) {
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    app.emit("ssh-output", SshOutput {
+                        session_id: session_id.clone(),
+                        stream: stream.to_string(),
+                        data: String::from_utf8_lossy(&buf[..n]).to_string(),
+                    }).ok();
+                }
+            }
+        }
+
+        if stream == "stdout" {
+            // Wait outside the lock so other sessions aren't blocked on this one exiting
+            let session = with_sessions(|sessions| sessions.remove(&session_id));
+            let exit_code = session
+                .and_then(|mut s| s.child.wait().ok())
+                .and_then(|status| status.code());
+
+            app.emit("ssh-session-closed", SshSessionClosed {
+                session_id,
+                exit_code,
+            }).ok();
+        }
+    });
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_ssh_hosts() -> Result<Vec<SshHost>, String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    manager.list_hosts().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_ssh_host(host: SshHost) -> Result<SshHost, String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    manager.save_host(host).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_ssh_host(host_id: String) -> Result<(), String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    manager.delete_host(&host_id).map_err(|e| e.to_string())
+}
+
+/// Open an interactive shell; output arrives as `ssh-output` events
+#[tauri::command]
+pub async fn open_ssh_session(app: tauri::AppHandle, host_id: String) -> Result<SshSessionInfo, String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    let host = manager.get_host(&host_id).map_err(|e| e.to_string())?;
+
+    let connection = host.connection_args("-p").map_err(|e| e.to_string())?;
+    let mut child = Command::new("ssh")
+        .arg("-tt") // Force a remote PTY so prompts and colors behave
+        .args(&connection.args)
+        .arg(host.destination())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start ssh: {}", e))?;
+
+    let stdin = child.stdin.take().ok_or("Failed to open ssh stdin")?;
+    let stdout = child.stdout.take().ok_or("Failed to open ssh stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to open ssh stderr")?;
+
+    let info = SshSessionInfo {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        host_id,
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    with_sessions(|sessions| {
+        sessions.insert(info.session_id.clone(), SshSession {
+            info: info.clone(),
+            child,
+            stdin,
+            _connection: connection,
+        });
+    });
+
+    spawn_reader(app.clone(), info.session_id.clone(), "stdout", stdout);
+    spawn_reader(app, info.session_id.clone(), "stderr", stderr);
+
+    tracing::info!("Opened SSH session {} to {}", info.session_id, host.host);
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn ssh_send_input(session_id: String, data: String) -> Result<(), String> {
+    with_sessions(|sessions| {
+        let session = sessions.get_mut(&session_id).ok_or("SSH session not found")?;
+        session.stdin.write_all(data.as_bytes())
+            .and_then(|_| session.stdin.flush())
+            .map_err(|e| format!("Failed to write to session: {}", e))
+    })
+}
+
+#[tauri::command]
+pub async fn close_ssh_session(session_id: String) -> Result<(), String> {
+    let session = with_sessions(|sessions| sessions.remove(&session_id));
+
+    if let Some(mut session) = session {
+        session.child.kill().ok();
+        tracing::info!("Closed SSH session {}", session_id);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_ssh_sessions() -> Result<Vec<SshSessionInfo>, String> {
+    Ok(with_sessions(|sessions| sessions.values().map(|s| s.info.clone()).collect()))
+}
+
+/// Store the private key for a host in the OS credential store; `None` removes it
+#[tauri::command]
+pub async fn set_ssh_host_key(host_id: String, private_key: Option<String>) -> Result<SshHost, String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    manager.set_key(&host_id, private_key.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Fingerprints the host presents, for the user to confirm before the first connection
+#[tauri::command]
+pub async fn scan_ssh_host_key(host_id: String) -> Result<HostKeyStatus, String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    let host = manager.get_host(&host_id).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || host_key_status(&host))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Trust the host key the user confirmed from `scan_ssh_host_key`
+#[tauri::command]
+pub async fn trust_ssh_host_key(host_id: String, fingerprints: Vec<String>) -> Result<(), String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    let host = manager.get_host(&host_id).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || trust_host_key(&host, &fingerprints))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn sftp_upload_file(host_id: String, local_path: String, remote_path: String) -> Result<(), String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    let host = manager.get_host(&host_id).map_err(|e| e.to_string())?;
+    upload_file(&host, &local_path, &remote_path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn sftp_download_file(host_id: String, remote_path: String, local_path: String) -> Result<(), String> {
+    let manager = SshHostManager::new().map_err(|e| e.to_string())?;
+    let host = manager.get_host(&host_id).map_err(|e| e.to_string())?;
+    download_file(&host, &remote_path, &local_path).map_err(|e| e.to_string())
+}
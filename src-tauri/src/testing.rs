@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 use std::process::Command;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::package_manager;
+use crate::python_envs;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestRunner {
@@ -31,6 +34,22 @@ pub struct TestResult {
     pub failures: Vec<TestFailure>,
 }
 
+/// One finished run, kept in `.sai-metadata/test_runs.json` for activity digests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestRunRecord {
+    pub ran_at: String,
+    pub framework: TestFramework,
+    pub total_tests: u32,
+    pub passed: u32,
+    pub failed: u32,
+    pub skipped: u32,
+    pub duration_ms: u64,
+}
+
+const RUN_LOG_FILE: &str = "test_runs.json";
+/// Older runs are dropped past this
+const RUN_LOG_LIMIT: usize = 200;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Coverage {
     pub lines: f32,
@@ -44,6 +63,9 @@ pub struct TestFailure {
     pub test_name: String,
     pub error_message: String,
     pub stack_trace: Option<String>,
+    /// Test file, when the runner reports it
+    #[serde(default)]
+    pub file: Option<String>,
 }
 
 impl TestRunner {
@@ -84,45 +106,110 @@ impl TestRunner {
             return Ok(TestFramework::Go);
         }
         
+        // What project detection found when the project was opened
+        if let Some(framework) = crate::project_profile::load(project_path).and_then(|p| p.test_framework) {
+            return Ok(framework);
+        }
+        
         anyhow::bail!("Could not detect test framework")
     }
     
     /// Run tests
     pub async fn run_tests(&self) -> Result<TestResult> {
+        self.run_with(&[]).await
+    }
+    
+    /// Re-run only the tests behind `failures`
+    pub async fn run_failures(&self, failures: &[TestFailure]) -> Result<TestResult> {
+        self.run_with(&self.filter_args(failures)).await
+    }
+    
+    /// Run only the given test files, for runners that take files as arguments
+    pub async fn run_files(&self, files: &[String]) -> Result<TestResult> {
         match self.framework {
-            TestFramework::Jest => self.run_jest().await,
-            TestFramework::Vitest => self.run_vitest().await,
-            TestFramework::PyTest => self.run_pytest().await,
-            TestFramework::Cargo => self.run_cargo_test().await,
-            TestFramework::Go => self.run_go_test().await,
-            TestFramework::Mocha => self.run_mocha().await,
-            TestFramework::JUnit => self.run_junit().await,
+            TestFramework::Jest | TestFramework::Vitest | TestFramework::Mocha | TestFramework::PyTest => {
+                self.run_with(files).await
+            }
+            _ => anyhow::bail!("{:?} can't run individual test files", self.framework),
         }
     }
     
-    async fn run_jest(&self) -> Result<TestResult> {
-        let output = Command::new("npm")
-            .args(&["test", "--", "--json", "--coverage"])
-            .current_dir(&self.project_path)
-            .output()?;
+    async fn run_with(&self, filter: &[String]) -> Result<TestResult> {
+        let result = match self.framework {
+            TestFramework::Jest => self.run_jest(filter).await,
+            TestFramework::Vitest => self.run_vitest(filter).await,
+            TestFramework::PyTest => self.run_pytest(filter).await,
+            TestFramework::Cargo => self.run_cargo_test(filter).await,
+            TestFramework::Go => self.run_go_test(filter).await,
+            TestFramework::Mocha => self.run_mocha(filter).await,
+            TestFramework::JUnit => self.run_junit(filter).await,
+        };
+        if let Ok(result) = &result {
+            if let Err(e) = record_run(&self.project_path, &self.framework, result) {
+                tracing::warn!("Failed to record test run: {}", e);
+            }
+        }
+        result
+    }
+    
+    /// Runner arguments that select the given failures
+    fn filter_args(&self, failures: &[TestFailure]) -> Vec<String> {
+        let mut files: Vec<String> = failures.iter().filter_map(|f| f.file.clone()).collect();
+        files.sort();
+        files.dedup();
+        let names = failures.iter().map(|f| f.test_name.clone());
+        
+        match self.framework {
+            // Test path patterns
+            TestFramework::Jest | TestFramework::Vitest | TestFramework::Mocha => files,
+            // Node ids, e.g. tests/test_api.py::test_login
+            TestFramework::PyTest => names.collect(),
+            // libtest accepts several name filters
+            TestFramework::Cargo => names.collect(),
+            TestFramework::Go => {
+                let pattern = names.map(|n| regex::escape(&n)).collect::<Vec<_>>().join("|");
+                vec!["-run".to_string(), format!("^({})$", pattern)]
+            }
+            TestFramework::JUnit => vec![format!("-Dtest={}", names.collect::<Vec<_>>().join(","))],
+        }
+    }
+    
+    /// A package.json script through the project's package manager
+    fn script_command(&self, script: &str, args: &[&str], filter: &[String]) -> Command {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).chain(filter.iter().cloned()).collect();
+        let script = package_manager::for_project(&self.project_path).run(script, &args);
+        let mut command = Command::new(script.program);
+        command.args(&script.args).current_dir(&self.project_path);
+        command
+    }
+
+    async fn run_jest(&self, filter: &[String]) -> Result<TestResult> {
+        let output = self.script_command("test", &["--json", "--coverage"], filter).output()?;
         
         let stdout = String::from_utf8_lossy(&output.stdout);
         self.parse_jest_output(&stdout)
     }
     
-    async fn run_vitest(&self) -> Result<TestResult> {
-        let output = Command::new("npm")
-            .args(&["run", "test", "--", "--reporter=json", "--coverage"])
-            .current_dir(&self.project_path)
-            .output()?;
+    async fn run_vitest(&self, filter: &[String]) -> Result<TestResult> {
+        let output = self.script_command("test", &["--reporter=json", "--coverage"], filter).output()?;
         
         let stdout = String::from_utf8_lossy(&output.stdout);
         self.parse_vitest_output(&stdout)
     }
     
-    async fn run_pytest(&self) -> Result<TestResult> {
-        let output = Command::new("pytest")
+    async fn run_pytest(&self, filter: &[String]) -> Result<TestResult> {
+        // The project's interpreter, so pytest sees the packages installed in its venv
+        let mut command = match python_envs::interpreter_for(&self.project_path) {
+            Some(python) => {
+                let mut command = Command::new(python);
+                command.args(["-m", "pytest"]);
+                command
+            }
+            None => Command::new("pytest"),
+        };
+        let output = command
             .args(&["--json-report", "--cov", "--cov-report=json"])
+            .args(filter)
             .current_dir(&self.project_path)
             .output()?;
         
@@ -130,9 +217,10 @@ impl TestRunner {
         self.parse_pytest_output(&stdout)
     }
     
-    async fn run_cargo_test(&self) -> Result<TestResult> {
+    async fn run_cargo_test(&self, filter: &[String]) -> Result<TestResult> {
         let output = Command::new("cargo")
             .args(&["test", "--", "--format", "json"])
+            .args(filter)
             .current_dir(&self.project_path)
             .output()?;
         
@@ -140,9 +228,10 @@ impl TestRunner {
         self.parse_cargo_output(&stdout)
     }
     
-    async fn run_go_test(&self) -> Result<TestResult> {
+    async fn run_go_test(&self, filter: &[String]) -> Result<TestResult> {
         let output = Command::new("go")
             .args(&["test", "-json", "-cover", "./..."])
+            .args(filter)
             .current_dir(&self.project_path)
             .output()?;
         
@@ -150,19 +239,17 @@ impl TestRunner {
         self.parse_go_output(&stdout)
     }
     
-    async fn run_mocha(&self) -> Result<TestResult> {
-        let output = Command::new("npm")
-            .args(&["test", "--", "--reporter", "json"])
-            .current_dir(&self.project_path)
-            .output()?;
+    async fn run_mocha(&self, filter: &[String]) -> Result<TestResult> {
+        let output = self.script_command("test", &["--reporter", "json"], filter).output()?;
         
         let stdout = String::from_utf8_lossy(&output.stdout);
         self.parse_mocha_output(&stdout)
     }
     
-    async fn run_junit(&self) -> Result<TestResult> {
+    async fn run_junit(&self, filter: &[String]) -> Result<TestResult> {
         let output = Command::new("mvn")
             .args(&["test"])
+            .args(filter)
             .current_dir(&self.project_path)
             .output()?;
         
@@ -193,6 +280,29 @@ impl TestRunner {
             None
         };
         
+        let mut failures = Vec::new();
+        for suite in json["testResults"].as_array().into_iter().flatten() {
+            let file = suite["name"].as_str().map(String::from);
+            for test in suite["assertionResults"].as_array().into_iter().flatten() {
+                if test["status"] != "failed" {
+                    continue;
+                }
+                // Each message is the assertion text followed by the stack
+                let messages: Vec<&str> = test["failureMessages"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|m| m.as_str())
+                    .collect();
+                failures.push(TestFailure {
+                    test_name: test["fullName"].as_str().or(test["title"].as_str()).unwrap_or("").to_string(),
+                    error_message: messages.first().and_then(|m| m.lines().next()).unwrap_or("").to_string(),
+                    stack_trace: (!messages.is_empty()).then(|| messages.join("\n")),
+                    file: file.clone(),
+                });
+            }
+        }
+        
         Ok(TestResult {
             total_tests: total,
             passed,
@@ -200,7 +310,7 @@ impl TestRunner {
             skipped,
             duration_ms: 0,
             coverage,
-            failures: Vec::new(),
+            failures,
         })
     }
     
@@ -220,6 +330,24 @@ impl TestRunner {
         let failed = summary["failed"].as_u64().unwrap_or(0) as u32;
         let skipped = summary["skipped"].as_u64().unwrap_or(0) as u32;
         
+        let failures = json["tests"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|test| test["outcome"] == "failed" || test["outcome"] == "error")
+            .map(|test| {
+                // Failures during setup have no "call" stage
+                let stage = if test["call"].is_object() { &test["call"] } else { &test["setup"] };
+                let node_id = test["nodeid"].as_str().unwrap_or("");
+                TestFailure {
+                    test_name: node_id.to_string(),
+                    error_message: stage["crash"]["message"].as_str().unwrap_or("").to_string(),
+                    stack_trace: stage["longrepr"].as_str().map(String::from),
+                    file: node_id.split("::").next().map(String::from),
+                }
+            })
+            .collect();
+        
         Ok(TestResult {
             total_tests: total,
             passed,
@@ -227,16 +355,36 @@ impl TestRunner {
             skipped,
             duration_ms: 0,
             coverage: None,
-            failures: Vec::new(),
+            failures,
         })
     }
     
     fn parse_cargo_output(&self, output: &str) -> Result<TestResult> {
         // Parse Cargo test output
         let mut passed = 0;
-        let mut failed = 0;
+        let mut failures = Vec::new();
         
         for line in output.lines() {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
+                if json["type"] == "test" && json["event"] == "ok" {
+                    passed += 1;
+                } else if json["type"] == "test" && json["event"] == "failed" {
+                    let stdout = json["stdout"].as_str().unwrap_or("");
+                    failures.push(TestFailure {
+                        test_name: json["name"].as_str().unwrap_or("").to_string(),
+                        error_message: stdout
+                            .lines()
+                            .find(|l| l.contains("panicked at"))
+                            .unwrap_or("")
+                            .trim()
+                            .to_string(),
+                        stack_trace: (!stdout.is_empty()).then(|| stdout.to_string()),
+                        file: None,
+                    });
+                }
+                continue;
+            }
+
             if line.contains("test result: ok") {
                 // Extract numbers
                 if let Some(nums) = line.split("passed").nth(0) {
@@ -247,6 +395,7 @@ impl TestRunner {
             }
         }
         
+        let failed = failures.len() as u32;
         Ok(TestResult {
             total_tests: passed + failed,
             passed,
@@ -254,20 +403,39 @@ impl TestRunner {
             skipped: 0,
             duration_ms: 0,
             coverage: None,
-            failures: Vec::new(),
+            failures,
         })
     }
     
     fn parse_go_output(&self, output: &str) -> Result<TestResult> {
         let mut passed = 0;
         let mut failed = 0;
+        let mut test_output: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        let mut failures = Vec::new();
         
         for line in output.lines() {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
+                let test = json["Test"].as_str();
                 if json["Action"] == "pass" {
                     passed += 1;
                 } else if json["Action"] == "fail" {
                     failed += 1;
+                    if let Some(test) = test {
+                        let output = test_output.remove(test).unwrap_or_default();
+                        failures.push(TestFailure {
+                            test_name: test.to_string(),
+                            error_message: output
+                                .lines()
+                                .map(str::trim)
+                                .find(|l| !l.starts_with("=== RUN") && !l.starts_with("--- FAIL") && !l.is_empty())
+                                .unwrap_or("")
+                                .to_string(),
+                            stack_trace: Some(output),
+                            file: None,
+                        });
+                    }
+                } else if let (Some(test), Some(text)) = (test, json["Output"].as_str()) {
+                    test_output.entry(test.to_string()).or_default().push_str(text);
                 }
             }
         }
@@ -279,7 +447,7 @@ impl TestRunner {
             skipped: 0,
             duration_ms: 0,
             coverage: None,
-            failures: Vec::new(),
+            failures,
         })
     }
     
@@ -292,6 +460,18 @@ impl TestRunner {
         let passed = stats["passes"].as_u64().unwrap_or(0) as u32;
         let failed = stats["failures"].as_u64().unwrap_or(0) as u32;
         
+        let failures = json["failures"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|test| TestFailure {
+                test_name: test["fullTitle"].as_str().unwrap_or("").to_string(),
+                error_message: test["err"]["message"].as_str().unwrap_or("").to_string(),
+                stack_trace: test["err"]["stack"].as_str().map(String::from),
+                file: test["file"].as_str().map(String::from),
+            })
+            .collect();
+        
         Ok(TestResult {
             total_tests: total,
             passed,
@@ -299,7 +479,7 @@ impl TestRunner {
             skipped: 0,
             duration_ms: stats["duration"].as_u64().unwrap_or(0),
             coverage: None,
-            failures: Vec::new(),
+            failures,
         })
     }
     
@@ -317,6 +497,38 @@ impl TestRunner {
     }
 }
 
+fn run_log_path(project_path: &Path) -> PathBuf {
+    project_path.join(".sai-metadata").join(RUN_LOG_FILE)
+}
+
+/// Oldest first
+pub fn recorded_runs(project_path: &Path) -> Vec<TestRunRecord> {
+    std::fs::read_to_string(run_log_path(project_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn record_run(project_path: &Path, framework: &TestFramework, result: &TestResult) -> Result<()> {
+    let mut runs = recorded_runs(project_path);
+    runs.push(TestRunRecord {
+        ran_at: chrono::Utc::now().to_rfc3339(),
+        framework: framework.clone(),
+        total_tests: result.total_tests,
+        passed: result.passed,
+        failed: result.failed,
+        skipped: result.skipped,
+        duration_ms: result.duration_ms,
+    });
+    let skip = runs.len().saturating_sub(RUN_LOG_LIMIT);
+    let path = run_log_path(project_path);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(&runs[skip..])?)?;
+    Ok(())
+}
+
 // Tauri commands
 #[tauri::command]
 pub async fn detect_test_framework(project_path: String) -> Result<String, String> {
@@ -354,3 +566,41 @@ pub async fn watch_tests(project_path: String) -> Result<(), String> {
     tracing::info!("Starting test watcher for: {}", project_path);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jest_failures_and_rerun_filter() {
+        let runner = TestRunner::new(TestFramework::Jest, PathBuf::from("/p"));
+        let output = serde_json::json!({
+            "numTotalTests": 2,
+            "numPassedTests": 1,
+            "numFailedTests": 1,
+            "testResults": [{
+                "name": "/p/src/sum.test.ts",
+                "assertionResults": [
+                    { "fullName": "sum adds", "status": "passed", "failureMessages": [] },
+                    {
+                        "fullName": "sum handles negatives",
+                        "status": "failed",
+                        "failureMessages": ["Expected: -1\nReceived: 1\n    at Object.<anonymous> (/p/src/sum.test.ts:9:21)"]
+                    }
+                ]
+            }]
+        });
+
+        let result = runner.parse_jest_output(&output.to_string()).unwrap();
+        assert_eq!(result.failures.len(), 1);
+        let failure = &result.failures[0];
+        assert_eq!(failure.test_name, "sum handles negatives");
+        assert_eq!(failure.error_message, "Expected: -1");
+        assert_eq!(failure.file.as_deref(), Some("/p/src/sum.test.ts"));
+        assert_eq!(runner.filter_args(&result.failures), vec!["/p/src/sum.test.ts".to_string()]);
+
+        let go = TestRunner::new(TestFramework::Go, PathBuf::from("/p"));
+        let failure = TestFailure { test_name: "TestSum".to_string(), ..failure.clone() };
+        assert_eq!(go.filter_args(&[failure]), vec!["-run".to_string(), "^(TestSum)$".to_string()]);
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 use std::collections::HashMap;
+use std::sync::Mutex;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Theme {
@@ -75,6 +76,10 @@ impl ThemeManager {
         self.themes.insert("light".to_string(), Self::create_light_theme());
         self.themes.insert("github-light".to_string(), Self::create_github_light_theme());
         self.themes.insert("solarized-light".to_string(), Self::create_solarized_light_theme());
+        
+        // High contrast themes (WCAG AA, checked by validate_contrast)
+        self.themes.insert("high-contrast-dark".to_string(), Self::create_high_contrast_dark_theme());
+        self.themes.insert("high-contrast-light".to_string(), Self::create_high_contrast_light_theme());
     }
     
     fn create_dark_theme() -> Theme {
@@ -397,6 +402,86 @@ impl ThemeManager {
         }
     }
     
+    fn create_high_contrast_dark_theme() -> Theme {
+        Theme {
+            id: "high-contrast-dark".to_string(),
+            name: "High Contrast Dark".to_string(),
+            description: "Dark theme meeting WCAG AA contrast for all text".to_string(),
+            author: "SAI IDE".to_string(),
+            colors: ThemeColors {
+                background: "#000000".to_string(),
+                foreground: "#ffffff".to_string(),
+                primary: "#6fc3df".to_string(),
+                secondary: "#1f1f1f".to_string(),
+                accent: "#ffd700".to_string(),
+                error: "#ff6b6b".to_string(),
+                warning: "#ffd700".to_string(),
+                success: "#5ff967".to_string(),
+                info: "#6fc3df".to_string(),
+            },
+            syntax: SyntaxColors {
+                keyword: "#569cd6".to_string(),
+                string: "#ce9178".to_string(),
+                number: "#b5cea8".to_string(),
+                comment: "#7ca668".to_string(),
+                function: "#dcdcaa".to_string(),
+                variable: "#9cdcfe".to_string(),
+                type_name: "#4ec9b0".to_string(),
+                operator: "#ffffff".to_string(),
+            },
+            ui: UIColors {
+                sidebar: "#000000".to_string(),
+                editor: "#000000".to_string(),
+                terminal: "#000000".to_string(),
+                statusbar: "#000000".to_string(),
+                border: "#6fc3df".to_string(),
+                hover: "#1a1a1a".to_string(),
+                selection: "#264f78".to_string(),
+                active: "#0f4a85".to_string(),
+            },
+        }
+    }
+    
+    fn create_high_contrast_light_theme() -> Theme {
+        Theme {
+            id: "high-contrast-light".to_string(),
+            name: "High Contrast Light".to_string(),
+            description: "Light theme meeting WCAG AA contrast for all text".to_string(),
+            author: "SAI IDE".to_string(),
+            colors: ThemeColors {
+                background: "#ffffff".to_string(),
+                foreground: "#000000".to_string(),
+                primary: "#0f4a85".to_string(),
+                secondary: "#f3f3f3".to_string(),
+                accent: "#0f4a85".to_string(),
+                error: "#b5200d".to_string(),
+                warning: "#895503".to_string(),
+                success: "#1e6e1e".to_string(),
+                info: "#0f4a85".to_string(),
+            },
+            syntax: SyntaxColors {
+                keyword: "#0000ff".to_string(),
+                string: "#a31515".to_string(),
+                number: "#096d48".to_string(),
+                comment: "#515151".to_string(),
+                function: "#5e2cbc".to_string(),
+                variable: "#001080".to_string(),
+                type_name: "#185e73".to_string(),
+                operator: "#000000".to_string(),
+            },
+            ui: UIColors {
+                sidebar: "#ffffff".to_string(),
+                editor: "#ffffff".to_string(),
+                terminal: "#ffffff".to_string(),
+                statusbar: "#ffffff".to_string(),
+                border: "#0f4a85".to_string(),
+                hover: "#f2f2f2".to_string(),
+                selection: "#add6ff".to_string(),
+                active: "#cce4ff".to_string(),
+            },
+        }
+    }
+    
     pub fn get_theme(&self, id: &str) -> Option<&Theme> {
         self.themes.get(id)
     }
@@ -417,43 +502,209 @@ impl ThemeManager {
         }
     }
     
-    pub fn import_theme(&mut self, json: &str) -> Result<String> {
+    /// Import a theme, rejecting it when text contrast falls below WCAG AA unless `allow_low_contrast`
+    pub fn import_theme(&mut self, json: &str, allow_low_contrast: bool) -> Result<String> {
         let theme: Theme = serde_json::from_str(json)?;
+        ensure_contrast(&theme, allow_low_contrast)?;
         let id = theme.id.clone();
         self.themes.insert(id.clone(), theme);
         Ok(id)
     }
 }
 
+/// WCAG AA minimums: 4.5:1 for normal text, 3:1 for UI components such as borders
+const MIN_TEXT_CONTRAST: f64 = 4.5;
+const MIN_UI_CONTRAST: f64 = 3.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContrastIssue {
+    pub element: String,
+    pub foreground: String,
+    pub background: String,
+    /// None when a color couldn't be parsed
+    pub ratio: Option<f64>,
+    pub required: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContrastReport {
+    pub theme_id: String,
+    pub passes_wcag_aa: bool,
+    pub issues: Vec<ContrastIssue>,
+}
+
+/// Parse `#rgb` or `#rrggbb` (alpha in `#rrggbbaa` is ignored)
+fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().strip_prefix('#').filter(|h| h.is_ascii())?;
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        3 => {
+            let expand = |i: usize| channel(&hex[i..i + 1].repeat(2));
+            Some((expand(0)?, expand(1)?, expand(2)?))
+        }
+        6 | 8 => Some((channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?)),
+        _ => None,
+    }
+}
+
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+pub fn contrast_ratio(foreground: &str, background: &str) -> Option<f64> {
+    let a = relative_luminance(parse_hex(foreground)?);
+    let b = relative_luminance(parse_hex(background)?);
+    Some((a.max(b) + 0.05) / (a.min(b) + 0.05))
+}
+
+pub fn validate_contrast(theme: &Theme) -> ContrastReport {
+    let c = &theme.colors;
+    let ui = &theme.ui;
+    let syntax = &theme.syntax;
+
+    let mut checks: Vec<(&str, &str, &str, f64)> = vec![
+        ("foreground on background", c.foreground.as_str(), c.background.as_str(), MIN_TEXT_CONTRAST),
+        ("foreground on editor", c.foreground.as_str(), ui.editor.as_str(), MIN_TEXT_CONTRAST),
+        ("foreground on sidebar", c.foreground.as_str(), ui.sidebar.as_str(), MIN_TEXT_CONTRAST),
+        ("foreground on terminal", c.foreground.as_str(), ui.terminal.as_str(), MIN_TEXT_CONTRAST),
+        ("foreground on selection", c.foreground.as_str(), ui.selection.as_str(), MIN_TEXT_CONTRAST),
+        ("primary on background", c.primary.as_str(), c.background.as_str(), MIN_TEXT_CONTRAST),
+        ("error on background", c.error.as_str(), c.background.as_str(), MIN_TEXT_CONTRAST),
+        ("warning on background", c.warning.as_str(), c.background.as_str(), MIN_TEXT_CONTRAST),
+        ("success on background", c.success.as_str(), c.background.as_str(), MIN_TEXT_CONTRAST),
+        ("info on background", c.info.as_str(), c.background.as_str(), MIN_TEXT_CONTRAST),
+        ("border on background", ui.border.as_str(), c.background.as_str(), MIN_UI_CONTRAST),
+    ];
+    for (name, color) in [
+        ("keyword", syntax.keyword.as_str()),
+        ("string", syntax.string.as_str()),
+        ("number", syntax.number.as_str()),
+        ("comment", syntax.comment.as_str()),
+        ("function", syntax.function.as_str()),
+        ("variable", syntax.variable.as_str()),
+        ("type", syntax.type_name.as_str()),
+        ("operator", syntax.operator.as_str()),
+    ] {
+        checks.push((name, color, ui.editor.as_str(), MIN_TEXT_CONTRAST));
+    }
+
+    let issues: Vec<ContrastIssue> = checks
+        .into_iter()
+        .filter_map(|(element, foreground, background, required)| {
+            let ratio = contrast_ratio(foreground, background);
+            if ratio.map_or(false, |r| r >= required) {
+                return None;
+            }
+            Some(ContrastIssue {
+                element: element.to_string(),
+                foreground: foreground.to_string(),
+                background: background.to_string(),
+                ratio: ratio.map(|r| (r * 100.0).round() / 100.0),
+                required,
+            })
+        })
+        .collect();
+
+    ContrastReport {
+        theme_id: theme.id.clone(),
+        passes_wcag_aa: issues.is_empty(),
+        issues,
+    }
+}
+
+fn ensure_contrast(theme: &Theme, allow_low_contrast: bool) -> Result<()> {
+    let report = validate_contrast(theme);
+    if report.passes_wcag_aa {
+        return Ok(());
+    }
+
+    let summary: Vec<String> = report
+        .issues
+        .iter()
+        .map(|i| match i.ratio {
+            Some(ratio) => format!("{} ({:.2}:1, needs {}:1)", i.element, ratio, i.required),
+            None => format!("{} (invalid color)", i.element),
+        })
+        .collect();
+
+    if allow_low_contrast {
+        tracing::warn!("Theme '{}' has low contrast: {}", theme.id, summary.join(", "));
+        Ok(())
+    } else {
+        anyhow::bail!("Theme '{}' fails WCAG AA contrast: {}", theme.id, summary.join(", "))
+    }
+}
+
+/// Built-in themes are constructed on first use rather than at startup
+static THEME_MANAGER: Mutex<Option<ThemeManager>> = Mutex::new(None);
+
+fn with_themes<T>(f: impl FnOnce(&mut ThemeManager) -> T) -> T {
+    let mut guard = THEME_MANAGER.lock().unwrap_or_else(|e| e.into_inner());
+    let manager = guard.get_or_insert_with(|| crate::profiler::time_startup_phase("themes", ThemeManager::new));
+    f(manager)
+}
+
 // Tauri commands
 
 #[tauri::command]
 pub async fn list_all_themes() -> Result<Vec<Theme>, String> {
-    let manager = ThemeManager::new();
-    Ok(manager.list_themes().into_iter().cloned().collect())
+    Ok(with_themes(|manager| manager.list_themes().into_iter().cloned().collect()))
 }
 
 #[tauri::command]
 pub async fn get_theme_by_id(theme_id: String) -> Result<Option<Theme>, String> {
-    let manager = ThemeManager::new();
-    Ok(manager.get_theme(&theme_id).cloned())
+    Ok(with_themes(|manager| manager.get_theme(&theme_id).cloned()))
 }
 
 #[tauri::command]
 pub async fn export_theme_json(theme_id: String) -> Result<String, String> {
-    let manager = ThemeManager::new();
-    manager.export_theme(&theme_id).map_err(|e| e.to_string())
+    with_themes(|manager| manager.export_theme(&theme_id)).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn import_theme_json(json: String) -> Result<String, String> {
-    let mut manager = ThemeManager::new();
-    manager.import_theme(&json).map_err(|e| e.to_string())
+pub async fn import_theme_json(json: String, allow_low_contrast: Option<bool>) -> Result<String, String> {
+    with_themes(|manager| manager.import_theme(&json, allow_low_contrast.unwrap_or(false))).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn add_theme(theme: Theme) -> Result<(), String> {
-    let mut manager = ThemeManager::new();
-    manager.add_custom_theme(theme);
+pub async fn add_theme(theme: Theme, allow_low_contrast: Option<bool>) -> Result<(), String> {
+    ensure_contrast(&theme, allow_low_contrast.unwrap_or(false)).map_err(|e| e.to_string())?;
+    with_themes(|manager| manager.add_custom_theme(theme));
     Ok(())
 }
+
+/// Check a custom or generated theme before it is applied
+#[tauri::command]
+pub async fn validate_theme_contrast(theme: Theme) -> Result<ContrastReport, String> {
+    Ok(validate_contrast(&theme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert_eq!(contrast_ratio("#000", "#ffffff").map(|r| r.round()), Some(21.0));
+        assert_eq!(contrast_ratio("#777777", "#777777"), Some(1.0));
+        assert_eq!(contrast_ratio("red", "#fff"), None);
+    }
+
+    #[test]
+    fn test_high_contrast_themes_pass_wcag_aa() {
+        let manager = ThemeManager::new();
+        for id in ["high-contrast-dark", "high-contrast-light"] {
+            let report = validate_contrast(manager.get_theme(id).unwrap());
+            assert!(report.passes_wcag_aa, "{}: {:?}", id, report.issues);
+        }
+
+        let mut theme = manager.get_theme("high-contrast-dark").unwrap().clone();
+        theme.syntax.comment = "#222222".to_string();
+        assert!(ensure_contrast(&theme, false).is_err());
+        assert!(ensure_contrast(&theme, true).is_ok());
+    }
+}
@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::database::{self, DatabaseManager, WorkSession};
+
+/// A gap in activity longer than this ends the session; the gap itself isn't counted
+pub const IDLE_TIMEOUT_SECS: i64 = 5 * 60;
+/// Usage stats feature that tracked time is also added to
+const USAGE_FEATURE: &str = "time_tracking";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    Day,
+    Week,
+}
+
+impl ReportPeriod {
+    /// Start of the bucket containing `date`; weeks start on Monday
+    fn bucket(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            ReportPeriod::Day => date,
+            ReportPeriod::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        }
+    }
+
+    fn default_range_days(&self) -> i64 {
+        match self {
+            ReportPeriod::Day => 30,
+            ReportPeriod::Week => 12 * 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeReportRow {
+    /// First day of the day or week, YYYY-MM-DD
+    pub period_start: String,
+    pub project_id: String,
+    /// Empty for time not attributed to a feature
+    pub feature: String,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeReport {
+    pub period: ReportPeriod,
+    pub since: String,
+    pub rows: Vec<TimeReportRow>,
+    pub total_seconds: i64,
+}
+
+fn parse_time(text: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(text)
+        .with_context(|| format!("Invalid timestamp '{}'", text))?
+        .with_timezone(&Utc))
+}
+
+/// Count time since the last activity, unless it was an idle gap.
+/// Returns false when the session went idle and should be closed instead.
+pub fn touch(session: &mut WorkSession, now: DateTime<Utc>) -> Result<bool> {
+    let gap = (now - parse_time(&session.last_activity_at)?).num_seconds();
+    if gap > IDLE_TIMEOUT_SECS {
+        return Ok(false);
+    }
+    session.active_seconds += gap.max(0);
+    session.last_activity_at = now.to_rfc3339();
+    Ok(true)
+}
+
+/// Close a session; an idle one ends at its last activity, not now
+fn close(db: &DatabaseManager, mut session: WorkSession, now: DateTime<Utc>) -> Result<WorkSession> {
+    touch(&mut session, now)?;
+    session.ended_at = Some(session.last_activity_at.clone());
+    db.update_work_session(&session)?;
+    if session.active_seconds > 0 {
+        db.track_feature_usage(USAGE_FEATURE, session.active_seconds)?;
+    }
+    Ok(session)
+}
+
+/// The open session, closing it first if it has gone idle
+pub fn current(db: &DatabaseManager, now: DateTime<Utc>) -> Result<Option<WorkSession>> {
+    let Some(session) = db.get_open_work_session()? else {
+        return Ok(None);
+    };
+    let idle = (now - parse_time(&session.last_activity_at)?).num_seconds() > IDLE_TIMEOUT_SECS;
+    if idle {
+        close(db, session, now)?;
+        return Ok(None);
+    }
+    Ok(Some(session))
+}
+
+pub fn start(project_id: &str, feature: Option<String>, now: DateTime<Utc>) -> Result<WorkSession> {
+    let db = database::open()?;
+    if let Some(open) = current(&db, now)? {
+        close(&db, open, now)?;
+    }
+    let mut session = WorkSession {
+        id: 0,
+        project_id: project_id.to_string(),
+        feature,
+        started_at: now.to_rfc3339(),
+        last_activity_at: now.to_rfc3339(),
+        ended_at: None,
+        active_seconds: 0,
+    };
+    session.id = db.add_work_session(&session)?;
+    Ok(session)
+}
+
+pub fn stop(now: DateTime<Utc>) -> Result<Option<WorkSession>> {
+    let db = database::open()?;
+    match current(&db, now)? {
+        Some(session) => Ok(Some(close(&db, session, now)?)),
+        None => Ok(None),
+    }
+}
+
+/// Editor activity: keeps the open session going, or starts one after idle time
+/// or when work moves to another project or feature
+pub fn activity(project_id: &str, feature: Option<String>, now: DateTime<Utc>) -> Result<WorkSession> {
+    let db = database::open()?;
+    if let Some(mut session) = current(&db, now)? {
+        let same_work = session.project_id == project_id && (feature.is_none() || feature == session.feature);
+        if same_work {
+            touch(&mut session, now)?;
+            db.update_work_session(&session)?;
+            return Ok(session);
+        }
+        close(&db, session, now)?;
+    }
+    start(project_id, feature, now)
+}
+
+/// Sum active time per period, project and feature. A session counts toward
+/// the day it started in; sessions are split at idle gaps so few span midnight.
+pub fn aggregate<Tz: TimeZone>(sessions: &[WorkSession], period: ReportPeriod, tz: &Tz) -> Vec<TimeReportRow> {
+    let mut totals: BTreeMap<(NaiveDate, String, String), i64> = BTreeMap::new();
+    for session in sessions {
+        let Ok(started) = parse_time(&session.started_at) else {
+            continue;
+        };
+        let bucket = period.bucket(started.with_timezone(tz).date_naive());
+        let key = (bucket, session.project_id.clone(), session.feature.clone().unwrap_or_default());
+        *totals.entry(key).or_default() += session.active_seconds;
+    }
+    totals
+        .into_iter()
+        .map(|((date, project_id, feature), seconds)| TimeReportRow {
+            period_start: date.format("%Y-%m-%d").to_string(),
+            project_id,
+            feature,
+            seconds,
+        })
+        .collect()
+}
+
+pub fn report(project_id: Option<&str>, period: ReportPeriod, since: Option<&str>) -> Result<TimeReport> {
+    let now = Utc::now();
+    let since = match since {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .with_context(|| format!("'{}' is not YYYY-MM-DD", date))?,
+        None => period.bucket((now - Duration::days(period.default_range_days())).with_timezone(&chrono::Local).date_naive()),
+    };
+    let since_utc = since
+        .and_hms_opt(0, 0, 0)
+        .and_then(|midnight| midnight.and_local_timezone(chrono::Local).earliest())
+        .context("Invalid start date")?
+        .with_timezone(&Utc);
+
+    let db = database::open()?;
+    // Close an idle session so its time shows up
+    current(&db, now)?;
+    let sessions = db.list_work_sessions(project_id, &since_utc.to_rfc3339())?;
+    let rows = aggregate(&sessions, period, &chrono::Local);
+
+    Ok(TimeReport {
+        period,
+        since: since.format("%Y-%m-%d").to_string(),
+        total_seconds: rows.iter().map(|r| r.seconds).sum(),
+        rows,
+    })
+}
+
+pub fn report_csv(report: &TimeReport) -> String {
+    let columns: Vec<String> = ["period_start", "project", "feature", "hours", "seconds"]
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let rows: Vec<Vec<serde_json::Value>> = report
+        .rows
+        .iter()
+        .map(|row| {
+            vec![
+                row.period_start.clone().into(),
+                row.project_id.clone().into(),
+                row.feature.clone().into(),
+                format!("{:.2}", row.seconds as f64 / 3600.0).into(),
+                row.seconds.into(),
+            ]
+        })
+        .collect();
+    crate::db_browser::to_csv(&columns, &rows)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn start_time_tracking(project_id: String, feature: Option<String>) -> Result<WorkSession, String> {
+    start(&project_id, feature, Utc::now()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_time_tracking() -> Result<Option<WorkSession>, String> {
+    stop(Utc::now()).map_err(|e| e.to_string())
+}
+
+/// Called by the editor on typing, saves and other activity
+#[tauri::command]
+pub async fn record_time_activity(project_id: String, feature: Option<String>) -> Result<WorkSession, String> {
+    activity(&project_id, feature, Utc::now()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_time_tracking_status() -> Result<Option<WorkSession>, String> {
+    database::open()
+        .and_then(|db| current(&db, Utc::now()))
+        .map_err(|e| e.to_string())
+}
+
+/// `since` is YYYY-MM-DD; defaults to the last 30 days or 12 weeks
+#[tauri::command]
+pub async fn get_time_report(
+    project_id: Option<String>,
+    period: ReportPeriod,
+    since: Option<String>,
+) -> Result<TimeReport, String> {
+    report(project_id.as_deref(), period, since.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Write the report as CSV; returns the number of rows
+#[tauri::command]
+pub async fn export_time_report_csv(
+    project_id: Option<String>,
+    period: ReportPeriod,
+    since: Option<String>,
+    path: String,
+) -> Result<usize, String> {
+    let report = report(project_id.as_deref(), period, since.as_deref()).map_err(|e| e.to_string())?;
+    std::fs::write(Path::new(&path), report_csv(&report)).map_err(|e| e.to_string())?;
+    Ok(report.rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(project: &str, feature: Option<&str>, started_at: &str, active_seconds: i64) -> WorkSession {
+        WorkSession {
+            id: 0,
+            project_id: project.to_string(),
+            feature: feature.map(String::from),
+            started_at: started_at.to_string(),
+            last_activity_at: started_at.to_string(),
+            ended_at: None,
+            active_seconds,
+        }
+    }
+
+    #[test]
+    fn test_idle_and_aggregation() {
+        let start = parse_time("2024-03-04T09:00:00Z").unwrap();
+        let mut open = session("app", None, "2024-03-04T09:00:00Z", 0);
+        assert!(touch(&mut open, start + Duration::seconds(120)).unwrap());
+        assert!(touch(&mut open, start + Duration::seconds(240)).unwrap());
+        assert_eq!(open.active_seconds, 240);
+        assert!(!touch(&mut open, start + Duration::seconds(240 + IDLE_TIMEOUT_SECS + 1)).unwrap());
+        assert_eq!(open.active_seconds, 240);
+
+        // 2024-03-04 is a Monday; the last session is Sunday evening in UTC but Monday in UTC+2
+        let sessions = vec![
+            session("app", Some("auth"), "2024-03-04T09:00:00Z", 3600),
+            session("app", Some("auth"), "2024-03-06T09:00:00Z", 1800),
+            session("app", None, "2024-03-10T23:00:00Z", 600),
+        ];
+        let utc = chrono::FixedOffset::east_opt(0).unwrap();
+        let weekly = aggregate(&sessions, ReportPeriod::Week, &utc);
+        assert_eq!(weekly.len(), 2);
+        assert_eq!((weekly[1].period_start.as_str(), weekly[1].seconds), ("2024-03-04", 5400));
+
+        let plus_two = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        let weekly = aggregate(&sessions, ReportPeriod::Week, &plus_two);
+        assert_eq!(weekly.iter().map(|r| r.period_start.as_str()).collect::<Vec<_>>(), vec!["2024-03-04", "2024-03-11"]);
+
+        let daily = aggregate(&sessions, ReportPeriod::Day, &utc);
+        assert_eq!(daily.len(), 3);
+
+        let report = TimeReport { period: ReportPeriod::Day, since: "2024-03-01".to_string(), total_seconds: 3600, rows: daily[..1].to_vec() };
+        assert_eq!(report_csv(&report), "period_start,project,feature,hours,seconds\n2024-03-04,app,auth,1.00,3600\n");
+    }
+}
@@ -0,0 +1,393 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use pulldown_cmark::{Event, Parser, Tag};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::terminal::shell::find_in_path;
+
+/// Engines' own default speaking speed, in words per minute
+const BASE_WPM: f32 = 175.0;
+
+/// SAPI is driven from PowerShell, which takes pause/resume/stop on stdin
+const SAPI_SCRIPT: &str = r#"Add-Type -AssemblyName System.Speech
+$s = New-Object System.Speech.Synthesis.SpeechSynthesizer
+$s.Rate = [int]$env:TTS_RATE
+if ($env:TTS_VOICE) { $s.SelectVoice($env:TTS_VOICE) }
+$s.SpeakAsync((Get-Content -Raw -Encoding UTF8 $env:TTS_FILE)) | Out-Null
+while ($true) {
+    $line = [Console]::In.ReadLineAsync()
+    while (-not $line.Wait(200)) { if ($s.State -eq 'Ready') { exit } }
+    switch ($line.Result) {
+        'pause' { $s.Pause() }
+        'resume' { $s.Resume() }
+        default { $s.SpeakAsyncCancelAll(); exit }
+    }
+}
+"#;
+
+lazy_static::lazy_static! {
+    /// A line of `say -v ?`: `Daniel              en_GB    # Hello, my name is Daniel.`
+    static ref SAY_VOICE: regex::Regex = regex::Regex::new(r"^(.+?)\s+([a-z]{2,3}[_-][A-Za-z0-9]+)\s+#").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TtsEngine {
+    Say,
+    Sapi,
+    Espeak,
+    SpeechDispatcher,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TtsVoice {
+    pub name: String,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaybackState {
+    Playing,
+    Paused,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeechSession {
+    pub id: String,
+    pub engine: TtsEngine,
+    pub state: PlaybackState,
+    /// Characters actually read out, after markdown and code blocks are dropped
+    pub chars: usize,
+}
+
+struct Speaking {
+    session: SpeechSession,
+    child: Child,
+    /// The text file SAPI reads from
+    text_file: Option<PathBuf>,
+}
+
+impl Speaking {
+    fn end(mut self) {
+        if self.session.engine == TtsEngine::Sapi {
+            if let Some(stdin) = self.child.stdin.as_mut() {
+                stdin.write_all(b"stop\n").ok();
+            }
+        }
+        self.child.kill().ok();
+        self.child.wait().ok();
+        if let Some(file) = self.text_file {
+            std::fs::remove_file(file).ok();
+        }
+    }
+}
+
+/// Only one readout plays at a time; starting another stops it
+static SPEAKING: Mutex<Option<Speaking>> = Mutex::new(None);
+
+fn espeak_binary() -> Option<PathBuf> {
+    find_in_path("espeak-ng").or_else(|| find_in_path("espeak"))
+}
+
+/// The platform's speech engine. On Linux espeak is preferred over speech-dispatcher
+/// because it runs in-process and can be paused.
+pub fn engine() -> Option<TtsEngine> {
+    if cfg!(target_os = "macos") {
+        Some(TtsEngine::Say)
+    } else if cfg!(target_os = "windows") {
+        Some(TtsEngine::Sapi)
+    } else if espeak_binary().is_some() {
+        Some(TtsEngine::Espeak)
+    } else if find_in_path("spd-say").is_some() {
+        Some(TtsEngine::SpeechDispatcher)
+    } else {
+        None
+    }
+}
+
+/// Markdown as it should sound: formatting dropped, code blocks skipped since
+/// reading code symbol by symbol helps no one
+pub fn speakable_text(markdown: &str) -> String {
+    let mut text = String::new();
+    let mut in_code_block = false;
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Text(t) | Event::Code(t) if !in_code_block => text.push_str(&t),
+            Event::SoftBreak => text.push(' '),
+            // A pause after each block, so headings and list items aren't run together
+            Event::HardBreak | Event::End(Tag::Paragraph | Tag::Heading(..) | Tag::Item) => {
+                if !text.trim_end().ends_with(['.', '!', '?', ':']) {
+                    text.push('.');
+                }
+                text.push('\n');
+            }
+            _ => {}
+        }
+    }
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && *line != ".")
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `rate` is relative to the engine's normal speed: 1.0 normal, 0.5 half, 2.0 double
+fn spawn(engine: TtsEngine, text: &str, voice: Option<&str>, rate: f32) -> Result<(Child, Option<PathBuf>)> {
+    let rate = rate.clamp(0.5, 2.0);
+    let wpm = ((BASE_WPM * rate) as u32).to_string();
+    let mut text_file = None;
+
+    let mut command = match engine {
+        TtsEngine::Say => {
+            let mut c = Command::new("say");
+            c.args(["-r", &wpm]);
+            if let Some(voice) = voice {
+                c.args(["-v", voice]);
+            }
+            c.arg("--").arg(text);
+            c
+        }
+        TtsEngine::Espeak => {
+            let mut c = Command::new(espeak_binary().context("espeak is not installed")?);
+            c.args(["-s", &wpm]);
+            if let Some(voice) = voice {
+                c.args(["-v", voice]);
+            }
+            c.arg("--").arg(text);
+            c
+        }
+        TtsEngine::SpeechDispatcher => {
+            let mut c = Command::new("spd-say");
+            c.args(["--wait", "-r", &(((rate - 1.0) * 100.0) as i32).clamp(-100, 100).to_string()]);
+            if let Some(voice) = voice {
+                c.args(["-y", voice]);
+            }
+            c.arg("--").arg(text);
+            c
+        }
+        TtsEngine::Sapi => {
+            let file = std::env::temp_dir().join(format!("luciai-tts-{}.txt", uuid::Uuid::new_v4()));
+            std::fs::write(&file, text)?;
+            let mut c = Command::new("powershell");
+            c.args(["-NoProfile", "-NonInteractive", "-Command", SAPI_SCRIPT])
+                .env("TTS_FILE", &file)
+                .env("TTS_RATE", (((rate - 1.0) * 10.0).round() as i32).clamp(-10, 10).to_string())
+                .env("TTS_VOICE", voice.unwrap_or(""));
+            text_file = Some(file);
+            c
+        }
+    };
+
+    let child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to start text-to-speech")?;
+    Ok((child, text_file))
+}
+
+pub fn speak(text: &str, voice: Option<&str>, rate: f32) -> Result<SpeechSession> {
+    let engine = engine().context("No text-to-speech engine found; install espeak-ng or speech-dispatcher")?;
+    let text = speakable_text(text);
+    if text.is_empty() {
+        anyhow::bail!("Nothing to read out");
+    }
+
+    stop();
+    let (child, text_file) = spawn(engine, &text, voice, rate)?;
+    let session = SpeechSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        engine,
+        state: PlaybackState::Playing,
+        chars: text.chars().count(),
+    };
+    *SPEAKING.lock().unwrap() = Some(Speaking { session: session.clone(), child, text_file });
+    Ok(session)
+}
+
+/// Block until `session_id` finishes or is replaced; true if it played to the end
+pub fn wait_finished(session_id: &str) -> bool {
+    loop {
+        {
+            let mut speaking = SPEAKING.lock().unwrap();
+            let Some(current) = speaking.as_mut().filter(|s| s.session.id == session_id) else {
+                return false;
+            };
+            if let Ok(Some(_)) = current.child.try_wait() {
+                if let Some(done) = speaking.take() {
+                    done.end();
+                }
+                return true;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+fn signal(child: &Child, signal: &str) -> Result<()> {
+    let status = Command::new("kill").args([signal, &child.id().to_string()]).status()?;
+    if !status.success() {
+        anyhow::bail!("Failed to signal the speech process");
+    }
+    Ok(())
+}
+
+fn set_paused(paused: bool) -> Result<SpeechSession> {
+    let mut speaking = SPEAKING.lock().unwrap();
+    let current = speaking.as_mut().context("Nothing is being read out")?;
+    match current.session.engine {
+        TtsEngine::Sapi => {
+            let stdin = current.child.stdin.as_mut().context("Speech process has no input")?;
+            stdin.write_all(if paused { b"pause\n" } else { b"resume\n" })?;
+        }
+        TtsEngine::SpeechDispatcher => {
+            // The daemon does the speaking, so stopping spd-say itself wouldn't pause it
+            anyhow::bail!("Pausing isn't supported with speech-dispatcher; install espeak-ng");
+        }
+        TtsEngine::Say | TtsEngine::Espeak => signal(&current.child, if paused { "-STOP" } else { "-CONT" })?,
+    }
+    current.session.state = if paused { PlaybackState::Paused } else { PlaybackState::Playing };
+    Ok(current.session.clone())
+}
+
+pub fn stop() {
+    if let Some(speaking) = SPEAKING.lock().unwrap().take() {
+        if speaking.session.engine == TtsEngine::SpeechDispatcher {
+            Command::new("spd-say").arg("--cancel").status().ok();
+        }
+        speaking.end();
+    }
+}
+
+pub fn list_voices() -> Result<Vec<TtsVoice>> {
+    let Some(engine) = engine() else { return Ok(Vec::new()) };
+    let voices = match engine {
+        TtsEngine::Say => {
+            let output = Command::new("say").args(["-v", "?"]).output()?;
+            String::from_utf8_lossy(&output.stdout).lines().filter_map(parse_say_voice).collect()
+        }
+        TtsEngine::Espeak => {
+            // Pty Language Age/Gender VoiceName File Other Languages
+            let output = Command::new(espeak_binary().context("espeak is not installed")?).arg("--voices").output()?;
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .skip(1)
+                .filter_map(|line| {
+                    let columns: Vec<&str> = line.split_whitespace().collect();
+                    Some(TtsVoice { name: columns.get(3)?.to_string(), language: Some(columns.get(1)?.to_string()) })
+                })
+                .collect()
+        }
+        TtsEngine::SpeechDispatcher => {
+            let output = Command::new("spd-say").arg("-L").output()?;
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .skip(1)
+                .filter_map(|line| {
+                    let columns: Vec<&str> = line.split_whitespace().collect();
+                    Some(TtsVoice { name: columns.first()?.to_string(), language: columns.get(1).map(|l| l.to_string()) })
+                })
+                .collect()
+        }
+        TtsEngine::Sapi => {
+            let script = "Add-Type -AssemblyName System.Speech; \
+                (New-Object System.Speech.Synthesis.SpeechSynthesizer).GetInstalledVoices() | \
+                ForEach-Object { $_.VoiceInfo.Name + \"`t\" + $_.VoiceInfo.Culture.Name }";
+            let output = Command::new("powershell").args(["-NoProfile", "-Command", script]).output()?;
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| {
+                    let (name, language) = line.trim().split_once('\t')?;
+                    Some(TtsVoice { name: name.to_string(), language: Some(language.to_string()) })
+                })
+                .collect()
+        }
+    };
+    Ok(voices)
+}
+
+fn parse_say_voice(line: &str) -> Option<TtsVoice> {
+    let captures = SAY_VOICE.captures(line)?;
+    Some(TtsVoice { name: captures[1].trim().to_string(), language: Some(captures[2].replace('_', "-")) })
+}
+
+// Tauri commands
+
+/// Read `text` (markdown is fine) aloud, stopping any earlier readout. `rate` is
+/// relative to normal speed. Emits `tts-finished` with the session id when done.
+#[tauri::command]
+pub async fn speak_text(
+    app: tauri::AppHandle,
+    text: String,
+    voice: Option<String>,
+    rate: Option<f32>,
+) -> Result<SpeechSession, String> {
+    let session = tokio::task::spawn_blocking(move || speak(&text, voice.as_deref(), rate.unwrap_or(1.0)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let id = session.id.clone();
+    tokio::task::spawn_blocking(move || {
+        if wait_finished(&id) {
+            app.emit("tts-finished", &id).ok();
+        }
+    });
+    Ok(session)
+}
+
+#[tauri::command]
+pub async fn pause_speech() -> Result<SpeechSession, String> {
+    set_paused(true).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn resume_speech() -> Result<SpeechSession, String> {
+    set_paused(false).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_speech() -> Result<(), String> {
+    stop();
+    Ok(())
+}
+
+/// The readout in progress, if any
+#[tauri::command]
+pub async fn get_speech_session() -> Result<Option<SpeechSession>, String> {
+    Ok(SPEAKING.lock().unwrap().as_ref().map(|s| s.session.clone()))
+}
+
+#[tauri::command]
+pub async fn list_tts_voices() -> Result<Vec<TtsVoice>, String> {
+    tokio::task::spawn_blocking(list_voices)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speakable_text() {
+        let markdown = "## What it does\n\nParses the `config` file.\n\n```rust\nlet x = 1;\n```\n\n- Reads env vars\n- Falls back to defaults.";
+        assert_eq!(
+            speakable_text(markdown),
+            "What it does.\nParses the config file.\nReads env vars.\nFalls back to defaults."
+        );
+
+        let voice = parse_say_voice("Daniel              en_GB    # Hello, my name is Daniel.").unwrap();
+        assert_eq!((voice.name.as_str(), voice.language.as_deref()), ("Daniel", Some("en-GB")));
+    }
+}
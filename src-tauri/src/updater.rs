@@ -10,6 +10,9 @@ pub struct UpdateInfo {
     pub changelog: Vec<String>,
     pub size_mb: f32,
     pub required: bool,
+    /// Published SHA-256 of the download, when the release ships checksums
+    #[serde(default)]
+    pub sha256: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,7 +62,7 @@ impl AutoUpdater {
         tracing::info!("Checking for updates...");
         
         // Make HTTP request to check latest version
-        let client = reqwest::Client::new();
+        let client = crate::network::client();
         let response = client.get(&self.update_url)
             .header("User-Agent", "SAI-IDE")
             .send()
@@ -80,6 +83,13 @@ impl AutoUpdater {
             
             // Find appropriate asset for current platform
             let download_url = self.get_download_url_for_platform(&release)?;
+            let sha256 = match self.published_checksum(&release, &download_url).await {
+                Ok(sha256) => sha256,
+                Err(e) => {
+                    tracing::warn!("Could not fetch update checksum: {}", e);
+                    None
+                }
+            };
             
             let update_info = UpdateInfo {
                 version: release.tag_name.clone(),
@@ -88,6 +98,7 @@ impl AutoUpdater {
                 changelog,
                 size_mb: 50.0, // Approximate
                 required: release.tag_name.contains("CRITICAL"),
+                sha256,
             };
             
             tracing::info!("Update available: {}", update_info.version);
@@ -98,11 +109,42 @@ impl AutoUpdater {
         }
     }
     
-    /// Download update
-    pub async fn download_update(&self, download_url: &str) -> Result<PathBuf> {
+    /// SHA-256 for the asset at `download_url`, from a `<asset>.sha256` file or a
+    /// `SHA256SUMS`/`checksums.txt` listing attached to the release
+    async fn published_checksum(&self, release: &GithubRelease, download_url: &str) -> Result<Option<String>> {
+        let asset_name = download_url.split('/').last().unwrap_or_default();
+        let Some(checksum_asset) = release.assets.iter().find(|a| {
+            a.name == format!("{}.sha256", asset_name)
+                || a.name.eq_ignore_ascii_case("SHA256SUMS")
+                || a.name.eq_ignore_ascii_case("checksums.txt")
+        }) else {
+            return Ok(None);
+        };
+        
+        let text = crate::network::client()
+            .get(&checksum_asset.browser_download_url)
+            .header("User-Agent", "SAI-IDE")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        
+        let entries = crate::checksums::parse_sums(&text, crate::checksums::HashAlgorithm::Sha256);
+        let digest = entries
+            .iter()
+            .find(|e| e.path == asset_name)
+            .map(|e| e.expected.clone())
+            // A per-asset .sha256 file may hold just the digest
+            .or_else(|| text.split_whitespace().next().filter(|_| checksum_asset.name.ends_with(".sha256")).map(str::to_lowercase));
+        Ok(digest)
+    }
+    
+    /// Download update, checking it against `expected_sha256` when given
+    pub async fn download_update(&self, download_url: &str, expected_sha256: Option<&str>) -> Result<PathBuf> {
         tracing::info!("Downloading update from: {}", download_url);
         
-        let client = reqwest::Client::new();
+        let client = crate::network::client();
         let response = client.get(download_url)
             .send()
             .await?;
@@ -119,6 +161,14 @@ impl AutoUpdater {
         let bytes = response.bytes().await?;
         std::fs::write(&download_path, &bytes)?;
         
+        if let Some(expected) = expected_sha256 {
+            if !crate::checksums::matches(&download_path, crate::checksums::HashAlgorithm::Sha256, expected)? {
+                std::fs::remove_file(&download_path).ok();
+                anyhow::bail!("Downloaded update does not match its published checksum");
+            }
+            tracing::info!("Update checksum verified");
+        }
+        
         tracing::info!("Update downloaded to: {:?}", download_path);
         Ok(download_path)
     }
@@ -280,11 +330,11 @@ pub async fn check_for_updates() -> Result<Option<UpdateInfo>, String> {
 }
 
 #[tauri::command]
-pub async fn download_update(download_url: String) -> Result<String, String> {
+pub async fn download_update(download_url: String, sha256: Option<String>) -> Result<String, String> {
     let updater = AutoUpdater::new()
         .map_err(|e| e.to_string())?;
     
-    let path = updater.download_update(&download_url)
+    let path = updater.download_update(&download_url, sha256.as_deref())
         .await
         .map_err(|e| e.to_string())?;
     
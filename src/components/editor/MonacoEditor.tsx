@@ -2,6 +2,7 @@ import { useEffect, useRef, useState } from "react";
 import Editor, { OnMount } from "@monaco-editor/react";
 import { invoke } from "@tauri-apps/api/core";
 import { toast } from "../ui/NotificationToast";
+import { errorMessage, isReadOnlyError } from "../../lib/utils";
 import { SaveIcon, FileIcon, SearchIcon } from "lucide-react";
 import FindReplace from "./FindReplace";
 import { useAdvancedEditorFeatures } from "./AdvancedEditorFeatures";
@@ -120,7 +121,7 @@ export default function MonacoEditor({
       onSave?.(content);
     } catch (error) {
       console.error("Failed to save file:", error);
-      toast.error("Failed to save file", error as string);
+      toast.error(isReadOnlyError(error) ? "File is read-only" : "Failed to save file", errorMessage(error));
     } finally {
       setIsSaving(false);
     }
@@ -14,6 +14,7 @@ import {
   XIcon,
 } from "lucide-react";
 import { toast } from "../ui/NotificationToast";
+import { errorMessage } from "../../lib/utils";
 import { DndProvider, useDrag, useDrop } from "react-dnd";
 import { HTML5Backend } from "react-dnd-html5-backend";
 
@@ -23,6 +24,7 @@ interface FileInfo {
   is_directory: boolean;
   size: number;
   modified: number;
+  ignored?: boolean;
 }
 
 interface FileExplorerProps {
@@ -146,7 +148,7 @@ const FileNode = ({
               <FileIcon className="w-4 h-4 text-muted-foreground flex-shrink-0" />
             </>
           )}
-          <span className="text-sm truncate">{node.info.name}</span>
+          <span className={`text-sm truncate ${node.info.ignored ? "opacity-50" : ""}`}>{node.info.name}</span>
         </div>
 
         {/* Context Actions (visible on hover) */}
@@ -362,7 +364,7 @@ function FileExplorerContent({
       cancelCreating();
     } catch (error) {
       console.error("Failed to create item:", error);
-      toast.error("Failed to create item", error as string);
+      toast.error("Failed to create item", errorMessage(error));
     }
   };
 
@@ -396,7 +398,7 @@ function FileExplorerContent({
 
     } catch (error) {
       console.error("Failed to move file:", error);
-      toast.error("Failed to move file", error as string);
+      toast.error("Failed to move file", errorMessage(error));
     }
   };
 
@@ -431,7 +433,7 @@ function FileExplorerContent({
       loadDirectory(projectPath);
     } catch (error) {
       console.error("Failed to delete:", error);
-      toast.error("Failed to delete", error as string);
+      toast.error("Failed to delete", errorMessage(error));
     }
   };
 
@@ -449,7 +451,7 @@ function FileExplorerContent({
       loadDirectory(projectPath);
     } catch (error) {
       console.error("Failed to rename:", error);
-      toast.error("Failed to rename", error as string);
+      toast.error("Failed to rename", errorMessage(error));
     }
   };
 
@@ -23,6 +23,25 @@ export interface RefactoringImpact {
   testability: number;
 }
 
+export interface CodeExplanation {
+  overview: string;
+  steps: { lines?: string; explanation: string }[];
+  pitfalls: string[];
+}
+
+export interface SelectionExplanation {
+  session_id: string;
+  selection: {
+    file_path?: string;
+    start_line: number;
+    end_line: number;
+    language: string;
+    code?: string;
+  };
+  code: string;
+  explanation: CodeExplanation;
+}
+
 export type RefactorFocus = 
   | 'readability'
   | 'performance'
@@ -66,7 +85,7 @@ export function useCodeRefactoring() {
     setError(null);
 
     try {
-      const explanation = await invoke<string>('explain_code', {
+      const explanation = await invoke<SelectionExplanation>('explain_code', {
         code,
         language,
       });
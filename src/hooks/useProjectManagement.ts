@@ -43,6 +43,11 @@ export interface PromptEntry {
   files_modified: string[];
 }
 
+export interface BatchSaveReport {
+  saved: string[];
+  failed: { path: string; error: string }[];
+}
+
 export function useProjectManagement() {
   const [projects, setProjects] = useState<Project[]>([]);
   const [currentProject, setCurrentProject] = useState<ProjectMetadata | null>(null);
@@ -154,7 +159,7 @@ export function useProjectManagement() {
   const saveMultipleFiles = useCallback(
     async (projectId: string, files: [string, string][]) => {
       try {
-        await invoke('save_multiple_files', {
+        return await invoke<BatchSaveReport>('save_multiple_files', {
           projectId,
           files,
         });
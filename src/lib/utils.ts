@@ -34,4 +34,21 @@ export function throttle<T extends (...args: any[]) => any>(
       fn(...args);
     }
   };
-}
\ No newline at end of file
+}
+
+/** Error from commands that write files; `read_only` means the path is locked in the project */
+export type WriteError =
+  | { kind: 'read_only'; path: string; locked_by: string; message: string }
+  | { kind: 'failed'; message: string };
+
+export function isReadOnlyError(error: unknown): error is Extract<WriteError, { kind: 'read_only' }> {
+  return typeof error === 'object' && error !== null && (error as { kind?: unknown }).kind === 'read_only';
+}
+
+/** Message for a rejected `invoke`, which is either a plain string or a typed error */
+export function errorMessage(error: unknown): string {
+  if (typeof error === 'object' && error !== null && 'message' in error) {
+    return String((error as { message: unknown }).message);
+  }
+  return String(error);
+}
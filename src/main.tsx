@@ -1,5 +1,6 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { invoke } from "@tauri-apps/api/core";
 import App from "./App";
 import "./index.css";
 
@@ -8,3 +9,10 @@ ReactDOM.createRoot(document.getElementById("root")!).render(
     <App />
   </React.StrictMode>
 );
+
+// Report the first painted frame for the startup profile
+requestAnimationFrame(() => {
+  requestAnimationFrame(() => {
+    invoke("report_first_paint").catch(() => {});
+  });
+});
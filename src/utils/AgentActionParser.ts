@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { toast } from "../components/ui/NotificationToast";
+import { errorMessage } from "../lib/utils";
 
 export interface AgentAction {
   type: "create_file" | "modify_file" | "delete_file" | "create_directory" | "run_command" | "install_package";
@@ -139,10 +140,10 @@ export class AgentActionParser {
         message: `Created file: ${relativePath}`,
       };
     } catch (error) {
-      toast.error("Failed to create file", error as string);
+      toast.error("Failed to create file", errorMessage(error));
       return {
         success: false,
-        message: `Failed to create file: ${error}`,
+        message: `Failed to create file: ${errorMessage(error)}`,
       };
     }
   }
@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { ProjectTemplate } from "./ProjectTemplates";
 import { toast } from "../components/ui/NotificationToast";
+import { errorMessage } from "../lib/utils";
 
 export interface TemplateApplicationResult {
   success: boolean;
@@ -41,7 +42,7 @@ export async function applyTemplate(
 
         filesCreated++;
       } catch (error) {
-        errors.push(`Failed to create ${file.path}: ${error}`);
+        errors.push(`Failed to create ${file.path}: ${errorMessage(error)}`);
         console.error(`Error creating ${file.path}:`, error);
       }
     }